@@ -0,0 +1,234 @@
+//! Baseline timings for the hottest database paths, so changes such as switching to
+//! `prepare_cached` or adding indexes can show their effect.
+//!
+//! Reads share one seeded database and connection, since a batch of setups each holding a
+//! pooled connection would exhaust the pool; writes get a fresh database per insert.
+//! `concurrent_writes` compares many writers each on its own pooled connection against
+//! the same writes queued on the single writer thread, on a database file so that they
+//! contend for its lock.
+//!
+//! Run with `cargo bench --features bench`.
+
+#[cfg(feature = "bench")]
+mod benches {
+    use backend::db::{
+        DbPool, SCHEMA_SQL, fetch_vaccines, goat_columns, load_goat_details, row_to_goat,
+    };
+    use backend::errors::AppError;
+    use backend::models::GoatParams;
+    use criterion::{BatchSize, Criterion, Throughput};
+    use rusqlite::{Connection, Transaction};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Goats in the dataset `get_goats` runs against.
+    const LIST_GOATS: usize = 1000;
+    const VACCINES: [&str; 3] = ["CDT", "Rabies", "PPR"];
+    const DISEASES: [&str; 2] = ["Mastitis", "Foot rot"];
+    /// Concurrent writers in `concurrent_writes`, and the inserts each one makes.
+    const WRITERS: usize = 8;
+    const WRITES_PER_WRITER: usize = 25;
+
+    /// A goat linked to every vaccine in `VACCINES` and disease in `DISEASES`; ids are
+    /// assigned in that order by `seeded_pool`.
+    fn goat(name: &str) -> GoatParams {
+        serde_json::from_value(json!({
+            "breed": "Beetal",
+            "name": name,
+            "gender": "Female",
+            "offspring": 1,
+            "cost": 100.0,
+            "weight": 40.0,
+            "current_price": 120.0,
+            "diet": "Hay",
+            "last_bred": null,
+            "health_status": "healthy",
+            "vaccinations": [
+                {"id": 1, "name": "CDT"},
+                {"id": 2, "name": "Rabies"},
+                {"id": 3, "name": "PPR"}
+            ],
+            "diseases": [
+                {"id": 1, "name": "Mastitis"},
+                {"id": 2, "name": "Foot rot"}
+            ]
+        }))
+        .expect("Invalid benchmark goat")
+    }
+
+    /// A fresh in-memory database with the catalog entries `goat` links to and `goats`
+    /// fully related goats named `Goat 0`, `Goat 1`, ...
+    fn seeded_pool(goats: usize) -> DbPool {
+        let pool = DbPool::in_memory().expect("Failed to open in-memory database");
+        let mut conn = pool.get_conn().expect("Failed to get connection");
+        conn.execute_batch(SCHEMA_SQL)
+            .expect("Failed to apply schema");
+        let tx = conn.transaction().expect("Failed to start transaction");
+        for name in VACCINES {
+            tx.execute("INSERT INTO vaccines (name) VALUES (?1)", [name])
+                .expect("Failed to seed vaccine");
+        }
+        for name in DISEASES {
+            tx.execute("INSERT INTO diseases (name) VALUES (?1)", [name])
+                .expect("Failed to seed disease");
+        }
+        for i in 0..goats {
+            let name = format!("Goat {}", i);
+            DbPool::insert_goat(&tx, &goat(&name)).expect("Failed to seed goat");
+        }
+        tx.commit().expect("Failed to commit seed data");
+        drop(conn);
+        pool
+    }
+
+    /// The query `GET /goats` runs when unfiltered, mapping every row.
+    fn list_goats(conn: &Connection) -> usize {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM goats WHERE deleted_at IS NULL",
+                goat_columns("goats")
+            ))
+            .expect("Failed to prepare goat list");
+        stmt.query_map([], |row| {
+            Ok(row_to_goat(row).expect("Failed to map goat row"))
+        })
+        .expect("Failed to list goats")
+        .count()
+    }
+
+    pub fn get_goats(c: &mut Criterion) {
+        let pool = seeded_pool(LIST_GOATS);
+        let conn = pool.get_conn().expect("Failed to get connection");
+        let mut group = c.benchmark_group("get_goats");
+        group.throughput(Throughput::Elements(1));
+        group.bench_function("1000_goats", |b| {
+            b.iter(|| assert_eq!(list_goats(&conn), LIST_GOATS))
+        });
+        group.finish();
+    }
+
+    /// Each insert gets a database of its own, set up outside the timed section, so the
+    /// goat table does not grow over the run.
+    pub fn add_goat(c: &mut Criterion) {
+        let mut group = c.benchmark_group("add_goat");
+        group.throughput(Throughput::Elements(1));
+        group.bench_function("3_vaccines_2_diseases", |b| {
+            b.iter_batched(
+                || (seeded_pool(0), goat("Benchmark goat")),
+                |(pool, goat)| {
+                    let mut conn = pool.get_conn().expect("Failed to get connection");
+                    let tx = conn.transaction().expect("Failed to start transaction");
+                    DbPool::insert_goat(&tx, &goat).expect("Failed to insert goat");
+                    tx.commit().expect("Failed to commit goat");
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.finish();
+    }
+
+    /// A fresh database file with the schema applied; an in-memory database would not
+    /// show contention on the file lock.
+    fn file_pool(name: &str) -> DbPool {
+        let path = std::env::temp_dir().join(format!("yagi_bench_{}.db", name));
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+        let pool = DbPool::new(path.to_str().expect("temp path is not UTF-8"))
+            .expect("Failed to open database");
+        pool.get_conn()
+            .expect("Failed to get connection")
+            .execute_batch(SCHEMA_SQL)
+            .expect("Failed to apply schema");
+        pool
+    }
+
+    /// Inserts a vaccine under a name no earlier call used.
+    fn insert_vaccine(tx: &Transaction) -> Result<(), AppError> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        tx.execute(
+            "INSERT INTO vaccines (name) VALUES (?1)",
+            [format!("Vaccine {}", NEXT.fetch_add(1, Ordering::Relaxed))],
+        )?;
+        Ok(())
+    }
+
+    /// The same `WRITERS * WRITES_PER_WRITER` inserts, first from `WRITERS` threads each
+    /// writing on its own pooled connection and retrying on `SQLITE_BUSY`, then queued
+    /// all at once through `DbPool::write`.
+    pub fn concurrent_writes(c: &mut Criterion) {
+        let mut group = c.benchmark_group("concurrent_writes");
+        group.throughput(Throughput::Elements((WRITERS * WRITES_PER_WRITER) as u64));
+
+        let pool = file_pool("pooled_writes");
+        group.bench_function("pooled_connections", |b| {
+            b.iter(|| {
+                let threads: Vec<_> = (0..WRITERS)
+                    .map(|_| {
+                        let pool = pool.clone();
+                        std::thread::spawn(move || {
+                            for _ in 0..WRITES_PER_WRITER {
+                                pool.with_write_retry(insert_vaccine)
+                                    .expect("Pooled write failed");
+                            }
+                        })
+                    })
+                    .collect();
+                for thread in threads {
+                    thread.join().expect("Writer thread panicked");
+                }
+            })
+        });
+
+        let pool = file_pool("writer_writes");
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("Failed to start runtime");
+        group.bench_function("single_writer", |b| {
+            b.iter(|| {
+                let writes = (0..WRITERS * WRITES_PER_WRITER).map(|_| pool.write(insert_vaccine));
+                for result in runtime.block_on(futures_util::future::join_all(writes)) {
+                    result.expect("Writer write failed");
+                }
+            })
+        });
+        group.finish();
+    }
+
+    pub fn load_goat(c: &mut Criterion) {
+        let pool = seeded_pool(1);
+        let conn = pool.get_conn().expect("Failed to get connection");
+        let mut group = c.benchmark_group("load_goat_details");
+        group.throughput(Throughput::Elements(1));
+        group.bench_function("full_relations", |b| {
+            b.iter(|| load_goat_details(&conn, 1).expect("Failed to load goat"))
+        });
+        group.finish();
+    }
+
+    pub fn vaccines(c: &mut Criterion) {
+        let pool = seeded_pool(1);
+        let conn = pool.get_conn().expect("Failed to get connection");
+        let mut group = c.benchmark_group("fetch_vaccines");
+        group.throughput(Throughput::Elements(1));
+        group.bench_function("3_vaccines", |b| {
+            b.iter(|| fetch_vaccines(&conn, 1).expect("Failed to fetch vaccines"))
+        });
+        group.finish();
+    }
+}
+
+#[cfg(feature = "bench")]
+criterion::criterion_group!(
+    db_benchmarks,
+    benches::get_goats,
+    benches::add_goat,
+    benches::load_goat,
+    benches::vaccines,
+    benches::concurrent_writes
+);
+#[cfg(feature = "bench")]
+criterion::criterion_main!(db_benchmarks);
+
+#[cfg(not(feature = "bench"))]
+fn main() {}
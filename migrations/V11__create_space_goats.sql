@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS space_goats (
+    goat_id INTEGER PRIMARY KEY,
+    space_id INTEGER NOT NULL,
+    assigned_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
+    FOREIGN KEY (space_id) REFERENCES spaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_space_goats_space_id ON space_goats(space_id);
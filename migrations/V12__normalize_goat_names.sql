@@ -0,0 +1,14 @@
+-- Canonicalise existing names: tabs become spaces, runs of spaces collapse (each pass
+-- halves a run, so four passes cover runs of up to sixteen), then trim.
+UPDATE goats SET name = trim(
+    replace(replace(replace(replace(replace(name, char(9), ' '), '  ', ' '), '  ', ' '), '  ', ' '), '  ', ' ')
+);
+
+-- Names that now collide ignoring case keep the oldest row as-is; later ones get their id appended.
+UPDATE goats SET name = name || ' (' || id || ')'
+WHERE EXISTS (
+    SELECT 1 FROM goats older
+    WHERE older.name = goats.name COLLATE NOCASE AND older.id < goats.id
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_goats_name_nocase ON goats(name COLLATE NOCASE);
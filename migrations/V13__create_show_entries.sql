@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS show_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    show_name TEXT NOT NULL,
+    show_date DATE NOT NULL,
+    location TEXT,
+    class TEXT,
+    placement INTEGER CHECK(placement IS NULL OR placement >= 1),
+    prize_amount REAL,
+    notes TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_show_entries_goat_id ON show_entries(goat_id);
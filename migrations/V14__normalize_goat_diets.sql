@@ -0,0 +1,13 @@
+-- Canonicalise the casing of known diets; unrecognised free text is left untouched.
+UPDATE goats SET diet = CASE lower(trim(diet))
+    WHEN 'hay' THEN 'Hay'
+    WHEN 'pasture' THEN 'Pasture'
+    WHEN 'grass' THEN 'Pasture'
+    WHEN 'grazing' THEN 'Pasture'
+    WHEN 'mixed' THEN 'Mixed'
+    WHEN 'concentrate' THEN 'Concentrate'
+    ELSE diet
+END
+WHERE diet IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_goats_diet ON goats(diet);
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS famacha_scores (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    score INTEGER NOT NULL CHECK(score BETWEEN 1 AND 5),
+    scored_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    scored_by TEXT,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_famacha_scores_goat_id ON famacha_scores(goat_id, scored_at);
+
+CREATE TABLE IF NOT EXISTS deworming_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK(status IN ('pending', 'completed')),
+    reason TEXT,
+    product TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
@@ -0,0 +1,54 @@
+-- SQLite cannot alter a CHECK constraint, so the goats table is rebuilt to admit 'Wether'.
+-- Dropping the old table also drops its indexes and change_log triggers; both are recreated.
+CREATE TABLE goats_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    breed TEXT NOT NULL,
+    name TEXT NOT NULL,
+    gender TEXT CHECK(gender IN ('Male', 'Female', 'Wether')) NOT NULL,
+    offspring INTEGER DEFAULT 0,
+    cost REAL,
+    weight REAL,
+    current_price REAL,
+    diet TEXT,
+    last_bred DATE,
+    health_status TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP,
+    deleted_at TIMESTAMP,
+    lifecycle_state TEXT NOT NULL DEFAULT 'Active',
+    birth_date DATE,
+    is_pregnant INTEGER NOT NULL DEFAULT 0,
+    is_sold INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT INTO goats_new (
+    id, breed, name, gender, offspring, cost, weight, current_price, diet, last_bred,
+    health_status, created_at, updated_at, deleted_at, lifecycle_state, birth_date,
+    is_pregnant, is_sold
+)
+SELECT
+    id, breed, name, gender, offspring, cost, weight, current_price, diet, last_bred,
+    health_status, created_at, updated_at, deleted_at, lifecycle_state, birth_date,
+    is_pregnant, is_sold
+FROM goats;
+
+DROP TABLE goats;
+ALTER TABLE goats_new RENAME TO goats;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_goats_name_nocase ON goats(name COLLATE NOCASE);
+CREATE INDEX IF NOT EXISTS idx_goats_diet ON goats(diet);
+
+CREATE TRIGGER IF NOT EXISTS goats_change_insert AFTER INSERT ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_change_update AFTER UPDATE ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat', NEW.id, 'update');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_change_delete AFTER DELETE ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat', OLD.id, 'delete');
+END;
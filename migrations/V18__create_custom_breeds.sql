@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS custom_breeds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_custom_breeds_name_nocase ON custom_breeds(name COLLATE NOCASE);
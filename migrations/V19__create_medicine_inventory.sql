@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS medicine_inventory (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    category TEXT,
+    stock_units REAL NOT NULL DEFAULT 0,
+    unit TEXT NOT NULL,
+    expiry_date TEXT,
+    cost_per_unit REAL
+);
+
+CREATE TABLE IF NOT EXISTS medicine_dispensing (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    medicine_id INTEGER NOT NULL,
+    goat_id INTEGER NOT NULL,
+    amount REAL NOT NULL,
+    reason TEXT NOT NULL,
+    dispensed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (medicine_id) REFERENCES medicine_inventory(id) ON DELETE CASCADE,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_medicine_dispensing_goat_id ON medicine_dispensing(goat_id);
@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS behavior_observations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    behavior TEXT NOT NULL,
+    intensity TEXT NOT NULL CHECK(intensity IN ('Mild', 'Moderate', 'Severe')),
+    recorded_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    observed_by TEXT,
+    notes TEXT,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_behavior_observations_goat_recorded
+    ON behavior_observations(goat_id, recorded_at);
+
+CREATE TABLE IF NOT EXISTS alerts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER,
+    kind TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_alerts_open ON alerts(resolved_at, created_at);
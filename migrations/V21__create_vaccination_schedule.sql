@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS vaccination_schedule (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    vaccine_id INTEGER NOT NULL,
+    administered_on DATE,
+    next_due_on DATE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
+    FOREIGN KEY (vaccine_id) REFERENCES vaccines(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_vaccination_schedule_goat_vaccine
+    ON vaccination_schedule(goat_id, vaccine_id, next_due_on);
@@ -0,0 +1,9 @@
+-- Exact money amounts in minor units (paise); the REAL columns are kept in step for
+-- older readers.
+ALTER TABLE goats ADD COLUMN cost_minor INTEGER;
+ALTER TABLE goats ADD COLUMN current_price_minor INTEGER;
+ALTER TABLE goats ADD COLUMN currency TEXT NOT NULL DEFAULT 'INR';
+
+UPDATE goats SET cost_minor = CAST(ROUND(cost * 100) AS INTEGER) WHERE cost IS NOT NULL;
+UPDATE goats SET current_price_minor = CAST(ROUND(current_price * 100) AS INTEGER)
+WHERE current_price IS NOT NULL;
@@ -0,0 +1,60 @@
+-- Record the entity's name alongside each change so the activity feed can show it after
+-- deletion, and extend change tracking to workers and equipment.
+ALTER TABLE change_log ADD COLUMN name TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_change_log_created ON change_log(created_at);
+
+DROP TRIGGER IF EXISTS goats_change_insert;
+DROP TRIGGER IF EXISTS goats_change_update;
+DROP TRIGGER IF EXISTS goats_change_delete;
+
+CREATE TRIGGER IF NOT EXISTS goats_change_insert AFTER INSERT ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('goat', NEW.id, 'insert', NEW.name);
+END;
+
+-- Soft deletes arrive as updates setting `deleted_at`; log them as deletions.
+CREATE TRIGGER IF NOT EXISTS goats_change_update AFTER UPDATE ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES (
+        'goat',
+        NEW.id,
+        CASE WHEN NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN 'delete' ELSE 'update' END,
+        NEW.name
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_change_delete AFTER DELETE ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('goat', OLD.id, 'delete', OLD.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS workers_change_insert AFTER INSERT ON workers
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('worker', NEW.id, 'insert', NEW.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS workers_change_update AFTER UPDATE ON workers
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('worker', NEW.id, 'update', NEW.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS workers_change_delete AFTER DELETE ON workers
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('worker', OLD.id, 'delete', OLD.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS equipment_change_insert AFTER INSERT ON equipment
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('equipment', NEW.id, 'insert', NEW.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS equipment_change_update AFTER UPDATE ON equipment
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('equipment', NEW.id, 'update', NEW.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS equipment_change_delete AFTER DELETE ON equipment
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('equipment', OLD.id, 'delete', OLD.name);
+END;
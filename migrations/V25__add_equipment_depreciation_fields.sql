@@ -0,0 +1,2 @@
+ALTER TABLE equipment ADD COLUMN purchase_price REAL;
+ALTER TABLE equipment ADD COLUMN useful_life_years INTEGER;
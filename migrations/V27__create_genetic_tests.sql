@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS genetic_tests (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    test_type TEXT NOT NULL,
+    test_date TEXT NOT NULL,
+    lab_name TEXT,
+    result_json TEXT NOT NULL DEFAULT '{}' CHECK(json_valid(result_json)),
+    report_url TEXT,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_genetic_tests_goat_date ON genetic_tests(goat_id, test_date);
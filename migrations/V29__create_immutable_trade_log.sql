@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS immutable_trade_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    transaction_hash TEXT NOT NULL,
+    goat_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    from_party TEXT,
+    to_party TEXT,
+    price REAL,
+    timestamp TEXT NOT NULL,
+    prev_hash TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_immutable_trade_log_goat ON immutable_trade_log(goat_id);
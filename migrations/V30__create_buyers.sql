@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS buyers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    contact TEXT,
+    notes TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE sales ADD COLUMN buyer_id INTEGER REFERENCES buyers(id) ON DELETE SET NULL;
+
+-- Promote the free-text buyer names of existing sales to buyer records.
+INSERT OR IGNORE INTO buyers (name)
+    SELECT DISTINCT trim(buyer) FROM sales WHERE trim(COALESCE(buyer, '')) != '';
+
+UPDATE sales
+SET buyer_id = (SELECT id FROM buyers WHERE name = trim(sales.buyer))
+WHERE trim(COALESCE(buyer, '')) != '';
+
+CREATE INDEX IF NOT EXISTS idx_sales_buyer ON sales(buyer_id);
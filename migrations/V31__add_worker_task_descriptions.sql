@@ -0,0 +1 @@
+ALTER TABLE worker_tasks ADD COLUMN description TEXT;
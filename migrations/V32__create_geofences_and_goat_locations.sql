@@ -0,0 +1,36 @@
+CREATE TABLE IF NOT EXISTS geofences (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    lat_center REAL NOT NULL,
+    lon_center REAL NOT NULL,
+    radius_m REAL NOT NULL CHECK(radius_m > 0),
+    alert_on TEXT NOT NULL CHECK(alert_on IN ('Exit', 'Entry', 'Both')),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS goat_locations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    lat REAL NOT NULL,
+    lon REAL NOT NULL,
+    recorded_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_goat_locations_goat ON goat_locations(goat_id, id);
+
+CREATE TABLE IF NOT EXISTS location_alerts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    geofence_id INTEGER,
+    geofence_name TEXT NOT NULL,
+    event TEXT NOT NULL CHECK(event IN ('Exit', 'Entry')),
+    lat REAL NOT NULL,
+    lon REAL NOT NULL,
+    distance_m REAL NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
+    FOREIGN KEY (geofence_id) REFERENCES geofences(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_location_alerts_created ON location_alerts(created_at);
@@ -0,0 +1,3 @@
+ALTER TABLE sensors ADD COLUMN space_id INTEGER REFERENCES spaces(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_sensors_space ON sensors(space_id);
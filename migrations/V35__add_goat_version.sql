@@ -0,0 +1 @@
+ALTER TABLE goats ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
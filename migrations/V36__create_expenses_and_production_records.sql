@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS expenses (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER,
+    category TEXT CHECK(category IN ('Feed', 'Vet', 'Labor', 'Equipment', 'Other')) NOT NULL,
+    amount REAL NOT NULL,
+    incurred_on DATE NOT NULL DEFAULT CURRENT_DATE,
+    notes TEXT,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_expenses_goat ON expenses(goat_id, category);
+
+CREATE TABLE IF NOT EXISTS production_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    type TEXT CHECK(type IN ('Milk', 'Fiber', 'Meat')) NOT NULL,
+    quantity REAL NOT NULL,
+    revenue REAL NOT NULL DEFAULT 0,
+    recorded_on DATE NOT NULL DEFAULT CURRENT_DATE,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_production_records_goat ON production_records(goat_id, type);
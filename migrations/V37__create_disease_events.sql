@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS disease_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    disease_id INTEGER NOT NULL,
+    event TEXT CHECK(event IN ('Diagnosed', 'Resolved')) NOT NULL,
+    occurred_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_disease_events_occurred ON disease_events(occurred_at);
+
+-- Links present before this migration count as diagnosed when they were created.
+INSERT INTO disease_events (goat_id, disease_id, event, occurred_at)
+SELECT goat_id, disease_id, 'Diagnosed', COALESCE(created_at, CURRENT_TIMESTAMP)
+FROM goat_diseases;
+
+CREATE TRIGGER IF NOT EXISTS goat_diseases_diagnosed AFTER INSERT ON goat_diseases
+BEGIN
+    INSERT INTO disease_events (goat_id, disease_id, event, occurred_at)
+    VALUES (NEW.goat_id, NEW.disease_id, 'Diagnosed', COALESCE(NEW.created_at, CURRENT_TIMESTAMP));
+END;
+
+CREATE TRIGGER IF NOT EXISTS goat_diseases_resolved AFTER DELETE ON goat_diseases
+BEGIN
+    INSERT INTO disease_events (goat_id, disease_id, event)
+    VALUES (OLD.goat_id, OLD.disease_id, 'Resolved');
+END;
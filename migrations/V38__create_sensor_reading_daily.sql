@@ -0,0 +1,13 @@
+CREATE INDEX IF NOT EXISTS idx_sensor_readings_recorded ON sensor_readings(recorded_at);
+
+CREATE TABLE IF NOT EXISTS sensor_reading_daily (
+    sensor_id INTEGER NOT NULL,
+    day TEXT NOT NULL,
+    count INTEGER NOT NULL,
+    avg REAL NOT NULL,
+    min REAL NOT NULL,
+    max REAL NOT NULL,
+    anomalies INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (sensor_id, day),
+    FOREIGN KEY (sensor_id) REFERENCES sensors(id) ON DELETE CASCADE
+);
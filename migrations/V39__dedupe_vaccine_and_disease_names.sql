@@ -0,0 +1,52 @@
+-- Vaccine and disease names are unique ignoring case and surrounding whitespace. Entries
+-- that collide fold into the oldest one: links move to it (a goat linked to both keeps a
+-- single link), then the duplicates are deleted and the remaining names trimmed.
+
+CREATE TEMP TABLE vaccine_duplicates AS
+SELECT id AS duplicate_id, keep_id FROM (
+    SELECT v.id,
+           (SELECT MIN(k.id) FROM vaccines k WHERE trim(k.name) = trim(v.name) COLLATE NOCASE) AS keep_id
+    FROM vaccines v
+)
+WHERE id <> keep_id;
+
+UPDATE OR IGNORE goat_vaccines
+SET vaccine_id = (SELECT keep_id FROM vaccine_duplicates WHERE duplicate_id = vaccine_id)
+WHERE vaccine_id IN (SELECT duplicate_id FROM vaccine_duplicates);
+DELETE FROM goat_vaccines WHERE vaccine_id IN (SELECT duplicate_id FROM vaccine_duplicates);
+
+UPDATE vaccination_schedule
+SET vaccine_id = (SELECT keep_id FROM vaccine_duplicates WHERE duplicate_id = vaccine_id)
+WHERE vaccine_id IN (SELECT duplicate_id FROM vaccine_duplicates);
+
+DELETE FROM vaccines WHERE id IN (SELECT duplicate_id FROM vaccine_duplicates);
+UPDATE vaccines SET name = trim(name) WHERE name <> trim(name);
+DROP TABLE vaccine_duplicates;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_vaccines_name_nocase ON vaccines(name COLLATE NOCASE);
+
+CREATE TEMP TABLE disease_duplicates AS
+SELECT id AS duplicate_id, keep_id FROM (
+    SELECT d.id,
+           (SELECT MIN(k.id) FROM diseases k WHERE trim(k.name) = trim(d.name) COLLATE NOCASE) AS keep_id
+    FROM diseases d
+)
+WHERE id <> keep_id;
+
+-- History moves first. Dropping a goat's second link fires the resolution trigger, and
+-- those events are discarded below since the goat still has the disease.
+UPDATE disease_events
+SET disease_id = (SELECT keep_id FROM disease_duplicates WHERE duplicate_id = disease_id)
+WHERE disease_id IN (SELECT duplicate_id FROM disease_duplicates);
+
+UPDATE OR IGNORE goat_diseases
+SET disease_id = (SELECT keep_id FROM disease_duplicates WHERE duplicate_id = disease_id)
+WHERE disease_id IN (SELECT duplicate_id FROM disease_duplicates);
+DELETE FROM goat_diseases WHERE disease_id IN (SELECT duplicate_id FROM disease_duplicates);
+DELETE FROM disease_events WHERE disease_id IN (SELECT duplicate_id FROM disease_duplicates);
+
+DELETE FROM diseases WHERE id IN (SELECT duplicate_id FROM disease_duplicates);
+UPDATE diseases SET name = trim(name) WHERE name <> trim(name);
+DROP TABLE disease_duplicates;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_diseases_name_nocase ON diseases(name COLLATE NOCASE);
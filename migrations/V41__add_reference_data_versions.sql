@@ -0,0 +1,75 @@
+-- Versions of the reference lists behind the `ETag`s of `GET /vaccines`, `GET /diseases` and
+-- `GET /breeds`, bumped by triggers in the transaction of every write that can change them.
+-- The breed list includes breeds recorded on live goats.
+
+CREATE TRIGGER IF NOT EXISTS vaccines_version_insert AFTER INSERT ON vaccines
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('vaccines_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS vaccines_version_update AFTER UPDATE ON vaccines
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('vaccines_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS vaccines_version_delete AFTER DELETE ON vaccines
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('vaccines_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS diseases_version_insert AFTER INSERT ON diseases
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('diseases_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS diseases_version_update AFTER UPDATE ON diseases
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('diseases_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS diseases_version_delete AFTER DELETE ON diseases
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('diseases_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS custom_breeds_version_insert AFTER INSERT ON custom_breeds
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS custom_breeds_version_update AFTER UPDATE ON custom_breeds
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS custom_breeds_version_delete AFTER DELETE ON custom_breeds
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_breeds_version_insert AFTER INSERT ON goats
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_breeds_version_update AFTER UPDATE OF breed, deleted_at ON goats
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_breeds_version_delete AFTER DELETE ON goats
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS goat_pedigree (
+    goat_id INTEGER PRIMARY KEY,
+    sire_id INTEGER,
+    dam_id INTEGER,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
+    FOREIGN KEY (sire_id) REFERENCES goats(id) ON DELETE SET NULL,
+    FOREIGN KEY (dam_id) REFERENCES goats(id) ON DELETE SET NULL
+);
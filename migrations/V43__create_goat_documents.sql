@@ -0,0 +1,20 @@
+-- Files attached to goats, such as purchase receipts and vet reports. Foreign keys are not
+-- enforced on our connections, so a trigger removes a goat's documents when its row is
+-- deleted; soft-deleted goats keep theirs.
+CREATE TABLE IF NOT EXISTS goat_documents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    filename TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    uploaded_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    content BLOB NOT NULL,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_goat_documents_goat_id ON goat_documents(goat_id);
+
+CREATE TRIGGER IF NOT EXISTS goats_documents_delete AFTER DELETE ON goats
+BEGIN
+    DELETE FROM goat_documents WHERE goat_id = OLD.id;
+END;
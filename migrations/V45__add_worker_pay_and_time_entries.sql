@@ -0,0 +1,17 @@
+-- Per-worker pay terms; NULL falls back to the farm-wide defaults
+ALTER TABLE workers ADD COLUMN hourly_rate REAL;
+ALTER TABLE workers ADD COLUMN overtime_threshold_hours REAL;
+
+-- Hours a worker put in on one day, the basis of payroll
+CREATE TABLE IF NOT EXISTS worker_time_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    worker_id INTEGER NOT NULL,
+    work_date DATE NOT NULL,
+    hours REAL NOT NULL CHECK(hours > 0 AND hours <= 24),
+    note TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (worker_id) REFERENCES workers(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_worker_time_entries_worker_date
+    ON worker_time_entries(worker_id, work_date);
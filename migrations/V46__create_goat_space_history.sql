@@ -0,0 +1,19 @@
+-- Every stay of a goat in a space; the open row (moved_out_at NULL) mirrors space_goats
+CREATE TABLE IF NOT EXISTS goat_space_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    space_id INTEGER NOT NULL,
+    moved_in_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    moved_out_at TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
+    FOREIGN KEY (space_id) REFERENCES spaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_goat_space_history_goat_id
+    ON goat_space_history(goat_id, moved_in_at);
+CREATE INDEX IF NOT EXISTS idx_goat_space_history_space_id
+    ON goat_space_history(space_id, moved_in_at);
+
+-- Current assignments become the first recorded stays
+INSERT INTO goat_space_history (goat_id, space_id, moved_in_at)
+SELECT goat_id, space_id, COALESCE(assigned_at, CURRENT_TIMESTAMP) FROM space_goats;
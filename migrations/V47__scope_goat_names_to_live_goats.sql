@@ -0,0 +1,89 @@
+-- Goat names only need to be unique among live goats, so a soft-deleted goat no longer
+-- holds on to its name. SQLite cannot drop a column's UNIQUE constraint, so the goats
+-- table is rebuilt as in V17, and the name index is recreated as a partial index.
+-- Dropping the old table also drops its indexes and triggers; all are recreated.
+CREATE TABLE goats_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    breed TEXT NOT NULL,
+    name TEXT NOT NULL,
+    gender TEXT CHECK(gender IN ('Male', 'Female', 'Wether')) NOT NULL,
+    offspring INTEGER DEFAULT 0,
+    cost REAL,
+    weight REAL,
+    current_price REAL,
+    diet TEXT,
+    last_bred DATE,
+    health_status TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP,
+    deleted_at TIMESTAMP,
+    lifecycle_state TEXT NOT NULL DEFAULT 'Active',
+    birth_date DATE,
+    is_pregnant INTEGER NOT NULL DEFAULT 0,
+    is_sold INTEGER NOT NULL DEFAULT 0,
+    cost_minor INTEGER,
+    current_price_minor INTEGER,
+    currency TEXT NOT NULL DEFAULT 'INR',
+    version INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT INTO goats_new (
+    id, breed, name, gender, offspring, cost, weight, current_price, diet, last_bred,
+    health_status, created_at, updated_at, deleted_at, lifecycle_state, birth_date,
+    is_pregnant, is_sold, cost_minor, current_price_minor, currency, version
+)
+SELECT
+    id, breed, name, gender, offspring, cost, weight, current_price, diet, last_bred,
+    health_status, created_at, updated_at, deleted_at, lifecycle_state, birth_date,
+    is_pregnant, is_sold, cost_minor, current_price_minor, currency, version
+FROM goats;
+
+DROP TABLE goats;
+ALTER TABLE goats_new RENAME TO goats;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_goats_name_nocase ON goats(name COLLATE NOCASE)
+    WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_goats_diet ON goats(diet);
+
+CREATE TRIGGER IF NOT EXISTS goats_change_insert AFTER INSERT ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('goat', NEW.id, 'insert', NEW.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_change_update AFTER UPDATE ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES (
+        'goat',
+        NEW.id,
+        CASE WHEN NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN 'delete' ELSE 'update' END,
+        NEW.name
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_change_delete AFTER DELETE ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('goat', OLD.id, 'delete', OLD.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_breeds_version_insert AFTER INSERT ON goats
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_breeds_version_update AFTER UPDATE OF breed, deleted_at ON goats
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_breeds_version_delete AFTER DELETE ON goats
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_documents_delete AFTER DELETE ON goats
+BEGIN
+    DELETE FROM goat_documents WHERE goat_id = OLD.id;
+END;
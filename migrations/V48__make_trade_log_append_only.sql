@@ -0,0 +1,10 @@
+-- Trade log rows can only be appended; the hash chain detects edits made around these
+CREATE TRIGGER IF NOT EXISTS immutable_trade_log_no_update BEFORE UPDATE ON immutable_trade_log
+BEGIN
+    SELECT RAISE(ABORT, 'immutable_trade_log is append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS immutable_trade_log_no_delete BEFORE DELETE ON immutable_trade_log
+BEGIN
+    SELECT RAISE(ABORT, 'immutable_trade_log is append-only');
+END;
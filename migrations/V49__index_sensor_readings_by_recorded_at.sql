@@ -0,0 +1,3 @@
+-- Anomaly detection reads each sensor's latest readings by recording time
+CREATE INDEX IF NOT EXISTS idx_sensor_readings_sensor_recorded
+    ON sensor_readings(sensor_id, recorded_at);
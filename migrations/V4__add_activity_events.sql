@@ -0,0 +1,42 @@
+ALTER TABLE goats ADD COLUMN updated_at TIMESTAMP;
+ALTER TABLE goat_vaccines ADD COLUMN created_at TIMESTAMP;
+ALTER TABLE goat_diseases ADD COLUMN created_at TIMESTAMP;
+
+UPDATE goat_vaccines SET created_at = CURRENT_TIMESTAMP WHERE created_at IS NULL;
+UPDATE goat_diseases SET created_at = CURRENT_TIMESTAMP WHERE created_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS goat_weight_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    weight REAL NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS feed_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER,
+    feed TEXT NOT NULL,
+    quantity_kg REAL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS vet_visits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    vet_name TEXT,
+    reason TEXT,
+    cost REAL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS sales (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    buyer TEXT,
+    price REAL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+ALTER TABLE goats ADD COLUMN deleted_at TIMESTAMP;
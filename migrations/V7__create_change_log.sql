@@ -0,0 +1,72 @@
+CREATE TABLE IF NOT EXISTS change_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity TEXT NOT NULL,
+    entity_id INTEGER,
+    action TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER IF NOT EXISTS goats_change_insert AFTER INSERT ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_change_update AFTER UPDATE ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat', NEW.id, 'update');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_change_delete AFTER DELETE ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat', OLD.id, 'delete');
+END;
+
+CREATE TRIGGER IF NOT EXISTS vaccines_change_insert AFTER INSERT ON vaccines
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('vaccine', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER IF NOT EXISTS vaccines_change_update AFTER UPDATE ON vaccines
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('vaccine', NEW.id, 'update');
+END;
+
+CREATE TRIGGER IF NOT EXISTS vaccines_change_delete AFTER DELETE ON vaccines
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('vaccine', OLD.id, 'delete');
+END;
+
+CREATE TRIGGER IF NOT EXISTS diseases_change_insert AFTER INSERT ON diseases
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('disease', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER IF NOT EXISTS diseases_change_update AFTER UPDATE ON diseases
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('disease', NEW.id, 'update');
+END;
+
+CREATE TRIGGER IF NOT EXISTS diseases_change_delete AFTER DELETE ON diseases
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('disease', OLD.id, 'delete');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goat_vaccines_change_insert AFTER INSERT ON goat_vaccines
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat_vaccine', NEW.goat_id, 'insert');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goat_vaccines_change_delete AFTER DELETE ON goat_vaccines
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat_vaccine', OLD.goat_id, 'delete');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goat_diseases_change_insert AFTER INSERT ON goat_diseases
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat_disease', NEW.goat_id, 'insert');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goat_diseases_change_delete AFTER DELETE ON goat_diseases
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat_disease', OLD.goat_id, 'delete');
+END;
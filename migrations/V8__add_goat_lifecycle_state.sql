@@ -0,0 +1 @@
+ALTER TABLE goats ADD COLUMN lifecycle_state TEXT NOT NULL DEFAULT 'Active';
@@ -0,0 +1,10 @@
+ALTER TABLE goats ADD COLUMN birth_date DATE;
+ALTER TABLE goats ADD COLUMN is_pregnant INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE goats ADD COLUMN is_sold INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS market_prices (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    breed TEXT,
+    price_per_kg REAL NOT NULL,
+    recorded_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,88 @@
+//! In-process cache of the unfiltered `GET /goats` response.
+//!
+//! Clients poll the goat list far more often than goats change, so the serialized body is
+//! kept and served as is while it is known to be current. An entry is current while
+//! both of these hold:
+//! - no goat write has called `invalidate` since the entry was computed;
+//! - the `change_log` sequence still has the value read before it was computed.
+//!
+//! The sequence catches writes that bypass the goat handlers, such as imports, catalog
+//! merges or other processes. `invalidate` covers tables no trigger tracks, such as tags.
+
+use actix_web::web::Bytes;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use tracing::{debug, trace};
+
+/// A serialized goat list and the state it was computed from.
+#[derive(Debug)]
+pub struct CachedGoats {
+    pub generation: u64,
+    pub change_seq: i64,
+    pub body: Bytes,
+    pub count: usize,
+}
+
+/// Shared cache of the goat list; register one as app data.
+#[derive(Debug, Default)]
+pub struct GoatListCache {
+    generation: AtomicU64,
+    entry: RwLock<Option<Arc<CachedGoats>>>,
+}
+
+impl GoatListCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current generation. Read it before loading the goats to be stored, so a write
+    /// committed while they load makes `store` drop them.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// The cached list, if it is still current for `change_seq`.
+    pub fn get(&self, change_seq: i64) -> Option<Arc<CachedGoats>> {
+        let entry = self.entry.read().unwrap_or_else(|e| e.into_inner());
+        entry
+            .as_ref()
+            .filter(|cached| {
+                cached.generation == self.generation() && cached.change_seq == change_seq
+            })
+            .cloned()
+    }
+
+    /// Caches `body`, computed at `generation` and `change_seq`, unless a write has
+    /// invalidated that generation since or a newer list is already cached. Returns
+    /// whether the body was stored.
+    pub fn store(&self, generation: u64, change_seq: i64, body: Bytes, count: usize) -> bool {
+        let mut entry = self.entry.write().unwrap_or_else(|e| e.into_inner());
+        // Checked under the lock: `invalidate` bumps the generation before clearing the
+        // entry, so a list loaded before a write can never replace the cleared entry.
+        if generation != self.generation() {
+            trace!(generation, "Goat list outdated before it could be cached");
+            return false;
+        }
+        if entry
+            .as_ref()
+            .is_some_and(|cached| cached.change_seq > change_seq)
+        {
+            return false;
+        }
+        *entry = Some(Arc::new(CachedGoats {
+            generation,
+            change_seq,
+            body,
+            count,
+        }));
+        true
+    }
+
+    /// Drops the cached list. Call after committing any write that changes a goat, its
+    /// relations or its tags.
+    pub fn invalidate(&self) {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        *self.entry.write().unwrap_or_else(|e| e.into_inner()) = None;
+        debug!(generation, "Goat list cache invalidated");
+    }
+}
@@ -0,0 +1,423 @@
+//! Runtime configuration read from environment variables.
+//!
+//! Values are read when needed rather than cached at startup, so tests can adjust them
+//! per case; the db module caches the slow-query threshold itself since it is consulted
+//! on every query. Unparseable values fall back to the default with a warning, except
+//! the SQLite pragmas, which are rejected since a silently wrong durability setting is
+//! worse than refusing to start.
+
+use crate::errors::AppError;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::warn;
+
+/// Environment variable for the minimum sale weight, in kilograms.
+pub const SALE_MIN_WEIGHT_KG: &str = "SALE_MIN_WEIGHT_KG";
+/// Environment variable for the minimum sale age, in months.
+pub const SALE_MIN_AGE_MONTHS: &str = "SALE_MIN_AGE_MONTHS";
+
+/// Environment variable for the weight below which a goat needs attention, in kilograms.
+pub const ATTENTION_MIN_WEIGHT_KG: &str = "YAGI_ATTENTION_MIN_WEIGHT_KG";
+/// Environment variable for the weight above which a goat needs attention, in kilograms.
+pub const ATTENTION_MAX_WEIGHT_KG: &str = "YAGI_ATTENTION_MAX_WEIGHT_KG";
+/// Environment variable for how many days without a weighing a goat needs attention.
+pub const ATTENTION_WEIGHT_STALE_DAYS: &str = "YAGI_ATTENTION_WEIGHT_STALE_DAYS";
+
+/// Environment variable for the hourly rate of workers without their own, in rupees.
+pub const DEFAULT_HOURLY_RATE: &str = "YAGI_DEFAULT_HOURLY_RATE";
+/// Environment variable for the monthly hours beyond which work counts as overtime, for
+/// workers without their own threshold.
+pub const OVERTIME_THRESHOLD_HOURS: &str = "YAGI_OVERTIME_THRESHOLD_HOURS";
+/// Environment variable for the factor overtime hours are paid at, relative to the
+/// hourly rate.
+pub const OVERTIME_PAY_MULTIPLIER: &str = "YAGI_OVERTIME_PAY_MULTIPLIER";
+
+/// Environment variable for the slow-query log threshold, in milliseconds.
+pub const SLOW_QUERY_MS: &str = "YAGI_SLOW_QUERY_MS";
+
+/// Environment variable for the overdue-vaccination scan interval, in seconds.
+pub const VACCINATION_REMINDER_SECS: &str = "YAGI_VACCINATION_REMINDER_SECS";
+
+/// Environment variable for the WAL checkpoint interval, in seconds.
+pub const WAL_CHECKPOINT_SECS: &str = "YAGI_WAL_CHECKPOINT_SECS";
+
+/// Environment variable for the per-client request limit, in requests per minute.
+pub const RATE_LIMIT_PER_MINUTE: &str = "YAGI_RATE_LIMIT_PER_MINUTE";
+
+/// Environment variable holding the token admin-only routes expect in `X-Admin-Token`.
+pub const ADMIN_TOKEN: &str = "YAGI_ADMIN_TOKEN";
+
+/// Environment variable listing the API keys, comma-separated, that the rate limiter
+/// accepts as client identities.
+pub const API_KEYS: &str = "YAGI_API_KEYS";
+
+/// Environment variable for how many recent readings sensor anomaly detection compares
+/// against.
+pub const SENSOR_ANOMALY_WINDOW: &str = "YAGI_SENSOR_ANOMALY_WINDOW";
+
+/// Environment variable for how many standard deviations from the rolling mean a sensor
+/// reading must be to count as an anomaly.
+pub const SENSOR_ANOMALY_SIGMA: &str = "YAGI_SENSOR_ANOMALY_SIGMA";
+
+/// Environment variable for how many days of raw sensor readings are kept.
+pub const SENSOR_RETENTION_DAYS: &str = "YAGI_SENSOR_RETENTION_DAYS";
+
+/// Environment variable for the sensor reading pruning interval, in seconds.
+pub const SENSOR_PRUNE_SECS: &str = "YAGI_SENSOR_PRUNE_SECS";
+
+/// Environment variable choosing whether pruned sensor readings are first rolled up into
+/// daily summaries: `true` or `false`.
+pub const SENSOR_ROLLUP: &str = "YAGI_SENSOR_ROLLUP";
+
+/// Environment variable choosing whether request and response bodies are logged at debug
+/// level: `true` or `false`.
+pub const LOG_BODIES: &str = "YAGI_LOG_BODIES";
+
+/// Environment variable for the SQLite journal mode: `WAL`, `DELETE`, `TRUNCATE` or
+/// `MEMORY`. WAL needs shared memory, which networked filesystems often lack.
+pub const JOURNAL_MODE: &str = "YAGI_JOURNAL_MODE";
+
+/// SQLite journal modes the server can run with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JournalMode {
+    #[default]
+    Wal,
+    Delete,
+    Truncate,
+    Memory,
+}
+
+impl JournalMode {
+    /// Every supported mode, in the order they are listed in error messages.
+    pub const ALL: [JournalMode; 4] = [
+        JournalMode::Wal,
+        JournalMode::Delete,
+        JournalMode::Truncate,
+        JournalMode::Memory,
+    ];
+
+    /// The mode as passed to `PRAGMA journal_mode`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JournalMode::Wal => "WAL",
+            JournalMode::Delete => "DELETE",
+            JournalMode::Truncate => "TRUNCATE",
+            JournalMode::Memory => "MEMORY",
+        }
+    }
+}
+
+impl fmt::Display for JournalMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for JournalMode {
+    type Err = AppError;
+
+    /// Parses a mode name, ignoring case and surrounding whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_pragma_value("journal mode", s, JournalMode::ALL, JournalMode::as_str)
+    }
+}
+
+/// Environment variable for SQLite's `synchronous` setting: `OFF`, `NORMAL` or `FULL`.
+pub const SYNCHRONOUS: &str = "YAGI_SYNCHRONOUS";
+
+/// How hard SQLite works to get each commit onto disk.
+///
+/// - `Full` syncs on every commit; no committed transaction is lost on power failure.
+/// - `Normal` syncs less often. In WAL mode the database never corrupts, but the last
+///   commits before a power failure or OS crash may roll back; an application crash
+///   loses nothing. Commits are markedly cheaper, hence the default.
+/// - `Off` never syncs. Fastest, but an OS crash or power failure can corrupt the
+///   database; only for throwaway data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SynchronousMode {
+    Off,
+    #[default]
+    Normal,
+    Full,
+}
+
+impl SynchronousMode {
+    /// Every supported setting, in the order they are listed in error messages.
+    pub const ALL: [SynchronousMode; 3] = [
+        SynchronousMode::Off,
+        SynchronousMode::Normal,
+        SynchronousMode::Full,
+    ];
+
+    /// The setting as passed to `PRAGMA synchronous`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SynchronousMode::Off => "OFF",
+            SynchronousMode::Normal => "NORMAL",
+            SynchronousMode::Full => "FULL",
+        }
+    }
+}
+
+impl fmt::Display for SynchronousMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SynchronousMode {
+    type Err = AppError;
+
+    /// Parses a setting name, ignoring case and surrounding whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_pragma_value(
+            "synchronous setting",
+            s,
+            SynchronousMode::ALL,
+            SynchronousMode::as_str,
+        )
+    }
+}
+
+/// Finds the value in `all` whose name matches `s`, ignoring case and surrounding
+/// whitespace, or names every allowed value in the error.
+fn parse_pragma_value<T: Copy, const N: usize>(
+    kind: &str,
+    s: &str,
+    all: [T; N],
+    name: fn(T) -> &'static str,
+) -> Result<T, AppError> {
+    let s = s.trim();
+    all.into_iter()
+        .find(|value| name(*value).eq_ignore_ascii_case(s))
+        .ok_or_else(|| {
+            let allowed: Vec<&str> = all.into_iter().map(name).collect();
+            AppError::InvalidInput(format!(
+                "Unsupported {} '{}'; expected one of {}",
+                kind,
+                s,
+                allowed.join(", ")
+            ))
+        })
+}
+
+/// Environment variable for the most database connections open at once.
+pub const DB_POOL_SIZE: &str = "YAGI_DB_POOL_SIZE";
+
+/// Environment variable for how long a request waits for a free database connection
+/// before failing with 503, in milliseconds.
+pub const DB_POOL_TIMEOUT_MS: &str = "YAGI_DB_POOL_TIMEOUT_MS";
+
+/// Environment variable for the largest JSON request body accepted, in bytes.
+pub const MAX_BODY_BYTES: &str = "YAGI_MAX_BODY_BYTES";
+
+/// Environment variable for the largest JSON request body accepted by
+/// `POST /admin/import`, in bytes.
+pub const MAX_IMPORT_BODY_BYTES: &str = "YAGI_MAX_IMPORT_BODY_BYTES";
+
+/// Environment variable for the largest file accepted by `POST /goats/{id}/documents`,
+/// in bytes.
+pub const MAX_DOCUMENT_BYTES: &str = "YAGI_MAX_DOCUMENT_BYTES";
+
+/// Environment variable for how long a request may hold a database connection before
+/// its running query is interrupted, in milliseconds.
+pub const QUERY_TIMEOUT_MS: &str = "YAGI_QUERY_TIMEOUT_MS";
+
+/// Environment variable for the query timeout of `GET /admin/export`, in milliseconds.
+pub const EXPORT_QUERY_TIMEOUT_MS: &str = "YAGI_EXPORT_QUERY_TIMEOUT_MS";
+
+/// Reads `key` from the environment, returning `default` if it is unset or invalid.
+pub fn env_or<T: FromStr>(key: &str, default: T) -> T {
+    match std::env::var(key) {
+        Ok(raw) => raw.trim().parse().unwrap_or_else(|_| {
+            warn!(key, value = %raw, "Ignoring unparseable environment variable");
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+/// Minimum weight (kg) a goat must reach to be offered for sale.
+pub fn sale_min_weight_kg() -> f64 {
+    env_or(SALE_MIN_WEIGHT_KG, 25.0)
+}
+
+/// Minimum age (months) a goat must reach to be offered for sale.
+pub fn sale_min_age_months() -> u32 {
+    env_or(SALE_MIN_AGE_MONTHS, 6)
+}
+
+/// Weight (kg) below which a goat is flagged as underweight; 15 by default.
+pub fn attention_min_weight_kg() -> f64 {
+    env_or(ATTENTION_MIN_WEIGHT_KG, 15.0)
+}
+
+/// Weight (kg) above which a goat is flagged as overweight; 120 by default.
+pub fn attention_max_weight_kg() -> f64 {
+    env_or(ATTENTION_MAX_WEIGHT_KG, 120.0)
+}
+
+/// Days without a recorded weight after which a goat is flagged; 30 by default.
+pub fn attention_weight_stale_days() -> u32 {
+    env_or(ATTENTION_WEIGHT_STALE_DAYS, 30)
+}
+
+/// Hourly rate, in rupees, for workers without their own; 100 by default.
+pub fn default_hourly_rate() -> f64 {
+    env_or(DEFAULT_HOURLY_RATE, 100.0)
+}
+
+/// Monthly hours beyond which work is overtime, for workers without their own threshold;
+/// 208 (26 eight-hour days) by default.
+pub fn overtime_threshold_hours() -> f64 {
+    env_or(OVERTIME_THRESHOLD_HOURS, 208.0)
+}
+
+/// Factor applied to the hourly rate for overtime hours; 1.5 by default.
+pub fn overtime_pay_multiplier() -> f64 {
+    env_or(OVERTIME_PAY_MULTIPLIER, 1.5)
+}
+
+/// Queries slower than this many milliseconds are logged at warn level.
+pub fn slow_query_ms() -> u64 {
+    env_or(SLOW_QUERY_MS, 200)
+}
+
+/// How often the background job scans for overdue vaccinations; daily by default.
+pub fn vaccination_reminder_interval() -> Duration {
+    Duration::from_secs(env_or(VACCINATION_REMINDER_SECS, 86_400).max(1))
+}
+
+/// How often the write-ahead log is checkpointed; every five minutes by default.
+pub fn wal_checkpoint_interval() -> Duration {
+    Duration::from_secs(env_or(WAL_CHECKPOINT_SECS, 300).max(1))
+}
+
+/// Requests each API key or IP may make per minute before receiving HTTP 429.
+pub fn rate_limit_per_minute() -> u32 {
+    env_or(RATE_LIMIT_PER_MINUTE, 120)
+}
+
+/// Token for admin-only routes; unset or blank leaves those routes locked.
+pub fn admin_token() -> Option<String> {
+    std::env::var(ADMIN_TOKEN)
+        .ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// API keys the rate limiter trusts as client identities; none unless configured.
+pub fn api_keys() -> Vec<String> {
+    std::env::var(API_KEYS)
+        .map(|raw| {
+            raw.split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Number of recent readings per sensor used for the rolling mean and deviation.
+pub fn sensor_anomaly_window() -> u32 {
+    env_or(SENSOR_ANOMALY_WINDOW, 20).max(1)
+}
+
+/// Readings beyond this many standard deviations from the rolling mean raise an alert;
+/// 3 by default. Values that are not positive would flag every reading, so they fall
+/// back to the default with a warning.
+pub fn sensor_anomaly_sigma() -> f64 {
+    let sigma = env_or(SENSOR_ANOMALY_SIGMA, 3.0);
+    if sigma > 0.0 && sigma.is_finite() {
+        sigma
+    } else {
+        warn!(
+            key = SENSOR_ANOMALY_SIGMA,
+            sigma, "Ignoring non-positive anomaly sigma"
+        );
+        3.0
+    }
+}
+
+/// Days of raw sensor readings kept before pruning; 90 by default.
+pub fn sensor_retention_days() -> u32 {
+    env_or(SENSOR_RETENTION_DAYS, 90)
+}
+
+/// How often old sensor readings are pruned; daily by default.
+pub fn sensor_prune_interval() -> Duration {
+    Duration::from_secs(env_or(SENSOR_PRUNE_SECS, 86_400).max(1))
+}
+
+/// Whether pruned sensor readings are rolled up into daily summaries; true by default.
+pub fn sensor_rollup() -> bool {
+    env_or(SENSOR_ROLLUP, true)
+}
+
+/// Whether request and response bodies are logged at debug level; false by default.
+pub fn log_bodies() -> bool {
+    env_or(LOG_BODIES, false)
+}
+
+/// Most database connections the pool keeps open; 10 by default.
+pub fn db_pool_size() -> u32 {
+    env_or(DB_POOL_SIZE, 10).max(1)
+}
+
+/// How long to wait for a pooled connection; 30 seconds by default.
+pub fn db_pool_timeout() -> Duration {
+    Duration::from_millis(env_or(DB_POOL_TIMEOUT_MS, 30_000).max(1))
+}
+
+/// Query timeout for connections checked out by requests; 10 seconds by default.
+pub fn query_timeout() -> Duration {
+    Duration::from_millis(env_or(QUERY_TIMEOUT_MS, 10_000).max(1))
+}
+
+/// Query timeout for snapshot exports, which read every table; two minutes by default.
+pub fn export_query_timeout() -> Duration {
+    Duration::from_millis(env_or(EXPORT_QUERY_TIMEOUT_MS, 120_000).max(1))
+}
+
+/// JSON body limit for single-record routes; 256 KiB by default.
+pub fn max_body_bytes() -> usize {
+    env_or(MAX_BODY_BYTES, 256 * 1024).max(1)
+}
+
+/// JSON body limit for snapshot imports, which carry the whole database; 32 MiB by
+/// default, and never below `max_body_bytes`.
+pub fn max_import_body_bytes() -> usize {
+    env_or(MAX_IMPORT_BODY_BYTES, 32 * 1024 * 1024).max(max_body_bytes())
+}
+
+/// Size limit for goat document uploads; 10 MiB by default.
+pub fn max_document_bytes() -> usize {
+    env_or(MAX_DOCUMENT_BYTES, 10 * 1024 * 1024).max(1)
+}
+
+/// Journal mode for database connections; WAL unless `YAGI_JOURNAL_MODE` is set.
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` listing the supported modes if the variable holds
+/// anything else.
+pub fn journal_mode() -> Result<JournalMode, AppError> {
+    match std::env::var(JOURNAL_MODE) {
+        Ok(raw) => raw.parse(),
+        Err(_) => Ok(JournalMode::default()),
+    }
+}
+
+/// `synchronous` setting for database connections; NORMAL unless `YAGI_SYNCHRONOUS` is
+/// set. See `SynchronousMode` for the durability each setting gives.
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` listing the supported settings if the variable
+/// holds anything else.
+pub fn synchronous_mode() -> Result<SynchronousMode, AppError> {
+    match std::env::var(SYNCHRONOUS) {
+        Ok(raw) => raw.parse(),
+        Err(_) => Ok(SynchronousMode::default()),
+    }
+}
@@ -10,53 +10,366 @@
 //! Detailed multi-level logging is applied throughout for diagnostics and troubleshooting.
 //! Errors are carefully mapped to the app’s unified `AppError` type.
 
-use crate::db_helpers::{str_to_breed, str_to_gender};
-use crate::errors::{AppError, ParseEnumError};
+use crate::config::{
+    JournalMode, SynchronousMode, db_pool_size, db_pool_timeout, journal_mode, query_timeout,
+    slow_query_ms, synchronous_mode,
+};
+use crate::db_helpers::{
+    BUILTIN_BREEDS, SQLITE_TIMESTAMP_FORMAT, breed_to_str, diet_to_str, gender_to_str,
+    normalize_name, parse_iso_date, str_to_breed, str_to_diet, str_to_gender,
+};
+use crate::domain::anomaly::{MIN_ANOMALY_SAMPLES, RollingStats, is_anomaly};
+use crate::domain::attention::{
+    AttentionFlag, AttentionKind, AttentionReason, AttentionThresholds,
+};
+use crate::domain::breeding::{Parents, Pedigree};
+use crate::domain::finance::{DepreciationInfo, compute_depreciation};
+use crate::domain::geo::{alerts_on, fence_crossing, haversine_distance};
+use crate::domain::payroll::{PayTerms, compute_monthly_pay};
+use crate::domain::sale::{SALEABLE_HEALTH_STATUS, SaleCandidate};
+use crate::domain::simulation::PopulationRates;
+use crate::domain::trade_log::{GENESIS_HASH, compute_trade_hash, trade_payload};
+use crate::domain::visualization::{MapPosition, SpaceLayout};
+use crate::errors::{AppError, FieldError};
+use crate::models::{
+    ActivityEntry, AttachedDocument, BehaviorConcern, BehaviorObservation, Breed, BreedPriceHint,
+    BreedStats, BreedWeightGain, BulkTransferSummary, Buyer, BuyerPurchase, CONCERNING_BEHAVIORS,
+    CampaignGoatResult, CampaignSummary, CensusReport, Cohort, CohortStats, CustomBreed,
+    DailyReport, DataQualityIssue, DataQualityReport, DiseaseRef, DiseaseTrendPoint,
+    EnvironmentReading, Equipment, FAMACHA_ACTION_THRESHOLD, FamachaAlert, FamachaScore,
+    FinancialStats, Gender, GeneticTest, GeneticTraitCount, Geofence, Goat, GoatChanges,
+    GoatDocument, GoatParams, GoatSearchParams, GoatTombstone, HealthTrendPoint, ImportMode,
+    ImportSummary, LocationAlert, LocationPayload, LocationReport, MedicineItem, MergeSummary,
+    MonthlyFinancialReport, OverdueVaccination, ProfitAnalysis, ReadingBucket, ReclassifySummary,
+    RestockPayload, Sale, SalePayload, Sensor, SensorPruneSummary, SensorReading,
+    SensorReadingBucket, SensorReadingPayload, ShowEntry, Snapshot, Space, SpaceEnvironment,
+    SpaceGoats, SpaceMovement, SpaceResident, Supplier, SupplierPurchase, SupplierPurchases,
+    TimeEntry, TimelineEvent, TradeLogEntry, TradePayload, TrendInterval, VaccinationCampaign,
+    VaccineCoverage, VaccineRef, WeightEntry, Worker, WorkerPayTerms, WorkerPayroll,
+    WorkerPerformance, WorkerTask,
+};
+use crate::money::{Currency, Money};
+use crate::schema::ensure_schema;
+use crate::units::round2;
+use crate::watchdog::{WatchGuard, watch};
+use crate::writer::Writer;
+use chrono::NaiveDate;
 use r2d2::{Pool, PooledConnection};
 use r2d2_sqlite::SqliteConnectionManager;
-use shared::{Breed, DiseaseRef, Gender, GoatParams, VaccineRef};
 //use refinery::embed_migrations;
-use rusqlite::{Connection, OpenFlags, OptionalExtension, Row, Transaction};
-use std::sync::Arc;
-use tracing::{error, info, trace};
+use rand::Rng;
+use rusqlite::{
+    Connection, ErrorCode, OpenFlags, OptionalExtension, Row, ToSql, Transaction,
+    TransactionBehavior, params, params_from_iter, types::ValueRef,
+};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, field, info, info_span, trace, warn};
+
+/// Version of the document produced by `DbPool::export_snapshot`; imports of any other
+/// version are rejected.
+pub const SNAPSHOT_SCHEMA_VERSION: u32 = 1;
+
+/// `document_version` written by `export_goat_document` and accepted on import.
+pub const GOAT_DOCUMENT_VERSION: u32 = 1;
+
+/// Thresholds at or above this many milliseconds disable slow-query timing entirely.
+pub const SLOW_QUERY_DISABLED_MS: u64 = 3_600_000;
+
+/// Settings keys holding the versions of the vaccine, disease and breed lists. Schema
+/// triggers bump them within every write that can change the list.
+pub const VACCINES_VERSION_SETTING: &str = "vaccines_version";
+pub const DISEASES_VERSION_SETTING: &str = "diseases_version";
+pub const BREEDS_VERSION_SETTING: &str = "breeds_version";
+
+/// Slow-query threshold, read once from `YAGI_SLOW_QUERY_MS` since it is consulted on
+/// every timed query. `None` means timing is disabled.
+fn slow_query_threshold() -> Option<Duration> {
+    static THRESHOLD: OnceLock<Option<Duration>> = OnceLock::new();
+    *THRESHOLD.get_or_init(|| {
+        let ms = slow_query_ms();
+        (ms < SLOW_QUERY_DISABLED_MS).then(|| Duration::from_millis(ms))
+    })
+}
+
+/// Runs `op` inside a `db.query` span, warning with `sql` and the elapsed time if it takes
+/// longer than `threshold`.
+///
+/// The span carries the SQL text only; parameters are bound separately and never logged.
+/// With `threshold` set to `None` the clock is never read, so disabled logging costs nothing.
+pub fn timed_with<T>(threshold: Option<Duration>, sql: &str, op: impl FnOnce() -> T) -> T {
+    let span = info_span!("db.query", sql, elapsed_ms = field::Empty);
+    let _entered = span.enter();
+    let Some(threshold) = threshold else {
+        return op();
+    };
+    let started = Instant::now();
+    let result = op();
+    let elapsed = started.elapsed();
+    span.record("elapsed_ms", elapsed.as_millis() as u64);
+    if elapsed > threshold {
+        warn!(
+            sql,
+            elapsed_ms = elapsed.as_millis() as u64,
+            threshold_ms = threshold.as_millis() as u64,
+            "Slow query"
+        );
+    }
+    result
+}
+
+/// Runs `op` under the configured slow-query threshold (`YAGI_SLOW_QUERY_MS`, default 200).
+pub fn timed<T>(sql: &str, op: impl FnOnce() -> T) -> T {
+    timed_with(slow_query_threshold(), sql, op)
+}
+
+/// `Connection::execute`, timed by the slow-query log.
+pub fn timed_execute(
+    conn: &Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+) -> rusqlite::Result<usize> {
+    timed(sql, || conn.execute(sql, params))
+}
+
+/// `Connection::query_row`, timed by the slow-query log.
+pub fn timed_query_row<T>(
+    conn: &Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+    f: impl FnOnce(&Row) -> rusqlite::Result<T>,
+) -> rusqlite::Result<T> {
+    timed(sql, || conn.query_row(sql, params, f))
+}
+
+/// Prepares `sql`, maps every row with `f` and collects the results, timing the whole
+/// round trip for the slow-query log.
+pub fn timed_query_map<T>(
+    conn: &Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+    f: impl FnMut(&Row) -> rusqlite::Result<T>,
+) -> rusqlite::Result<Vec<T>> {
+    timed(sql, || {
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params, f)?.collect();
+        rows
+    })
+}
 
 // Embed refinery migrations located inside the `migrations` directory under `src`.
 //embed_migrations!("migrations");
 
+/// The full current schema, as applied to fresh databases.
+pub const SCHEMA_SQL: &str = include_str!("schema.sql");
+
+/// Number of times a request gave up waiting for a pooled connection since startup.
+static POOL_EXHAUSTED_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+/// How often the connection pool has been exhausted since startup, for `/health`.
+pub fn pool_exhausted_total() -> u64 {
+    POOL_EXHAUSTED_TOTAL.load(Ordering::Relaxed)
+}
+
+/// Most times `DbPool::with_write_retry` runs a write transaction before giving up.
+pub const WRITE_RETRY_ATTEMPTS: u32 = 5;
+
+/// Backoff before the first retry of a busy write; doubled on each further attempt.
+const WRITE_RETRY_BASE_DELAY: Duration = Duration::from_millis(20);
+
+/// Whether `err` means another connection held the lock, so the write may succeed if
+/// simply tried again.
+fn is_busy(err: &AppError) -> bool {
+    matches!(
+        err,
+        AppError::DbError(e) if matches!(
+            e.sqlite_error_code(),
+            Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+        )
+    )
+}
+
+/// Exponential backoff for retry number `attempt` (starting at 1), with up to the same
+/// again added at random so that competing writers don't retry in lockstep.
+fn write_retry_delay(attempt: u32) -> Duration {
+    let base = WRITE_RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+    let jitter_ms = rand::thread_rng().gen_range(0..=base.as_millis() as u64);
+    base + Duration::from_millis(jitter_ms)
+}
+
+/// Runs `f` inside an immediate write transaction on `conn` and commits it, retrying the
+/// whole transaction with jittered backoff, up to `WRITE_RETRY_ATTEMPTS` times in all,
+/// while SQLite reports the database busy or locked. Any other error, from `f` or the
+/// commit, rolls back and is returned straight away.
+///
+/// `f` may run more than once, so it must not have side effects outside the transaction.
+///
+/// # Errors
+/// Returns the error from `f` or the commit, or the last busy error once the attempts
+/// run out.
+///
+/// # Logging
+/// Warns with the attempt number on every busy retry.
+pub(crate) fn write_with_retry<T>(
+    conn: &mut Connection,
+    mut f: impl FnMut(&Transaction) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let mut attempt = 1;
+    loop {
+        let result = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(AppError::from)
+            .and_then(|tx| {
+                let value = f(&tx)?;
+                tx.commit()?;
+                Ok(value)
+            });
+        match result {
+            Err(e) if is_busy(&e) && attempt < WRITE_RETRY_ATTEMPTS => {
+                let delay = write_retry_delay(attempt);
+                warn!(
+                    attempt,
+                    max_attempts = WRITE_RETRY_ATTEMPTS,
+                    delay_ms = delay.as_millis() as u64,
+                    error = %e,
+                    "Database busy, retrying write transaction"
+                );
+                std::thread::sleep(delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// How `DbPool` opens and hands out connections.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolOptions {
+    pub journal_mode: JournalMode,
+    pub synchronous: SynchronousMode,
+    /// Most connections open at once.
+    pub max_size: u32,
+    /// How long `get_conn` waits for a free connection before failing.
+    pub connection_timeout: Duration,
+    /// How long a connection may stay checked out before its running query is
+    /// interrupted; `get_conn_with_timeout` overrides it per checkout.
+    pub query_timeout: Duration,
+}
+
+impl Default for PoolOptions {
+    /// WAL with NORMAL syncing, r2d2's own defaults of ten connections and a 30 second
+    /// wait, and a 10 second query timeout.
+    fn default() -> Self {
+        Self {
+            journal_mode: JournalMode::Wal,
+            synchronous: SynchronousMode::Normal,
+            max_size: 10,
+            connection_timeout: Duration::from_secs(30),
+            query_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl PoolOptions {
+    /// Options from `YAGI_JOURNAL_MODE`, `YAGI_SYNCHRONOUS`, `YAGI_DB_POOL_SIZE`,
+    /// `YAGI_DB_POOL_TIMEOUT_MS` and `YAGI_QUERY_TIMEOUT_MS`.
+    ///
+    /// # Errors
+    /// Returns `AppError::InvalidInput` on an unsupported journal mode or synchronous
+    /// setting.
+    pub fn from_env() -> Result<Self, AppError> {
+        Ok(Self {
+            journal_mode: journal_mode()?,
+            synchronous: synchronous_mode()?,
+            max_size: db_pool_size(),
+            connection_timeout: db_pool_timeout(),
+            query_timeout: query_timeout(),
+        })
+    }
+}
+
 /// Thread-safe database pool using r2d2 and rusqlite with connection multiplexing.
 #[derive(Clone)]
 pub struct DbPool {
     pool: Arc<Pool<SqliteConnectionManager>>,
+    query_timeout: Duration,
+    writer: Arc<OnceLock<Writer>>,
+}
+
+/// A pooled connection checked out of `DbPool`, derefs to `Connection`. Its queries are
+/// interrupted once the checkout outlives its timeout; dropping it returns the connection.
+pub struct DbConn {
+    // Declared first so the interrupt is disarmed before the connection goes back.
+    _watch: WatchGuard,
+    conn: PooledConnection<SqliteConnectionManager>,
+}
+
+impl Deref for DbConn {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+impl DerefMut for DbConn {
+    fn deref_mut(&mut self) -> &mut Connection {
+        &mut self.conn
+    }
 }
 
 impl DbPool {
-    /// Opens or creates the SQLite database at the provided path,
+    /// Opens or creates the SQLite database at the provided path, configured from the
+    /// environment (see `PoolOptions::from_env`).
     ///
     /// # Arguments
     /// * `db_path` - The file path to the SQLite database.
     ///
     /// # Errors
-    /// Fails if opening the DB fails, wrapped in `AppError::DbError`.
+    /// Fails with `AppError::InvalidInput` on an unsupported journal mode, if opening
+    /// the DB fails, wrapped in `AppError::PoolError`, or if creating tables fails.
     ///
     /// # Logging
-    /// Emits info-level logs on DB open, error-level logs on failure.
+    /// Emits info-level logs on DB open and for created tables, error-level logs on failure.
     pub fn new(db_path: &str) -> Result<Self, AppError> {
+        Self::with_options(db_path, PoolOptions::from_env()?)
+    }
+
+    /// Opens or creates the SQLite database at `db_path` with the given pool size,
+    /// connection timeout, journal mode and synchronous setting. Only WAL is stored in the
+    /// database file; the other journal modes and `synchronous` apply per connection, so
+    /// both pragmas are set as each connection is opened. Any tables, indexes or triggers
+    /// the database is missing are then created (see `schema::ensure_schema`).
+    ///
+    /// # Errors
+    /// Fails if opening the DB, setting the journal mode or creating tables fails.
+    pub fn with_options(db_path: &str, options: PoolOptions) -> Result<Self, AppError> {
+        let mode = options.journal_mode;
+        let synchronous = options.synchronous;
         info!(
             db_path,
+            %mode,
+            %synchronous,
+            max_size = options.max_size,
             "Opening SQLite database and creating connection pool"
         );
 
         // Create connection manager with flags
         let manager = SqliteConnectionManager::file(db_path)
-            .with_flags(OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE);
-        let pool = Pool::new(manager).map_err(AppError::PoolError)?;
-
-        // Get a connection from the pool and enable WAL mode
-        {
-            let conn = pool.get().map_err(AppError::PoolError)?;
-            conn.pragma_update(None, "journal_mode", &"WAL")
-                .map_err(AppError::DbError)?;
-        }
+            .with_flags(OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE)
+            .with_init(move |conn| {
+                conn.pragma_update(None, "journal_mode", mode.as_str())?;
+                conn.pragma_update(None, "synchronous", synchronous.as_str())
+            });
+        let pool = Pool::builder()
+            .max_size(options.max_size)
+            .connection_timeout(options.connection_timeout)
+            .build(manager)
+            .map_err(AppError::PoolError)?;
 
         // Run migrations here if desired
         //{
@@ -64,21 +377,264 @@ impl DbPool {
         //    // run_migrations(&mut conn).map_err(AppError::DbError)?;
         //}
 
-        info!("Database WAL enabled and ready for use with connection pool");
+        info!(%mode, "Database journal mode set and ready for use with connection pool");
+
+        let db = Self {
+            pool: Arc::new(pool),
+            query_timeout: options.query_timeout,
+            writer: Arc::default(),
+        };
+        ensure_schema(&db.get_conn()?)?;
+        Ok(db)
+    }
+
+    /// Opens a private, empty in-memory database, e.g. for tests. Every pooled connection
+    /// shares it through SQLite's shared cache under a name unique to this pool, and the
+    /// pool keeps its connections open so the database lives as long as the pool. No
+    /// schema is applied; see `SCHEMA_SQL`.
+    ///
+    /// # Errors
+    /// Fails if SQLite cannot open the database.
+    pub fn in_memory() -> Result<Self, AppError> {
+        static NEXT_DB: AtomicU64 = AtomicU64::new(0);
+        let uri = format!(
+            "file:yagi_memory_{}_{}?mode=memory&cache=shared",
+            std::process::id(),
+            NEXT_DB.fetch_add(1, Ordering::Relaxed)
+        );
+        debug!(uri, "Opening in-memory database");
 
+        let manager = SqliteConnectionManager::file(uri).with_flags(
+            OpenFlags::SQLITE_OPEN_READ_WRITE
+                | OpenFlags::SQLITE_OPEN_CREATE
+                | OpenFlags::SQLITE_OPEN_URI,
+        );
+        let pool = Pool::builder()
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .build(manager)
+            .map_err(AppError::PoolError)?;
         Ok(Self {
             pool: Arc::new(pool),
+            query_timeout: PoolOptions::default().query_timeout,
+            writer: Arc::default(),
+        })
+    }
+
+    /// Acquires a pooled SQLite connection for use in queries. Queries still running once
+    /// the connection has been checked out for the pool's query timeout are interrupted
+    /// and fail with `AppError::Timeout`.
+    ///
+    /// # Errors
+    /// Returns `AppError::PoolError` if no connection frees up within the pool's
+    /// connection timeout; each such failure is counted in `pool_exhausted_total`.
+    pub fn get_conn(&self) -> Result<DbConn, AppError> {
+        self.get_conn_with_timeout(self.query_timeout)
+    }
+
+    /// `get_conn` with a query timeout of its own, for routes known to run heavy queries.
+    ///
+    /// # Errors
+    /// As `get_conn`.
+    pub fn get_conn_with_timeout(&self, timeout: Duration) -> Result<DbConn, AppError> {
+        let conn = self.pool.get().map_err(|e| {
+            let total = POOL_EXHAUSTED_TOTAL.fetch_add(1, Ordering::Relaxed) + 1;
+            warn!(total, error = %e, "Connection pool exhausted");
+            AppError::PoolError(e)
+        })?;
+        Ok(DbConn {
+            _watch: watch(conn.get_interrupt_handle(), timeout),
+            conn,
         })
     }
 
-    /// Acquires a pooled SQLite connection for use in queries.
-    pub fn get_conn(&self) -> Result<PooledConnection<SqliteConnectionManager>, AppError> {
-        self.pool.get().map_err(AppError::PoolError)
+    /// Runs `f` inside an immediate write transaction on a pooled connection and commits
+    /// it, retrying while the database is busy; see `write_with_retry`.
+    ///
+    /// # Errors
+    /// Returns `AppError::PoolError` if no connection is free, otherwise as
+    /// `write_with_retry`.
+    pub fn with_write_retry<T>(
+        &self,
+        f: impl FnMut(&Transaction) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        write_with_retry(&mut self.get_conn()?, f)
+    }
+
+    /// Runs `f` as `with_write_retry` does, but on the pool's writer thread, so that
+    /// neither SQLite calls nor the backoff between busy retries stall an Actix worker
+    /// and writes are applied one at a time. Write handlers go through this.
+    ///
+    /// # Errors
+    /// As `with_write_retry`, or `AppError::Internal` if the writer has stopped.
+    pub async fn write<T, F>(&self, f: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnMut(&Transaction) -> Result<T, AppError> + Send + 'static,
+    {
+        self.writer().transaction(f).await
+    }
+
+    /// The pool's single writer, started on first use. Writes sent through it, directly
+    /// or via `write`, are applied one at a time, in the order they were sent.
+    pub fn writer(&self) -> &Writer {
+        self.writer
+            .get_or_init(|| Writer::spawn(Arc::clone(&self.pool), self.query_timeout))
+    }
+
+    /// Inserts a goat and links its vaccinations and diseases, returning its new id.
+    ///
+    /// Meant to run inside a caller-owned transaction (or savepoint) so that a failing link
+    /// rolls back the base record as well. Strict diet checking is left to `Validate` on
+    /// the API path; see `goat_column_values` for how fields are stored.
+    ///
+    /// # Errors
+    /// Returns `AppError::Conflict` if a goat with the same name, ignoring case, already
+    /// exists, or a database error if any insert fails.
+    pub fn insert_goat(conn: &Connection, goat: &GoatParams) -> Result<i64, AppError> {
+        ensure_goat_name_free(conn, &normalize_name(&goat.name), None)?;
+        timed_execute(
+            conn,
+            &format!(
+                "INSERT INTO goats ({}) VALUES ({})",
+                GOAT_WRITE_COLUMNS.join(", "),
+                vec!["?"; GOAT_WRITE_COLUMNS.len()].join(", ")
+            ),
+            params_from_iter(goat_column_values(goat)),
+        )?;
+        let goat_id = conn.last_insert_rowid();
+        debug!(goat_id, "Inserted goat base record");
+
+        sync_goat_relations(conn, goat_id, goat)?;
+        Ok(goat_id)
+    }
+
+    /// Overwrites every field of the live goat `goat_id`, name included, makes its
+    /// vaccine and disease links match and bumps its version. Returns the number of goats
+    /// updated, 0 if there is no such live goat. Meant to run inside a caller-owned
+    /// transaction or savepoint.
+    ///
+    /// With `expected_version` set, the update only applies while the stored version
+    /// still matches it.
+    ///
+    /// # Errors
+    /// Returns `AppError::Conflict` if another goat already has the new name, ignoring
+    /// case, `AppError::VersionConflict` if the goat exists at another version than
+    /// `expected_version`, or a database error.
+    pub fn update_goat(
+        conn: &Connection,
+        goat_id: i64,
+        goat: &GoatParams,
+        expected_version: Option<u32>,
+    ) -> Result<usize, AppError> {
+        ensure_goat_name_free(conn, &normalize_name(&goat.name), Some(goat_id))?;
+        let assignments: Vec<String> = GOAT_WRITE_COLUMNS
+            .iter()
+            .map(|column| format!("{} = ?", column))
+            .collect();
+        let mut values = Vec::from(goat_column_values(goat));
+        values.push(Box::new(goat_id));
+        let mut sql = format!(
+            "UPDATE goats SET {}, version = version + 1, updated_at = CURRENT_TIMESTAMP \
+             WHERE id = ? AND deleted_at IS NULL",
+            assignments.join(", ")
+        );
+        if let Some(version) = expected_version {
+            sql.push_str(" AND version = ?");
+            values.push(Box::new(version));
+        }
+        let updated = timed_execute(conn, &sql, params_from_iter(values))?;
+        if updated == 0 {
+            if let Some(version) = expected_version
+                && goat_is_live(conn, goat_id)?
+            {
+                warn!(goat_id, version, "Goat update lost to a concurrent write");
+                return Err(AppError::VersionConflict(
+                    "Record was modified by another request; please re-fetch and retry".to_string(),
+                ));
+            }
+            debug!(goat_id, "No live goat to update");
+            return Ok(0);
+        }
+
+        sync_goat_relations(conn, goat_id, goat)?;
+        Ok(updated)
+    }
+
+    /// Soft-deletes the live goat `goat_id`, keeping the row with `deleted_at` set so that
+    /// syncing clients receive a tombstone. Returns the number of goats deleted, 0 if
+    /// there is no such live goat.
+    ///
+    /// # Errors
+    /// Returns a database error if the update fails.
+    pub fn soft_delete_goat(conn: &Connection, goat_id: i64) -> Result<usize, AppError> {
+        Ok(timed_execute(
+            conn,
+            "UPDATE goats SET deleted_at = CURRENT_TIMESTAMP, version = version + 1, \
+             updated_at = CURRENT_TIMESTAMP WHERE id = ?1 AND deleted_at IS NULL",
+            [goat_id],
+        )?)
+    }
+}
+/// Columns of `goats` that goat reads select, in the order `row_to_goat` reads them
+/// positionally. Checked against the live schema at startup by `check_goat_columns`.
+pub const GOAT_COLUMNS: [&str; 14] = [
+    "id",
+    "breed",
+    "name",
+    "gender",
+    "offspring",
+    "cost",
+    "weight",
+    "current_price",
+    "diet",
+    "last_bred",
+    "health_status",
+    "cost_minor",
+    "current_price_minor",
+    "version",
+];
+
+/// `GOAT_COLUMNS` as a select list qualified with `table`, the table name or its alias
+/// in the query, so it also works in joins.
+pub fn goat_columns(table: &str) -> String {
+    GOAT_COLUMNS
+        .iter()
+        .map(|column| format!("{}.{}", table, column))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Verifies that the live `goats` table has every column in `GOAT_COLUMNS`, so that a
+/// database behind on migrations fails at startup rather than on the first goat read.
+///
+/// # Errors
+/// Returns `AppError::Internal` naming the first missing column, or a database error.
+pub fn check_goat_columns(conn: &Connection) -> Result<(), AppError> {
+    let present: Vec<String> = timed_query_map(
+        conn,
+        "SELECT name FROM pragma_table_info('goats')",
+        [],
+        |r| r.get(0),
+    )?;
+    if let Some(missing) = GOAT_COLUMNS
+        .iter()
+        .find(|column| !present.iter().any(|p| p == *column))
+    {
+        error!(column = missing, "goats table is missing a required column");
+        return Err(AppError::Internal(format!(
+            "goats table is missing column '{}'; run the pending migrations",
+            missing
+        )));
     }
+    debug!("goats table has every required column");
+    Ok(())
 }
+
 /// Maps a SQLite row from the `goats` table to a fully validated and parsed `Goat` struct.
 ///
-/// This method converts string fields into Rust enums and returns application-level parse errors as necessary.
+/// The row must be selected with `goat_columns`. This method converts string fields into Rust enums and returns application-level parse errors as necessary.
+/// Diets are canonicalised via `str_to_diet`, keeping unknown legacy values verbatim.
 /// It does not load related vaccinations or diseases; use `load_goat_details` for full loading.
 ///
 /// # Errors
@@ -91,28 +647,45 @@ pub fn row_to_goat(row: &Row) -> Result<GoatParams, AppError> {
     let breed_str: String = row.get(1)?;
     let gender_str: String = row.get(3)?;
 
-    let breed = Breed::from_str(&breed_str);
-    let gender = Gender::from_str(&gender_str).map_err(|e| {
-        error!(e);
-        AppError::ParseError(ParseEnumError::new(&e, "Gender"))
-    })?;
+    let breed = str_to_breed(&breed_str)?;
+    let gender = str_to_gender(&gender_str).inspect_err(|e| error!(%e, "Invalid stored gender"))?;
+
+    // These columns are nullable in the schema; legacy rows may leave them empty.
+    let diet: Option<String> = row.get(8)?;
+    let diet = diet.map(|d| diet_to_str(&str_to_diet(&d)).to_string());
 
     Ok(GoatParams {
         breed,
         name: row.get(2)?,
         gender,
-        offspring: row.get(4)?,
-        cost: row.get(5)?,
-        weight: row.get(6)?,
-        current_price: row.get(7)?,
-        diet: row.get(8)?,
+        offspring: row.get::<_, Option<_>>(4)?.unwrap_or_default(),
+        cost: minor_or_legacy(row, "cost_minor", 5)?,
+        weight: row.get::<_, Option<_>>(6)?.unwrap_or_default(),
+        current_price: minor_or_legacy(row, "current_price_minor", 7)?,
+        diet: diet.unwrap_or_default(),
         last_bred: row.get(9).ok(),
-        health_status: row.get(10)?,
+        health_status: row.get::<_, Option<_>>(10)?.unwrap_or_default(),
         vaccinations: Vec::new(),
         diseases: Vec::new(),
     })
 }
 
+/// Reads a goat money field in rupees, preferring the exact minor-unit column when the
+/// query selected it and it is set, and falling back to the legacy `REAL` column.
+fn minor_or_legacy(row: &Row, minor_column: &str, legacy_index: usize) -> Result<f64, AppError> {
+    match row.get::<_, Option<i64>>(minor_column) {
+        Ok(Some(amount)) => Ok(Money {
+            amount,
+            currency: Currency::Inr,
+        }
+        .to_major()),
+        Ok(None) | Err(rusqlite::Error::InvalidColumnName(_)) => {
+            Ok(row.get::<_, Option<f64>>(legacy_index)?.unwrap_or_default())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
 /// Fetches the list of vaccine references associated with a goat.
 ///
 /// # Errors
@@ -125,7 +698,7 @@ pub fn fetch_vaccines(conn: &Connection, goat_id: i64) -> Result<Vec<VaccineRef>
 
     let mut stmt = conn.prepare(
         "SELECT v.id, v.name FROM vaccines v INNER JOIN goat_vaccines gv ON v.id = gv.vaccine_id WHERE gv.goat_id = ?1"
-    ).map_err(AppError::DbError)?;
+    )?;
 
     let vaccines: Vec<VaccineRef> = stmt
         .query_map([goat_id], |row| {
@@ -173,6 +746,21 @@ pub fn fetch_diseases(conn: &Connection, goat_id: i64) -> Result<Vec<DiseaseRef>
     Ok(diseases)
 }
 
+/// Fetches a goat's tag names in alphabetical order.
+///
+/// # Errors
+/// Returns database errors that occur during querying.
+pub fn fetch_tags(conn: &Connection, goat_id: i64) -> Result<Vec<String>, AppError> {
+    trace!(goat_id, "Fetching tag list");
+    Ok(timed_query_map(
+        conn,
+        "SELECT t.name FROM tags t INNER JOIN goat_tags gt ON t.id = gt.tag_id \
+         WHERE gt.goat_id = ?1 ORDER BY t.name COLLATE NOCASE",
+        [goat_id],
+        |row| row.get(0),
+    )?)
+}
+
 /// Runs all embedded refinery migrations on the provided connection,
 /// ensuring the database schema is current.
 ///
@@ -198,35 +786,5964 @@ pub fn fetch_diseases(conn: &Connection, goat_id: i64) -> Result<Vec<DiseaseRef>
 //    Ok(())
 //}
 
-/// Attempts to fetch the ID of the vaccine by name in the given transaction.
-/// Inserts the vaccine if missing, ensuring referential integrity.
+/// Result of a get-or-insert lookup in the `vaccines` or `diseases` catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogEntry {
+    /// An existing entry was reused.
+    Found(i64),
+    /// No entry matched, so one was created.
+    Inserted(i64),
+}
+
+impl CatalogEntry {
+    pub fn id(self) -> i64 {
+        match self {
+            CatalogEntry::Found(id) | CatalogEntry::Inserted(id) => id,
+        }
+    }
+
+    pub fn inserted(self) -> bool {
+        matches!(self, CatalogEntry::Inserted(_))
+    }
+}
+
+/// Attempts to fetch the ID of the vaccine by name on the given connection or transaction.
+/// Names are trimmed and match case-insensitively. Inserts the vaccine if missing, ensuring
+/// referential integrity.
 ///
 /// # Errors
-/// Returns a database error if queries or inserts fail.
+/// Returns `AppError::InvalidInput` for a blank name without an id, or a database error
+/// if queries or inserts fail.
 ///
 /// # Logging
 /// Forwards errors and logs keys steps and outcomes.
-pub fn get_or_insert_vaccine(tx: &Transaction, vaccine: &VaccineRef) -> Result<i64, AppError> {
-    if let Some(id) = vaccine.id {
-        return Ok(id);
+pub fn get_or_insert_vaccine(
+    tx: &Connection,
+    vaccine: &VaccineRef,
+) -> Result<CatalogEntry, AppError> {
+    get_or_insert_catalog_entry(tx, "vaccines", "Vaccine", vaccine.id, &vaccine.name)
+}
+
+/// Like `get_or_insert_vaccine`, but for diseases.
+pub fn get_or_insert_disease(
+    tx: &Connection,
+    disease: &DiseaseRef,
+) -> Result<CatalogEntry, AppError> {
+    get_or_insert_catalog_entry(tx, "diseases", "Disease", disease.id, &disease.name)
+}
+
+/// Shared body of `get_or_insert_vaccine` and `get_or_insert_disease`; `table` is one
+/// of the two catalog tables and `label` names its entries in errors. A given `id` is
+/// trusted as is.
+fn get_or_insert_catalog_entry(
+    tx: &Connection,
+    table: &str,
+    label: &str,
+    id: Option<i64>,
+    name: &str,
+) -> Result<CatalogEntry, AppError> {
+    if let Some(id) = id {
+        return Ok(CatalogEntry::Found(id));
     }
-    let mut stmt = tx.prepare("SELECT id FROM vaccines WHERE name = ?1")?;
-    if let Some(id) = stmt.query_row([&vaccine.name], |r| r.get(0)).optional()? {
-        return Ok(id);
+    let name = name.trim();
+    if name.is_empty() {
+        warn!(table, "Rejected blank catalog name");
+        return Err(AppError::InvalidInput(format!(
+            "{} name must not be empty",
+            label
+        )));
+    }
+    if let Some(id) = find_id(tx, table, "name = ?1 COLLATE NOCASE", [name])? {
+        trace!(table, id, name, "Reusing catalog entry");
+        return Ok(CatalogEntry::Found(id));
     }
-    tx.execute("INSERT INTO vaccines (name) VALUES (?1)", [&vaccine.name])?;
-    Ok(tx.last_insert_rowid())
+    timed_execute(
+        tx,
+        &format!("INSERT INTO {} (name) VALUES (?1)", table),
+        [name],
+    )?;
+    let id = tx.last_insert_rowid();
+    debug!(table, id, name, "Catalog entry created");
+    Ok(CatalogEntry::Inserted(id))
 }
 
-/// Like `get_or_insert_vaccine`, but for diseases.
-pub fn get_or_insert_disease(tx: &Transaction, disease: &DiseaseRef) -> Result<i64, AppError> {
-    if let Some(id) = disease.id {
+/// Resolves the buyer of a sale: `buyer_id` if given, otherwise the buyer named
+/// `buyer_name` (ignoring case), which is created if it does not exist yet.
+///
+/// # Errors
+/// Returns `AppError::NotFound` for an unknown `buyer_id`, `AppError::InvalidInput` if
+/// neither is given, or a database error.
+pub fn get_or_insert_buyer(
+    tx: &Connection,
+    buyer_id: Option<i64>,
+    buyer_name: Option<&str>,
+) -> Result<i64, AppError> {
+    if let Some(id) = buyer_id {
+        DbPool::get_buyer(tx, id)?;
         return Ok(id);
     }
-    let mut stmt = tx.prepare("SELECT id FROM diseases WHERE name = ?1")?;
-    if let Some(id) = stmt.query_row([&disease.name], |r| r.get(0)).optional()? {
+    let name = buyer_name
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| AppError::InvalidInput("A buyer id or name is required".to_string()))?;
+    if let Some(id) = find_id(tx, "buyers", "name = ?1 COLLATE NOCASE", [name])? {
         return Ok(id);
     }
-    tx.execute("INSERT INTO diseases (name) VALUES (?1)", [&disease.name])?;
-    Ok(tx.last_insert_rowid())
+    timed_execute(tx, "INSERT INTO buyers (name) VALUES (?1)", [name])?;
+    let id = tx.last_insert_rowid();
+    info!(buyer_id = id, name, "Buyer created from sale");
+    Ok(id)
+}
+
+/// Columns of `goats` written from a `GoatParams`, in the order `goat_column_values`
+/// binds them. `DbPool::insert_goat` and `DbPool::update_goat` both build their SQL from
+/// this list, so a new goat column is added here and in `goat_column_values` only.
+const GOAT_WRITE_COLUMNS: [&str; 13] = [
+    "breed",
+    "name",
+    "gender",
+    "offspring",
+    "cost",
+    "weight",
+    "current_price",
+    "diet",
+    "last_bred",
+    "health_status",
+    "cost_minor",
+    "current_price_minor",
+    "currency",
+];
+
+/// Binds a goat's fields in `GOAT_WRITE_COLUMNS` order.
+///
+/// The name and diet are stored in their canonical forms (see `normalize_name` and
+/// `str_to_diet`), and money both in rupees and as exact minor units.
+fn goat_column_values(goat: &GoatParams) -> [Box<dyn ToSql>; GOAT_WRITE_COLUMNS.len()] {
+    let cost = Money::from_major(goat.cost, Currency::Inr);
+    let current_price = Money::from_major(goat.current_price, Currency::Inr);
+    [
+        Box::new(breed_to_str(&goat.breed).to_string()),
+        Box::new(normalize_name(&goat.name)),
+        Box::new(gender_to_str(&goat.gender).to_string()),
+        Box::new(goat.offspring),
+        Box::new(cost.to_major()),
+        Box::new(goat.weight),
+        Box::new(current_price.to_major()),
+        Box::new(diet_to_str(&str_to_diet(&goat.diet)).to_string()),
+        Box::new(goat.last_bred.clone()),
+        Box::new(goat.health_status.clone()),
+        Box::new(cost.amount),
+        Box::new(current_price.amount),
+        Box::new(Currency::Inr.code()),
+    ]
+}
+
+/// Fails with `AppError::Conflict` if a live goat other than `except_id` already has
+/// `name`, ignoring case. Soft-deleted goats do not hold on to their names.
+pub(crate) fn ensure_goat_name_free(
+    conn: &Connection,
+    name: &str,
+    except_id: Option<i64>,
+) -> Result<(), AppError> {
+    let existing: Option<String> = conn
+        .query_row(
+            "SELECT name FROM goats WHERE name = ?1 COLLATE NOCASE AND id IS NOT ?2 \
+             AND deleted_at IS NULL LIMIT 1",
+            params![name, except_id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    if let Some(existing) = existing {
+        warn!(goat_name = name, existing, "Duplicate goat name");
+        return Err(AppError::Conflict(format!(
+            "A goat named {} already exists",
+            existing
+        )));
+    }
+    Ok(())
+}
+
+/// Makes a goat's vaccine and disease links match `goat.vaccinations` and `goat.diseases`.
+///
+/// Links that are still present are kept untouched so they retain their original
+/// `created_at`; only stale links are removed and missing ones added.
+///
+/// # Logging
+/// Debugs once stale links are cleared and traces each linked vaccine and disease.
+fn sync_goat_relations(conn: &Connection, goat_id: i64, goat: &GoatParams) -> Result<(), AppError> {
+    let mut vaccine_ids = Vec::with_capacity(goat.vaccinations.len());
+    for vaccine in &goat.vaccinations {
+        let entry = get_or_insert_vaccine(conn, vaccine)?;
+        if entry.inserted() {
+            info!(goat_id, vaccine_id = entry.id(), name = %vaccine.name.trim(), "New vaccine added to catalog");
+        }
+        vaccine_ids.push(entry.id());
+    }
+    let mut disease_ids = Vec::with_capacity(goat.diseases.len());
+    for disease in &goat.diseases {
+        let entry = get_or_insert_disease(conn, disease)?;
+        if entry.inserted() {
+            info!(goat_id, disease_id = entry.id(), name = %disease.name.trim(), "New disease added to catalog");
+        }
+        disease_ids.push(entry.id());
+    }
+
+    // Drop only the links that are no longer present, so existing links keep their
+    // original `created_at` and don't reappear as new events in the daily report.
+    timed_execute(
+        conn,
+        "DELETE FROM goat_vaccines WHERE goat_id = ?1 \
+         AND vaccine_id NOT IN (SELECT value FROM json_each(?2))",
+        params![
+            goat_id,
+            serde_json::to_string(&vaccine_ids).unwrap_or_default()
+        ],
+    )?;
+    timed_execute(
+        conn,
+        "DELETE FROM goat_diseases WHERE goat_id = ?1 \
+         AND disease_id NOT IN (SELECT value FROM json_each(?2))",
+        params![
+            goat_id,
+            serde_json::to_string(&disease_ids).unwrap_or_default()
+        ],
+    )?;
+    debug!(goat_id, "Cleared stale vaccine and disease links");
+
+    for vaccine_id in &vaccine_ids {
+        timed_execute(
+            conn,
+            "INSERT OR IGNORE INTO goat_vaccines (goat_id, vaccine_id, created_at) VALUES (?, ?, CURRENT_TIMESTAMP)",
+            [&goat_id, vaccine_id],
+        )?;
+        trace!(goat_id, vaccine_id, "Linked vaccine");
+    }
+    for disease_id in &disease_ids {
+        timed_execute(
+            conn,
+            "INSERT OR IGNORE INTO goat_diseases (goat_id, disease_id, created_at) VALUES (?, ?, CURRENT_TIMESTAMP)",
+            [&goat_id, disease_id],
+        )?;
+        trace!(goat_id, disease_id, "Linked disease");
+    }
+    Ok(())
+}
+
+/// Looks up a live goat by name, ignoring case and surrounding or repeated whitespace.
+///
+/// Returns the goat's id and its canonical stored name. Should several goats match, as
+/// only a database predating the unique name index allows, the oldest is returned, so
+/// repeated lookups within a request always resolve to the same goat.
+///
+/// # Errors
+/// Returns a database error if the query fails.
+pub fn find_goat_by_name(conn: &Connection, name: &str) -> Result<Option<(i64, String)>, AppError> {
+    Ok(conn
+        .query_row(
+            "SELECT id, name FROM goats \
+             WHERE name = ?1 COLLATE NOCASE AND deleted_at IS NULL ORDER BY id LIMIT 1",
+            [normalize_name(name)],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()?)
+}
+
+/// Updates the goat identified by `goat.name` and replaces its vaccine and disease links.
+///
+/// The name is matched as in `find_goat_by_name` once, up front; the update and every
+/// link change then address that id, never the name. The stored name is left unchanged.
+/// The write itself is `DbPool::update_goat`, including its `expected_version` check.
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if no goat has that name,
+/// `AppError::VersionConflict` on a stale version, or a database error.
+///
+/// # Logging
+/// Warns on a missing goat.
+pub fn update_goat_by_name(
+    conn: &Connection,
+    goat: &GoatParams,
+    expected_version: Option<u32>,
+) -> Result<i64, AppError> {
+    let Some((goat_id, stored_name)) = find_goat_by_name(conn, &goat.name)? else {
+        warn!(goat_name = goat.name, "No goat found for update");
+        return Err(AppError::InvalidInput(format!(
+            "No goat found with name {}",
+            goat.name
+        )));
+    };
+
+    let goat = GoatParams {
+        name: stored_name,
+        ..goat.clone()
+    };
+    DbPool::update_goat(conn, goat_id, &goat, expected_version)?;
+    Ok(goat_id)
+}
+
+/// Soft-deletes the goat with the given name, matched as in `find_goat_by_name`.
+///
+/// The row is kept with `deleted_at` set so that syncing clients receive a tombstone;
+/// every read path filters on `deleted_at IS NULL`. Returns the canonical stored name.
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if no goat has that name, or a database error.
+///
+/// # Logging
+/// Warns when the goat does not exist.
+pub fn delete_goat_by_name(conn: &Connection, name: &str) -> Result<String, AppError> {
+    let Some((goat_id, stored_name)) = find_goat_by_name(conn, name)? else {
+        warn!(goat_name = name, "Goat not found for deletion");
+        return Err(AppError::InvalidInput(format!(
+            "No goat found with name {}",
+            name
+        )));
+    };
+
+    DbPool::soft_delete_goat(conn, goat_id)?;
+    Ok(stored_name)
+}
+
+/// Loads a live goat by id with its vaccines and diseases.
+///
+/// # Errors
+/// Returns `AppError::NotFound` if there is no such goat, or database and parsing errors.
+pub fn load_goat_details(conn: &Connection, goat_id: i64) -> Result<Goat, AppError> {
+    trace!(goat_id, "Loading goat details");
+    let stored = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM goats WHERE id = ?1 AND deleted_at IS NULL",
+                goat_columns("goats")
+            ),
+            [goat_id],
+            |row| {
+                let params = row_to_goat(row)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+                Ok((params, row.get::<_, u32>("version")?))
+            },
+        )
+        .optional()?;
+    let Some((mut params, version)) = stored else {
+        warn!(goat_id, "Goat not found");
+        return Err(AppError::NotFound(format!(
+            "No goat found with id {}",
+            goat_id
+        )));
+    };
+    params.vaccinations = fetch_vaccines(conn, goat_id)?;
+    params.diseases = fetch_diseases(conn, goat_id)?;
+    Ok(Goat::new(Some(goat_id), params).with_version(version))
+}
+
+/// Builds the id query behind `GET /goats/search`, adding one `AND` clause and bound
+/// parameter per filter that is set. Values are never spliced into the SQL text.
+///
+/// Name matching is a case-insensitive substring match with `%` and `_` taken literally;
+/// breed, gender, health status, vaccine and disease names match exactly, ignoring case.
+pub fn build_goat_search_query(params: &GoatSearchParams) -> (String, Vec<Box<dyn ToSql>>) {
+    let (mut sql, values) = build_goat_filter(params);
+    sql.push_str(" ORDER BY name");
+    (sql, values)
+}
+
+/// The unordered id query of `build_goat_search_query`, for callers choosing their own
+/// order and limit.
+fn build_goat_filter(params: &GoatSearchParams) -> (String, Vec<Box<dyn ToSql>>) {
+    let mut sql = String::from("SELECT id FROM goats WHERE deleted_at IS NULL");
+    let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+    let mut push = |clause: &str, value: Box<dyn ToSql>| {
+        values.push(value);
+        sql.push_str(" AND ");
+        sql.push_str(&clause.replace('?', &format!("?{}", values.len())));
+    };
+
+    if let Some(q) = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        let escaped = q
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        push("name LIKE '%' || ? || '%' ESCAPE '\\'", Box::new(escaped));
+    }
+    if let Some(breed) = &params.breed {
+        push(
+            "breed = ? COLLATE NOCASE",
+            Box::new(breed.trim().to_string()),
+        );
+    }
+    if let Some(gender) = &params.gender {
+        push(
+            "gender = ? COLLATE NOCASE",
+            Box::new(gender.trim().to_string()),
+        );
+    }
+    if let Some(status) = &params.health_status {
+        push(
+            "health_status = ? COLLATE NOCASE",
+            Box::new(status.trim().to_string()),
+        );
+    }
+    if let Some(min) = params.min_weight {
+        push("weight >= ?", Box::new(min));
+    }
+    if let Some(max) = params.max_weight {
+        push("weight <= ?", Box::new(max));
+    }
+    if let Some(space_id) = params.space_id {
+        push(
+            "EXISTS (SELECT 1 FROM space_goats sg WHERE sg.goat_id = goats.id AND sg.space_id = ?)",
+            Box::new(space_id),
+        );
+    }
+    if let Some(vaccine) = &params.has_vaccine {
+        push(
+            "EXISTS (SELECT 1 FROM goat_vaccines gv JOIN vaccines v ON v.id = gv.vaccine_id \
+             WHERE gv.goat_id = goats.id AND v.name = ? COLLATE NOCASE)",
+            Box::new(vaccine.trim().to_string()),
+        );
+    }
+    if let Some(disease) = &params.has_disease {
+        push(
+            "EXISTS (SELECT 1 FROM goat_diseases gd JOIN diseases d ON d.id = gd.disease_id \
+             WHERE gd.goat_id = goats.id AND d.name = ? COLLATE NOCASE)",
+            Box::new(disease.trim().to_string()),
+        );
+    }
+    if let Some(pregnant) = params.is_pregnant {
+        push("is_pregnant = ?", Box::new(pregnant));
+    }
+
+    (sql, values)
+}
+
+/// Loads up to `count` live goats matching the search filters, picked at random, with
+/// relations.
+///
+/// # Errors
+/// Returns database and parsing errors.
+pub fn find_random_goats(
+    conn: &Connection,
+    params: &GoatSearchParams,
+    count: u32,
+) -> Result<Vec<Goat>, AppError> {
+    let (mut sql, mut values) = build_goat_filter(params);
+    values.push(Box::new(count));
+    sql.push_str(&format!(" ORDER BY RANDOM() LIMIT ?{}", values.len()));
+    trace!(sql, count, "Sampling goats");
+    let ids: Vec<i64> = timed_query_map(conn, &sql, params_from_iter(values.iter()), |row| {
+        row.get(0)
+    })?;
+    ids.into_iter()
+        .map(|goat_id| load_goat_details(conn, goat_id))
+        .collect()
+}
+
+/// Loads every live goat matching the search filters, with relations, ordered by name.
+///
+/// # Errors
+/// Returns database and parsing errors.
+pub fn find_goats(conn: &Connection, params: &GoatSearchParams) -> Result<Vec<Goat>, AppError> {
+    let (sql, values) = build_goat_search_query(params);
+    trace!(sql, params = values.len(), "Searching goats");
+    let ids: Vec<i64> = timed_query_map(conn, &sql, params_from_iter(values.iter()), |row| {
+        row.get(0)
+    })?;
+    ids.into_iter()
+        .map(|goat_id| load_goat_details(conn, goat_id))
+        .collect()
+}
+
+/// Whether a live (not soft-deleted) goat with the given id exists.
+pub(crate) fn goat_is_live(conn: &Connection, goat_id: i64) -> Result<bool, AppError> {
+    Ok(timed_query_row(
+        conn,
+        "SELECT EXISTS(SELECT 1 FROM goats WHERE id = ?1 AND deleted_at IS NULL)",
+        [goat_id],
+        |r| r.get(0),
+    )?)
+}
+
+/// Ensures a live (not soft-deleted) goat with the given id exists.
+///
+/// # Errors
+/// Returns `AppError::NotFound` if there is no such goat, or a database error.
+pub fn ensure_goat_exists(conn: &Connection, goat_id: i64) -> Result<(), AppError> {
+    if !goat_is_live(conn, goat_id)? {
+        warn!(goat_id, "Goat not found");
+        return Err(AppError::NotFound(format!(
+            "No goat found with id {}",
+            goat_id
+        )));
+    }
+    Ok(())
+}
+
+/// Moves a goat into a space, keeping `space_goats` and `goat_space_history` in step: the
+/// goat's open stay is closed and a new one opened at the same moment. A goat already in
+/// `space_id` keeps its current stay. Run it inside the write transaction of the move.
+///
+/// # Errors
+/// Returns a database error.
+fn move_goat_to_space(conn: &Connection, goat_id: i64, space_id: i64) -> Result<(), AppError> {
+    let current: Option<i64> = timed_query_row(
+        conn,
+        "SELECT space_id FROM space_goats WHERE goat_id = ?1",
+        [goat_id],
+        |r| r.get(0),
+    )
+    .optional()?;
+    if current == Some(space_id) {
+        debug!(goat_id, space_id, "Goat already in space");
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now()
+        .format(SQLITE_TIMESTAMP_FORMAT)
+        .to_string();
+    timed_execute(
+        conn,
+        "UPDATE goat_space_history SET moved_out_at = ?2 \
+         WHERE goat_id = ?1 AND moved_out_at IS NULL",
+        params![goat_id, now],
+    )?;
+    timed_execute(
+        conn,
+        "INSERT INTO goat_space_history (goat_id, space_id, moved_in_at) VALUES (?1, ?2, ?3)",
+        params![goat_id, space_id, now],
+    )?;
+    timed_execute(
+        conn,
+        "INSERT INTO space_goats (goat_id, space_id, assigned_at) VALUES (?1, ?2, ?3) \
+         ON CONFLICT(goat_id) DO UPDATE SET space_id = excluded.space_id, \
+         assigned_at = excluded.assigned_at",
+        params![goat_id, space_id, now],
+    )?;
+    debug!(goat_id, ?current, space_id, "Goat moved between spaces");
+    Ok(())
+}
+
+/// Ensures a worker with the given id exists.
+///
+/// # Errors
+/// Returns `AppError::NotFound` if there is no such worker, or a database error.
+pub fn ensure_worker_exists(conn: &Connection, worker_id: i64) -> Result<(), AppError> {
+    let exists: bool = timed_query_row(
+        conn,
+        "SELECT EXISTS(SELECT 1 FROM workers WHERE id = ?1)",
+        [worker_id],
+        |r| r.get(0),
+    )?;
+    if !exists {
+        warn!(worker_id, "Worker not found");
+        return Err(AppError::NotFound(format!(
+            "No worker found with id {}",
+            worker_id
+        )));
+    }
+    Ok(())
+}
+
+/// Columns selected by `row_to_worker_task`, in order.
+const WORKER_TASK_COLUMNS: &str =
+    "id, worker_id, task, assigned_at, due_at, completed_at, priority, description";
+
+/// Columns selected for `row_to_genetic_test`.
+const GENETIC_TEST_COLUMNS: &str =
+    "id, goat_id, test_type, test_date, lab_name, result_json, report_url";
+
+/// CSV files of `DbPool::export_full_snapshot`, with the query producing each.
+const FULL_EXPORT_TABLES: &[(&str, &str)] = &[
+    (
+        "goats.csv",
+        "SELECT * FROM goats WHERE deleted_at IS NULL ORDER BY id",
+    ),
+    ("workers.csv", "SELECT * FROM workers ORDER BY id"),
+    ("equipment.csv", "SELECT * FROM equipment ORDER BY id"),
+    ("sensors.csv", "SELECT * FROM sensors ORDER BY id"),
+    ("spaces.csv", "SELECT * FROM spaces ORDER BY id"),
+    ("vaccines.csv", "SELECT * FROM vaccines ORDER BY id"),
+    ("diseases.csv", "SELECT * FROM diseases ORDER BY id"),
+];
+
+/// Runs `sql` and renders its result as CSV with a header row, returning the text and
+/// the number of data rows. Nulls become empty fields and blobs lowercase hex.
+fn query_csv(conn: &Connection, sql: &str) -> Result<(String, usize), AppError> {
+    let mut stmt = conn.prepare(sql)?;
+    let columns = stmt.column_count();
+    let mut csv = stmt
+        .column_names()
+        .into_iter()
+        .map(csv_field)
+        .collect::<Vec<_>>()
+        .join(",");
+    csv.push_str("\r\n");
+
+    let mut rows = stmt.query([])?;
+    let mut count = 0;
+    while let Some(row) = rows.next()? {
+        let mut fields = Vec::with_capacity(columns);
+        for i in 0..columns {
+            fields.push(match row.get_ref(i)? {
+                ValueRef::Null => String::new(),
+                ValueRef::Integer(n) => n.to_string(),
+                ValueRef::Real(x) => x.to_string(),
+                ValueRef::Text(text) => csv_field(&String::from_utf8_lossy(text)),
+                ValueRef::Blob(bytes) => bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+            });
+        }
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+        count += 1;
+    }
+    Ok((csv, count))
+}
+
+/// `value` as a CSV field, quoted when it holds a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Columns selected for `row_to_leave_request`.
+const LEAVE_REQUEST_COLUMNS: &str =
+    "id, worker_id, from_date, to_date, reason, status, decided_by, decided_at, created_at";
+
+/// Maps a `leave_requests` row selected with `LEAVE_REQUEST_COLUMNS`.
+pub fn row_to_leave_request(row: &Row) -> rusqlite::Result<LeaveRequest> {
+    Ok(LeaveRequest {
+        id: row.get(0)?,
+        worker_id: row.get(1)?,
+        from_date: row.get(2)?,
+        to_date: row.get(3)?,
+        reason: row.get(4)?,
+        status: row.get(5)?,
+        decided_by: row.get(6)?,
+        decided_at: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+/// Fails with `AppError::Conflict` if `request`'s days overlap leave already approved for
+/// the same worker, leaving out the request with id `except`.
+fn ensure_no_approved_leave_overlap(
+    conn: &Connection,
+    request: &LeaveRequest,
+    except: Option<i64>,
+) -> Result<(), AppError> {
+    let overlapping: Option<i64> = timed_query_row(
+        conn,
+        "SELECT id FROM leave_requests WHERE worker_id = ?1 AND status = 'approved' \
+         AND from_date <= ?3 AND to_date >= ?2 AND (?4 IS NULL OR id != ?4) \
+         ORDER BY from_date LIMIT 1",
+        params![
+            request.worker_id,
+            request.from_date.trim(),
+            request.to_date.trim(),
+            except
+        ],
+        |r| r.get(0),
+    )
+    .optional()?;
+    if let Some(overlapping) = overlapping {
+        warn!(
+            worker_id = request.worker_id,
+            overlapping, "Leave overlaps approved leave"
+        );
+        return Err(AppError::Conflict(format!(
+            "Leave from {} to {} overlaps approved leave request {}",
+            request.from_date.trim(),
+            request.to_date.trim(),
+            overlapping
+        )));
+    }
+    Ok(())
+}
+
+/// Whether the database has every one of `tables`.
+fn has_tables(conn: &Connection, tables: &[&str]) -> Result<bool, AppError> {
+    for table in tables {
+        let found: i64 = timed_query_row(
+            conn,
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [table],
+            |r| r.get(0),
+        )?;
+        if found == 0 {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Maps the leading `id, goat_id, filename, content_type, size, uploaded_at` columns of a
+/// `goat_documents` row.
+pub fn row_to_attached_document(row: &Row) -> rusqlite::Result<AttachedDocument> {
+    Ok(AttachedDocument {
+        id: row.get(0)?,
+        goat_id: row.get(1)?,
+        filename: row.get(2)?,
+        content_type: row.get(3)?,
+        size: row.get(4)?,
+        uploaded_at: row.get(5)?,
+    })
+}
+
+/// Maps a `genetic_tests` row selected with `GENETIC_TEST_COLUMNS`, parsing `result_json`.
+pub fn row_to_genetic_test(row: &Row) -> rusqlite::Result<GeneticTest> {
+    let result_json: String = row.get(5)?;
+    Ok(GeneticTest {
+        id: row.get(0)?,
+        goat_id: row.get(1)?,
+        test_type: row.get(2)?,
+        test_date: row.get(3)?,
+        lab_name: row.get(4)?,
+        result_json: serde_json::from_str(&result_json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        report_url: row.get(6)?,
+    })
+}
+
+/// Maps a `worker_tasks` row selected with `WORKER_TASK_COLUMNS`.
+pub fn row_to_worker_task(row: &Row) -> rusqlite::Result<WorkerTask> {
+    Ok(WorkerTask {
+        id: row.get(0)?,
+        worker_id: row.get(1)?,
+        task: row.get(2)?,
+        assigned_at: row.get(3)?,
+        due_at: row.get(4)?,
+        completed_at: row.get(5)?,
+        priority: row.get(6)?,
+        description: row.get(7)?,
+    })
+}
+
+/// Columns selected for `row_to_worker`.
+const WORKER_COLUMNS: &str =
+    "id, name, hours_worked, leaves, role, contact, hourly_rate, overtime_threshold_hours";
+
+/// Maps a `workers` row selected as `WORKER_COLUMNS`.
+pub fn row_to_worker(row: &Row) -> rusqlite::Result<Worker> {
+    Ok(Worker {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        hours_worked: row.get::<_, Option<i64>>(2)?.unwrap_or_default(),
+        leaves: row.get::<_, Option<i64>>(3)?.unwrap_or_default(),
+        role: row.get(4)?,
+        contact: row.get(5)?,
+        hourly_rate: row.get(6)?,
+        overtime_threshold_hours: row.get(7)?,
+    })
+}
+
+/// Columns selected for `row_to_time_entry`.
+const TIME_ENTRY_COLUMNS: &str = "id, worker_id, work_date, hours, note";
+
+/// Maps a `worker_time_entries` row selected as `TIME_ENTRY_COLUMNS`.
+fn row_to_time_entry(row: &Row) -> rusqlite::Result<TimeEntry> {
+    Ok(TimeEntry {
+        id: row.get(0)?,
+        worker_id: row.get(1)?,
+        work_date: row.get(2)?,
+        hours: row.get(3)?,
+        note: row.get(4)?,
+    })
+}
+
+/// Columns selected for `row_to_equipment`.
+const EQUIPMENT_COLUMNS: &str = "id, name, description, purchase_date, condition, \
+     last_maintenance, purchase_price, useful_life_years, supplier_id";
+
+/// Maps an `equipment` row selected as `EQUIPMENT_COLUMNS`.
+pub fn row_to_equipment(row: &Row) -> rusqlite::Result<Equipment> {
+    Ok(Equipment {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        purchase_date: row.get(3)?,
+        condition: row.get(4)?,
+        last_maintenance: row.get(5)?,
+        purchase_price: row.get(6)?,
+        useful_life_years: row.get(7)?,
+        supplier_id: row.get(8)?,
+    })
+}
+
+/// Values `item` as of `as_of`, or `None` if its purchase date, price or useful life is
+/// missing or the date is malformed.
+pub fn equipment_depreciation(item: &Equipment, as_of: NaiveDate) -> Option<DepreciationInfo> {
+    let purchase_date = item.purchase_date.as_deref()?;
+    let Ok(purchase_date) = NaiveDate::parse_from_str(purchase_date.trim(), "%Y-%m-%d") else {
+        warn!(name = %item.name, purchase_date, "Skipping equipment with malformed purchase date");
+        return None;
+    };
+    Some(compute_depreciation(
+        purchase_date,
+        item.purchase_price?,
+        item.useful_life_years?,
+        as_of,
+    ))
+}
+
+/// Maps a `sensors` row selected as
+/// `id, sensor_type, location, last_reading, last_reading_time, status, space_id`.
+pub fn row_to_sensor(row: &Row) -> rusqlite::Result<Sensor> {
+    Ok(Sensor {
+        id: row.get(0)?,
+        sensor_type: row.get(1)?,
+        location: row.get(2)?,
+        last_reading: row.get(3)?,
+        last_reading_time: row.get(4)?,
+        status: row.get(5)?,
+        space_id: row.get(6)?,
+    })
+}
+
+/// Maps a `spaces` row selected as `id, name, type, capacity, grass_condition, health`.
+pub fn row_to_space(row: &Row) -> rusqlite::Result<Space> {
+    Ok(Space {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        space_type: row.get(2)?,
+        capacity: row.get(3)?,
+        grass_condition: row.get(4)?,
+        health: row.get(5)?,
+    })
+}
+
+/// Maps a `show_entries` row selected as
+/// `id, goat_id, show_name, show_date, location, class, placement, prize_amount, notes`.
+pub fn row_to_show_entry(row: &Row) -> rusqlite::Result<ShowEntry> {
+    Ok(ShowEntry {
+        id: row.get(0)?,
+        goat_id: row.get(1)?,
+        show_name: row.get(2)?,
+        show_date: row.get(3)?,
+        location: row.get(4)?,
+        class: row.get(5)?,
+        placement: row.get(6)?,
+        prize_amount: row.get(7)?,
+        notes: row.get(8)?,
+    })
+}
+
+/// Maps a `famacha_scores` row selected as `id, goat_id, score, scored_at, scored_by`.
+pub fn row_to_famacha_score(row: &Row) -> rusqlite::Result<FamachaScore> {
+    Ok(FamachaScore {
+        id: row.get(0)?,
+        goat_id: row.get(1)?,
+        score: row.get(2)?,
+        scored_at: row.get(3)?,
+        scored_by: row.get(4)?,
+    })
+}
+
+/// Columns selected by `row_to_behavior_observation`, in order.
+const BEHAVIOR_COLUMNS: &str = "id, goat_id, behavior, intensity, recorded_at, observed_by, notes";
+
+/// Maps a `behavior_observations` row selected with `BEHAVIOR_COLUMNS`.
+pub fn row_to_behavior_observation(row: &Row) -> rusqlite::Result<BehaviorObservation> {
+    Ok(BehaviorObservation {
+        id: row.get(0)?,
+        goat_id: row.get(1)?,
+        behavior: row.get(2)?,
+        intensity: row.get(3)?,
+        recorded_at: row.get(4)?,
+        observed_by: row.get(5)?,
+        notes: row.get(6)?,
+    })
+}
+
+/// Maps a `cohorts` row selected as `id, name, description, created_at`.
+pub fn row_to_cohort(row: &Row) -> rusqlite::Result<Cohort> {
+    Ok(Cohort {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        created_at: row.get(3)?,
+    })
+}
+
+/// Maps a `custom_breeds` row selected as `id, name, created_at`.
+pub fn row_to_custom_breed(row: &Row) -> rusqlite::Result<CustomBreed> {
+    Ok(CustomBreed {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        created_at: row.get(2)?,
+    })
+}
+
+/// Columns selected by `row_to_supplier`, in order.
+const SUPPLIER_COLUMNS: &str = "id, name, contact, notes, created_at";
+
+/// Maps a `suppliers` row selected with `SUPPLIER_COLUMNS`.
+pub fn row_to_supplier(row: &Row) -> rusqlite::Result<Supplier> {
+    Ok(Supplier {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        contact: row.get(2)?,
+        notes: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+/// Checks run by `DbPool::data_quality_scan`: an issue label and a query selecting the
+/// `id, name` of every live goat that has the issue.
+const DATA_QUALITY_CHECKS: [(&str, &str); 6] = [
+    (
+        "Weight is 0",
+        "SELECT id, name FROM goats WHERE deleted_at IS NULL AND weight = 0 ORDER BY id",
+    ),
+    (
+        "Cost is 0",
+        "SELECT id, name FROM goats WHERE deleted_at IS NULL AND cost = 0 ORDER BY id",
+    ),
+    (
+        "Last bred date is in the future",
+        "SELECT id, name FROM goats \
+         WHERE deleted_at IS NULL AND date(last_bred) > date('now') ORDER BY id",
+    ),
+    (
+        "Sick with no disease recorded",
+        "SELECT g.id, g.name FROM goats g \
+         WHERE g.deleted_at IS NULL AND lower(trim(g.health_status)) = 'sick' \
+         AND NOT EXISTS (SELECT 1 FROM goat_diseases d WHERE d.goat_id = g.id) ORDER BY g.id",
+    ),
+    (
+        "No vaccinations recorded",
+        "SELECT g.id, g.name FROM goats g \
+         WHERE g.deleted_at IS NULL \
+         AND NOT EXISTS (SELECT 1 FROM goat_vaccines v WHERE v.goat_id = g.id) ORDER BY g.id",
+    ),
+    (
+        "Male goat marked pregnant",
+        "SELECT id, name FROM goats \
+         WHERE deleted_at IS NULL AND gender = 'Male' AND is_pregnant = 1 ORDER BY id",
+    ),
+];
+
+/// Columns selected by `row_to_geofence`, in order.
+const GEOFENCE_COLUMNS: &str = "id, name, lat_center, lon_center, radius_m, alert_on";
+
+/// Maps a `geofences` row selected with `GEOFENCE_COLUMNS`.
+pub fn row_to_geofence(row: &Row) -> rusqlite::Result<Geofence> {
+    Ok(Geofence {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        lat_center: row.get(2)?,
+        lon_center: row.get(3)?,
+        radius_m: row.get(4)?,
+        alert_on: row.get(5)?,
+    })
+}
+
+/// Columns selected by `row_to_location_alert`, in order.
+const LOCATION_ALERT_COLUMNS: &str =
+    "id, goat_id, geofence_id, geofence_name, event, lat, lon, distance_m, created_at";
+
+/// Maps a `location_alerts` row selected with `LOCATION_ALERT_COLUMNS`.
+pub fn row_to_location_alert(row: &Row) -> rusqlite::Result<LocationAlert> {
+    Ok(LocationAlert {
+        id: row.get(0)?,
+        goat_id: row.get(1)?,
+        geofence_id: row.get(2)?,
+        geofence_name: row.get(3)?,
+        event: row.get(4)?,
+        lat: row.get(5)?,
+        lon: row.get(6)?,
+        distance_m: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+/// Columns selected by `row_to_trade_log_entry`, in order.
+const TRADE_LOG_COLUMNS: &str =
+    "id, transaction_hash, goat_id, action, from_party, to_party, price, timestamp, prev_hash";
+
+/// Maps an `immutable_trade_log` row selected with `TRADE_LOG_COLUMNS`.
+pub fn row_to_trade_log_entry(row: &Row) -> rusqlite::Result<TradeLogEntry> {
+    Ok(TradeLogEntry {
+        id: row.get(0)?,
+        transaction_hash: row.get(1)?,
+        goat_id: row.get(2)?,
+        action: row.get(3)?,
+        from_party: row.get(4)?,
+        to_party: row.get(5)?,
+        price: row.get(6)?,
+        timestamp: row.get(7)?,
+        prev_hash: row.get(8)?,
+    })
+}
+
+/// Columns selected by `row_to_buyer`, in order.
+const BUYER_COLUMNS: &str = "id, name, contact, notes, created_at";
+
+/// Maps a `buyers` row selected with `BUYER_COLUMNS`.
+pub fn row_to_buyer(row: &Row) -> rusqlite::Result<Buyer> {
+    Ok(Buyer {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        contact: row.get(2)?,
+        notes: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+/// Columns selected by `row_to_medicine_item`, in order.
+const MEDICINE_COLUMNS: &str = "id, name, category, stock_units, unit, expiry_date, cost_per_unit";
+
+/// Maps a `medicine_inventory` row selected with `MEDICINE_COLUMNS`.
+pub fn row_to_medicine_item(row: &Row) -> rusqlite::Result<MedicineItem> {
+    Ok(MedicineItem {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        category: row.get(2)?,
+        stock_units: row.get(3)?,
+        unit: row.get(4)?,
+        expiry_date: row.get(5)?,
+        cost_per_unit: row.get(6)?,
+    })
+}
+
+/// Most sensor readings removed per transaction when pruning, so the write lock is
+/// released between batches.
+pub const SENSOR_PRUNE_BATCH: u32 = 1_000;
+
+/// Ids of the next batch of readings recorded before `?1`, at most `?2` of them.
+const SENSOR_PRUNE_IDS: &str =
+    "SELECT id FROM sensor_readings WHERE recorded_at < ?1 ORDER BY id LIMIT ?2";
+
+/// Goats `g` in the herd at instant `?1`: added before it and neither deleted nor sold
+/// by then.
+const IN_HERD_AT: &str = "g.created_at < ?1 \
+     AND (g.deleted_at IS NULL OR g.deleted_at >= ?1) \
+     AND NOT EXISTS (SELECT 1 FROM sales s WHERE s.goat_id = g.id AND s.created_at < ?1)";
+
+/// Per-goat money totals behind `ProfitAnalysis`, selected from `goats g`; callers
+/// append the `WHERE` clause.
+const PROFIT_ANALYSIS_SELECT: &str = "SELECT g.id, g.name, \
+     COALESCE(g.cost_minor / 100.0, g.cost, 0.0), \
+     COALESCE(g.current_price_minor / 100.0, g.current_price, 0.0), \
+     (SELECT COALESCE(SUM(amount), 0.0) FROM expenses \
+      WHERE goat_id = g.id AND category = 'Feed'), \
+     (SELECT COALESCE(SUM(amount), 0.0) FROM expenses \
+      WHERE goat_id = g.id AND category = 'Vet') \
+     + (SELECT COALESCE(SUM(cost), 0.0) FROM vet_visits WHERE goat_id = g.id), \
+     (SELECT COALESCE(SUM(revenue), 0.0) FROM production_records \
+      WHERE goat_id = g.id AND type = 'Milk') \
+     FROM goats g";
+
+/// Maps a row selected with `PROFIT_ANALYSIS_SELECT`, deriving `net_position`.
+pub fn row_to_profit_analysis(row: &Row) -> rusqlite::Result<ProfitAnalysis> {
+    let acquisition_cost: f64 = row.get(2)?;
+    let current_value: f64 = row.get(3)?;
+    let feed_cost_to_date: f64 = row.get(4)?;
+    let vet_cost_to_date: f64 = row.get(5)?;
+    let milk_revenue_to_date: f64 = row.get(6)?;
+    Ok(ProfitAnalysis {
+        goat_id: row.get(0)?,
+        name: row.get(1)?,
+        acquisition_cost,
+        current_value,
+        feed_cost_to_date,
+        vet_cost_to_date,
+        milk_revenue_to_date,
+        net_position: current_value + milk_revenue_to_date
+            - acquisition_cost
+            - feed_cost_to_date
+            - vet_cost_to_date,
+    })
+}
+
+/// Runs `sql` without parameters and maps every row with `f`.
+fn query_all<T>(
+    conn: &Connection,
+    sql: &str,
+    f: impl FnMut(&Row) -> rusqlite::Result<T>,
+) -> Result<Vec<T>, AppError> {
+    Ok(timed_query_map(conn, sql, [], f)?)
+}
+
+/// Returns the id of the first row in `table` whose `key_sql` predicate matches `params`.
+fn find_id(
+    conn: &Connection,
+    table: &str,
+    key_sql: &str,
+    params: impl rusqlite::Params,
+) -> Result<Option<i64>, AppError> {
+    let sql = format!("SELECT id FROM {} WHERE {} LIMIT 1", table, key_sql);
+    Ok(timed_query_row(conn, &sql, params, |r| r.get(0)).optional()?)
+}
+
+/// Moves the goat just inserted as `from` to the id `to`, vaccine and disease links
+/// included, so that a goat restored from a snapshot keeps its exported id.
+///
+/// # Errors
+/// Returns a database error, including a constraint error if `to` is taken.
+fn move_goat_id(conn: &Connection, from: i64, to: i64) -> Result<(), AppError> {
+    timed_execute(conn, "UPDATE goats SET id = ?2 WHERE id = ?1", [from, to])?;
+    timed_execute(
+        conn,
+        "UPDATE goat_vaccines SET goat_id = ?2 WHERE goat_id = ?1",
+        [from, to],
+    )?;
+    timed_execute(
+        conn,
+        "UPDATE goat_diseases SET goat_id = ?2 WHERE goat_id = ?1",
+        [from, to],
+    )?;
+    debug!(from, to, "Restored goat id");
+    Ok(())
+}
+
+/// Reads a persisted server setting by key.
+///
+/// # Errors
+/// Returns a database error if the `settings` table cannot be queried.
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>, AppError> {
+    trace!(key, "Reading setting");
+    Ok(conn
+        .query_row("SELECT value FROM settings WHERE key = ?1", [key], |r| {
+            r.get(0)
+        })
+        .optional()?)
+}
+
+/// Current version of a reference list, kept under `key` in `settings`; 0 before its
+/// first change.
+///
+/// # Errors
+/// Returns a database error if the `settings` table cannot be queried.
+pub fn reference_version(conn: &Connection, key: &str) -> Result<i64, AppError> {
+    Ok(get_setting(conn, key)?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0))
+}
+
+/// Folds `duplicate_ids` into `keep_id` within one catalog table (`vaccines` or `diseases`).
+///
+/// Links are re-pointed to `keep_id` (dropping any that would become double-links), the
+/// duplicates are deleted and the merge is written to the audit log. Meant to run inside a
+/// caller-owned transaction.
+///
+/// # Errors
+/// - `InvalidInput` if `duplicate_ids` is empty or contains `keep_id`.
+/// - `NotFound` if `keep_id` or any duplicate id does not exist.
+fn merge_catalog_entries(
+    conn: &Connection,
+    table: &str,
+    link_table: &str,
+    link_column: &str,
+    keep_id: i64,
+    duplicate_ids: &[i64],
+) -> Result<MergeSummary, AppError> {
+    let mut merged_ids = duplicate_ids.to_vec();
+    merged_ids.sort_unstable();
+    merged_ids.dedup();
+    if merged_ids.is_empty() {
+        return Err(AppError::InvalidInput(
+            "duplicate_ids must list at least one id".into(),
+        ));
+    }
+    if merged_ids.contains(&keep_id) {
+        return Err(AppError::InvalidInput(format!(
+            "Cannot merge {} {} into itself",
+            table, keep_id
+        )));
+    }
+
+    let name_sql = format!("SELECT name FROM {} WHERE id = ?1", table);
+    let mut names = Vec::with_capacity(merged_ids.len());
+    for &id in std::iter::once(&keep_id).chain(&merged_ids) {
+        let name: String = conn
+            .query_row(&name_sql, [id], |r| r.get(0))
+            .optional()?
+            .ok_or_else(|| AppError::NotFound(format!("No {} entry with id {}", table, id)))?;
+        names.push(name);
+    }
+
+    let repoint_sql = format!(
+        "INSERT OR IGNORE INTO {link} (goat_id, {col}, created_at) \
+         SELECT goat_id, ?1, created_at FROM {link} WHERE {col} = ?2",
+        link = link_table,
+        col = link_column
+    );
+    let count_sql = format!(
+        "SELECT COUNT(*) FROM {} WHERE {} = ?1",
+        link_table, link_column
+    );
+    let delete_links_sql = format!("DELETE FROM {} WHERE {} = ?1", link_table, link_column);
+    let delete_sql = format!("DELETE FROM {} WHERE id = ?1", table);
+
+    let mut links_moved = 0;
+    let mut links_deduplicated = 0;
+    for &id in &merged_ids {
+        let total: usize = timed_query_row(conn, &count_sql, [id], |r| r.get(0))?;
+        let moved = timed_execute(conn, &repoint_sql, [keep_id, id])?;
+        timed_execute(conn, &delete_links_sql, [id])?;
+        timed_execute(conn, &delete_sql, [id])?;
+        trace!(table, id, keep_id, moved, "Merged duplicate entry");
+        links_moved += moved;
+        links_deduplicated += total - moved;
+    }
+
+    let details = serde_json::json!({
+        "kept_name": names[0],
+        "merged_ids": merged_ids,
+        "merged_names": &names[1..],
+        "links_moved": links_moved,
+        "links_deduplicated": links_deduplicated,
+    });
+    record_audit(conn, table, keep_id, "merge", &details.to_string())?;
+
+    info!(
+        table,
+        keep_id,
+        merged = merged_ids.len(),
+        links_moved,
+        links_deduplicated,
+        "Catalog entries merged"
+    );
+    Ok(MergeSummary {
+        kept_id: keep_id,
+        merged_ids,
+        links_moved,
+        links_deduplicated,
+    })
+}
+
+/// Appends an entry to the audit log; `details` is stored verbatim and is expected to be JSON.
+///
+/// # Errors
+/// Returns a database error if the insert fails.
+pub fn record_audit(
+    conn: &Connection,
+    entity: &str,
+    entity_id: i64,
+    action: &str,
+    details: &str,
+) -> Result<(), AppError> {
+    trace!(entity, entity_id, action, "Recording audit entry");
+    timed_execute(
+        conn,
+        "INSERT INTO audit_log (entity, entity_id, action, details) VALUES (?1, ?2, ?3, ?4)",
+        params![entity, entity_id, action, details],
+    )?;
+    Ok(())
+}
+
+/// Opens an alert for an operator's attention and returns its id.
+///
+/// # Errors
+/// Returns a database error if the insert fails.
+pub fn raise_alert(
+    conn: &Connection,
+    goat_id: Option<i64>,
+    kind: &str,
+    message: &str,
+) -> Result<i64, AppError> {
+    timed_execute(
+        conn,
+        "INSERT INTO alerts (goat_id, kind, message) VALUES (?1, ?2, ?3)",
+        params![goat_id, kind, message],
+    )?;
+    let alert_id = conn.last_insert_rowid();
+    warn!(alert_id, ?goat_id, kind, message, "Alert raised");
+    Ok(alert_id)
+}
+
+/// Inserts or overwrites a persisted server setting.
+///
+/// # Errors
+/// Returns a database error if the upsert fails.
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<(), AppError> {
+    trace!(key, value, "Writing setting");
+    timed_execute(
+        conn,
+        "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, CURRENT_TIMESTAMP) \
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        [key, value],
+    )?;
+    Ok(())
+}
+
+impl DbPool {
+    /// Builds the activity report for a single calendar day.
+    ///
+    /// Every category is counted by its own query filtered on `date(created_at) = ?`;
+    /// goat updates are keyed on `updated_at` instead.
+    ///
+    /// # Errors
+    /// Returns database errors raised by any of the category queries.
+    ///
+    /// # Logging
+    /// Traces each category query and debugs the assembled report.
+    pub fn daily_report(conn: &Connection, date: NaiveDate) -> Result<DailyReport, AppError> {
+        let day = date.format("%Y-%m-%d").to_string();
+        trace!(day, "Building daily report");
+
+        let count = |table: &str, column: &str| -> Result<i64, AppError> {
+            let sql = format!("SELECT COUNT(*) FROM {} WHERE date({}) = ?1", table, column);
+            trace!(table, "Counting daily events");
+            Ok(timed_query_row(conn, &sql, [&day], |row| row.get(0))?)
+        };
+
+        let report = DailyReport {
+            goats_added: count("goats", "created_at")?,
+            goats_updated: count("goats", "updated_at")?,
+            diseases_diagnosed: count("goat_diseases", "created_at")?,
+            vaccinations_administered: count("goat_vaccines", "created_at")?,
+            weight_measurements: count("goat_weight_history", "created_at")?,
+            feed_events: count("feed_events", "created_at")?,
+            vet_visits: count("vet_visits", "created_at")?,
+            sales: count("sales", "created_at")?,
+            date: day,
+        };
+
+        debug!(?report, "Daily report built");
+        Ok(report)
+    }
+
+    /// Counts new disease diagnoses between `from` and `to` (inclusive), bucketed by
+    /// `interval` and disease name.
+    ///
+    /// Buckets are computed in SQL with `strftime` over the link's `created_at`, so a week
+    /// label is `YYYY-WW` (Monday-based) and a month label is `YYYY-MM`.
+    ///
+    /// # Errors
+    /// Returns database errors raised while preparing or running the query.
+    ///
+    /// # Logging
+    /// Traces the query parameters and the number of points returned.
+    pub fn disease_trends(
+        conn: &Connection,
+        from: NaiveDate,
+        to: NaiveDate,
+        interval: TrendInterval,
+    ) -> Result<Vec<DiseaseTrendPoint>, AppError> {
+        trace!(%from, %to, ?interval, "Computing disease trends");
+
+        let sql = format!(
+            "SELECT strftime('{fmt}', gd.created_at) AS bucket, d.name, COUNT(*) \
+             FROM goat_diseases gd INNER JOIN diseases d ON d.id = gd.disease_id \
+             WHERE date(gd.created_at) BETWEEN ?1 AND ?2 \
+             GROUP BY strftime('{fmt}', gd.created_at), d.name \
+             ORDER BY bucket, d.name",
+            fmt = interval.strftime_format()
+        );
+        let points = timed_query_map(
+            conn,
+            &sql,
+            [
+                from.format("%Y-%m-%d").to_string(),
+                to.format("%Y-%m-%d").to_string(),
+            ],
+            |row| {
+                Ok(DiseaseTrendPoint {
+                    interval: row.get(0)?,
+                    disease: row.get(1)?,
+                    count: row.get(2)?,
+                })
+            },
+        )?;
+
+        trace!(count = points.len(), "Disease trend points computed");
+        Ok(points)
+    }
+
+    /// Collects goats created, updated, or soft-deleted at or after `since`.
+    ///
+    /// `since` must already be in SQLite's `YYYY-MM-DD HH:MM:SS` UTC form. The comparison is
+    /// inclusive because timestamps only have second resolution; clients may therefore see
+    /// a record twice, but never miss one. The returned `server_time` is read before the
+    /// queries run, so it is safe to use as the next `since`.
+    ///
+    /// # Errors
+    /// Returns database or enum parsing errors while loading the changed goats.
+    ///
+    /// # Logging
+    /// Debugs the number of changed and deleted goats.
+    pub fn goat_changes(conn: &Connection, since: &str) -> Result<GoatChanges, AppError> {
+        trace!(since, "Collecting goat changes");
+        let server_time: String =
+            timed_query_row(conn, "SELECT CURRENT_TIMESTAMP", [], |r| r.get(0))?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM goats \
+             WHERE deleted_at IS NULL AND COALESCE(updated_at, created_at) >= ?1 \
+             ORDER BY id",
+            goat_columns("goats")
+        ))?;
+        let rows: Vec<(i64, GoatParams)> = stmt
+            .query_map([since], |row| {
+                let params = row_to_goat(row)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+                Ok((row.get(0)?, params))
+            })?
+            .collect::<Result<_, _>>()?;
+
+        let mut changed = Vec::with_capacity(rows.len());
+        for (id, mut params) in rows {
+            params.vaccinations = fetch_vaccines(conn, id)?;
+            params.diseases = fetch_diseases(conn, id)?;
+            changed.push(Goat::new(Some(id), params));
+        }
+
+        let mut stmt = conn
+            .prepare("SELECT id, name, deleted_at FROM goats WHERE deleted_at >= ?1 ORDER BY id")?;
+        let deleted: Vec<GoatTombstone> = stmt
+            .query_map([since], |row| {
+                Ok(GoatTombstone {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    deleted_at: row.get(2)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        debug!(
+            changed = changed.len(),
+            deleted = deleted.len(),
+            "Goat changes collected"
+        );
+        Ok(GoatChanges {
+            server_time,
+            changed,
+            deleted,
+        })
+    }
+
+    /// Computes the average daily weight gain, in grams, of goats of `breed` between `from`
+    /// and `to` (inclusive).
+    ///
+    /// Each goat contributes `(last_weight - first_weight) / days_between` using its first
+    /// and last `goat_weight_history` entries inside the range. Goats with a single
+    /// measurement (or all measurements at the same instant) are excluded.
+    ///
+    /// # Errors
+    /// Returns database errors raised while loading the weight history.
+    ///
+    /// # Logging
+    /// Traces per-goat gains and debugs the final sample size.
+    pub fn breed_weight_gain(
+        conn: &Connection,
+        breed: &Breed,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<BreedWeightGain, AppError> {
+        let breed_str = breed_to_str(breed);
+        trace!(breed = breed_str, %from, %to, "Computing breed weight gain");
+
+        let mut stmt = conn.prepare(
+            "SELECT h.goat_id, h.weight, julianday(h.created_at) \
+             FROM goat_weight_history h INNER JOIN goats g ON g.id = h.goat_id \
+             WHERE g.breed = ?1 AND g.deleted_at IS NULL \
+             AND date(h.created_at) BETWEEN ?2 AND ?3 \
+             ORDER BY h.goat_id, h.created_at",
+        )?;
+        let rows: Vec<(i64, f64, f64)> = stmt
+            .query_map(
+                params![
+                    breed_str,
+                    from.format("%Y-%m-%d").to_string(),
+                    to.format("%Y-%m-%d").to_string()
+                ],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?
+            .collect::<Result<_, _>>()?;
+
+        let mut gains = Vec::new();
+        for measurements in rows.chunk_by(|a, b| a.0 == b.0) {
+            let (goat_id, first_weight, first_day) = measurements[0];
+            let (_, last_weight, last_day) = measurements[measurements.len() - 1];
+            let days = last_day - first_day;
+            if measurements.len() < 2 || days <= 0.0 {
+                trace!(goat_id, "Skipping goat without a measurable interval");
+                continue;
+            }
+            let gain_g = (last_weight - first_weight) * 1000.0 / days;
+            trace!(goat_id, gain_g, "Per-goat daily gain");
+            gains.push(gain_g);
+        }
+
+        let avg_daily_gain_g = if gains.is_empty() {
+            0.0
+        } else {
+            gains.iter().sum::<f64>() / gains.len() as f64
+        };
+
+        debug!(
+            breed = breed_str,
+            sample_size = gains.len(),
+            "Breed weight gain computed"
+        );
+        Ok(BreedWeightGain {
+            breed: breed_str.to_string(),
+            avg_daily_gain_g,
+            sample_size: gains.len() as u32,
+            from: from.format("%Y-%m-%d").to_string(),
+            to: to.format("%Y-%m-%d").to_string(),
+        })
+    }
+
+    /// Exports every live goat (with relations) and all catalog and farm tables as a
+    /// single versioned document.
+    ///
+    /// # Errors
+    /// Returns database or enum parsing errors raised while reading any table.
+    ///
+    /// # Logging
+    /// Info-logs the exported record counts.
+    pub fn export_snapshot(conn: &Connection) -> Result<Snapshot, AppError> {
+        trace!("Exporting database snapshot");
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM goats WHERE deleted_at IS NULL ORDER BY id",
+            goat_columns("goats")
+        ))?;
+        let rows: Vec<(i64, GoatParams)> = stmt
+            .query_map([], |row| {
+                let params = row_to_goat(row)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+                Ok((row.get(0)?, params))
+            })?
+            .collect::<Result<_, _>>()?;
+        let mut goats = Vec::with_capacity(rows.len());
+        for (id, mut params) in rows {
+            params.vaccinations = fetch_vaccines(conn, id)?;
+            params.diseases = fetch_diseases(conn, id)?;
+            goats.push(Goat::new(Some(id), params));
+        }
+
+        let snapshot = Snapshot {
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            goats,
+            vaccines: query_all(conn, "SELECT id, name FROM vaccines ORDER BY id", |r| {
+                Ok(VaccineRef {
+                    id: r.get(0)?,
+                    name: r.get(1)?,
+                })
+            })?,
+            diseases: query_all(conn, "SELECT id, name FROM diseases ORDER BY id", |r| {
+                Ok(DiseaseRef {
+                    id: r.get(0)?,
+                    name: r.get(1)?,
+                })
+            })?,
+            workers: query_all(
+                conn,
+                &format!("SELECT {} FROM workers ORDER BY id", WORKER_COLUMNS),
+                row_to_worker,
+            )?,
+            equipment: Self::list_equipment(conn)?,
+            sensors: query_all(
+                conn,
+                "SELECT id, sensor_type, location, last_reading, last_reading_time, status, \
+                 space_id FROM sensors ORDER BY id",
+                row_to_sensor,
+            )?,
+            spaces: query_all(
+                conn,
+                "SELECT id, name, type, capacity, grass_condition, health FROM spaces ORDER BY id",
+                row_to_space,
+            )?,
+        };
+
+        info!(
+            goats = snapshot.goats.len(),
+            vaccines = snapshot.vaccines.len(),
+            diseases = snapshot.diseases.len(),
+            workers = snapshot.workers.len(),
+            equipment = snapshot.equipment.len(),
+            sensors = snapshot.sensors.len(),
+            spaces = snapshot.spaces.len(),
+            "Snapshot exported"
+        );
+        Ok(snapshot)
+    }
+
+    /// Loads a snapshot produced by `export_snapshot`.
+    ///
+    /// In `Replace` mode the exported tables are emptied first and every record is
+    /// restored under its exported id; goats the snapshot carries are overwritten in
+    /// place instead, keeping their documents. Tables the snapshot does not carry are
+    /// kept, minus the rows left pointing at a goat, worker, sensor, or space that did
+    /// not come back. In `Merge` mode every record is upserted by its natural key:
+    /// goats, vaccines, diseases, workers, equipment, and spaces by name, sensors by type
+    /// and location. A goat matching only a soft-deleted goat brings that goat back.
+    /// Catalog ids inside goat relations are ignored and re-resolved by name, since they
+    /// refer to the source database; likewise a sensor's space is resolved to the id that
+    /// space was imported under.
+    ///
+    /// Must be called inside a transaction so a failing record aborts the whole import.
+    ///
+    /// # Errors
+    /// Returns `AppError::InvalidInput` for an unsupported `schema_version`, or any database
+    /// error raised while writing.
+    ///
+    /// # Logging
+    /// Warns on replace-mode wipes and info-logs the final summary.
+    pub fn import_snapshot(
+        conn: &Connection,
+        snapshot: &Snapshot,
+        mode: ImportMode,
+    ) -> Result<ImportSummary, AppError> {
+        if snapshot.schema_version != SNAPSHOT_SCHEMA_VERSION {
+            warn!(
+                version = snapshot.schema_version,
+                "Unsupported snapshot version"
+            );
+            return Err(AppError::InvalidInput(format!(
+                "Unsupported snapshot schema_version {} (expected {})",
+                snapshot.schema_version, SNAPSHOT_SCHEMA_VERSION
+            )));
+        }
+
+        if mode == ImportMode::Replace {
+            warn!("Replacing exported tables with snapshot contents");
+            // Goats the snapshot carries are overwritten in place rather than deleted, as
+            // deleting a goat also deletes its documents.
+            let goat_ids: Vec<i64> = snapshot.goats.iter().filter_map(Goat::id).collect();
+            timed_execute(
+                conn,
+                "DELETE FROM goats WHERE id NOT IN (SELECT value FROM json_each(?1))",
+                [serde_json::to_string(&goat_ids).unwrap_or_default()],
+            )?;
+            conn.execute_batch(
+                "DELETE FROM goat_vaccines WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM goat_diseases WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM vaccines;
+                 DELETE FROM diseases;
+                 DELETE FROM workers;
+                 DELETE FROM equipment;
+                 DELETE FROM sensors;
+                 DELETE FROM spaces;",
+            )?;
+        }
+        // Replace restores rows under their exported ids, so that records the snapshot
+        // does not carry keep pointing at the same goats, workers, sensors, and spaces.
+        let restored_id = |id: Option<i64>| id.filter(|_| mode == ImportMode::Replace);
+
+        let mut summary = ImportSummary {
+            mode,
+            ..ImportSummary::default()
+        };
+
+        for vaccine in &snapshot.vaccines {
+            summary.vaccines += timed_execute(
+                conn,
+                "INSERT OR IGNORE INTO vaccines (id, name) VALUES (?1, ?2)",
+                params![restored_id(vaccine.id), vaccine.name.trim()],
+            )?;
+        }
+        for disease in &snapshot.diseases {
+            summary.diseases += timed_execute(
+                conn,
+                "INSERT OR IGNORE INTO diseases (id, name) VALUES (?1, ?2)",
+                params![restored_id(disease.id), disease.name.trim()],
+            )?;
+        }
+
+        for goat in &snapshot.goats {
+            let mut params = goat.params.clone();
+            for vaccine in &mut params.vaccinations {
+                vaccine.id = None;
+            }
+            for disease in &mut params.diseases {
+                disease.id = None;
+            }
+            let existing = match mode {
+                ImportMode::Replace => match goat.id() {
+                    Some(id) => find_id(conn, "goats", "id = ?1", [id])?,
+                    None => None,
+                },
+                ImportMode::Merge => match find_goat_by_name(conn, &params.name)? {
+                    Some((goat_id, stored_name)) => {
+                        params.name = stored_name;
+                        Some(goat_id)
+                    }
+                    None => find_id(
+                        conn,
+                        "goats",
+                        "name = ?1 COLLATE NOCASE AND deleted_at IS NOT NULL ORDER BY id DESC",
+                        [normalize_name(&params.name)],
+                    )?,
+                },
+            };
+            match existing {
+                Some(goat_id) => {
+                    // A soft-deleted goat is brought back with the snapshot's data.
+                    timed_execute(
+                        conn,
+                        "UPDATE goats SET deleted_at = NULL \
+                         WHERE id = ?1 AND deleted_at IS NOT NULL",
+                        [goat_id],
+                    )?;
+                    Self::update_goat(conn, goat_id, &params, None)?;
+                }
+                None => {
+                    let goat_id = Self::insert_goat(conn, &params)?;
+                    if let Some(restored) = restored_id(goat.id())
+                        && restored != goat_id
+                    {
+                        move_goat_id(conn, goat_id, restored)?;
+                    }
+                }
+            }
+            summary.goats += 1;
+        }
+
+        for worker in &snapshot.workers {
+            match find_id(conn, "workers", "name = ?1", [&worker.name])? {
+                Some(id) => timed_execute(
+                    conn,
+                    "UPDATE workers SET hours_worked = ?1, leaves = ?2, role = ?3, contact = ?4, \
+                     hourly_rate = ?5, overtime_threshold_hours = ?6 WHERE id = ?7",
+                    params![
+                        worker.hours_worked,
+                        worker.leaves,
+                        worker.role,
+                        worker.contact,
+                        worker.hourly_rate,
+                        worker.overtime_threshold_hours,
+                        id
+                    ],
+                )?,
+                None => timed_execute(
+                    conn,
+                    "INSERT INTO workers (id, name, hours_worked, leaves, role, contact, \
+                     hourly_rate, overtime_threshold_hours) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        restored_id(worker.id),
+                        worker.name,
+                        worker.hours_worked,
+                        worker.leaves,
+                        worker.role,
+                        worker.contact,
+                        worker.hourly_rate,
+                        worker.overtime_threshold_hours
+                    ],
+                )?,
+            };
+            summary.workers += 1;
+        }
+
+        for item in &snapshot.equipment {
+            match find_id(conn, "equipment", "name = ?1", [&item.name])? {
+                Some(id) => timed_execute(
+                    conn,
+                    "UPDATE equipment SET description = ?1, purchase_date = ?2, condition = ?3, \
+                     last_maintenance = ?4, purchase_price = ?5, useful_life_years = ?6 \
+                     WHERE id = ?7",
+                    params![
+                        item.description,
+                        item.purchase_date,
+                        item.condition,
+                        item.last_maintenance,
+                        item.purchase_price,
+                        item.useful_life_years,
+                        id
+                    ],
+                )?,
+                None => timed_execute(
+                    conn,
+                    "INSERT INTO equipment (id, name, description, purchase_date, condition, \
+                     last_maintenance, purchase_price, useful_life_years) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        restored_id(item.id),
+                        item.name,
+                        item.description,
+                        item.purchase_date,
+                        item.condition,
+                        item.last_maintenance,
+                        item.purchase_price,
+                        item.useful_life_years
+                    ],
+                )?,
+            };
+            summary.equipment += 1;
+        }
+
+        // Snapshot space ids mapped to the ids the spaces ended up with here, so sensors
+        // follow their space even when a merge matched it under another id.
+        let mut space_ids = HashMap::new();
+        for space in &snapshot.spaces {
+            let local_id = match find_id(conn, "spaces", "name = ?1", [&space.name])? {
+                Some(id) => {
+                    timed_execute(
+                        conn,
+                        "UPDATE spaces SET type = ?1, capacity = ?2, grass_condition = ?3, \
+                         health = ?4 WHERE id = ?5",
+                        params![
+                            space.space_type,
+                            space.capacity,
+                            space.grass_condition,
+                            space.health,
+                            id
+                        ],
+                    )?;
+                    id
+                }
+                None => {
+                    timed_execute(
+                        conn,
+                        "INSERT INTO spaces (id, name, type, capacity, grass_condition, health) \
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                        params![
+                            restored_id(space.id),
+                            space.name,
+                            space.space_type,
+                            space.capacity,
+                            space.grass_condition,
+                            space.health
+                        ],
+                    )?;
+                    conn.last_insert_rowid()
+                }
+            };
+            if let Some(id) = space.id {
+                space_ids.insert(id, local_id);
+            }
+            summary.spaces += 1;
+        }
+
+        for sensor in &snapshot.sensors {
+            // A space the snapshot does not carry leaves the sensor uninstalled.
+            let space_id = sensor.space_id.and_then(|id| space_ids.get(&id).copied());
+            match find_id(
+                conn,
+                "sensors",
+                "sensor_type = ?1 AND location IS ?2",
+                params![sensor.sensor_type, sensor.location],
+            )? {
+                Some(id) => timed_execute(
+                    conn,
+                    "UPDATE sensors SET last_reading = ?1, last_reading_time = ?2, status = ?3, \
+                     space_id = ?4 WHERE id = ?5",
+                    params![
+                        sensor.last_reading,
+                        sensor.last_reading_time,
+                        sensor.status,
+                        space_id,
+                        id
+                    ],
+                )?,
+                None => timed_execute(
+                    conn,
+                    "INSERT INTO sensors (id, sensor_type, location, last_reading, \
+                     last_reading_time, status, space_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        restored_id(sensor.id),
+                        sensor.sensor_type,
+                        sensor.location,
+                        sensor.last_reading,
+                        sensor.last_reading_time,
+                        sensor.status,
+                        space_id
+                    ],
+                )?,
+            };
+            summary.sensors += 1;
+        }
+
+        if mode == ImportMode::Replace {
+            // Foreign keys are not enforced, so apply their ON DELETE actions to the rows
+            // whose goat, worker, sensor, or space did not come back with the snapshot.
+            conn.execute_batch(
+                "DELETE FROM goat_vaccines WHERE vaccine_id NOT IN (SELECT id FROM vaccines);
+                 DELETE FROM goat_diseases WHERE disease_id NOT IN (SELECT id FROM diseases);
+                 DELETE FROM disease_events WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM goat_weight_history WHERE goat_id NOT IN (SELECT id FROM goats);
+                 UPDATE feed_events SET goat_id = NULL WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM vet_visits WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM sales WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM show_entries WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM famacha_scores WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM deworming_records WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM cohort_goats WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM medicine_dispensing WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM behavior_observations WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM alerts WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM vaccination_schedule WHERE goat_id NOT IN (SELECT id FROM goats)
+                     OR vaccine_id NOT IN (SELECT id FROM vaccines);
+                 DELETE FROM leave_requests WHERE worker_id NOT IN (SELECT id FROM workers);
+                 DELETE FROM worker_tasks WHERE worker_id NOT IN (SELECT id FROM workers);
+                 DELETE FROM worker_time_entries WHERE worker_id NOT IN (SELECT id FROM workers);
+                 DELETE FROM goat_tags WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM genetic_tests WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM goat_locations WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM location_alerts WHERE goat_id NOT IN (SELECT id FROM goats);
+                 UPDATE expenses SET goat_id = NULL WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM production_records WHERE goat_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM space_goats WHERE goat_id NOT IN (SELECT id FROM goats)
+                     OR space_id NOT IN (SELECT id FROM spaces);
+                 DELETE FROM goat_space_history WHERE goat_id NOT IN (SELECT id FROM goats)
+                     OR space_id NOT IN (SELECT id FROM spaces);
+                 DELETE FROM goat_pedigree WHERE goat_id NOT IN (SELECT id FROM goats);
+                 UPDATE goat_pedigree SET sire_id = NULL WHERE sire_id NOT IN (SELECT id FROM goats);
+                 UPDATE goat_pedigree SET dam_id = NULL WHERE dam_id NOT IN (SELECT id FROM goats);
+                 DELETE FROM sensor_readings WHERE sensor_id NOT IN (SELECT id FROM sensors);
+                 DELETE FROM sensor_reading_daily WHERE sensor_id NOT IN (SELECT id FROM sensors);
+                 DELETE FROM farm_map WHERE space_id NOT IN (SELECT id FROM spaces);",
+            )?;
+        }
+
+        info!(?summary, "Snapshot imported");
+        Ok(summary)
+    }
+
+    /// Computes per-vaccine coverage of the living herd, optionally restricted to a breed.
+    ///
+    /// The denominator excludes soft-deleted goats and goats whose `lifecycle_state` is
+    /// `Deceased`. Vaccines nobody has received are still listed with zero coverage.
+    ///
+    /// # Errors
+    /// Returns database errors raised by either query.
+    ///
+    /// # Logging
+    /// Traces the filter and debugs the herd size used as denominator.
+    pub fn vaccination_coverage(
+        conn: &Connection,
+        breed: Option<&str>,
+    ) -> Result<Vec<VaccineCoverage>, AppError> {
+        trace!(?breed, "Computing vaccination coverage");
+        let breed_filter = if breed.is_some() {
+            " AND g.breed = ?1"
+        } else {
+            ""
+        };
+
+        let total_sql = format!(
+            "SELECT COUNT(*) FROM goats g \
+             WHERE g.deleted_at IS NULL AND g.lifecycle_state != 'Deceased'{}",
+            breed_filter
+        );
+        let coverage_sql = format!(
+            "SELECT v.name, COUNT(DISTINCT g.id) FROM vaccines v \
+             LEFT JOIN goat_vaccines gv ON gv.vaccine_id = v.id \
+             LEFT JOIN goats g ON g.id = gv.goat_id \
+             AND g.deleted_at IS NULL AND g.lifecycle_state != 'Deceased'{} \
+             GROUP BY v.id ORDER BY v.name",
+            breed_filter
+        );
+        let params: Vec<&str> = breed.into_iter().collect();
+
+        let total_goats: i32 =
+            timed_query_row(conn, &total_sql, rusqlite::params_from_iter(&params), |r| {
+                r.get(0)
+            })?;
+        debug!(total_goats, "Coverage denominator computed");
+
+        let coverage = timed_query_map(
+            conn,
+            &coverage_sql,
+            rusqlite::params_from_iter(&params),
+            |row| {
+                let vaccinated_count: i32 = row.get(1)?;
+                let coverage_pct = if total_goats > 0 {
+                    vaccinated_count as f64 / total_goats as f64 * 100.0
+                } else {
+                    0.0
+                };
+                Ok(VaccineCoverage {
+                    vaccine: row.get(0)?,
+                    vaccinated_count,
+                    total_goats,
+                    coverage_pct,
+                })
+            },
+        )?;
+
+        Ok(coverage)
+    }
+
+    /// Returns the most recent market price per kg for `breed`, falling back to the latest
+    /// herd-wide price (recorded with a NULL breed) when the breed has none.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the lookup.
+    pub fn get_latest_market_price(
+        conn: &Connection,
+        breed: &str,
+    ) -> Result<Option<f64>, AppError> {
+        trace!(breed, "Looking up latest market price");
+        Ok(conn
+            .query_row(
+                "SELECT price_per_kg FROM market_prices \
+                 WHERE breed = ?1 OR breed IS NULL \
+                 ORDER BY breed IS NULL, recorded_at DESC, id DESC LIMIT 1",
+                [breed],
+                |r| r.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Loads live goats that pass the SQL-checkable sale criteria (healthy, not pregnant,
+    /// not sold, not deceased), along with their birth dates.
+    ///
+    /// Weight and age thresholds are applied by the caller via `is_sale_ready`.
+    ///
+    /// # Errors
+    /// Returns database or enum parsing errors raised while mapping rows.
+    pub fn load_sale_candidates(conn: &Connection) -> Result<Vec<SaleCandidate>, AppError> {
+        trace!("Loading sale candidates");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}, birth_date FROM goats \
+             WHERE deleted_at IS NULL AND lifecycle_state != 'Deceased' \
+             AND lower(health_status) = ?1 AND is_pregnant = 0 AND is_sold = 0",
+            goat_columns("goats")
+        ))?;
+        let candidates = stmt
+            .query_map([SALEABLE_HEALTH_STATUS], |row| {
+                let params = row_to_goat(row)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+                let birth_date: Option<String> = row.get("birth_date")?;
+                Ok(SaleCandidate {
+                    goat: Goat::new(Some(row.get("id")?), params),
+                    birth_date: birth_date
+                        .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        debug!(count = candidates.len(), "Sale candidates loaded");
+        Ok(candidates)
+    }
+
+    /// Merges duplicate vaccines into `keep_id`, re-pointing their goat links.
+    ///
+    /// # Errors
+    /// See `merge_catalog_entries`.
+    pub fn merge_vaccines(
+        conn: &Connection,
+        keep_id: i64,
+        duplicate_ids: &[i64],
+    ) -> Result<MergeSummary, AppError> {
+        merge_catalog_entries(
+            conn,
+            "vaccines",
+            "goat_vaccines",
+            "vaccine_id",
+            keep_id,
+            duplicate_ids,
+        )
+    }
+
+    /// Merges duplicate diseases into `keep_id`, re-pointing their goat links.
+    ///
+    /// # Errors
+    /// See `merge_catalog_entries`.
+    pub fn merge_diseases(
+        conn: &Connection,
+        keep_id: i64,
+        duplicate_ids: &[i64],
+    ) -> Result<MergeSummary, AppError> {
+        merge_catalog_entries(
+            conn,
+            "diseases",
+            "goat_diseases",
+            "disease_id",
+            keep_id,
+            duplicate_ids,
+        )
+    }
+
+    /// Loads a single space by id.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such space, or a database error.
+    pub fn get_space(conn: &Connection, space_id: i64) -> Result<Space, AppError> {
+        trace!(space_id, "Loading space");
+        timed_query_row(
+            conn,
+            "SELECT id, name, type, capacity, grass_condition, health FROM spaces WHERE id = ?1",
+            [space_id],
+            row_to_space,
+        )
+        .optional()?
+        .ok_or_else(|| {
+            warn!(space_id, "Space not found");
+            AppError::NotFound(format!("No space found with id {}", space_id))
+        })
+    }
+
+    /// Moves a goat into a space, replacing any previous assignment and recording the move
+    /// in the goat's space history.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat or space does not exist, or a database error.
+    pub fn assign_goat_to_space(
+        conn: &Connection,
+        goat_id: i64,
+        space_id: i64,
+    ) -> Result<(), AppError> {
+        ensure_goat_exists(conn, goat_id)?;
+        Self::get_space(conn, space_id)?;
+        move_goat_to_space(conn, goat_id, space_id)?;
+        debug!(goat_id, space_id, "Goat assigned to space");
+        Ok(())
+    }
+
+    /// Lists the live goats currently assigned to a space, with relations, alongside the
+    /// space itself.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown space, or database and parsing errors.
+    pub fn goats_in_space(conn: &Connection, space_id: i64) -> Result<SpaceGoats, AppError> {
+        let space = Self::get_space(conn, space_id)?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM goats g JOIN space_goats sg ON sg.goat_id = g.id \
+             WHERE sg.space_id = ?1 AND g.deleted_at IS NULL ORDER BY g.id",
+            goat_columns("g")
+        ))?;
+        let rows: Vec<(i64, GoatParams)> = stmt
+            .query_map([space_id], |row| {
+                let params = row_to_goat(row)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+                Ok((row.get(0)?, params))
+            })?
+            .collect::<Result<_, _>>()?;
+
+        let mut goats = Vec::with_capacity(rows.len());
+        for (id, mut params) in rows {
+            params.vaccinations = fetch_vaccines(conn, id)?;
+            params.diseases = fetch_diseases(conn, id)?;
+            goats.push(Goat::new(Some(id), params));
+        }
+
+        debug!(space_id, count = goats.len(), "Goats in space loaded");
+        Ok(SpaceGoats {
+            occupancy: goats.len(),
+            capacity: space.capacity,
+            space,
+            goats,
+        })
+    }
+
+    /// Moves goats from one space to another. Nothing is written unless every goat is
+    /// currently in `from_space_id` and `to_space_id` has room for all of them. Should
+    /// run inside a transaction.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if either space does not exist,
+    /// `AppError::Validation` listing goats not in the source space,
+    /// `AppError::Conflict` if the destination lacks capacity, or a database error.
+    pub fn bulk_transfer_goats(
+        conn: &Connection,
+        from_space_id: i64,
+        to_space_id: i64,
+        goat_ids: &[i64],
+    ) -> Result<BulkTransferSummary, AppError> {
+        Self::get_space(conn, from_space_id)?;
+        let to_space = Self::get_space(conn, to_space_id)?;
+        let goat_ids: Vec<i64> = goat_ids
+            .iter()
+            .copied()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        let placeholders = vec!["?"; goat_ids.len()].join(", ");
+        let present: HashSet<i64> = timed_query_map(
+            conn,
+            &format!(
+                "SELECT sg.goat_id FROM space_goats sg JOIN goats g ON g.id = sg.goat_id \
+                 WHERE sg.space_id = ? AND g.deleted_at IS NULL AND sg.goat_id IN ({})",
+                placeholders
+            ),
+            params_from_iter(std::iter::once(from_space_id).chain(goat_ids.iter().copied())),
+            |r| r.get(0),
+        )?
+        .into_iter()
+        .collect();
+        let mut missing: Vec<i64> = goat_ids
+            .iter()
+            .copied()
+            .filter(|id| !present.contains(id))
+            .collect();
+        if !missing.is_empty() {
+            missing.sort_unstable();
+            warn!(from_space_id, ?missing, "Goats not in source space");
+            let ids: Vec<String> = missing.iter().map(i64::to_string).collect();
+            return Err(AppError::Validation(vec![FieldError::new(
+                "goat_ids",
+                &format!(
+                    "goats {} are not in space {}",
+                    ids.join(", "),
+                    from_space_id
+                ),
+            )]));
+        }
+
+        let occupancy: i64 = timed_query_row(
+            conn,
+            "SELECT COUNT(*) FROM space_goats sg JOIN goats g ON g.id = sg.goat_id \
+             WHERE sg.space_id = ?1 AND g.deleted_at IS NULL",
+            [to_space_id],
+            |r| r.get(0),
+        )?;
+        let moved = goat_ids.len();
+        if let Some(capacity) = to_space.capacity
+            && occupancy + moved as i64 > capacity
+        {
+            warn!(
+                to_space_id,
+                occupancy, capacity, moved, "Destination space is too full"
+            );
+            return Err(AppError::Conflict(format!(
+                "Space {} has room for {} more goats, cannot move {}",
+                to_space.name,
+                (capacity - occupancy).max(0),
+                moved
+            )));
+        }
+
+        for goat_id in &goat_ids {
+            move_goat_to_space(conn, *goat_id, to_space_id)?;
+        }
+
+        info!(
+            from_space_id,
+            to_space_id, moved, "Goats transferred between spaces"
+        );
+        Ok(BulkTransferSummary {
+            moved,
+            remaining_capacity: to_space
+                .capacity
+                .map(|capacity| capacity - occupancy - moved as i64),
+            to_space: to_space.name,
+        })
+    }
+
+    /// Installs a sensor in a space, replacing any previous space.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the sensor or space does not exist, or a database error.
+    pub fn assign_sensor_to_space(
+        conn: &Connection,
+        sensor_id: i64,
+        space_id: i64,
+    ) -> Result<(), AppError> {
+        Self::get_space(conn, space_id)?;
+        let updated = timed_execute(
+            conn,
+            "UPDATE sensors SET space_id = ?1 WHERE id = ?2",
+            [space_id, sensor_id],
+        )?;
+        if updated == 0 {
+            warn!(sensor_id, "Sensor not found");
+            return Err(AppError::NotFound(format!(
+                "No sensor found with id {}",
+                sensor_id
+            )));
+        }
+        debug!(sensor_id, space_id, "Sensor assigned to space");
+        Ok(())
+    }
+
+    /// Returns the latest reading per sensor type among the sensors installed in a space.
+    /// When several sensors share a type, the one read most recently wins; sensors that
+    /// have never reported are skipped.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown space, or a database error.
+    pub fn space_environment(
+        conn: &Connection,
+        space_id: i64,
+    ) -> Result<SpaceEnvironment, AppError> {
+        let space = Self::get_space(conn, space_id)?;
+        let readings = timed_query_map(
+            conn,
+            "SELECT sensor_type, id, last_reading, last_reading_time, status FROM ( \
+                 SELECT *, ROW_NUMBER() OVER ( \
+                     PARTITION BY sensor_type ORDER BY last_reading_time DESC, id DESC \
+                 ) AS rn \
+                 FROM sensors WHERE space_id = ?1 AND last_reading IS NOT NULL \
+             ) WHERE rn = 1 ORDER BY sensor_type",
+            [space_id],
+            |row| {
+                Ok(EnvironmentReading {
+                    sensor_type: row.get(0)?,
+                    sensor_id: row.get(1)?,
+                    reading: row.get(2)?,
+                    reading_time: row.get(3)?,
+                    status: row.get(4)?,
+                })
+            },
+        )?;
+
+        debug!(space_id, count = readings.len(), "Space environment loaded");
+        Ok(SpaceEnvironment { space, readings })
+    }
+
+    /// Records a show result for a goat and returns the new entry id.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat does not exist, or a database error.
+    pub fn insert_show_entry(conn: &Connection, entry: &ShowEntry) -> Result<i64, AppError> {
+        ensure_goat_exists(conn, entry.goat_id)?;
+        timed_execute(
+            conn,
+            "INSERT INTO show_entries \
+             (goat_id, show_name, show_date, location, class, placement, prize_amount, notes) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.goat_id,
+                entry.show_name,
+                entry.show_date,
+                entry.location,
+                entry.class,
+                entry.placement,
+                entry.prize_amount,
+                entry.notes,
+            ],
+        )?;
+        let entry_id = conn.last_insert_rowid();
+        debug!(entry_id, goat_id = entry.goat_id, "Show entry recorded");
+        Ok(entry_id)
+    }
+
+    /// Lists show entries sorted by date, optionally restricted to one goat and/or year.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn show_entries(
+        conn: &Connection,
+        goat_id: Option<i64>,
+        year: Option<i32>,
+    ) -> Result<Vec<ShowEntry>, AppError> {
+        trace!(?goat_id, ?year, "Loading show entries");
+        let mut stmt = conn.prepare(
+            "SELECT id, goat_id, show_name, show_date, location, class, placement, prize_amount, notes \
+             FROM show_entries \
+             WHERE (?1 IS NULL OR goat_id = ?1) \
+             AND (?2 IS NULL OR CAST(strftime('%Y', show_date) AS INTEGER) = ?2) \
+             ORDER BY show_date, id",
+        )?;
+        let entries = stmt
+            .query_map(params![goat_id, year], row_to_show_entry)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// Lists live goats, with relations, that have won at least one show class.
+    ///
+    /// # Errors
+    /// Returns database or enum parsing errors raised while mapping rows.
+    pub fn show_champions(conn: &Connection) -> Result<Vec<Goat>, AppError> {
+        trace!("Loading show champions");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM goats \
+             WHERE deleted_at IS NULL \
+             AND id IN (SELECT goat_id FROM show_entries WHERE placement = 1) \
+             ORDER BY id",
+            goat_columns("goats")
+        ))?;
+        let rows: Vec<(i64, GoatParams)> = stmt
+            .query_map([], |row| {
+                let params = row_to_goat(row)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+                Ok((row.get(0)?, params))
+            })?
+            .collect::<Result<_, _>>()?;
+
+        let mut champions = Vec::with_capacity(rows.len());
+        for (id, mut params) in rows {
+            params.vaccinations = fetch_vaccines(conn, id)?;
+            params.diseases = fetch_diseases(conn, id)?;
+            champions.push(Goat::new(Some(id), params));
+        }
+        debug!(count = champions.len(), "Show champions loaded");
+        Ok(champions)
+    }
+
+    /// Sums herd-wide money flows: purchase cost and current value of live goats, sales
+    /// revenue, vet costs, show prize money, and equipment value as of `as_of`.
+    ///
+    /// Goat totals are summed exactly in paise; rows written without minor units (e.g. by
+    /// `generate_sample_data`) fall back to their legacy rupee columns.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the aggregation.
+    pub fn financial_stats(
+        conn: &Connection,
+        as_of: NaiveDate,
+    ) -> Result<FinancialStats, AppError> {
+        trace!("Computing financial stats");
+        let total_equipment_value = Self::list_equipment(conn)?
+            .iter()
+            .filter_map(|item| equipment_depreciation(item, as_of))
+            .map(|info| info.current_value)
+            .sum();
+        let stats = timed_query_row(
+            conn,
+            "SELECT \
+                (SELECT COALESCE(SUM(COALESCE(cost_minor, CAST(ROUND(cost * 100) AS INTEGER))), 0) \
+                 FROM goats WHERE deleted_at IS NULL), \
+                (SELECT COALESCE(SUM(COALESCE(current_price_minor, CAST(ROUND(current_price * 100) AS INTEGER))), 0) \
+                 FROM goats WHERE deleted_at IS NULL AND is_sold = 0), \
+                (SELECT COALESCE(SUM(price), 0) FROM sales), \
+                (SELECT COALESCE(SUM(cost), 0) FROM vet_visits), \
+                (SELECT COALESCE(SUM(prize_amount), 0) FROM show_entries)",
+            [],
+            |row| {
+                Ok(FinancialStats {
+                    total_purchase_cost: Money {
+                        amount: row.get(0)?,
+                        currency: Currency::Inr,
+                    }
+                    .to_major(),
+                    total_herd_value: Money {
+                        amount: row.get(1)?,
+                        currency: Currency::Inr,
+                    }
+                    .to_major(),
+                    total_sales_revenue: row.get(2)?,
+                    total_vet_costs: row.get(3)?,
+                    total_prize_money: row.get(4)?,
+                    total_equipment_value,
+                })
+            },
+        )?;
+        debug!(?stats, "Financial stats computed");
+        Ok(stats)
+    }
+
+    /// Totals what a live goat has cost and earned so far: its purchase cost and current
+    /// price, `Feed` and `Vet` expenses plus vet visit costs, and `Milk` production
+    /// revenue. Each total is 0 when nothing is recorded.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat does not exist, or a database error.
+    pub fn compute_profit_analysis(
+        conn: &Connection,
+        goat_id: i64,
+    ) -> Result<ProfitAnalysis, AppError> {
+        ensure_goat_exists(conn, goat_id)?;
+        Ok(timed_query_row(
+            conn,
+            &format!("{} WHERE g.id = ?1", PROFIT_ANALYSIS_SELECT),
+            [goat_id],
+            row_to_profit_analysis,
+        )?)
+    }
+
+    /// Profit analysis of every live goat that is not deceased, best `net_position` first.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn herd_profitability(conn: &Connection) -> Result<Vec<ProfitAnalysis>, AppError> {
+        let mut analyses = query_all(
+            conn,
+            &format!(
+                "{} WHERE g.deleted_at IS NULL AND g.lifecycle_state != 'Deceased'",
+                PROFIT_ANALYSIS_SELECT
+            ),
+            row_to_profit_analysis,
+        )?;
+        analyses.sort_by(|a, b| {
+            b.net_position
+                .total_cmp(&a.net_position)
+                .then(a.goat_id.cmp(&b.goat_id))
+        });
+        debug!(count = analyses.len(), "Herd profitability computed");
+        Ok(analyses)
+    }
+
+    /// Records a FAMACHA score and returns the new row id.
+    ///
+    /// `scored_at` must already be normalised to SQLite's timestamp form; `None` means now.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat does not exist, or a database error.
+    pub fn insert_famacha_score(conn: &Connection, score: &FamachaScore) -> Result<i64, AppError> {
+        ensure_goat_exists(conn, score.goat_id)?;
+        timed_execute(
+            conn,
+            "INSERT INTO famacha_scores (goat_id, score, scored_at, scored_by) \
+             VALUES (?1, ?2, COALESCE(?3, CURRENT_TIMESTAMP), ?4)",
+            params![score.goat_id, score.score, score.scored_at, score.scored_by],
+        )?;
+        let score_id = conn.last_insert_rowid();
+        debug!(
+            score_id,
+            goat_id = score.goat_id,
+            score = score.score,
+            "FAMACHA score recorded"
+        );
+        Ok(score_id)
+    }
+
+    /// Loads a single FAMACHA score by id.
+    ///
+    /// # Errors
+    /// Returns a database error if the row cannot be read.
+    pub fn get_famacha_score(conn: &Connection, score_id: i64) -> Result<FamachaScore, AppError> {
+        Ok(timed_query_row(
+            conn,
+            "SELECT id, goat_id, score, scored_at, scored_by FROM famacha_scores WHERE id = ?1",
+            [score_id],
+            row_to_famacha_score,
+        )?)
+    }
+
+    /// Lists a goat's FAMACHA scores, most recent first.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn famacha_history(conn: &Connection, goat_id: i64) -> Result<Vec<FamachaScore>, AppError> {
+        trace!(goat_id, "Loading FAMACHA history");
+        Ok(timed_query_map(
+            conn,
+            "SELECT id, goat_id, score, scored_at, scored_by FROM famacha_scores \
+             WHERE goat_id = ?1 ORDER BY scored_at DESC, id DESC",
+            [goat_id],
+            row_to_famacha_score,
+        )?)
+    }
+
+    /// Opens a pending deworming record for the goat unless one is already pending.
+    ///
+    /// Returns the id of the new record, or `None` if a pending one already existed.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the lookup or insert.
+    pub fn suggest_deworming(
+        conn: &Connection,
+        goat_id: i64,
+        reason: &str,
+    ) -> Result<Option<i64>, AppError> {
+        let pending: bool = timed_query_row(
+            conn,
+            "SELECT EXISTS(SELECT 1 FROM deworming_records WHERE goat_id = ?1 AND status = 'pending')",
+            [goat_id],
+            |r| r.get(0),
+        )?;
+        if pending {
+            trace!(goat_id, "Deworming already pending");
+            return Ok(None);
+        }
+        timed_execute(
+            conn,
+            "INSERT INTO deworming_records (goat_id, status, reason) VALUES (?1, 'pending', ?2)",
+            params![goat_id, reason],
+        )?;
+        let record_id = conn.last_insert_rowid();
+        info!(goat_id, record_id, "Suggested deworming");
+        Ok(Some(record_id))
+    }
+
+    /// Lists live goats whose most recent FAMACHA score is at or above
+    /// `FAMACHA_ACTION_THRESHOLD`, worst first.
+    ///
+    /// The latest score per goat is picked by a correlated subquery, so an old high score
+    /// followed by a healthy one does not flag the goat.
+    ///
+    /// # Errors
+    /// Returns database or enum parsing errors raised while mapping rows.
+    pub fn famacha_action_needed(conn: &Connection) -> Result<Vec<FamachaAlert>, AppError> {
+        trace!("Loading goats needing deworming");
+        let rows: Vec<(i64, GoatParams, i64, String)> = timed_query_map(
+            conn,
+            &format!(
+                "SELECT {}, f.score, f.scored_at FROM goats g \
+                 JOIN famacha_scores f ON f.goat_id = g.id \
+                 WHERE g.deleted_at IS NULL \
+                 AND f.id = (SELECT f2.id FROM famacha_scores f2 WHERE f2.goat_id = g.id \
+                             ORDER BY f2.scored_at DESC, f2.id DESC LIMIT 1) \
+                 AND f.score >= ?1 \
+                 ORDER BY f.score DESC, g.id",
+                goat_columns("g")
+            ),
+            [FAMACHA_ACTION_THRESHOLD],
+            |row| {
+                let params = row_to_goat(row)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+                Ok((
+                    row.get("id")?,
+                    params,
+                    row.get("score")?,
+                    row.get("scored_at")?,
+                ))
+            },
+        )?;
+
+        let mut alerts = Vec::with_capacity(rows.len());
+        for (id, mut params, latest_score, scored_at) in rows {
+            params.vaccinations = fetch_vaccines(conn, id)?;
+            params.diseases = fetch_diseases(conn, id)?;
+            alerts.push(FamachaAlert {
+                goat: Goat::new(Some(id), params),
+                latest_score,
+                scored_at,
+            });
+        }
+        debug!(count = alerts.len(), "FAMACHA action list built");
+        Ok(alerts)
+    }
+
+    /// Creates a cohort and returns it as stored.
+    ///
+    /// # Errors
+    /// Returns `AppError::Conflict` if a cohort with that name exists, or a database error.
+    pub fn create_cohort(conn: &Connection, cohort: &Cohort) -> Result<Cohort, AppError> {
+        if find_id(conn, "cohorts", "name = ?1", [&cohort.name])?.is_some() {
+            warn!(name = cohort.name, "Duplicate cohort name");
+            return Err(AppError::Conflict(format!(
+                "A cohort named {} already exists",
+                cohort.name
+            )));
+        }
+        timed_execute(
+            conn,
+            "INSERT INTO cohorts (name, description) VALUES (?1, ?2)",
+            params![cohort.name, cohort.description],
+        )?;
+        let cohort_id = conn.last_insert_rowid();
+        debug!(cohort_id, name = cohort.name, "Cohort created");
+        Self::get_cohort(conn, cohort_id)
+    }
+
+    /// Loads a cohort by id.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such cohort, or a database error.
+    pub fn get_cohort(conn: &Connection, cohort_id: i64) -> Result<Cohort, AppError> {
+        timed_query_row(
+            conn,
+            "SELECT id, name, description, created_at FROM cohorts WHERE id = ?1",
+            [cohort_id],
+            row_to_cohort,
+        )
+        .optional()?
+        .ok_or_else(|| {
+            warn!(cohort_id, "Cohort not found");
+            AppError::NotFound(format!("No cohort found with id {}", cohort_id))
+        })
+    }
+
+    /// Lists all cohorts by name.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn list_cohorts(conn: &Connection) -> Result<Vec<Cohort>, AppError> {
+        query_all(
+            conn,
+            "SELECT id, name, description, created_at FROM cohorts ORDER BY name",
+            row_to_cohort,
+        )
+    }
+
+    /// Adds a goat to a cohort; adding an existing member is a no-op.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the cohort or goat does not exist, or a database error.
+    pub fn add_goat_to_cohort(
+        conn: &Connection,
+        cohort_id: i64,
+        goat_id: i64,
+    ) -> Result<(), AppError> {
+        Self::get_cohort(conn, cohort_id)?;
+        ensure_goat_exists(conn, goat_id)?;
+        let added = timed_execute(
+            conn,
+            "INSERT OR IGNORE INTO cohort_goats (cohort_id, goat_id) VALUES (?1, ?2)",
+            [cohort_id, goat_id],
+        )?;
+        debug!(cohort_id, goat_id, added, "Goat added to cohort");
+        Ok(())
+    }
+
+    /// Removes a goat from a cohort.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat is not a member of the cohort.
+    pub fn remove_goat_from_cohort(
+        conn: &Connection,
+        cohort_id: i64,
+        goat_id: i64,
+    ) -> Result<(), AppError> {
+        let removed = timed_execute(
+            conn,
+            "DELETE FROM cohort_goats WHERE cohort_id = ?1 AND goat_id = ?2",
+            [cohort_id, goat_id],
+        )?;
+        if removed == 0 {
+            warn!(cohort_id, goat_id, "Goat is not in cohort");
+            return Err(AppError::NotFound(format!(
+                "Goat {} is not in cohort {}",
+                goat_id, cohort_id
+            )));
+        }
+        debug!(cohort_id, goat_id, "Goat removed from cohort");
+        Ok(())
+    }
+
+    /// Lists the live goats in a cohort, with relations.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown cohort, or database and parsing errors.
+    pub fn cohort_goats(conn: &Connection, cohort_id: i64) -> Result<Vec<Goat>, AppError> {
+        Self::get_cohort(conn, cohort_id)?;
+        let ids: Vec<i64> = timed_query_map(
+            conn,
+            "SELECT g.id FROM goats g JOIN cohort_goats cg ON cg.goat_id = g.id \
+             WHERE cg.cohort_id = ?1 AND g.deleted_at IS NULL ORDER BY g.id",
+            [cohort_id],
+            |r| r.get(0),
+        )?;
+        ids.into_iter()
+            .map(|id| load_goat_details(conn, id))
+            .collect()
+    }
+
+    /// Computes average weight, health status distribution and vaccination coverage over
+    /// the live goats in a cohort, each with its own aggregate query.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown cohort, or database errors.
+    pub fn fetch_cohort_stats(conn: &Connection, cohort_id: i64) -> Result<CohortStats, AppError> {
+        Self::get_cohort(conn, cohort_id)?;
+        trace!(cohort_id, "Computing cohort stats");
+        const MEMBERS: &str = "SELECT g.id FROM goats g JOIN cohort_goats cg ON cg.goat_id = g.id \
+                               WHERE cg.cohort_id = ?1 AND g.deleted_at IS NULL";
+
+        let (goat_count, average_weight): (i32, Option<f64>) = timed_query_row(
+            conn,
+            &format!(
+                "SELECT COUNT(*), AVG(weight) FROM goats WHERE id IN ({})",
+                MEMBERS
+            ),
+            [cohort_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+
+        let health_status_distribution = timed_query_map(
+            conn,
+            &format!(
+                "SELECT COALESCE(health_status, 'unknown'), COUNT(*) FROM goats \
+                 WHERE id IN ({}) GROUP BY 1 ORDER BY 1",
+                MEMBERS
+            ),
+            [cohort_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?
+        .into_iter()
+        .collect();
+
+        let vaccination_coverage = timed_query_map(
+            conn,
+            &format!(
+                "SELECT v.name, COUNT(DISTINCT gv.goat_id) FROM vaccines v \
+                 JOIN goat_vaccines gv ON gv.vaccine_id = v.id \
+                 WHERE gv.goat_id IN ({}) GROUP BY v.id ORDER BY v.name",
+                MEMBERS
+            ),
+            [cohort_id],
+            |row| {
+                let vaccinated_count: i32 = row.get(1)?;
+                Ok(VaccineCoverage {
+                    vaccine: row.get(0)?,
+                    vaccinated_count,
+                    total_goats: goat_count,
+                    coverage_pct: vaccinated_count as f64 / goat_count as f64 * 100.0,
+                })
+            },
+        )?;
+
+        let stats = CohortStats {
+            cohort_id,
+            goat_count,
+            average_weight,
+            health_status_distribution,
+            vaccination_coverage,
+        };
+        debug!(?stats, "Cohort stats computed");
+        Ok(stats)
+    }
+
+    /// Reclassifies the given goats as wethers. Only live goats currently recorded as
+    /// Male are changed; their breeding date is cleared and the prior value audited.
+    /// Should run inside a transaction so the batch applies atomically.
+    ///
+    /// # Errors
+    /// Returns a database error if any statement fails.
+    pub fn reclassify_wethers(
+        conn: &Connection,
+        goat_ids: &[i64],
+    ) -> Result<ReclassifySummary, AppError> {
+        trace!(count = goat_ids.len(), "Reclassifying goats as wethers");
+        let mut summary = ReclassifySummary::default();
+        for &goat_id in goat_ids {
+            let last_bred: Option<Option<String>> = conn
+                .query_row(
+                    "SELECT last_bred FROM goats \
+                     WHERE id = ?1 AND gender = 'Male' AND deleted_at IS NULL",
+                    [goat_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let Some(last_bred) = last_bred else {
+                debug!(goat_id, "Skipping goat that is not a live Male");
+                summary.skipped.push(goat_id);
+                continue;
+            };
+
+            timed_execute(
+                conn,
+                "UPDATE goats SET gender = 'Wether', last_bred = NULL, is_pregnant = 0, \
+                 version = version + 1, updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                [goat_id],
+            )?;
+            let details = match last_bred {
+                Some(date) => format!("Male -> Wether; cleared last_bred {}", date),
+                None => "Male -> Wether".to_string(),
+            };
+            record_audit(conn, "goat", goat_id, "reclassify", &details)?;
+            summary.reclassified.push(goat_id);
+        }
+
+        info!(
+            reclassified = summary.reclassified.len(),
+            skipped = summary.skipped.len(),
+            "Wether reclassification finished"
+        );
+        Ok(summary)
+    }
+
+    /// Lists every known breed name: the built-in variants first, then custom breeds and
+    /// any `Other` breeds used by live goats, alphabetically and without case-insensitive
+    /// duplicates.
+    ///
+    /// # Errors
+    /// Returns a database error if either table cannot be read.
+    pub fn list_breeds(conn: &Connection) -> Result<Vec<String>, AppError> {
+        let extra: Vec<String> = query_all(
+            conn,
+            "SELECT name FROM custom_breeds \
+             UNION SELECT DISTINCT breed FROM goats WHERE deleted_at IS NULL \
+             ORDER BY 1 COLLATE NOCASE",
+            |row| row.get(0),
+        )?;
+
+        let mut seen: HashSet<String> = BUILTIN_BREEDS.iter().map(|b| b.to_lowercase()).collect();
+        let mut breeds: Vec<String> = BUILTIN_BREEDS.iter().map(|b| b.to_string()).collect();
+        for name in extra {
+            if seen.insert(name.to_lowercase()) {
+                breeds.push(name);
+            }
+        }
+        debug!(count = breeds.len(), "Breed list built");
+        Ok(breeds)
+    }
+
+    /// Registers a custom breed, returning the stored entry and whether it was newly
+    /// created. A name matching an existing custom breed ignoring case returns that entry.
+    ///
+    /// # Errors
+    /// Returns `AppError::InvalidInput` for an empty name, `AppError::Conflict` if the name
+    /// is a built-in breed, or a database error.
+    pub fn register_custom_breed(
+        conn: &Connection,
+        name: &str,
+    ) -> Result<(CustomBreed, bool), AppError> {
+        let name = normalize_name(name);
+        if name.is_empty() {
+            return Err(AppError::InvalidInput(
+                "Breed name must not be empty".to_string(),
+            ));
+        }
+        if let Some(builtin) = BUILTIN_BREEDS
+            .iter()
+            .find(|b| b.eq_ignore_ascii_case(&name))
+        {
+            warn!(name, "Custom breed shadows a built-in breed");
+            return Err(AppError::Conflict(format!(
+                "{} is a built-in breed",
+                builtin
+            )));
+        }
+
+        let select =
+            "SELECT id, name, created_at FROM custom_breeds WHERE name = ?1 COLLATE NOCASE";
+        if let Some(existing) = conn
+            .query_row(select, [&name], row_to_custom_breed)
+            .optional()?
+        {
+            debug!(name = existing.name, "Custom breed already registered");
+            return Ok((existing, false));
+        }
+
+        timed_execute(
+            conn,
+            "INSERT INTO custom_breeds (name) VALUES (?1)",
+            [&name],
+        )?;
+        let created = timed_query_row(conn, select, [&name], row_to_custom_breed)?;
+        info!(name = created.name, "Custom breed registered");
+        Ok((created, true))
+    }
+
+    /// Adds a medicine to the inventory and returns it as stored.
+    ///
+    /// # Errors
+    /// Returns a database error if the insert fails.
+    pub fn insert_medicine(
+        conn: &Connection,
+        item: &MedicineItem,
+    ) -> Result<MedicineItem, AppError> {
+        timed_execute(
+            conn,
+            "INSERT INTO medicine_inventory (name, category, stock_units, unit, expiry_date, cost_per_unit) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                item.name.trim(),
+                item.category,
+                item.stock_units,
+                item.unit.trim(),
+                item.expiry_date,
+                item.cost_per_unit
+            ],
+        )?;
+        let medicine_id = conn.last_insert_rowid();
+        info!(medicine_id, name = item.name, "Medicine added to inventory");
+        Self::get_medicine(conn, medicine_id)
+    }
+
+    /// Loads a medicine by id.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such medicine, or a database error.
+    pub fn get_medicine(conn: &Connection, medicine_id: i64) -> Result<MedicineItem, AppError> {
+        timed_query_row(
+            conn,
+            &format!(
+                "SELECT {} FROM medicine_inventory WHERE id = ?1",
+                MEDICINE_COLUMNS
+            ),
+            [medicine_id],
+            row_to_medicine_item,
+        )
+        .optional()?
+        .ok_or_else(|| {
+            warn!(medicine_id, "Medicine not found");
+            AppError::NotFound(format!("No medicine found with id {}", medicine_id))
+        })
+    }
+
+    /// Lists the whole medicine inventory by name.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn list_medicines(conn: &Connection) -> Result<Vec<MedicineItem>, AppError> {
+        query_all(
+            conn,
+            &format!(
+                "SELECT {} FROM medicine_inventory ORDER BY name COLLATE NOCASE, id",
+                MEDICINE_COLUMNS
+            ),
+            row_to_medicine_item,
+        )
+    }
+
+    /// Deducts `amount` from a medicine's stock and logs the dispensing against the goat.
+    /// Should run inside a transaction so the deduction and the log entry land together.
+    ///
+    /// # Errors
+    /// Returns `AppError::InvalidInput` for a non-positive amount or empty reason,
+    /// `AppError::NotFound` for an unknown medicine or goat, `AppError::Conflict` if the
+    /// amount exceeds the current stock, or a database error.
+    pub fn dispense_medicine(
+        conn: &Connection,
+        medicine_id: i64,
+        goat_id: i64,
+        amount: f64,
+        reason: &str,
+    ) -> Result<MedicineItem, AppError> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(AppError::InvalidInput(
+                "Dispensed amount must be a positive number".to_string(),
+            ));
+        }
+        if reason.trim().is_empty() {
+            return Err(AppError::InvalidInput(
+                "Dispensing reason must not be empty".to_string(),
+            ));
+        }
+        let item = Self::get_medicine(conn, medicine_id)?;
+        ensure_goat_exists(conn, goat_id)?;
+
+        // The stock check lives in the UPDATE itself so concurrent dispenses cannot overdraw.
+        let updated = timed_execute(
+            conn,
+            "UPDATE medicine_inventory SET stock_units = stock_units - ?2 \
+             WHERE id = ?1 AND stock_units >= ?2",
+            params![medicine_id, amount],
+        )?;
+        if updated == 0 {
+            warn!(
+                medicine_id,
+                amount,
+                stock = item.stock_units,
+                "Insufficient medicine stock"
+            );
+            return Err(AppError::Conflict(format!(
+                "Cannot dispense {} {} of {}: only {} in stock",
+                amount, item.unit, item.name, item.stock_units
+            )));
+        }
+        timed_execute(
+            conn,
+            "INSERT INTO medicine_dispensing (medicine_id, goat_id, amount, reason) \
+             VALUES (?1, ?2, ?3, ?4)",
+            params![medicine_id, goat_id, amount, reason.trim()],
+        )?;
+
+        info!(medicine_id, goat_id, amount, "Medicine dispensed");
+        Self::get_medicine(conn, medicine_id)
+    }
+
+    /// Lists medicines still in stock whose expiry date is on or before `cutoff`,
+    /// soonest first. Already-expired stock is included.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn expiring_medicines(
+        conn: &Connection,
+        cutoff: NaiveDate,
+    ) -> Result<Vec<MedicineItem>, AppError> {
+        Ok(timed_query_map(
+            conn,
+            &format!(
+                "SELECT {} FROM medicine_inventory \
+                 WHERE expiry_date IS NOT NULL AND expiry_date <= ?1 AND stock_units > 0 \
+                 ORDER BY expiry_date, name COLLATE NOCASE",
+                MEDICINE_COLUMNS
+            ),
+            [cutoff.to_string()],
+            row_to_medicine_item,
+        )?)
+    }
+
+    /// Lists medicines whose stock is below `threshold`, lowest first.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn low_stock_medicines(
+        conn: &Connection,
+        threshold: f64,
+    ) -> Result<Vec<MedicineItem>, AppError> {
+        Ok(timed_query_map(
+            conn,
+            &format!(
+                "SELECT {} FROM medicine_inventory WHERE stock_units < ?1 \
+                 ORDER BY stock_units, name COLLATE NOCASE",
+                MEDICINE_COLUMNS
+            ),
+            [threshold],
+            row_to_medicine_item,
+        )?)
+    }
+
+    /// Records a behavior observation and returns it as stored.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat does not exist, or a database error.
+    pub fn insert_behavior_observation(
+        conn: &Connection,
+        observation: &BehaviorObservation,
+    ) -> Result<BehaviorObservation, AppError> {
+        ensure_goat_exists(conn, observation.goat_id)?;
+        timed_execute(
+            conn,
+            "INSERT INTO behavior_observations (goat_id, behavior, intensity, recorded_at, observed_by, notes) \
+             VALUES (?1, ?2, ?3, COALESCE(?4, CURRENT_TIMESTAMP), ?5, ?6)",
+            params![
+                observation.goat_id,
+                observation.behavior,
+                observation.intensity,
+                observation.recorded_at,
+                observation.observed_by,
+                observation.notes
+            ],
+        )?;
+        let observation_id = conn.last_insert_rowid();
+        debug!(
+            observation_id,
+            goat_id = observation.goat_id,
+            behavior = observation.behavior,
+            "Behavior observation recorded"
+        );
+        Ok(timed_query_row(
+            conn,
+            &format!(
+                "SELECT {} FROM behavior_observations WHERE id = ?1",
+                BEHAVIOR_COLUMNS
+            ),
+            [observation_id],
+            row_to_behavior_observation,
+        )?)
+    }
+
+    /// Lists a goat's behavior observations, most recent first.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn behavior_history(
+        conn: &Connection,
+        goat_id: i64,
+    ) -> Result<Vec<BehaviorObservation>, AppError> {
+        trace!(goat_id, "Loading behavior history");
+        Ok(timed_query_map(
+            conn,
+            &format!(
+                "SELECT {} FROM behavior_observations \
+                 WHERE goat_id = ?1 ORDER BY recorded_at DESC, id DESC",
+                BEHAVIOR_COLUMNS
+            ),
+            [goat_id],
+            row_to_behavior_observation,
+        )?)
+    }
+
+    /// Lists live goats with a `CONCERNING_BEHAVIORS` observation recorded in the last
+    /// 24 hours, each with its most recent such observation, newest first.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the queries.
+    pub fn behavior_concerns(conn: &Connection) -> Result<Vec<BehaviorConcern>, AppError> {
+        trace!("Loading goats with concerning behavior");
+        let placeholders = vec!["?"; CONCERNING_BEHAVIORS.len()].join(", ");
+        let sql = format!(
+            "SELECT {1}, b.behavior, b.intensity, b.recorded_at FROM goats g \
+             JOIN behavior_observations b ON b.goat_id = g.id \
+             WHERE g.deleted_at IS NULL \
+             AND b.id = (SELECT b2.id FROM behavior_observations b2 \
+                         WHERE b2.goat_id = g.id AND b2.behavior IN ({0}) \
+                         AND b2.recorded_at >= datetime('now', '-1 day') \
+                         ORDER BY b2.recorded_at DESC, b2.id DESC LIMIT 1) \
+             ORDER BY b.recorded_at DESC, g.id",
+            placeholders,
+            goat_columns("g")
+        );
+        let rows: Vec<(i64, GoatParams, String, String, String)> = timed_query_map(
+            conn,
+            &sql,
+            rusqlite::params_from_iter(CONCERNING_BEHAVIORS),
+            |row| {
+                let params = row_to_goat(row)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+                Ok((
+                    row.get("id")?,
+                    params,
+                    row.get("behavior")?,
+                    row.get("intensity")?,
+                    row.get("recorded_at")?,
+                ))
+            },
+        )?;
+
+        let mut concerns = Vec::with_capacity(rows.len());
+        for (id, mut params, behavior, intensity, recorded_at) in rows {
+            params.vaccinations = fetch_vaccines(conn, id)?;
+            params.diseases = fetch_diseases(conn, id)?;
+            concerns.push(BehaviorConcern {
+                goat: Goat::new(Some(id), params),
+                behavior,
+                intensity,
+                recorded_at,
+            });
+        }
+        debug!(count = concerns.len(), "Behavior concern list built");
+        Ok(concerns)
+    }
+
+    /// Lists vaccinations of live goats that were due before `today`, most overdue first.
+    ///
+    /// Only the latest `vaccination_schedule` row per goat and vaccine counts, so a later
+    /// dose with a future due date clears an older overdue entry.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn overdue_vaccinations(
+        conn: &Connection,
+        today: NaiveDate,
+    ) -> Result<Vec<OverdueVaccination>, AppError> {
+        trace!(%today, "Loading overdue vaccinations");
+        Ok(timed_query_map(
+            conn,
+            "SELECT g.id, g.name, v.id, v.name, s.next_due_on, \
+                    CAST(julianday(?1) - julianday(s.next_due_on) AS INTEGER) \
+             FROM vaccination_schedule s \
+             JOIN goats g ON g.id = s.goat_id \
+             JOIN vaccines v ON v.id = s.vaccine_id \
+             WHERE g.deleted_at IS NULL \
+             AND s.id = (SELECT s2.id FROM vaccination_schedule s2 \
+                         WHERE s2.goat_id = s.goat_id AND s2.vaccine_id = s.vaccine_id \
+                         ORDER BY s2.next_due_on DESC, s2.id DESC LIMIT 1) \
+             AND s.next_due_on < ?1 \
+             ORDER BY s.next_due_on, g.id, v.id",
+            [today.to_string()],
+            |row| {
+                Ok(OverdueVaccination {
+                    goat_id: row.get(0)?,
+                    goat_name: row.get(1)?,
+                    vaccine_id: row.get(2)?,
+                    vaccine: row.get(3)?,
+                    due_on: row.get(4)?,
+                    days_overdue: row.get(5)?,
+                })
+            },
+        )?)
+    }
+
+    /// Assigns a task to a worker and returns it as stored.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the worker does not exist, or a database error.
+    pub fn insert_worker_task(
+        conn: &Connection,
+        task: &WorkerTask,
+    ) -> Result<WorkerTask, AppError> {
+        ensure_worker_exists(conn, task.worker_id)?;
+        timed_execute(
+            conn,
+            "INSERT INTO worker_tasks (worker_id, task, assigned_at, due_at, priority, description) \
+             VALUES (?1, ?2, COALESCE(?3, CURRENT_TIMESTAMP), ?4, ?5, ?6)",
+            params![
+                task.worker_id,
+                task.task.trim(),
+                task.assigned_at,
+                task.due_at,
+                task.priority,
+                task.description
+            ],
+        )?;
+        let task_id = conn.last_insert_rowid();
+        debug!(task_id, worker_id = task.worker_id, "Worker task assigned");
+        Self::get_worker_task(conn, task.worker_id, task_id)
+    }
+
+    /// Loads one of a worker's tasks.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the task does not exist or belongs to another
+    /// worker, or a database error.
+    pub fn get_worker_task(
+        conn: &Connection,
+        worker_id: i64,
+        task_id: i64,
+    ) -> Result<WorkerTask, AppError> {
+        timed_query_row(
+            conn,
+            &format!(
+                "SELECT {} FROM worker_tasks WHERE id = ?1 AND worker_id = ?2",
+                WORKER_TASK_COLUMNS
+            ),
+            [task_id, worker_id],
+            row_to_worker_task,
+        )
+        .optional()?
+        .ok_or_else(|| {
+            warn!(worker_id, task_id, "Worker task not found");
+            AppError::NotFound(format!(
+                "No task {} found for worker {}",
+                task_id, worker_id
+            ))
+        })
+    }
+
+    /// Lists a worker's tasks by due date (undated last), optionally only open
+    /// (`completed = Some(false)`) or only completed ones.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn worker_tasks(
+        conn: &Connection,
+        worker_id: i64,
+        completed: Option<bool>,
+    ) -> Result<Vec<WorkerTask>, AppError> {
+        trace!(worker_id, ?completed, "Loading worker tasks");
+        Ok(timed_query_map(
+            conn,
+            &format!(
+                "SELECT {} FROM worker_tasks \
+                 WHERE worker_id = ?1 AND (?2 IS NULL OR (completed_at IS NOT NULL) = ?2) \
+                 ORDER BY due_at IS NULL, due_at, id",
+                WORKER_TASK_COLUMNS
+            ),
+            params![worker_id, completed],
+            row_to_worker_task,
+        )?)
+    }
+
+    /// Marks a worker's task completed now and returns it.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown task, `AppError::Conflict` if it is
+    /// already completed, or a database error.
+    pub fn complete_worker_task(
+        conn: &Connection,
+        worker_id: i64,
+        task_id: i64,
+    ) -> Result<WorkerTask, AppError> {
+        let task = Self::get_worker_task(conn, worker_id, task_id)?;
+        if let Some(completed_at) = task.completed_at {
+            return Err(AppError::Conflict(format!(
+                "Task {} was already completed at {}",
+                task_id, completed_at
+            )));
+        }
+        timed_execute(
+            conn,
+            "UPDATE worker_tasks SET completed_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            [task_id],
+        )?;
+        info!(worker_id, task_id, "Worker task completed");
+        Self::get_worker_task(conn, worker_id, task_id)
+    }
+
+    /// Computes a worker's task metrics over tasks assigned in the last 30 days.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the worker does not exist, or a database error.
+    pub fn worker_performance(
+        conn: &Connection,
+        worker_id: i64,
+    ) -> Result<WorkerPerformance, AppError> {
+        ensure_worker_exists(conn, worker_id)?;
+        let (total_tasks, completed_tasks, overdue_tasks, avg_completion_hours): (
+            i32,
+            i32,
+            i32,
+            Option<f64>,
+        ) = timed_query_row(
+            conn,
+            "SELECT COUNT(*), \
+                    COUNT(completed_at), \
+                    COUNT(CASE WHEN completed_at IS NULL AND due_at < CURRENT_TIMESTAMP THEN 1 END), \
+                    AVG((julianday(completed_at) - julianday(assigned_at)) * 24) \
+             FROM worker_tasks \
+             WHERE worker_id = ?1 AND assigned_at >= datetime('now', '-30 days')",
+            [worker_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+
+        let task_completion_pct = if total_tasks == 0 {
+            0.0
+        } else {
+            completed_tasks as f64 / total_tasks as f64 * 100.0
+        };
+        let performance = WorkerPerformance {
+            worker_id,
+            total_tasks,
+            completed_tasks,
+            task_completion_pct,
+            overdue_tasks,
+            avg_completion_hours: avg_completion_hours.unwrap_or(0.0),
+        };
+        debug!(?performance, "Worker performance computed");
+        Ok(performance)
+    }
+
+    /// Returns the `limit` most recent changes to goats, workers and equipment, newest
+    /// first, merging trigger-recorded changes with operator actions from the audit log.
+    ///
+    /// Change rows carry the name captured at the time, so deleted entities keep theirs;
+    /// audit rows fall back to the entity's current name.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn recent_activity(conn: &Connection, limit: u32) -> Result<Vec<ActivityEntry>, AppError> {
+        trace!(limit, "Loading recent activity");
+        Ok(timed_query_map(
+            conn,
+            "SELECT entity_type, entity_id, name, action, timestamp FROM ( \
+                 SELECT entity AS entity_type, entity_id, name, action, \
+                        created_at AS timestamp, id AS seq, 0 AS source \
+                 FROM change_log \
+                 WHERE entity IN ('goat', 'worker', 'equipment') \
+                 UNION ALL \
+                 SELECT a.entity, a.entity_id, \
+                        CASE a.entity \
+                            WHEN 'goat' THEN (SELECT name FROM goats WHERE id = a.entity_id) \
+                            WHEN 'worker' THEN (SELECT name FROM workers WHERE id = a.entity_id) \
+                            WHEN 'equipment' THEN (SELECT name FROM equipment WHERE id = a.entity_id) \
+                        END, \
+                        a.action, a.created_at, a.id, 1 \
+                 FROM audit_log a \
+                 WHERE a.entity IN ('goat', 'worker', 'equipment') \
+             ) \
+             ORDER BY timestamp DESC, source DESC, seq DESC \
+             LIMIT ?1",
+            [limit],
+            |row| {
+                Ok(ActivityEntry {
+                    entity_type: row.get(0)?,
+                    entity_id: row.get(1)?,
+                    name: row.get(2)?,
+                    action: row.get(3)?,
+                    timestamp: row.get(4)?,
+                })
+            },
+        )?)
+    }
+
+    /// Lists all equipment by id.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn list_equipment(conn: &Connection) -> Result<Vec<Equipment>, AppError> {
+        Ok(query_all(
+            conn,
+            &format!("SELECT {} FROM equipment ORDER BY id", EQUIPMENT_COLUMNS),
+            row_to_equipment,
+        )?)
+    }
+
+    /// Loads one piece of equipment.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if it does not exist, or a database error.
+    pub fn get_equipment(conn: &Connection, equipment_id: i64) -> Result<Equipment, AppError> {
+        timed_query_row(
+            conn,
+            &format!("SELECT {} FROM equipment WHERE id = ?1", EQUIPMENT_COLUMNS),
+            [equipment_id],
+            row_to_equipment,
+        )
+        .optional()?
+        .ok_or_else(|| {
+            warn!(equipment_id, "Equipment not found");
+            AppError::NotFound(format!("No equipment found with id {}", equipment_id))
+        })
+    }
+
+    /// Tags a goat, creating the tag on first use, and returns the goat's tags.
+    /// Tagging a goat with a tag it already has is a no-op.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat does not exist, or a database error.
+    pub fn add_goat_tag(
+        conn: &Connection,
+        goat_id: i64,
+        tag: &str,
+    ) -> Result<Vec<String>, AppError> {
+        ensure_goat_exists(conn, goat_id)?;
+        let tag = tag.trim();
+        timed_execute(conn, "INSERT OR IGNORE INTO tags (name) VALUES (?1)", [tag])?;
+        let added = timed_execute(
+            conn,
+            "INSERT OR IGNORE INTO goat_tags (goat_id, tag_id) \
+             SELECT ?1, id FROM tags WHERE name = ?2",
+            params![goat_id, tag],
+        )?;
+        debug!(goat_id, tag, added = added > 0, "Goat tagged");
+        fetch_tags(conn, goat_id)
+    }
+
+    /// Removes a tag from a goat and returns the goat's remaining tags.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat does not exist or does not carry `tag`,
+    /// or a database error.
+    pub fn remove_goat_tag(
+        conn: &Connection,
+        goat_id: i64,
+        tag: &str,
+    ) -> Result<Vec<String>, AppError> {
+        ensure_goat_exists(conn, goat_id)?;
+        let removed = timed_execute(
+            conn,
+            "DELETE FROM goat_tags \
+             WHERE goat_id = ?1 AND tag_id = (SELECT id FROM tags WHERE name = ?2)",
+            params![goat_id, tag.trim()],
+        )?;
+        if removed == 0 {
+            warn!(goat_id, tag, "Goat does not carry tag");
+            return Err(AppError::NotFound(format!(
+                "Goat {} is not tagged '{}'",
+                goat_id, tag
+            )));
+        }
+        debug!(goat_id, tag, "Goat untagged");
+        fetch_tags(conn, goat_id)
+    }
+
+    /// Maps every tagged goat's id to its tag names, each list in alphabetical order.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn tags_by_goat(conn: &Connection) -> Result<HashMap<i64, Vec<String>>, AppError> {
+        let rows: Vec<(i64, String)> = timed_query_map(
+            conn,
+            "SELECT gt.goat_id, t.name FROM goat_tags gt INNER JOIN tags t ON t.id = gt.tag_id \
+             ORDER BY gt.goat_id, t.name COLLATE NOCASE",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+        for (goat_id, name) in rows {
+            tags.entry(goat_id).or_default().push(name);
+        }
+        Ok(tags)
+    }
+
+    /// Records a genetic test for a goat and returns it as stored.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat does not exist, or a database error.
+    pub fn insert_genetic_test(
+        conn: &Connection,
+        test: &GeneticTest,
+    ) -> Result<GeneticTest, AppError> {
+        ensure_goat_exists(conn, test.goat_id)?;
+        timed_execute(
+            conn,
+            "INSERT INTO genetic_tests (goat_id, test_type, test_date, lab_name, result_json, report_url) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                test.goat_id,
+                test.test_type,
+                test.test_date,
+                test.lab_name,
+                test.result_json.to_string(),
+                test.report_url
+            ],
+        )?;
+        let test_id = conn.last_insert_rowid();
+        debug!(test_id, goat_id = test.goat_id, "Genetic test recorded");
+        Ok(timed_query_row(
+            conn,
+            &format!(
+                "SELECT {} FROM genetic_tests WHERE id = ?1",
+                GENETIC_TEST_COLUMNS
+            ),
+            [test_id],
+            row_to_genetic_test,
+        )?)
+    }
+
+    /// Lists a goat's genetic tests, most recent first.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn genetic_tests(conn: &Connection, goat_id: i64) -> Result<Vec<GeneticTest>, AppError> {
+        Ok(timed_query_map(
+            conn,
+            &format!(
+                "SELECT {} FROM genetic_tests WHERE goat_id = ?1 ORDER BY test_date DESC, id DESC",
+                GENETIC_TEST_COLUMNS
+            ),
+            [goat_id],
+            row_to_genetic_test,
+        )?)
+    }
+
+    /// Counts genetic tests by the value they report for the top-level `result_json` key
+    /// `trait_name`, most common first. Tests without the key are not counted.
+    ///
+    /// The key is bound as a parameter into the JSON path, never spliced into the SQL.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the aggregation.
+    pub fn genetic_trait_summary(
+        conn: &Connection,
+        trait_name: &str,
+    ) -> Result<Vec<GeneticTraitCount>, AppError> {
+        trace!(trait_name, "Summarising genetic trait");
+        let path = format!("$.\"{}\"", trait_name);
+        let rows: Vec<(rusqlite::types::Value, String, i64)> = timed_query_map(
+            conn,
+            "SELECT json_extract(result_json, ?1), json_type(result_json, ?1), COUNT(*) \
+             FROM genetic_tests \
+             WHERE json_type(result_json, ?1) IS NOT NULL \
+             GROUP BY json_extract(result_json, ?1), json_type(result_json, ?1) \
+             ORDER BY COUNT(*) DESC, json_extract(result_json, ?1)",
+            [&path],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        rows.into_iter()
+            .map(|(value, json_type, count)| {
+                use rusqlite::types::Value as Sql;
+                let value = match (json_type.as_str(), value) {
+                    ("true", _) => serde_json::Value::Bool(true),
+                    ("false", _) => serde_json::Value::Bool(false),
+                    ("object" | "array", Sql::Text(raw)) => {
+                        serde_json::from_str(&raw).map_err(|e| {
+                            AppError::Internal(format!("Bad JSON in result_json: {}", e))
+                        })?
+                    }
+                    (_, Sql::Integer(n)) => serde_json::json!(n),
+                    (_, Sql::Real(x)) => serde_json::json!(x),
+                    (_, Sql::Text(s)) => serde_json::Value::String(s),
+                    _ => serde_json::Value::Null,
+                };
+                Ok(GeneticTraitCount { value, count })
+            })
+            .collect()
+    }
+
+    /// Adds a supplier and returns it as stored.
+    ///
+    /// # Errors
+    /// Returns a database error if the insert fails.
+    pub fn create_supplier(conn: &Connection, supplier: &Supplier) -> Result<Supplier, AppError> {
+        timed_execute(
+            conn,
+            "INSERT INTO suppliers (name, contact, notes) VALUES (?1, ?2, ?3)",
+            params![supplier.name.trim(), supplier.contact, supplier.notes],
+        )?;
+        let supplier_id = conn.last_insert_rowid();
+        info!(supplier_id, name = supplier.name, "Supplier created");
+        Self::get_supplier(conn, supplier_id)
+    }
+
+    /// Loads a supplier by id.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such supplier, or a database error.
+    pub fn get_supplier(conn: &Connection, supplier_id: i64) -> Result<Supplier, AppError> {
+        timed_query_row(
+            conn,
+            &format!("SELECT {} FROM suppliers WHERE id = ?1", SUPPLIER_COLUMNS),
+            [supplier_id],
+            row_to_supplier,
+        )
+        .optional()?
+        .ok_or_else(|| {
+            warn!(supplier_id, "Supplier not found");
+            AppError::NotFound(format!("No supplier found with id {}", supplier_id))
+        })
+    }
+
+    /// Lists all suppliers by name.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn list_suppliers(conn: &Connection) -> Result<Vec<Supplier>, AppError> {
+        query_all(
+            conn,
+            &format!(
+                "SELECT {} FROM suppliers ORDER BY name COLLATE NOCASE, id",
+                SUPPLIER_COLUMNS
+            ),
+            row_to_supplier,
+        )
+    }
+
+    /// Replaces a supplier's name, contact and notes and returns it as stored.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such supplier, or a database error.
+    pub fn update_supplier(
+        conn: &Connection,
+        supplier_id: i64,
+        supplier: &Supplier,
+    ) -> Result<Supplier, AppError> {
+        let updated = timed_execute(
+            conn,
+            "UPDATE suppliers SET name = ?2, contact = ?3, notes = ?4 WHERE id = ?1",
+            params![
+                supplier_id,
+                supplier.name.trim(),
+                supplier.contact,
+                supplier.notes
+            ],
+        )?;
+        if updated == 0 {
+            warn!(supplier_id, "Supplier not found");
+            return Err(AppError::NotFound(format!(
+                "No supplier found with id {}",
+                supplier_id
+            )));
+        }
+        info!(supplier_id, "Supplier updated");
+        Self::get_supplier(conn, supplier_id)
+    }
+
+    /// Deletes a supplier, unlinking it from any equipment and restocks bought from it.
+    /// Should run inside a transaction so the unlinking and the delete land together.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such supplier, or a database error.
+    pub fn delete_supplier(conn: &Connection, supplier_id: i64) -> Result<(), AppError> {
+        Self::get_supplier(conn, supplier_id)?;
+        // Foreign keys are not enforced on our connections, so `ON DELETE SET NULL` is
+        // applied by hand.
+        timed_execute(
+            conn,
+            "UPDATE equipment SET supplier_id = NULL WHERE supplier_id = ?1",
+            [supplier_id],
+        )?;
+        timed_execute(
+            conn,
+            "UPDATE medicine_restocks SET supplier_id = NULL WHERE supplier_id = ?1",
+            [supplier_id],
+        )?;
+        timed_execute(conn, "DELETE FROM suppliers WHERE id = ?1", [supplier_id])?;
+        info!(supplier_id, "Supplier deleted");
+        Ok(())
+    }
+
+    /// Adds `restock.units` to a medicine's stock and records the restock, with its
+    /// supplier and price when given. Should run inside a transaction.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown medicine or supplier, or a database error.
+    pub fn restock_medicine(
+        conn: &Connection,
+        medicine_id: i64,
+        restock: &RestockPayload,
+    ) -> Result<MedicineItem, AppError> {
+        Self::get_medicine(conn, medicine_id)?;
+        if let Some(supplier_id) = restock.supplier_id {
+            Self::get_supplier(conn, supplier_id)?;
+        }
+
+        timed_execute(
+            conn,
+            "UPDATE medicine_inventory SET stock_units = stock_units + ?2 WHERE id = ?1",
+            params![medicine_id, restock.units],
+        )?;
+        timed_execute(
+            conn,
+            "INSERT INTO medicine_restocks (medicine_id, supplier_id, units, cost_per_unit) \
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                medicine_id,
+                restock.supplier_id,
+                restock.units,
+                restock.cost_per_unit
+            ],
+        )?;
+
+        info!(
+            medicine_id,
+            supplier_id = ?restock.supplier_id,
+            units = restock.units,
+            "Medicine restocked"
+        );
+        Self::get_medicine(conn, medicine_id)
+    }
+
+    /// Lists equipment and medicine restocks bought from a supplier, newest first, and
+    /// totals the ones with a recorded price.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown supplier, or database errors.
+    pub fn supplier_purchases(
+        conn: &Connection,
+        supplier_id: i64,
+    ) -> Result<SupplierPurchases, AppError> {
+        Self::get_supplier(conn, supplier_id)?;
+        let purchases = timed_query_map(
+            conn,
+            "SELECT 'equipment', id, name, purchase_price, purchase_date \
+             FROM equipment WHERE supplier_id = ?1 \
+             UNION ALL \
+             SELECT 'medicine', m.id, m.name, r.units * r.cost_per_unit, date(r.restocked_at) \
+             FROM medicine_restocks r JOIN medicine_inventory m ON m.id = r.medicine_id \
+             WHERE r.supplier_id = ?1 \
+             ORDER BY 5 DESC, 1, 2",
+            [supplier_id],
+            |row| {
+                Ok(SupplierPurchase {
+                    kind: row.get(0)?,
+                    item_id: row.get(1)?,
+                    item_name: row.get(2)?,
+                    amount: row.get(3)?,
+                    purchased_on: row.get(4)?,
+                })
+            },
+        )?;
+        let total_spent = purchases.iter().filter_map(|p| p.amount).sum();
+
+        debug!(
+            supplier_id,
+            count = purchases.len(),
+            total_spent,
+            "Supplier purchases aggregated"
+        );
+        Ok(SupplierPurchases {
+            supplier_id,
+            purchase_count: purchases.len(),
+            total_spent,
+            purchases,
+        })
+    }
+
+    /// Appends a trade to the log, chaining its hash to the latest entry's.
+    ///
+    /// Should run inside an immediate transaction so no other append can slip in between
+    /// reading the latest hash and inserting the new entry.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat does not exist, or a database error.
+    pub fn append_trade(
+        conn: &Connection,
+        goat_id: i64,
+        trade: &TradePayload,
+        timestamp: &str,
+    ) -> Result<TradeLogEntry, AppError> {
+        ensure_goat_exists(conn, goat_id)?;
+        let prev_hash: String = timed_query_row(
+            conn,
+            "SELECT transaction_hash FROM immutable_trade_log ORDER BY id DESC LIMIT 1",
+            [],
+            |r| r.get(0),
+        )
+        .optional()?
+        .unwrap_or_else(|| GENESIS_HASH.to_string());
+        let action = trade.action.trim();
+        let payload = trade_payload(
+            goat_id,
+            action,
+            trade.from_party.as_deref(),
+            trade.to_party.as_deref(),
+            trade.price,
+            timestamp,
+        );
+        let transaction_hash = compute_trade_hash(&prev_hash, &payload);
+
+        timed_execute(
+            conn,
+            "INSERT INTO immutable_trade_log \
+             (transaction_hash, goat_id, action, from_party, to_party, price, timestamp, prev_hash) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                transaction_hash,
+                goat_id,
+                action,
+                trade.from_party,
+                trade.to_party,
+                trade.price,
+                timestamp,
+                prev_hash
+            ],
+        )?;
+        let entry_id = conn.last_insert_rowid();
+        info!(entry_id, goat_id, action, "Trade appended to log");
+
+        Ok(timed_query_row(
+            conn,
+            &format!(
+                "SELECT {} FROM immutable_trade_log WHERE id = ?1",
+                TRADE_LOG_COLUMNS
+            ),
+            [entry_id],
+            row_to_trade_log_entry,
+        )?)
+    }
+
+    /// Lists a goat's trades, oldest first. Trades of deleted goats are still listed.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn goat_trade_log(conn: &Connection, goat_id: i64) -> Result<Vec<TradeLogEntry>, AppError> {
+        Ok(timed_query_map(
+            conn,
+            &format!(
+                "SELECT {} FROM immutable_trade_log WHERE goat_id = ?1 ORDER BY id",
+                TRADE_LOG_COLUMNS
+            ),
+            [goat_id],
+            row_to_trade_log_entry,
+        )?)
+    }
+
+    /// Lists the whole trade log in chain order.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn trade_log(conn: &Connection) -> Result<Vec<TradeLogEntry>, AppError> {
+        query_all(
+            conn,
+            &format!(
+                "SELECT {} FROM immutable_trade_log ORDER BY id",
+                TRADE_LOG_COLUMNS
+            ),
+            row_to_trade_log_entry,
+        )
+    }
+
+    /// Adds a buyer and returns it as stored.
+    ///
+    /// # Errors
+    /// Returns `AppError::Conflict` if a buyer with that name exists, or a database error.
+    pub fn create_buyer(conn: &Connection, buyer: &Buyer) -> Result<Buyer, AppError> {
+        let name = buyer.name.trim();
+        if find_id(conn, "buyers", "name = ?1", [name])?.is_some() {
+            warn!(name, "Duplicate buyer name");
+            return Err(AppError::Conflict(format!(
+                "A buyer named {} already exists",
+                name
+            )));
+        }
+        timed_execute(
+            conn,
+            "INSERT INTO buyers (name, contact, notes) VALUES (?1, ?2, ?3)",
+            params![name, buyer.contact, buyer.notes],
+        )?;
+        let buyer_id = conn.last_insert_rowid();
+        info!(buyer_id, name, "Buyer created");
+        Self::get_buyer(conn, buyer_id)
+    }
+
+    /// Loads a buyer by id.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such buyer, or a database error.
+    pub fn get_buyer(conn: &Connection, buyer_id: i64) -> Result<Buyer, AppError> {
+        timed_query_row(
+            conn,
+            &format!("SELECT {} FROM buyers WHERE id = ?1", BUYER_COLUMNS),
+            [buyer_id],
+            row_to_buyer,
+        )
+        .optional()?
+        .ok_or_else(|| {
+            warn!(buyer_id, "Buyer not found");
+            AppError::NotFound(format!("No buyer found with id {}", buyer_id))
+        })
+    }
+
+    /// Lists all buyers by name.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn list_buyers(conn: &Connection) -> Result<Vec<Buyer>, AppError> {
+        query_all(
+            conn,
+            &format!("SELECT {} FROM buyers ORDER BY name, id", BUYER_COLUMNS),
+            row_to_buyer,
+        )
+    }
+
+    /// Replaces a buyer's name, contact and notes and returns it as stored.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such buyer, `AppError::Conflict` if
+    /// another buyer has the new name, or a database error.
+    pub fn update_buyer(
+        conn: &Connection,
+        buyer_id: i64,
+        buyer: &Buyer,
+    ) -> Result<Buyer, AppError> {
+        Self::get_buyer(conn, buyer_id)?;
+        let name = buyer.name.trim();
+        if find_id(
+            conn,
+            "buyers",
+            "name = ?1 AND id != ?2",
+            params![name, buyer_id],
+        )?
+        .is_some()
+        {
+            warn!(buyer_id, name, "Duplicate buyer name");
+            return Err(AppError::Conflict(format!(
+                "A buyer named {} already exists",
+                name
+            )));
+        }
+        timed_execute(
+            conn,
+            "UPDATE buyers SET name = ?2, contact = ?3, notes = ?4 WHERE id = ?1",
+            params![buyer_id, name, buyer.contact, buyer.notes],
+        )?;
+        info!(buyer_id, "Buyer updated");
+        Self::get_buyer(conn, buyer_id)
+    }
+
+    /// Deletes a buyer. Their sales are kept, with the name recorded at sale time, but
+    /// no longer linked to a buyer record. Should run inside a transaction.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such buyer, or a database error.
+    pub fn delete_buyer(conn: &Connection, buyer_id: i64) -> Result<(), AppError> {
+        Self::get_buyer(conn, buyer_id)?;
+        // Foreign keys are not enforced on our connections, so `ON DELETE SET NULL` is
+        // applied by hand.
+        timed_execute(
+            conn,
+            "UPDATE sales SET buyer_id = NULL WHERE buyer_id = ?1",
+            [buyer_id],
+        )?;
+        timed_execute(conn, "DELETE FROM buyers WHERE id = ?1", [buyer_id])?;
+        info!(buyer_id, "Buyer deleted");
+        Ok(())
+    }
+
+    /// Sells a live goat: records the sale against its buyer (see `get_or_insert_buyer`)
+    /// and marks the goat sold. Should run inside a transaction.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown goat or `buyer_id`,
+    /// `AppError::Conflict` if the goat is already sold, or a database error.
+    pub fn sell_goat(
+        conn: &Connection,
+        goat_id: i64,
+        sale: &SalePayload,
+    ) -> Result<Sale, AppError> {
+        ensure_goat_exists(conn, goat_id)?;
+        let is_sold: bool = timed_query_row(
+            conn,
+            "SELECT is_sold FROM goats WHERE id = ?1",
+            [goat_id],
+            |r| r.get(0),
+        )?;
+        if is_sold {
+            warn!(goat_id, "Goat already sold");
+            return Err(AppError::Conflict(format!(
+                "Goat {} has already been sold",
+                goat_id
+            )));
+        }
+
+        let buyer_id = get_or_insert_buyer(conn, sale.buyer_id, sale.buyer_name.as_deref())?;
+        timed_execute(
+            conn,
+            "INSERT INTO sales (goat_id, buyer_id, buyer, price) \
+             VALUES (?1, ?2, (SELECT name FROM buyers WHERE id = ?2), ?3)",
+            params![goat_id, buyer_id, sale.price],
+        )?;
+        let sale_id = conn.last_insert_rowid();
+        timed_execute(
+            conn,
+            "UPDATE goats SET is_sold = 1, version = version + 1, \
+             updated_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            [goat_id],
+        )?;
+        info!(sale_id, goat_id, buyer_id, price = sale.price, "Goat sold");
+
+        Ok(timed_query_row(
+            conn,
+            "SELECT id, goat_id, buyer_id, buyer, price, created_at FROM sales WHERE id = ?1",
+            [sale_id],
+            |row| {
+                Ok(Sale {
+                    id: row.get(0)?,
+                    goat_id: row.get(1)?,
+                    buyer_id: row.get(2)?,
+                    buyer: row.get(3)?,
+                    price: row.get(4)?,
+                    created_at: row.get(5)?,
+                })
+            },
+        )?)
+    }
+
+    /// Lists the goats a buyer has bought, most recent sale first.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown buyer, or database errors.
+    pub fn buyer_purchases(
+        conn: &Connection,
+        buyer_id: i64,
+    ) -> Result<Vec<BuyerPurchase>, AppError> {
+        Self::get_buyer(conn, buyer_id)?;
+        Ok(timed_query_map(
+            conn,
+            "SELECT s.id, g.id, g.name, g.breed, s.price, s.created_at \
+             FROM sales s JOIN goats g ON g.id = s.goat_id \
+             WHERE s.buyer_id = ?1 \
+             ORDER BY s.created_at DESC, s.id DESC",
+            [buyer_id],
+            |row| {
+                Ok(BuyerPurchase {
+                    sale_id: row.get(0)?,
+                    goat_id: row.get(1)?,
+                    goat_name: row.get(2)?,
+                    breed: row.get(3)?,
+                    price: row.get(4)?,
+                    sold_at: row.get(5)?,
+                })
+            },
+        )?)
+    }
+
+    /// Scans the live goats for common data entry errors, one query per check in
+    /// `DATA_QUALITY_CHECKS`. A goat with several problems is listed once per problem.
+    ///
+    /// # Errors
+    /// Returns database errors raised by any check.
+    pub fn data_quality_scan(conn: &Connection) -> Result<DataQualityReport, AppError> {
+        let mut issues = Vec::new();
+        for (issue, sql) in DATA_QUALITY_CHECKS {
+            let found = timed_query_map(conn, sql, [], |row| {
+                Ok(DataQualityIssue {
+                    goat_id: row.get(0)?,
+                    goat_name: row.get(1)?,
+                    issue: issue.to_string(),
+                })
+            })?;
+            trace!(issue, count = found.len(), "Data quality check ran");
+            issues.extend(found);
+        }
+
+        let total_goats: i64 = timed_query_row(
+            conn,
+            "SELECT COUNT(*) FROM goats WHERE deleted_at IS NULL",
+            [],
+            |r| r.get(0),
+        )?;
+        let goats_with_issues = issues
+            .iter()
+            .map(|issue| issue.goat_id)
+            .collect::<HashSet<_>>()
+            .len();
+        let quality_score_pct = if total_goats == 0 {
+            100.0
+        } else {
+            (total_goats as f64 - goats_with_issues as f64) / total_goats as f64 * 100.0
+        };
+
+        info!(
+            total_goats,
+            goats_with_issues,
+            total_issues = issues.len(),
+            quality_score_pct,
+            "Data quality scan finished"
+        );
+        Ok(DataQualityReport {
+            total_issues: issues.len() as u32,
+            quality_score_pct,
+            issues,
+        })
+    }
+
+    /// Loads a task by id, whoever it is assigned to.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such task, or a database error.
+    pub fn get_task(conn: &Connection, task_id: i64) -> Result<WorkerTask, AppError> {
+        timed_query_row(
+            conn,
+            &format!(
+                "SELECT {} FROM worker_tasks WHERE id = ?1",
+                WORKER_TASK_COLUMNS
+            ),
+            [task_id],
+            row_to_worker_task,
+        )
+        .optional()?
+        .ok_or_else(|| {
+            warn!(task_id, "Task not found");
+            AppError::NotFound(format!("No task found with id {}", task_id))
+        })
+    }
+
+    /// Lists every worker's tasks by due date (undated last), optionally only open
+    /// (`completed = Some(false)`) or only completed ones.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn list_tasks(
+        conn: &Connection,
+        completed: Option<bool>,
+    ) -> Result<Vec<WorkerTask>, AppError> {
+        Ok(timed_query_map(
+            conn,
+            &format!(
+                "SELECT {} FROM worker_tasks \
+                 WHERE ?1 IS NULL OR (completed_at IS NOT NULL) = ?1 \
+                 ORDER BY due_at IS NULL, due_at, id",
+                WORKER_TASK_COLUMNS
+            ),
+            [completed],
+            row_to_worker_task,
+        )?)
+    }
+
+    /// Replaces a task's assignee, title, description, due date and priority, keeping its
+    /// assignment and completion times, and returns it as stored.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the task or the new assignee does not exist, or a
+    /// database error.
+    pub fn update_task(
+        conn: &Connection,
+        task_id: i64,
+        task: &WorkerTask,
+    ) -> Result<WorkerTask, AppError> {
+        Self::get_task(conn, task_id)?;
+        ensure_worker_exists(conn, task.worker_id)?;
+        timed_execute(
+            conn,
+            "UPDATE worker_tasks \
+             SET worker_id = ?2, task = ?3, description = ?4, due_at = ?5, priority = ?6 \
+             WHERE id = ?1",
+            params![
+                task_id,
+                task.worker_id,
+                task.task.trim(),
+                task.description,
+                task.due_at,
+                task.priority
+            ],
+        )?;
+        info!(task_id, worker_id = task.worker_id, "Task updated");
+        Self::get_task(conn, task_id)
+    }
+
+    /// Deletes a task.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such task, or a database error.
+    pub fn delete_task(conn: &Connection, task_id: i64) -> Result<(), AppError> {
+        let deleted = timed_execute(conn, "DELETE FROM worker_tasks WHERE id = ?1", [task_id])?;
+        if deleted == 0 {
+            warn!(task_id, "Task not found");
+            return Err(AppError::NotFound(format!(
+                "No task found with id {}",
+                task_id
+            )));
+        }
+        info!(task_id, "Task deleted");
+        Ok(())
+    }
+
+    /// Adds a geofence and returns it as stored.
+    ///
+    /// # Errors
+    /// Returns a database error if the insert fails.
+    pub fn create_geofence(conn: &Connection, fence: &Geofence) -> Result<Geofence, AppError> {
+        timed_execute(
+            conn,
+            "INSERT INTO geofences (name, lat_center, lon_center, radius_m, alert_on) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                fence.name.trim(),
+                fence.lat_center,
+                fence.lon_center,
+                fence.radius_m,
+                fence.alert_on
+            ],
+        )?;
+        let geofence_id = conn.last_insert_rowid();
+        info!(geofence_id, name = fence.name, "Geofence created");
+        Ok(timed_query_row(
+            conn,
+            &format!("SELECT {} FROM geofences WHERE id = ?1", GEOFENCE_COLUMNS),
+            [geofence_id],
+            row_to_geofence,
+        )?)
+    }
+
+    /// Lists all geofences by id.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn list_geofences(conn: &Connection) -> Result<Vec<Geofence>, AppError> {
+        query_all(
+            conn,
+            &format!("SELECT {} FROM geofences ORDER BY id", GEOFENCE_COLUMNS),
+            row_to_geofence,
+        )
+    }
+
+    /// Deletes a geofence. Alerts it raised are kept under the fence's name.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such geofence, or a database error.
+    pub fn delete_geofence(conn: &Connection, geofence_id: i64) -> Result<(), AppError> {
+        let deleted = timed_execute(conn, "DELETE FROM geofences WHERE id = ?1", [geofence_id])?;
+        if deleted == 0 {
+            warn!(geofence_id, "Geofence not found");
+            return Err(AppError::NotFound(format!(
+                "No geofence found with id {}",
+                geofence_id
+            )));
+        }
+        timed_execute(
+            conn,
+            "UPDATE location_alerts SET geofence_id = NULL WHERE geofence_id = ?1",
+            [geofence_id],
+        )?;
+        info!(geofence_id, "Geofence deleted");
+        Ok(())
+    }
+
+    /// Stores a GPS fix for a goat and raises a location alert for every geofence whose
+    /// boundary the goat crossed since its previous fix, when the fence's `alert_on`
+    /// covers that crossing. Should run inside a transaction.
+    ///
+    /// `recorded_at` must already be normalised to SQLite's timestamp form; `None` means now.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat does not exist, or a database error.
+    pub fn record_goat_location(
+        conn: &Connection,
+        goat_id: i64,
+        location: &LocationPayload,
+    ) -> Result<LocationReport, AppError> {
+        ensure_goat_exists(conn, goat_id)?;
+        let previous: Option<(f64, f64)> = timed_query_row(
+            conn,
+            "SELECT lat, lon FROM goat_locations WHERE goat_id = ?1 ORDER BY id DESC LIMIT 1",
+            [goat_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .optional()?;
+
+        timed_execute(
+            conn,
+            "INSERT INTO goat_locations (goat_id, lat, lon, recorded_at) \
+             VALUES (?1, ?2, ?3, COALESCE(?4, CURRENT_TIMESTAMP))",
+            params![goat_id, location.lat, location.lon, location.recorded_at],
+        )?;
+        let recorded_at: String = timed_query_row(
+            conn,
+            "SELECT recorded_at FROM goat_locations WHERE id = ?1",
+            [conn.last_insert_rowid()],
+            |r| r.get(0),
+        )?;
+
+        let mut alerts = Vec::new();
+        for fence in Self::list_geofences(conn)? {
+            let inside = |lat: f64, lon: f64| {
+                haversine_distance(fence.lat_center, fence.lon_center, lat, lon) <= fence.radius_m
+            };
+            let distance_m = haversine_distance(
+                fence.lat_center,
+                fence.lon_center,
+                location.lat,
+                location.lon,
+            );
+            let was_inside = previous.map(|(lat, lon)| inside(lat, lon));
+            let Some(crossing) = fence_crossing(was_inside, distance_m <= fence.radius_m) else {
+                continue;
+            };
+            if !alerts_on(&fence.alert_on, crossing) {
+                continue;
+            }
+
+            timed_execute(
+                conn,
+                "INSERT INTO location_alerts \
+                 (goat_id, geofence_id, geofence_name, event, lat, lon, distance_m) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    goat_id,
+                    fence.id,
+                    fence.name,
+                    crossing.as_str(),
+                    location.lat,
+                    location.lon,
+                    distance_m
+                ],
+            )?;
+            let alert = timed_query_row(
+                conn,
+                &format!(
+                    "SELECT {} FROM location_alerts WHERE id = ?1",
+                    LOCATION_ALERT_COLUMNS
+                ),
+                [conn.last_insert_rowid()],
+                row_to_location_alert,
+            )?;
+            warn!(
+                goat_id,
+                geofence = fence.name,
+                event = alert.event,
+                distance_m,
+                "Geofence crossed"
+            );
+            alerts.push(alert);
+        }
+
+        debug!(goat_id, alerts = alerts.len(), "Goat location recorded");
+        Ok(LocationReport {
+            goat_id,
+            lat: location.lat,
+            lon: location.lon,
+            recorded_at,
+            alerts,
+        })
+    }
+
+    /// Lists location alerts, newest first.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the query.
+    pub fn location_alerts(conn: &Connection) -> Result<Vec<LocationAlert>, AppError> {
+        query_all(
+            conn,
+            &format!(
+                "SELECT {} FROM location_alerts ORDER BY created_at DESC, id DESC",
+                LOCATION_ALERT_COLUMNS
+            ),
+            row_to_location_alert,
+        )
+    }
+
+    /// Returns the count, mean and standard deviation of a sensor's latest `window`
+    /// readings by `recorded_at`, computed in SQL over the `(sensor_id, recorded_at)`
+    /// index. Readings backfilled with an older timestamp therefore fall in the window
+    /// by when they were taken, not when they arrived.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn sensor_rolling_stats(
+        conn: &Connection,
+        sensor_id: i64,
+        window: u32,
+    ) -> Result<RollingStats, AppError> {
+        let (count, mean, mean_of_squares): (u32, Option<f64>, Option<f64>) = timed_query_row(
+            conn,
+            "SELECT COUNT(*), AVG(value), AVG(value * value) FROM ( \
+                 SELECT value FROM sensor_readings WHERE sensor_id = ?1 \
+                 ORDER BY recorded_at DESC, id DESC LIMIT ?2 \
+             )",
+            [sensor_id, i64::from(window)],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )?;
+        Ok(RollingStats::from_moments(
+            count,
+            mean.unwrap_or_default(),
+            mean_of_squares.unwrap_or_default(),
+        ))
+    }
+
+    /// Fails with `AppError::NotFound` unless the sensor exists, returning its type.
+    fn sensor_type(conn: &Connection, sensor_id: i64) -> Result<String, AppError> {
+        timed_query_row(
+            conn,
+            "SELECT sensor_type FROM sensors WHERE id = ?1",
+            [sensor_id],
+            |r| r.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| {
+            warn!(sensor_id, "Sensor not found");
+            AppError::NotFound(format!("No sensor found with id {}", sensor_id))
+        })
+    }
+
+    /// Lists up to `limit` of a sensor's readings recorded between `from` and `to`
+    /// (inclusive, either open-ended when `None`), oldest first, skipping the first
+    /// `offset`.
+    ///
+    /// Timestamps must already be normalised to SQLite's timestamp form.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the sensor does not exist, or a database error.
+    pub fn sensor_readings(
+        conn: &Connection,
+        sensor_id: i64,
+        from: Option<&str>,
+        to: Option<&str>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<SensorReading>, AppError> {
+        Self::sensor_type(conn, sensor_id)?;
+        Ok(timed_query_map(
+            conn,
+            "SELECT id, sensor_id, value, recorded_at, is_anomaly FROM sensor_readings \
+             WHERE sensor_id = ?1 AND (?2 IS NULL OR recorded_at >= ?2) \
+             AND (?3 IS NULL OR recorded_at <= ?3) \
+             ORDER BY recorded_at, id LIMIT ?4 OFFSET ?5",
+            params![sensor_id, from, to, limit, offset],
+            |row| {
+                Ok(SensorReading {
+                    id: row.get(0)?,
+                    sensor_id: row.get(1)?,
+                    value: row.get(2)?,
+                    recorded_at: row.get(3)?,
+                    is_anomaly: row.get(4)?,
+                    mean: None,
+                    std_dev: None,
+                })
+            },
+        )?)
+    }
+
+    /// Downsamples a sensor's readings between `from` and `to` into `bucket`-wide time
+    /// buckets, oldest first, returning at most `limit` buckets. Grouping and the
+    /// average, minimum and maximum are computed in SQL.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the sensor does not exist, or a database error.
+    pub fn sensor_reading_buckets(
+        conn: &Connection,
+        sensor_id: i64,
+        from: Option<&str>,
+        to: Option<&str>,
+        bucket: ReadingBucket,
+        limit: u32,
+    ) -> Result<Vec<SensorReadingBucket>, AppError> {
+        Self::sensor_type(conn, sensor_id)?;
+        let buckets = timed_query_map(
+            conn,
+            &format!(
+                "SELECT strftime('{fmt}', recorded_at) AS bucket_start, COUNT(*), \
+                        AVG(value), MIN(value), MAX(value) \
+                 FROM sensor_readings \
+                 WHERE sensor_id = ?1 AND (?2 IS NULL OR recorded_at >= ?2) \
+                 AND (?3 IS NULL OR recorded_at <= ?3) \
+                 GROUP BY bucket_start ORDER BY bucket_start LIMIT ?4",
+                fmt = bucket.strftime_format()
+            ),
+            params![sensor_id, from, to, limit],
+            |row| {
+                Ok(SensorReadingBucket {
+                    bucket_start: row.get(0)?,
+                    count: row.get(1)?,
+                    avg: row.get(2)?,
+                    min: row.get(3)?,
+                    max: row.get(4)?,
+                })
+            },
+        )?;
+        trace!(
+            sensor_id,
+            ?bucket,
+            count = buckets.len(),
+            "Sensor readings bucketed"
+        );
+        Ok(buckets)
+    }
+
+    /// Stores a sensor reading, makes it the sensor's `last_reading`, and flags it as an
+    /// anomaly, raising an alert, when it lies more than `sigma` standard deviations
+    /// from the mean of the sensor's previous `window` readings. Should run inside a
+    /// transaction.
+    ///
+    /// `recorded_at` must already be normalised to SQLite's timestamp form; `None` means now.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the sensor does not exist, or a database error.
+    pub fn record_sensor_reading(
+        conn: &Connection,
+        sensor_id: i64,
+        reading: &SensorReadingPayload,
+        window: u32,
+        sigma: f64,
+    ) -> Result<SensorReading, AppError> {
+        let sensor_type = Self::sensor_type(conn, sensor_id)?;
+
+        let stats = Self::sensor_rolling_stats(conn, sensor_id, window)?;
+        let anomaly = is_anomaly(reading.value, &stats, sigma);
+        timed_execute(
+            conn,
+            "INSERT INTO sensor_readings (sensor_id, value, recorded_at, is_anomaly) \
+             VALUES (?1, ?2, COALESCE(?3, CURRENT_TIMESTAMP), ?4)",
+            params![sensor_id, reading.value, reading.recorded_at, anomaly],
+        )?;
+        let reading_id = conn.last_insert_rowid();
+        let recorded_at: String = timed_query_row(
+            conn,
+            "SELECT recorded_at FROM sensor_readings WHERE id = ?1",
+            [reading_id],
+            |r| r.get(0),
+        )?;
+        timed_execute(
+            conn,
+            "UPDATE sensors SET last_reading = ?1, last_reading_time = ?2 WHERE id = ?3",
+            params![reading.value, recorded_at, sensor_id],
+        )?;
+
+        if anomaly {
+            raise_alert(
+                conn,
+                None,
+                "sensor_anomaly",
+                &format!(
+                    "{} sensor {} read {}, outside {} sigma of its recent mean {:.2} (std dev {:.2})",
+                    sensor_type, sensor_id, reading.value, sigma, stats.mean, stats.std_dev
+                ),
+            )?;
+        }
+
+        let judged = stats.count >= MIN_ANOMALY_SAMPLES;
+        debug!(sensor_id, reading_id, anomaly, "Sensor reading recorded");
+        Ok(SensorReading {
+            id: reading_id,
+            sensor_id,
+            value: reading.value,
+            recorded_at,
+            is_anomaly: anomaly,
+            mean: judged.then_some(stats.mean),
+            std_dev: judged.then_some(stats.std_dev),
+        })
+    }
+
+    /// Collects everything recorded about a live goat into one oldest-first history.
+    ///
+    /// Each activity table contributes one `UNION ALL` branch selecting `timestamp`,
+    /// `event_type` and `description`; rows without a time are left out. Events at the
+    /// same instant are ordered by type.
+    /// There is no breeding history table, so breeding comes from `goats.last_bred`. Space
+    /// assignments only cover the goat's current space, as moves overwrite the previous
+    /// assignment.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat does not exist, or a database error.
+    pub fn build_goat_timeline(
+        conn: &Connection,
+        goat_id: i64,
+    ) -> Result<Vec<TimelineEvent>, AppError> {
+        ensure_goat_exists(conn, goat_id)?;
+        let events = timed_query_map(
+            conn,
+            "SELECT timestamp, event_type, description FROM ( \
+             SELECT created_at AS timestamp, 'created' AS event_type, \
+                    'Added as ' || name AS description \
+             FROM goats WHERE id = ?1 \
+             UNION ALL \
+             SELECT created_at, 'weight', printf('Weighed %.1f kg', weight) \
+             FROM goat_weight_history WHERE goat_id = ?1 \
+             UNION ALL \
+             SELECT gv.created_at, 'vaccination', 'Vaccinated: ' || v.name \
+             FROM goat_vaccines gv JOIN vaccines v ON v.id = gv.vaccine_id \
+             WHERE gv.goat_id = ?1 \
+             UNION ALL \
+             SELECT gd.created_at, 'disease', 'Diagnosed: ' || d.name \
+             FROM goat_diseases gd JOIN diseases d ON d.id = gd.disease_id \
+             WHERE gd.goat_id = ?1 \
+             UNION ALL \
+             SELECT md.dispensed_at, 'treatment', \
+                    printf('Given %g %s of %s: %s', md.amount, m.unit, m.name, md.reason) \
+             FROM medicine_dispensing md JOIN medicine_inventory m ON m.id = md.medicine_id \
+             WHERE md.goat_id = ?1 \
+             UNION ALL \
+             SELECT completed_at, 'deworming', 'Dewormed' || COALESCE(' with ' || product, '') \
+             FROM deworming_records WHERE goat_id = ?1 AND status = 'completed' \
+             UNION ALL \
+             SELECT created_at, 'vet_visit', \
+                    'Vet visit' || COALESCE(' by ' || vet_name, '') \
+                    || COALESCE(': ' || reason, '') \
+             FROM vet_visits WHERE goat_id = ?1 \
+             UNION ALL \
+             SELECT last_bred, 'breeding', 'Bred' \
+             FROM goats WHERE id = ?1 AND last_bred IS NOT NULL \
+             UNION ALL \
+             SELECT sg.assigned_at, 'space_assignment', 'Moved to ' || s.name \
+             FROM space_goats sg JOIN spaces s ON s.id = sg.space_id WHERE sg.goat_id = ?1 \
+             UNION ALL \
+             SELECT created_at, 'sale', \
+                    'Sold' || COALESCE(' to ' || buyer, '') \
+                    || CASE WHEN price IS NULL THEN '' ELSE printf(' for %.2f', price) END \
+             FROM sales WHERE goat_id = ?1 \
+             UNION ALL \
+             SELECT scored_at, 'famacha', printf('FAMACHA score %d', score) \
+             FROM famacha_scores WHERE goat_id = ?1 \
+             UNION ALL \
+             SELECT show_date, 'show', \
+                    'Shown at ' || show_name \
+                    || CASE WHEN placement IS NULL THEN '' \
+                            ELSE printf(', placed %d', placement) END \
+             FROM show_entries WHERE goat_id = ?1 \
+             UNION ALL \
+             SELECT recorded_at, 'behavior', intensity || ' ' || behavior \
+             FROM behavior_observations WHERE goat_id = ?1 \
+             UNION ALL \
+             SELECT test_date, 'genetic_test', 'Genetic test: ' || test_type \
+             FROM genetic_tests WHERE goat_id = ?1) \
+             WHERE timestamp IS NOT NULL \
+             ORDER BY timestamp, event_type",
+            [goat_id],
+            |row| {
+                Ok(TimelineEvent {
+                    timestamp: row.get(0)?,
+                    event_type: row.get(1)?,
+                    description: row.get(2)?,
+                })
+            },
+        )?;
+        debug!(goat_id, count = events.len(), "Goat timeline built");
+        Ok(events)
+    }
+
+    /// Counts disease diagnoses and resolutions per time bucket between `from` and `to`
+    /// (inclusive), oldest bucket first; buckets without events are left out.
+    ///
+    /// Events come from `disease_events`, which triggers on `goat_diseases` fill in as
+    /// links are added and removed. Buckets are labelled like `disease_trends`.
+    ///
+    /// # Errors
+    /// Returns database errors raised while running the query.
+    pub fn health_trends(
+        conn: &Connection,
+        from: NaiveDate,
+        to: NaiveDate,
+        bucket: TrendInterval,
+    ) -> Result<Vec<HealthTrendPoint>, AppError> {
+        trace!(%from, %to, ?bucket, "Computing health trends");
+
+        let sql = format!(
+            "SELECT strftime('{fmt}', occurred_at) AS bucket, \
+                    SUM(event = 'Diagnosed'), SUM(event = 'Resolved') \
+             FROM disease_events \
+             WHERE date(occurred_at) BETWEEN ?1 AND ?2 \
+             GROUP BY bucket ORDER BY bucket",
+            fmt = bucket.strftime_format()
+        );
+        let points = timed_query_map(
+            conn,
+            &sql,
+            [
+                from.format("%Y-%m-%d").to_string(),
+                to.format("%Y-%m-%d").to_string(),
+            ],
+            |row| {
+                Ok(HealthTrendPoint {
+                    bucket: row.get(0)?,
+                    diagnoses: row.get(1)?,
+                    resolutions: row.get(2)?,
+                })
+            },
+        )?;
+
+        trace!(count = points.len(), "Health trend points computed");
+        Ok(points)
+    }
+
+    /// Totals the money that came in and went out during `month` of `year`, and the
+    /// resulting profit or loss.
+    ///
+    /// Each total is a sub-query matching its date column on `strftime('%m')` and
+    /// `strftime('%Y')`: goat `created_at` for purchases (soft-deleted goats included, as
+    /// they were still bought), sale `created_at`, expense `incurred_on` and vet visit
+    /// `created_at`. Purchases are summed exactly in paise, falling back to the legacy
+    /// rupee column like `financial_stats`.
+    ///
+    /// # Errors
+    /// Returns database errors raised by the aggregation.
+    pub fn monthly_financial_report(
+        conn: &Connection,
+        month: u32,
+        year: i32,
+    ) -> Result<MonthlyFinancialReport, AppError> {
+        trace!(month, year, "Building monthly financial report");
+        let report = timed_query_row(
+            conn,
+            "SELECT \
+                (SELECT COALESCE(SUM(COALESCE(cost_minor, CAST(ROUND(cost * 100) AS INTEGER))), 0) \
+                 FROM goats WHERE strftime('%m', created_at) = ?1 AND strftime('%Y', created_at) = ?2), \
+                (SELECT COALESCE(SUM(price), 0.0) FROM sales \
+                 WHERE strftime('%m', created_at) = ?1 AND strftime('%Y', created_at) = ?2), \
+                (SELECT COALESCE(SUM(amount), 0.0) FROM expenses WHERE category = 'Feed' \
+                 AND strftime('%m', incurred_on) = ?1 AND strftime('%Y', incurred_on) = ?2), \
+                (SELECT COALESCE(SUM(amount), 0.0) FROM expenses WHERE category = 'Vet' \
+                 AND strftime('%m', incurred_on) = ?1 AND strftime('%Y', incurred_on) = ?2) \
+                + (SELECT COALESCE(SUM(cost), 0.0) FROM vet_visits \
+                 WHERE strftime('%m', created_at) = ?1 AND strftime('%Y', created_at) = ?2), \
+                (SELECT COALESCE(SUM(amount), 0.0) FROM expenses WHERE category = 'Labor' \
+                 AND strftime('%m', incurred_on) = ?1 AND strftime('%Y', incurred_on) = ?2)",
+            [format!("{:02}", month), format!("{:04}", year)],
+            |row| {
+                let total_purchases = Money {
+                    amount: row.get(0)?,
+                    currency: Currency::Inr,
+                }
+                .to_major();
+                let total_sales_revenue: f64 = row.get(1)?;
+                let total_feed_costs: f64 = row.get(2)?;
+                let total_vet_costs: f64 = row.get(3)?;
+                let total_payroll: f64 = row.get(4)?;
+                let net_profit = total_sales_revenue
+                    - total_purchases
+                    - total_feed_costs
+                    - total_vet_costs
+                    - total_payroll;
+                Ok(MonthlyFinancialReport {
+                    month: format!("{:04}-{:02}", year, month),
+                    total_purchases,
+                    total_sales_revenue,
+                    total_feed_costs,
+                    total_vet_costs,
+                    total_payroll,
+                    net_profit,
+                    profit_margin_percent: (total_sales_revenue != 0.0)
+                        .then(|| net_profit / total_sales_revenue * 100.0),
+                })
+            },
+        )?;
+        debug!(?report, "Monthly financial report built");
+        Ok(report)
+    }
+
+    /// Builds the shareable export of a live goat: its fields, vaccines and diseases by
+    /// name, and weight history, oldest first.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such goat, or a database error.
+    pub fn export_goat_document(conn: &Connection, goat_id: i64) -> Result<GoatDocument, AppError> {
+        let goat = load_goat_details(conn, goat_id)?;
+        let weight_history = timed_query_map(
+            conn,
+            "SELECT weight, created_at FROM goat_weight_history \
+             WHERE goat_id = ?1 ORDER BY created_at, id",
+            [goat_id],
+            |row| {
+                Ok(WeightEntry {
+                    weight: row.get(0)?,
+                    recorded_at: row.get(1)?,
+                })
+            },
+        )?;
+        debug!(
+            goat_id,
+            weighings = weight_history.len(),
+            "Goat document exported"
+        );
+        Ok(GoatDocument {
+            document_version: GOAT_DOCUMENT_VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            goat: goat.params,
+            weight_history,
+        })
+    }
+
+    /// Stores the goat carried by `document` as a new goat with its weight history,
+    /// creating any vaccine or disease it names that does not exist yet, and returns the
+    /// new id. Meant to run inside a caller-owned transaction.
+    ///
+    /// # Errors
+    /// Returns `AppError::InvalidInput` for an unsupported `document_version`,
+    /// `AppError::Conflict` if the goat's name is taken ignoring case, or a database error.
+    pub fn import_goat_document(
+        conn: &Connection,
+        document: &GoatDocument,
+    ) -> Result<i64, AppError> {
+        if document.document_version != GOAT_DOCUMENT_VERSION {
+            warn!(
+                version = document.document_version,
+                "Unsupported goat document version"
+            );
+            return Err(AppError::InvalidInput(format!(
+                "Unsupported goat document_version {} (expected {})",
+                document.document_version, GOAT_DOCUMENT_VERSION
+            )));
+        }
+
+        let mut params = document.goat.clone();
+        for vaccine in &mut params.vaccinations {
+            vaccine.id = None;
+        }
+        for disease in &mut params.diseases {
+            disease.id = None;
+        }
+        let goat_id = Self::insert_goat(conn, &params)?;
+        for entry in &document.weight_history {
+            timed_execute(
+                conn,
+                "INSERT INTO goat_weight_history (goat_id, weight, created_at) VALUES (?1, ?2, ?3)",
+                params![goat_id, entry.weight, entry.recorded_at],
+            )?;
+        }
+        info!(
+            goat_id,
+            weighings = document.weight_history.len(),
+            "Goat document imported"
+        );
+        Ok(goat_id)
+    }
+
+    /// Builds the livestock census for `year`, one query per section.
+    ///
+    /// Instants are compared as `YYYY-MM-DD` strings against stored timestamps, so the
+    /// year-end herd is the herd at `(year + 1)-01-01`. Births come from `birth_date`;
+    /// deleting a goat is how the herd records a death, so goats deleted after being sold
+    /// only count as sold.
+    ///
+    /// # Errors
+    /// Returns database errors raised by any of the queries.
+    pub fn annual_census(conn: &Connection, year: i32) -> Result<CensusReport, AppError> {
+        trace!(year, "Building annual census");
+        let year_start = format!("{:04}-01-01", year);
+        let year_end = format!("{:04}-01-01", year + 1);
+        let herd_at = |instant: &str| -> Result<i64, AppError> {
+            Ok(timed_query_row(
+                conn,
+                &format!("SELECT COUNT(*) FROM goats g WHERE {}", IN_HERD_AT),
+                [instant],
+                |r| r.get(0),
+            )?)
+        };
+
+        let by_breed = timed_query_map(
+            conn,
+            &format!(
+                "SELECT g.breed, COUNT(*), \
+                        SUM(g.gender = 'Male'), SUM(g.gender = 'Female'), SUM(g.gender = 'Wether') \
+                 FROM goats g WHERE {} GROUP BY g.breed ORDER BY g.breed",
+                IN_HERD_AT
+            ),
+            [&year_end],
+            |row| {
+                Ok(BreedStats {
+                    breed: row.get(0)?,
+                    total: row.get(1)?,
+                    male: row.get(2)?,
+                    female: row.get(3)?,
+                    wether: row.get(4)?,
+                })
+            },
+        )?;
+
+        let year_label = format!("{:04}", year);
+        let born = timed_query_row(
+            conn,
+            "SELECT COUNT(*) FROM goats WHERE strftime('%Y', birth_date) = ?1",
+            [&year_label],
+            |r| r.get(0),
+        )?;
+        let died = timed_query_row(
+            conn,
+            "SELECT COUNT(*) FROM goats g WHERE strftime('%Y', g.deleted_at) = ?1 \
+             AND NOT EXISTS (SELECT 1 FROM sales s \
+                             WHERE s.goat_id = g.id AND s.created_at <= g.deleted_at)",
+            [&year_label],
+            |r| r.get(0),
+        )?;
+        let sold = timed_query_row(
+            conn,
+            "SELECT COUNT(DISTINCT goat_id) FROM sales WHERE strftime('%Y', created_at) = ?1",
+            [&year_label],
+            |r| r.get(0),
+        )?;
+
+        let mut month_end_total = 0;
+        for month in 2..=12 {
+            month_end_total += herd_at(&format!("{:04}-{:02}-01", year, month))?;
+        }
+        let closing_count = herd_at(&year_end)?;
+        month_end_total += closing_count;
+
+        let report = CensusReport {
+            year,
+            opening_count: herd_at(&year_start)?,
+            closing_count,
+            by_breed,
+            born,
+            died,
+            sold,
+            average_herd_size: month_end_total as f64 / 12.0,
+        };
+        debug!(?report, "Annual census built");
+        Ok(report)
+    }
+
+    /// Averages the purchase cost and current price of live goats of `breed`, for
+    /// estimating what a new goat left out. Amounts are read like `financial_stats`, from
+    /// paise with a fallback to the legacy rupee columns.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn breed_price_hint(conn: &Connection, breed: &str) -> Result<BreedPriceHint, AppError> {
+        let hint = timed_query_row(
+            conn,
+            "SELECT AVG(COALESCE(cost_minor / 100.0, cost)), \
+                    AVG(COALESCE(current_price_minor / 100.0, current_price)) \
+             FROM goats WHERE breed = ?1 AND deleted_at IS NULL",
+            [breed],
+            |row| {
+                let to_paisa =
+                    |avg: Option<f64>| avg.map(|amount| (amount * 100.0).round() / 100.0);
+                Ok(BreedPriceHint {
+                    cost: to_paisa(row.get(0)?),
+                    current_price: to_paisa(row.get(1)?),
+                })
+            },
+        )?;
+        trace!(breed, ?hint, "Breed price hint computed");
+        Ok(hint)
+    }
+
+    /// Deletes sensor readings recorded before `cutoff` (SQLite timestamp form), at most
+    /// `batch_size` per transaction so other writers get the lock in between. With
+    /// `rollup`, each batch is first folded into the per-sensor, per-day summaries in
+    /// `sensor_reading_daily`, merging with any summary already there for that day.
+    ///
+    /// # Errors
+    /// Returns a database error; batches committed before it stay pruned.
+    pub fn prune_sensor_readings(
+        &self,
+        cutoff: &str,
+        rollup: bool,
+        batch_size: u32,
+    ) -> Result<SensorPruneSummary, AppError> {
+        let batch_size = batch_size.max(1);
+        let mut summary = SensorPruneSummary {
+            cutoff: cutoff.to_string(),
+            deleted: 0,
+            batches: 0,
+            rolled_up: rollup,
+        };
+        loop {
+            let deleted = self.with_write_retry(|tx| {
+                if rollup {
+                    timed_execute(
+                        tx,
+                        &format!(
+                            "INSERT INTO sensor_reading_daily \
+                                 (sensor_id, day, count, avg, min, max, anomalies) \
+                             SELECT sensor_id, date(recorded_at), COUNT(*), AVG(value), \
+                                    MIN(value), MAX(value), SUM(is_anomaly) \
+                             FROM sensor_readings WHERE id IN ({}) \
+                             GROUP BY sensor_id, date(recorded_at) \
+                             ON CONFLICT (sensor_id, day) DO UPDATE SET \
+                                 avg = (avg * count + excluded.avg * excluded.count) \
+                                       / (count + excluded.count), \
+                                 count = count + excluded.count, \
+                                 min = MIN(min, excluded.min), \
+                                 max = MAX(max, excluded.max), \
+                                 anomalies = anomalies + excluded.anomalies",
+                            SENSOR_PRUNE_IDS
+                        ),
+                        params![cutoff, batch_size],
+                    )?;
+                }
+                Ok(timed_execute(
+                    tx,
+                    &format!(
+                        "DELETE FROM sensor_readings WHERE id IN ({})",
+                        SENSOR_PRUNE_IDS
+                    ),
+                    params![cutoff, batch_size],
+                )?)
+            })?;
+            if deleted == 0 {
+                break;
+            }
+            summary.deleted += deleted;
+            summary.batches += 1;
+            debug!(
+                deleted,
+                batch = summary.batches,
+                "Pruned a batch of sensor readings"
+            );
+            if deleted < batch_size as usize {
+                break;
+            }
+        }
+        info!(?summary, "Sensor readings pruned");
+        Ok(summary)
+    }
+
+    /// A goat's weighings as `(unix_secs, kg)` pairs, oldest first, for trend fitting.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat does not exist, or a database error.
+    pub fn weight_history_points(
+        conn: &Connection,
+        goat_id: i64,
+    ) -> Result<Vec<(i64, f64)>, AppError> {
+        ensure_goat_exists(conn, goat_id)?;
+        Ok(timed_query_map(
+            conn,
+            "SELECT CAST(strftime('%s', created_at) AS INTEGER), weight \
+             FROM goat_weight_history WHERE goat_id = ?1 ORDER BY created_at, id",
+            [goat_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?)
+    }
+
+    /// Current herd size and per-head yearly birth, death and sale rates over the year
+    /// before `as_of`, for seeding `simulate_population`.
+    ///
+    /// Events are counted as in `annual_census` and divided by the average of the herd
+    /// at the start of the window and now; all rates are zero when both are empty.
+    pub fn population_rates(
+        conn: &Connection,
+        as_of: NaiveDate,
+    ) -> Result<PopulationRates, AppError> {
+        trace!(%as_of, "Measuring population rates");
+        let window_start = (as_of - chrono::Duration::days(365)).to_string();
+        let window_end = (as_of + chrono::Duration::days(1)).to_string();
+        let herd_at = |instant: &str| -> Result<i64, AppError> {
+            Ok(timed_query_row(
+                conn,
+                &format!("SELECT COUNT(*) FROM goats g WHERE {}", IN_HERD_AT),
+                [instant],
+                |r| r.get(0),
+            )?)
+        };
+        let opening = herd_at(&window_start)?;
+        let current = herd_at(&window_end)?;
+
+        let (born, died, sold): (i64, i64, i64) = timed_query_row(
+            conn,
+            "SELECT \
+                (SELECT COUNT(*) FROM goats WHERE birth_date >= ?1 AND birth_date < ?2), \
+                (SELECT COUNT(*) FROM goats g WHERE g.deleted_at >= ?1 AND g.deleted_at < ?2 \
+                 AND NOT EXISTS (SELECT 1 FROM sales s \
+                                 WHERE s.goat_id = g.id AND s.created_at <= g.deleted_at)), \
+                (SELECT COUNT(DISTINCT goat_id) FROM sales WHERE created_at >= ?1 AND created_at < ?2)",
+            [&window_start, &window_end],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )?;
+
+        let average_herd = (opening + current) as f64 / 2.0;
+        let rate = |events: i64| {
+            if average_herd > 0.0 {
+                events as f64 / average_herd
+            } else {
+                0.0
+            }
+        };
+        let rates = PopulationRates {
+            initial_herd: current as i32,
+            breeding_rate: rate(born),
+            mortality_rate: rate(died),
+            sales_rate: rate(sold),
+        };
+        debug!(
+            opening,
+            current, born, died, sold, "Measured population rates"
+        );
+        Ok(rates)
+    }
+
+    /// Every space with its map position, if placed, and current occupancy, ordered by
+    /// id. `only_space` narrows the result to that one space.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn farm_map(
+        conn: &Connection,
+        only_space: Option<i64>,
+    ) -> Result<Vec<SpaceLayout>, AppError> {
+        trace!(?only_space, "Loading farm map");
+        let layouts = timed_query_map(
+            conn,
+            "SELECT s.id, s.name, s.capacity, \
+                    (SELECT COUNT(*) FROM space_goats sg JOIN goats g ON g.id = sg.goat_id \
+                     WHERE sg.space_id = s.id AND g.deleted_at IS NULL), \
+                    m.x, m.y, m.width, m.height \
+             FROM spaces s LEFT JOIN farm_map m ON m.space_id = s.id \
+             WHERE ?1 IS NULL OR s.id = ?1 ORDER BY s.id",
+            [only_space],
+            |row| {
+                let capacity: Option<i64> = row.get(2)?;
+                let occupancy: i64 = row.get(3)?;
+                let position = match row.get::<_, Option<f64>>(4)? {
+                    Some(x) => Some(MapPosition {
+                        x,
+                        y: row.get(5)?,
+                        width: row.get(6)?,
+                        height: row.get(7)?,
+                    }),
+                    None => None,
+                };
+                Ok(SpaceLayout {
+                    space_id: row.get(0)?,
+                    name: row.get(1)?,
+                    capacity,
+                    occupancy,
+                    occupancy_pct: capacity
+                        .filter(|&capacity| capacity > 0)
+                        .map(|capacity| round2(occupancy as f64 * 100.0 / capacity as f64)),
+                    position,
+                })
+            },
+        )?;
+        debug!(count = layouts.len(), "Farm map loaded");
+        Ok(layouts)
+    }
+
+    /// Places a space on the farm map, replacing any earlier position, and returns its
+    /// updated layout.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown space, or a database error.
+    pub fn set_space_position(
+        conn: &Connection,
+        space_id: i64,
+        position: &MapPosition,
+    ) -> Result<SpaceLayout, AppError> {
+        Self::get_space(conn, space_id)?;
+        timed_execute(
+            conn,
+            "INSERT INTO farm_map (space_id, x, y, width, height) VALUES (?1, ?2, ?3, ?4, ?5) \
+             ON CONFLICT(space_id) DO UPDATE SET x = excluded.x, y = excluded.y, \
+             width = excluded.width, height = excluded.height",
+            params![
+                space_id,
+                position.x,
+                position.y,
+                position.width,
+                position.height
+            ],
+        )?;
+        debug!(space_id, "Space placed on farm map");
+        Self::farm_map(conn, Some(space_id))?
+            .pop()
+            .ok_or_else(|| AppError::NotFound(format!("No space found with id {}", space_id)))
+    }
+
+    /// Every vaccine in the catalog, ordered by name ignoring case.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn list_vaccines(conn: &Connection) -> Result<Vec<VaccineRef>, AppError> {
+        query_all(
+            conn,
+            "SELECT id, name FROM vaccines ORDER BY name COLLATE NOCASE, id",
+            |row| {
+                Ok(VaccineRef {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                })
+            },
+        )
+    }
+
+    /// Every disease in the catalog, ordered by name ignoring case.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn list_diseases(conn: &Connection) -> Result<Vec<DiseaseRef>, AppError> {
+        query_all(
+            conn,
+            "SELECT id, name FROM diseases ORDER BY name COLLATE NOCASE, id",
+            |row| {
+                Ok(DiseaseRef {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                })
+            },
+        )
+    }
+
+    /// Every recorded parentage, by goat id.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn load_pedigree(conn: &Connection) -> Result<Pedigree, AppError> {
+        let rows = query_all(
+            conn,
+            "SELECT goat_id, sire_id, dam_id FROM goat_pedigree",
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    Parents {
+                        sire_id: row.get(1)?,
+                        dam_id: row.get(2)?,
+                    },
+                ))
+            },
+        )?;
+        trace!(count = rows.len(), "Pedigree loaded");
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Records a goat's parents, replacing any earlier record.
+    ///
+    /// # Errors
+    /// Returns a database error if the write fails.
+    pub fn set_pedigree(
+        conn: &Connection,
+        goat_id: i64,
+        parents: &Parents,
+    ) -> Result<(), AppError> {
+        timed_execute(
+            conn,
+            "INSERT INTO goat_pedigree (goat_id, sire_id, dam_id) VALUES (?1, ?2, ?3) \
+             ON CONFLICT(goat_id) DO UPDATE SET sire_id = excluded.sire_id, dam_id = excluded.dam_id",
+            params![goat_id, parents.sire_id, parents.dam_id],
+        )?;
+        debug!(goat_id, ?parents, "Pedigree recorded");
+        Ok(())
+    }
+
+    /// Gender of a live goat, or `None` if there is no such goat.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails or the stored gender is unknown.
+    pub fn live_goat_gender(conn: &Connection, goat_id: i64) -> Result<Option<Gender>, AppError> {
+        let gender: Option<String> = timed_query_row(
+            conn,
+            "SELECT gender FROM goats WHERE id = ?1 AND deleted_at IS NULL",
+            [goat_id],
+            |r| r.get(0),
+        )
+        .optional()?;
+        gender.as_deref().map(str_to_gender).transpose()
+    }
+
+    /// Attaches a file to a goat and returns its listing.
+    ///
+    /// # Errors
+    /// Returns a database error if the write fails.
+    pub fn insert_goat_document(
+        conn: &Connection,
+        goat_id: i64,
+        filename: &str,
+        content_type: &str,
+        content: &[u8],
+    ) -> Result<AttachedDocument, AppError> {
+        let document = timed_query_row(
+            conn,
+            "INSERT INTO goat_documents (goat_id, filename, content_type, size, content) \
+             VALUES (?1, ?2, ?3, ?4, ?5) \
+             RETURNING id, goat_id, filename, content_type, size, uploaded_at",
+            params![
+                goat_id,
+                filename,
+                content_type,
+                content.len() as i64,
+                content
+            ],
+            row_to_attached_document,
+        )?;
+        debug!(
+            goat_id,
+            document_id = document.id,
+            size = document.size,
+            "Document attached"
+        );
+        Ok(document)
+    }
+
+    /// Files attached to a goat, oldest first, without their content.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn goat_documents(
+        conn: &Connection,
+        goat_id: i64,
+    ) -> Result<Vec<AttachedDocument>, AppError> {
+        Ok(timed_query_map(
+            conn,
+            "SELECT id, goat_id, filename, content_type, size, uploaded_at \
+             FROM goat_documents WHERE goat_id = ?1 ORDER BY id",
+            [goat_id],
+            row_to_attached_document,
+        )?)
+    }
+
+    /// An attached file with its content, or `None` if there is no such document.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn goat_document_content(
+        conn: &Connection,
+        document_id: i64,
+    ) -> Result<Option<(AttachedDocument, Vec<u8>)>, AppError> {
+        Ok(timed_query_row(
+            conn,
+            "SELECT id, goat_id, filename, content_type, size, uploaded_at, content \
+             FROM goat_documents WHERE id = ?1",
+            [document_id],
+            |row| Ok((row_to_attached_document(row)?, row.get(6)?)),
+        )
+        .optional()?)
+    }
+
+    /// Every reason a live goat needs attention as of `today`, by condition: current
+    /// diseases, quarantine (a `health_status` of `quarantine` or `quarantined`), overdue
+    /// vaccinations, a weight outside `thresholds`, and no weighing recorded for more than
+    /// `thresholds.weight_stale_days` (counted from when the goat was added if it was never
+    /// weighed). Conditions whose tables the database lacks are skipped.
+    ///
+    /// # Errors
+    /// Returns a database error if a query fails.
+    pub fn attention_flags(
+        conn: &Connection,
+        today: NaiveDate,
+        thresholds: &AttentionThresholds,
+    ) -> Result<Vec<AttentionFlag>, AppError> {
+        let flag =
+            |goat_id: i64, goat_name: String, kind: AttentionKind, detail: String| AttentionFlag {
+                goat_id,
+                goat_name,
+                reason: AttentionReason { kind, detail },
+            };
+        let mut flags = Vec::new();
+
+        if has_tables(conn, &["goat_diseases", "diseases"])? {
+            flags.extend(timed_query_map(
+                conn,
+                "SELECT g.id, g.name, d.name FROM goat_diseases gd \
+                 JOIN goats g ON g.id = gd.goat_id \
+                 JOIN diseases d ON d.id = gd.disease_id \
+                 WHERE g.deleted_at IS NULL ORDER BY g.id, d.name",
+                [],
+                |row| {
+                    Ok(flag(
+                        row.get(0)?,
+                        row.get(1)?,
+                        AttentionKind::ActiveDisease,
+                        format!("Has {}", row.get::<_, String>(2)?),
+                    ))
+                },
+            )?);
+        } else {
+            debug!("No disease tables; skipping active disease check");
+        }
+
+        flags.extend(timed_query_map(
+            conn,
+            "SELECT id, name, health_status FROM goats WHERE deleted_at IS NULL \
+             AND lower(trim(health_status)) IN ('quarantine', 'quarantined') ORDER BY id",
+            [],
+            |row| {
+                Ok(flag(
+                    row.get(0)?,
+                    row.get(1)?,
+                    AttentionKind::Quarantine,
+                    format!("Health status is {}", row.get::<_, String>(2)?),
+                ))
+            },
+        )?);
+
+        if has_tables(conn, &["vaccination_schedule", "vaccines"])? {
+            flags.extend(
+                DbPool::overdue_vaccinations(conn, today)?
+                    .into_iter()
+                    .map(|overdue| {
+                        flag(
+                            overdue.goat_id,
+                            overdue.goat_name,
+                            AttentionKind::OverdueVaccination,
+                            format!(
+                                "{} was due on {} ({} days overdue)",
+                                overdue.vaccine, overdue.due_on, overdue.days_overdue
+                            ),
+                        )
+                    }),
+            );
+        } else {
+            debug!("No vaccination schedule; skipping overdue vaccination check");
+        }
+
+        flags.extend(timed_query_map(
+            conn,
+            "SELECT id, name, weight FROM goats WHERE deleted_at IS NULL \
+             AND (weight < ?1 OR weight > ?2) ORDER BY id",
+            params![thresholds.min_weight_kg, thresholds.max_weight_kg],
+            |row| {
+                let weight: f64 = row.get(2)?;
+                let (kind, detail) = if weight < thresholds.min_weight_kg {
+                    (
+                        AttentionKind::Underweight,
+                        format!(
+                            "Weighs {} kg, under {} kg",
+                            weight, thresholds.min_weight_kg
+                        ),
+                    )
+                } else {
+                    (
+                        AttentionKind::Overweight,
+                        format!("Weighs {} kg, over {} kg", weight, thresholds.max_weight_kg),
+                    )
+                };
+                Ok(flag(row.get(0)?, row.get(1)?, kind, detail))
+            },
+        )?);
+
+        if has_tables(conn, &["goat_weight_history"])? {
+            flags.extend(timed_query_map(
+                conn,
+                "SELECT g.id, g.name, MAX(h.created_at), g.created_at FROM goats g \
+                 LEFT JOIN goat_weight_history h ON h.goat_id = g.id \
+                 WHERE g.deleted_at IS NULL GROUP BY g.id \
+                 HAVING julianday(COALESCE(MAX(h.created_at), g.created_at)) \
+                        < julianday(?1) - ?2 \
+                 ORDER BY g.id",
+                params![today.to_string(), thresholds.weight_stale_days],
+                |row| {
+                    let detail = match row.get::<_, Option<String>>(2)? {
+                        Some(weighed) => format!("Last weighed at {}", weighed),
+                        None => format!(
+                            "Never weighed since being added at {}",
+                            row.get::<_, String>(3)?
+                        ),
+                    };
+                    Ok(flag(
+                        row.get(0)?,
+                        row.get(1)?,
+                        AttentionKind::StaleWeight,
+                        detail,
+                    ))
+                },
+            )?);
+        } else {
+            debug!("No weight history; skipping stale weight check");
+        }
+
+        trace!(count = flags.len(), "Attention flags loaded");
+        Ok(flags)
+    }
+
+    /// Exports the entity tables as a ZIP archive: one CSV file per table, with a header
+    /// row of column names, plus a `manifest.json` (`ExportManifest`). Deleted goats are
+    /// left out. Every table is read inside one immediate transaction, so the files agree
+    /// with each other.
+    ///
+    /// # Errors
+    /// Returns a database error if a query fails, or `AppError::Internal` if the archive
+    /// cannot be written.
+    pub fn export_full_snapshot(conn: &Connection) -> Result<Vec<u8>, AppError> {
+        trace!("Exporting full archive");
+        let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+        let archive_error = |e: &dyn std::fmt::Display| {
+            AppError::Internal(format!("Failed to write export archive: {}", e))
+        };
+
+        let options = zip::write::SimpleFileOptions::default();
+        let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let mut record_counts = std::collections::BTreeMap::new();
+        for (file, sql) in FULL_EXPORT_TABLES {
+            let (csv, rows) = query_csv(&tx, sql)?;
+            archive
+                .start_file(*file, options)
+                .map_err(|e| archive_error(&e))?;
+            archive
+                .write_all(csv.as_bytes())
+                .map_err(|e| archive_error(&e))?;
+            record_counts.insert(file.to_string(), rows);
+        }
+        tx.commit()?;
+
+        let manifest = ExportManifest {
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            record_counts,
+        };
+        let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| archive_error(&e))?;
+        archive
+            .start_file("manifest.json", options)
+            .map_err(|e| archive_error(&e))?;
+        archive
+            .write_all(&manifest_json)
+            .map_err(|e| archive_error(&e))?;
+        let bytes = archive
+            .finish()
+            .map_err(|e| archive_error(&e))?
+            .into_inner();
+
+        info!(counts = ?manifest.record_counts, size = bytes.len(), "Full archive exported");
+        Ok(bytes)
+    }
+
+    /// Stores a pending leave request and returns it.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the worker does not exist, `AppError::Conflict` if
+    /// the dates overlap leave already approved for the worker, or a database error.
+    pub fn insert_leave_request(
+        conn: &Connection,
+        request: &LeaveRequest,
+    ) -> Result<LeaveRequest, AppError> {
+        ensure_worker_exists(conn, request.worker_id)?;
+        ensure_no_approved_leave_overlap(conn, request, None)?;
+        let id = timed_query_row(
+            conn,
+            "INSERT INTO leave_requests (worker_id, from_date, to_date, reason) \
+             VALUES (?1, ?2, ?3, ?4) RETURNING id",
+            params![
+                request.worker_id,
+                request.from_date.trim(),
+                request.to_date.trim(),
+                request.reason
+            ],
+            |r| r.get(0),
+        )?;
+        info!(
+            worker_id = request.worker_id,
+            leave_request_id = id,
+            "Leave requested"
+        );
+        Self::get_leave_request(conn, id)
+    }
+
+    /// Loads one leave request.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such request, or a database error.
+    pub fn get_leave_request(conn: &Connection, id: i64) -> Result<LeaveRequest, AppError> {
+        timed_query_row(
+            conn,
+            &format!(
+                "SELECT {} FROM leave_requests WHERE id = ?1",
+                LEAVE_REQUEST_COLUMNS
+            ),
+            [id],
+            row_to_leave_request,
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("No leave request found with id {}", id)))
+    }
+
+    /// Approves or rejects a pending leave request and returns it. Approval adds the
+    /// request's days, both ends included, to the worker's `leaves` total; run this in a
+    /// transaction so the two writes land together.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown request, `AppError::Conflict` if it
+    /// was already decided or, on approval, overlaps leave already approved for the
+    /// worker, or a database error.
+    pub fn decide_leave_request(
+        conn: &Connection,
+        id: i64,
+        decision: &LeaveDecision,
+    ) -> Result<LeaveRequest, AppError> {
+        let request = Self::get_leave_request(conn, id)?;
+        if request.status != "pending" {
+            return Err(AppError::Conflict(format!(
+                "Leave request {} was already {}",
+                id, request.status
+            )));
+        }
+
+        if decision.status == "approved" {
+            ensure_no_approved_leave_overlap(conn, &request, Some(id))?;
+            let from = parse_iso_date("from_date", &request.from_date)?;
+            let to = parse_iso_date("to_date", &request.to_date)?;
+            let days = (to - from).num_days() + 1;
+            timed_execute(
+                conn,
+                "UPDATE workers SET leaves = COALESCE(leaves, 0) + ?2 WHERE id = ?1",
+                params![request.worker_id, days],
+            )?;
+            debug!(worker_id = request.worker_id, days, "Leave days added");
+        }
+        timed_execute(
+            conn,
+            "UPDATE leave_requests SET status = ?2, decided_by = ?3, \
+             decided_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            params![id, decision.status, decision.decided_by.trim()],
+        )?;
+        info!(leave_request_id = id, status = %decision.status, "Leave request decided");
+        Self::get_leave_request(conn, id)
+    }
+
+    /// Leave requests overlapping the days `from` to `to` (either end open when `None`),
+    /// optionally only those with `status`, ordered by start date.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn list_leave_requests(
+        conn: &Connection,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        status: Option<&str>,
+    ) -> Result<Vec<LeaveRequest>, AppError> {
+        Ok(timed_query_map(
+            conn,
+            &format!(
+                "SELECT {} FROM leave_requests \
+                 WHERE (?1 IS NULL OR to_date >= ?1) AND (?2 IS NULL OR from_date <= ?2) \
+                 AND (?3 IS NULL OR status = ?3) \
+                 ORDER BY from_date, id",
+                LEAVE_REQUEST_COLUMNS
+            ),
+            params![
+                from.map(|d| d.to_string()),
+                to.map(|d| d.to_string()),
+                status
+            ],
+            row_to_leave_request,
+        )?)
+    }
+
+    /// Runs a vaccination campaign: resolves `campaign.vaccine` once, then links it to
+    /// each listed goat and records a `vaccination_schedule` row for the dose. When a
+    /// medicine inventory item shares the vaccine's name (ignoring case), `dose_units` are
+    /// dispensed from it per goat. Should run inside a transaction.
+    ///
+    /// Goats that are unknown, soft-deleted, listed twice, or cannot be given a dose from
+    /// the remaining stock are reported as failures and left untouched; the others are
+    /// still vaccinated.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown vaccine id, `AppError::InvalidInput`
+    /// for a blank vaccine name, or a database error.
+    pub fn run_vaccination_campaign(
+        conn: &Connection,
+        campaign: &VaccinationCampaign,
+    ) -> Result<CampaignSummary, AppError> {
+        let vaccine_id = get_or_insert_vaccine(conn, &campaign.vaccine)?.id();
+        let vaccine_name: String = timed_query_row(
+            conn,
+            "SELECT name FROM vaccines WHERE id = ?1",
+            [vaccine_id],
+            |r| r.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("Vaccine {} not found", vaccine_id)))?;
+        let stock_medicine_id = find_id(
+            conn,
+            "medicine_inventory",
+            "name = ?1 COLLATE NOCASE ORDER BY id",
+            [&vaccine_name],
+        )?;
+        let administered_on = campaign.administered_on.trim();
+        let next_due_on = campaign.next_due_on.trim();
+        let reason = format!("Vaccination campaign: {}", vaccine_name);
+
+        let mut seen = HashSet::new();
+        let mut results = Vec::with_capacity(campaign.goat_ids.len());
+        for &goat_id in &campaign.goat_ids {
+            let failure = if !seen.insert(goat_id) {
+                Some("Goat is listed more than once".to_string())
+            } else if !goat_is_live(conn, goat_id)? {
+                Some(format!("Goat {} not found", goat_id))
+            } else if let Some(medicine_id) = stock_medicine_id {
+                match Self::dispense_medicine(
+                    conn,
+                    medicine_id,
+                    goat_id,
+                    campaign.dose_units,
+                    &reason,
+                ) {
+                    Ok(_) => None,
+                    Err(AppError::Conflict(message)) => Some(message),
+                    Err(e) => return Err(e),
+                }
+            } else {
+                None
+            };
+            if let Some(error) = failure {
+                debug!(goat_id, error = %error, "Goat skipped by vaccination campaign");
+                results.push(CampaignGoatResult {
+                    goat_id,
+                    success: false,
+                    error: Some(error),
+                });
+                continue;
+            }
+
+            timed_execute(
+                conn,
+                "INSERT OR IGNORE INTO goat_vaccines (goat_id, vaccine_id, created_at) \
+                 VALUES (?1, ?2, CURRENT_TIMESTAMP)",
+                params![goat_id, vaccine_id],
+            )?;
+            timed_execute(
+                conn,
+                "INSERT INTO vaccination_schedule (goat_id, vaccine_id, administered_on, next_due_on) \
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![goat_id, vaccine_id, administered_on, next_due_on],
+            )?;
+            results.push(CampaignGoatResult {
+                goat_id,
+                success: true,
+                error: None,
+            });
+        }
+
+        let vaccinated = results.iter().filter(|r| r.success).count();
+        info!(
+            vaccine_id,
+            vaccinated,
+            failed = results.len() - vaccinated,
+            "Vaccination campaign recorded"
+        );
+        Ok(CampaignSummary {
+            vaccine_id,
+            vaccinated,
+            stock_medicine_id,
+            results,
+        })
+    }
+
+    /// Logs a worker's hours for one day and returns the stored entry.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the worker does not exist, or a database error.
+    pub fn insert_time_entry(conn: &Connection, entry: &TimeEntry) -> Result<TimeEntry, AppError> {
+        ensure_worker_exists(conn, entry.worker_id)?;
+        timed_execute(
+            conn,
+            "INSERT INTO worker_time_entries (worker_id, work_date, hours, note) \
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                entry.worker_id,
+                entry.work_date.trim(),
+                entry.hours,
+                entry
+                    .note
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|n| !n.is_empty())
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        debug!(
+            id,
+            worker_id = entry.worker_id,
+            hours = entry.hours,
+            "Time entry logged"
+        );
+        Ok(timed_query_row(
+            conn,
+            &format!(
+                "SELECT {} FROM worker_time_entries WHERE id = ?1",
+                TIME_ENTRY_COLUMNS
+            ),
+            [id],
+            row_to_time_entry,
+        )?)
+    }
+
+    /// Sets a worker's own hourly rate and overtime threshold, clearing either that is
+    /// `None` so the farm default applies again. Returns the updated worker.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the worker does not exist, or a database error.
+    pub fn set_worker_pay_terms(
+        conn: &Connection,
+        worker_id: i64,
+        terms: &WorkerPayTerms,
+    ) -> Result<Worker, AppError> {
+        ensure_worker_exists(conn, worker_id)?;
+        timed_execute(
+            conn,
+            "UPDATE workers SET hourly_rate = ?2, overtime_threshold_hours = ?3 WHERE id = ?1",
+            params![worker_id, terms.hourly_rate, terms.overtime_threshold_hours],
+        )?;
+        info!(worker_id, hourly_rate = ?terms.hourly_rate, overtime_threshold_hours = ?terms.overtime_threshold_hours, "Worker pay terms updated");
+        Ok(timed_query_row(
+            conn,
+            &format!("SELECT {} FROM workers WHERE id = ?1", WORKER_COLUMNS),
+            [worker_id],
+            row_to_worker,
+        )?)
+    }
+
+    /// Computes pay for the days `first` to `last` (one calendar month) for `worker_id`,
+    /// or for every worker when `None`, ordered by worker id. Hours come from time
+    /// entries and leave days from approved leave requests, counting only the days that
+    /// fall in the month. Workers without their own rate or threshold are paid on
+    /// `defaults`; a month without entries yields zeros.
+    ///
+    /// # Errors
+    /// Returns a database error if the query fails.
+    pub fn monthly_payroll(
+        conn: &Connection,
+        worker_id: Option<i64>,
+        first: NaiveDate,
+        last: NaiveDate,
+        defaults: &PayTerms,
+    ) -> Result<Vec<WorkerPayroll>, AppError> {
+        let month = first.format("%Y-%m").to_string();
+        trace!(%month, ?worker_id, "Computing payroll");
+        let rows = timed_query_map(
+            conn,
+            "SELECT w.id, w.name, w.hourly_rate, w.overtime_threshold_hours, \
+                (SELECT COALESCE(SUM(t.hours), 0.0) FROM worker_time_entries t \
+                 WHERE t.worker_id = w.id AND t.work_date BETWEEN ?1 AND ?2), \
+                (SELECT CAST(COALESCE(SUM(julianday(MIN(l.to_date, ?2)) \
+                                          - julianday(MAX(l.from_date, ?1)) + 1), 0) AS INTEGER) \
+                 FROM leave_requests l \
+                 WHERE l.worker_id = w.id AND l.status = 'approved' \
+                 AND l.from_date <= ?2 AND l.to_date >= ?1) \
+             FROM workers w WHERE (?3 IS NULL OR w.id = ?3) ORDER BY w.id",
+            params![first.to_string(), last.to_string(), worker_id],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<f64>>(2)?,
+                    row.get::<_, Option<f64>>(3)?,
+                    row.get::<_, f64>(4)?,
+                    row.get::<_, i64>(5)?,
+                ))
+            },
+        )?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(worker_id, worker_name, rate, threshold, hours, leave_days)| {
+                    let terms = PayTerms {
+                        hourly_rate: rate.unwrap_or(defaults.hourly_rate),
+                        overtime_threshold_hours: threshold
+                            .unwrap_or(defaults.overtime_threshold_hours),
+                        overtime_multiplier: defaults.overtime_multiplier,
+                    };
+                    let pay = compute_monthly_pay(hours, &terms);
+                    WorkerPayroll {
+                        worker_id,
+                        worker_name,
+                        month: month.clone(),
+                        hours_worked: round2(hours),
+                        leave_days,
+                        overtime_hours: pay.overtime_hours,
+                        hourly_rate: terms.hourly_rate,
+                        overtime_threshold_hours: terms.overtime_threshold_hours,
+                        gross_pay: pay.gross_pay,
+                    }
+                },
+            )
+            .collect())
+    }
+
+    /// Lists every stay of a live goat in a space, oldest first.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if the goat does not exist, or a database error.
+    pub fn goat_space_movements(
+        conn: &Connection,
+        goat_id: i64,
+    ) -> Result<Vec<SpaceMovement>, AppError> {
+        ensure_goat_exists(conn, goat_id)?;
+        let movements = timed_query_map(
+            conn,
+            "SELECT h.space_id, s.name, h.moved_in_at, h.moved_out_at \
+             FROM goat_space_history h JOIN spaces s ON s.id = h.space_id \
+             WHERE h.goat_id = ?1 ORDER BY h.moved_in_at, h.id",
+            [goat_id],
+            |r| {
+                Ok(SpaceMovement {
+                    space_id: r.get(0)?,
+                    space_name: r.get(1)?,
+                    moved_in_at: r.get(2)?,
+                    moved_out_at: r.get(3)?,
+                })
+            },
+        )?;
+        debug!(goat_id, count = movements.len(), "Goat movements loaded");
+        Ok(movements)
+    }
+
+    /// Lists the goats that were in a space at `at` (a `YYYY-MM-DD HH:MM:SS` UTC
+    /// timestamp): those moved in at or before it and not moved out until after it.
+    /// Goats sold or deleted since are included, so contacts can still be traced.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` for an unknown space, or a database error.
+    pub fn space_residents_at(
+        conn: &Connection,
+        space_id: i64,
+        at: &str,
+    ) -> Result<Vec<SpaceResident>, AppError> {
+        Self::get_space(conn, space_id)?;
+        let residents = timed_query_map(
+            conn,
+            "SELECT h.goat_id, g.name, h.moved_in_at, h.moved_out_at \
+             FROM goat_space_history h JOIN goats g ON g.id = h.goat_id \
+             WHERE h.space_id = ?1 AND h.moved_in_at <= ?2 \
+             AND (h.moved_out_at IS NULL OR h.moved_out_at > ?2) \
+             ORDER BY h.goat_id",
+            params![space_id, at],
+            |r| {
+                Ok(SpaceResident {
+                    goat_id: r.get(0)?,
+                    name: r.get(1)?,
+                    moved_in_at: r.get(2)?,
+                    moved_out_at: r.get(3)?,
+                })
+            },
+        )?;
+        debug!(
+            space_id,
+            at,
+            count = residents.len(),
+            "Space residents loaded"
+        );
+        Ok(residents)
+    }
 }
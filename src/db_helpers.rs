@@ -4,15 +4,24 @@
 //! along with detailed logging and error handling.
 
 use crate::errors::{AppError, ParseEnumError};
-use shared::{Breed, Gender};
+use crate::models::{Breed, Diet, Gender};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use std::sync::LazyLock;
 use tracing::{debug, trace};
 
+/// Canonical form of a goat name: surrounding whitespace trimmed and internal runs of
+/// whitespace collapsed to a single space.
+pub fn normalize_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 /// Converts a database string to `Gender` enum with detailed error reporting.
 pub fn str_to_gender(s: &str) -> Result<Gender, AppError> {
     trace!("Parsing Gender from '{}'", s);
     match s {
         "Male" => Ok(Gender::Male),
         "Female" => Ok(Gender::Female),
+        "Wether" => Ok(Gender::Wether),
         other => {
             debug!("Failed to parse Gender enum from '{}'", other);
             Err(AppError::ParseError(ParseEnumError::new(other, "Gender")))
@@ -25,26 +34,37 @@ pub fn gender_to_str(gender: &Gender) -> &str {
     match gender {
         Gender::Male => "Male",
         Gender::Female => "Female",
+        Gender::Wether => "Wether",
     }
 }
 
+/// Built-in `Breed` variants with their stored names, in declaration order; anything
+/// else is `Other`.
+static BREED_TABLE: [(&str, Breed); 10] = [
+    ("Beetal", Breed::Beetal),
+    ("Jamunapari", Breed::Jamunapari),
+    ("Barbari", Breed::Barbari),
+    ("Sirohi", Breed::Sirohi),
+    ("Osmanabadi", Breed::Osmanabadi),
+    ("BlackBengal", Breed::BlackBengal),
+    ("Kutchi", Breed::Kutchi),
+    ("Kaghani", Breed::Kaghani),
+    ("Chegu", Breed::Chegu),
+    ("Jakhrana", Breed::Jakhrana),
+];
+
+/// Names of the built-in `Breed` variants in declaration order, from `BREED_TABLE`.
+pub static BUILTIN_BREEDS: LazyLock<Vec<&'static str>> =
+    LazyLock::new(|| BREED_TABLE.iter().map(|(name, _)| *name).collect());
+
 /// Converts a database string to `Breed` enum, treating unknown values as `Other`.
 pub fn str_to_breed(s: &str) -> Result<Breed, AppError> {
     trace!("Parsing Breed from '{}'", s);
-    match s {
-        "Beetal" => Ok(Breed::Beetal),
-        "Jamunapari" => Ok(Breed::Jamunapari),
-        "Barbari" => Ok(Breed::Barbari),
-        "Sirohi" => Ok(Breed::Sirohi),
-        "Osmanabadi" => Ok(Breed::Osmanabadi),
-        "BlackBengal" => Ok(Breed::BlackBengal),
-        "Kutchi" => Ok(Breed::Kutchi),
-        "Kaghani" => Ok(Breed::Kaghani),
-        "Chegu" => Ok(Breed::Chegu),
-        "Jakhrana" => Ok(Breed::Jakhrana),
-        other => {
-            debug!("Unknown Breed '{}', mapping to Other", other);
-            Ok(Breed::Other(other.to_string()))
+    match BREED_TABLE.iter().find(|(name, _)| *name == s) {
+        Some((_, breed)) => Ok(breed.clone()),
+        None => {
+            debug!("Unknown Breed '{}', mapping to Other", s);
+            Ok(Breed::Other(s.to_string()))
         }
     }
 }
@@ -65,3 +85,87 @@ pub fn breed_to_str(breed: &Breed) -> &str {
         Breed::Other(name) => name,
     }
 }
+
+/// Converts a database string to `Diet`, ignoring case; unknown values map to `Other`.
+///
+/// Reads are lenient so that legacy free-text diets still load.
+pub fn str_to_diet(s: &str) -> Diet {
+    trace!("Parsing Diet from '{}'", s);
+    parse_diet(s).unwrap_or_else(|_| {
+        debug!("Unknown Diet '{}', mapping to Other", s);
+        Diet::Other(s.to_string())
+    })
+}
+
+/// Strictly parses a diet supplied through the API, ignoring case and surrounding
+/// whitespace. "grass" and "grazing" are accepted as `Pasture`.
+///
+/// # Errors
+/// Returns `AppError::ParseError` for anything that is not a known diet.
+pub fn parse_diet(s: &str) -> Result<Diet, AppError> {
+    match s.trim().to_lowercase().as_str() {
+        "hay" => Ok(Diet::Hay),
+        "pasture" | "grass" | "grazing" => Ok(Diet::Pasture),
+        "mixed" => Ok(Diet::Mixed),
+        "concentrate" => Ok(Diet::Concentrate),
+        _ => Err(AppError::ParseError(ParseEnumError::new(s, "Diet"))),
+    }
+}
+
+/// Converts a `Diet` to its canonical database string.
+pub fn diet_to_str(diet: &Diet) -> &str {
+    match diet {
+        Diet::Hay => "Hay",
+        Diet::Pasture => "Pasture",
+        Diet::Mixed => "Mixed",
+        Diet::Concentrate => "Concentrate",
+        Diet::Other(name) => name,
+    }
+}
+
+/// Parses a `YYYY-MM-DD` string into a `NaiveDate`, naming the offending field on failure.
+pub fn parse_iso_date(field: &str, s: &str) -> Result<NaiveDate, AppError> {
+    trace!("Parsing {} from '{}'", field, s);
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").map_err(|e| {
+        debug!("Failed to parse {} '{}': {}", field, s, e);
+        AppError::InvalidInput(format!(
+            "{} must be a date in YYYY-MM-DD format, got '{}'",
+            field, s
+        ))
+    })
+}
+
+/// Format SQLite uses for `CURRENT_TIMESTAMP`, in UTC.
+pub const SQLITE_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Parses a client timestamp into SQLite's `YYYY-MM-DD HH:MM:SS` UTC form.
+///
+/// Accepts the SQLite form itself, RFC 3339 (converted to UTC), or a bare date
+/// (interpreted as midnight UTC).
+pub fn parse_timestamp(field: &str, s: &str) -> Result<String, AppError> {
+    trace!("Parsing {} timestamp from '{}'", field, s);
+    let s = s.trim();
+    let parsed = NaiveDateTime::parse_from_str(s, SQLITE_TIMESTAMP_FORMAT)
+        .ok()
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|dt| dt.with_timezone(&Utc).naive_utc())
+        })
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        });
+
+    match parsed {
+        Some(ts) => Ok(ts.format(SQLITE_TIMESTAMP_FORMAT).to_string()),
+        None => {
+            debug!("Failed to parse {} timestamp '{}'", field, s);
+            Err(AppError::InvalidInput(format!(
+                "{} must be a timestamp (YYYY-MM-DD HH:MM:SS or RFC 3339), got '{}'",
+                field, s
+            )))
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Statistical anomaly rules for sensor readings.
+
+/// Fewest earlier readings a sensor needs before new readings are judged; below this the
+/// spread is too noisy to call anything an outlier.
+pub const MIN_ANOMALY_SAMPLES: u32 = 5;
+
+/// Smallest spread, as a fraction of the mean's magnitude, that readings are judged
+/// against, so a near-flat history does not flag sensor noise.
+pub const MIN_RELATIVE_STD_DEV: f64 = 0.01;
+
+/// Smallest absolute spread readings are judged against, for histories averaging zero.
+pub const MIN_STD_DEV: f64 = 0.01;
+
+/// Mean and population standard deviation of a sensor's recent readings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RollingStats {
+    pub count: u32,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl RollingStats {
+    /// Builds the stats from a count, the mean and the mean of squares, as an SQL
+    /// aggregate returns them in one pass.
+    pub fn from_moments(count: u32, mean: f64, mean_of_squares: f64) -> Self {
+        Self {
+            count,
+            mean,
+            // Rounding can push the variance of a flat series slightly below zero.
+            std_dev: (mean_of_squares - mean * mean).max(0.0).sqrt(),
+        }
+    }
+}
+
+/// Returns whether `value` lies more than `sigma` standard deviations from the mean of
+/// the earlier readings summarised by `stats`.
+///
+/// Nothing is flagged until `MIN_ANOMALY_SAMPLES` readings exist. The deviation is
+/// floored at `MIN_RELATIVE_STD_DEV` of the mean and at `MIN_STD_DEV`, so a flat or
+/// near-flat history only flags values that stray meaningfully from it.
+pub fn is_anomaly(value: f64, stats: &RollingStats, sigma: f64) -> bool {
+    if stats.count < MIN_ANOMALY_SAMPLES {
+        return false;
+    }
+    let std_dev = stats
+        .std_dev
+        .max(MIN_RELATIVE_STD_DEV * stats.mean.abs())
+        .max(MIN_STD_DEV);
+    (value - stats.mean).abs() > sigma * std_dev
+}
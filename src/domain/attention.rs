@@ -0,0 +1,86 @@
+//! Ranking of goats that need a farm manager's attention.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Condition that puts a goat on the attention list.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttentionKind {
+    ActiveDisease,
+    Quarantine,
+    OverdueVaccination,
+    Underweight,
+    Overweight,
+    StaleWeight,
+}
+
+impl AttentionKind {
+    /// Points one such reason adds to a goat's score: illness and quarantine outrank
+    /// missed care, which outranks routine upkeep.
+    pub fn points(self) -> u32 {
+        match self {
+            AttentionKind::ActiveDisease => 5,
+            AttentionKind::Quarantine => 4,
+            AttentionKind::OverdueVaccination | AttentionKind::Underweight => 3,
+            AttentionKind::Overweight | AttentionKind::StaleWeight => 1,
+        }
+    }
+}
+
+/// Limits beyond which a goat's weight needs attention.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct AttentionThresholds {
+    /// Goats lighter than this, in kilograms, are underweight.
+    pub min_weight_kg: f64,
+    /// Goats heavier than this, in kilograms, are overweight.
+    pub max_weight_kg: f64,
+    /// Goats not weighed for more than this many days are due a weighing.
+    pub weight_stale_days: u32,
+}
+
+/// One reason a goat is flagged, with a human-readable explanation.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AttentionReason {
+    pub kind: AttentionKind,
+    pub detail: String,
+}
+
+/// A reason found for one goat, before flags are grouped by goat.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttentionFlag {
+    pub goat_id: i64,
+    pub goat_name: String,
+    pub reason: AttentionReason,
+}
+
+/// A goat on the attention list.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AttentionGoat {
+    pub goat_id: i64,
+    pub name: String,
+    /// Sum of `AttentionKind::points` over `reasons`.
+    pub score: u32,
+    pub reasons: Vec<AttentionReason>,
+}
+
+/// Groups `flags` by goat and orders the goats by score, highest first, then by id.
+/// Each goat's reasons keep the order they were flagged in.
+pub fn rank_attention(flags: Vec<AttentionFlag>) -> Vec<AttentionGoat> {
+    let mut by_goat: BTreeMap<i64, AttentionGoat> = BTreeMap::new();
+    for flag in flags {
+        let goat = by_goat
+            .entry(flag.goat_id)
+            .or_insert_with(|| AttentionGoat {
+                goat_id: flag.goat_id,
+                name: flag.goat_name,
+                score: 0,
+                reasons: Vec::new(),
+            });
+        goat.score += flag.reason.kind.points();
+        goat.reasons.push(flag.reason);
+    }
+    let mut goats: Vec<AttentionGoat> = by_goat.into_values().collect();
+    goats.sort_by(|a, b| b.score.cmp(&a.score).then(a.goat_id.cmp(&b.goat_id)));
+    goats
+}
@@ -0,0 +1,163 @@
+//! Breeding eligibility rules and pedigree-based mate selection.
+
+use crate::models::Gender;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Whether a goat of this gender can be bred or recorded as pregnant.
+///
+/// Wethers are castrated males and never eligible.
+pub fn is_breeding_eligible(gender: &Gender) -> bool {
+    !matches!(gender, Gender::Wether)
+}
+
+/// Generations within which a shared ancestor rules a pairing out: parents and
+/// grandparents. Covers parent and offspring, full and half siblings, and first cousins.
+pub const CLOSE_ANCESTOR_GENERATIONS: u32 = 2;
+/// Generations of pedigree followed when computing kinship; older ancestry is treated
+/// as unrelated.
+pub const MAX_PEDIGREE_DEPTH: u32 = 8;
+
+/// A goat's recorded parents; either may be unknown.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Parents {
+    pub sire_id: Option<i64>,
+    pub dam_id: Option<i64>,
+}
+
+/// Recorded parents by goat id. Goats missing from the map have unknown parents.
+pub type Pedigree = HashMap<i64, Parents>;
+
+/// Suggested sire for one dam, from `plan_matings`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MatingSuggestion {
+    pub dam_id: i64,
+    /// Best sire for the dam, or `None` when every candidate is closely related to her.
+    pub sire_id: Option<i64>,
+    /// Expected inbreeding coefficient of the kids of the suggested pairing.
+    pub inbreeding_coefficient: Option<f64>,
+    /// Candidate sires ruled out for sharing a close ancestor with the dam.
+    pub excluded_sire_ids: Vec<i64>,
+}
+
+/// `goat` and its ancestors up to `generations` back, each with the fewest generations
+/// separating it from `goat` (0 for `goat` itself).
+pub fn ancestors_within(pedigree: &Pedigree, goat: i64, generations: u32) -> HashMap<i64, u32> {
+    let mut found = HashMap::from([(goat, 0)]);
+    let mut frontier = vec![goat];
+    for generation in 1..=generations {
+        let mut next = Vec::new();
+        for id in frontier {
+            let parents = pedigree.get(&id).copied().unwrap_or_default();
+            for parent in [parents.sire_id, parents.dam_id].into_iter().flatten() {
+                if !found.contains_key(&parent) {
+                    found.insert(parent, generation);
+                    next.push(parent);
+                }
+            }
+        }
+        frontier = next;
+    }
+    found
+}
+
+/// Whether `a` and `b` are the same goat, one descends from the other, or they share an
+/// ancestor, all within `CLOSE_ANCESTOR_GENERATIONS`.
+pub fn shares_close_ancestor(pedigree: &Pedigree, a: i64, b: i64) -> bool {
+    let of_a = ancestors_within(pedigree, a, CLOSE_ANCESTOR_GENERATIONS);
+    ancestors_within(pedigree, b, CLOSE_ANCESTOR_GENERATIONS)
+        .keys()
+        .any(|id| of_a.contains_key(id))
+}
+
+/// Coefficient of kinship between `a` and `b`: the chance that an allele drawn from
+/// each is identical by descent. It equals the inbreeding coefficient of their kids.
+///
+/// Computed with the recursive (tabular) method: the kinship of two goats is the mean
+/// kinship of the younger one's parents with the other, where the younger is whichever
+/// is not an ancestor of the other. A goat's kinship with itself is half of one plus its
+/// own inbreeding. Unknown parents, and ancestry beyond `MAX_PEDIGREE_DEPTH`, count as
+/// unrelated.
+pub fn kinship(pedigree: &Pedigree, a: i64, b: i64) -> f64 {
+    kinship_at(pedigree, a, b, 0, &mut HashMap::new())
+}
+
+fn kinship_at(
+    pedigree: &Pedigree,
+    a: i64,
+    b: i64,
+    depth: u32,
+    memo: &mut HashMap<(i64, i64), f64>,
+) -> f64 {
+    if depth > MAX_PEDIGREE_DEPTH {
+        return 0.0;
+    }
+    let key = (a.min(b), a.max(b));
+    if let Some(&known) = memo.get(&key) {
+        return known;
+    }
+    let parents_of = |id: i64| pedigree.get(&id).copied().unwrap_or_default();
+    let value = if a == b {
+        let parents = parents_of(a);
+        let inbreeding = match (parents.sire_id, parents.dam_id) {
+            (Some(sire), Some(dam)) => kinship_at(pedigree, sire, dam, depth + 1, memo),
+            _ => 0.0,
+        };
+        0.5 * (1.0 + inbreeding)
+    } else {
+        // Expand whichever goat is not an ancestor of the other.
+        let (younger, other) = if ancestors_within(pedigree, a, MAX_PEDIGREE_DEPTH).contains_key(&b)
+        {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let parents = parents_of(younger);
+        [parents.sire_id, parents.dam_id]
+            .into_iter()
+            .map(|parent| match parent {
+                Some(parent) => kinship_at(pedigree, parent, other, depth + 1, memo),
+                None => 0.0,
+            })
+            .sum::<f64>()
+            / 2.0
+    };
+    memo.insert(key, value);
+    value
+}
+
+/// Pairs each of `dam_ids` with the candidate sire giving the least inbred kids.
+///
+/// Sires sharing a close ancestor with a dam (see `shares_close_ancestor`) are excluded
+/// for her; the rest are ranked by `kinship`, ties going to the lower sire id. Sires may
+/// be suggested for several dams. Suggestions are ordered best first: by inbreeding
+/// coefficient, then dam id, with dams left without a sire last.
+pub fn plan_matings(
+    pedigree: &Pedigree,
+    sire_ids: &[i64],
+    dam_ids: &[i64],
+) -> Vec<MatingSuggestion> {
+    let mut suggestions: Vec<MatingSuggestion> = dam_ids
+        .iter()
+        .map(|&dam_id| {
+            let (excluded, eligible): (Vec<i64>, Vec<i64>) = sire_ids
+                .iter()
+                .partition(|&&sire_id| shares_close_ancestor(pedigree, sire_id, dam_id));
+            let best = eligible
+                .into_iter()
+                .map(|sire_id| (sire_id, kinship(pedigree, sire_id, dam_id)))
+                .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+            MatingSuggestion {
+                dam_id,
+                sire_id: best.map(|(sire_id, _)| sire_id),
+                inbreeding_coefficient: best.map(|(_, coefficient)| coefficient),
+                excluded_sire_ids: excluded,
+            }
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        let rank = |s: &MatingSuggestion| s.inbreeding_coefficient.unwrap_or(f64::INFINITY);
+        rank(a).total_cmp(&rank(b)).then(a.dam_id.cmp(&b.dam_id))
+    });
+    suggestions
+}
@@ -0,0 +1,42 @@
+//! Valuation rules for farm assets.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Average days per year, accounting for leap years.
+const DAYS_PER_YEAR: f64 = 365.25;
+
+/// Straight-line valuation of an asset at a point in time.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DepreciationInfo {
+    pub current_value: f64,
+    pub accumulated_depreciation: f64,
+    pub remaining_useful_life_years: f64,
+}
+
+/// Values an asset by straight-line depreciation as of `as_of`.
+///
+/// `current_value = purchase_price * max(0, 1 - years_old / useful_life_years)`, so an
+/// asset past its useful life is worth 0, never less. Dates before the purchase count as
+/// zero years old, and a zero useful life means the asset is fully depreciated.
+pub fn compute_depreciation(
+    purchase_date: NaiveDate,
+    purchase_price: f64,
+    useful_life_years: u32,
+    as_of: NaiveDate,
+) -> DepreciationInfo {
+    let years_old = ((as_of - purchase_date).num_days() as f64 / DAYS_PER_YEAR).max(0.0);
+    let life = f64::from(useful_life_years);
+    let remaining_fraction = if useful_life_years == 0 {
+        0.0
+    } else {
+        (1.0 - years_old / life).clamp(0.0, 1.0)
+    };
+    let current_value = purchase_price * remaining_fraction;
+
+    DepreciationInfo {
+        current_value,
+        accumulated_depreciation: purchase_price - current_value,
+        remaining_useful_life_years: (life - years_old).max(0.0),
+    }
+}
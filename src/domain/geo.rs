@@ -0,0 +1,47 @@
+//! Distance and geofence rules for GPS positions.
+
+/// Mean Earth radius in meters, as used by the Haversine formula.
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Great-circle distance in meters between two points given in decimal degrees.
+pub fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().atan2((1.0 - a).sqrt())
+}
+
+/// A goat crossing a geofence boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenceCrossing {
+    Entry,
+    Exit,
+}
+
+impl FenceCrossing {
+    /// The name stored in `location_alerts.event`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FenceCrossing::Entry => "Entry",
+            FenceCrossing::Exit => "Exit",
+        }
+    }
+}
+
+/// Returns the boundary crossing between two positions, if any. Without a previous
+/// position there is nothing to cross, so a goat's first fix never counts.
+pub fn fence_crossing(was_inside: Option<bool>, is_inside: bool) -> Option<FenceCrossing> {
+    match (was_inside?, is_inside) {
+        (false, true) => Some(FenceCrossing::Entry),
+        (true, false) => Some(FenceCrossing::Exit),
+        _ => None,
+    }
+}
+
+/// Returns whether a geofence with `alert_on` (`Exit`, `Entry` or `Both`) alerts on
+/// `crossing`.
+pub fn alerts_on(alert_on: &str, crossing: FenceCrossing) -> bool {
+    alert_on == "Both" || alert_on == crossing.as_str()
+}
@@ -0,0 +1,18 @@
+//! Pure business rules with no database or HTTP dependencies.
+//!
+//! Handlers load data, call into these modules, and serialize the result, which keeps
+//! the rules themselves easy to test in isolation.
+
+pub mod anomaly;
+pub mod attention;
+pub mod breeding;
+pub mod finance;
+pub mod geo;
+pub mod nutrition;
+pub mod payroll;
+pub mod prediction;
+pub mod sale;
+pub mod scheduling;
+pub mod simulation;
+pub mod trade_log;
+pub mod visualization;
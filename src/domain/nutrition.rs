@@ -0,0 +1,272 @@
+//! Nutrient requirements and least-cost ration formulation.
+//!
+//! Requirements are expressed per kilogram of dry matter, after the NRC (2007) tables
+//! for goats, simplified to one row per production stage. Feed values are taken to be
+//! on a dry-matter basis as well.
+
+use crate::units::round2;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Accepted `production_stage` values.
+pub const PRODUCTION_STAGES: &[&str] = &["maintenance", "growing", "gestation", "lactation"];
+/// Goats younger than this are held to at least the growing requirement.
+pub const YOUNG_STOCK_MAX_MONTHS: u32 = 12;
+/// Share of the mix, in percentage points, moved per formulation step.
+const STEP_PCT: f64 = 0.5;
+/// Upper bound on formulation steps; enough to move the whole mix many times over.
+const MAX_STEPS: usize = 10_000;
+/// Slack when comparing nutrient levels, so rounding never flips a met requirement.
+const EPSILON: f64 = 1e-9;
+
+/// What a goat's ration must supply.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct NutrientRequirement {
+    /// Daily dry matter intake as a percentage of body weight.
+    pub dry_matter_intake_pct: f64,
+    /// Minimum crude protein, as a percentage of dry matter.
+    pub crude_protein_pct: f64,
+    /// Minimum metabolizable energy per kilogram of dry matter.
+    pub energy_mj_per_kg: f64,
+}
+
+const MAINTENANCE: NutrientRequirement = NutrientRequirement {
+    dry_matter_intake_pct: 2.0,
+    crude_protein_pct: 7.0,
+    energy_mj_per_kg: 8.4,
+};
+const GROWING: NutrientRequirement = NutrientRequirement {
+    dry_matter_intake_pct: 3.0,
+    crude_protein_pct: 14.0,
+    energy_mj_per_kg: 10.5,
+};
+const GESTATION: NutrientRequirement = NutrientRequirement {
+    dry_matter_intake_pct: 2.5,
+    crude_protein_pct: 12.0,
+    energy_mj_per_kg: 9.6,
+};
+const LACTATION: NutrientRequirement = NutrientRequirement {
+    dry_matter_intake_pct: 3.5,
+    crude_protein_pct: 14.0,
+    energy_mj_per_kg: 10.5,
+};
+
+/// Physiological state that sets a goat's requirement.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProductionStage {
+    Maintenance,
+    Growing,
+    Gestation,
+    Lactation,
+}
+
+impl FromStr for ProductionStage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "maintenance" => Ok(ProductionStage::Maintenance),
+            "growing" => Ok(ProductionStage::Growing),
+            "gestation" => Ok(ProductionStage::Gestation),
+            "lactation" => Ok(ProductionStage::Lactation),
+            other => Err(format!(
+                "Unknown production stage '{}'; expected one of {}",
+                other,
+                PRODUCTION_STAGES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Requirement for a goat `age_months` old at `stage`. Young stock get the higher of
+/// each nutrient between their stage and growing.
+pub fn requirement(stage: ProductionStage, age_months: u32) -> NutrientRequirement {
+    let base = match stage {
+        ProductionStage::Maintenance => MAINTENANCE,
+        ProductionStage::Growing => GROWING,
+        ProductionStage::Gestation => GESTATION,
+        ProductionStage::Lactation => LACTATION,
+    };
+    if age_months >= YOUNG_STOCK_MAX_MONTHS {
+        return base;
+    }
+    NutrientRequirement {
+        dry_matter_intake_pct: base
+            .dry_matter_intake_pct
+            .max(GROWING.dry_matter_intake_pct),
+        crude_protein_pct: base.crude_protein_pct.max(GROWING.crude_protein_pct),
+        energy_mj_per_kg: base.energy_mj_per_kg.max(GROWING.energy_mj_per_kg),
+    }
+}
+
+/// A feed available to the formulation, with values per kilogram of dry matter.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FeedIngredient {
+    pub name: String,
+    pub crude_protein_pct: f64,
+    pub energy_mj_per_kg: f64,
+    pub cost_per_kg: f64,
+    /// Largest share of the mix this feed may make up, in percent.
+    pub max_inclusion_pct: f64,
+}
+
+/// One feed's share of a formulated ration.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FormulaLine {
+    pub feed: String,
+    pub inclusion_pct: f64,
+    pub kg_per_day: f64,
+}
+
+/// A formulated daily ration. Percentages, weights and cost are rounded to two decimals.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FeedFormulation {
+    pub formula: Vec<FormulaLine>,
+    pub total_cost_per_day: f64,
+    pub protein_pct: f64,
+    pub energy_mj_per_kg: f64,
+    pub meets_requirements: bool,
+}
+
+#[derive(Clone, Copy)]
+enum Nutrient {
+    Protein,
+    Energy,
+}
+
+impl Nutrient {
+    fn of(self, feed: &FeedIngredient) -> f64 {
+        match self {
+            Nutrient::Protein => feed.crude_protein_pct,
+            Nutrient::Energy => feed.energy_mj_per_kg,
+        }
+    }
+
+    fn required(self, requirement: &NutrientRequirement) -> f64 {
+        match self {
+            Nutrient::Protein => requirement.crude_protein_pct,
+            Nutrient::Energy => requirement.energy_mj_per_kg,
+        }
+    }
+
+    fn other(self) -> Nutrient {
+        match self {
+            Nutrient::Protein => Nutrient::Energy,
+            Nutrient::Energy => Nutrient::Protein,
+        }
+    }
+}
+
+/// Level of `nutrient` in a mix of `feeds` at `inclusion` percent each.
+fn level(feeds: &[FeedIngredient], inclusion: &[f64], nutrient: Nutrient) -> f64 {
+    feeds
+        .iter()
+        .zip(inclusion)
+        .map(|(feed, pct)| nutrient.of(feed) * pct / 100.0)
+        .sum()
+}
+
+/// Formulates a daily ration for a goat of `weight_kg` meeting `requirement` from
+/// `feeds` at close to the least cost.
+///
+/// Greedy and iterative: the mix is first filled with the cheapest feeds up to their
+/// `max_inclusion_pct`. While protein, then energy, falls short, `STEP_PCT` points move
+/// from the included feed poorest in that nutrient to the richer feed with the best
+/// nutrient-to-cost ratio and room to spare, never pushing the other nutrient below its
+/// requirement. When no such move is left, the mix is returned with
+/// `meets_requirements` false. If the feeds' limits add up to less than 100%, the rest
+/// of the mix stays empty and the requirements are not met.
+pub fn formulate_feed(
+    weight_kg: f64,
+    requirement: &NutrientRequirement,
+    feeds: &[FeedIngredient],
+) -> FeedFormulation {
+    let mut inclusion = vec![0.0; feeds.len()];
+    let mut by_cost: Vec<usize> = (0..feeds.len()).collect();
+    by_cost.sort_by(|&a, &b| feeds[a].cost_per_kg.total_cmp(&feeds[b].cost_per_kg));
+    let mut unfilled = 100.0;
+    for i in by_cost {
+        let share = feeds[i].max_inclusion_pct.min(unfilled).max(0.0);
+        inclusion[i] = share;
+        unfilled -= share;
+    }
+
+    for _ in 0..MAX_STEPS {
+        let short = [Nutrient::Protein, Nutrient::Energy]
+            .into_iter()
+            .find(|&n| level(feeds, &inclusion, n) < n.required(requirement) - EPSILON);
+        let Some(nutrient) = short else {
+            break;
+        };
+        let other = nutrient.other();
+        let other_met = level(feeds, &inclusion, other) >= other.required(requirement) - EPSILON;
+
+        let Some(donor) = (0..feeds.len())
+            .filter(|&i| inclusion[i] > EPSILON)
+            .min_by(|&a, &b| nutrient.of(&feeds[a]).total_cmp(&nutrient.of(&feeds[b])))
+        else {
+            break;
+        };
+        let ratio = |i: usize| {
+            if feeds[i].cost_per_kg > 0.0 {
+                nutrient.of(&feeds[i]) / feeds[i].cost_per_kg
+            } else {
+                f64::INFINITY
+            }
+        };
+        let receiver = (0..feeds.len())
+            .filter(|&i| {
+                nutrient.of(&feeds[i]) > nutrient.of(&feeds[donor])
+                    && feeds[i].max_inclusion_pct - inclusion[i] > EPSILON
+            })
+            .filter(|&i| {
+                // A move may not trade one shortfall for another.
+                let step = STEP_PCT.min(inclusion[donor]);
+                let change = (other.of(&feeds[i]) - other.of(&feeds[donor])) * step / 100.0;
+                !other_met
+                    || level(feeds, &inclusion, other) + change
+                        >= other.required(requirement) - EPSILON
+            })
+            .max_by(|&a, &b| ratio(a).total_cmp(&ratio(b)));
+        let Some(receiver) = receiver else {
+            break;
+        };
+
+        let step = STEP_PCT
+            .min(inclusion[donor])
+            .min(feeds[receiver].max_inclusion_pct - inclusion[receiver]);
+        inclusion[donor] -= step;
+        inclusion[receiver] += step;
+    }
+
+    let dry_matter_kg = weight_kg * requirement.dry_matter_intake_pct / 100.0;
+    let protein = level(feeds, &inclusion, Nutrient::Protein);
+    let energy = level(feeds, &inclusion, Nutrient::Energy);
+    let mut total_cost = 0.0;
+    let formula = feeds
+        .iter()
+        .zip(&inclusion)
+        .filter(|(_, pct)| **pct > EPSILON)
+        .map(|(feed, pct)| {
+            let kg_per_day = dry_matter_kg * pct / 100.0;
+            total_cost += kg_per_day * feed.cost_per_kg;
+            FormulaLine {
+                feed: feed.name.clone(),
+                inclusion_pct: round2(*pct),
+                kg_per_day: round2(kg_per_day),
+            }
+        })
+        .collect();
+    let filled = inclusion.iter().sum::<f64>() >= 100.0 - EPSILON;
+
+    FeedFormulation {
+        formula,
+        total_cost_per_day: round2(total_cost),
+        protein_pct: round2(protein),
+        energy_mj_per_kg: round2(energy),
+        meets_requirements: filled
+            && protein >= requirement.crude_protein_pct - EPSILON
+            && energy >= requirement.energy_mj_per_kg - EPSILON,
+    }
+}
@@ -0,0 +1,48 @@
+//! Monthly pay from the hours a worker logged.
+
+use crate::units::round2;
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Terms one worker is paid on for a month.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PayTerms {
+    /// Rupees per hour.
+    pub hourly_rate: f64,
+    /// Hours in the month beyond which work is overtime.
+    pub overtime_threshold_hours: f64,
+    /// Factor applied to `hourly_rate` for overtime hours.
+    pub overtime_multiplier: f64,
+}
+
+/// Pay for one month's hours.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct MonthlyPay {
+    pub regular_hours: f64,
+    pub overtime_hours: f64,
+    /// Rupees, rounded to paise.
+    pub gross_pay: f64,
+}
+
+/// Splits `hours` at the overtime threshold and prices both parts. Hours up to and
+/// including the threshold are regular.
+pub fn compute_monthly_pay(hours: f64, terms: &PayTerms) -> MonthlyPay {
+    let hours = hours.max(0.0);
+    let regular_hours = hours.min(terms.overtime_threshold_hours.max(0.0));
+    let overtime_hours = hours - regular_hours;
+    MonthlyPay {
+        regular_hours: round2(regular_hours),
+        overtime_hours: round2(overtime_hours),
+        gross_pay: round2(
+            regular_hours * terms.hourly_rate
+                + overtime_hours * terms.hourly_rate * terms.overtime_multiplier,
+        ),
+    }
+}
+
+/// First and last day of the month written `YYYY-MM`, or `None` if it is not one.
+pub fn month_bounds(month: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let first = NaiveDate::parse_from_str(&format!("{}-01", month.trim()), "%Y-%m-%d").ok()?;
+    let last = first.checked_add_months(Months::new(1))?.pred_opt()?;
+    Some((first, last))
+}
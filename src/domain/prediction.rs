@@ -0,0 +1,80 @@
+//! Trend fitting for forecasting goat measurements.
+
+use serde::{Deserialize, Serialize};
+
+/// Seconds per day, for converting forecast horizons to regression units.
+pub const SECS_PER_DAY: i64 = 86_400;
+
+/// Fits `y = slope * x + intercept` to `points` by ordinary least squares, returning
+/// `(slope, intercept)`.
+///
+/// Returns `None` for fewer than two points or when every `x` is the same, since no
+/// line is then determined. The sums are taken about the means so that large `x`
+/// values, such as Unix timestamps, do not lose precision.
+pub fn linear_regression(points: &[(i64, f64)]) -> Option<(f64, f64)> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|&(x, _)| x as f64).sum::<f64>() / n;
+    let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / n;
+    let (covariance, variance) = points.iter().fold((0.0, 0.0), |(cov, var), &(x, y)| {
+        let dx = x as f64 - mean_x;
+        (cov + dx * (y - mean_y), var + dx * dx)
+    });
+    if variance == 0.0 {
+        return None;
+    }
+    let slope = covariance / variance;
+    Some((slope, mean_y - slope * mean_x))
+}
+
+/// How much a forecast can be trusted, by how many measurements it was fitted to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// Fewer than 3 measurements.
+    Low,
+    /// 3 to 9 measurements.
+    Medium,
+    /// 10 or more measurements.
+    High,
+}
+
+impl Confidence {
+    pub fn from_samples(count: usize) -> Self {
+        match count {
+            0..=2 => Confidence::Low,
+            3..=9 => Confidence::Medium,
+            _ => Confidence::High,
+        }
+    }
+}
+
+/// A goat's latest weight and its forecast, in kilograms.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WeightPrediction {
+    pub current_weight: f64,
+    pub predicted_weight: f64,
+    pub confidence: Confidence,
+}
+
+/// Forecasts weight at Unix time `at` from `history`, `(unix_secs, kg)` pairs oldest
+/// first. The current weight is the latest measurement.
+///
+/// With a single measurement, or several taken at the same instant, the latest weight
+/// is carried forward. The forecast is never negative. Returns `None` for an empty
+/// history.
+pub fn predict_weight(history: &[(i64, f64)], at: i64) -> Option<WeightPrediction> {
+    let &(_, current_weight) = history.last()?;
+    let predicted_weight = linear_regression(history)
+        .map_or(current_weight, |(slope, intercept)| {
+            slope * at as f64 + intercept
+        })
+        .max(0.0);
+    Some(WeightPrediction {
+        current_weight,
+        predicted_weight,
+        confidence: Confidence::from_samples(history.len()),
+    })
+}
@@ -0,0 +1,38 @@
+//! Rules deciding whether a goat can be offered for sale.
+
+use crate::models::{Goat, GoatParams};
+use chrono::{Months, NaiveDate};
+
+/// Health status a goat must have to be sold.
+pub const SALEABLE_HEALTH_STATUS: &str = "healthy";
+
+/// A goat that passed the SQL-checkable sale criteria, with its birth date, which
+/// `GoatParams` does not carry.
+#[derive(Debug, Clone)]
+pub struct SaleCandidate {
+    pub goat: Goat,
+    pub birth_date: Option<NaiveDate>,
+}
+
+/// Returns whether `goat`, born on `birth_date`, meets the sale criteria as of `today`.
+///
+/// A goat is sale-ready when it is healthy, weighs at least `min_weight` kg, and is at
+/// least `min_age_months` old. Goats without a recorded birth date are never sale-ready,
+/// since their age cannot be verified. Pregnancy and sold status live outside
+/// `GoatParams` and are filtered out when candidates are loaded.
+pub fn is_sale_ready(
+    goat: &GoatParams,
+    birth_date: Option<NaiveDate>,
+    min_weight: f64,
+    min_age_months: u32,
+    today: NaiveDate,
+) -> bool {
+    let old_enough = birth_date
+        .and_then(|born| born.checked_add_months(Months::new(min_age_months)))
+        .is_some_and(|eligible_from| eligible_from <= today);
+
+    goat.health_status
+        .eq_ignore_ascii_case(SALEABLE_HEALTH_STATUS)
+        && goat.weight >= min_weight
+        && old_enough
+}
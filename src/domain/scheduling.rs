@@ -0,0 +1,62 @@
+//! Planning rules for batching vaccinations into veterinarian visits.
+
+use crate::models::OverdueVaccination;
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One planned vet visit: every listed goat gets `vaccine` on `date`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScheduleSlot {
+    /// `YYYY-MM-DD`.
+    pub date: String,
+    pub vaccine: String,
+    pub goat_count: i32,
+    pub goat_ids: Vec<i64>,
+}
+
+/// Plans the vaccinations in `due` over the `date_range_days` days starting `today`,
+/// greedily giving each vaccine a single slot so it costs one visit.
+///
+/// A slot falls on the earliest due date among its goats, so no goat is vaccinated later
+/// than planned; anything already overdue is scheduled for `today`. Entries due after
+/// the range are left for a later plan. Slots are ordered by date, then vaccine.
+pub fn optimize_vaccination_schedule(
+    due: &[OverdueVaccination],
+    date_range_days: u32,
+    today: NaiveDate,
+) -> Vec<ScheduleSlot> {
+    let last_day = today + Days::new(u64::from(date_range_days.max(1) - 1));
+
+    let mut by_vaccine: BTreeMap<&str, (NaiveDate, Vec<i64>)> = BTreeMap::new();
+    for entry in due {
+        let Ok(due_on) = NaiveDate::parse_from_str(&entry.due_on, "%Y-%m-%d") else {
+            continue;
+        };
+        if due_on > last_day {
+            continue;
+        }
+        let slot = by_vaccine
+            .entry(entry.vaccine.as_str())
+            .or_insert((due_on, Vec::new()));
+        slot.0 = slot.0.min(due_on);
+        if !slot.1.contains(&entry.goat_id) {
+            slot.1.push(entry.goat_id);
+        }
+    }
+
+    let mut slots: Vec<ScheduleSlot> = by_vaccine
+        .into_iter()
+        .map(|(vaccine, (earliest_due, mut goat_ids))| {
+            goat_ids.sort_unstable();
+            ScheduleSlot {
+                date: earliest_due.max(today).to_string(),
+                vaccine: vaccine.to_string(),
+                goat_count: goat_ids.len() as i32,
+                goat_ids,
+            }
+        })
+        .collect();
+    slots.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.vaccine.cmp(&b.vaccine)));
+    slots
+}
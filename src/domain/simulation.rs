@@ -0,0 +1,135 @@
+//! Monte Carlo projection of herd size.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Herd sizes above this draw births, deaths and sales from a normal approximation
+/// instead of one trial per goat.
+const EXACT_BINOMIAL_MAX: i64 = 1_000;
+/// Lower and upper percentiles reported as the confidence interval (95%).
+const CONFIDENCE_PERCENTILES: (f64, f64) = (0.025, 0.975);
+
+/// Yearly rates per head of herd, as measured from history.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PopulationRates {
+    /// Goats in the herd now.
+    pub initial_herd: i32,
+    /// Kids born per goat per year; may exceed 1 with twins.
+    pub breeding_rate: f64,
+    /// Share of the herd dying per year.
+    pub mortality_rate: f64,
+    /// Share of the herd sold per year.
+    pub sales_rate: f64,
+}
+
+/// Projected herd size at the end of one simulated year.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProjectionYear {
+    /// Years from the start of the simulation, from 1.
+    pub year: i32,
+    /// Mean over all iterations.
+    pub projected_herd_size: f64,
+    /// 2.5th percentile over all iterations.
+    pub confidence_lower: f64,
+    /// 97.5th percentile over all iterations.
+    pub confidence_upper: f64,
+}
+
+/// Simulates `iterations` independent herds over `years` years; see
+/// `simulate_population_with`.
+pub fn simulate_population(
+    initial: i32,
+    breeding_rate: f64,
+    mortality_rate: f64,
+    sales_rate: f64,
+    years: u32,
+    iterations: u32,
+) -> Vec<ProjectionYear> {
+    simulate_population_with(
+        &mut rand::thread_rng(),
+        initial,
+        breeding_rate,
+        mortality_rate,
+        sales_rate,
+        years,
+        iterations,
+    )
+}
+
+/// Simulates `iterations` independent herds starting at `initial` goats over `years`
+/// years, drawing from `rng`, and summarises each year across the iterations.
+///
+/// Each year, every goat at the start of the year dies with probability
+/// `mortality_rate`, and each survivor is sold with probability `sales_rate`. Every goat
+/// at the start of the year also bears `breeding_rate` kids on average: the whole part
+/// for certain, plus one more with probability equal to the fraction. Rates are clamped
+/// to be non-negative, and the two probabilities to at most 1. At least one iteration
+/// is run.
+pub fn simulate_population_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    initial: i32,
+    breeding_rate: f64,
+    mortality_rate: f64,
+    sales_rate: f64,
+    years: u32,
+    iterations: u32,
+) -> Vec<ProjectionYear> {
+    let breeding_rate = breeding_rate.max(0.0);
+    let whole_kids = breeding_rate.trunc() as i64;
+    let extra_kid = breeding_rate.fract();
+    let mortality_rate = mortality_rate.clamp(0.0, 1.0);
+    let sales_rate = sales_rate.clamp(0.0, 1.0);
+
+    let iterations = iterations.max(1) as usize;
+    let mut sizes = vec![Vec::with_capacity(iterations); years as usize];
+    for _ in 0..iterations {
+        let mut herd = i64::from(initial.max(0));
+        for year in sizes.iter_mut() {
+            let births = herd * whole_kids + binomial(rng, herd, extra_kid);
+            let deaths = binomial(rng, herd, mortality_rate);
+            let sales = binomial(rng, herd - deaths, sales_rate);
+            herd = herd - deaths - sales + births;
+            year.push(herd as f64);
+        }
+    }
+
+    sizes
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut year)| {
+            year.sort_by(f64::total_cmp);
+            ProjectionYear {
+                year: i as i32 + 1,
+                projected_herd_size: year.iter().sum::<f64>() / year.len() as f64,
+                confidence_lower: percentile(&year, CONFIDENCE_PERCENTILES.0),
+                confidence_upper: percentile(&year, CONFIDENCE_PERCENTILES.1),
+            }
+        })
+        .collect()
+}
+
+/// Number of successes in `n` trials of probability `p`.
+fn binomial<R: Rng + ?Sized>(rng: &mut R, n: i64, p: f64) -> i64 {
+    if n <= 0 || p <= 0.0 {
+        return 0;
+    }
+    if p >= 1.0 {
+        return n;
+    }
+    if n <= EXACT_BINOMIAL_MAX {
+        return (0..n).filter(|_| rng.gen_bool(p)).count() as i64;
+    }
+    // Box-Muller normal draw, plenty accurate for a herd this size.
+    let mean = n as f64 * p;
+    let std_dev = (mean * (1.0 - p)).sqrt();
+    let u1: f64 = rng.gen_range(f64::MIN_POSITIVE..1.0);
+    let u2: f64 = rng.gen_range(0.0..1.0);
+    let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+    ((mean + z * std_dev).round() as i64).clamp(0, n)
+}
+
+/// Value at fraction `q` through `sorted`, by nearest rank.
+fn percentile(sorted: &[f64], q: f64) -> f64 {
+    let rank = ((sorted.len() - 1) as f64 * q).round() as usize;
+    sorted[rank]
+}
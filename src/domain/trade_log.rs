@@ -0,0 +1,67 @@
+//! Hash chain that makes the goat trade log tamper-evident.
+//!
+//! Each entry's `transaction_hash` covers the previous entry's hash, so editing or
+//! deleting any entry breaks every link after it. The table's triggers already refuse
+//! updates and deletes; the chain catches edits made with those triggers dropped.
+
+use crate::models::TradeLogEntry;
+use sha2::{Digest, Sha256};
+
+/// `prev_hash` of the first entry in the log.
+pub const GENESIS_HASH: &str = "";
+
+/// Returns the lowercase hex SHA-256 of `prev` followed by `payload`.
+pub fn compute_trade_hash(prev: &str, payload: &str) -> String {
+    format!("{:x}", Sha256::digest(format!("{}{}", prev, payload)))
+}
+
+/// The hashed fields of an entry: goat id, action, parties, price and timestamp.
+///
+/// Each field is written as its byte length, a colon and its value, with `-` standing for
+/// a missing optional field, so no two different entries share a payload.
+pub fn trade_payload(
+    goat_id: i64,
+    action: &str,
+    from_party: Option<&str>,
+    to_party: Option<&str>,
+    price: Option<f64>,
+    timestamp: &str,
+) -> String {
+    let price = price.map(|p| p.to_string());
+    [
+        Some(goat_id.to_string().as_str()),
+        Some(action),
+        from_party,
+        to_party,
+        price.as_deref(),
+        Some(timestamp),
+    ]
+    .iter()
+    .map(|field| match field {
+        Some(value) => format!("{}:{}", value.len(), value),
+        None => "-".to_string(),
+    })
+    .collect()
+}
+
+/// Walks `entries` in log order and returns the id of the first one whose `prev_hash`
+/// does not match its predecessor's hash or whose own hash does not match its contents.
+/// Returns `None` for an intact chain.
+pub fn first_broken_link(entries: &[TradeLogEntry]) -> Option<i64> {
+    let mut prev = GENESIS_HASH;
+    for entry in entries {
+        let payload = trade_payload(
+            entry.goat_id,
+            &entry.action,
+            entry.from_party.as_deref(),
+            entry.to_party.as_deref(),
+            entry.price,
+            &entry.timestamp,
+        );
+        if entry.prev_hash != prev || entry.transaction_hash != compute_trade_hash(prev, &payload) {
+            return Some(entry.id);
+        }
+        prev = &entry.transaction_hash;
+    }
+    None
+}
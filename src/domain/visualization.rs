@@ -0,0 +1,110 @@
+//! SVG rendering of the farm map.
+
+use serde::{Deserialize, Serialize};
+
+/// Occupancy below this percentage is drawn green.
+pub const OCCUPANCY_WARN_PCT: f64 = 70.0;
+/// Occupancy above this percentage is drawn red; in between is yellow.
+pub const OCCUPANCY_FULL_PCT: f64 = 90.0;
+/// Fill of spaces whose occupancy is unknown because they have no capacity.
+const UNKNOWN_FILL: &str = "#bdbdbd";
+/// Margin around the drawn spaces, in map units.
+const MARGIN: f64 = 1.0;
+
+/// Where a space sits on the farm map, in grid units from the top-left corner.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct MapPosition {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// A space as drawn on the farm map.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpaceLayout {
+    pub space_id: i64,
+    pub name: String,
+    pub capacity: Option<i64>,
+    /// Live goats currently assigned to the space.
+    pub occupancy: i64,
+    /// `occupancy` as a percentage of `capacity`; absent without a positive capacity.
+    pub occupancy_pct: Option<f64>,
+    /// Absent until the space is placed on the map.
+    pub position: Option<MapPosition>,
+}
+
+/// Fill colour for a space at `occupancy_pct`: green below 70%, yellow up to 90%, red
+/// above, and grey when unknown.
+pub fn occupancy_color(occupancy_pct: Option<f64>) -> &'static str {
+    match occupancy_pct {
+        None => UNKNOWN_FILL,
+        Some(pct) if pct < OCCUPANCY_WARN_PCT => "#4caf50",
+        Some(pct) if pct <= OCCUPANCY_FULL_PCT => "#ffeb3b",
+        Some(_) => "#f44336",
+    }
+}
+
+/// Renders the placed spaces among `spaces` as an SVG document: one `<rect>` per space,
+/// filled by `occupancy_color` and labelled with its name. Spaces without a position are
+/// left out. The view box fits every placed space plus a small margin.
+pub fn generate_farm_svg(spaces: &[SpaceLayout]) -> String {
+    let placed: Vec<(&SpaceLayout, MapPosition)> = spaces
+        .iter()
+        .filter_map(|space| space.position.map(|position| (space, position)))
+        .collect();
+    let width = placed
+        .iter()
+        .map(|(_, p)| p.x + p.width)
+        .fold(0.0, f64::max)
+        + MARGIN;
+    let height = placed
+        .iter()
+        .map(|(_, p)| p.y + p.height)
+        .fold(0.0, f64::max)
+        + MARGIN;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        -MARGIN,
+        -MARGIN,
+        width + MARGIN,
+        height + MARGIN
+    );
+    for (space, p) in placed {
+        svg.push_str(&format!(
+            r##"<g><rect x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="#333333" stroke-width="0.05"/>"##,
+            p.x,
+            p.y,
+            p.width,
+            p.height,
+            occupancy_color(space.occupancy_pct)
+        ));
+        let font_size = (p.width.min(p.height) / 4.0).max(0.1);
+        svg.push_str(&format!(
+            r#"<text x="{}" y="{}" font-size="{}" text-anchor="middle" dominant-baseline="middle">{}</text></g>"#,
+            p.x + p.width / 2.0,
+            p.y + p.height / 2.0,
+            font_size,
+            escape_xml(&space.name)
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// `text` with the characters XML reserves replaced by entities.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,75 @@
+//! Optional metadata envelope for list responses.
+//!
+//! Clients that keep a local cache can pass `?meta=true` to receive
+//! `{ "data": ..., "meta": { "server_time", "change_seq", "api_version" } }` instead of the
+//! bare payload. `change_seq` is the `change_log` sequence, which triggers bump on every
+//! mutation, so an unchanged value means the cached data is still current.
+
+use crate::errors::AppError;
+use actix_web::HttpResponse;
+use chrono::{SecondsFormat, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+use utoipa::IntoParams;
+
+/// Version reported in the `meta.api_version` field.
+pub const API_VERSION: &str = "1";
+
+/// Query switch enabling the metadata envelope.
+#[derive(Deserialize, IntoParams, Debug, Default, Clone, Copy)]
+#[into_params(parameter_in = Query)]
+pub struct MetaQuery {
+    /// Wrap the response as `{ "data": .., "meta": .. }`.
+    #[serde(default)]
+    pub meta: bool,
+}
+
+/// Metadata attached to enveloped responses.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ResponseMeta {
+    pub server_time: String,
+    pub change_seq: i64,
+    pub api_version: String,
+}
+
+/// Wrapper pairing a payload with its `ResponseMeta`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Envelope<T> {
+    pub data: T,
+    pub meta: ResponseMeta,
+}
+
+/// Reads the current value of the mutation counter.
+///
+/// Uses `sqlite_sequence` rather than `MAX(id)` so the value stays monotonic even if old
+/// `change_log` rows are pruned.
+pub fn current_change_seq(conn: &Connection) -> Result<i64, AppError> {
+    Ok(conn.query_row(
+        "SELECT COALESCE((SELECT seq FROM sqlite_sequence WHERE name = 'change_log'), 0)",
+        [],
+        |r| r.get(0),
+    )?)
+}
+
+/// Builds an HTTP 200 JSON response, wrapping `data` in an `Envelope` when requested.
+///
+/// # Errors
+/// Returns a database error if the change counter cannot be read.
+pub fn list_response<T: Serialize>(
+    conn: &Connection,
+    data: T,
+    query: MetaQuery,
+) -> Result<HttpResponse, AppError> {
+    if !query.meta {
+        return Ok(HttpResponse::Ok().json(data));
+    }
+
+    let meta = ResponseMeta {
+        server_time: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        change_seq: current_change_seq(conn)?,
+        api_version: API_VERSION.to_string(),
+    };
+    trace!(?meta, "Wrapping list response in envelope");
+    Ok(HttpResponse::Ok().json(Envelope { data, meta }))
+}
@@ -1,25 +1,163 @@
 //! Defines application-specific error types with descriptive messages
 //! and maps them to proper HTTP responses for API clients.
+//!
+//! Every error response is a JSON `ErrorBody`: `{ "error", "message", "code" }`, plus the
+//! invalid `fields` for validation failures.
 
-use actix_web::{HttpResponse, ResponseError};
+use actix_web::error::{InternalError, JsonPayloadError};
+use actix_web::http::StatusCode;
+use actix_web::http::header::{HeaderValue, RETRY_AFTER};
+use actix_web::{HttpResponse, ResponseError, web};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::fmt;
 use thiserror::Error;
+use utoipa::ToSchema;
 
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Database error: {0}")]
-    DbError(#[from] rusqlite::Error),
+    DbError(rusqlite::Error),
 
-    #[error("Connection pool error: {0}")]
+    /// A query ran past the connection's deadline and was interrupted.
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    /// No database connection freed up within the pool timeout.
+    #[error("Timed out waiting for a database connection: {0}")]
     PoolError(#[from] r2d2::Error),
 
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    /// The request body is larger than the route accepts.
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    /// The request carries content of a type the route does not accept.
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+
+    #[error("Validation failed: {} invalid field(s)", .0.len())]
+    Validation(Vec<FieldError>),
+
+    /// The request lacks the credentials the route requires.
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// A versioned write lost a race: the record changed since the client read it.
+    #[error("Version conflict: {0}")]
+    VersionConflict(String),
+
+    #[error("Internal error: {0}")]
+    Internal(String),
+
     #[error("Parsing error: {0}")]
     ParseError(#[from] ParseEnumError),
 }
 
+impl From<rusqlite::Error> for AppError {
+    /// Interrupted statements only come from the query watchdog, so they become
+    /// `Timeout`; everything else is a plain `DbError`.
+    fn from(e: rusqlite::Error) -> Self {
+        match e.sqlite_error_code() {
+            Some(rusqlite::ErrorCode::OperationInterrupted) => {
+                AppError::Timeout("The database query took too long and was cancelled".into())
+            }
+            _ => AppError::DbError(e),
+        }
+    }
+}
+
+/// Seconds clients are told to wait, via `Retry-After`, when the connection pool is
+/// exhausted.
+pub const POOL_RETRY_AFTER_SECS: u64 = 1;
+
+/// JSON extractor settings accepting bodies of up to `limit` bytes. Larger bodies are
+/// answered with `AppError::PayloadTooLarge`; other payload errors keep Actix's status
+/// but get an `ErrorBody`.
+pub fn json_config(limit: usize) -> web::JsonConfig {
+    web::JsonConfig::default()
+        .limit(limit)
+        .error_handler(move |err, _req| match err {
+            JsonPayloadError::Overflow { .. } | JsonPayloadError::OverflowKnownLength { .. } => {
+                AppError::PayloadTooLarge(format!("Request body exceeds the {} byte limit", limit))
+                    .into()
+            }
+            JsonPayloadError::ContentType => extractor_error("UnsupportedMediaType", err),
+            err => extractor_error("InvalidInput", err),
+        })
+}
+
+/// Query string extractor settings answering malformed queries with
+/// `AppError::InvalidInput`.
+pub fn query_config() -> web::QueryConfig {
+    web::QueryConfig::default().error_handler(|err, _req| {
+        AppError::InvalidInput(format!("Invalid query string: {}", err)).into()
+    })
+}
+
+/// Path extractor settings answering unparseable path segments, such as a non-numeric
+/// id, with `AppError::InvalidInput`.
+pub fn path_config() -> web::PathConfig {
+    web::PathConfig::default().error_handler(|err, _req| {
+        AppError::InvalidInput(format!("Invalid path parameter: {}", err)).into()
+    })
+}
+
+/// Wraps an extractor error in an `ErrorBody` of kind `error`, keeping its status.
+fn extractor_error<E: ResponseError + 'static>(error: &str, err: E) -> actix_web::Error {
+    tracing::warn!("Rejected request: {}", err);
+    let response = error_json(error, &err.to_string(), err.status_code().as_u16());
+    InternalError::from_response(err, response).into()
+}
+
+/// A single invalid field reported by payload validation.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// JSON body of every error response.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, PartialEq, Eq)]
+pub struct ErrorBody {
+    /// Machine-readable error kind, e.g. `VersionConflict`.
+    pub error: String,
+    pub message: String,
+    /// HTTP status code of the response.
+    pub code: u16,
+    /// Every invalid field, for `Validation` errors only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
+}
+
+/// An error response with an `ErrorBody` of kind `error` and status `code`. Unknown
+/// codes are answered as 500.
+pub fn error_json(error: &str, message: &str, code: u16) -> HttpResponse {
+    let status = StatusCode::from_u16(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    HttpResponse::build(status).json(json!({
+        "error": error,
+        "message": message,
+        "code": status.as_u16(),
+    }))
+}
+
 /// Error type for enum parsing failures with context.
 #[derive(Debug, Clone)]
 pub struct ParseEnumError {
@@ -49,25 +187,92 @@ impl fmt::Display for ParseEnumError {
 impl std::error::Error for ParseEnumError {}
 
 impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::DbError(_) | AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            AppError::PoolError(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::InvalidInput(_) | AppError::ParseError(_) => StatusCode::BAD_REQUEST,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) | AppError::VersionConflict(_) => StatusCode::CONFLICT,
+        }
+    }
+
     fn error_response(&self) -> HttpResponse {
+        let code = self.status_code().as_u16();
         match self {
             AppError::DbError(e) => {
-                // Log internal database errors with detail
+                // The detail stays in the log; it would reveal the schema to clients.
                 tracing::error!("Database error: {:?}", e);
-                HttpResponse::InternalServerError().body(format!("Internal database error: {}", e))
+                error_json("DbError", "Internal database error", code)
+            }
+            AppError::Timeout(msg) => {
+                tracing::warn!("Query timeout: {}", msg);
+                error_json("Timeout", msg, code)
             }
             AppError::PoolError(e) => {
-                // Log internal database errors with detail
-                tracing::error!("Connection pool error: {:?}", e);
-                HttpResponse::InternalServerError().body(format!("Internal database error: {}", e))
+                tracing::warn!("Connection pool exhausted: {}", e);
+                let mut response = error_json(
+                    "PoolExhausted",
+                    &format!(
+                        "The server is busy; retry after {} second(s)",
+                        POOL_RETRY_AFTER_SECS
+                    ),
+                    code,
+                );
+                response
+                    .headers_mut()
+                    .insert(RETRY_AFTER, HeaderValue::from(POOL_RETRY_AFTER_SECS));
+                response
             }
             AppError::InvalidInput(msg) => {
                 tracing::warn!("Invalid input error: {}", msg);
-                HttpResponse::BadRequest().body(msg.clone())
+                error_json("InvalidInput", msg, code)
+            }
+            AppError::PayloadTooLarge(msg) => {
+                tracing::warn!("Payload too large: {}", msg);
+                error_json("PayloadTooLarge", msg, code)
+            }
+            AppError::UnsupportedMediaType(msg) => {
+                tracing::warn!("Unsupported media type: {}", msg);
+                error_json("UnsupportedMediaType", msg, code)
+            }
+            AppError::Validation(errors) => {
+                tracing::warn!("Validation failed: {:?}", errors);
+                HttpResponse::UnprocessableEntity().json(ErrorBody {
+                    error: "Validation".to_string(),
+                    message: self.to_string(),
+                    code,
+                    fields: errors.clone(),
+                })
+            }
+            AppError::Unauthorized(msg) => {
+                tracing::warn!("Unauthorized request: {}", msg);
+                error_json("Unauthorized", msg, code)
+            }
+            AppError::NotFound(msg) => {
+                tracing::warn!("Not found error: {}", msg);
+                error_json("NotFound", msg, code)
+            }
+            AppError::Conflict(msg) => {
+                tracing::warn!("Conflict error: {}", msg);
+                error_json("Conflict", msg, code)
+            }
+            AppError::VersionConflict(msg) => {
+                tracing::warn!("Version conflict: {}", msg);
+                error_json("VersionConflict", msg, code)
+            }
+            AppError::Internal(msg) => {
+                tracing::error!("Internal error: {}", msg);
+                error_json("Internal", msg, code)
             }
             AppError::ParseError(e) => {
                 tracing::warn!("Parsing error: {}", e);
-                HttpResponse::BadRequest().body(format!("Parsing error: {}", e))
+                error_json("ParseError", &format!("Parsing error: {}", e), code)
             }
         }
     }
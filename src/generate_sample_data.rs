@@ -1,5 +1,6 @@
 //! Generates sample livestock data with vaccines, diseases, and relationships
 
+use backend::schema::ensure_schema;
 use chrono::NaiveDate;
 use rand::{Rng, seq::SliceRandom};
 use rusqlite::{Connection, Result, params};
@@ -12,8 +13,7 @@ fn main() -> Result<()> {
         .with_test_writer()
         .try_init();
     let conn = Connection::open("livestock.db")?;
-
-    // Load schema from file or ensure created manually before running this
+    ensure_schema(&conn).expect("Failed to create database tables");
 
     let mut rng = rand::thread_rng();
 
@@ -0,0 +1,42 @@
+//! Handler for the dashboard's recent-activity feed.
+
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::ActivityQuery;
+use actix_web::{HttpResponse, Responder, web};
+use tracing::{debug, info};
+
+/// Entries returned when `limit` is omitted.
+const DEFAULT_ACTIVITY_LIMIT: u32 = 20;
+/// Largest accepted `limit`.
+const MAX_ACTIVITY_LIMIT: u32 = 200;
+
+/// Handler listing the most recent changes across goats, workers and equipment.
+///
+/// # HTTP Method
+/// - `GET /activity?limit=20`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `{ entity_type, entity_id, name, action, timestamp }`,
+///   newest first. `limit` defaults to 20 and is clamped to 1–200. Deleted entities keep
+///   the name they had when the change was made.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Number of entries returned.
+pub async fn get_activity(
+    db: web::Data<DbPool>,
+    query: web::Query<ActivityQuery>,
+) -> Result<impl Responder, AppError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_ACTIVITY_LIMIT)
+        .clamp(1, MAX_ACTIVITY_LIMIT);
+    debug!(limit, "GET /activity called");
+
+    let conn = db.get_conn()?;
+    let entries = DbPool::recent_activity(&conn, limit)?;
+
+    info!(count = entries.len(), "Returning recent activity");
+    Ok(HttpResponse::Ok().json(entries))
+}
@@ -0,0 +1,241 @@
+//! Administrative endpoints for operating the server itself rather than farm data.
+
+use crate::config::{export_query_timeout, sensor_retention_days, sensor_rollup};
+use crate::db::{DbPool, set_setting};
+use crate::errors::AppError;
+use crate::jobs::{JobRegistry, prune_old_sensor_readings};
+use crate::models::{
+    ImportQuery, MaintenancePayload, ReclassifyPayload, SensorPruneQuery, Snapshot,
+};
+use crate::state::{AdminToken, AppState, READ_ONLY_SETTING};
+use actix_web::http::header::ContentDisposition;
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use chrono::Utc;
+use tracing::{debug, info};
+
+/// Handler for switching read-only maintenance mode on or off.
+///
+/// # HTTP Method
+/// - `POST /admin/maintenance`
+///
+/// # Request
+/// - JSON payload `{ "read_only": bool }`.
+///
+/// # Success
+/// - Returns HTTP 200 with the new flag value. The flag is persisted in the
+///   `settings` table so it survives restarts.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: New maintenance state.
+pub async fn set_maintenance(
+    db: web::Data<DbPool>,
+    state: web::Data<AppState>,
+    payload: web::Json<MaintenancePayload>,
+) -> Result<impl Responder, AppError> {
+    debug!(
+        read_only = payload.read_only,
+        "POST /admin/maintenance called"
+    );
+
+    let read_only = payload.read_only;
+    db.write(move |tx| set_setting(tx, READ_ONLY_SETTING, &read_only.to_string()))
+        .await?;
+    state.set_read_only(payload.read_only);
+
+    info!(read_only = payload.read_only, "Maintenance mode updated");
+    Ok(HttpResponse::Ok().json(*payload))
+}
+
+/// Handler exporting the whole database as one JSON document.
+///
+/// # HTTP Method
+/// - `GET /admin/export`
+///
+/// # Success
+/// - Returns HTTP 200 with a `Snapshot` (goats with relations, vaccines, diseases,
+///   workers, equipment, sensors, spaces) tagged with `schema_version`.
+///
+/// # Errors
+/// - Returns HTTP 504 if the export runs past `YAGI_EXPORT_QUERY_TIMEOUT_MS`, which
+///   replaces the usual query timeout for this route.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Export finished (counts are logged by the DB layer).
+pub async fn export_snapshot(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /admin/export called");
+    let conn = db.get_conn_with_timeout(export_query_timeout())?;
+    let snapshot = DbPool::export_snapshot(&conn)?;
+
+    info!("Returning database snapshot");
+    Ok(HttpResponse::Ok().json(snapshot))
+}
+
+/// Handler exporting the entity tables as a ZIP archive of CSV files.
+///
+/// # HTTP Method
+/// - `GET /admin/export/full`, with the `YAGI_ADMIN_TOKEN` value in `X-Admin-Token`.
+///
+/// # Success
+/// - Returns HTTP 200 with an `application/zip` attachment named
+///   `farm_export_YYYYMMDD.zip`, holding `goats.csv`, `workers.csv`, `equipment.csv`,
+///   `sensors.csv`, `spaces.csv`, `vaccines.csv`, `diseases.csv` and a `manifest.json`
+///   with the export time and the rows in each file.
+///
+/// # Errors
+/// - Returns HTTP 401 if the token is missing or wrong, or none is configured.
+/// - Returns HTTP 504 if the export runs past `YAGI_EXPORT_QUERY_TIMEOUT_MS`.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Warn: Requests refused for lack of a configured token.
+/// - Info: Export finished (counts are logged by the DB layer).
+pub async fn export_full_archive(
+    req: HttpRequest,
+    db: web::Data<DbPool>,
+    admin: Option<web::Data<AdminToken>>,
+) -> Result<impl Responder, AppError> {
+    debug!("GET /admin/export/full called");
+    match admin {
+        Some(admin) => admin.authorize(&req)?,
+        None => AdminToken::default().authorize(&req)?,
+    }
+    let conn = db.get_conn_with_timeout(export_query_timeout())?;
+    let archive = DbPool::export_full_snapshot(&conn)?;
+
+    let filename = format!("farm_export_{}.zip", Utc::now().format("%Y%m%d"));
+    info!(filename = %filename, size = archive.len(), "Returning full archive");
+    Ok(HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header(ContentDisposition::attachment(filename))
+        .body(archive))
+}
+
+/// Handler loading a snapshot document inside a single transaction.
+///
+/// # HTTP Method
+/// - `POST /admin/import?mode=replace|merge`
+///
+/// # Request
+/// - JSON `Snapshot` as produced by `GET /admin/export`.
+///
+/// # Success
+/// - Returns HTTP 200 with an `ImportSummary` of records written per entity.
+///
+/// # Errors
+/// - Returns HTTP 400 for an unknown `schema_version`; nothing is written on any error.
+/// - Returns HTTP 413 if the body exceeds `YAGI_MAX_IMPORT_BODY_BYTES`.
+///
+/// # Logs
+/// - Info: Receipt of the import and its committed summary.
+pub async fn import_snapshot(
+    db: web::Data<DbPool>,
+    query: web::Query<ImportQuery>,
+    snapshot: web::Json<Snapshot>,
+) -> Result<impl Responder, AppError> {
+    info!(mode = ?query.mode, version = snapshot.schema_version, "POST /admin/import called");
+
+    let summary = db
+        .write(move |tx| DbPool::import_snapshot(tx, &snapshot, query.mode))
+        .await?;
+
+    info!(?summary, "Import committed");
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+/// Handler reclassifying castrated bucks recorded as Male to Wether.
+///
+/// # HTTP Method
+/// - `POST /admin/goats/reclassify-wethers`
+///
+/// # Request
+/// - JSON payload `{ "goat_ids": [i64] }`.
+///
+/// # Success
+/// - Returns HTTP 200 with a `ReclassifySummary`. Ids that are missing, deleted, or not
+///   Male are listed under `skipped`; the rest are updated in one transaction.
+///
+/// # Logs
+/// - Info: Receipt of the request and its outcome.
+pub async fn reclassify_wethers(
+    db: web::Data<DbPool>,
+    payload: web::Json<ReclassifyPayload>,
+) -> Result<impl Responder, AppError> {
+    info!(
+        count = payload.goat_ids.len(),
+        "POST /admin/goats/reclassify-wethers called"
+    );
+
+    let summary = db
+        .write(move |tx| DbPool::reclassify_wethers(tx, &payload.goat_ids))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+/// Handler pruning old sensor readings on demand, as the `sensor_retention` job does.
+///
+/// # HTTP Method
+/// - `POST /admin/sensors/prune?older_than_days=90&rollup=true`
+///
+/// # Success
+/// - Returns HTTP 200 with a `SensorPruneSummary`. Readings recorded more than
+///   `older_than_days` days ago (default `YAGI_SENSOR_RETENTION_DAYS`) are deleted in
+///   batches, after being summarised per sensor and day into `sensor_reading_daily`
+///   when `rollup` (default `YAGI_SENSOR_ROLLUP`) is true.
+///
+/// # Logs
+/// - Info: Receipt of the request; the DB layer logs the summary.
+pub async fn prune_sensor_readings(
+    db: web::Data<DbPool>,
+    query: web::Query<SensorPruneQuery>,
+) -> Result<impl Responder, AppError> {
+    let older_than_days = query.older_than_days.unwrap_or_else(sensor_retention_days);
+    let rollup = query.rollup.unwrap_or_else(sensor_rollup);
+    info!(older_than_days, rollup, "POST /admin/sensors/prune called");
+
+    // Pruning commits batch by batch, so it runs as a whole on a blocking thread rather
+    // than inside one `DbPool::write` transaction.
+    let pool = db.get_ref().clone();
+    let summary = web::block(move || prune_old_sensor_readings(&pool, older_than_days, rollup))
+        .await
+        .map_err(|e| AppError::Internal(format!("Prune task failed: {}", e)))??;
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+/// Handler listing registered background jobs and how their last runs went.
+///
+/// # HTTP Method
+/// - `GET /admin/jobs`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `JobStatus` in registration order.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+pub async fn list_jobs(registry: web::Data<JobRegistry>) -> Result<impl Responder, AppError> {
+    debug!("GET /admin/jobs called");
+    Ok(HttpResponse::Ok().json(registry.statuses()))
+}
+
+/// Handler scanning the live goats for common data entry errors.
+///
+/// # HTTP Method
+/// - `GET /admin/data-quality`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ issues: [{ goat_id, goat_name, issue }], total_issues,
+///   quality_score_pct }`. Checks cover zero weight or cost, a future `last_bred` date,
+///   sick goats without a disease record, goats without vaccinations, and Male goats
+///   marked pregnant. The score is the percentage of goats with no issues.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Scan totals (logged by the DB layer).
+pub async fn get_data_quality(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /admin/data-quality called");
+    let conn = db.get_conn()?;
+    let report = DbPool::data_quality_scan(&conn)?;
+    Ok(HttpResponse::Ok().json(report))
+}
@@ -0,0 +1,154 @@
+//! Batch endpoint letting offline clients flush a queue of mixed operations at once.
+//!
+//! All operations run inside a single transaction. Each one is wrapped in its own
+//! savepoint, so in the default mode a failing operation is rolled back on its own while
+//! the rest are kept; with `?atomic=true` the first failure aborts the whole batch.
+
+use crate::db::{DbPool, delete_goat_by_name, update_goat_by_name};
+use crate::errors::AppError;
+use crate::models::{
+    BatchOp, BatchOperation, BatchQuery, BatchResponse, BatchResult, GoatPayload,
+    GoatUpdatePayload, NamePayload,
+};
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use rusqlite::Connection;
+use serde::de::DeserializeOwned;
+use tracing::{debug, info, warn};
+
+/// Deserializes an operation payload, reporting the entity it was meant for on failure.
+fn parse_payload<T: DeserializeOwned>(operation: &BatchOperation) -> Result<T, AppError> {
+    serde_json::from_value(operation.payload.clone())
+        .map_err(|e| AppError::InvalidInput(format!("Invalid {} payload: {}", operation.entity, e)))
+}
+
+/// Applies a single batch operation, returning the id of the affected record if known.
+fn apply_operation(conn: &Connection, operation: &BatchOperation) -> Result<Option<i64>, AppError> {
+    match operation.entity.as_str() {
+        "goat" => match operation.op {
+            BatchOp::Create => {
+                let goat: GoatPayload = parse_payload(operation)?;
+                goat.validate()?;
+                DbPool::insert_goat(conn, &goat.into_metric()).map(Some)
+            }
+            BatchOp::Update => {
+                let GoatUpdatePayload { version, payload } = parse_payload(operation)?;
+                payload.validate()?;
+                update_goat_by_name(conn, &payload.into_metric(), Some(version)).map(Some)
+            }
+            BatchOp::Delete => {
+                let payload: NamePayload = parse_payload(operation)?;
+                delete_goat_by_name(conn, &payload.name).map(|_| None)
+            }
+        },
+        other => Err(AppError::InvalidInput(format!(
+            "Unsupported batch entity '{}'",
+            other
+        ))),
+    }
+}
+
+/// Applies `operations` in order on `conn`, each in its own savepoint so that a failing
+/// one leaves no partial writes. With `atomic`, the first failure also undoes every
+/// earlier operation and stops the batch. Returns whether the batch's writes stand, and
+/// one result per operation attempted.
+fn apply_batch(
+    conn: &Connection,
+    operations: &[BatchOperation],
+    atomic: bool,
+) -> Result<(bool, Vec<BatchResult>), AppError> {
+    conn.execute_batch("SAVEPOINT batch")?;
+    let mut results = Vec::with_capacity(operations.len());
+
+    for (index, operation) in operations.iter().enumerate() {
+        conn.execute_batch("SAVEPOINT batch_operation")?;
+        let outcome = apply_operation(conn, operation);
+        // Rolling back to the savepoint undoes whatever the failed operation wrote.
+        conn.execute_batch(if outcome.is_ok() {
+            "RELEASE batch_operation"
+        } else {
+            "ROLLBACK TO batch_operation; RELEASE batch_operation"
+        })?;
+
+        match outcome {
+            Ok(id) => {
+                debug!(index, op = ?operation.op, id, "Batch operation applied");
+                results.push(BatchResult {
+                    index,
+                    success: true,
+                    id,
+                    error: None,
+                });
+            }
+            Err(e) => {
+                warn!(index, op = ?operation.op, "Batch operation failed: {}", e);
+                results.push(BatchResult {
+                    index,
+                    success: false,
+                    id: None,
+                    error: Some(e.to_string()),
+                });
+                if atomic {
+                    conn.execute_batch("ROLLBACK TO batch; RELEASE batch")?;
+                    info!(index, "Atomic batch rolled back");
+                    return Ok((false, results));
+                }
+            }
+        }
+    }
+
+    conn.execute_batch("RELEASE batch")?;
+    Ok((true, results))
+}
+
+/// Handler executing a list of queued operations in order.
+///
+/// # HTTP Method
+/// - `POST /batch[?atomic=true]`
+///
+/// # Request
+/// - JSON array of `{ "op": "create|update|delete", "entity": "goat", "payload": {...} }`.
+///   Update payloads carry the goat's `version` as last read, as for `PUT /goats`.
+///
+/// # Success
+/// - Returns HTTP 200 with `{ "committed": true, "results": [...] }`, one result per
+///   operation. Without `atomic`, individual failures are reported but do not prevent
+///   the other operations from being committed.
+///
+/// # Errors
+/// - With `atomic=true`, returns HTTP 422 with `committed: false` and the results up to
+///   and including the first failure; nothing is written.
+///
+/// # Logs
+/// - Info: Receipt of the batch and its final outcome.
+/// - Debug: Each operation applied.
+/// - Warn: Each failed operation.
+pub async fn run_batch(
+    db: web::Data<DbPool>,
+    query: web::Query<BatchQuery>,
+    operations: web::Json<Vec<BatchOperation>>,
+) -> Result<impl Responder, AppError> {
+    info!(
+        count = operations.len(),
+        atomic = query.atomic,
+        "POST /batch called"
+    );
+
+    let atomic = query.atomic;
+    let operations = operations.into_inner();
+    let (committed, results) = db
+        .write(move |tx| apply_batch(tx, &operations, atomic))
+        .await?;
+    if !committed {
+        return Ok(HttpResponse::UnprocessableEntity().json(BatchResponse {
+            committed: false,
+            results,
+        }));
+    }
+
+    info!(count = results.len(), "Batch committed");
+    Ok(HttpResponse::Ok().json(BatchResponse {
+        committed: true,
+        results,
+    }))
+}
@@ -0,0 +1,107 @@
+//! Handlers for behavior observations and the recent-concern watchlist.
+
+use crate::db::{DbPool, ensure_goat_exists, raise_alert};
+use crate::db_helpers::parse_timestamp;
+use crate::errors::AppError;
+use crate::models::BehaviorObservation;
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use tracing::{debug, info};
+
+/// Handler recording a behavior observation for a goat.
+///
+/// # HTTP Method
+/// - `POST /goats/{id}/behavior`
+///
+/// # Request
+/// - JSON `{ "behavior": string, "intensity": "Mild"|"Moderate"|"Severe",
+///   "recorded_at"?: timestamp, "observed_by"?: string, "notes"?: string }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored observation. A Severe Off_Feed observation also
+///   raises an `alerts` row in the same transaction.
+///
+/// # Errors
+/// - Returns HTTP 422 for an unknown behavior or intensity, or a malformed `recorded_at`.
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn add_behavior_observation(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    observation: web::Json<BehaviorObservation>,
+) -> Result<impl Responder, AppError> {
+    let mut observation = observation.into_inner();
+    observation.goat_id = path.into_inner();
+    info!(
+        goat_id = observation.goat_id,
+        behavior = %observation.behavior,
+        intensity = %observation.intensity,
+        "POST /goats/{{id}}/behavior called"
+    );
+    observation.validate()?;
+    observation.recorded_at = observation
+        .recorded_at
+        .as_deref()
+        .map(|ts| parse_timestamp("recorded_at", ts))
+        .transpose()?;
+
+    let stored = db
+        .write(move |tx| {
+            let stored = DbPool::insert_behavior_observation(tx, &observation)?;
+            if stored.behavior == "Off_Feed" && stored.intensity == "Severe" {
+                raise_alert(
+                    tx,
+                    Some(stored.goat_id),
+                    "behavior",
+                    "Severe Off_Feed behavior observed",
+                )?;
+            }
+            Ok(stored)
+        })
+        .await?;
+
+    Ok(HttpResponse::Created().json(stored))
+}
+
+/// Handler listing a goat's behavior observations, most recent first.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/behavior/history`
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn get_behavior_history(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, "GET /goats/{{id}}/behavior/history called");
+
+    let conn = db.get_conn()?;
+    ensure_goat_exists(&conn, goat_id)?;
+    let history = DbPool::behavior_history(&conn, goat_id)?;
+
+    info!(goat_id, count = history.len(), "Returning behavior history");
+    Ok(HttpResponse::Ok().json(history))
+}
+
+/// Handler listing goats with a concerning behavior observed in the last 24 hours.
+///
+/// # HTTP Method
+/// - `GET /goats/behavior/concerning`
+///
+/// # Success
+/// - Returns HTTP 200 with goats (with ids and relations) plus the `behavior`,
+///   `intensity`, and `recorded_at` of their latest Lethargic, Aggressive,
+///   Abnormal_Gait, or Off_Feed observation, newest first.
+pub async fn get_concerning_behavior(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /goats/behavior/concerning called");
+
+    let conn = db.get_conn()?;
+    let concerns = DbPool::behavior_concerns(&conn)?;
+
+    info!(
+        count = concerns.len(),
+        "Returning goats with concerning behavior"
+    );
+    Ok(HttpResponse::Ok().json(concerns))
+}
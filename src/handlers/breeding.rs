@@ -0,0 +1,157 @@
+//! Handlers for goat pedigrees and mating plans.
+
+use crate::db::{DbPool, ensure_goat_exists};
+use crate::domain::breeding::{Parents, ancestors_within, plan_matings};
+use crate::errors::{AppError, FieldError};
+use crate::models::{BreedingPlanRequest, Gender};
+use actix_web::{HttpResponse, Responder, web};
+use rusqlite::Connection;
+use tracing::{debug, info, warn};
+
+/// Pushes a `FieldError` on `field` unless `goat_id` is a live goat of `gender`.
+fn check_parent(
+    conn: &Connection,
+    errors: &mut Vec<FieldError>,
+    field: &str,
+    goat_id: i64,
+    gender: Gender,
+) -> Result<(), AppError> {
+    let expected = match gender {
+        Gender::Female => "must be a live female goat",
+        _ => "must be a live male goat",
+    };
+    let matches = matches!(
+        (DbPool::live_goat_gender(conn, goat_id)?, gender),
+        (Some(Gender::Male), Gender::Male) | (Some(Gender::Female), Gender::Female)
+    );
+    if !matches {
+        errors.push(FieldError::new(field, expected));
+    }
+    Ok(())
+}
+
+/// Handler recording a goat's sire and dam.
+///
+/// # HTTP Method
+/// - `PUT /goats/{id}/pedigree`
+///
+/// # Request
+/// - JSON `{ "sire_id": i64 | null, "dam_id": i64 | null }`; null marks a parent unknown.
+///
+/// # Success
+/// - Returns HTTP 200 with the stored `{ sire_id, dam_id }`, replacing any earlier record.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+/// - Returns HTTP 422 unless the sire is a live male and the dam a live female, or if
+///   either is the goat itself or one of its descendants.
+///
+/// # Logs
+/// - Info: Receipt of the request.
+/// - Warn: Parentage that would make a goat its own ancestor.
+pub async fn set_goat_pedigree(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    parents: web::Json<Parents>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    let parents = parents.into_inner();
+    info!(goat_id, ?parents, "PUT /goats/{{id}}/pedigree called");
+
+    db.write(move |tx| {
+        ensure_goat_exists(tx, goat_id)?;
+        let mut errors = Vec::new();
+        if let Some(sire_id) = parents.sire_id {
+            check_parent(tx, &mut errors, "sire_id", sire_id, Gender::Male)?;
+        }
+        if let Some(dam_id) = parents.dam_id {
+            check_parent(tx, &mut errors, "dam_id", dam_id, Gender::Female)?;
+        }
+
+        let pedigree = DbPool::load_pedigree(tx)?;
+        let all_generations = pedigree.len() as u32 + 1;
+        for (field, parent) in [("sire_id", parents.sire_id), ("dam_id", parents.dam_id)] {
+            let Some(parent) = parent else { continue };
+            if ancestors_within(&pedigree, parent, all_generations).contains_key(&goat_id) {
+                warn!(goat_id, parent, "Parent descends from the goat");
+                errors.push(FieldError::new(
+                    field,
+                    "must not be the goat itself or one of its descendants",
+                ));
+            }
+        }
+        if !errors.is_empty() {
+            debug!(?errors, "Pedigree failed validation");
+            return Err(AppError::Validation(errors));
+        }
+        DbPool::set_pedigree(tx, goat_id, &parents)
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(parents))
+}
+
+/// Handler suggesting a sire for each dam that keeps the kids' inbreeding lowest.
+///
+/// # HTTP Method
+/// - `POST /breeding/plan`
+///
+/// # Request
+/// - JSON `{ "sire_ids": [i64], "dam_ids": [i64] }`, candidate live males and females.
+///
+/// # Success
+/// - Returns HTTP 200 with one `{ dam_id, sire_id, inbreeding_coefficient,
+///   excluded_sire_ids }` per dam, best pairing first. Sires sharing a parent or
+///   grandparent with a dam, or descending from her or she from them, are excluded for
+///   her; the rest are ranked by their kinship with her from the recorded pedigree.
+///   `sire_id` is null when every candidate is excluded.
+///
+/// # Errors
+/// - Returns HTTP 422 for empty lists, or ids that are not live goats of the right
+///   gender.
+///
+/// # Logs
+/// - Info: Receipt of the request and the number of dams matched.
+pub async fn plan_breeding(
+    db: web::Data<DbPool>,
+    request: web::Json<BreedingPlanRequest>,
+) -> Result<impl Responder, AppError> {
+    info!(
+        sires = request.sire_ids.len(),
+        dams = request.dam_ids.len(),
+        "POST /breeding/plan called"
+    );
+    let conn = db.get_conn()?;
+
+    let mut errors = Vec::new();
+    for (field, ids) in [
+        ("sire_ids", &request.sire_ids),
+        ("dam_ids", &request.dam_ids),
+    ] {
+        if ids.is_empty() {
+            errors.push(FieldError::new(field, "must list at least one goat"));
+        }
+    }
+    for (i, &sire_id) in request.sire_ids.iter().enumerate() {
+        let field = format!("sire_ids[{}]", i);
+        check_parent(&conn, &mut errors, &field, sire_id, Gender::Male)?;
+    }
+    for (i, &dam_id) in request.dam_ids.iter().enumerate() {
+        let field = format!("dam_ids[{}]", i);
+        check_parent(&conn, &mut errors, &field, dam_id, Gender::Female)?;
+    }
+    if !errors.is_empty() {
+        debug!(?errors, "Breeding plan request failed validation");
+        return Err(AppError::Validation(errors));
+    }
+
+    let pedigree = DbPool::load_pedigree(&conn)?;
+    let plan = plan_matings(&pedigree, &request.sire_ids, &request.dam_ids);
+
+    info!(
+        matched = plan.iter().filter(|s| s.sire_id.is_some()).count(),
+        dams = plan.len(),
+        "Returning breeding plan"
+    );
+    Ok(HttpResponse::Ok().json(plan))
+}
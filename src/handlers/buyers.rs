@@ -0,0 +1,142 @@
+//! Handlers for buyers and the goats they have bought.
+
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::Buyer;
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use serde_json::json;
+use tracing::{debug, info};
+
+/// Handler adding a buyer.
+///
+/// # HTTP Method
+/// - `POST /buyers`
+///
+/// # Request
+/// - JSON `{ "name": string, "contact"?: string, "notes"?: string }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored buyer, including its new `id`.
+///
+/// # Errors
+/// - Returns HTTP 422 for an empty name.
+/// - Returns HTTP 409 if a buyer with that name (ignoring case) exists.
+pub async fn create_buyer(
+    db: web::Data<DbPool>,
+    buyer: web::Json<Buyer>,
+) -> Result<impl Responder, AppError> {
+    info!(name = %buyer.name, "POST /buyers called");
+    buyer.validate()?;
+
+    let created = db.write(move |tx| DbPool::create_buyer(tx, &buyer)).await?;
+    Ok(HttpResponse::Created().json(created))
+}
+
+/// Handler listing all buyers by name.
+///
+/// # HTTP Method
+/// - `GET /buyers`
+pub async fn list_buyers(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /buyers called");
+    let conn = db.get_conn()?;
+    let buyers = DbPool::list_buyers(&conn)?;
+
+    info!(count = buyers.len(), "Returning buyers");
+    Ok(HttpResponse::Ok().json(buyers))
+}
+
+/// Handler returning one buyer.
+///
+/// # HTTP Method
+/// - `GET /buyers/{id}`
+///
+/// # Errors
+/// - Returns HTTP 404 if the buyer does not exist.
+pub async fn get_buyer(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let buyer_id = path.into_inner();
+    debug!(buyer_id, "GET /buyers/{{id}} called");
+
+    let conn = db.get_conn()?;
+    let buyer = DbPool::get_buyer(&conn, buyer_id)?;
+    Ok(HttpResponse::Ok().json(buyer))
+}
+
+/// Handler replacing a buyer's name, contact and notes.
+///
+/// # HTTP Method
+/// - `PUT /buyers/{id}`
+///
+/// # Success
+/// - Returns HTTP 200 with the stored buyer.
+///
+/// # Errors
+/// - Returns HTTP 422 for an empty name.
+/// - Returns HTTP 404 if the buyer does not exist.
+/// - Returns HTTP 409 if another buyer has the new name.
+pub async fn update_buyer(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    buyer: web::Json<Buyer>,
+) -> Result<impl Responder, AppError> {
+    let buyer_id = path.into_inner();
+    info!(buyer_id, name = %buyer.name, "PUT /buyers/{{id}} called");
+    buyer.validate()?;
+
+    let updated = db
+        .write(move |tx| DbPool::update_buyer(tx, buyer_id, &buyer))
+        .await?;
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Handler deleting a buyer. Their sales are kept, with the buyer name recorded at
+/// sale time, but no longer linked to any buyer.
+///
+/// # HTTP Method
+/// - `DELETE /buyers/{id}`
+///
+/// # Errors
+/// - Returns HTTP 404 if the buyer does not exist.
+pub async fn delete_buyer(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let buyer_id = path.into_inner();
+    info!(buyer_id, "DELETE /buyers/{{id}} called");
+
+    db.write(move |tx| DbPool::delete_buyer(tx, buyer_id))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(json!({ "message": "Buyer deleted" })))
+}
+
+/// Handler listing the goats a buyer has bought.
+///
+/// # HTTP Method
+/// - `GET /buyers/{id}/purchases`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `BuyerPurchase`, most recent sale first.
+///
+/// # Errors
+/// - Returns HTTP 404 if the buyer does not exist.
+pub async fn get_buyer_purchases(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let buyer_id = path.into_inner();
+    debug!(buyer_id, "GET /buyers/{{id}}/purchases called");
+
+    let conn = db.get_conn()?;
+    let purchases = DbPool::buyer_purchases(&conn, buyer_id)?;
+
+    info!(
+        buyer_id,
+        count = purchases.len(),
+        "Returning buyer purchases"
+    );
+    Ok(HttpResponse::Ok().json(purchases))
+}
@@ -0,0 +1,297 @@
+//! Endpoints for the breed, vaccine, and disease catalogs shared by all goats.
+
+use crate::db::{
+    BREEDS_VERSION_SETTING, CatalogEntry, DISEASES_VERSION_SETTING, DbPool,
+    VACCINES_VERSION_SETTING, get_or_insert_disease, get_or_insert_vaccine, reference_version,
+};
+use crate::errors::AppError;
+use crate::models::{CatalogName, CustomBreed, DiseaseRef, MergePayload, VaccineRef};
+use actix_web::http::header::{CacheControl, CacheDirective, ETag, EntityTag, Header, IfNoneMatch};
+use actix_web::{HttpRequest, HttpResponse, Responder, web};
+use serde::Serialize;
+use tracing::{debug, info};
+
+/// How long clients may reuse a reference list without revalidating it.
+pub const REFERENCE_MAX_AGE_SECS: u32 = 3600;
+
+/// Answers a conditional GET of the reference list `list` at `version`.
+///
+/// Returns HTTP 304 when `If-None-Match` already names the current tag. Otherwise the
+/// list is loaded with `load` and returned as JSON. Both responses carry the `ETag`
+/// and `Cache-Control` headers. The version must be read before the list: a write in
+/// between then only makes the tag older than the data, never newer.
+fn reference_response<T: Serialize>(
+    req: &HttpRequest,
+    list: &str,
+    version: i64,
+    load: impl FnOnce() -> Result<T, AppError>,
+) -> Result<HttpResponse, AppError> {
+    let etag = EntityTag::new_strong(format!("{}-{}", list, version));
+    let cache_control = CacheControl(vec![
+        CacheDirective::Public,
+        CacheDirective::MaxAge(REFERENCE_MAX_AGE_SECS),
+    ]);
+    let fresh = match IfNoneMatch::parse(req) {
+        Ok(IfNoneMatch::Any) => true,
+        Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&etag)),
+        Err(_) => false,
+    };
+    if fresh {
+        debug!(list, version, "Reference list not modified");
+        return Ok(HttpResponse::NotModified()
+            .insert_header(ETag(etag))
+            .insert_header(cache_control)
+            .finish());
+    }
+
+    let body = load()?;
+    Ok(HttpResponse::Ok()
+        .insert_header(ETag(etag))
+        .insert_header(cache_control)
+        .json(body))
+}
+
+/// Handler folding duplicate vaccines (e.g. "CDT" and "cdt") into a single entry.
+///
+/// # HTTP Method
+/// - `POST /vaccines/{keep_id}/merge`
+///
+/// # Request
+/// - JSON payload `{ "duplicate_ids": [..] }`.
+///
+/// # Success
+/// - Returns HTTP 200 with a `MergeSummary`. Goat links are re-pointed to `keep_id`,
+///   double-links are dropped, the duplicates are deleted and the merge is audited,
+///   all in one transaction.
+///
+/// # Errors
+/// - Returns HTTP 400 if `duplicate_ids` is empty or contains `keep_id`.
+/// - Returns HTTP 404 if any id does not exist; nothing is changed.
+///
+/// # Logs
+/// - Info: Receipt of the merge request; the DB layer logs the outcome.
+pub async fn merge_vaccines(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    payload: web::Json<MergePayload>,
+) -> Result<impl Responder, AppError> {
+    let keep_id = path.into_inner();
+    info!(keep_id, duplicates = ?payload.duplicate_ids, "POST /vaccines/{{keep_id}}/merge called");
+
+    let summary = db
+        .write(move |tx| DbPool::merge_vaccines(tx, keep_id, &payload.duplicate_ids))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+/// Handler folding duplicate diseases into a single entry.
+///
+/// # HTTP Method
+/// - `POST /diseases/{keep_id}/merge`
+///
+/// Behaves exactly like `merge_vaccines`, operating on `diseases` and `goat_diseases`.
+pub async fn merge_diseases(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    payload: web::Json<MergePayload>,
+) -> Result<impl Responder, AppError> {
+    let keep_id = path.into_inner();
+    info!(keep_id, duplicates = ?payload.duplicate_ids, "POST /diseases/{{keep_id}}/merge called");
+
+    let summary = db
+        .write(move |tx| DbPool::merge_diseases(tx, keep_id, &payload.duplicate_ids))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+/// Handler listing every breed name the UI should offer.
+///
+/// # HTTP Method
+/// - `GET /breeds`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of names: the built-in breeds in their usual
+///   order, followed by registered custom breeds and any other breeds recorded on goats.
+/// - Returns HTTP 304 when `If-None-Match` matches the current `ETag`. Responses may be
+///   cached for an hour.
+pub async fn list_breeds(
+    db: web::Data<DbPool>,
+    req: HttpRequest,
+) -> Result<impl Responder, AppError> {
+    debug!("GET /breeds called");
+    let conn = db.get_conn()?;
+    let version = reference_version(&conn, BREEDS_VERSION_SETTING)?;
+
+    reference_response(&req, "breeds", version, || {
+        let breeds = DbPool::list_breeds(&conn)?;
+        info!(count = breeds.len(), "Returning breeds");
+        Ok(breeds)
+    })
+}
+
+/// Handler registering a custom breed so it is listed before any goat uses it.
+///
+/// # HTTP Method
+/// - `POST /breeds`
+///
+/// # Request
+/// - JSON `{ "name": string }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the new `CustomBreed`, or HTTP 200 with the existing entry if
+///   the name is already registered ignoring case.
+///
+/// # Errors
+/// - Returns HTTP 400 for an empty name and HTTP 409 for a built-in breed name.
+pub async fn register_breed(
+    db: web::Data<DbPool>,
+    breed: web::Json<CustomBreed>,
+) -> Result<impl Responder, AppError> {
+    info!(name = %breed.name, "POST /breeds called");
+    let (stored, created) = db
+        .write(move |tx| DbPool::register_custom_breed(tx, &breed.name))
+        .await?;
+
+    if created {
+        Ok(HttpResponse::Created().json(stored))
+    } else {
+        Ok(HttpResponse::Ok().json(stored))
+    }
+}
+
+/// Handler listing the vaccine catalog.
+///
+/// # HTTP Method
+/// - `GET /vaccines`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `{ id, name }`, ordered by name.
+/// - Returns HTTP 304 when `If-None-Match` matches the current `ETag`. Responses may be
+///   cached for an hour.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Number of vaccines returned.
+pub async fn list_vaccines(
+    db: web::Data<DbPool>,
+    req: HttpRequest,
+) -> Result<impl Responder, AppError> {
+    debug!("GET /vaccines called");
+    let conn = db.get_conn()?;
+    let version = reference_version(&conn, VACCINES_VERSION_SETTING)?;
+
+    reference_response(&req, "vaccines", version, || {
+        let vaccines = DbPool::list_vaccines(&conn)?;
+        info!(count = vaccines.len(), "Returning vaccines");
+        Ok(vaccines)
+    })
+}
+
+/// Handler listing the disease catalog.
+///
+/// # HTTP Method
+/// - `GET /diseases`
+///
+/// Behaves exactly like `list_vaccines`, listing `diseases`.
+pub async fn list_diseases(
+    db: web::Data<DbPool>,
+    req: HttpRequest,
+) -> Result<impl Responder, AppError> {
+    debug!("GET /diseases called");
+    let conn = db.get_conn()?;
+    let version = reference_version(&conn, DISEASES_VERSION_SETTING)?;
+
+    reference_response(&req, "diseases", version, || {
+        let diseases = DbPool::list_diseases(&conn)?;
+        info!(count = diseases.len(), "Returning diseases");
+        Ok(diseases)
+    })
+}
+
+/// Handler adding a vaccine to the catalog.
+///
+/// # HTTP Method
+/// - `POST /vaccines`
+///
+/// # Request
+/// - JSON `{ "name": string }`; surrounding whitespace is trimmed.
+///
+/// # Success
+/// - Returns HTTP 201 with the new `{ id, name }`, or HTTP 200 with the existing entry if
+///   the name is already listed ignoring case.
+///
+/// # Errors
+/// - Returns HTTP 400 for a blank name.
+///
+/// # Logs
+/// - Info: Receipt of the request.
+pub async fn add_vaccine(
+    db: web::Data<DbPool>,
+    payload: web::Json<CatalogName>,
+) -> Result<impl Responder, AppError> {
+    info!(name = %payload.name, "POST /vaccines called");
+    let vaccine = VaccineRef {
+        id: None,
+        name: payload.name.clone(),
+    };
+    let (entry, name) = db
+        .write(move |tx| {
+            let entry = get_or_insert_vaccine(tx, &vaccine)?;
+            let name: String = tx.query_row(
+                "SELECT name FROM vaccines WHERE id = ?1",
+                [entry.id()],
+                |r| r.get(0),
+            )?;
+            Ok((entry, name))
+        })
+        .await?;
+
+    Ok(catalog_entry_response(entry).json(VaccineRef {
+        id: Some(entry.id()),
+        name,
+    }))
+}
+
+/// Handler adding a disease to the catalog.
+///
+/// # HTTP Method
+/// - `POST /diseases`
+///
+/// Behaves exactly like `add_vaccine`, adding to `diseases`.
+pub async fn add_disease(
+    db: web::Data<DbPool>,
+    payload: web::Json<CatalogName>,
+) -> Result<impl Responder, AppError> {
+    info!(name = %payload.name, "POST /diseases called");
+    let disease = DiseaseRef {
+        id: None,
+        name: payload.name.clone(),
+    };
+    let (entry, name) = db
+        .write(move |tx| {
+            let entry = get_or_insert_disease(tx, &disease)?;
+            let name: String = tx.query_row(
+                "SELECT name FROM diseases WHERE id = ?1",
+                [entry.id()],
+                |r| r.get(0),
+            )?;
+            Ok((entry, name))
+        })
+        .await?;
+
+    Ok(catalog_entry_response(entry).json(DiseaseRef {
+        id: Some(entry.id()),
+        name,
+    }))
+}
+
+/// HTTP 201 for a newly inserted catalog entry, HTTP 200 for one that already existed.
+fn catalog_entry_response(entry: CatalogEntry) -> actix_web::HttpResponseBuilder {
+    if entry.inserted() {
+        HttpResponse::Created()
+    } else {
+        HttpResponse::Ok()
+    }
+}
@@ -0,0 +1,139 @@
+//! Handlers for cohorts: named, possibly overlapping groups of goats.
+
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::{Cohort, CohortMember};
+use actix_web::{HttpResponse, Responder, web};
+use serde_json::json;
+use tracing::{debug, info};
+
+/// Handler creating a cohort.
+///
+/// # HTTP Method
+/// - `POST /cohorts`
+///
+/// # Request
+/// - JSON `{ "name": string, "description"?: string }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored cohort.
+///
+/// # Errors
+/// - Returns HTTP 400 for an empty name and HTTP 409 for a duplicate one.
+pub async fn create_cohort(
+    db: web::Data<DbPool>,
+    cohort: web::Json<Cohort>,
+) -> Result<impl Responder, AppError> {
+    info!(name = %cohort.name, "POST /cohorts called");
+    if cohort.name.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Cohort name must not be empty".to_string(),
+        ));
+    }
+
+    let created = db
+        .write(move |tx| DbPool::create_cohort(tx, &cohort))
+        .await?;
+    Ok(HttpResponse::Created().json(created))
+}
+
+/// Handler listing all cohorts by name.
+///
+/// # HTTP Method
+/// - `GET /cohorts`
+pub async fn list_cohorts(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /cohorts called");
+    let conn = db.get_conn()?;
+    let cohorts = DbPool::list_cohorts(&conn)?;
+    Ok(HttpResponse::Ok().json(cohorts))
+}
+
+/// Handler adding a goat to a cohort. A goat may belong to any number of cohorts.
+///
+/// # HTTP Method
+/// - `POST /cohorts/{id}/add`
+///
+/// # Request
+/// - JSON `{ "goat_id": i64 }`.
+///
+/// # Errors
+/// - Returns HTTP 404 if the cohort or goat does not exist.
+pub async fn add_cohort_goat(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    member: web::Json<CohortMember>,
+) -> Result<impl Responder, AppError> {
+    let cohort_id = path.into_inner();
+    info!(
+        cohort_id,
+        goat_id = member.goat_id,
+        "POST /cohorts/{{id}}/add called"
+    );
+
+    db.write(move |tx| DbPool::add_goat_to_cohort(tx, cohort_id, member.goat_id))
+        .await?;
+    Ok(HttpResponse::Ok().json(json!({ "message": "Goat added to cohort" })))
+}
+
+/// Handler removing a goat from a cohort.
+///
+/// # HTTP Method
+/// - `DELETE /cohorts/{id}/remove/{goat_id}`
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat is not in the cohort.
+pub async fn remove_cohort_goat(
+    db: web::Data<DbPool>,
+    path: web::Path<(i64, i64)>,
+) -> Result<impl Responder, AppError> {
+    let (cohort_id, goat_id) = path.into_inner();
+    info!(
+        cohort_id,
+        goat_id, "DELETE /cohorts/{{id}}/remove/{{goat_id}} called"
+    );
+
+    db.write(move |tx| DbPool::remove_goat_from_cohort(tx, cohort_id, goat_id))
+        .await?;
+    Ok(HttpResponse::Ok().json(json!({ "message": "Goat removed from cohort" })))
+}
+
+/// Handler listing the goats in a cohort with full relations.
+///
+/// # HTTP Method
+/// - `GET /cohorts/{id}/goats`
+///
+/// # Errors
+/// - Returns HTTP 404 for an unknown cohort.
+pub async fn get_cohort_goats(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let cohort_id = path.into_inner();
+    debug!(cohort_id, "GET /cohorts/{{id}}/goats called");
+
+    let conn = db.get_conn()?;
+    let goats = DbPool::cohort_goats(&conn, cohort_id)?;
+
+    info!(cohort_id, count = goats.len(), "Returning cohort goats");
+    Ok(HttpResponse::Ok().json(goats))
+}
+
+/// Handler for cohort aggregates: average weight, health status distribution and
+/// vaccination coverage.
+///
+/// # HTTP Method
+/// - `GET /cohorts/{id}/stats`
+///
+/// # Errors
+/// - Returns HTTP 404 for an unknown cohort.
+pub async fn get_cohort_stats(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let cohort_id = path.into_inner();
+    debug!(cohort_id, "GET /cohorts/{{id}}/stats called");
+
+    let conn = db.get_conn()?;
+    let stats = DbPool::fetch_cohort_stats(&conn, cohort_id)?;
+    Ok(HttpResponse::Ok().json(stats))
+}
@@ -0,0 +1,195 @@
+//! Handlers for files attached to goats, such as purchase receipts and vet reports.
+
+use crate::config::max_document_bytes;
+use crate::db::{DbPool, ensure_goat_exists};
+use crate::errors::AppError;
+use actix_multipart::{Multipart, MultipartError};
+use actix_web::http::header::{self, ContentDisposition};
+use actix_web::{HttpResponse, Responder, web};
+use futures_util::TryStreamExt;
+use tracing::{debug, info, warn};
+
+/// Accepted document types and the bytes their content must start with.
+const DOCUMENT_SIGNATURES: &[(&str, &[u8])] = &[
+    ("application/pdf", b"%PDF-"),
+    ("image/jpeg", &[0xFF, 0xD8, 0xFF]),
+    ("image/png", b"\x89PNG\r\n\x1a\n"),
+];
+
+fn malformed(e: MultipartError) -> AppError {
+    AppError::InvalidInput(format!("Malformed multipart body: {}", e))
+}
+
+/// The last path component of a client-supplied file name, trimmed.
+fn base_filename(name: &str) -> String {
+    name.rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// Handler attaching a file to a goat.
+///
+/// # HTTP Method
+/// - `POST /goats/{id}/documents`
+///
+/// # Request
+/// - `multipart/form-data` whose first file part is stored. Its content type must be
+///   `application/pdf`, `image/jpeg` or `image/png`, and its content must be of that type.
+///
+/// # Success
+/// - Returns HTTP 201 with the document's `{ id, goat_id, filename, content_type, size,
+///   uploaded_at }`.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+/// - Returns HTTP 400 for a malformed body, no file part, or an empty file.
+/// - Returns HTTP 413 if the file exceeds `YAGI_MAX_DOCUMENT_BYTES` (10 MiB by default).
+/// - Returns HTTP 415 for any other content type, or content not matching it.
+///
+/// # Logs
+/// - Info: Receipt of the request and the stored document.
+/// - Warn: Rejected content.
+pub async fn upload_goat_document(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    mut payload: Multipart,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    info!(goat_id, "POST /goats/{{id}}/documents called");
+    ensure_goat_exists(&db.get_conn()?, goat_id)?;
+
+    let limit = max_document_bytes();
+    let mut upload = None;
+    while let Some(mut field) = payload.try_next().await.map_err(malformed)? {
+        let Some(filename) = field
+            .content_disposition()
+            .and_then(|cd| cd.get_filename())
+            .map(base_filename)
+        else {
+            continue;
+        };
+        let declared = field
+            .content_type()
+            .map(|mime| mime.essence_str().to_ascii_lowercase());
+        let mut content = Vec::new();
+        while let Some(chunk) = field.try_next().await.map_err(malformed)? {
+            if content.len() + chunk.len() > limit {
+                return Err(AppError::PayloadTooLarge(format!(
+                    "Document exceeds the {} byte limit",
+                    limit
+                )));
+            }
+            content.extend_from_slice(&chunk);
+        }
+        upload = Some((filename, declared, content));
+        break;
+    }
+    let Some((filename, declared, content)) = upload else {
+        return Err(AppError::InvalidInput(
+            "Request has no file part".to_string(),
+        ));
+    };
+    if filename.is_empty() || content.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Document must have a file name and content".to_string(),
+        ));
+    }
+
+    let declared = declared.unwrap_or_default();
+    let Some(&(content_type, signature)) = DOCUMENT_SIGNATURES
+        .iter()
+        .find(|(allowed, _)| *allowed == declared)
+    else {
+        warn!(goat_id, filename = %filename, declared = %declared, "Rejected document type");
+        return Err(AppError::UnsupportedMediaType(format!(
+            "Unsupported document type '{}'; expected one of {}",
+            declared,
+            DOCUMENT_SIGNATURES
+                .iter()
+                .map(|(allowed, _)| *allowed)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    };
+    if !content.starts_with(signature) {
+        warn!(goat_id, filename = %filename, content_type, "Document content does not match its type");
+        return Err(AppError::UnsupportedMediaType(format!(
+            "Content of '{}' is not {}",
+            filename, content_type
+        )));
+    }
+
+    let document = db
+        .write(move |tx| {
+            ensure_goat_exists(tx, goat_id)?;
+            DbPool::insert_goat_document(tx, goat_id, &filename, content_type, &content)
+        })
+        .await?;
+    info!(
+        goat_id,
+        document_id = document.id,
+        size = document.size,
+        "Document stored"
+    );
+    Ok(HttpResponse::Created().json(document))
+}
+
+/// Handler listing the files attached to a goat.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/documents`
+///
+/// # Success
+/// - Returns HTTP 200 with the documents' metadata, oldest first; see
+///   `upload_goat_document`.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn list_goat_documents(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, "GET /goats/{{id}}/documents called");
+
+    let conn = db.get_conn()?;
+    ensure_goat_exists(&conn, goat_id)?;
+    let documents = DbPool::goat_documents(&conn, goat_id)?;
+    debug!(goat_id, count = documents.len(), "Returning goat documents");
+    Ok(HttpResponse::Ok().json(documents))
+}
+
+/// Handler downloading an attached file.
+///
+/// # HTTP Method
+/// - `GET /documents/{doc_id}`
+///
+/// # Success
+/// - Returns HTTP 200 with the content under its stored `Content-Type`, as an attachment
+///   named after the uploaded file.
+///
+/// # Errors
+/// - Returns HTTP 404 if there is no such document.
+pub async fn get_document(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let document_id = path.into_inner();
+    debug!(document_id, "GET /documents/{{doc_id}} called");
+
+    let conn = db.get_conn()?;
+    let Some((document, content)) = DbPool::goat_document_content(&conn, document_id)? else {
+        return Err(AppError::NotFound(format!(
+            "No document with id {}",
+            document_id
+        )));
+    };
+    info!(document_id, size = document.size, "Returning document");
+    Ok(HttpResponse::Ok()
+        .content_type(document.content_type)
+        .insert_header(ContentDisposition::attachment(document.filename))
+        .insert_header((header::X_CONTENT_TYPE_OPTIONS, "nosniff"))
+        .body(content))
+}
@@ -0,0 +1,43 @@
+//! Handlers for farm equipment valuation.
+
+use crate::db::{DbPool, equipment_depreciation};
+use crate::errors::AppError;
+use actix_web::{HttpResponse, Responder, web};
+use chrono::Utc;
+use tracing::{debug, info};
+
+/// Handler for the straight-line depreciated value of a piece of equipment today.
+///
+/// # HTTP Method
+/// - `GET /equipment/{id}/depreciation`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ current_value, accumulated_depreciation,
+///   remaining_useful_life_years }`. Equipment past its useful life is valued at 0.
+///
+/// # Errors
+/// - Returns HTTP 404 if the equipment does not exist.
+/// - Returns HTTP 400 if its purchase date, purchase price or useful life is not recorded.
+pub async fn get_equipment_depreciation(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let equipment_id = path.into_inner();
+    debug!(equipment_id, "GET /equipment/{{id}}/depreciation called");
+
+    let conn = db.get_conn()?;
+    let item = DbPool::get_equipment(&conn, equipment_id)?;
+    let info = equipment_depreciation(&item, Utc::now().date_naive()).ok_or_else(|| {
+        AppError::InvalidInput(format!(
+            "Equipment {} needs a purchase date, purchase price and useful life to be valued",
+            equipment_id
+        ))
+    })?;
+
+    info!(
+        equipment_id,
+        current_value = info.current_value,
+        "Returning equipment depreciation"
+    );
+    Ok(HttpResponse::Ok().json(info))
+}
@@ -0,0 +1,104 @@
+//! Handlers for FAMACHA anemia scoring and the resulting deworming worklist.
+
+use crate::db::{DbPool, ensure_goat_exists};
+use crate::db_helpers::parse_timestamp;
+use crate::errors::AppError;
+use crate::models::{FAMACHA_ACTION_THRESHOLD, FamachaScore};
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use tracing::{debug, info};
+
+/// Handler recording a FAMACHA score for a goat.
+///
+/// # HTTP Method
+/// - `POST /goats/{id}/famacha`
+///
+/// # Request
+/// - JSON `{ "score": 1-5, "scored_at"?: timestamp, "scored_by"?: string }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored score. A score of 3 or more also opens a pending
+///   `deworming_records` entry, unless one is already pending; both writes share one
+///   transaction.
+///
+/// # Errors
+/// - Returns HTTP 422 if `score` is outside 1–5 or `scored_at` is malformed.
+/// - Returns HTTP 404 if the goat does not exist.
+///
+/// # Logs
+/// - Info: Receipt of the score and any deworming suggestion (logged by the DB layer).
+pub async fn add_famacha_score(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    score: web::Json<FamachaScore>,
+) -> Result<impl Responder, AppError> {
+    let mut score = score.into_inner();
+    score.goat_id = path.into_inner();
+    info!(
+        goat_id = score.goat_id,
+        score = score.score,
+        "POST /goats/{{id}}/famacha called"
+    );
+    score.validate()?;
+    score.scored_at = score
+        .scored_at
+        .as_deref()
+        .map(|ts| parse_timestamp("scored_at", ts))
+        .transpose()?;
+
+    let stored = db
+        .write(move |tx| {
+            let score_id = DbPool::insert_famacha_score(tx, &score)?;
+            if score.score >= FAMACHA_ACTION_THRESHOLD {
+                DbPool::suggest_deworming(
+                    tx,
+                    score.goat_id,
+                    &format!("FAMACHA score {}", score.score),
+                )?;
+            }
+            DbPool::get_famacha_score(tx, score_id)
+        })
+        .await?;
+
+    Ok(HttpResponse::Created().json(stored))
+}
+
+/// Handler listing a goat's FAMACHA scores, most recent first.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/famacha/history`
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn get_famacha_history(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, "GET /goats/{{id}}/famacha/history called");
+
+    let conn = db.get_conn()?;
+    ensure_goat_exists(&conn, goat_id)?;
+    let history = DbPool::famacha_history(&conn, goat_id)?;
+
+    info!(goat_id, count = history.len(), "Returning FAMACHA history");
+    Ok(HttpResponse::Ok().json(history))
+}
+
+/// Handler listing goats whose latest FAMACHA score means they need deworming.
+///
+/// # HTTP Method
+/// - `GET /goats/famacha/action-needed`
+///
+/// # Success
+/// - Returns HTTP 200 with goats (with ids and relations) plus `latest_score` and
+///   `scored_at`, worst scores first.
+pub async fn get_famacha_action_needed(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /goats/famacha/action-needed called");
+
+    let conn = db.get_conn()?;
+    let alerts = DbPool::famacha_action_needed(&conn)?;
+
+    info!(count = alerts.len(), "Returning goats needing deworming");
+    Ok(HttpResponse::Ok().json(alerts))
+}
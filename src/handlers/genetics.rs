@@ -0,0 +1,109 @@
+//! Handlers for goat genetic test results and trait summaries.
+
+use crate::db::{DbPool, ensure_goat_exists};
+use crate::errors::AppError;
+use crate::models::{GeneticSummaryQuery, GeneticTest};
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use tracing::{debug, info};
+
+/// Handler recording a genetic test result for a goat.
+///
+/// # HTTP Method
+/// - `POST /goats/{id}/genetic-tests`
+///
+/// # Request
+/// - JSON `{ "test_type": string, "test_date": "YYYY-MM-DD", "lab_name"?: string,
+///   "result_json": object, "report_url"?: string }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored test.
+///
+/// # Errors
+/// - Returns HTTP 422 for an unknown `test_type`, a malformed date, a non-object
+///   `result_json`, or a non-http(s) `report_url`.
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn add_genetic_test(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    test: web::Json<GeneticTest>,
+) -> Result<impl Responder, AppError> {
+    let mut test = test.into_inner();
+    test.goat_id = path.into_inner();
+    info!(
+        goat_id = test.goat_id,
+        test_type = %test.test_type,
+        "POST /goats/{{id}}/genetic-tests called"
+    );
+    test.validate()?;
+
+    let stored = db
+        .write(move |tx| DbPool::insert_genetic_test(tx, &test))
+        .await?;
+    Ok(HttpResponse::Created().json(stored))
+}
+
+/// Handler listing a goat's genetic tests.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/genetic-tests`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `GeneticTest`, most recent first.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn get_genetic_tests(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, "GET /goats/{{id}}/genetic-tests called");
+
+    let conn = db.get_conn()?;
+    ensure_goat_exists(&conn, goat_id)?;
+    let tests = DbPool::genetic_tests(&conn, goat_id)?;
+
+    info!(goat_id, count = tests.len(), "Returning genetic tests");
+    Ok(HttpResponse::Ok().json(tests))
+}
+
+/// Handler counting genetic tests by the value reported for one trait.
+///
+/// # HTTP Method
+/// - `GET /genetic-tests/summary?trait=<name>`
+///
+/// # Success
+/// - Returns HTTP 200 with `[{ "value": .., "count": n }]`, most common value first.
+///   Tests whose `result_json` lacks the trait are left out.
+///
+/// # Errors
+/// - Returns HTTP 400 if `trait` is empty or contains characters other than letters,
+///   digits, `_` and `-`.
+pub async fn get_genetic_trait_summary(
+    db: web::Data<DbPool>,
+    query: web::Query<GeneticSummaryQuery>,
+) -> Result<impl Responder, AppError> {
+    let trait_name = query.trait_name.trim();
+    debug!(trait_name, "GET /genetic-tests/summary called");
+    if trait_name.is_empty()
+        || !trait_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(AppError::InvalidInput(format!(
+            "trait must be a result key of letters, digits, '_' or '-', got '{}'",
+            trait_name
+        )));
+    }
+
+    let conn = db.get_conn()?;
+    let summary = DbPool::genetic_trait_summary(&conn, trait_name)?;
+
+    info!(
+        trait_name,
+        values = summary.len(),
+        "Returning genetic trait summary"
+    );
+    Ok(HttpResponse::Ok().json(summary))
+}
@@ -0,0 +1,127 @@
+//! Handlers for geofences, goat GPS fixes and the alerts raised when a goat crosses a fence.
+
+use crate::db::DbPool;
+use crate::db_helpers::parse_timestamp;
+use crate::errors::AppError;
+use crate::models::{Geofence, LocationPayload};
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use serde_json::json;
+use tracing::{debug, info};
+
+/// Handler adding a circular geofence.
+///
+/// # HTTP Method
+/// - `POST /geofences`
+///
+/// # Request
+/// - JSON `{ "name": string, "lat_center": f64, "lon_center": f64, "radius_m": f64,
+///   "alert_on": "Exit" | "Entry" | "Both" }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored geofence, including its new `id`.
+///
+/// # Errors
+/// - Returns HTTP 422 for an empty name, out-of-range coordinates, a non-positive radius
+///   or an unknown `alert_on`.
+pub async fn create_geofence(
+    db: web::Data<DbPool>,
+    fence: web::Json<Geofence>,
+) -> Result<impl Responder, AppError> {
+    info!(name = %fence.name, "POST /geofences called");
+    fence.validate()?;
+
+    let created = db
+        .write(move |tx| DbPool::create_geofence(tx, &fence))
+        .await?;
+    Ok(HttpResponse::Created().json(created))
+}
+
+/// Handler listing all geofences.
+///
+/// # HTTP Method
+/// - `GET /geofences`
+pub async fn list_geofences(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /geofences called");
+    let conn = db.get_conn()?;
+    let fences = DbPool::list_geofences(&conn)?;
+
+    info!(count = fences.len(), "Returning geofences");
+    Ok(HttpResponse::Ok().json(fences))
+}
+
+/// Handler deleting a geofence. Alerts it already raised are kept.
+///
+/// # HTTP Method
+/// - `DELETE /geofences/{id}`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ "message": "Geofence deleted" }`.
+///
+/// # Errors
+/// - Returns HTTP 404 if the geofence does not exist.
+pub async fn delete_geofence(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let geofence_id = path.into_inner();
+    info!(geofence_id, "DELETE /geofences/{{id}} called");
+
+    db.write(move |tx| DbPool::delete_geofence(tx, geofence_id))
+        .await?;
+    Ok(HttpResponse::Ok().json(json!({ "message": "Geofence deleted" })))
+}
+
+/// Handler recording a GPS fix for a goat.
+///
+/// # HTTP Method
+/// - `POST /goats/{id}/location`
+///
+/// # Request
+/// - JSON `{ "lat": f64, "lon": f64, "recorded_at"?: timestamp }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored fix and any location alerts it raised. An alert is
+///   raised when the goat crossed a geofence boundary since its previous fix and the
+///   fence alerts on that direction.
+///
+/// # Errors
+/// - Returns HTTP 422 for out-of-range coordinates or a malformed `recorded_at`.
+/// - Returns HTTP 404 if the goat does not exist.
+///
+/// # Logs
+/// - Logs a warning for every geofence crossed.
+pub async fn record_goat_location(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    location: web::Json<LocationPayload>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    let mut location = location.into_inner();
+    debug!(goat_id, "POST /goats/{{id}}/location called");
+    location.validate()?;
+    location.recorded_at = location
+        .recorded_at
+        .as_deref()
+        .map(|ts| parse_timestamp("recorded_at", ts))
+        .transpose()?;
+
+    let report = db
+        .write(move |tx| DbPool::record_goat_location(tx, goat_id, &location))
+        .await?;
+
+    Ok(HttpResponse::Created().json(report))
+}
+
+/// Handler listing geofence location alerts, newest first.
+///
+/// # HTTP Method
+/// - `GET /alerts/location`
+pub async fn get_location_alerts(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /alerts/location called");
+    let conn = db.get_conn()?;
+    let alerts = DbPool::location_alerts(&conn)?;
+
+    info!(count = alerts.len(), "Returning location alerts");
+    Ok(HttpResponse::Ok().json(alerts))
+}
@@ -7,14 +7,51 @@
 //! All operations return structured errors using the `AppError` type to communicate
 //! clear feedback to API clients while logging internal errors for troubleshooting.
 
-use crate::db::{DbPool, get_or_insert_disease, get_or_insert_vaccine, row_to_goat};
-use crate::db_helpers::{breed_to_str, gender_to_str};
-use crate::errors::AppError;
-use crate::models::NamePayload;
+use crate::cache::GoatListCache;
+use crate::config::{
+    attention_max_weight_kg, attention_min_weight_kg, attention_weight_stale_days,
+    sale_min_age_months, sale_min_weight_kg,
+};
+use crate::db::{
+    DbPool, delete_goat_by_name, ensure_goat_exists, ensure_goat_name_free, fetch_diseases,
+    fetch_vaccines, find_goats, find_random_goats, goat_columns, row_to_goat, timed_query_map,
+    timed_query_row, update_goat_by_name,
+};
+use crate::db_helpers::{breed_to_str, diet_to_str, normalize_name, parse_diet, parse_timestamp};
+use crate::domain::attention::{AttentionThresholds, rank_attention};
+use crate::domain::prediction::{SECS_PER_DAY, predict_weight};
+use crate::domain::sale::is_sale_ready;
+use crate::envelope::{MetaQuery, current_change_seq, list_response};
+use crate::errors::{AppError, FieldError};
+use crate::models::{
+    AttentionQuery, ChangesQuery, CompactGoat, CompactQuery, GoatDocument, GoatImportQuery,
+    GoatImportReport, GoatListQuery, GoatParams, GoatPatch, GoatPayload, GoatSearchParams,
+    GoatUpdatePayload, ImportRowError, MAX_RANDOM_GOATS, NamePayload, QrCodeQuery,
+    RandomGoatsQuery, SalePayload, SaleReadyGoat, TagPayload, UnitsQuery, WeightPredictionQuery,
+};
+use crate::money::money_fields_to_structured;
+use crate::services::GoatService;
+use crate::units::{UnitSystem, WeightUnit, round2};
+use crate::validation::Validate;
+use actix_web::http::header::{self, CacheControl, CacheDirective, ContentType};
 use actix_web::{HttpResponse, Responder, web};
-use rusqlite::params;
-use shared::{Breed, Gender, GoatParams};
-use tracing::{debug, info, trace, warn};
+use chrono::Utc;
+use image::{ImageFormat, Luma, imageops};
+use qrcode::QrCode;
+use rusqlite::{Connection, params};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::io::Cursor;
+use tracing::{debug, info, warn};
+
+/// Environment variable holding the URL encoded into goat QR codes; `{id}` is substituted.
+pub const QR_URL_TEMPLATE_ENV: &str = "YAGI_QR_URL_TEMPLATE";
+/// URL template used when `YAGI_QR_URL_TEMPLATE` is unset.
+pub const DEFAULT_QR_URL_TEMPLATE: &str = "https://farm.example/goats/{id}";
+/// Edge length in pixels when `size` is omitted.
+const DEFAULT_QR_SIZE: u32 = 256;
+/// Smallest and largest accepted QR image edge, in pixels.
+const QR_SIZE_RANGE: (u32, u32) = (64, 1024);
 
 /// Handler for retrieving the full list of goats with complete details.
 ///
@@ -23,33 +60,223 @@ use tracing::{debug, info, trace, warn};
 ///
 /// # Success
 /// - Returns HTTP 200 with JSON array containing all goats including their vaccines and diseases.
+/// - With `?meta=true`, the array is wrapped as `{ "data": [...], "meta": {...} }`.
+/// - With `?diet=pasture`, only goats on that diet are returned.
+/// - With `?tag=for-sale`, only goats carrying that tag are returned.
+/// - Each goat carries a `tags` array of its tag names.
+/// - With `?units=imperial`, weights are reported in pounds (rounded to two decimals).
+/// - `cost` and `current_price` are `{ "amount": paise, "currency": "INR" }` objects.
+/// - With `?compact=true`, each goat carries only `id`, `name`, `breed`, `gender`,
+///   `health_status` and `weight` (see `CompactGoat`).
+/// - Without any of these parameters, the list is served from `GoatListCache` when it is
+///   registered and still current.
 ///
 /// # Errors
+/// - Returns HTTP 400 for an unknown `diet`.
 /// - Returns appropriate error responses if database access or mapping fails.
 ///
 /// # Logs
 /// - Info: Entry point of request.
 /// - Trace: Loading each goat by ID.
 /// - Error: On any failure loading individual goats.
-pub async fn get_goats(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
-    debug!("GET /goats called");
+#[utoipa::path(
+    get,
+    path = "/goats",
+    tag = "goats",
+    params(GoatListQuery, MetaQuery, UnitsQuery, CompactQuery),
+    responses(
+        (status = 200, description = "All live goats", body = [crate::models::Goat]),
+        (status = 400, description = "Unknown diet", body = crate::errors::ErrorBody),
+        (status = 500, description = "Database failure", body = crate::errors::ErrorBody)
+    )
+)]
+pub async fn get_goats(
+    db: web::Data<DbPool>,
+    cache: Option<web::Data<GoatListCache>>,
+    query: web::Query<GoatListQuery>,
+    meta: web::Query<MetaQuery>,
+    units: web::Query<UnitsQuery>,
+    compact: web::Query<CompactQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(
+        diet = ?query.diet,
+        tag = ?query.tag,
+        units = ?units.units,
+        compact = compact.compact,
+        "GET /goats called"
+    );
+    let weight_unit = UnitSystem::from_param(units.units.as_deref()).weight_unit();
+    let diet = query.diet.as_deref().map(parse_diet).transpose()?;
+    let tag = query.tag.as_deref().map(str::trim);
     let conn = db.get_conn()?;
     debug!("Acquired connection in get_goats");
-    let mut stmt = conn
-        .prepare("SELECT * FROM goats")
-        .map_err(AppError::DbError)?;
-    let goats: Result<Vec<GoatParams>, rusqlite::Error> = stmt
-        .query_map([], |row| {
-            row_to_goat(row).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
-        })?
-        .collect();
 
-    let goats = goats?; // propagate or handle your error here
+    let unfiltered = diet.is_none()
+        && tag.is_none()
+        && !meta.meta
+        && !compact.compact
+        && weight_unit == WeightUnit::Kg;
+    if let Some(cache) = cache.filter(|_| unfiltered) {
+        // Read before the goats, so a write landing in between is never cached as current.
+        let generation = cache.generation();
+        let change_seq = current_change_seq(&conn)?;
+        if let Some(cached) = cache.get(change_seq) {
+            debug!(count = cached.count, change_seq, "Serving goats from cache");
+            return Ok(HttpResponse::Ok()
+                .content_type(ContentType::json())
+                .body(cached.body.clone()));
+        }
+        let goats = load_goat_list(&conn, weight_unit, None, None)?;
+        let body = serde_json::to_vec(&goats)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize goats: {}", e)))?;
+        let stored = cache.store(generation, change_seq, body.clone().into(), goats.len());
+        info!(stored, "Returning {} goats", goats.len());
+        return Ok(HttpResponse::Ok()
+            .content_type(ContentType::json())
+            .body(body));
+    }
 
+    let diet = diet.as_ref().map(diet_to_str);
+    if compact.compact {
+        let goats: Vec<CompactGoat> = query_live_goats(&conn, diet, tag)?
+            .into_iter()
+            .map(|(goat_id, goat)| {
+                let mut compact = CompactGoat::from(goat);
+                compact.id = Some(goat_id);
+                compact.weight = weight_unit.from_kg(compact.weight);
+                compact
+            })
+            .collect();
+        info!("Returning {} compact goats", goats.len());
+        return list_response(&conn, goats, meta.into_inner());
+    }
+    let goats = load_goat_list(&conn, weight_unit, diet, tag)?;
     info!("Returning {} goats", goats.len());
-    Ok(HttpResponse::Ok()
-        .content_type("application/json")
-        .json(goats))
+    list_response(&conn, goats, meta.into_inner())
+}
+
+/// Handler for `GET /goats/compact`, an alias of `GET /goats?compact=true` accepting the
+/// same other parameters.
+pub async fn get_compact_goats(
+    db: web::Data<DbPool>,
+    query: web::Query<GoatListQuery>,
+    meta: web::Query<MetaQuery>,
+    units: web::Query<UnitsQuery>,
+) -> Result<impl Responder, AppError> {
+    let compact = web::Query(CompactQuery { compact: true });
+    get_goats(db, None, query, meta, units, compact).await
+}
+
+/// Live goats as served by `GET /goats`, optionally only those on `diet` or carrying
+/// `tag`, with weights in `weight_unit`.
+fn load_goat_list(
+    conn: &Connection,
+    weight_unit: WeightUnit,
+    diet: Option<&str>,
+    tag: Option<&str>,
+) -> Result<Vec<serde_json::Value>, AppError> {
+    let mut goats = query_live_goats(conn, diet, tag)?;
+    let mut tags = DbPool::tags_by_goat(conn)?;
+
+    for (_, goat) in &mut goats {
+        goat.weight = weight_unit.from_kg(goat.weight);
+    }
+    goats
+        .iter()
+        .map(|(goat_id, goat)| {
+            let mut value = serde_json::to_value(goat)?;
+            if let Some(map) = value.as_object_mut() {
+                money_fields_to_structured(map);
+                map.insert(
+                    "tags".to_string(),
+                    serde_json::json!(tags.remove(goat_id).unwrap_or_default()),
+                );
+            }
+            Ok(value)
+        })
+        .collect::<Result<Vec<_>, serde_json::Error>>()
+        .map_err(|e| AppError::Internal(format!("Failed to serialize goats: {}", e)))
+}
+
+/// Ids and parameters of live goats, optionally only those on `diet` or carrying `tag`.
+fn query_live_goats(
+    conn: &Connection,
+    diet: Option<&str>,
+    tag: Option<&str>,
+) -> Result<Vec<(i64, GoatParams)>, AppError> {
+    timed_query_map(
+        conn,
+        &format!(
+            "SELECT {} FROM goats WHERE deleted_at IS NULL AND (?1 IS NULL OR diet = ?1) \
+             AND (?2 IS NULL OR EXISTS (SELECT 1 FROM goat_tags gt JOIN tags t \
+                                        ON t.id = gt.tag_id \
+                                        WHERE gt.goat_id = goats.id AND t.name = ?2))",
+            goat_columns("goats")
+        ),
+        params![diet, tag],
+        |row| {
+            let params = row_to_goat(row)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+            Ok((row.get("id")?, params))
+        },
+    )
+}
+
+/// Handler searching goats by any combination of filters.
+///
+/// # HTTP Method
+/// - `GET /goats/search?q=&breed=&gender=&health_status=&min_weight=&max_weight=&space_id=&has_vaccine=&has_disease=&is_pregnant=`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of goats (with ids and relations) matching every
+///   supplied filter, ordered by name. With no filters, all live goats are returned.
+///
+/// # Errors
+/// - Returns HTTP 400 if a numeric or boolean parameter cannot be parsed.
+///
+/// # Logs
+/// - Debug: Entry point of request with the filters applied.
+/// - Info: Number of matches.
+pub async fn search_goats(
+    db: web::Data<DbPool>,
+    query: web::Query<GoatSearchParams>,
+) -> Result<impl Responder, AppError> {
+    debug!(filters = ?query, "GET /goats/search called");
+    let conn = db.get_conn()?;
+    let goats = find_goats(&conn, &query)?;
+
+    info!(count = goats.len(), "Returning goat search results");
+    Ok(HttpResponse::Ok().json(goats))
+}
+
+/// Handler sampling random goats, e.g. for veterinary spot-checks.
+///
+/// # HTTP Method
+/// - `GET /goats/random?count=3&space_id=1`, with any filter of `GET /goats/search`.
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of up to `count` distinct goats (with ids and
+///   relations) drawn at random from those matching every filter. `count` defaults to
+///   1 and is capped at 50; fewer goats are returned when fewer match.
+///
+/// # Errors
+/// - Returns HTTP 400 if a numeric or boolean parameter cannot be parsed.
+///
+/// # Logs
+/// - Debug: Entry point of request with the filters applied.
+/// - Info: Number of goats sampled.
+pub async fn get_random_goats(
+    db: web::Data<DbPool>,
+    filters: web::Query<GoatSearchParams>,
+    sample: web::Query<RandomGoatsQuery>,
+) -> Result<impl Responder, AppError> {
+    let count = sample.count.clamp(1, MAX_RANDOM_GOATS);
+    debug!(count, filters = ?filters, "GET /goats/random called");
+    let conn = db.get_conn()?;
+    let goats = find_random_goats(&conn, &filters, count)?;
+
+    info!(count = goats.len(), "Returning random goats");
+    Ok(HttpResponse::Ok().json(goats))
 }
 
 /// Handler for adding a new goat along with vaccinations and diseases.
@@ -58,70 +285,96 @@ pub async fn get_goats(db: web::Data<DbPool>) -> Result<impl Responder, AppError
 /// - `POST /goats`
 ///
 /// # Request
-/// - JSON payload conforming to `Goat` struct.
+/// - JSON payload conforming to `Goat` struct, plus an optional `weight_unit` of `kg`
+///   (default) or `lb`. Weights are stored in kilograms.
+/// - `cost` and `current_price` may be left out; each is then estimated as the average
+///   over live goats of the same breed, and the response carries `cost_estimated: true`.
 ///
 /// # Success
-/// - Returns HTTP 201 on successful insertion.
+/// - Returns HTTP 201 with the stored goat (including its `id`, canonical name and
+///   relations) and a `Location: /goats/{id}` header. The insert goes through the
+///   pool's writer, which reads the goat back in the same transaction;
+///   `?units=imperial` reports its weight in pounds.
 ///
 /// # Errors
-/// - Returns error responses if input validation or database operations fail.
+/// - Returns HTTP 422 with an `ErrorBody` whose `fields` list every invalid field as
+///   `{ field, message }`, including any left-out money field with no goats of the breed
+///   to estimate it from or in an unsupported currency, before touching the database.
+/// - Returns HTTP 409 if a goat with the same name, ignoring case, already exists.
+/// - Returns error responses if database operations fail.
 ///
 /// # Logs
 /// - Info: Receipt of add request.
 /// - Debug: After inserting base goat entry.
 /// - Trace: Adding each vaccine and disease link.
 /// - Info: Upon successful commit.
+#[utoipa::path(
+    post,
+    path = "/goats",
+    tag = "goats",
+    params(UnitsQuery),
+    request_body = GoatPayload,
+    responses(
+        (status = 201, description = "Goat created", body = crate::models::Goat,
+            headers(("Location" = String, description = "`/goats/{id}` of the new goat"))),
+        (status = 409, description = "Name already taken", body = crate::errors::ErrorBody),
+        (status = 422, description = "Every invalid field", body = crate::errors::ErrorBody),
+        (status = 500, description = "Database failure", body = crate::errors::ErrorBody)
+    )
+)]
 pub async fn add_goat(
     db: web::Data<DbPool>,
-    new_goat: web::Json<GoatParams>,
+    cache: Option<web::Data<GoatListCache>>,
+    new_goat: web::Json<GoatPayload>,
+    units: web::Query<UnitsQuery>,
 ) -> Result<impl Responder, AppError> {
-    debug!(name = %new_goat.name, "POST /goats called");
-    let mut conn = db.get_conn()?;
-    info!("Connection recieved in add_goat instance");
-
-    let tx = conn.transaction()?;
-
-    tx.execute(
-        "INSERT INTO goats (breed, name, gender, offspring, cost, weight, current_price, diet, last_bred, health_status) \
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-        params![
-            Breed::to_str(&new_goat.breed),
-            &new_goat.name,
-            Gender::to_str(&new_goat.gender),
-            &new_goat.offspring,
-            &new_goat.cost,
-            &new_goat.weight,
-            &new_goat.current_price,
-            &new_goat.diet,
-            &new_goat.last_bred,
-            &new_goat.health_status,
-        ]
-    )?;
-
-    let goat_id = tx.last_insert_rowid();
-    debug!(goat_id, "Inserted goat base record");
-
-    for vaccine in &new_goat.vaccinations {
-        let vaccine_id = get_or_insert_vaccine(&tx, vaccine)?;
-        tx.execute(
-            "INSERT INTO goat_vaccines (goat_id, vaccine_id) VALUES (?, ?)",
-            &[&goat_id, &vaccine_id],
-        )?;
-        info!(goat_id, vaccine_id, "Linked vaccine");
-    }
+    let mut payload = new_goat.into_inner();
+    debug!(name = %payload.goat.name, "POST /goats called");
+    let estimated = !payload.omitted.is_empty() && {
+        let breed = breed_to_str(&payload.goat.breed);
+        let hint = DbPool::breed_price_hint(&db.get_conn()?, breed)?;
+        debug!(breed, ?hint, omitted = ?payload.omitted, "Estimating omitted money fields");
+        payload.estimate_omitted(&hint)
+    };
+    payload.validate()?;
+
+    let mut created = db.writer().insert_goat(payload.into_metric()).await?;
+    invalidate_goat_list(cache.as_ref());
+    let goat_id = created
+        .id()
+        .ok_or_else(|| AppError::Internal("Inserted goat has no id".to_string()))?;
+    created.params.weight = UnitSystem::from_param(units.units.as_deref())
+        .weight_unit()
+        .from_kg(created.params.weight);
 
-    for disease in &new_goat.diseases {
-        let disease_id = get_or_insert_disease(&tx, disease)?;
-        tx.execute(
-            "INSERT INTO goat_diseases (goat_id, disease_id) VALUES (?, ?)",
-            &[&goat_id, &disease_id],
-        )?;
-        trace!(goat_id, disease_id, "Linked disease");
+    let mut body = serde_json::to_value(&created)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize goat: {}", e)))?;
+    if estimated && let Some(map) = body.as_object_mut() {
+        map.insert("cost_estimated".to_string(), serde_json::json!(true));
     }
 
-    tx.commit()?;
-    info!(goat_id, "Successfully added new goat with associations");
-    Ok(HttpResponse::Created().body("Goat added"))
+    info!(
+        goat_id,
+        estimated, "Successfully added new goat with associations"
+    );
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("/goats/{}", goat_id)))
+        .json(body))
+}
+
+/// Validates a goat in metric units and stores it with its relations, returning the new
+/// id. This is the insert path of `POST /goats` without the HTTP layer, for synchronous
+/// callers: it writes on a pooled connection instead of through `DbPool::writer`.
+///
+/// # Errors
+/// Returns `AppError::Validation` listing every invalid field (empty name, negative or
+/// non-finite amounts, unknown diet, bad `last_bred`), `AppError::Conflict` if the name
+/// is taken ignoring case, or a database error. Nothing is written on any error.
+pub fn validate_and_insert_goat(pool: &DbPool, params: GoatParams) -> Result<i64, AppError> {
+    params.validate()?;
+    let goat_id = pool.with_write_retry(|tx| DbPool::insert_goat(tx, &params))?;
+    debug!(goat_id, "Goat inserted");
+    Ok(goat_id)
 }
 
 /// Handler for updating an existing goat and its relations by ID.
@@ -130,13 +383,19 @@ pub async fn add_goat(
 /// - `PUT /goats`
 ///
 /// # Request
-/// - JSON payload conforming to `Goat` struct, with `id` field.
+/// - JSON payload conforming to `Goat` struct, with `id` field, plus an optional
+///   `weight_unit` of `kg` (default) or `lb` and the required `version` as last read.
 ///
 /// # Success
-/// - Returns HTTP 200 on successful update.
+/// - Returns HTTP 200 with `{ "name": .. }` holding the canonical stored name. The goat
+///   is matched by name ignoring case and extra whitespace.
 ///
 /// # Errors
-/// - Returns HTTP 400 for missing `id` or if goat does not exist.
+/// - Returns HTTP 422 with an `ErrorBody` whose `fields` list every invalid field, before
+///   touching the database.
+/// - Returns HTTP 400 for missing `id` or `version`, or if goat does not exist.
+/// - Returns HTTP 409 with `{ "error": "VersionConflict", "message": .. }` if the goat
+///   has been updated since `version`.
 /// - Returns other errors on database failure.
 ///
 /// # Logs
@@ -144,86 +403,81 @@ pub async fn add_goat(
 /// - Debug: After base update, and clearing old relations.
 /// - Trace: Adding vaccine and disease links.
 /// - Warn/Error: For missing record or update failures.
+#[utoipa::path(
+    put,
+    path = "/goats",
+    tag = "goats",
+    request_body = GoatPayload,
+    responses(
+        (status = 200, description = "Canonical stored name", body = NamePayload),
+        (status = 400, description = "No goat with that name", body = crate::errors::ErrorBody),
+        (status = 409, description = "Goat changed since `version` was read", body = crate::errors::ErrorBody),
+        (status = 422, description = "Every invalid field", body = crate::errors::ErrorBody),
+        (status = 500, description = "Database failure", body = crate::errors::ErrorBody)
+    )
+)]
 pub async fn update_goat(
     db: web::Data<DbPool>,
-    goat: web::Json<GoatParams>,
+    cache: Option<web::Data<GoatListCache>>,
+    goat: web::Json<GoatUpdatePayload>,
 ) -> Result<impl Responder, AppError> {
-    let name = &goat.name;
+    let GoatUpdatePayload { version, payload } = goat.into_inner();
+    info!(goat_name = %payload.goat.name, version, "PUT /goats called");
+    payload.validate()?;
+    let goat = payload.into_metric();
 
-    info!(goat_name = name, "PUT /goats called");
+    debug!("Params loaded in update_goat");
+    let stored_name: String = db
+        .write(move |tx| {
+            let goat_id = update_goat_by_name(tx, &goat, Some(version))?;
+            Ok(timed_query_row(
+                tx,
+                "SELECT name FROM goats WHERE id = ?1",
+                [goat_id],
+                |r| r.get(0),
+            )?)
+        })
+        .await?;
+    invalidate_goat_list(cache.as_ref());
 
-    let mut conn = db.get_conn()?;
-    let tx = conn.transaction()?;
+    info!(
+        goat_name = stored_name,
+        "Updated goat and associations successfully"
+    );
+    Ok(HttpResponse::Ok().json(NamePayload { name: stored_name }))
+}
 
-    debug!("Params loaded in update_goat");
+/// Handler for the delta of goat changes since a given time, for offline clients.
+///
+/// # HTTP Method
+/// - `GET /goats/changes?since=<timestamp>`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ server_time, changed, deleted }`, where `changed` holds full
+///   goats (with ids and relations) and `deleted` holds tombstones.
+///
+/// # Errors
+/// - Returns HTTP 400 if `since` is not a recognised timestamp.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Size of the returned delta.
+pub async fn get_goat_changes(
+    db: web::Data<DbPool>,
+    query: web::Query<ChangesQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(since = %query.since, "GET /goats/changes called");
+    let since = parse_timestamp("since", &query.since)?;
 
-    let affected = tx.execute(
-        "UPDATE goats 
-         SET breed = ?, gender = ?, offspring = ?, cost = ?, weight = ?, current_price = ?, diet = ?, last_bred = ?, health_status = ? 
-         WHERE name = ?",
-        params![
-            Breed::to_str(&goat.breed),
-            Gender::to_str(&goat.gender),
-            &goat.offspring,
-            &goat.cost,
-            &goat.weight,
-            &goat.current_price,
-            &goat.diet,
-            &goat.last_bred,
-            &goat.health_status,
-            &goat.name,
-        ],
-    )?;
-
-    if affected == 0 {
-        warn!(goat_name = name, "No goat found for update");
-        return Err(AppError::InvalidInput(format!(
-            "No goat found with name {}",
-            name
-        )));
-    } else {
-        // Delete existing links for the goat
-        tx.execute(
-            "DELETE FROM goat_vaccines WHERE goat_id IN (SELECT id FROM goats WHERE name = ?1 LIMIT 1)",
-            [&name],
-        )?;
-        tx.execute(
-            "DELETE FROM goat_diseases WHERE goat_id IN (SELECT id FROM goats WHERE name = ?1 LIMIT 1)",
-            [&name],
-        )?;
-        debug!(goat_name = name, "Cleared old vaccine and disease links");
-
-        // Fetch goat id
-        let goat_id: i64 = tx.query_row(
-            "SELECT id FROM goats WHERE name = ?1 LIMIT 1",
-            [&name],
-            |row| row.get(0),
-        )?;
-
-        // Insert updated vaccine links
-        for vaccine in &goat.vaccinations {
-            let vaccine_id = get_or_insert_vaccine(&tx, vaccine)?;
-            tx.execute(
-                "INSERT OR IGNORE INTO goat_vaccines (goat_id, vaccine_id) VALUES (?, ?)",
-                &[&goat_id, &vaccine_id],
-            )?;
-        }
-        // Insert updated disease links
-        for disease in &goat.diseases {
-            let disease_id = get_or_insert_disease(&tx, disease)?;
-            tx.execute(
-                "INSERT OR IGNORE INTO goat_diseases (goat_id, disease_id) VALUES (?, ?)",
-                &[&goat_id, &disease_id],
-            )?;
-        }
-    }
+    let conn = db.get_conn()?;
+    let changes = DbPool::goat_changes(&conn, &since)?;
 
-    tx.commit()?;
     info!(
-        goat_name = name,
-        "Updated goat and associations successfully"
+        changed = changes.changed.len(),
+        deleted = changes.deleted.len(),
+        "Returning goat changes"
     );
-    Ok(HttpResponse::Ok().body("Goat updated"))
+    Ok(HttpResponse::Ok().json(changes))
 }
 
 /// Handler for deleting a goat by ID.
@@ -235,7 +489,9 @@ pub async fn update_goat(
 /// - JSON payload containing the goat's `id`.
 ///
 /// # Success
-/// - Returns HTTP 200 when deletion is successful.
+/// - Returns HTTP 200 with `{ "name": .. }` holding the canonical name of the deleted goat.
+///   The name is matched ignoring case and extra whitespace. The goat is soft-deleted and
+///   reported as a tombstone by `GET /goats/changes`.
 ///
 /// # Errors
 /// - Returns HTTP 400 if no goat matches the provided ID.
@@ -244,23 +500,1009 @@ pub async fn update_goat(
 /// - Info: Receipt of delete request.
 /// - Warn: If goat not found.
 /// - Info: Successful deletion.
+#[utoipa::path(
+    delete,
+    path = "/goats",
+    tag = "goats",
+    request_body = NamePayload,
+    responses(
+        (status = 200, description = "Canonical name of the deleted goat", body = NamePayload),
+        (status = 400, description = "No goat with that name", body = crate::errors::ErrorBody),
+        (status = 500, description = "Database failure", body = crate::errors::ErrorBody)
+    )
+)]
 pub async fn delete_goat(
     db: web::Data<DbPool>,
+    cache: Option<web::Data<GoatListCache>>,
     name: web::Json<NamePayload>,
 ) -> Result<impl Responder, AppError> {
     info!(goat_id = name.name, "DELETE /goats called");
 
+    let stored_name = db
+        .write(move |tx| delete_goat_by_name(tx, &name.name))
+        .await?;
+    invalidate_goat_list(cache.as_ref());
+
+    info!(goat_name = stored_name, "Goat deleted successfully");
+    Ok(HttpResponse::Ok().json(NamePayload { name: stored_name }))
+}
+
+/// Handler fetching a single goat by id.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}?units=imperial`
+///
+/// # Success
+/// - Returns HTTP 200 with the goat, including its `id` and relations.
+/// - With `?compact=true`, returns only the `CompactGoat` fields.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist or has been deleted.
+pub async fn get_goat(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    units: web::Query<UnitsQuery>,
+    compact: web::Query<CompactQuery>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(
+        goat_id,
+        compact = compact.compact,
+        "GET /goats/{{id}} called"
+    );
+
+    let mut goat = GoatService::get(&db, goat_id)?;
+    goat.params.weight = UnitSystem::from_param(units.units.as_deref())
+        .weight_unit()
+        .from_kg(goat.params.weight);
+
+    if compact.compact {
+        return Ok(HttpResponse::Ok().json(CompactGoat::from(goat)));
+    }
+    Ok(HttpResponse::Ok().json(goat))
+}
+
+/// Handler overwriting a goat addressed by id, including its name and relations.
+///
+/// # HTTP Method
+/// - `PUT /goats/{id}`
+///
+/// # Request
+/// - JSON payload as for `POST /goats`, plus the required `version` as last read.
+///
+/// # Success
+/// - Returns HTTP 200 with the goat as stored, weight in kilograms, at its new version.
+///
+/// # Errors
+/// - Returns HTTP 400 if `version` is missing.
+/// - Returns HTTP 422 with every invalid field if the payload fails validation.
+/// - Returns HTTP 404 if the goat does not exist.
+/// - Returns HTTP 409 if the new name belongs to another goat, ignoring case, or with
+///   `{ "error": "VersionConflict", "message": .. }` if the goat has been updated since
+///   `version`.
+pub async fn update_goat_by_id(
+    db: web::Data<DbPool>,
+    cache: Option<web::Data<GoatListCache>>,
+    path: web::Path<i64>,
+    goat: web::Json<GoatUpdatePayload>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    let GoatUpdatePayload { version, payload } = goat.into_inner();
+    info!(goat_id, version, "PUT /goats/{{id}} called");
+    payload.validate()?;
+
+    let updated = db
+        .writer()
+        .update_goat(goat_id, payload.into_metric(), Some(version))
+        .await?;
+    invalidate_goat_list(cache.as_ref());
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Handler changing some fields of a goat addressed by id, leaving the rest as stored.
+///
+/// # HTTP Method
+/// - `PATCH /goats/{id}`
+///
+/// # Request
+/// - JSON object with any of the fields of `POST /goats`, plus the required `version` as
+///   last read. A `weight_unit` applies to a `weight` sent alongside it.
+///
+/// # Success
+/// - Returns HTTP 200 with the goat as stored, weight in kilograms, at its new version.
+///
+/// # Errors
+/// - Returns HTTP 400 if `version` is missing or a field has the wrong type.
+/// - Returns HTTP 422 with an `ErrorBody` whose `fields` list every invalid field of the
+///   patched goat.
+/// - Returns HTTP 404 if the goat does not exist.
+/// - Returns HTTP 409 if the new name belongs to another goat, ignoring case, or with
+///   `{ "error": "VersionConflict", "message": .. }` if the goat has been updated since
+///   `version`.
+pub async fn patch_goat(
+    db: web::Data<DbPool>,
+    cache: Option<web::Data<GoatListCache>>,
+    path: web::Path<i64>,
+    patch: web::Json<GoatPatch>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    let patch = patch.into_inner();
+    let version = patch.version;
+    info!(
+        goat_id,
+        version,
+        fields = patch.changes.len(),
+        "PATCH /goats/{{id}} called"
+    );
+
+    let stored = GoatService::get(&db, goat_id)?;
+    let payload = patch
+        .apply_to(&stored)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid goat patch: {}", e)))?;
+    payload.validate()?;
+
+    let updated = db
+        .writer()
+        .update_goat(goat_id, payload.into_metric(), Some(version))
+        .await?;
+    invalidate_goat_list(cache.as_ref());
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Handler soft-deleting a goat addressed by id.
+///
+/// # HTTP Method
+/// - `DELETE /goats/{id}`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ "name": .. }` holding the name of the deleted goat, which is
+///   then reported as a tombstone by `GET /goats/changes`.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn delete_goat_by_id(
+    db: web::Data<DbPool>,
+    cache: Option<web::Data<GoatListCache>>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    info!(goat_id, "DELETE /goats/{{id}} called");
+
+    let deleted = db.writer().delete_goat(goat_id).await?;
+    invalidate_goat_list(cache.as_ref());
+    Ok(HttpResponse::Ok().json(NamePayload {
+        name: deleted.params.name,
+    }))
+}
+
+/// Handler rendering a PNG QR code that links to a goat's record, for printed pen cards.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/qrcode?size=256`
+///
+/// # Success
+/// - Returns HTTP 200 with an `image/png` body of `size`x`size` pixels (clamped to
+///   64–1024) encoding the URL from `YAGI_QR_URL_TEMPLATE`. The image is cacheable.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+/// - Returns HTTP 500 if the configured URL does not fit in a QR code or the PNG cannot
+///   be written.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Rendered image size.
+pub async fn get_goat_qrcode(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    query: web::Query<QrCodeQuery>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, size = ?query.size, "GET /goats/{{id}}/qrcode called");
+
+    let conn = db.get_conn()?;
+    ensure_goat_exists(&conn, goat_id)?;
+
+    let size = query
+        .size
+        .unwrap_or(DEFAULT_QR_SIZE)
+        .clamp(QR_SIZE_RANGE.0, QR_SIZE_RANGE.1);
+    let template =
+        std::env::var(QR_URL_TEMPLATE_ENV).unwrap_or_else(|_| DEFAULT_QR_URL_TEMPLATE.to_string());
+    let url = template.replace("{id}", &goat_id.to_string());
+
+    let code = QrCode::new(url.as_bytes())
+        .map_err(|e| AppError::Internal(format!("Cannot encode QR payload: {}", e)))?;
+    let rendered = code.render::<Luma<u8>>().min_dimensions(size, size).build();
+    // The renderer rounds up to whole modules; scale to the exact requested edge.
+    let image = imageops::resize(&rendered, size, size, imageops::FilterType::Nearest);
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| AppError::Internal(format!("Failed to encode QR code PNG: {}", e)))?;
+
+    info!(goat_id, size, "Returning goat QR code");
+    Ok(HttpResponse::Ok()
+        .content_type("image/png")
+        .insert_header(CacheControl(vec![
+            CacheDirective::Public,
+            CacheDirective::MaxAge(86400),
+        ]))
+        .body(png))
+}
+
+/// Handler for what a single goat has cost and earned so far.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/profit-analysis`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ acquisition_cost, current_value, feed_cost_to_date,
+///   vet_cost_to_date, milk_revenue_to_date, net_position }` in rupees, plus the goat's
+///   `goat_id` and `name`. Totals with no records behind them are 0.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn get_goat_profit_analysis(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, "GET /goats/{{id}}/profit-analysis called");
+
+    let conn = db.get_conn()?;
+    let analysis = DbPool::compute_profit_analysis(&conn, goat_id)?;
+
+    info!(
+        goat_id,
+        net_position = analysis.net_position,
+        "Returning goat profit analysis"
+    );
+    Ok(HttpResponse::Ok().json(analysis))
+}
+
+/// Handler for everything recorded about a goat, oldest first.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/timeline`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `{ timestamp, event_type, description }`
+///   covering creation, weighings, vaccinations, diseases, treatments, vet visits,
+///   breeding, space moves, sales, FAMACHA scores, shows, behavior and genetic tests.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn get_goat_timeline(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, "GET /goats/{{id}}/timeline called");
+
+    let conn = db.get_conn()?;
+    let timeline = DbPool::build_goat_timeline(&conn, goat_id)?;
+
+    info!(goat_id, count = timeline.len(), "Returning goat timeline");
+    Ok(HttpResponse::Ok().json(timeline))
+}
+
+/// Handler forecasting a goat's weight from the trend of its weighings.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/weight-prediction?days=30`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ current_weight, predicted_weight, confidence }` in
+///   kilograms. `predicted_weight` extends a least-squares line through the goat's
+///   weight history to `days` days from now and is never negative. `confidence` is
+///   `low` below 3 weighings, `medium` up to 9 and `high` from 10.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+/// - Returns HTTP 422 if the goat has never been weighed.
+pub async fn get_weight_prediction(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    query: web::Query<WeightPredictionQuery>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(
+        goat_id,
+        days = query.days,
+        "GET /goats/{{id}}/weight-prediction called"
+    );
+
+    let conn = db.get_conn()?;
+    let history = DbPool::weight_history_points(&conn, goat_id)?;
+    let at = Utc::now().timestamp() + i64::from(query.days) * SECS_PER_DAY;
+    let mut prediction = predict_weight(&history, at).ok_or_else(|| {
+        AppError::Validation(vec![FieldError::new(
+            "weight_history",
+            "no weighings recorded for this goat",
+        )])
+    })?;
+    prediction.predicted_weight = round2(prediction.predicted_weight);
+
+    info!(
+        goat_id,
+        weighings = history.len(),
+        predicted = prediction.predicted_weight,
+        "Returning weight prediction"
+    );
+    Ok(HttpResponse::Ok().json(prediction))
+}
+
+/// Handler for a goat's shareable export document, for moving it to another farm.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/export`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON `GoatDocument`: the goat with its vaccines and diseases
+///   by name and its weight history, tagged with `document_version`.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn export_goat_document(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, "GET /goats/{{id}}/export called");
+
     let conn = db.get_conn()?;
-    let affected = conn.execute("DELETE FROM goats WHERE name = ?", &[&name.name])?;
+    let document = DbPool::export_goat_document(&conn, goat_id)?;
+
+    info!(goat_id, "Returning goat export document");
+    Ok(HttpResponse::Ok().json(document))
+}
 
-    if affected == 0 {
-        warn!(goat_id = name.name, "Goat not found for deletion");
+/// Handler creating a goat from another farm's export document.
+///
+/// # HTTP Method
+/// - `POST /goats/import-document`
+///
+/// # Request
+/// - JSON `GoatDocument` as returned by `GET /goats/{id}/export`.
+///
+/// # Success
+/// - Returns HTTP 201 with the new goat and a `Location` header. Vaccines and diseases
+///   are matched by name, ignoring case, and created if missing.
+///
+/// # Errors
+/// - Returns HTTP 400 for an unsupported `document_version`.
+/// - Returns HTTP 409 if a goat with the same name exists, ignoring case.
+/// - Returns HTTP 422 with every invalid field if the goat fails validation.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Imported goat id.
+pub async fn import_goat_document(
+    db: web::Data<DbPool>,
+    cache: Option<web::Data<GoatListCache>>,
+    document: web::Json<GoatDocument>,
+) -> Result<impl Responder, AppError> {
+    let document = document.into_inner();
+    debug!(
+        goat_name = %document.goat.name,
+        version = document.document_version,
+        "POST /goats/import-document called"
+    );
+    document.goat.validate()?;
+
+    let goat_id = db
+        .write(move |tx| DbPool::import_goat_document(tx, &document))
+        .await?;
+    invalidate_goat_list(cache.as_ref());
+    let created = GoatService::get(&db, goat_id)?;
+
+    info!(goat_id, "Goat created from export document");
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("/goats/{}", goat_id)))
+        .json(created))
+}
+
+/// Handler importing many goats from a JSON array, or only checking them with
+/// `dry_run=true`.
+///
+/// # HTTP Method
+/// - `POST /goats/import[?dry_run=true]`
+///
+/// # Request
+/// - JSON array of goats as accepted by `POST /goats`.
+///
+/// # Success
+/// - Returns HTTP 201 with a `GoatImportReport` once every row is inserted, in one
+///   transaction.
+/// - With `dry_run=true`, returns HTTP 200 with the report of what would be inserted and
+///   every row error; nothing is written and no write transaction is opened.
+///
+/// # Errors
+/// - Returns HTTP 422 with the report if any row fails parsing or validation, or repeats
+///   or takes an existing name; nothing is written.
+/// - Returns HTTP 413 if the body exceeds `YAGI_MAX_IMPORT_BODY_BYTES`.
+///
+/// # Logs
+/// - Info: Receipt of the request and its outcome.
+pub async fn import_goats(
+    db: web::Data<DbPool>,
+    cache: Option<web::Data<GoatListCache>>,
+    query: web::Query<GoatImportQuery>,
+    rows: web::Json<Vec<Value>>,
+) -> Result<HttpResponse, AppError> {
+    let rows = rows.into_inner();
+    info!(
+        rows = rows.len(),
+        dry_run = query.dry_run,
+        "POST /goats/import called"
+    );
+    run_goat_import(
+        &db,
+        cache.as_ref(),
+        rows.into_iter().map(Ok).collect(),
+        query.dry_run,
+    )
+    .await
+}
+
+/// Handler importing many goats from CSV, or only checking them with `dry_run=true`.
+///
+/// # HTTP Method
+/// - `POST /goats/import/csv[?dry_run=true]`
+///
+/// # Request
+/// - CSV text with a header row naming the columns: any of `name`, `breed`, `gender`,
+///   `offspring`, `cost`, `weight`, `current_price`, `diet`, `last_bred`,
+///   `health_status`, `weight_unit`, `vaccinations` and `diseases`, the last two holding
+///   names separated by `;`. Empty `cost` and `current_price` cells are estimated as in
+///   `POST /goats`.
+///
+/// # Success
+/// - As for `POST /goats/import`.
+///
+/// # Errors
+/// - Returns HTTP 400 for a body without a header row, an unknown column, or an
+///   unterminated quote.
+/// - Otherwise as for `POST /goats/import`.
+///
+/// # Logs
+/// - Info: Receipt of the request and its outcome.
+pub async fn import_goats_csv(
+    db: web::Data<DbPool>,
+    cache: Option<web::Data<GoatListCache>>,
+    query: web::Query<GoatImportQuery>,
+    body: String,
+) -> Result<HttpResponse, AppError> {
+    info!(
+        size = body.len(),
+        dry_run = query.dry_run,
+        "POST /goats/import/csv called"
+    );
+    let rows = goat_csv_rows(&body)?;
+    run_goat_import(&db, cache.as_ref(), rows, query.dry_run).await
+}
+
+/// Checks every import row and, unless `dry_run` or any row failed, inserts them all in
+/// one transaction. Rows arrive as goat JSON objects, or as the error that kept a row
+/// from becoming one.
+async fn run_goat_import(
+    db: &DbPool,
+    cache: Option<&web::Data<GoatListCache>>,
+    rows: Vec<Result<Value, ImportRowError>>,
+    dry_run: bool,
+) -> Result<HttpResponse, AppError> {
+    let row_count = rows.len();
+    let (goats, errors) = check_import_rows(&db.get_conn()?, rows)?;
+    let mut report = GoatImportReport {
+        dry_run,
+        rows: row_count,
+        would_insert: goats.len(),
+        inserted: 0,
+        errors,
+    };
+
+    if dry_run {
+        info!(
+            rows = row_count,
+            would_insert = report.would_insert,
+            errors = report.errors.len(),
+            "Goat import dry run finished"
+        );
+        return Ok(HttpResponse::Ok().json(report));
+    }
+    if !report.errors.is_empty() {
+        warn!(
+            rows = row_count,
+            errors = report.errors.len(),
+            "Goat import rejected"
+        );
+        return Ok(HttpResponse::UnprocessableEntity().json(report));
+    }
+
+    report.inserted = db
+        .write(move |tx| {
+            for goat in &goats {
+                DbPool::insert_goat(tx, goat)?;
+            }
+            Ok(goats.len())
+        })
+        .await?;
+    invalidate_goat_list(cache);
+    info!(inserted = report.inserted, "Goat import committed");
+    Ok(HttpResponse::Created().json(report))
+}
+
+/// Runs each import row through the `POST /goats` pipeline (parsing, estimating omitted
+/// money fields, validation, conversion to kilograms) and checks its name is neither
+/// taken nor repeated by an earlier row. Returns the goats that passed and the errors of
+/// the rest. Only reads from `conn`.
+///
+/// # Errors
+/// Returns a database error if a lookup fails.
+fn check_import_rows(
+    conn: &Connection,
+    rows: Vec<Result<Value, ImportRowError>>,
+) -> Result<(Vec<GoatParams>, Vec<ImportRowError>), AppError> {
+    let mut goats = Vec::with_capacity(rows.len());
+    let mut errors = Vec::new();
+    let mut seen_names: HashMap<String, usize> = HashMap::new();
+    let row_error = |row: usize, field: Option<&str>, message: String| ImportRowError {
+        row,
+        field: field.map(str::to_string),
+        message,
+    };
+
+    for (index, value) in rows.into_iter().enumerate() {
+        let row = index + 1;
+        let value = match value {
+            Ok(value) => value,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+        let mut payload: GoatPayload = match serde_json::from_value(value) {
+            Ok(payload) => payload,
+            Err(e) => {
+                errors.push(row_error(row, None, e.to_string()));
+                continue;
+            }
+        };
+        if !payload.omitted.is_empty() {
+            let hint = DbPool::breed_price_hint(conn, breed_to_str(&payload.goat.breed))?;
+            payload.estimate_omitted(&hint);
+        }
+        match payload.validate() {
+            Ok(()) => {}
+            Err(AppError::Validation(fields)) => {
+                errors.extend(
+                    fields
+                        .into_iter()
+                        .map(|f| row_error(row, Some(&f.field), f.message)),
+                );
+                continue;
+            }
+            Err(e) => {
+                errors.push(row_error(row, None, e.to_string()));
+                continue;
+            }
+        }
+
+        let goat = payload.into_metric();
+        let name = normalize_name(&goat.name);
+        if let Some(first) = seen_names.get(&name.to_lowercase()) {
+            errors.push(row_error(
+                row,
+                Some("name"),
+                format!("Repeats the name of row {}", first),
+            ));
+            continue;
+        }
+        seen_names.insert(name.to_lowercase(), row);
+        match ensure_goat_name_free(conn, &name, None) {
+            Ok(()) => goats.push(goat),
+            Err(AppError::Conflict(message)) => errors.push(row_error(row, Some("name"), message)),
+            Err(e) => return Err(e),
+        }
+    }
+    debug!(
+        valid = goats.len(),
+        errors = errors.len(),
+        "Import rows checked"
+    );
+    Ok((goats, errors))
+}
+
+/// Columns a goat import CSV may have.
+const GOAT_CSV_COLUMNS: [&str; 13] = [
+    "name",
+    "breed",
+    "gender",
+    "offspring",
+    "cost",
+    "weight",
+    "current_price",
+    "diet",
+    "last_bred",
+    "health_status",
+    "weight_unit",
+    "vaccinations",
+    "diseases",
+];
+
+/// Turns a goat import CSV into one goat JSON object per data row, or the error of a row
+/// that cannot become one. Empty cells are left out, except that `last_bred` becomes
+/// null and the list columns empty lists.
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` for a missing header, an unknown column, or an
+/// unterminated quote.
+fn goat_csv_rows(text: &str) -> Result<Vec<Result<Value, ImportRowError>>, AppError> {
+    let mut records = parse_csv(text)?.into_iter();
+    let header: Vec<String> = records
+        .next()
+        .ok_or_else(|| AppError::InvalidInput("CSV has no header row".to_string()))?
+        .iter()
+        .map(|column| column.trim().to_lowercase())
+        .collect();
+    if let Some(unknown) = header
+        .iter()
+        .find(|column| !GOAT_CSV_COLUMNS.contains(&column.as_str()))
+    {
         return Err(AppError::InvalidInput(format!(
-            "No goat found with name {}",
-            name.name
+            "Unknown CSV column '{}'; expected any of {}",
+            unknown,
+            GOAT_CSV_COLUMNS.join(", ")
         )));
     }
 
-    info!(goat_id = name.name, "Goat deleted successfully");
-    Ok(HttpResponse::Ok().body("Goat deleted"))
+    Ok(records
+        .enumerate()
+        .map(|(index, record)| {
+            let row = index + 1;
+            if record.len() != header.len() {
+                return Err(ImportRowError {
+                    row,
+                    field: None,
+                    message: format!("Expected {} fields, found {}", header.len(), record.len()),
+                });
+            }
+            let mut goat = Map::new();
+            goat.insert("last_bred".to_string(), Value::Null);
+            goat.insert("vaccinations".to_string(), json!([]));
+            goat.insert("diseases".to_string(), json!([]));
+            for (column, cell) in header.iter().zip(record) {
+                let cell = cell.trim();
+                if cell.is_empty() {
+                    continue;
+                }
+                let value = match column.as_str() {
+                    "offspring" | "cost" | "weight" | "current_price" => {
+                        match cell.parse::<f64>() {
+                            Ok(n) if column == "offspring" && n.fract() == 0.0 => json!(n as i64),
+                            Ok(n) if column != "offspring" => json!(n),
+                            _ => {
+                                return Err(ImportRowError {
+                                    row,
+                                    field: Some(column.clone()),
+                                    message: format!("'{}' is not a valid number", cell),
+                                });
+                            }
+                        }
+                    }
+                    "vaccinations" | "diseases" => Value::Array(
+                        cell.split(';')
+                            .map(str::trim)
+                            .filter(|name| !name.is_empty())
+                            .map(|name| json!({ "name": name }))
+                            .collect(),
+                    ),
+                    _ => json!(cell),
+                };
+                goat.insert(column.clone(), value);
+            }
+            Ok(Value::Object(goat))
+        })
+        .collect())
+}
+
+/// Splits CSV text into records of fields. Fields may be quoted with `"`, inside which
+/// commas and line breaks are literal and `""` stands for a quote. Blank lines are
+/// skipped.
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if a quoted field is never closed.
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, AppError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                c => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            c => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(AppError::InvalidInput(
+            "CSV has an unterminated quoted field".to_string(),
+        ));
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Handler listing only a goat's vaccinations.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/vaccines`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `VaccineRef`.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn get_goat_vaccines(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, "GET /goats/{{id}}/vaccines called");
+
+    let conn = db.get_conn()?;
+    ensure_goat_exists(&conn, goat_id)?;
+    let vaccines = fetch_vaccines(&conn, goat_id)?;
+
+    info!(goat_id, count = vaccines.len(), "Returning goat vaccines");
+    Ok(HttpResponse::Ok().json(vaccines))
+}
+
+/// Handler listing only a goat's diseases.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/diseases`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `DiseaseRef`.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn get_goat_diseases(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, "GET /goats/{{id}}/diseases called");
+
+    let conn = db.get_conn()?;
+    ensure_goat_exists(&conn, goat_id)?;
+    let diseases = fetch_diseases(&conn, goat_id)?;
+
+    info!(goat_id, count = diseases.len(), "Returning goat diseases");
+    Ok(HttpResponse::Ok().json(diseases))
+}
+
+/// Handler listing goats that need attention, most urgent first.
+///
+/// # HTTP Method
+/// - `GET /goats/attention?min_weight_kg=15&max_weight_kg=120&weight_stale_days=30`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ goat_id, name, score, reasons }` per flagged live goat,
+///   highest score first. Each reason is `{ kind, detail }`, `kind` being one of
+///   `active_disease`, `quarantine`, `overdue_vaccination`, `underweight`, `overweight`
+///   or `stale_weight`; the score adds up points per reason, weighted towards illness.
+///   Conditions whose history tables the database lacks are skipped.
+///
+/// # Configuration
+/// - `YAGI_ATTENTION_MIN_WEIGHT_KG` (default 15), `YAGI_ATTENTION_MAX_WEIGHT_KG` (default
+///   120) and `YAGI_ATTENTION_WEIGHT_STALE_DAYS` (default 30), each overridden by the
+///   query parameter of the same name.
+///
+/// # Logs
+/// - Debug: Entry point of request and thresholds applied.
+/// - Info: Number of goats flagged.
+pub async fn get_attention_goats(
+    db: web::Data<DbPool>,
+    query: web::Query<AttentionQuery>,
+) -> Result<impl Responder, AppError> {
+    let thresholds = AttentionThresholds {
+        min_weight_kg: query.min_weight_kg.unwrap_or_else(attention_min_weight_kg),
+        max_weight_kg: query.max_weight_kg.unwrap_or_else(attention_max_weight_kg),
+        weight_stale_days: query
+            .weight_stale_days
+            .unwrap_or_else(attention_weight_stale_days),
+    };
+    debug!(?thresholds, "GET /goats/attention called");
+
+    let conn = db.get_conn()?;
+    let flags = DbPool::attention_flags(&conn, Utc::now().date_naive(), &thresholds)?;
+    let goats = rank_attention(flags);
+
+    info!(count = goats.len(), "Returning goats needing attention");
+    Ok(HttpResponse::Ok().json(goats))
+}
+
+/// Handler listing goats that currently meet every sale criterion.
+///
+/// # HTTP Method
+/// - `GET /goats/sale-ready`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of goats (with ids), heaviest first. Each entry
+///   carries `estimated_sale_price`, derived from the latest market price per kg for its
+///   breed, or `null` if no price is known.
+///
+/// # Configuration
+/// - `SALE_MIN_WEIGHT_KG` (default 25) and `SALE_MIN_AGE_MONTHS` (default 6).
+///
+/// # Logs
+/// - Debug: Entry point of request and thresholds applied.
+/// - Info: Number of sale-ready goats.
+pub async fn get_sale_ready_goats(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    let min_weight = sale_min_weight_kg();
+    let min_age_months = sale_min_age_months();
+    debug!(min_weight, min_age_months, "GET /goats/sale-ready called");
+
+    let conn = db.get_conn()?;
+    let today = Utc::now().date_naive();
+    let mut ready: Vec<_> = DbPool::load_sale_candidates(&conn)?
+        .into_iter()
+        .filter(|candidate| {
+            is_sale_ready(
+                &candidate.goat.params,
+                candidate.birth_date,
+                min_weight,
+                min_age_months,
+                today,
+            )
+        })
+        .collect();
+    ready.sort_by(|a, b| b.goat.params.weight.total_cmp(&a.goat.params.weight));
+
+    let mut goats = Vec::with_capacity(ready.len());
+    for candidate in ready {
+        let params = &candidate.goat.params;
+        let estimated_sale_price =
+            DbPool::get_latest_market_price(&conn, breed_to_str(&params.breed))?
+                .map(|price_per_kg| price_per_kg * params.weight);
+        goats.push(SaleReadyGoat {
+            goat: candidate.goat,
+            estimated_sale_price,
+        });
+    }
+
+    info!(count = goats.len(), "Returning sale-ready goats");
+    Ok(HttpResponse::Ok().json(goats))
+}
+
+/// Handler selling a goat to a buyer.
+///
+/// # HTTP Method
+/// - `POST /goats/{id}/sell`
+///
+/// # Request
+/// - JSON `{ "buyer_id"?: i64, "buyer_name"?: string, "price": f64 }`. Without
+///   `buyer_id`, the buyer is found by name (ignoring case) or created.
+///
+/// # Success
+/// - Returns HTTP 201 with the recorded sale; the goat is marked sold. The buyer, sale
+///   and goat update are written in one transaction.
+///
+/// # Errors
+/// - Returns HTTP 422 for a negative price or a missing buyer.
+/// - Returns HTTP 404 if the goat or `buyer_id` does not exist.
+/// - Returns HTTP 409 if the goat has already been sold.
+pub async fn sell_goat(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    sale: web::Json<SalePayload>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    info!(
+        goat_id,
+        buyer_id = ?sale.buyer_id,
+        price = sale.price,
+        "POST /goats/{{id}}/sell called"
+    );
+    sale.validate()?;
+
+    let recorded = db
+        .write(move |tx| DbPool::sell_goat(tx, goat_id, &sale))
+        .await?;
+
+    Ok(HttpResponse::Created().json(recorded))
+}
+
+/// Handler tagging a goat.
+///
+/// # HTTP Method
+/// - `POST /goats/{id}/tags`
+///
+/// # Request
+/// - JSON `{ "tag": string }`, e.g. `"show-quality"`. Tags are created on first use and
+///   matched ignoring case.
+///
+/// # Success
+/// - Returns HTTP 200 with the goat's tags in alphabetical order. Re-adding a tag the
+///   goat already carries changes nothing.
+///
+/// # Errors
+/// - Returns HTTP 422 for an empty or over-long tag.
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn add_goat_tag(
+    db: web::Data<DbPool>,
+    cache: Option<web::Data<GoatListCache>>,
+    path: web::Path<i64>,
+    payload: web::Json<TagPayload>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    info!(goat_id, tag = %payload.tag, "POST /goats/{{id}}/tags called");
+    payload.validate()?;
+
+    let tags = db
+        .write(move |tx| DbPool::add_goat_tag(tx, goat_id, &payload.tag))
+        .await?;
+    invalidate_goat_list(cache.as_ref());
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+/// Handler removing a tag from a goat.
+///
+/// # HTTP Method
+/// - `DELETE /goats/{id}/tags/{tag}`
+///
+/// # Success
+/// - Returns HTTP 200 with the goat's remaining tags.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist or does not carry the tag.
+pub async fn remove_goat_tag(
+    db: web::Data<DbPool>,
+    cache: Option<web::Data<GoatListCache>>,
+    path: web::Path<(i64, String)>,
+) -> Result<impl Responder, AppError> {
+    let (goat_id, tag) = path.into_inner();
+    info!(goat_id, tag, "DELETE /goats/{{id}}/tags/{{tag}} called");
+
+    let tags = db
+        .write(move |tx| DbPool::remove_goat_tag(tx, goat_id, &tag))
+        .await?;
+    invalidate_goat_list(cache.as_ref());
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+/// Drops the cached goat list, if a cache is registered, once a write has committed.
+fn invalidate_goat_list(cache: Option<&web::Data<GoatListCache>>) {
+    if let Some(cache) = cache {
+        cache.invalidate();
+    }
 }
@@ -0,0 +1,24 @@
+//! Liveness endpoint for load balancers and monitoring.
+
+use crate::db::pool_exhausted_total;
+use crate::state::AppState;
+use actix_web::{HttpResponse, Responder, web};
+use serde_json::json;
+use tracing::trace;
+
+/// Handler reporting that the server is up, along with its maintenance state.
+///
+/// # HTTP Method
+/// - `GET /health`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ "status": "ok", "read_only": bool, "pool_exhausted_total": n }`,
+///   the last counting requests that timed out waiting for a database connection.
+pub async fn health(state: web::Data<AppState>) -> impl Responder {
+    trace!("GET /health called");
+    HttpResponse::Ok().json(json!({
+        "status": "ok",
+        "read_only": state.is_read_only(),
+        "pool_exhausted_total": pool_exhausted_total(),
+    }))
+}
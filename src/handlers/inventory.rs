@@ -0,0 +1,192 @@
+//! Handlers for the medicine inventory: stock levels, dispensing, and expiry.
+
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::{DispensePayload, ExpiringQuery, LowStockQuery, MedicineItem, RestockPayload};
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use chrono::{Days, Utc};
+use tracing::{debug, info};
+
+/// Look-ahead window used by `GET /inventory/medicine/expiring` when `days` is omitted.
+const DEFAULT_EXPIRY_WINDOW_DAYS: i64 = 30;
+/// Stock level used by `GET /inventory/medicine/low-stock` when `threshold` is omitted.
+const DEFAULT_LOW_STOCK_THRESHOLD: f64 = 5.0;
+
+/// Handler listing every medicine in the inventory.
+///
+/// # HTTP Method
+/// - `GET /inventory/medicine`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `MedicineItem`, by name.
+pub async fn list_medicines(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /inventory/medicine called");
+    let conn = db.get_conn()?;
+    let items = DbPool::list_medicines(&conn)?;
+
+    info!(count = items.len(), "Returning medicine inventory");
+    Ok(HttpResponse::Ok().json(items))
+}
+
+/// Handler adding a medicine to the inventory.
+///
+/// # HTTP Method
+/// - `POST /inventory/medicine`
+///
+/// # Request
+/// - JSON `MedicineItem` without `id`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored item, including its new `id`.
+///
+/// # Errors
+/// - Returns HTTP 422 listing every invalid field (e.g. negative stock).
+pub async fn add_medicine(
+    db: web::Data<DbPool>,
+    item: web::Json<MedicineItem>,
+) -> Result<impl Responder, AppError> {
+    info!(name = %item.name, "POST /inventory/medicine called");
+    item.validate()?;
+
+    let stored = db
+        .write(move |tx| DbPool::insert_medicine(tx, &item))
+        .await?;
+    Ok(HttpResponse::Created().json(stored))
+}
+
+/// Handler dispensing a medicine to a goat.
+///
+/// # HTTP Method
+/// - `POST /inventory/medicine/{id}/dispense`
+///
+/// # Request
+/// - JSON `{ "goat_id": i64, "amount": f64, "reason": string }`.
+///
+/// # Success
+/// - Returns HTTP 200 with the item's remaining stock. The deduction and the dispensing
+///   record are written in one transaction.
+///
+/// # Errors
+/// - Returns HTTP 400 for a non-positive amount or an empty reason.
+/// - Returns HTTP 404 if the medicine or goat does not exist.
+/// - Returns HTTP 409 if `amount` exceeds the current stock; nothing is changed.
+pub async fn dispense_medicine(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    payload: web::Json<DispensePayload>,
+) -> Result<impl Responder, AppError> {
+    let medicine_id = path.into_inner();
+    info!(
+        medicine_id,
+        goat_id = payload.goat_id,
+        amount = payload.amount,
+        "POST /inventory/medicine/{{id}}/dispense called"
+    );
+
+    let item = db
+        .write(move |tx| {
+            DbPool::dispense_medicine(
+                tx,
+                medicine_id,
+                payload.goat_id,
+                payload.amount,
+                &payload.reason,
+            )
+        })
+        .await?;
+
+    Ok(HttpResponse::Ok().json(item))
+}
+
+/// Handler adding stock to a medicine, optionally recording the supplier and price.
+///
+/// # HTTP Method
+/// - `POST /inventory/medicine/{id}/restock`
+///
+/// # Request
+/// - JSON `{ "units": f64, "cost_per_unit"?: f64, "supplier_id"?: i64 }`.
+///
+/// # Success
+/// - Returns HTTP 200 with the item's new stock. The stock change and the restock record
+///   are written in one transaction.
+///
+/// # Errors
+/// - Returns HTTP 422 for a non-positive `units` or a negative `cost_per_unit`.
+/// - Returns HTTP 404 if the medicine or supplier does not exist.
+pub async fn restock_medicine(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    payload: web::Json<RestockPayload>,
+) -> Result<impl Responder, AppError> {
+    let medicine_id = path.into_inner();
+    info!(
+        medicine_id,
+        units = payload.units,
+        supplier_id = ?payload.supplier_id,
+        "POST /inventory/medicine/{{id}}/restock called"
+    );
+    payload.validate()?;
+
+    let item = db
+        .write(move |tx| DbPool::restock_medicine(tx, medicine_id, &payload))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(item))
+}
+
+/// Handler listing in-stock medicines that expire within `days` (default 30).
+///
+/// # HTTP Method
+/// - `GET /inventory/medicine/expiring?days=N`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `MedicineItem`, soonest expiry first. Items
+///   that have already expired are included.
+///
+/// # Errors
+/// - Returns HTTP 400 if `days` is negative.
+pub async fn get_expiring_medicines(
+    db: web::Data<DbPool>,
+    query: web::Query<ExpiringQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(days = ?query.days, "GET /inventory/medicine/expiring called");
+    let days = query.days.unwrap_or(DEFAULT_EXPIRY_WINDOW_DAYS);
+    let cutoff = u64::try_from(days)
+        .ok()
+        .and_then(|days| Utc::now().date_naive().checked_add_days(Days::new(days)))
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!("days must be a non-negative number, got {}", days))
+        })?;
+
+    let conn = db.get_conn()?;
+    let items = DbPool::expiring_medicines(&conn, cutoff)?;
+
+    info!(%cutoff, count = items.len(), "Returning expiring medicines");
+    Ok(HttpResponse::Ok().json(items))
+}
+
+/// Handler listing medicines whose stock is below `threshold` (default 5).
+///
+/// # HTTP Method
+/// - `GET /inventory/medicine/low-stock?threshold=N`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `MedicineItem`, lowest stock first.
+pub async fn get_low_stock_medicines(
+    db: web::Data<DbPool>,
+    query: web::Query<LowStockQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(threshold = ?query.threshold, "GET /inventory/medicine/low-stock called");
+    let threshold = query.threshold.unwrap_or(DEFAULT_LOW_STOCK_THRESHOLD);
+
+    let conn = db.get_conn()?;
+    let items = DbPool::low_stock_medicines(&conn, threshold)?;
+
+    info!(
+        threshold,
+        count = items.len(),
+        "Returning low-stock medicines"
+    );
+    Ok(HttpResponse::Ok().json(items))
+}
@@ -0,0 +1,123 @@
+//! Handlers for workers' leave requests and their approval.
+
+use crate::db::DbPool;
+use crate::db_helpers::parse_iso_date;
+use crate::errors::AppError;
+use crate::models::{LEAVE_STATUSES, LeaveDecision, LeaveRequest, LeaveRequestsQuery};
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use tracing::{debug, info};
+
+/// Handler recording a worker's request for leave.
+///
+/// # HTTP Method
+/// - `POST /leave-requests`
+///
+/// # Request
+/// - JSON `{ "worker_id": i64, "from_date": "YYYY-MM-DD", "to_date": "YYYY-MM-DD",
+///   "reason"?: string }`; both dates are days of leave.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored request, `pending` until decided.
+///
+/// # Errors
+/// - Returns HTTP 422 for malformed dates or `to_date` before `from_date`.
+/// - Returns HTTP 404 if the worker does not exist.
+/// - Returns HTTP 409 if the days overlap leave already approved for the worker.
+pub async fn create_leave_request(
+    db: web::Data<DbPool>,
+    request: web::Json<LeaveRequest>,
+) -> Result<impl Responder, AppError> {
+    let mut request = request.into_inner();
+    info!(
+        worker_id = request.worker_id,
+        from = %request.from_date,
+        to = %request.to_date,
+        "POST /leave-requests called"
+    );
+    request.validate()?;
+    // Stored zero-padded, so dates compare correctly as text.
+    request.from_date = parse_iso_date("from_date", &request.from_date)?.to_string();
+    request.to_date = parse_iso_date("to_date", &request.to_date)?.to_string();
+
+    let stored = db
+        .write(move |tx| DbPool::insert_leave_request(tx, &request))
+        .await?;
+    Ok(HttpResponse::Created().json(stored))
+}
+
+/// Handler approving or rejecting a pending leave request.
+///
+/// # HTTP Method
+/// - `POST /leave-requests/{id}/decide`
+///
+/// # Request
+/// - JSON `{ "status": "approved"|"rejected", "decided_by": string }`.
+///
+/// # Success
+/// - Returns HTTP 200 with the decided request. Approval adds its days, both ends
+///   included, to the worker's `leaves` total in the same transaction.
+///
+/// # Errors
+/// - Returns HTTP 422 for any other status or an empty `decided_by`.
+/// - Returns HTTP 404 if the request does not exist.
+/// - Returns HTTP 409 if it was already decided, or if approving it would overlap leave
+///   already approved for the worker.
+pub async fn decide_leave_request(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    decision: web::Json<LeaveDecision>,
+) -> Result<impl Responder, AppError> {
+    let id = path.into_inner();
+    info!(id, status = %decision.status, "POST /leave-requests/{{id}}/decide called");
+    decision.validate()?;
+
+    let decided = db
+        .write(move |tx| DbPool::decide_leave_request(tx, id, &decision))
+        .await?;
+    Ok(HttpResponse::Ok().json(decided))
+}
+
+/// Handler listing leave requests as a calendar.
+///
+/// # HTTP Method
+/// - `GET /leave-requests?from=YYYY-MM-DD&to=YYYY-MM-DD&status=approved`
+///
+/// # Success
+/// - Returns HTTP 200 with the requests overlapping the days `from` to `to` (either end
+///   open when omitted), optionally only those with `status`, earliest start first.
+///
+/// # Errors
+/// - Returns HTTP 400 for malformed dates or an unknown status.
+pub async fn list_leave_requests(
+    db: web::Data<DbPool>,
+    query: web::Query<LeaveRequestsQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(?query, "GET /leave-requests called");
+    let from = query
+        .from
+        .as_deref()
+        .map(|d| parse_iso_date("from", d))
+        .transpose()?;
+    let to = query
+        .to
+        .as_deref()
+        .map(|d| parse_iso_date("to", d))
+        .transpose()?;
+    let status = query.status.as_deref().map(str::trim);
+    if let Some(status) = status {
+        if !LEAVE_STATUSES.contains(&status) {
+            return Err(AppError::InvalidInput(format!(
+                "status must be one of {}, got {}",
+                LEAVE_STATUSES.join(", "),
+                status
+            )));
+        }
+    }
+
+    let conn = db.get_conn()?;
+    let requests = DbPool::list_leave_requests(&conn, from, to, status)?;
+
+    info!(count = requests.len(), "Returning leave requests");
+    Ok(HttpResponse::Ok().json(requests))
+}
@@ -1,3 +1,31 @@
 //! Handler modules re-export for easier imports
 
+pub mod activity;
+pub mod admin;
+pub mod batch;
+pub mod behavior;
+pub mod breeding;
+pub mod buyers;
+pub mod catalog;
+pub mod cohorts;
+pub mod documents;
+pub mod equipment;
+pub mod famacha;
+pub mod genetics;
+pub mod geofences;
 pub mod goats;
+pub mod health;
+pub mod inventory;
+pub mod leave_requests;
+pub mod payroll;
+pub mod reports;
+pub mod sensors;
+pub mod shows;
+pub mod spaces;
+pub mod stats;
+pub mod suppliers;
+pub mod tasks;
+pub mod tools;
+pub mod trade_log;
+pub mod vaccines;
+pub mod workers;
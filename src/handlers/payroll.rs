@@ -0,0 +1,183 @@
+//! Handlers for workers' time entries, pay terms and monthly payroll.
+
+use crate::config::{default_hourly_rate, overtime_pay_multiplier, overtime_threshold_hours};
+use crate::db::{DbPool, ensure_worker_exists};
+use crate::domain::payroll::{PayTerms, month_bounds};
+use crate::errors::AppError;
+use crate::models::{PayrollQuery, PayrollSummary, TimeEntry, WorkerPayTerms};
+use crate::units::round2;
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use chrono::NaiveDate;
+use tracing::{debug, info, warn};
+
+/// Farm-wide pay terms for workers without their own, from the configuration.
+fn default_pay_terms() -> PayTerms {
+    PayTerms {
+        hourly_rate: default_hourly_rate(),
+        overtime_threshold_hours: overtime_threshold_hours(),
+        overtime_multiplier: overtime_pay_multiplier(),
+    }
+}
+
+/// First and last day of the queried month.
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if `month` is not `YYYY-MM`.
+fn parse_month(month: &str) -> Result<(NaiveDate, NaiveDate), AppError> {
+    month_bounds(month).ok_or_else(|| {
+        warn!(month, "Rejected payroll month");
+        AppError::InvalidInput(format!("month must be YYYY-MM, got '{}'", month))
+    })
+}
+
+/// Handler logging the hours a worker put in on one day.
+///
+/// # HTTP Method
+/// - `POST /workers/{id}/time-entries`
+///
+/// # Request
+/// - JSON `{ "work_date": "YYYY-MM-DD", "hours": f64, "note"?: string }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored entry.
+///
+/// # Errors
+/// - Returns HTTP 422 for a malformed date or hours outside (0, 24].
+/// - Returns HTTP 404 if the worker does not exist.
+pub async fn add_time_entry(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    entry: web::Json<TimeEntry>,
+) -> Result<impl Responder, AppError> {
+    let mut entry = entry.into_inner();
+    entry.worker_id = path.into_inner();
+    info!(
+        worker_id = entry.worker_id,
+        work_date = %entry.work_date,
+        hours = entry.hours,
+        "POST /workers/{{id}}/time-entries called"
+    );
+    entry.validate()?;
+
+    let stored = db
+        .write(move |tx| DbPool::insert_time_entry(tx, &entry))
+        .await?;
+    Ok(HttpResponse::Created().json(stored))
+}
+
+/// Handler setting a worker's own hourly rate and overtime threshold.
+///
+/// # HTTP Method
+/// - `PUT /workers/{id}/pay-terms`
+///
+/// # Request
+/// - JSON `{ "hourly_rate"?: f64, "overtime_threshold_hours"?: f64 }`; an absent value
+///   reverts to the farm default.
+///
+/// # Success
+/// - Returns HTTP 200 with the updated worker.
+///
+/// # Errors
+/// - Returns HTTP 422 for negative values.
+/// - Returns HTTP 404 if the worker does not exist.
+///
+/// # Configuration
+/// - `YAGI_DEFAULT_HOURLY_RATE` and `YAGI_OVERTIME_THRESHOLD_HOURS` hold the defaults.
+pub async fn set_worker_pay_terms(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    terms: web::Json<WorkerPayTerms>,
+) -> Result<impl Responder, AppError> {
+    let worker_id = path.into_inner();
+    info!(worker_id, "PUT /workers/{{id}}/pay-terms called");
+    terms.validate()?;
+
+    let worker = db
+        .write(move |tx| DbPool::set_worker_pay_terms(tx, worker_id, &terms))
+        .await?;
+    Ok(HttpResponse::Ok().json(worker))
+}
+
+/// Handler computing a worker's pay for a month.
+///
+/// # HTTP Method
+/// - `GET /workers/{id}/payroll?month=YYYY-MM`
+///
+/// # Success
+/// - Returns HTTP 200 with a `WorkerPayroll`: hours logged, approved leave days in the
+///   month, overtime hours beyond the threshold, and gross pay with overtime at the
+///   overtime multiplier. A month without entries reports zeros.
+///
+/// # Errors
+/// - Returns HTTP 400 if `month` is not `YYYY-MM`.
+/// - Returns HTTP 404 if the worker does not exist.
+///
+/// # Configuration
+/// - `YAGI_DEFAULT_HOURLY_RATE` (100) and `YAGI_OVERTIME_THRESHOLD_HOURS` (208) apply to
+///   workers without their own; `YAGI_OVERTIME_PAY_MULTIPLIER` (1.5) to everyone.
+pub async fn get_worker_payroll(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    query: web::Query<PayrollQuery>,
+) -> Result<impl Responder, AppError> {
+    let worker_id = path.into_inner();
+    debug!(worker_id, month = %query.month, "GET /workers/{{id}}/payroll called");
+    let (first, last) = parse_month(&query.month)?;
+
+    let conn = db.get_conn()?;
+    ensure_worker_exists(&conn, worker_id)?;
+    let payroll =
+        DbPool::monthly_payroll(&conn, Some(worker_id), first, last, &default_pay_terms())?
+            .pop()
+            .ok_or_else(|| AppError::NotFound(format!("Worker {} not found", worker_id)))?;
+
+    info!(
+        worker_id,
+        month = %payroll.month,
+        gross_pay = payroll.gross_pay,
+        "Returning worker payroll"
+    );
+    Ok(HttpResponse::Ok().json(payroll))
+}
+
+/// Handler computing every worker's pay for a month, for the accountant.
+///
+/// # HTTP Method
+/// - `GET /payroll/summary?month=YYYY-MM`
+///
+/// # Success
+/// - Returns HTTP 200 with a `PayrollSummary`: each worker's `WorkerPayroll`, by id, and
+///   totals of hours, overtime, leave days and gross pay across them.
+///
+/// # Errors
+/// - Returns HTTP 400 if `month` is not `YYYY-MM`.
+///
+/// # Configuration
+/// - As for `GET /workers/{id}/payroll`.
+pub async fn get_payroll_summary(
+    db: web::Data<DbPool>,
+    query: web::Query<PayrollQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(month = %query.month, "GET /payroll/summary called");
+    let (first, last) = parse_month(&query.month)?;
+
+    let conn = db.get_conn()?;
+    let workers = DbPool::monthly_payroll(&conn, None, first, last, &default_pay_terms())?;
+    let summary = PayrollSummary {
+        month: first.format("%Y-%m").to_string(),
+        total_hours: round2(workers.iter().map(|w| w.hours_worked).sum()),
+        total_overtime_hours: round2(workers.iter().map(|w| w.overtime_hours).sum()),
+        total_leave_days: workers.iter().map(|w| w.leave_days).sum(),
+        total_gross_pay: round2(workers.iter().map(|w| w.gross_pay).sum()),
+        workers,
+    };
+
+    info!(
+        month = %summary.month,
+        workers = summary.workers.len(),
+        total_gross_pay = summary.total_gross_pay,
+        "Returning payroll summary"
+    );
+    Ok(HttpResponse::Ok().json(summary))
+}
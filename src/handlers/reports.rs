@@ -0,0 +1,232 @@
+//! Reporting endpoints that aggregate farm activity across tables.
+//!
+//! Reports are read-only and computed on demand from the timestamps stored alongside
+//! each record, so they always reflect the current state of the database.
+
+use crate::db::DbPool;
+use crate::db_helpers::parse_iso_date;
+use crate::errors::AppError;
+use crate::handlers::stats::MAX_TREND_RANGE_MONTHS;
+use crate::models::{DateQuery, HealthTrendQuery, MonthQuery, YearQuery};
+use actix_web::{HttpResponse, Responder, web};
+use chrono::{Datelike, Months, Utc};
+use tracing::{debug, info, warn};
+
+/// Handler for the activity report of a given day.
+///
+/// # HTTP Method
+/// - `GET /reports/daily?date=YYYY-MM-DD`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON `DailyReport`.
+///
+/// # Errors
+/// - Returns HTTP 400 if `date` is malformed or lies in the future.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Warn: Rejected future dates.
+/// - Info: Report returned.
+pub async fn get_daily_report(
+    db: web::Data<DbPool>,
+    query: web::Query<DateQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(date = %query.date, "GET /reports/daily called");
+    let date = parse_iso_date("date", &query.date)?;
+
+    if date > Utc::now().date_naive() {
+        warn!(%date, "Daily report requested for a future date");
+        return Err(AppError::InvalidInput(format!(
+            "date {} is in the future",
+            date
+        )));
+    }
+
+    let conn = db.get_conn()?;
+    let report = DbPool::daily_report(&conn, date)?;
+
+    info!(%date, "Returning daily report");
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Handler for today's activity report.
+///
+/// # HTTP Method
+/// - `GET /reports/daily/latest`
+///
+/// # Success
+/// - Returns HTTP 200 with the JSON `DailyReport` for the current UTC date.
+pub async fn get_latest_daily_report(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /reports/daily/latest called");
+    let today = Utc::now().date_naive();
+
+    let conn = db.get_conn()?;
+    let report = DbPool::daily_report(&conn, today)?;
+
+    info!(date = %today, "Returning latest daily report");
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Earliest year the monthly and census reports cover.
+pub const MIN_REPORT_YEAR: i32 = 2000;
+
+/// Latest year the monthly and census reports cover.
+pub const MAX_REPORT_YEAR: i32 = 2100;
+
+/// Rejects years outside `MIN_REPORT_YEAR..=MAX_REPORT_YEAR`.
+fn validate_report_year(year: i32) -> Result<(), AppError> {
+    if (MIN_REPORT_YEAR..=MAX_REPORT_YEAR).contains(&year) {
+        return Ok(());
+    }
+    warn!(year, "Report requested for an invalid year");
+    Err(AppError::InvalidInput(format!(
+        "year must be between {} and {}, got {}",
+        MIN_REPORT_YEAR, MAX_REPORT_YEAR, year
+    )))
+}
+
+/// Handler for the financial report of a given month.
+///
+/// # HTTP Method
+/// - `GET /reports/monthly?month=1-12&year=YYYY`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON `MonthlyFinancialReport`.
+///
+/// # Errors
+/// - Returns HTTP 400 if `month` is outside 1-12 or `year` outside 2000-2100.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Warn: Rejected months and years.
+/// - Info: Report returned.
+pub async fn get_monthly_report(
+    db: web::Data<DbPool>,
+    query: web::Query<MonthQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(
+        month = query.month,
+        year = query.year,
+        "GET /reports/monthly called"
+    );
+
+    if !(1..=12).contains(&query.month) {
+        warn!(
+            month = query.month,
+            "Monthly report requested for an invalid month"
+        );
+        return Err(AppError::InvalidInput(format!(
+            "month must be between 1 and 12, got {}",
+            query.month
+        )));
+    }
+    validate_report_year(query.year)?;
+
+    let conn = db.get_conn()?;
+    let report = DbPool::monthly_financial_report(&conn, query.month, query.year)?;
+
+    info!(month = %report.month, "Returning monthly financial report");
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Handler for the current month's financial report.
+///
+/// # HTTP Method
+/// - `GET /reports/monthly/latest`
+///
+/// # Success
+/// - Returns HTTP 200 with the JSON `MonthlyFinancialReport` for the current UTC month
+///   so far.
+pub async fn get_latest_monthly_report(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /reports/monthly/latest called");
+    let today = Utc::now().date_naive();
+
+    let conn = db.get_conn()?;
+    let report = DbPool::monthly_financial_report(&conn, today.month(), today.year())?;
+
+    info!(month = %report.month, "Returning latest monthly financial report");
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Handler for the annual livestock census used in government returns.
+///
+/// # HTTP Method
+/// - `GET /reports/census?year=YYYY`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON `CensusReport`: the herd at the start and end of the
+///   year, the year-end herd by breed and gender, births, deaths and sales during the
+///   year, and the average month-end herd size.
+///
+/// # Errors
+/// - Returns HTTP 400 if `year` is outside 2000-2100.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Warn: Rejected years.
+/// - Info: Year-end headcount returned.
+pub async fn get_census(
+    db: web::Data<DbPool>,
+    query: web::Query<YearQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(year = query.year, "GET /reports/census called");
+    validate_report_year(query.year)?;
+
+    let conn = db.get_conn()?;
+    let report = DbPool::annual_census(&conn, query.year)?;
+
+    info!(
+        year = report.year,
+        closing_count = report.closing_count,
+        "Returning annual census"
+    );
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Handler for the herd health time series.
+///
+/// # HTTP Method
+/// - `GET /reports/health-trends?from=YYYY-MM-DD&to=YYYY-MM-DD&bucket=week|month`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `{ bucket, diagnoses, resolutions }`, one per
+///   bucket with any events, oldest first. Adding a disease to a goat counts as a
+///   diagnosis and removing it as a resolution.
+///
+/// # Errors
+/// - Returns HTTP 400 for malformed dates, `from` after `to`, or a range over two years.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Warn: Rejected date ranges.
+/// - Info: Number of buckets returned.
+pub async fn get_health_trends(
+    db: web::Data<DbPool>,
+    query: web::Query<HealthTrendQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(from = %query.from, to = %query.to, bucket = ?query.bucket, "GET /reports/health-trends called");
+    let from = parse_iso_date("from", &query.from)?;
+    let to = parse_iso_date("to", &query.to)?;
+
+    if from > to {
+        warn!(%from, %to, "Health trend range is inverted");
+        return Err(AppError::InvalidInput(
+            "from must not be after to".to_string(),
+        ));
+    }
+    if from
+        .checked_add_months(Months::new(MAX_TREND_RANGE_MONTHS))
+        .is_some_and(|limit| to > limit)
+    {
+        warn!(%from, %to, "Health trend range exceeds two years");
+        return Err(AppError::InvalidInput(
+            "Date range must not exceed 2 years".to_string(),
+        ));
+    }
+
+    let conn = db.get_conn()?;
+    let points = DbPool::health_trends(&conn, from, to, query.bucket)?;
+
+    info!(count = points.len(), "Returning health trend points");
+    Ok(HttpResponse::Ok().json(points))
+}
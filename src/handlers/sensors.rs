@@ -0,0 +1,150 @@
+//! Handlers for sensor readings: recording them with an anomaly check, and reading
+//! them back raw or downsampled.
+
+use crate::config::{sensor_anomaly_sigma, sensor_anomaly_window};
+use crate::db::DbPool;
+use crate::db_helpers::parse_timestamp;
+use crate::errors::AppError;
+use crate::models::{SensorReadingPayload, SensorReadingsQuery};
+use actix_web::{HttpResponse, Responder, web};
+use tracing::{debug, info};
+
+/// Most buckets `GET /sensors/{id}/readings?bucket=..` returns; ranges holding more are
+/// rejected rather than cut short.
+pub const MAX_READING_BUCKETS: u32 = 1000;
+
+/// Most raw readings `GET /sensors/{id}/readings` returns per page, and the default
+/// page size.
+pub const MAX_RAW_READINGS: u32 = 1000;
+
+/// Handler recording a new reading from a sensor.
+///
+/// # HTTP Method
+/// - `POST /sensors/{id}/readings`
+///
+/// # Request
+/// - JSON `{ "value": f64, "recorded_at"?: timestamp }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored reading. It is flagged `is_anomaly`, and a
+///   `sensor_anomaly` alert is raised, when it lies more than `YAGI_SENSOR_ANOMALY_SIGMA`
+///   (default 3) standard deviations from the mean of the sensor's last
+///   `YAGI_SENSOR_ANOMALY_WINDOW` (default 20) readings.
+///
+/// # Errors
+/// - Returns HTTP 400 for a malformed `recorded_at`.
+/// - Returns HTTP 404 if the sensor does not exist.
+pub async fn add_sensor_reading(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    reading: web::Json<SensorReadingPayload>,
+) -> Result<impl Responder, AppError> {
+    let sensor_id = path.into_inner();
+    let mut reading = reading.into_inner();
+    debug!(
+        sensor_id,
+        value = reading.value,
+        "POST /sensors/{{id}}/readings called"
+    );
+    reading.recorded_at = reading
+        .recorded_at
+        .as_deref()
+        .map(|ts| parse_timestamp("recorded_at", ts))
+        .transpose()?;
+
+    let stored = db
+        .write(move |tx| {
+            DbPool::record_sensor_reading(
+                tx,
+                sensor_id,
+                &reading,
+                sensor_anomaly_window(),
+                sensor_anomaly_sigma(),
+            )
+        })
+        .await?;
+
+    Ok(HttpResponse::Created().json(stored))
+}
+
+/// Handler listing a sensor's readings over a time range, raw or downsampled.
+///
+/// # HTTP Method
+/// - `GET /sensors/{id}/readings[?from=..&to=..&bucket=1h|1d]`
+/// - `GET /sensors/{id}/readings[?from=..&to=..&limit=..&offset=..]`
+///
+/// # Success
+/// - Without `bucket`, returns HTTP 200 with one page of raw readings, oldest first:
+///   `limit` (default and maximum `MAX_RAW_READINGS`) readings after skipping `offset`.
+/// - With `bucket`, returns HTTP 200 with `[{ bucket_start, count, avg, min, max }]` per
+///   hour or day that has readings, oldest first.
+///
+/// # Errors
+/// - Returns HTTP 400 for a malformed `from`/`to` or an unknown `bucket`, or if the range
+///   holds more than `MAX_READING_BUCKETS` buckets.
+/// - Returns HTTP 404 if the sensor does not exist.
+pub async fn get_sensor_readings(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    query: web::Query<SensorReadingsQuery>,
+) -> Result<impl Responder, AppError> {
+    let sensor_id = path.into_inner();
+    debug!(sensor_id, ?query, "GET /sensors/{{id}}/readings called");
+    let from = query
+        .from
+        .as_deref()
+        .map(|ts| parse_timestamp("from", ts))
+        .transpose()?;
+    let to = query
+        .to
+        .as_deref()
+        .map(|ts| parse_timestamp("to", ts))
+        .transpose()?;
+
+    let conn = db.get_conn()?;
+    match query.bucket {
+        Some(bucket) => {
+            // One past the cap tells a full range apart from one that was cut short.
+            let buckets = DbPool::sensor_reading_buckets(
+                &conn,
+                sensor_id,
+                from.as_deref(),
+                to.as_deref(),
+                bucket,
+                MAX_READING_BUCKETS + 1,
+            )?;
+            if buckets.len() > MAX_READING_BUCKETS as usize {
+                return Err(AppError::InvalidInput(format!(
+                    "The range holds more than {} buckets; narrow from/to or use a wider bucket",
+                    MAX_READING_BUCKETS
+                )));
+            }
+            info!(
+                sensor_id,
+                count = buckets.len(),
+                "Returning sensor reading buckets"
+            );
+            Ok(HttpResponse::Ok().json(buckets))
+        }
+        None => {
+            let limit = query
+                .limit
+                .unwrap_or(MAX_RAW_READINGS)
+                .clamp(1, MAX_RAW_READINGS);
+            let readings = DbPool::sensor_readings(
+                &conn,
+                sensor_id,
+                from.as_deref(),
+                to.as_deref(),
+                limit,
+                query.offset.unwrap_or(0),
+            )?;
+            info!(
+                sensor_id,
+                count = readings.len(),
+                "Returning sensor readings"
+            );
+            Ok(HttpResponse::Ok().json(readings))
+        }
+    }
+}
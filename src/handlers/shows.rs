@@ -0,0 +1,111 @@
+//! Handlers for exhibition and show results.
+
+use crate::db::{DbPool, ensure_goat_exists};
+use crate::errors::AppError;
+use crate::models::{ShowEntry, ShowsQuery};
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use tracing::{debug, info};
+
+/// Handler recording a show result for a goat.
+///
+/// # HTTP Method
+/// - `POST /goats/{id}/shows`
+///
+/// # Request
+/// - JSON `ShowEntry`; `goat_id` is taken from the path.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored entry, including its new `id`.
+///
+/// # Errors
+/// - Returns HTTP 422 listing every invalid field (e.g. `placement` below 1).
+/// - Returns HTTP 404 if the goat does not exist.
+///
+/// # Logs
+/// - Info: Receipt of the entry and the id it was stored under.
+pub async fn add_show_entry(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    entry: web::Json<ShowEntry>,
+) -> Result<impl Responder, AppError> {
+    let mut entry = entry.into_inner();
+    entry.goat_id = path.into_inner();
+    info!(goat_id = entry.goat_id, show = %entry.show_name, "POST /goats/{{id}}/shows called");
+    entry.validate()?;
+
+    let stored = entry.clone();
+    entry.id = Some(
+        db.write(move |tx| DbPool::insert_show_entry(tx, &stored))
+            .await?,
+    );
+
+    info!(entry_id = ?entry.id, "Show entry added");
+    Ok(HttpResponse::Created().json(entry))
+}
+
+/// Handler listing one goat's show results, oldest first.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/shows`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `ShowEntry`.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn get_goat_show_entries(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, "GET /goats/{{id}}/shows called");
+
+    let conn = db.get_conn()?;
+    ensure_goat_exists(&conn, goat_id)?;
+    let entries = DbPool::show_entries(&conn, Some(goat_id), None)?;
+
+    info!(
+        goat_id,
+        count = entries.len(),
+        "Returning goat show entries"
+    );
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+/// Handler listing show results across the herd, sorted by date.
+///
+/// # HTTP Method
+/// - `GET /shows[?year=YYYY]`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `ShowEntry`, restricted to `year` if given.
+pub async fn get_show_entries(
+    db: web::Data<DbPool>,
+    query: web::Query<ShowsQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(year = ?query.year, "GET /shows called");
+
+    let conn = db.get_conn()?;
+    let entries = DbPool::show_entries(&conn, None, query.year)?;
+
+    info!(count = entries.len(), "Returning show entries");
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+/// Handler listing goats that have placed first in at least one show.
+///
+/// # HTTP Method
+/// - `GET /goats/show-champions`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of goats (with ids and relations).
+pub async fn get_show_champions(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /goats/show-champions called");
+
+    let conn = db.get_conn()?;
+    let champions = DbPool::show_champions(&conn)?;
+
+    info!(count = champions.len(), "Returning show champions");
+    Ok(HttpResponse::Ok().json(champions))
+}
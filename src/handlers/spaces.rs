@@ -0,0 +1,337 @@
+//! Handlers for spaces (enclosures, grazing fields), the goats kept in them, the
+//! sensors installed there and their layout on the farm map.
+
+use crate::db::DbPool;
+use crate::db_helpers::{SQLITE_TIMESTAMP_FORMAT, parse_timestamp};
+use crate::domain::visualization::{MapPosition, generate_farm_svg};
+use crate::errors::AppError;
+use crate::models::{BulkTransferPayload, ResidentsQuery, SensorAssignment, SpaceAssignment};
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use chrono::Utc;
+use serde_json::json;
+use tracing::{debug, info};
+
+/// Handler moving a goat into a space.
+///
+/// # HTTP Method
+/// - `POST /spaces/{id}/goats`
+///
+/// # Request
+/// - JSON payload `{ "goat_id": i64 }`.
+///
+/// # Success
+/// - Returns HTTP 200 with `{ "message": "Goat assigned" }` once the goat is assigned;
+///   any previous assignment is replaced.
+///   The move closes the goat's previous stay in its space history and opens a new one,
+///   in the same transaction. Assigning a goat to the space it is already in changes
+///   nothing.
+///
+/// # Errors
+/// - Returns HTTP 404 if the space or goat does not exist.
+///
+/// # Logs
+/// - Info: Receipt of the assignment and its completion.
+pub async fn assign_goat(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    payload: web::Json<SpaceAssignment>,
+) -> Result<impl Responder, AppError> {
+    let space_id = path.into_inner();
+    info!(
+        space_id,
+        goat_id = payload.goat_id,
+        "POST /spaces/{{id}}/goats called"
+    );
+
+    let goat_id = payload.goat_id;
+    db.write(move |tx| DbPool::assign_goat_to_space(tx, goat_id, space_id))
+        .await?;
+
+    info!(space_id, goat_id, "Goat assigned to space");
+    Ok(HttpResponse::Ok().json(json!({ "message": "Goat assigned" })))
+}
+
+/// Handler listing the goats currently assigned to a space.
+///
+/// # HTTP Method
+/// - `GET /spaces/{id}/goats`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ space, occupancy, capacity, goats }`, where `goats` holds
+///   full goats with ids and relations.
+///
+/// # Errors
+/// - Returns HTTP 404 for an unknown space id.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Number of goats returned.
+pub async fn get_space_goats(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let space_id = path.into_inner();
+    debug!(space_id, "GET /spaces/{{id}}/goats called");
+
+    let conn = db.get_conn()?;
+    let listing = DbPool::goats_in_space(&conn, space_id)?;
+
+    info!(
+        space_id,
+        occupancy = listing.occupancy,
+        "Returning goats in space"
+    );
+    Ok(HttpResponse::Ok().json(listing))
+}
+
+/// Handler listing the goats that were in a space at a given moment, for tracing who
+/// shared a pen with a sick goat.
+///
+/// # HTTP Method
+/// - `GET /spaces/{id}/residents`
+///
+/// # Request
+/// - Optional query `at`: a timestamp (`YYYY-MM-DD HH:MM:SS` UTC or RFC 3339) or a bare
+///   date, read as midnight UTC. Defaults to now.
+///
+/// # Success
+/// - Returns HTTP 200 with `[{ goat_id, name, moved_in_at, moved_out_at }]`, one entry
+///   per goat whose stay in the space covers `at`, ordered by goat id. Goats deleted
+///   since are included.
+///
+/// # Errors
+/// - Returns HTTP 400 for an unparseable `at`.
+/// - Returns HTTP 404 for an unknown space id.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Number of residents returned.
+pub async fn get_space_residents(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    query: web::Query<ResidentsQuery>,
+) -> Result<impl Responder, AppError> {
+    let space_id = path.into_inner();
+    debug!(space_id, at = ?query.at, "GET /spaces/{{id}}/residents called");
+
+    let at = match &query.at {
+        Some(at) => parse_timestamp("at", at)?,
+        None => Utc::now().format(SQLITE_TIMESTAMP_FORMAT).to_string(),
+    };
+    let conn = db.get_conn()?;
+    let residents = DbPool::space_residents_at(&conn, space_id, &at)?;
+
+    info!(
+        space_id,
+        at = %at,
+        count = residents.len(),
+        "Returning space residents"
+    );
+    Ok(HttpResponse::Ok().json(residents))
+}
+
+/// Handler listing the spaces a goat has been kept in.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/movements`
+///
+/// # Success
+/// - Returns HTTP 200 with `[{ space_id, space_name, moved_in_at, moved_out_at }]`,
+///   oldest first. The current stay has a null `moved_out_at`.
+///
+/// # Errors
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn get_goat_movements(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, "GET /goats/{{id}}/movements called");
+
+    let conn = db.get_conn()?;
+    let movements = DbPool::goat_space_movements(&conn, goat_id)?;
+    debug!(goat_id, count = movements.len(), "Returning goat movements");
+    Ok(HttpResponse::Ok().json(movements))
+}
+
+/// Handler installing a sensor in a space.
+///
+/// # HTTP Method
+/// - `POST /spaces/{id}/sensors`
+///
+/// # Request
+/// - JSON payload `{ "sensor_id": i64 }`.
+///
+/// # Success
+/// - Returns HTTP 200 with `{ "message": "Sensor assigned" }` once the sensor is
+///   assigned; any previous space is replaced.
+///
+/// # Errors
+/// - Returns HTTP 404 if the space or sensor does not exist.
+pub async fn assign_sensor(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    payload: web::Json<SensorAssignment>,
+) -> Result<impl Responder, AppError> {
+    let space_id = path.into_inner();
+    info!(
+        space_id,
+        sensor_id = payload.sensor_id,
+        "POST /spaces/{{id}}/sensors called"
+    );
+
+    db.write(move |tx| DbPool::assign_sensor_to_space(tx, payload.sensor_id, space_id))
+        .await?;
+    Ok(HttpResponse::Ok().json(json!({ "message": "Sensor assigned" })))
+}
+
+/// Handler summarising a space's environment from its sensors.
+///
+/// # HTTP Method
+/// - `GET /spaces/{id}/environment`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ space, readings }`, where `readings` holds the latest
+///   `{ sensor_type, sensor_id, reading, reading_time, status }` per sensor type.
+///
+/// # Errors
+/// - Returns HTTP 404 for an unknown space id.
+pub async fn get_space_environment(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let space_id = path.into_inner();
+    debug!(space_id, "GET /spaces/{{id}}/environment called");
+
+    let conn = db.get_conn()?;
+    let environment = DbPool::space_environment(&conn, space_id)?;
+
+    info!(
+        space_id,
+        readings = environment.readings.len(),
+        "Returning space environment"
+    );
+    Ok(HttpResponse::Ok().json(environment))
+}
+
+/// Handler moving several goats from one space to another at once.
+///
+/// # HTTP Method
+/// - `POST /spaces/bulk-transfer`
+///
+/// # Request
+/// - JSON payload `{ "from_space_id": i64, "to_space_id": i64, "goat_ids": [i64] }`.
+///
+/// # Success
+/// - Returns HTTP 200 with `{ moved, to_space, remaining_capacity }`;
+///   `remaining_capacity` is null for a space without a capacity limit.
+///
+/// # Errors
+/// - Returns HTTP 422 for an empty `goat_ids`, identical spaces, or goats that are not
+///   in the source space (naming them). Nothing is moved.
+/// - Returns HTTP 409 if the destination cannot hold every goat. Nothing is moved.
+/// - Returns HTTP 404 if either space does not exist.
+pub async fn bulk_transfer(
+    db: web::Data<DbPool>,
+    payload: web::Json<BulkTransferPayload>,
+) -> Result<impl Responder, AppError> {
+    info!(
+        from_space_id = payload.from_space_id,
+        to_space_id = payload.to_space_id,
+        goats = payload.goat_ids.len(),
+        "POST /spaces/bulk-transfer called"
+    );
+    payload.validate()?;
+
+    let summary = db
+        .write(move |tx| {
+            DbPool::bulk_transfer_goats(
+                tx,
+                payload.from_space_id,
+                payload.to_space_id,
+                &payload.goat_ids,
+            )
+        })
+        .await?;
+
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+/// Handler listing every space with its farm map position and occupancy.
+///
+/// # HTTP Method
+/// - `GET /farm/map`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `{ space_id, name, capacity, occupancy,
+///   occupancy_pct, position }`, where `position` is `{ x, y, width, height }` or null
+///   for spaces not yet placed, and `occupancy_pct` is null without a capacity.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Number of spaces returned.
+pub async fn get_farm_map(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /farm/map called");
+    let conn = db.get_conn()?;
+    let layouts = DbPool::farm_map(&conn, None)?;
+
+    info!(count = layouts.len(), "Returning farm map");
+    Ok(HttpResponse::Ok().json(layouts))
+}
+
+/// Handler placing a space on the farm map or moving it.
+///
+/// # HTTP Method
+/// - `PUT /farm/map/{space_id}`
+///
+/// # Request
+/// - JSON payload `{ x, y, width, height }` in grid units; `x` and `y` must not be
+///   negative and `width` and `height` must be positive.
+///
+/// # Success
+/// - Returns HTTP 200 with the space's updated layout, as in `GET /farm/map`.
+///
+/// # Errors
+/// - Returns HTTP 404 for an unknown space id.
+/// - Returns HTTP 422 listing invalid coordinates.
+///
+/// # Logs
+/// - Info: Receipt of the request and the new position.
+pub async fn update_space_position(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    payload: web::Json<MapPosition>,
+) -> Result<impl Responder, AppError> {
+    let space_id = path.into_inner();
+    info!(space_id, "PUT /farm/map/{{space_id}} called");
+    payload.validate()?;
+
+    let layout = db
+        .write(move |tx| DbPool::set_space_position(tx, space_id, &payload))
+        .await?;
+
+    info!(space_id, position = ?layout.position, "Space position updated");
+    Ok(HttpResponse::Ok().json(layout))
+}
+
+/// Handler drawing the farm map as SVG.
+///
+/// # HTTP Method
+/// - `GET /farm/map/svg`
+///
+/// # Success
+/// - Returns HTTP 200 with an `image/svg+xml` document holding one labelled rectangle
+///   per placed space, green below 70% occupancy, yellow up to 90% and red above.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+pub async fn get_farm_map_svg(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /farm/map/svg called");
+    let conn = db.get_conn()?;
+    let layouts = DbPool::farm_map(&conn, None)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("image/svg+xml")
+        .body(generate_farm_svg(&layouts)))
+}
@@ -0,0 +1,243 @@
+//! Statistics endpoints summarising herd data for dashboards.
+//!
+//! Aggregation is pushed down into SQL wherever possible; handlers only validate the
+//! query parameters and serialize the results.
+
+use crate::db::DbPool;
+use crate::db_helpers::{breed_to_str, parse_iso_date, str_to_breed};
+use crate::domain::simulation::{ProjectionYear, simulate_population};
+use crate::envelope::{MetaQuery, list_response};
+use crate::errors::AppError;
+use crate::models::{CoverageQuery, DiseaseTrendQuery, SimulationQuery, WeightGainQuery};
+use actix_web::{HttpResponse, Responder, web};
+use chrono::{Datelike, Months, Utc};
+use tracing::{debug, info, warn};
+
+/// Longest date range accepted by the trend endpoints.
+pub const MAX_TREND_RANGE_MONTHS: u32 = 24;
+/// Most years `POST /simulate/population` projects.
+pub const MAX_SIMULATION_YEARS: u32 = 20;
+/// Most simulated herds `POST /simulate/population` averages over.
+pub const MAX_SIMULATION_ITERATIONS: u32 = 1000;
+
+/// Handler for the disease incidence time series.
+///
+/// # HTTP Method
+/// - `GET /stats/diseases/trends?from=YYYY-MM-DD&to=YYYY-MM-DD&interval=week|month`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `{ interval, disease, count }` points, enveloped
+///   with metadata when `?meta=true`.
+///
+/// # Errors
+/// - Returns HTTP 400 for malformed dates, `from` after `to`, or a range over two years.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Warn: Rejected date ranges.
+/// - Info: Number of points returned.
+pub async fn get_disease_trends(
+    db: web::Data<DbPool>,
+    query: web::Query<DiseaseTrendQuery>,
+    meta: web::Query<MetaQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(from = %query.from, to = %query.to, interval = ?query.interval, "GET /stats/diseases/trends called");
+    let from = parse_iso_date("from", &query.from)?;
+    let to = parse_iso_date("to", &query.to)?;
+
+    if from > to {
+        warn!(%from, %to, "Trend range is inverted");
+        return Err(AppError::InvalidInput(
+            "from must not be after to".to_string(),
+        ));
+    }
+    if from
+        .checked_add_months(Months::new(MAX_TREND_RANGE_MONTHS))
+        .is_some_and(|limit| to > limit)
+    {
+        warn!(%from, %to, "Trend range exceeds two years");
+        return Err(AppError::InvalidInput(
+            "Date range must not exceed 2 years".to_string(),
+        ));
+    }
+
+    let conn = db.get_conn()?;
+    let points = DbPool::disease_trends(&conn, from, to, query.interval)?;
+
+    info!(count = points.len(), "Returning disease trend points");
+    list_response(&conn, points, meta.into_inner())
+}
+
+/// Handler for the average daily weight gain of a breed.
+///
+/// # HTTP Method
+/// - `GET /stats/weight-gain?breed=<Breed>&from=YYYY-MM-DD&to=YYYY-MM-DD`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ breed, avg_daily_gain_g, sample_size, from, to }`.
+///   Goats with fewer than two measurements in the range are not sampled.
+///
+/// # Errors
+/// - Returns HTTP 400 for malformed dates or `from` after `to`.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Sample size of the result.
+pub async fn get_breed_weight_gain(
+    db: web::Data<DbPool>,
+    query: web::Query<WeightGainQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(breed = %query.breed, from = %query.from, to = %query.to, "GET /stats/weight-gain called");
+    let from = parse_iso_date("from", &query.from)?;
+    let to = parse_iso_date("to", &query.to)?;
+    if from > to {
+        warn!(%from, %to, "Weight gain range is inverted");
+        return Err(AppError::InvalidInput(
+            "from must not be after to".to_string(),
+        ));
+    }
+    let breed = str_to_breed(query.breed.trim())?;
+
+    let conn = db.get_conn()?;
+    let gain = DbPool::breed_weight_gain(&conn, &breed, from, to)?;
+
+    info!(breed = %gain.breed, sample_size = gain.sample_size, "Returning breed weight gain");
+    Ok(HttpResponse::Ok().json(gain))
+}
+
+/// Handler for per-vaccine coverage of the living herd.
+///
+/// # HTTP Method
+/// - `GET /stats/vaccination-coverage[?breed=Beetal]`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of
+///   `{ vaccine, vaccinated_count, total_goats, coverage_pct }`, enveloped with metadata
+///   when `?meta=true`.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Number of vaccines reported.
+pub async fn get_vaccination_coverage(
+    db: web::Data<DbPool>,
+    query: web::Query<CoverageQuery>,
+    meta: web::Query<MetaQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(breed = ?query.breed, "GET /stats/vaccination-coverage called");
+    let breed = query
+        .breed
+        .as_deref()
+        .map(|b| str_to_breed(b.trim()))
+        .transpose()?;
+
+    let conn = db.get_conn()?;
+    let coverage = DbPool::vaccination_coverage(&conn, breed.as_ref().map(breed_to_str))?;
+
+    info!(count = coverage.len(), "Returning vaccination coverage");
+    list_response(&conn, coverage, meta.into_inner())
+}
+
+/// Handler for herd-wide financial totals.
+///
+/// # HTTP Method
+/// - `GET /stats/financial`
+///
+/// # Success
+/// - Returns HTTP 200 with `FinancialStats`: purchase cost and current value of the live
+///   herd, sales revenue, vet costs, total show prize money, and the current depreciated
+///   value of equipment.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+pub async fn get_financial_stats(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /stats/financial called");
+    let conn = db.get_conn()?;
+    let stats = DbPool::financial_stats(&conn, Utc::now().date_naive())?;
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+/// Handler ranking the living herd by what each goat has earned minus what it has cost.
+///
+/// # HTTP Method
+/// - `GET /stats/profitability`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `ProfitAnalysis`, one per goat that is neither
+///   deleted nor deceased, highest `net_position` first, enveloped with metadata when
+///   `?meta=true`.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Number of goats ranked.
+pub async fn get_profitability(
+    db: web::Data<DbPool>,
+    meta: web::Query<MetaQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!("GET /stats/profitability called");
+    let conn = db.get_conn()?;
+    let ranking = DbPool::herd_profitability(&conn)?;
+
+    info!(count = ranking.len(), "Returning herd profitability");
+    list_response(&conn, ranking, meta.into_inner())
+}
+
+/// Handler projecting herd size with a Monte Carlo simulation.
+///
+/// # HTTP Method
+/// - `POST /simulate/population?years=5&iterations=1000`
+///
+/// # Request
+/// - `years` and `iterations` are capped at 20 and 1000, and raised to at least 1.
+///   Births, deaths and sales per head are measured over the past year.
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `{ year, projected_herd_size,
+///   confidence_lower, confidence_upper }`, one per calendar year after this one. The
+///   bounds are the 95% interval across the simulated herds.
+///
+/// # Logs
+/// - Debug: Entry point of request.
+/// - Info: Rates the simulation was seeded with.
+pub async fn simulate_population_growth(
+    db: web::Data<DbPool>,
+    query: web::Query<SimulationQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(
+        years = query.years,
+        iterations = query.iterations,
+        "POST /simulate/population called"
+    );
+    let years = query.years.clamp(1, MAX_SIMULATION_YEARS);
+    let iterations = query.iterations.clamp(1, MAX_SIMULATION_ITERATIONS);
+
+    let today = Utc::now().date_naive();
+    let rates = {
+        let conn = db.get_conn()?;
+        DbPool::population_rates(&conn, today)?
+    };
+    info!(
+        initial_herd = rates.initial_herd,
+        breeding_rate = rates.breeding_rate,
+        mortality_rate = rates.mortality_rate,
+        sales_rate = rates.sales_rate,
+        years,
+        iterations,
+        "Simulating herd population"
+    );
+
+    let projection: Vec<ProjectionYear> = simulate_population(
+        rates.initial_herd,
+        rates.breeding_rate,
+        rates.mortality_rate,
+        rates.sales_rate,
+        years,
+        iterations,
+    )
+    .into_iter()
+    .map(|year| ProjectionYear {
+        year: today.year() + year.year,
+        ..year
+    })
+    .collect();
+    Ok(HttpResponse::Ok().json(projection))
+}
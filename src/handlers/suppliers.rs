@@ -0,0 +1,145 @@
+//! Handlers for suppliers and what we have bought from them.
+
+use crate::db::DbPool;
+use crate::errors::AppError;
+use crate::models::Supplier;
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use serde_json::json;
+use tracing::{debug, info};
+
+/// Handler adding a supplier.
+///
+/// # HTTP Method
+/// - `POST /suppliers`
+///
+/// # Request
+/// - JSON `{ "name": string, "contact"?: string, "notes"?: string }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored supplier, including its new `id`.
+///
+/// # Errors
+/// - Returns HTTP 422 for an empty name.
+pub async fn create_supplier(
+    db: web::Data<DbPool>,
+    supplier: web::Json<Supplier>,
+) -> Result<impl Responder, AppError> {
+    info!(name = %supplier.name, "POST /suppliers called");
+    supplier.validate()?;
+
+    let created = db
+        .write(move |tx| DbPool::create_supplier(tx, &supplier))
+        .await?;
+    Ok(HttpResponse::Created().json(created))
+}
+
+/// Handler listing all suppliers by name.
+///
+/// # HTTP Method
+/// - `GET /suppliers`
+pub async fn list_suppliers(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /suppliers called");
+    let conn = db.get_conn()?;
+    let suppliers = DbPool::list_suppliers(&conn)?;
+
+    info!(count = suppliers.len(), "Returning suppliers");
+    Ok(HttpResponse::Ok().json(suppliers))
+}
+
+/// Handler returning one supplier.
+///
+/// # HTTP Method
+/// - `GET /suppliers/{id}`
+///
+/// # Errors
+/// - Returns HTTP 404 if the supplier does not exist.
+pub async fn get_supplier(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let supplier_id = path.into_inner();
+    debug!(supplier_id, "GET /suppliers/{{id}} called");
+
+    let conn = db.get_conn()?;
+    let supplier = DbPool::get_supplier(&conn, supplier_id)?;
+    Ok(HttpResponse::Ok().json(supplier))
+}
+
+/// Handler replacing a supplier's name, contact and notes.
+///
+/// # HTTP Method
+/// - `PUT /suppliers/{id}`
+///
+/// # Success
+/// - Returns HTTP 200 with the stored supplier.
+///
+/// # Errors
+/// - Returns HTTP 422 for an empty name.
+/// - Returns HTTP 404 if the supplier does not exist.
+pub async fn update_supplier(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    supplier: web::Json<Supplier>,
+) -> Result<impl Responder, AppError> {
+    let supplier_id = path.into_inner();
+    info!(supplier_id, name = %supplier.name, "PUT /suppliers/{{id}} called");
+    supplier.validate()?;
+
+    let updated = db
+        .write(move |tx| DbPool::update_supplier(tx, supplier_id, &supplier))
+        .await?;
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Handler deleting a supplier. Equipment and restocks bought from it are kept but
+/// no longer linked to any supplier.
+///
+/// # HTTP Method
+/// - `DELETE /suppliers/{id}`
+///
+/// # Errors
+/// - Returns HTTP 404 if the supplier does not exist.
+pub async fn delete_supplier(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let supplier_id = path.into_inner();
+    info!(supplier_id, "DELETE /suppliers/{{id}} called");
+
+    db.write(move |tx| DbPool::delete_supplier(tx, supplier_id))
+        .await?;
+
+    Ok(HttpResponse::Ok().json(json!({ "message": "Supplier deleted" })))
+}
+
+/// Handler listing what we have bought from a supplier.
+///
+/// # HTTP Method
+/// - `GET /suppliers/{id}/purchases`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ supplier_id, purchase_count, total_spent, purchases }`.
+///   Purchases are equipment and medicine restocks linked to the supplier, newest first;
+///   ones without a recorded price have a null `amount` and are left out of `total_spent`.
+///
+/// # Errors
+/// - Returns HTTP 404 if the supplier does not exist.
+pub async fn get_supplier_purchases(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let supplier_id = path.into_inner();
+    debug!(supplier_id, "GET /suppliers/{{id}}/purchases called");
+
+    let conn = db.get_conn()?;
+    let purchases = DbPool::supplier_purchases(&conn, supplier_id)?;
+
+    info!(
+        supplier_id,
+        count = purchases.purchase_count,
+        total_spent = purchases.total_spent,
+        "Returning supplier purchases"
+    );
+    Ok(HttpResponse::Ok().json(purchases))
+}
@@ -0,0 +1,186 @@
+//! Handlers for the farm-wide task list. Tasks are the same records as a worker's
+//! tasks under `/workers/{id}/tasks`, addressed by task id alone.
+
+use crate::db::DbPool;
+use crate::db_helpers::parse_timestamp;
+use crate::errors::AppError;
+use crate::models::{TasksQuery, WorkerTask};
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use serde_json::json;
+use tracing::{debug, info};
+
+/// Validates a task body and normalises its `due_at`. Assignment and completion times
+/// are set by the server, so any sent by the client are dropped.
+fn prepare_task(task: web::Json<WorkerTask>) -> Result<WorkerTask, AppError> {
+    let mut task = task.into_inner();
+    task.validate()?;
+    task.due_at = task
+        .due_at
+        .as_deref()
+        .map(|ts| parse_timestamp("due_at", ts))
+        .transpose()?;
+    task.assigned_at = None;
+    task.completed_at = None;
+    Ok(task)
+}
+
+/// Handler creating a task assigned to a worker.
+///
+/// # HTTP Method
+/// - `POST /tasks`
+///
+/// # Request
+/// - JSON `{ "title": string, "assignee_worker_id": i64, "description"?: string,
+///   "due_at"?: timestamp, "priority"?: "Low"|"Medium"|"High" }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored task.
+///
+/// # Errors
+/// - Returns HTTP 422 for an empty title, unknown priority, or malformed `due_at`.
+/// - Returns HTTP 404 if the assignee does not exist.
+pub async fn create_task(
+    db: web::Data<DbPool>,
+    task: web::Json<WorkerTask>,
+) -> Result<impl Responder, AppError> {
+    info!(worker_id = task.worker_id, task = %task.task, "POST /tasks called");
+    let task = prepare_task(task)?;
+
+    let stored = db
+        .write(move |tx| DbPool::insert_worker_task(tx, &task))
+        .await?;
+    Ok(HttpResponse::Created().json(stored))
+}
+
+/// Handler listing all tasks.
+///
+/// # HTTP Method
+/// - `GET /tasks?status=open`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `WorkerTask`, soonest due first. `status`
+///   selects `open` or `done` tasks; all are returned without it.
+///
+/// # Errors
+/// - Returns HTTP 400 for any other `status`.
+pub async fn list_tasks(
+    db: web::Data<DbPool>,
+    query: web::Query<TasksQuery>,
+) -> Result<impl Responder, AppError> {
+    debug!(status = ?query.status, "GET /tasks called");
+    let completed = match query.status.as_deref() {
+        None => None,
+        Some("open") => Some(false),
+        Some("done") => Some(true),
+        Some(other) => {
+            return Err(AppError::InvalidInput(format!(
+                "status must be open or done, got {}",
+                other
+            )));
+        }
+    };
+
+    let conn = db.get_conn()?;
+    let tasks = DbPool::list_tasks(&conn, completed)?;
+
+    info!(count = tasks.len(), "Returning tasks");
+    Ok(HttpResponse::Ok().json(tasks))
+}
+
+/// Handler returning one task.
+///
+/// # HTTP Method
+/// - `GET /tasks/{id}`
+///
+/// # Errors
+/// - Returns HTTP 404 if the task does not exist.
+pub async fn get_task(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let task_id = path.into_inner();
+    debug!(task_id, "GET /tasks/{{id}} called");
+
+    let conn = db.get_conn()?;
+    let task = DbPool::get_task(&conn, task_id)?;
+    Ok(HttpResponse::Ok().json(task))
+}
+
+/// Handler editing or reassigning a task.
+///
+/// # HTTP Method
+/// - `PUT /tasks/{id}`
+///
+/// # Request
+/// - The same body as `POST /tasks`; the task's assignment and completion times are kept.
+///
+/// # Success
+/// - Returns HTTP 200 with the stored task.
+///
+/// # Errors
+/// - Returns HTTP 422 for an empty title, unknown priority, or malformed `due_at`.
+/// - Returns HTTP 404 if the task or the assignee does not exist.
+pub async fn update_task(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    task: web::Json<WorkerTask>,
+) -> Result<impl Responder, AppError> {
+    let task_id = path.into_inner();
+    info!(
+        task_id,
+        worker_id = task.worker_id,
+        "PUT /tasks/{{id}} called"
+    );
+    let task = prepare_task(task)?;
+
+    let updated = db
+        .write(move |tx| DbPool::update_task(tx, task_id, &task))
+        .await?;
+    Ok(HttpResponse::Ok().json(updated))
+}
+
+/// Handler deleting a task.
+///
+/// # HTTP Method
+/// - `DELETE /tasks/{id}`
+///
+/// # Errors
+/// - Returns HTTP 404 if the task does not exist.
+pub async fn delete_task(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let task_id = path.into_inner();
+    info!(task_id, "DELETE /tasks/{{id}} called");
+
+    db.write(move |tx| DbPool::delete_task(tx, task_id)).await?;
+    Ok(HttpResponse::Ok().json(json!({ "message": "Task deleted" })))
+}
+
+/// Handler marking a task done now.
+///
+/// # HTTP Method
+/// - `POST /tasks/{id}/complete`
+///
+/// # Success
+/// - Returns HTTP 200 with the updated task.
+///
+/// # Errors
+/// - Returns HTTP 404 if the task does not exist.
+/// - Returns HTTP 409 if the task is already done.
+pub async fn complete_task(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let task_id = path.into_inner();
+    info!(task_id, "POST /tasks/{{id}}/complete called");
+
+    let task = db
+        .write(move |tx| {
+            let task = DbPool::get_task(tx, task_id)?;
+            DbPool::complete_worker_task(tx, task.worker_id, task_id)
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(task))
+}
@@ -0,0 +1,54 @@
+//! Handlers for stateless farm calculators.
+
+use crate::domain::nutrition::{ProductionStage, formulate_feed, requirement};
+use crate::errors::AppError;
+use crate::models::FeedFormulationRequest;
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use tracing::{debug, info};
+
+/// Handler formulating a least-cost daily ration from the feeds on hand.
+///
+/// # HTTP Method
+/// - `POST /tools/feed-formulation`
+///
+/// # Request
+/// - JSON `{ "goat_weight_kg": number, "age_months": number, "production_stage": string,
+///   "available_feeds": [{ "name", "crude_protein_pct", "energy_mj_per_kg",
+///   "cost_per_kg", "max_inclusion_pct" }] }`, with feed values on a dry-matter basis.
+///   `production_stage` is `maintenance`, `growing`, `gestation` or `lactation`.
+///
+/// # Success
+/// - Returns HTTP 200 with `{ formula: [{ feed, inclusion_pct, kg_per_day }],
+///   total_cost_per_day, protein_pct, energy_mj_per_kg, meets_requirements }`. When the
+///   feeds cannot meet the requirement, the closest mix found is returned with
+///   `meets_requirements` false.
+///
+/// # Errors
+/// - Returns HTTP 422 for a non-positive weight, an unknown stage, no feeds, a feed
+///   value out of range, or feed limits adding up to less than 100%.
+pub async fn feed_formulation(
+    payload: web::Json<FeedFormulationRequest>,
+) -> Result<impl Responder, AppError> {
+    let payload = payload.into_inner();
+    debug!(
+        stage = %payload.production_stage,
+        feeds = payload.available_feeds.len(),
+        "POST /tools/feed-formulation called"
+    );
+    payload.validate()?;
+    let stage: ProductionStage = payload
+        .production_stage
+        .parse()
+        .map_err(AppError::InvalidInput)?;
+
+    let needs = requirement(stage, payload.age_months);
+    let formulation = formulate_feed(payload.goat_weight_kg, &needs, &payload.available_feeds);
+
+    info!(
+        cost = formulation.total_cost_per_day,
+        meets_requirements = formulation.meets_requirements,
+        "Returning feed formulation"
+    );
+    Ok(HttpResponse::Ok().json(formulation))
+}
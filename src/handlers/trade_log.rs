@@ -0,0 +1,90 @@
+//! Handlers for the append-only goat trade log and its tamper check.
+
+use crate::db::DbPool;
+use crate::domain::trade_log::first_broken_link;
+use crate::errors::AppError;
+use crate::models::{TradeLogVerification, TradePayload};
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use chrono::{SecondsFormat, Utc};
+use tracing::{debug, info, warn};
+
+/// Handler recording a trade of a goat in the trade log.
+///
+/// # HTTP Method
+/// - `POST /goats/{id}/trade-log`
+///
+/// # Request
+/// - JSON `{ "action": string, "from_party"?: string, "to_party"?: string, "price"?: f64 }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored entry, timestamped now and hash-chained to the
+///   previous entry.
+///
+/// # Errors
+/// - Returns HTTP 422 for an empty action or a negative price.
+/// - Returns HTTP 404 if the goat does not exist.
+pub async fn add_trade(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    trade: web::Json<TradePayload>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    info!(goat_id, action = %trade.action, "POST /goats/{{id}}/trade-log called");
+    trade.validate()?;
+
+    let timestamp = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+    let entry = db
+        .write(move |tx| DbPool::append_trade(tx, goat_id, &trade, &timestamp))
+        .await?;
+
+    Ok(HttpResponse::Created().json(entry))
+}
+
+/// Handler listing a goat's trades.
+///
+/// # HTTP Method
+/// - `GET /goats/{id}/trade-log`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `TradeLogEntry`, oldest first.
+pub async fn get_goat_trade_log(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let goat_id = path.into_inner();
+    debug!(goat_id, "GET /goats/{{id}}/trade-log called");
+
+    let conn = db.get_conn()?;
+    let entries = DbPool::goat_trade_log(&conn, goat_id)?;
+
+    info!(goat_id, count = entries.len(), "Returning goat trade log");
+    Ok(HttpResponse::Ok().json(entries))
+}
+
+/// Handler re-computing every hash in the trade log to detect tampering.
+///
+/// # HTTP Method
+/// - `GET /trade-log/verify`
+///
+/// # Success
+/// - Returns HTTP 200 with `{ "valid": bool, "broken_at": i64 | null }`, where
+///   `broken_at` is the id of the first entry that does not chain to its predecessor.
+///
+/// # Logs
+/// - Warn: The chain is broken.
+pub async fn verify_trade_log(db: web::Data<DbPool>) -> Result<impl Responder, AppError> {
+    debug!("GET /trade-log/verify called");
+    let conn = db.get_conn()?;
+    let entries = DbPool::trade_log(&conn)?;
+
+    let broken_at = first_broken_link(&entries);
+    match broken_at {
+        Some(entry_id) => warn!(entry_id, "Trade log hash chain is broken"),
+        None => info!(count = entries.len(), "Trade log hash chain verified"),
+    }
+    Ok(HttpResponse::Ok().json(TradeLogVerification {
+        valid: broken_at.is_none(),
+        broken_at,
+    }))
+}
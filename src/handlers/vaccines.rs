@@ -0,0 +1,101 @@
+//! Handlers planning vaccinations across the herd.
+
+use crate::db::DbPool;
+use crate::domain::scheduling::optimize_vaccination_schedule;
+use crate::errors::AppError;
+use crate::models::{VaccinationCampaign, VaccinationScheduleQuery};
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use chrono::{Days, Utc};
+use tracing::{debug, info, warn};
+
+/// Longest planning horizon accepted by `GET /vaccines/schedule`, in days.
+const MAX_SCHEDULE_DAYS: u32 = 365;
+
+/// Handler planning vet visits for the vaccinations that are overdue or fall due soon.
+///
+/// # HTTP Method
+/// - `GET /vaccines/schedule[?date_range_days=30]`
+///
+/// # Success
+/// - Returns HTTP 200 with `[{ date, vaccine, goat_count, goat_ids }]`: one slot per
+///   vaccine, grouping every goat that needs it within the range so each vaccine takes a
+///   single visit. Overdue vaccinations are planned for today.
+///
+/// # Errors
+/// - Returns HTTP 400 if `date_range_days` is 0 or above 365.
+pub async fn get_vaccination_schedule(
+    db: web::Data<DbPool>,
+    query: web::Query<VaccinationScheduleQuery>,
+) -> Result<impl Responder, AppError> {
+    let date_range_days = query.date_range_days.unwrap_or(30);
+    debug!(date_range_days, "GET /vaccines/schedule called");
+    if date_range_days == 0 || date_range_days > MAX_SCHEDULE_DAYS {
+        warn!(date_range_days, "Schedule range out of bounds");
+        return Err(AppError::InvalidInput(format!(
+            "date_range_days must be between 1 and {}",
+            MAX_SCHEDULE_DAYS
+        )));
+    }
+
+    let today = Utc::now().date_naive();
+    let conn = db.get_conn()?;
+    // Everything due before the day after the range: overdue doses and upcoming ones.
+    let due = DbPool::overdue_vaccinations(&conn, today + Days::new(u64::from(date_range_days)))?;
+    let slots = optimize_vaccination_schedule(&due, date_range_days, today);
+
+    info!(
+        due = due.len(),
+        slots = slots.len(),
+        "Returning vaccination schedule"
+    );
+    Ok(HttpResponse::Ok().json(slots))
+}
+
+/// Handler vaccinating a group of goats with one vaccine in a single call.
+///
+/// # HTTP Method
+/// - `POST /vaccinations/campaign`
+///
+/// # Request
+/// - `{ vaccine: { id?, name }, goat_ids, administered_on, next_due_on, dose_units? }` with
+///   dates as `YYYY-MM-DD`. An unknown vaccine name is added to the catalog.
+///
+/// # Success
+/// - Returns HTTP 200 with `{ vaccine_id, vaccinated, stock_medicine_id, results }`, where
+///   `results` holds `{ goat_id, success, error }` per listed goat, in request order.
+///   Each vaccinated goat is linked to the vaccine and gets a schedule entry due on
+///   `next_due_on`. If the medicine inventory holds an item named like the vaccine,
+///   `dose_units` (1 by default) are dispensed from it per goat.
+///
+/// # Errors
+/// - Returns HTTP 422 with every invalid field: no goats, bad dates, `next_due_on` not
+///   after `administered_on`, or a non-positive `dose_units`.
+/// - Returns HTTP 404 for an unknown vaccine id.
+/// - Goats that are unknown, listed twice, or out of stock fail individually.
+///
+/// # Logs
+/// - Info: Receipt of the request and the campaign outcome.
+pub async fn run_vaccination_campaign(
+    db: web::Data<DbPool>,
+    payload: web::Json<VaccinationCampaign>,
+) -> Result<impl Responder, AppError> {
+    let campaign = payload.into_inner();
+    info!(
+        goats = campaign.goat_ids.len(),
+        "POST /vaccinations/campaign called"
+    );
+    campaign.validate()?;
+
+    let requested = campaign.goat_ids.len();
+    let summary = db
+        .write(move |tx| DbPool::run_vaccination_campaign(tx, &campaign))
+        .await?;
+    info!(
+        vaccine_id = summary.vaccine_id,
+        vaccinated = summary.vaccinated,
+        requested,
+        "Vaccination campaign completed"
+    );
+    Ok(HttpResponse::Ok().json(summary))
+}
@@ -0,0 +1,129 @@
+//! Handlers for worker task assignment and performance metrics.
+
+use crate::db::{DbPool, ensure_worker_exists};
+use crate::db_helpers::parse_timestamp;
+use crate::errors::AppError;
+use crate::models::{WorkerTask, WorkerTasksQuery};
+use crate::validation::Validate;
+use actix_web::{HttpResponse, Responder, web};
+use tracing::{debug, info};
+
+/// Handler assigning a task to a worker.
+///
+/// # HTTP Method
+/// - `POST /workers/{id}/tasks`
+///
+/// # Request
+/// - JSON `{ "task": string, "priority"?: "Low"|"Medium"|"High", "due_at"?: timestamp }`.
+///
+/// # Success
+/// - Returns HTTP 201 with the stored task; `priority` defaults to Medium.
+///
+/// # Errors
+/// - Returns HTTP 422 for an empty task, unknown priority, or malformed `due_at`.
+/// - Returns HTTP 404 if the worker does not exist.
+pub async fn add_worker_task(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    task: web::Json<WorkerTask>,
+) -> Result<impl Responder, AppError> {
+    let mut task = task.into_inner();
+    task.worker_id = path.into_inner();
+    info!(worker_id = task.worker_id, task = %task.task, "POST /workers/{{id}}/tasks called");
+    task.validate()?;
+    task.due_at = task
+        .due_at
+        .as_deref()
+        .map(|ts| parse_timestamp("due_at", ts))
+        .transpose()?;
+    // Assignment and completion times are set by the server.
+    task.assigned_at = None;
+
+    let stored = db
+        .write(move |tx| DbPool::insert_worker_task(tx, &task))
+        .await?;
+    Ok(HttpResponse::Created().json(stored))
+}
+
+/// Handler listing a worker's tasks.
+///
+/// # HTTP Method
+/// - `GET /workers/{id}/tasks?completed=false`
+///
+/// # Success
+/// - Returns HTTP 200 with a JSON array of `WorkerTask`, soonest due first. `completed`
+///   selects open (`false`) or finished (`true`) tasks; all are returned without it.
+///
+/// # Errors
+/// - Returns HTTP 404 if the worker does not exist.
+pub async fn get_worker_tasks(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+    query: web::Query<WorkerTasksQuery>,
+) -> Result<impl Responder, AppError> {
+    let worker_id = path.into_inner();
+    debug!(worker_id, completed = ?query.completed, "GET /workers/{{id}}/tasks called");
+
+    let conn = db.get_conn()?;
+    ensure_worker_exists(&conn, worker_id)?;
+    let tasks = DbPool::worker_tasks(&conn, worker_id, query.completed)?;
+
+    info!(worker_id, count = tasks.len(), "Returning worker tasks");
+    Ok(HttpResponse::Ok().json(tasks))
+}
+
+/// Handler marking a worker's task as completed now.
+///
+/// # HTTP Method
+/// - `PATCH /workers/{id}/tasks/{task_id}/complete`
+///
+/// # Success
+/// - Returns HTTP 200 with the updated task.
+///
+/// # Errors
+/// - Returns HTTP 404 if the task does not exist for this worker.
+/// - Returns HTTP 409 if the task is already completed.
+pub async fn complete_worker_task(
+    db: web::Data<DbPool>,
+    path: web::Path<(i64, i64)>,
+) -> Result<impl Responder, AppError> {
+    let (worker_id, task_id) = path.into_inner();
+    info!(
+        worker_id,
+        task_id, "PATCH /workers/{{id}}/tasks/{{task_id}}/complete called"
+    );
+
+    let task = db
+        .write(move |tx| DbPool::complete_worker_task(tx, worker_id, task_id))
+        .await?;
+    Ok(HttpResponse::Ok().json(task))
+}
+
+/// Handler reporting a worker's task metrics over the last 30 days.
+///
+/// # HTTP Method
+/// - `GET /workers/{id}/performance`
+///
+/// # Success
+/// - Returns HTTP 200 with a `WorkerPerformance`: completion percentage, open overdue
+///   tasks, and mean hours from assignment to completion.
+///
+/// # Errors
+/// - Returns HTTP 404 if the worker does not exist.
+pub async fn get_worker_performance(
+    db: web::Data<DbPool>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AppError> {
+    let worker_id = path.into_inner();
+    debug!(worker_id, "GET /workers/{{id}}/performance called");
+
+    let conn = db.get_conn()?;
+    let performance = DbPool::worker_performance(&conn, worker_id)?;
+
+    info!(
+        worker_id,
+        pct = performance.task_completion_pct,
+        "Returning worker performance"
+    );
+    Ok(HttpResponse::Ok().json(performance))
+}
@@ -0,0 +1,298 @@
+//! Background jobs that run on a timer instead of in response to requests.
+//!
+//! Each job implements `Job` and is registered with a `Scheduler`, which runs it on its
+//! own tokio interval. Runs happen on the blocking pool inside a `job` tracing span; a
+//! failing or panicking run is logged and recorded in the `JobRegistry`, and the job
+//! simply runs again on its next tick.
+
+use crate::db::{DbPool, SENSOR_PRUNE_BATCH, raise_alert};
+use crate::errors::AppError;
+use crate::models::{JobStatus, OverdueVaccination, SensorPruneSummary};
+use chrono::{NaiveDate, SecondsFormat, Utc};
+use rusqlite::{Connection, OptionalExtension};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{Instrument, debug, error, info, info_span, warn};
+
+/// `alerts.kind` used for overdue vaccination reminders.
+pub const OVERDUE_VACCINATION_ALERT: &str = "vaccination_overdue";
+
+/// Finds overdue vaccinations as of `today`, logs a warning for each, and opens an alert
+/// unless an identical one is still open, so repeated scans do not pile up duplicates.
+///
+/// Returns every overdue entry found, whether or not a new alert was written.
+///
+/// # Errors
+/// Returns database errors raised while querying or inserting alerts.
+pub fn scan_overdue_vaccinations(
+    conn: &Connection,
+    today: NaiveDate,
+) -> Result<Vec<OverdueVaccination>, AppError> {
+    let overdue = DbPool::overdue_vaccinations(conn, today)?;
+    let mut raised = 0;
+    for entry in &overdue {
+        warn!(
+            goat_id = entry.goat_id,
+            vaccine = entry.vaccine,
+            due_on = entry.due_on,
+            days_overdue = entry.days_overdue,
+            "Vaccination overdue"
+        );
+        let message = format!(
+            "{} vaccination for {} was due on {}",
+            entry.vaccine, entry.goat_name, entry.due_on
+        );
+        let open: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM alerts \
+                 WHERE goat_id = ?1 AND kind = ?2 AND message = ?3 AND resolved_at IS NULL",
+                rusqlite::params![entry.goat_id, OVERDUE_VACCINATION_ALERT, message],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if open.is_none() {
+            raise_alert(
+                conn,
+                Some(entry.goat_id),
+                OVERDUE_VACCINATION_ALERT,
+                &message,
+            )?;
+            raised += 1;
+        }
+    }
+
+    info!(
+        overdue = overdue.len(),
+        raised, "Overdue vaccination scan finished"
+    );
+    Ok(overdue)
+}
+
+/// Prunes sensor readings more than `older_than_days` days old, first rolling them up
+/// into daily summaries when `rollup` is set; see `DbPool::prune_sensor_readings`.
+///
+/// # Errors
+/// Returns database errors; batches committed before the error stay pruned.
+pub fn prune_old_sensor_readings(
+    pool: &DbPool,
+    older_than_days: u32,
+    rollup: bool,
+) -> Result<SensorPruneSummary, AppError> {
+    let cutoff = (Utc::now() - chrono::Duration::days(i64::from(older_than_days)))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    debug!(
+        older_than_days,
+        cutoff, rollup, "Pruning old sensor readings"
+    );
+    pool.prune_sensor_readings(&cutoff, rollup, SENSOR_PRUNE_BATCH)
+}
+
+/// A unit of periodic maintenance work.
+pub trait Job: Send + Sync + 'static {
+    /// Stable name shown by `GET /admin/jobs` and in logs.
+    fn name(&self) -> &'static str;
+
+    /// Time between runs; the first run happens as soon as the scheduler starts.
+    fn interval(&self) -> Duration;
+
+    /// Performs one run. Called on the blocking pool, so it may use the database freely.
+    fn run(&self, pool: &DbPool) -> Result<(), AppError>;
+}
+
+/// Last-run bookkeeping for every registered job, shared with the admin endpoint.
+#[derive(Debug, Default)]
+pub struct JobRegistry {
+    statuses: Mutex<Vec<JobStatus>>,
+}
+
+impl JobRegistry {
+    /// Returns the status of every registered job, in registration order.
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        self.statuses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn add(&self, job: &dyn Job) {
+        self.statuses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(JobStatus {
+                name: job.name().to_string(),
+                interval_secs: job.interval().as_secs(),
+                run_count: 0,
+                last_run_at: None,
+                last_outcome: None,
+                last_error: None,
+            });
+    }
+
+    fn record(&self, name: &str, error: Option<String>) {
+        let mut statuses = self.statuses.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(status) = statuses.iter_mut().find(|s| s.name == name) {
+            status.run_count += 1;
+            status.last_run_at = Some(Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true));
+            status.last_outcome = Some(
+                if error.is_none() {
+                    "success"
+                } else {
+                    "failure"
+                }
+                .to_string(),
+            );
+            status.last_error = error;
+        }
+    }
+}
+
+/// Collects jobs and spawns one interval task per job.
+#[derive(Default)]
+pub struct Scheduler {
+    jobs: Vec<Arc<dyn Job>>,
+    registry: Arc<JobRegistry>,
+}
+
+impl Scheduler {
+    /// Creates a scheduler with no jobs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `job`; it starts running when `start` is called.
+    pub fn register(&mut self, job: impl Job) {
+        info!(job = job.name(), interval = ?job.interval(), "Registering background job");
+        self.registry.add(&job);
+        self.jobs.push(Arc::new(job));
+    }
+
+    /// Registry reporting the registered jobs' last runs.
+    pub fn registry(&self) -> Arc<JobRegistry> {
+        Arc::clone(&self.registry)
+    }
+
+    /// Spawns every registered job. Abort the returned handles to stop them.
+    pub fn start(&self, pool: &DbPool) -> Vec<JoinHandle<()>> {
+        self.jobs
+            .iter()
+            .map(|job| spawn_job(Arc::clone(job), pool.clone(), self.registry()))
+            .collect()
+    }
+}
+
+/// Runs `job` on its interval until aborted, recording each outcome in `registry`.
+fn spawn_job(job: Arc<dyn Job>, pool: DbPool, registry: Arc<JobRegistry>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(job.interval());
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let span = info_span!("job", name = job.name());
+            let run = {
+                let job = Arc::clone(&job);
+                let pool = pool.clone();
+                let span = span.clone();
+                tokio::task::spawn_blocking(move || span.in_scope(|| job.run(&pool)))
+            };
+            let error = async {
+                match run.await {
+                    Ok(Ok(())) => {
+                        debug!("Job run succeeded");
+                        None
+                    }
+                    Ok(Err(e)) => {
+                        error!(error = %e, "Job run failed; retrying next tick");
+                        Some(e.to_string())
+                    }
+                    Err(e) => {
+                        error!(error = %e, "Job run panicked; retrying next tick");
+                        Some(format!("panicked: {}", e))
+                    }
+                }
+            }
+            .instrument(span)
+            .await;
+            registry.record(job.name(), error);
+        }
+    })
+}
+
+/// Periodically scans for overdue vaccinations; see `scan_overdue_vaccinations`.
+pub struct VaccinationReminderJob {
+    pub interval: Duration,
+}
+
+impl Job for VaccinationReminderJob {
+    fn name(&self) -> &'static str {
+        "vaccination_reminders"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn run(&self, pool: &DbPool) -> Result<(), AppError> {
+        let conn = pool.get_conn()?;
+        scan_overdue_vaccinations(&conn, Utc::now().date_naive())?;
+        Ok(())
+    }
+}
+
+/// Folds the write-ahead log back into the database file and truncates it, so the WAL
+/// does not grow without bound between SQLite's automatic checkpoints.
+pub struct WalCheckpointJob {
+    pub interval: Duration,
+}
+
+impl Job for WalCheckpointJob {
+    fn name(&self) -> &'static str {
+        "wal_checkpoint"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn run(&self, pool: &DbPool) -> Result<(), AppError> {
+        let conn = pool.get_conn()?;
+        let (busy, log_frames, checkpointed): (i64, i64, i64) =
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?;
+        if busy != 0 {
+            warn!(
+                log_frames,
+                checkpointed, "WAL checkpoint blocked by active readers"
+            );
+        } else {
+            debug!(log_frames, checkpointed, "WAL checkpoint complete");
+        }
+        Ok(())
+    }
+}
+
+/// Deletes sensor readings older than the retention window so the table does not grow
+/// forever; see `prune_old_sensor_readings`.
+pub struct SensorRetentionJob {
+    pub interval: Duration,
+    pub retention_days: u32,
+    pub rollup: bool,
+}
+
+impl Job for SensorRetentionJob {
+    fn name(&self) -> &'static str {
+        "sensor_retention"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn run(&self, pool: &DbPool) -> Result<(), AppError> {
+        prune_old_sensor_readings(pool, self.retention_days, self.rollup)?;
+        Ok(())
+    }
+}
@@ -1,5 +1,22 @@
+pub mod cache;
+pub mod config;
 pub mod db;
 pub mod db_helpers;
+pub mod domain;
+pub mod envelope;
 pub mod errors;
 pub mod handlers;
+pub mod jobs;
+pub mod middleware;
 pub mod models;
+pub mod money;
+pub mod openapi;
+pub mod rate_limit;
+pub mod schema;
+pub mod services;
+pub mod state;
+pub mod telemetry;
+pub mod units;
+pub mod validation;
+pub mod watchdog;
+pub mod writer;
@@ -9,43 +9,122 @@
 
 use actix_cors::Cors;
 use actix_web::{App, HttpServer, middleware, web};
-use backend::db::DbPool;
-use backend::handlers::goats;
-use tracing::info;
-use tracing_subscriber;
+use backend::cache::GoatListCache;
+use backend::config::{
+    JournalMode, admin_token, api_keys, log_bodies, max_body_bytes, max_import_body_bytes,
+    rate_limit_per_minute, sensor_prune_interval, sensor_retention_days, sensor_rollup,
+    vaccination_reminder_interval, wal_checkpoint_interval,
+};
+use backend::db::{DbPool, PoolOptions, check_goat_columns, get_setting};
+use backend::errors::{json_config, path_config, query_config};
+use backend::handlers::{
+    activity, admin, batch, behavior, breeding, buyers, catalog, cohorts, documents, equipment,
+    famacha, genetics, geofences, goats, health, inventory, leave_requests, payroll, reports,
+    sensors, shows, spaces, stats, suppliers, tasks, tools, trade_log, vaccines, workers,
+};
+use backend::jobs::{Scheduler, SensorRetentionJob, VaccinationReminderJob, WalCheckpointJob};
+use backend::middleware::{body_logger, rate_limit, read_only_guard};
+use backend::openapi::swagger_ui;
+use backend::rate_limit::RateLimiter;
+use backend::state::{AdminToken, AppState, READ_ONLY_SETTING};
+use backend::telemetry::init_tracing;
+use tracing::{info, warn};
+use tracing_actix_web::TracingLogger;
 
 /// Main asynchronous function to configure and start the backend server.
 ///
 /// # Steps performed:
-/// 1. Initialize structured logging with `tracing_subscriber`, respecting the RUST_LOG env var.
-/// 2. Open SQLite database connection (or create if missing).
-/// 3. Run any pending database schema migrations; exit if migration fails.
-/// 4. Wrap the DB connection in a thread-safe pool (`DbPool`).
-/// 5. Configure the Actix web server with middleware and route handlers.
-/// 6. Bind the server to `127.0.0.1:8000` and run.
+/// 1. Initialize structured logging, exporting traces over OTLP when
+///    `OTEL_EXPORTER_OTLP_ENDPOINT` is set.
+/// 2. Open a thread-safe pool (`DbPool`) on the SQLite database `livestock.db`,
+///    creating it if missing, in the journal mode set by `YAGI_JOURNAL_MODE` (WAL by
+///    default) and synchronous setting set by `YAGI_SYNCHRONOUS` (NORMAL by default).
+/// 3. Create any tables the database is missing, unless refinery migrations manage it,
+///    and check that the `goats` table has every column goat reads select; exit if
+///    either fails.
+/// 4. Restore the persisted maintenance flag into the shared `AppState`.
+/// 5. Start the background job scheduler (vaccination reminders, sensor reading
+///    retention, and WAL checkpoints when in WAL mode).
+/// 6. Configure the Actix web server with middleware, route handlers and JSON body limits
+///    (`YAGI_MAX_BODY_BYTES`, and `YAGI_MAX_IMPORT_BODY_BYTES` for `POST /admin/import` and
+///    the goat imports).
+///    Request and response bodies are logged at debug level when `YAGI_LOG_BODIES=true`.
+/// 7. Bind the server to `127.0.0.1:8000` and run, stopping background jobs and flushing
+///    the trace exporter on shutdown.
 ///
 /// # Panics
-/// This function will terminate the process if the database cannot be opened or if migrations fail.
+/// This function will terminate the process if the journal mode or synchronous setting
+/// is unsupported, if the database cannot be opened, if creating tables fails, or if the
+/// `goats` table is missing a column.
 ///
 /// # Logging
 /// - Emits info-level logs during startup phases.
 /// - Logs database errors and migration failures at error-level with details.
-/// - Default request logs provided by Actix's Logger middleware.
+/// - Default request logs provided by Actix's Logger middleware; when traces are
+///   exported, one span per request from `TracingLogger` instead, joined to the caller's
+///   trace when a `traceparent` header is sent.
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    // Initialize logging: use environment variable `RUST_LOG` to set verbosity.
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
-        .init();
+    let telemetry = init_tracing();
+    let exporting_traces = telemetry.is_exporting();
 
     info!("Starting Livestock Management Backend Server");
 
-    let db_pool = DbPool::new("livestock.db").expect("Failed to create DB pool");
+    let pool_options = PoolOptions::from_env().expect("Invalid database configuration");
+    let db_pool =
+        DbPool::with_options("livestock.db", pool_options).expect("Failed to create DB pool");
+    db_pool
+        .get_conn()
+        .and_then(|conn| check_goat_columns(&conn))
+        .expect("Database schema check failed");
+
+    // Restore the maintenance flag persisted by a previous run, defaulting to writable.
+    let read_only = db_pool
+        .get_conn()
+        .and_then(|conn| get_setting(&conn, READ_ONLY_SETTING))
+        .unwrap_or_else(|e| {
+            warn!(
+                "Could not load maintenance flag, defaulting to writable: {}",
+                e
+            );
+            None
+        })
+        .is_some_and(|value| value == "true");
+    let app_state = web::Data::new(AppState::new(read_only));
+    info!(read_only, "Maintenance state loaded");
+
+    let rate_limiter =
+        web::Data::new(RateLimiter::new(rate_limit_per_minute()).with_api_keys(api_keys()));
+    let goat_list_cache = web::Data::new(GoatListCache::new());
+    let admin_token = web::Data::new(AdminToken::new(admin_token()));
+    let log_bodies = log_bodies();
+    if log_bodies {
+        warn!("Request and response bodies are being logged");
+    }
+
+    let mut scheduler = Scheduler::new();
+    scheduler.register(VaccinationReminderJob {
+        interval: vaccination_reminder_interval(),
+    });
+    scheduler.register(SensorRetentionJob {
+        interval: sensor_prune_interval(),
+        retention_days: sensor_retention_days(),
+        rollup: sensor_rollup(),
+    });
+    if pool_options.journal_mode == JournalMode::Wal {
+        scheduler.register(WalCheckpointJob {
+            interval: wal_checkpoint_interval(),
+        });
+    }
+    let job_registry = web::Data::from(scheduler.registry());
+    let jobs = scheduler.start(&db_pool);
 
     // Build and run Actix web server.
     // Register logging middleware and route definitions.
-    HttpServer::new(move || {
+    let server = HttpServer::new(move || {
         App::new()
+            .wrap(middleware::from_fn(read_only_guard))
+            .wrap(middleware::from_fn(rate_limit))
             .wrap(
                 Cors::default()
                     .allowed_origin("http://127.0.0.1:8080/")
@@ -53,17 +132,371 @@ async fn main() -> std::io::Result<()> {
                     .allow_any_method()
                     .allow_any_header(),
             )
-            .wrap(middleware::Logger::default()) // Logs every request at info level.
+            .wrap(middleware::Condition::new(
+                log_bodies,
+                middleware::from_fn(body_logger),
+            ))
+            // Logs every request at info level, or wraps it in an exported span.
+            .wrap(middleware::Condition::new(
+                !exporting_traces,
+                middleware::Logger::default(),
+            ))
+            .wrap(middleware::Condition::new(
+                exporting_traces,
+                TracingLogger::default(),
+            ))
             .app_data(web::Data::new(db_pool.clone()))
+            .app_data(app_state.clone())
+            .app_data(rate_limiter.clone())
+            .app_data(admin_token.clone())
+            .app_data(goat_list_cache.clone())
+            .app_data(job_registry.clone())
+            .app_data(json_config(max_body_bytes()))
+            .app_data(query_config())
+            .app_data(path_config())
+            .route("/health", web::get().to(health::health))
+            .service(swagger_ui())
+            .route("/batch", web::post().to(batch::run_batch))
+            .route("/activity", web::get().to(activity::get_activity))
             .service(
                 web::scope("/goats")
                     .route("", web::get().to(goats::get_goats))
+                    .route("/compact", web::get().to(goats::get_compact_goats))
+                    .route("/changes", web::get().to(goats::get_goat_changes))
+                    .route("/search", web::get().to(goats::search_goats))
+                    .route("/random", web::get().to(goats::get_random_goats))
+                    .route("/sale-ready", web::get().to(goats::get_sale_ready_goats))
+                    .route("/attention", web::get().to(goats::get_attention_goats))
+                    .route(
+                        "/import-document",
+                        web::post().to(goats::import_goat_document),
+                    )
+                    .service(
+                        web::resource("/import")
+                            .app_data(json_config(max_import_body_bytes()))
+                            .route(web::post().to(goats::import_goats)),
+                    )
+                    .service(
+                        web::resource("/import/csv")
+                            .app_data(web::PayloadConfig::new(max_import_body_bytes()))
+                            .route(web::post().to(goats::import_goats_csv)),
+                    )
+                    .route("/show-champions", web::get().to(shows::get_show_champions))
+                    .route(
+                        "/famacha/action-needed",
+                        web::get().to(famacha::get_famacha_action_needed),
+                    )
+                    .route(
+                        "/behavior/concerning",
+                        web::get().to(behavior::get_concerning_behavior),
+                    )
+                    .route("/{id}/qrcode", web::get().to(goats::get_goat_qrcode))
+                    .route("/{id}/tags", web::post().to(goats::add_goat_tag))
+                    .route("/{id}/pedigree", web::put().to(breeding::set_goat_pedigree))
+                    .route(
+                        "/{id}/documents",
+                        web::post().to(documents::upload_goat_document),
+                    )
+                    .route(
+                        "/{id}/documents",
+                        web::get().to(documents::list_goat_documents),
+                    )
+                    .route(
+                        "/{id}/genetic-tests",
+                        web::post().to(genetics::add_genetic_test),
+                    )
+                    .route(
+                        "/{id}/genetic-tests",
+                        web::get().to(genetics::get_genetic_tests),
+                    )
+                    .route("/{id}/tags/{tag}", web::delete().to(goats::remove_goat_tag))
+                    .route("/{id}/sell", web::post().to(goats::sell_goat))
+                    .route(
+                        "/{id}/location",
+                        web::post().to(geofences::record_goat_location),
+                    )
+                    .route("/{id}/trade-log", web::post().to(trade_log::add_trade))
+                    .route(
+                        "/{id}/trade-log",
+                        web::get().to(trade_log::get_goat_trade_log),
+                    )
+                    .route("/{id}/vaccines", web::get().to(goats::get_goat_vaccines))
+                    .route("/{id}/diseases", web::get().to(goats::get_goat_diseases))
+                    .route("/{id}/timeline", web::get().to(goats::get_goat_timeline))
+                    .route("/{id}/movements", web::get().to(spaces::get_goat_movements))
+                    .route(
+                        "/{id}/weight-prediction",
+                        web::get().to(goats::get_weight_prediction),
+                    )
+                    .route("/{id}/export", web::get().to(goats::export_goat_document))
+                    .route(
+                        "/{id}/profit-analysis",
+                        web::get().to(goats::get_goat_profit_analysis),
+                    )
+                    .route("/{id}/shows", web::get().to(shows::get_goat_show_entries))
+                    .route("/{id}/shows", web::post().to(shows::add_show_entry))
+                    .route("/{id}/famacha", web::post().to(famacha::add_famacha_score))
+                    .route(
+                        "/{id}/famacha/history",
+                        web::get().to(famacha::get_famacha_history),
+                    )
+                    .route(
+                        "/{id}/behavior",
+                        web::post().to(behavior::add_behavior_observation),
+                    )
+                    .route(
+                        "/{id}/behavior/history",
+                        web::get().to(behavior::get_behavior_history),
+                    )
+                    .route("/{id}", web::get().to(goats::get_goat))
+                    .route("/{id}", web::put().to(goats::update_goat_by_id))
+                    .route("/{id}", web::patch().to(goats::patch_goat))
+                    .route("/{id}", web::delete().to(goats::delete_goat_by_id))
                     .route("", web::post().to(goats::add_goat))
                     .route("", web::put().to(goats::update_goat))
                     .route("", web::delete().to(goats::delete_goat)),
             )
+            .route("/breeding/plan", web::post().to(breeding::plan_breeding))
+            .route(
+                "/documents/{doc_id}",
+                web::get().to(documents::get_document),
+            )
+            .route("/breeds", web::get().to(catalog::list_breeds))
+            .route("/breeds", web::post().to(catalog::register_breed))
+            .route("/vaccines", web::get().to(catalog::list_vaccines))
+            .route("/vaccines", web::post().to(catalog::add_vaccine))
+            .route("/diseases", web::get().to(catalog::list_diseases))
+            .route("/diseases", web::post().to(catalog::add_disease))
+            .route(
+                "/vaccines/schedule",
+                web::get().to(vaccines::get_vaccination_schedule),
+            )
+            .route(
+                "/vaccinations/campaign",
+                web::post().to(vaccines::run_vaccination_campaign),
+            )
+            .route(
+                "/vaccines/{keep_id}/merge",
+                web::post().to(catalog::merge_vaccines),
+            )
+            .route(
+                "/diseases/{keep_id}/merge",
+                web::post().to(catalog::merge_diseases),
+            )
+            .route("/shows", web::get().to(shows::get_show_entries))
+            .service(
+                web::scope("/cohorts")
+                    .route("", web::post().to(cohorts::create_cohort))
+                    .route("", web::get().to(cohorts::list_cohorts))
+                    .route("/{id}/add", web::post().to(cohorts::add_cohort_goat))
+                    .route(
+                        "/{id}/remove/{goat_id}",
+                        web::delete().to(cohorts::remove_cohort_goat),
+                    )
+                    .route("/{id}/goats", web::get().to(cohorts::get_cohort_goats))
+                    .route("/{id}/stats", web::get().to(cohorts::get_cohort_stats)),
+            )
+            .route(
+                "/trade-log/verify",
+                web::get().to(trade_log::verify_trade_log),
+            )
+            .route(
+                "/genetic-tests/summary",
+                web::get().to(genetics::get_genetic_trait_summary),
+            )
+            .route(
+                "/equipment/{id}/depreciation",
+                web::get().to(equipment::get_equipment_depreciation),
+            )
+            .route(
+                "/tools/feed-formulation",
+                web::post().to(tools::feed_formulation),
+            )
+            .service(
+                web::scope("/tasks")
+                    .route("", web::get().to(tasks::list_tasks))
+                    .route("", web::post().to(tasks::create_task))
+                    .route("/{id}", web::get().to(tasks::get_task))
+                    .route("/{id}", web::put().to(tasks::update_task))
+                    .route("/{id}", web::delete().to(tasks::delete_task))
+                    .route("/{id}/complete", web::post().to(tasks::complete_task)),
+            )
+            .service(
+                web::scope("/leave-requests")
+                    .route("", web::get().to(leave_requests::list_leave_requests))
+                    .route("", web::post().to(leave_requests::create_leave_request))
+                    .route(
+                        "/{id}/decide",
+                        web::post().to(leave_requests::decide_leave_request),
+                    ),
+            )
+            .service(
+                web::scope("/workers")
+                    .route("/{id}/tasks", web::post().to(workers::add_worker_task))
+                    .route("/{id}/tasks", web::get().to(workers::get_worker_tasks))
+                    .route(
+                        "/{id}/tasks/{task_id}/complete",
+                        web::patch().to(workers::complete_worker_task),
+                    )
+                    .route(
+                        "/{id}/performance",
+                        web::get().to(workers::get_worker_performance),
+                    )
+                    .route(
+                        "/{id}/time-entries",
+                        web::post().to(payroll::add_time_entry),
+                    )
+                    .route(
+                        "/{id}/pay-terms",
+                        web::put().to(payroll::set_worker_pay_terms),
+                    )
+                    .route("/{id}/payroll", web::get().to(payroll::get_worker_payroll)),
+            )
+            .route(
+                "/payroll/summary",
+                web::get().to(payroll::get_payroll_summary),
+            )
+            .service(
+                web::scope("/inventory/medicine")
+                    .route("", web::get().to(inventory::list_medicines))
+                    .route("", web::post().to(inventory::add_medicine))
+                    .route(
+                        "/expiring",
+                        web::get().to(inventory::get_expiring_medicines),
+                    )
+                    .route(
+                        "/low-stock",
+                        web::get().to(inventory::get_low_stock_medicines),
+                    )
+                    .route(
+                        "/{id}/dispense",
+                        web::post().to(inventory::dispense_medicine),
+                    )
+                    .route("/{id}/restock", web::post().to(inventory::restock_medicine)),
+            )
+            .service(
+                web::scope("/buyers")
+                    .route("", web::get().to(buyers::list_buyers))
+                    .route("", web::post().to(buyers::create_buyer))
+                    .route("/{id}", web::get().to(buyers::get_buyer))
+                    .route("/{id}", web::put().to(buyers::update_buyer))
+                    .route("/{id}", web::delete().to(buyers::delete_buyer))
+                    .route(
+                        "/{id}/purchases",
+                        web::get().to(buyers::get_buyer_purchases),
+                    ),
+            )
+            .service(
+                web::scope("/suppliers")
+                    .route("", web::get().to(suppliers::list_suppliers))
+                    .route("", web::post().to(suppliers::create_supplier))
+                    .route("/{id}", web::get().to(suppliers::get_supplier))
+                    .route("/{id}", web::put().to(suppliers::update_supplier))
+                    .route("/{id}", web::delete().to(suppliers::delete_supplier))
+                    .route(
+                        "/{id}/purchases",
+                        web::get().to(suppliers::get_supplier_purchases),
+                    ),
+            )
+            .service(
+                web::scope("/geofences")
+                    .route("", web::get().to(geofences::list_geofences))
+                    .route("", web::post().to(geofences::create_geofence))
+                    .route("/{id}", web::delete().to(geofences::delete_geofence)),
+            )
+            .route(
+                "/alerts/location",
+                web::get().to(geofences::get_location_alerts),
+            )
+            .route(
+                "/sensors/{id}/readings",
+                web::post().to(sensors::add_sensor_reading),
+            )
+            .route(
+                "/sensors/{id}/readings",
+                web::get().to(sensors::get_sensor_readings),
+            )
+            .service(
+                web::scope("/farm/map")
+                    .route("", web::get().to(spaces::get_farm_map))
+                    .route("/svg", web::get().to(spaces::get_farm_map_svg))
+                    .route("/{space_id}", web::put().to(spaces::update_space_position)),
+            )
+            .service(
+                web::scope("/spaces")
+                    .route("/bulk-transfer", web::post().to(spaces::bulk_transfer))
+                    .route("/{id}/goats", web::get().to(spaces::get_space_goats))
+                    .route("/{id}/goats", web::post().to(spaces::assign_goat))
+                    .route(
+                        "/{id}/residents",
+                        web::get().to(spaces::get_space_residents),
+                    )
+                    .route("/{id}/sensors", web::post().to(spaces::assign_sensor))
+                    .route(
+                        "/{id}/environment",
+                        web::get().to(spaces::get_space_environment),
+                    ),
+            )
+            .service(
+                web::scope("/reports")
+                    .route("/daily", web::get().to(reports::get_daily_report))
+                    .route(
+                        "/daily/latest",
+                        web::get().to(reports::get_latest_daily_report),
+                    )
+                    .route("/monthly", web::get().to(reports::get_monthly_report))
+                    .route(
+                        "/monthly/latest",
+                        web::get().to(reports::get_latest_monthly_report),
+                    )
+                    .route("/census", web::get().to(reports::get_census))
+                    .route("/health-trends", web::get().to(reports::get_health_trends)),
+            )
+            .service(
+                web::scope("/stats")
+                    .route("/diseases/trends", web::get().to(stats::get_disease_trends))
+                    .route("/weight-gain", web::get().to(stats::get_breed_weight_gain))
+                    .route(
+                        "/vaccination-coverage",
+                        web::get().to(stats::get_vaccination_coverage),
+                    )
+                    .route("/financial", web::get().to(stats::get_financial_stats))
+                    .route("/profitability", web::get().to(stats::get_profitability)),
+            )
+            .route(
+                "/simulate/population",
+                web::post().to(stats::simulate_population_growth),
+            )
+            .service(
+                web::scope("/admin")
+                    .route("/maintenance", web::post().to(admin::set_maintenance))
+                    .route("/export", web::get().to(admin::export_snapshot))
+                    .route("/export/full", web::get().to(admin::export_full_archive))
+                    .service(
+                        web::resource("/import")
+                            .app_data(json_config(max_import_body_bytes()))
+                            .route(web::post().to(admin::import_snapshot)),
+                    )
+                    .route("/jobs", web::get().to(admin::list_jobs))
+                    .route(
+                        "/sensors/prune",
+                        web::post().to(admin::prune_sensor_readings),
+                    )
+                    .route("/data-quality", web::get().to(admin::get_data_quality))
+                    .route(
+                        "/goats/reclassify-wethers",
+                        web::post().to(admin::reclassify_wethers),
+                    ),
+            )
     })
     .bind(("127.0.0.1", 8000))?
     .run()
-    .await
+    .await;
+
+    for job in jobs {
+        job.abort();
+    }
+    info!("Background jobs stopped");
+    telemetry.shutdown().await;
+    server
 }
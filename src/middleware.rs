@@ -0,0 +1,212 @@
+//! Custom Actix middleware applied to the whole application.
+
+use crate::errors::error_json;
+use crate::rate_limit::RateLimiter;
+use crate::state::AppState;
+use actix_web::body::{BodySize, EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::error::ErrorInternalServerError;
+use actix_web::http::Method;
+use actix_web::http::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use actix_web::middleware::Next;
+use actix_web::web::{Bytes, BytesMut};
+use actix_web::{Error, web};
+use futures_util::StreamExt;
+use tracing::{debug, warn};
+
+/// Path of the maintenance toggle, which must stay writable so the flag can be cleared.
+pub const MAINTENANCE_PATH: &str = "/admin/maintenance";
+/// Header identifying an API client; requests without it are limited by source IP.
+pub const API_KEY_HEADER: &str = "X-API-Key";
+/// Path exempt from rate limiting so health checks are never throttled.
+pub const HEALTH_PATH: &str = "/health";
+/// Longest prefix of a request or response body written to the log by `body_logger`.
+pub const LOG_BODY_MAX_BYTES: usize = 4 * 1024;
+/// Headers whose values `body_logger` never writes to the log.
+pub const REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    API_KEY_HEADER,
+];
+
+/// Rejects mutating requests with HTTP 503 while maintenance mode is enabled.
+///
+/// `GET`/`HEAD` requests (including `/health`) always pass through, as does the
+/// maintenance toggle itself. Use with `actix_web::middleware::from_fn`.
+///
+/// # Logs
+/// - Warn: Each write rejected because of maintenance mode.
+pub async fn read_only_guard<B: MessageBody>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    let is_write = matches!(
+        *req.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+    let read_only = req
+        .app_data::<web::Data<AppState>>()
+        .is_some_and(|state| state.is_read_only());
+
+    if is_write && read_only && req.path() != MAINTENANCE_PATH {
+        warn!(method = %req.method(), path = req.path(), "Write rejected: maintenance mode");
+        let response = error_json(
+            "MaintenanceMode",
+            "The server is in read-only maintenance mode; writes are temporarily disabled",
+            503,
+        );
+        return Ok(req.into_response(response).map_into_right_body());
+    }
+
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
+
+/// Rejects requests with HTTP 429 once a client exhausts its token bucket.
+///
+/// Clients are identified by the `X-API-Key` header when it holds one of the limiter's
+/// configured keys, and otherwise by the TCP peer address. Forwarding headers such as
+/// `X-Forwarded-For` are ignored, since any client can set them.
+/// The limiter is taken from `web::Data<RateLimiter>`; without it, and for `/health`,
+/// requests pass through. Rejections carry a `Retry-After` header in whole seconds.
+/// Use with `actix_web::middleware::from_fn`.
+///
+/// # Logs
+/// - Warn: Each request rejected for exceeding the limit.
+pub async fn rate_limit<B: MessageBody>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    if let Some(limiter) = req.app_data::<web::Data<RateLimiter>>()
+        && req.path() != HEALTH_PATH
+    {
+        let client = match req
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|key| limiter.is_known_key(key))
+        {
+            Some(key) => format!("key:{}", key),
+            None => format!(
+                "ip:{}",
+                req.peer_addr()
+                    .map_or_else(|| "unknown".to_string(), |addr| addr.ip().to_string())
+            ),
+        };
+
+        if let Err(wait) = limiter.check(&client) {
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            warn!(
+                client,
+                path = req.path(),
+                retry_after,
+                "Request rejected: rate limit exceeded"
+            );
+            let mut response = error_json(
+                "RateLimited",
+                &format!("Too many requests; retry after {} seconds", retry_after),
+                429,
+            );
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after));
+            return Ok(req.into_response(response).map_into_right_body());
+        }
+    }
+
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
+
+/// Logs each request and response body with its headers at debug level, for debugging
+/// client issues. Enabled by `YAGI_LOG_BODIES`; use with `actix_web::middleware::from_fn`.
+///
+/// The request body is read in full and handed back to the handler unchanged. Only the
+/// first `LOG_BODY_MAX_BYTES` of each body are logged, and the values of
+/// `REDACTED_HEADERS` are masked. Streamed responses are passed through without being
+/// logged, so exports are never buffered.
+///
+/// # Logs
+/// - Debug: Each request and each non-streamed response, with headers and body.
+pub async fn body_logger<B: MessageBody + 'static>(
+    mut req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B, Bytes>>, Error> {
+    let mut payload = req.take_payload();
+    let mut request_body = BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        request_body.extend_from_slice(&chunk?);
+    }
+    let request_body = request_body.freeze();
+    debug!(
+        method = %req.method(),
+        path = req.path(),
+        headers = %redacted_headers(req.headers()),
+        size = request_body.len(),
+        body = %body_preview(&request_body),
+        "Request body"
+    );
+    let (_, mut replay) = actix_http::h1::Payload::create(true);
+    replay.unread_data(request_body);
+    req.set_payload(replay.into());
+
+    let res = next.call(req).await?;
+    if !matches!(res.response().body().size(), BodySize::Sized(_)) {
+        debug!(
+            status = res.status().as_u16(),
+            "Response body is streamed; not logged"
+        );
+        return Ok(res.map_into_left_body());
+    }
+    let status = res.status();
+    let headers = redacted_headers(res.headers());
+    let (req, res) = res.into_parts();
+    let (res, body) = res.into_parts();
+    let body = actix_web::body::to_bytes(body)
+        .await
+        .map_err(|e| ErrorInternalServerError(e.into()))?;
+    debug!(
+        status = status.as_u16(),
+        headers = %headers,
+        size = body.len(),
+        body = %body_preview(&body),
+        "Response body"
+    );
+    Ok(ServiceResponse::new(req, res.set_body(body)).map_into_right_body())
+}
+
+/// `name: value` pairs of `headers`, with the values of `REDACTED_HEADERS` masked.
+fn redacted_headers(headers: &HeaderMap) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if REDACTED_HEADERS
+                .iter()
+                .any(|redacted| name.as_str().eq_ignore_ascii_case(redacted))
+            {
+                "[redacted]"
+            } else {
+                value.to_str().unwrap_or("[binary]")
+            };
+            format!("{}: {}", name, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `body` as text, cut off after `LOG_BODY_MAX_BYTES`.
+fn body_preview(body: &[u8]) -> String {
+    let shown = &body[..body.len().min(LOG_BODY_MAX_BYTES)];
+    let mut preview = String::from_utf8_lossy(shown).into_owned();
+    if body.len() > LOG_BODY_MAX_BYTES {
+        preview.push_str(&format!(
+            "... ({} more bytes)",
+            body.len() - LOG_BODY_MAX_BYTES
+        ));
+    }
+    preview
+}
@@ -1,13 +1,1814 @@
-use serde::{Deserialize, Serialize};
-use shared::GoatParams;
+use crate::domain::nutrition::FeedIngredient;
+use crate::money::{GOAT_MONEY_FIELDS, Money, MoneyInput, money_fields_to_structured};
+use crate::units::WeightUnit;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de, ser};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Goat types shared with the frontend. The `shared` crate is their single definition,
+/// so the wire format cannot drift between the two; backend code imports them from here.
+pub use shared::{Breed, DiseaseRef, Gender, GoatParams, VaccineRef};
+
+/// A stored goat: its id and version plus parameters.
+///
+/// Serializes `cost` and `current_price` as `Money` objects; deserializing accepts those
+/// or legacy bare rupee amounts.
+#[derive(Debug, Clone)]
 pub struct Goat {
     id: Option<i64>,
+    version: u32,
     pub params: GoatParams,
 }
 
-#[derive(Deserialize)]
+impl Serialize for Goat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut value = serde_json::to_value(&self.params).map_err(ser::Error::custom)?;
+        let map = value
+            .as_object_mut()
+            .ok_or_else(|| ser::Error::custom("goat parameters must serialize to an object"))?;
+        map.insert("id".to_string(), serde_json::json!(self.id));
+        map.insert("version".to_string(), serde_json::json!(self.version));
+        money_fields_to_structured(map);
+        value.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Goat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = Value::deserialize(deserializer)?;
+        let map = value
+            .as_object_mut()
+            .ok_or_else(|| de::Error::custom("expected a goat object"))?;
+        let id = match map.remove("id") {
+            Some(id) => serde_json::from_value(id).map_err(de::Error::custom)?,
+            None => None,
+        };
+        let version = match map.remove("version") {
+            Some(version) => serde_json::from_value(version).map_err(de::Error::custom)?,
+            None => 0,
+        };
+        for (field, input) in take_money_fields(map).map_err(de::Error::custom)? {
+            let money = input.resolve().map_err(|code| {
+                de::Error::custom(format!("unsupported currency {} for {}", code, field))
+            })?;
+            map.insert(field.to_string(), serde_json::json!(money.to_major()));
+        }
+        let params = serde_json::from_value(value).map_err(de::Error::custom)?;
+        Ok(Goat {
+            id,
+            version,
+            params,
+        })
+    }
+}
+
+/// Removes the money fields from a goat object, returning them as sent.
+fn take_money_fields(
+    map: &mut Map<String, Value>,
+) -> Result<Vec<(&'static str, MoneyInput)>, serde_json::Error> {
+    let mut fields = Vec::new();
+    for field in GOAT_MONEY_FIELDS {
+        if let Some(raw) = map.remove(field) {
+            fields.push((field, serde_json::from_value(raw)?));
+        }
+    }
+    Ok(fields)
+}
+
+impl Goat {
+    /// Pairs stored goat parameters with their database id.
+    pub fn new(id: Option<i64>, params: GoatParams) -> Self {
+        Self {
+            id,
+            version: 0,
+            params,
+        }
+    }
+
+    /// Database id of the goat, if it has been stored.
+    pub fn id(&self) -> Option<i64> {
+        self.id
+    }
+
+    /// Returns the goat with its database id set.
+    pub fn with_id(mut self, id: i64) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Number of updates the stored goat has seen; send it back with a write to have the
+    /// write rejected if someone else updated the goat in between.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the goat with its stored version set.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+}
+
+/// The fields of a goat a bandwidth-constrained client needs for a herd list, served
+/// with `?compact=true`: no relations, dates or money.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CompactGoat {
+    pub id: Option<i64>,
+    pub name: String,
+    pub breed: Breed,
+    pub gender: Gender,
+    pub health_status: String,
+    pub weight: f64,
+}
+
+impl From<GoatParams> for CompactGoat {
+    fn from(goat: GoatParams) -> Self {
+        CompactGoat {
+            id: None,
+            name: goat.name,
+            breed: goat.breed,
+            gender: goat.gender,
+            health_status: goat.health_status,
+            weight: goat.weight,
+        }
+    }
+}
+
+impl From<Goat> for CompactGoat {
+    fn from(goat: Goat) -> Self {
+        CompactGoat {
+            id: goat.id,
+            ..goat.params.into()
+        }
+    }
+}
+
+/// What a goat is fed. Stored as its canonical name (see `db_helpers::diet_to_str`);
+/// `Other` only arises from legacy rows, as API writes must name a known diet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diet {
+    Hay,
+    Pasture,
+    Mixed,
+    Concentrate,
+    Other(String),
+}
+
+/// Query string for `GET /goats`.
+#[derive(Deserialize, IntoParams, Debug, Default)]
+#[into_params(parameter_in = Query)]
+pub struct GoatListQuery {
+    /// Only goats on this diet, e.g. `pasture` (case-insensitive).
+    pub diet: Option<String>,
+    /// Only goats carrying this tag (case-insensitive).
+    pub tag: Option<String>,
+}
+
+/// A file attached to a goat, as listed by `GET /goats/{id}/documents`; the content
+/// itself is served by `GET /documents/{doc_id}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AttachedDocument {
+    pub id: i64,
+    pub goat_id: i64,
+    pub filename: String,
+    pub content_type: String,
+    /// Size of the content, in bytes.
+    pub size: i64,
+    pub uploaded_at: String,
+}
+
+/// Query string for `GET /goats/attention`; each threshold overrides its
+/// `YAGI_ATTENTION_*` environment default.
+#[derive(Deserialize, IntoParams, Debug, Default)]
+#[into_params(parameter_in = Query)]
+pub struct AttentionQuery {
+    pub min_weight_kg: Option<f64>,
+    pub max_weight_kg: Option<f64>,
+    pub weight_stale_days: Option<u32>,
+}
+
+/// Query switch selecting the `CompactGoat` form of goats.
+#[derive(Deserialize, IntoParams, Debug, Default, Clone, Copy)]
+#[into_params(parameter_in = Query)]
+pub struct CompactQuery {
+    /// Return only `id`, `name`, `breed`, `gender`, `health_status` and `weight`.
+    #[serde(default)]
+    pub compact: bool,
+}
+
+/// Longest accepted tag name, in characters.
+pub const MAX_TAG_LEN: usize = 50;
+
+/// Longest accepted goat name, in characters.
+pub const MAX_GOAT_NAME_LEN: usize = 100;
+/// Longest accepted goat diet, in characters.
+pub const MAX_DIET_LEN: usize = 200;
+/// Longest accepted goat health status, in characters.
+pub const MAX_HEALTH_STATUS_LEN: usize = 100;
+
+/// Body of `POST /vaccines` and `POST /diseases`.
+#[derive(Deserialize, Debug)]
+pub struct CatalogName {
+    pub name: String,
+}
+
+/// Body of `POST /goats/{id}/tags`.
+#[derive(Deserialize, Debug)]
+pub struct TagPayload {
+    pub tag: String,
+}
+
+/// Query string for `GET /goats/search`; every filter is optional and they combine with AND.
+#[derive(Deserialize, Debug, Default)]
+pub struct GoatSearchParams {
+    /// Case-insensitive substring of the goat's name.
+    pub q: Option<String>,
+    pub breed: Option<String>,
+    pub gender: Option<String>,
+    pub health_status: Option<String>,
+    /// Inclusive weight bounds, in kilograms.
+    pub min_weight: Option<f64>,
+    pub max_weight: Option<f64>,
+    /// Only goats currently assigned to this space.
+    pub space_id: Option<i64>,
+    /// Only goats with a vaccine of this name.
+    pub has_vaccine: Option<String>,
+    /// Only goats with a disease of this name.
+    pub has_disease: Option<String>,
+    pub is_pregnant: Option<bool>,
+}
+
+/// Most goats `GET /goats/random` returns at once.
+pub const MAX_RANDOM_GOATS: u32 = 50;
+
+/// Sample size for `GET /goats/random`; its filters are those of `GoatSearchParams`.
+#[derive(Deserialize, Debug)]
+pub struct RandomGoatsQuery {
+    /// How many goats to return, 1 by default and at most `MAX_RANDOM_GOATS`.
+    #[serde(default = "default_random_count")]
+    pub count: u32,
+}
+
+fn default_random_count() -> u32 {
+    1
+}
+
+/// Query string selecting the unit system of weights in a response.
+#[derive(Deserialize, IntoParams, Debug, Default)]
+#[into_params(parameter_in = Query)]
+pub struct UnitsQuery {
+    /// `metric` (default) or `imperial`; unrecognised values are treated as metric.
+    pub units: Option<String>,
+}
+
+/// Goat write payload: `GoatParams` plus the unit its `weight` is expressed in.
+///
+/// `cost` and `current_price` may be `Money` objects or legacy bare rupee amounts; `goat`
+/// holds them in rupees and `money` keeps them as sent so validation can check the
+/// currency. Fields left out are listed in `omitted` and hold 0 in `goat` until
+/// estimated (see `estimate_omitted`); validation rejects any still omitted. A `version`
+/// key, as in a goat read back from the API, is ignored; updates take theirs through
+/// `GoatUpdatePayload`.
+#[derive(Debug, Clone)]
+pub struct GoatPayload {
+    pub goat: GoatParams,
+    /// `kg` (default) or `lb`; unrecognised values are treated as kilograms.
+    pub weight_unit: Option<String>,
+    pub money: Vec<(&'static str, MoneyInput)>,
+    pub omitted: Vec<&'static str>,
+}
+
+impl<'de> Deserialize<'de> for GoatPayload {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = Value::deserialize(deserializer)?;
+        let map = value
+            .as_object_mut()
+            .ok_or_else(|| de::Error::custom("expected a goat object"))?;
+        let weight_unit = match map.remove("weight_unit") {
+            Some(unit) => serde_json::from_value(unit).map_err(de::Error::custom)?,
+            None => None,
+        };
+        map.remove("version");
+        let money = take_money_fields(map).map_err(de::Error::custom)?;
+        for (field, input) in &money {
+            // Unsupported currencies are reported by `validate`; the placeholder is never stored.
+            let major = input.resolve().map(Money::to_major).unwrap_or_default();
+            map.insert(field.to_string(), serde_json::json!(major));
+        }
+        let omitted: Vec<&'static str> = GOAT_MONEY_FIELDS
+            .into_iter()
+            .filter(|field| !money.iter().any(|(sent, _)| sent == field))
+            .collect();
+        for field in &omitted {
+            map.insert(field.to_string(), serde_json::json!(0.0));
+        }
+        let goat = serde_json::from_value(value).map_err(de::Error::custom)?;
+        Ok(GoatPayload {
+            goat,
+            weight_unit,
+            money,
+            omitted,
+        })
+    }
+}
+
+/// Goat update payload: a `GoatPayload` plus the `version` the client last read, which
+/// is required. The update only applies while the goat is still at that version.
+#[derive(Debug, Clone)]
+pub struct GoatUpdatePayload {
+    pub version: u32,
+    pub payload: GoatPayload,
+}
+
+impl<'de> Deserialize<'de> for GoatUpdatePayload {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = Value::deserialize(deserializer)?;
+        let version = value
+            .as_object_mut()
+            .ok_or_else(|| de::Error::custom("expected a goat object"))?
+            .remove("version")
+            .ok_or_else(|| de::Error::missing_field("version"))?;
+        Ok(GoatUpdatePayload {
+            version: serde_json::from_value(version).map_err(de::Error::custom)?,
+            payload: serde_json::from_value(value).map_err(de::Error::custom)?,
+        })
+    }
+}
+
+/// Partial goat update: the fields to change plus the `version` the client last read,
+/// which is required. Fields left out keep their stored values.
+#[derive(Debug, Clone)]
+pub struct GoatPatch {
+    pub version: u32,
+    pub changes: Map<String, Value>,
+}
+
+impl<'de> Deserialize<'de> for GoatPatch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut changes = Map::deserialize(deserializer)?;
+        let version = changes
+            .remove("version")
+            .ok_or_else(|| de::Error::missing_field("version"))?;
+        changes.remove("id");
+        Ok(GoatPatch {
+            version: serde_json::from_value(version).map_err(de::Error::custom)?,
+            changes,
+        })
+    }
+}
+
+impl GoatPatch {
+    /// Lays the changes over `stored` and reads the result as a full goat payload. A
+    /// `weight_unit` only applies to a `weight` sent in the same patch, since the stored
+    /// weight is in kilograms.
+    pub fn apply_to(mut self, stored: &Goat) -> Result<GoatPayload, serde_json::Error> {
+        let Value::Object(mut merged) = serde_json::to_value(stored)? else {
+            return Err(de::Error::custom("stored goat must serialize to an object"));
+        };
+        merged.remove("id");
+        merged.remove("version");
+        if !self.changes.contains_key("weight") {
+            self.changes.remove("weight_unit");
+        }
+        merged.extend(self.changes);
+        serde_json::from_value(Value::Object(merged))
+    }
+}
+
+impl GoatPayload {
+    /// Fills omitted money fields from `hint`, where it has a value, and returns whether
+    /// any field was filled.
+    pub fn estimate_omitted(&mut self, hint: &BreedPriceHint) -> bool {
+        let before = self.omitted.len();
+        self.omitted.retain(|field| {
+            let estimate = match *field {
+                "cost" => hint.cost,
+                _ => hint.current_price,
+            };
+            match estimate {
+                Some(amount) if *field == "cost" => self.goat.cost = amount,
+                Some(amount) => self.goat.current_price = amount,
+                None => return true,
+            }
+            false
+        });
+        self.omitted.len() < before
+    }
+
+    /// Returns the goat with its weight converted to kilograms for storage.
+    pub fn into_metric(self) -> GoatParams {
+        let mut goat = self.goat;
+        goat.weight = WeightUnit::from_param(self.weight_unit.as_deref()).to_kg(goat.weight);
+        goat
+    }
+}
+
+/// Average `cost` and `current_price` of a breed's live goats, in rupees rounded to the
+/// paisa; `None` where no goat of the breed has the field.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct BreedPriceHint {
+    pub cost: Option<f64>,
+    pub current_price: Option<f64>,
+}
+
+/// A goat name, used both as the key of name-addressed requests and as the canonical
+/// stored name echoed back by writes.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct NamePayload {
     pub name: String,
 }
+
+/// Query string carrying a single `YYYY-MM-DD` date.
+#[derive(Deserialize)]
+pub struct DateQuery {
+    pub date: String,
+}
+
+/// Counts of farm activity recorded on a single calendar day.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DailyReport {
+    pub date: String,
+    pub goats_added: i64,
+    pub goats_updated: i64,
+    pub diseases_diagnosed: i64,
+    pub vaccinations_administered: i64,
+    pub weight_measurements: i64,
+    pub feed_events: i64,
+    pub vet_visits: i64,
+    pub sales: i64,
+}
+
+/// Request body for toggling read-only maintenance mode.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct MaintenancePayload {
+    pub read_only: bool,
+}
+
+/// Request body for `POST /admin/goats/reclassify-wethers`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReclassifyPayload {
+    pub goat_ids: Vec<i64>,
+}
+
+/// Outcome of reclassifying goats as wethers. Ids that are not live Male goats are skipped.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ReclassifySummary {
+    pub reclassified: Vec<i64>,
+    pub skipped: Vec<i64>,
+}
+
+/// A data entry problem found on one goat by `GET /admin/data-quality`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DataQualityIssue {
+    pub goat_id: i64,
+    pub goat_name: String,
+    pub issue: String,
+}
+
+/// Result of a data quality scan over the live goats.
+///
+/// `quality_score_pct` is the share of goats with no issues at all; an empty herd scores 100.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DataQualityReport {
+    pub issues: Vec<DataQualityIssue>,
+    pub total_issues: u32,
+    pub quality_score_pct: f64,
+}
+
+/// Kind of mutation requested by a single batch entry.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchOp {
+    Create,
+    Update,
+    Delete,
+}
+
+/// One queued operation in a `POST /batch` request.
+#[derive(Deserialize, Debug, Clone)]
+pub struct BatchOperation {
+    pub op: BatchOp,
+    pub entity: String,
+    pub payload: serde_json::Value,
+}
+
+/// Query flags accepted by `POST /batch`.
+#[derive(Deserialize, Debug, Default)]
+pub struct BatchQuery {
+    #[serde(default)]
+    pub atomic: bool,
+}
+
+/// Outcome of a single batch operation, reported in request order.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchResult {
+    pub index: usize,
+    pub success: bool,
+    pub id: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// Response body for `POST /batch`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchResponse {
+    pub committed: bool,
+    pub results: Vec<BatchResult>,
+}
+
+/// Bucket width for time-series statistics.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendInterval {
+    Week,
+    #[default]
+    Month,
+}
+
+impl TrendInterval {
+    /// SQLite `strftime` format producing the bucket label for this interval.
+    pub fn strftime_format(&self) -> &'static str {
+        match self {
+            TrendInterval::Week => "%Y-%W",
+            TrendInterval::Month => "%Y-%m",
+        }
+    }
+}
+
+/// Query string for `GET /stats/diseases/trends`.
+#[derive(Deserialize, Debug)]
+pub struct DiseaseTrendQuery {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub interval: TrendInterval,
+}
+
+/// Number of new diagnoses of one disease within one time bucket.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DiseaseTrendPoint {
+    pub interval: String,
+    pub disease: String,
+    pub count: i32,
+}
+
+/// Query string for `GET /reports/monthly`.
+#[derive(Deserialize, Debug)]
+pub struct MonthQuery {
+    pub month: u32,
+    pub year: i32,
+}
+
+/// Money in and out over one calendar month, for `GET /reports/monthly`. All amounts are
+/// in rupees.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MonthlyFinancialReport {
+    /// `YYYY-MM`.
+    pub month: String,
+    /// Purchase cost of goats added during the month.
+    pub total_purchases: f64,
+    pub total_sales_revenue: f64,
+    /// `Feed` expenses.
+    pub total_feed_costs: f64,
+    /// `Vet` expenses plus the cost of vet visits.
+    pub total_vet_costs: f64,
+    /// `Labor` expenses.
+    pub total_payroll: f64,
+    /// Sales revenue minus purchases, feed, vet and payroll costs.
+    pub net_profit: f64,
+    /// `net_profit` as a percentage of sales revenue; absent when nothing was sold.
+    pub profit_margin_percent: Option<f64>,
+}
+
+/// Query string for `GET /reports/census`.
+#[derive(Deserialize, Debug)]
+pub struct YearQuery {
+    pub year: i32,
+}
+
+/// Goats of one breed in the herd at year-end, by gender.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BreedStats {
+    pub breed: String,
+    pub total: i64,
+    pub male: i64,
+    pub female: i64,
+    pub wether: i64,
+}
+
+/// Annual livestock census for `GET /reports/census`.
+///
+/// A goat is in the herd from its `created_at` until it is deleted or sold.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CensusReport {
+    pub year: i32,
+    /// Herd at the start of 1 January.
+    pub opening_count: i64,
+    /// Herd at the end of 31 December.
+    pub closing_count: i64,
+    /// `closing_count` by breed, in breed order.
+    pub by_breed: Vec<BreedStats>,
+    /// Goats whose `birth_date` falls in the year.
+    pub born: i64,
+    /// Goats deleted during the year without having been sold first.
+    pub died: i64,
+    /// Goats sold during the year.
+    pub sold: i64,
+    /// Mean of the twelve month-end headcounts.
+    pub average_herd_size: f64,
+}
+
+/// Query string for `GET /reports/health-trends`.
+#[derive(Deserialize, Debug)]
+pub struct HealthTrendQuery {
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub bucket: TrendInterval,
+}
+
+/// New diagnoses and resolutions across the herd within one time bucket.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HealthTrendPoint {
+    pub bucket: String,
+    pub diagnoses: i64,
+    pub resolutions: i64,
+}
+
+/// Query string for `GET /goats/changes`.
+#[derive(Deserialize, Debug)]
+pub struct ChangesQuery {
+    pub since: String,
+}
+
+/// Marker for a goat soft-deleted since the client's last sync.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GoatTombstone {
+    pub id: i64,
+    pub name: String,
+    pub deleted_at: String,
+}
+
+/// Delta of goat changes since a client-supplied timestamp.
+///
+/// `server_time` should be passed back as `since` on the next sync.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GoatChanges {
+    pub server_time: String,
+    pub changed: Vec<Goat>,
+    pub deleted: Vec<GoatTombstone>,
+}
+
+/// Query string for `GET /stats/weight-gain`.
+#[derive(Deserialize, Debug)]
+pub struct WeightGainQuery {
+    pub breed: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Average daily weight gain across goats of one breed within a date range.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BreedWeightGain {
+    pub breed: String,
+    pub avg_daily_gain_g: f64,
+    pub sample_size: u32,
+    pub from: String,
+    pub to: String,
+}
+
+/// Query string for `GET /goats/{id}/qrcode`.
+#[derive(Deserialize, Debug)]
+pub struct QrCodeQuery {
+    pub size: Option<u32>,
+}
+
+/// Query string for `GET /goats/{id}/weight-prediction`.
+#[derive(Deserialize, Debug)]
+pub struct WeightPredictionQuery {
+    /// Days ahead to forecast; 30 when omitted.
+    #[serde(default = "default_prediction_days")]
+    pub days: u32,
+}
+
+fn default_prediction_days() -> u32 {
+    30
+}
+
+/// Query string for `POST /simulate/population`.
+#[derive(Deserialize, Debug)]
+pub struct SimulationQuery {
+    /// Years to project; 5 when omitted.
+    #[serde(default = "default_simulation_years")]
+    pub years: u32,
+    /// Simulated herds to average over; 1000 when omitted.
+    #[serde(default = "default_simulation_iterations")]
+    pub iterations: u32,
+}
+
+fn default_simulation_years() -> u32 {
+    5
+}
+
+fn default_simulation_iterations() -> u32 {
+    1000
+}
+
+/// Farm worker record from the `workers` table.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Worker {
+    pub id: Option<i64>,
+    pub name: String,
+    #[serde(default)]
+    pub hours_worked: i64,
+    #[serde(default)]
+    pub leaves: i64,
+    pub role: Option<String>,
+    pub contact: Option<String>,
+    /// Rupees per hour; the farm-wide default applies when absent.
+    #[serde(default)]
+    pub hourly_rate: Option<f64>,
+    /// Monthly hours beyond which work is overtime; the farm-wide default applies when
+    /// absent.
+    #[serde(default)]
+    pub overtime_threshold_hours: Option<f64>,
+}
+
+/// Equipment record from the `equipment` table.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Equipment {
+    pub id: Option<i64>,
+    pub name: String,
+    pub description: Option<String>,
+    pub purchase_date: Option<String>,
+    pub condition: Option<String>,
+    pub last_maintenance: Option<String>,
+    #[serde(default)]
+    pub purchase_price: Option<f64>,
+    #[serde(default)]
+    pub useful_life_years: Option<u32>,
+    #[serde(default)]
+    pub supplier_id: Option<i64>,
+}
+
+/// Sensor record from the `sensors` table.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Sensor {
+    pub id: Option<i64>,
+    pub sensor_type: String,
+    pub location: Option<String>,
+    pub last_reading: Option<f64>,
+    pub last_reading_time: Option<String>,
+    pub status: Option<String>,
+    /// Space the sensor is installed in, if any.
+    #[serde(default)]
+    pub space_id: Option<i64>,
+}
+
+/// Body of `POST /sensors/{id}/readings`. `recorded_at` defaults to now.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SensorReadingPayload {
+    pub value: f64,
+    #[serde(default)]
+    pub recorded_at: Option<String>,
+}
+
+/// A stored sensor reading. `mean` and `std_dev` describe the readings it was compared
+/// against when it was recorded, and are absent when there were too few to judge or
+/// when the reading is listed later.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SensorReading {
+    pub id: i64,
+    pub sensor_id: i64,
+    pub value: f64,
+    pub recorded_at: String,
+    pub is_anomaly: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mean: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub std_dev: Option<f64>,
+}
+
+/// Bucket width for downsampled sensor readings.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadingBucket {
+    #[serde(rename = "1h")]
+    Hour,
+    #[serde(rename = "1d")]
+    Day,
+}
+
+impl ReadingBucket {
+    /// SQLite `strftime` format truncating a timestamp to the start of its bucket.
+    pub fn strftime_format(&self) -> &'static str {
+        match self {
+            ReadingBucket::Hour => "%Y-%m-%d %H:00:00",
+            ReadingBucket::Day => "%Y-%m-%d 00:00:00",
+        }
+    }
+}
+
+/// Query string for `GET /sensors/{id}/readings`. `from` and `to` are inclusive
+/// timestamps; without `bucket` the raw readings are returned, a page at a time.
+#[derive(Deserialize, Debug, Default)]
+pub struct SensorReadingsQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub bucket: Option<ReadingBucket>,
+    /// Raw readings per page; defaults to and is capped at 1000.
+    pub limit: Option<u32>,
+    /// Raw readings to skip before the page starts; defaults to 0.
+    pub offset: Option<u32>,
+}
+
+/// Summary of one sensor's readings within one time bucket.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SensorReadingBucket {
+    /// Start of the bucket, `YYYY-MM-DD HH:MM:SS` UTC.
+    pub bucket_start: String,
+    pub count: i64,
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// Query string for `POST /admin/sensors/prune`; unset fields fall back to
+/// `YAGI_SENSOR_RETENTION_DAYS` and `YAGI_SENSOR_ROLLUP`.
+#[derive(Deserialize, Debug, Default)]
+pub struct SensorPruneQuery {
+    #[serde(default)]
+    pub older_than_days: Option<u32>,
+    #[serde(default)]
+    pub rollup: Option<bool>,
+}
+
+/// Outcome of pruning sensor readings recorded before `cutoff`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SensorPruneSummary {
+    /// `YYYY-MM-DD HH:MM:SS` UTC; older readings were removed.
+    pub cutoff: String,
+    pub deleted: usize,
+    /// Number of delete transactions the readings were removed in.
+    pub batches: usize,
+    /// Whether the removed readings were first summarised into `sensor_reading_daily`.
+    pub rolled_up: bool,
+}
+
+/// Enclosure, field, or other space from the `spaces` table.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Space {
+    pub id: Option<i64>,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub space_type: Option<String>,
+    pub capacity: Option<i64>,
+    pub grass_condition: Option<String>,
+    pub health: Option<String>,
+}
+
+/// `manifest.json` of the archive served by `GET /admin/export/full`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExportManifest {
+    pub schema_version: u32,
+    pub exported_at: String,
+    /// Data rows in each CSV file of the archive, by file name.
+    pub record_counts: BTreeMap<String, usize>,
+}
+
+/// Full-database export document exchanged by `/admin/export` and `/admin/import`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Snapshot {
+    pub schema_version: u32,
+    pub exported_at: String,
+    pub goats: Vec<Goat>,
+    pub vaccines: Vec<VaccineRef>,
+    pub diseases: Vec<DiseaseRef>,
+    pub workers: Vec<Worker>,
+    pub equipment: Vec<Equipment>,
+    pub sensors: Vec<Sensor>,
+    pub spaces: Vec<Space>,
+}
+
+/// One weighing of a goat, as carried by a `GoatDocument`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WeightEntry {
+    /// Kilograms.
+    pub weight: f64,
+    /// `YYYY-MM-DD HH:MM:SS`, as stored.
+    pub recorded_at: String,
+}
+
+/// Self-contained export of a single goat, exchanged by `GET /goats/{id}/export` and
+/// `POST /goats/import-document` to move a goat between farms.
+///
+/// Vaccines and diseases are carried by name; their ids belong to the exporting database
+/// and are ignored on import.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GoatDocument {
+    pub document_version: u32,
+    pub exported_at: String,
+    pub goat: GoatParams,
+    /// Oldest first.
+    pub weight_history: Vec<WeightEntry>,
+}
+
+/// How `POST /admin/import` reconciles a snapshot with existing data.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Make the exported tables match the snapshot, records keeping their exported ids.
+    Replace,
+    /// Upsert every record by its natural key (goat name, vaccine name, ...).
+    #[default]
+    Merge,
+}
+
+/// Query string for `POST /goats/import` and `POST /goats/import/csv`.
+#[derive(Deserialize, Debug, Default)]
+pub struct GoatImportQuery {
+    /// Validate every row and report the outcome without writing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A problem with one row of a goat import.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ImportRowError {
+    /// 1-based position of the row among the data rows.
+    pub row: usize,
+    /// Offending field, when the problem is with a single field.
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Outcome of a goat import. Imports are all or nothing: any row error means nothing is
+/// inserted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GoatImportReport {
+    pub dry_run: bool,
+    /// Data rows received.
+    pub rows: usize,
+    /// Rows that passed validation and would be inserted.
+    pub would_insert: usize,
+    pub inserted: usize,
+    pub errors: Vec<ImportRowError>,
+}
+
+/// Query string for `POST /admin/import`.
+#[derive(Deserialize, Debug)]
+pub struct ImportQuery {
+    #[serde(default)]
+    pub mode: ImportMode,
+}
+
+/// Per-entity counts of records written by an import.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ImportSummary {
+    pub mode: ImportMode,
+    pub goats: usize,
+    pub vaccines: usize,
+    pub diseases: usize,
+    pub workers: usize,
+    pub equipment: usize,
+    pub sensors: usize,
+    pub spaces: usize,
+}
+
+/// Query string for `GET /stats/vaccination-coverage`.
+#[derive(Deserialize, Debug, Default)]
+pub struct CoverageQuery {
+    pub breed: Option<String>,
+}
+
+/// Share of the living herd that has received a given vaccine.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VaccineCoverage {
+    pub vaccine: String,
+    pub vaccinated_count: i32,
+    pub total_goats: i32,
+    pub coverage_pct: f64,
+}
+
+/// A goat eligible for sale, with its estimated market value.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SaleReadyGoat {
+    #[serde(flatten)]
+    pub goat: Goat,
+    pub estimated_sale_price: Option<f64>,
+}
+
+/// A breed name registered through `POST /breeds`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CustomBreed {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub name: String,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// Body of `POST /breeding/plan`.
+#[derive(Deserialize, Debug)]
+pub struct BreedingPlanRequest {
+    pub sire_ids: Vec<i64>,
+    pub dam_ids: Vec<i64>,
+}
+
+/// Body of `POST /vaccines/{keep_id}/merge` and `POST /diseases/{keep_id}/merge`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergePayload {
+    pub duplicate_ids: Vec<i64>,
+}
+
+/// Outcome of a catalog merge.
+///
+/// `links_deduplicated` counts links dropped because the goat was already linked to the kept entry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MergeSummary {
+    pub kept_id: i64,
+    pub merged_ids: Vec<i64>,
+    pub links_moved: usize,
+    pub links_deduplicated: usize,
+}
+
+/// Body of `POST /spaces/{id}/goats`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct SpaceAssignment {
+    pub goat_id: i64,
+}
+
+/// Body of `POST /spaces/bulk-transfer`.
+#[derive(Deserialize, Debug)]
+pub struct BulkTransferPayload {
+    pub from_space_id: i64,
+    pub to_space_id: i64,
+    pub goat_ids: Vec<i64>,
+}
+
+/// Result of a bulk transfer. `remaining_capacity` is `None` for a space without a
+/// capacity limit.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BulkTransferSummary {
+    pub moved: usize,
+    pub to_space: String,
+    pub remaining_capacity: Option<i64>,
+}
+
+/// One stay of a goat in a space, from `goat_space_history`. `moved_out_at` is `None`
+/// for the space the goat is in now.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpaceMovement {
+    pub space_id: i64,
+    pub space_name: String,
+    pub moved_in_at: String,
+    pub moved_out_at: Option<String>,
+}
+
+/// A goat that was in a space at a given moment, with the stay that covers it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpaceResident {
+    pub goat_id: i64,
+    pub name: String,
+    pub moved_in_at: String,
+    pub moved_out_at: Option<String>,
+}
+
+/// Query string of `GET /spaces/{id}/residents`; `at` defaults to now.
+#[derive(Deserialize, Debug)]
+pub struct ResidentsQuery {
+    pub at: Option<String>,
+}
+
+/// Body of `POST /spaces/{id}/sensors`.
+#[derive(Deserialize, Debug)]
+pub struct SensorAssignment {
+    pub sensor_id: i64,
+}
+
+/// Latest reading of one sensor type in a space, taken from the most recently updated
+/// sensor of that type.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EnvironmentReading {
+    pub sensor_type: String,
+    pub sensor_id: i64,
+    pub reading: f64,
+    pub reading_time: Option<String>,
+    pub status: Option<String>,
+}
+
+/// Environmental conditions in a space: one latest reading per sensor type.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpaceEnvironment {
+    pub space: Space,
+    pub readings: Vec<EnvironmentReading>,
+}
+
+/// Goats currently in a space, headed by the space's occupancy against its capacity.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpaceGoats {
+    pub space: Space,
+    pub occupancy: usize,
+    pub capacity: Option<i64>,
+    pub goats: Vec<Goat>,
+}
+
+/// A goat's result at an exhibition or show, from the `show_entries` table.
+///
+/// `goat_id` is taken from the URL on `POST /goats/{id}/shows` and may be omitted in the body.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ShowEntry {
+    #[serde(default)]
+    pub id: Option<i64>,
+    #[serde(default)]
+    pub goat_id: i64,
+    pub show_name: String,
+    pub show_date: String,
+    pub location: Option<String>,
+    pub class: Option<String>,
+    pub placement: Option<i64>,
+    pub prize_amount: Option<f64>,
+    pub notes: Option<String>,
+}
+
+/// Query string for `GET /shows`.
+#[derive(Deserialize, Debug)]
+pub struct ShowsQuery {
+    pub year: Option<i32>,
+}
+
+/// Herd-wide money totals for `GET /stats/financial`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FinancialStats {
+    pub total_purchase_cost: f64,
+    pub total_herd_value: f64,
+    pub total_sales_revenue: f64,
+    pub total_vet_costs: f64,
+    pub total_prize_money: f64,
+    /// Current straight-line value of equipment with a purchase date, price and useful life.
+    pub total_equipment_value: f64,
+}
+
+/// One entry of a goat's history for `GET /goats/{id}/timeline`.
+///
+/// `timestamp` is as stored: `YYYY-MM-DD HH:MM:SS`, or `YYYY-MM-DD` for events only
+/// recorded by date.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimelineEvent {
+    pub timestamp: String,
+    /// `created`, `weight`, `vaccination`, `disease`, `treatment`, `deworming`,
+    /// `vet_visit`, `breeding`, `space_assignment`, `sale`, `famacha`, `show`, `behavior`
+    /// or `genetic_test`.
+    pub event_type: String,
+    pub description: String,
+}
+
+/// What a goat has cost and earned so far, for `GET /goats/{id}/profit-analysis` and
+/// `GET /stats/profitability`. All amounts are in rupees.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProfitAnalysis {
+    pub goat_id: i64,
+    pub name: String,
+    pub acquisition_cost: f64,
+    pub current_value: f64,
+    pub feed_cost_to_date: f64,
+    /// `Vet` expenses plus the cost of recorded vet visits.
+    pub vet_cost_to_date: f64,
+    pub milk_revenue_to_date: f64,
+    /// `current_value + milk_revenue_to_date` minus the acquisition, feed and vet costs.
+    pub net_position: f64,
+}
+
+/// Latest FAMACHA score at or above which a goat needs deworming.
+pub const FAMACHA_ACTION_THRESHOLD: i64 = 3;
+
+/// A FAMACHA anemia score on the 1 (healthy) to 5 (severe) scale.
+///
+/// `goat_id` is taken from the URL on `POST /goats/{id}/famacha`; `scored_at` defaults to now.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FamachaScore {
+    #[serde(default)]
+    pub id: Option<i64>,
+    #[serde(default)]
+    pub goat_id: i64,
+    pub score: i64,
+    #[serde(default)]
+    pub scored_at: Option<String>,
+    #[serde(default)]
+    pub scored_by: Option<String>,
+}
+
+/// A goat whose most recent FAMACHA score calls for deworming.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FamachaAlert {
+    #[serde(flatten)]
+    pub goat: Goat,
+    pub latest_score: i64,
+    pub scored_at: String,
+}
+
+/// A named group of goats, e.g. a kidding season or a trial group.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Cohort {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// Body of `POST /cohorts/{id}/add`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct CohortMember {
+    pub goat_id: i64,
+}
+
+/// Aggregates over the live goats in a cohort.
+///
+/// `average_weight` is `None` for an empty cohort; `health_status_distribution` maps each
+/// health status to the number of goats reporting it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CohortStats {
+    pub cohort_id: i64,
+    pub goat_count: i32,
+    pub average_weight: Option<f64>,
+    pub health_status_distribution: BTreeMap<String, i32>,
+    pub vaccination_coverage: Vec<VaccineCoverage>,
+}
+
+/// A medicine held in stock. `stock_units` and `cost_per_unit` are counted in `unit`
+/// (e.g. "ml", "tablet"); `expiry_date` is `YYYY-MM-DD`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MedicineItem {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub name: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    pub stock_units: f64,
+    pub unit: String,
+    #[serde(default)]
+    pub expiry_date: Option<String>,
+    #[serde(default)]
+    pub cost_per_unit: Option<f64>,
+}
+
+/// Body of `POST /inventory/medicine/{id}/dispense`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DispensePayload {
+    pub goat_id: i64,
+    pub amount: f64,
+    pub reason: String,
+}
+
+/// Query string for `GET /inventory/medicine/expiring`.
+#[derive(Deserialize, Debug)]
+pub struct ExpiringQuery {
+    pub days: Option<i64>,
+}
+
+/// Query string for `GET /inventory/medicine/low-stock`.
+#[derive(Deserialize, Debug)]
+pub struct LowStockQuery {
+    pub threshold: Option<f64>,
+}
+
+/// Body of `POST /inventory/medicine/{id}/restock`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RestockPayload {
+    pub units: f64,
+    #[serde(default)]
+    pub cost_per_unit: Option<f64>,
+    #[serde(default)]
+    pub supplier_id: Option<i64>,
+}
+
+/// A vendor we buy feed, medicines or equipment from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Supplier {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub name: String,
+    #[serde(default)]
+    pub contact: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// One purchase from a supplier: a piece of equipment or a medicine restock.
+///
+/// `kind` is `equipment` or `medicine`; `amount` is `None` when no price was recorded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SupplierPurchase {
+    pub kind: String,
+    pub item_id: i64,
+    pub item_name: String,
+    pub amount: Option<f64>,
+    pub purchased_on: Option<String>,
+}
+
+/// Everything bought from a supplier, newest first, with the total of the priced purchases.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SupplierPurchases {
+    pub supplier_id: i64,
+    pub purchase_count: usize,
+    pub total_spent: f64,
+    pub purchases: Vec<SupplierPurchase>,
+}
+
+/// Body of `POST /goats/{id}/trade-log`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TradePayload {
+    pub action: String,
+    #[serde(default)]
+    pub from_party: Option<String>,
+    #[serde(default)]
+    pub to_party: Option<String>,
+    #[serde(default)]
+    pub price: Option<f64>,
+}
+
+/// An entry in the append-only trade log.
+///
+/// `transaction_hash` is `sha256(prev_hash || goat_id || action || timestamp)`, where
+/// `prev_hash` is the previous entry's hash; see `domain::trade_log`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TradeLogEntry {
+    pub id: i64,
+    pub transaction_hash: String,
+    pub goat_id: i64,
+    pub action: String,
+    pub from_party: Option<String>,
+    pub to_party: Option<String>,
+    pub price: Option<f64>,
+    pub timestamp: String,
+    pub prev_hash: String,
+}
+
+/// Result of `GET /trade-log/verify`; `broken_at` is the first entry that fails to chain.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TradeLogVerification {
+    pub valid: bool,
+    pub broken_at: Option<i64>,
+}
+
+/// Someone we sell goats to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Buyer {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub name: String,
+    #[serde(default)]
+    pub contact: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// Body of `POST /goats/{id}/sell`. An existing buyer is given by `buyer_id`; otherwise
+/// `buyer_name` is looked up, and created if no buyer has that name.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SalePayload {
+    #[serde(default)]
+    pub buyer_id: Option<i64>,
+    #[serde(default)]
+    pub buyer_name: Option<String>,
+    pub price: f64,
+}
+
+/// A recorded goat sale.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Sale {
+    pub id: i64,
+    pub goat_id: i64,
+    pub buyer_id: Option<i64>,
+    pub buyer: Option<String>,
+    pub price: Option<f64>,
+    pub created_at: String,
+}
+
+/// A goat bought by a buyer, as listed by `GET /buyers/{id}/purchases`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BuyerPurchase {
+    pub sale_id: i64,
+    pub goat_id: i64,
+    pub goat_name: String,
+    pub breed: String,
+    pub price: Option<f64>,
+    pub sold_at: String,
+}
+
+/// Values accepted for `Geofence::alert_on`.
+pub const GEOFENCE_ALERT_ON: [&str; 3] = ["Exit", "Entry", "Both"];
+
+/// A circular zone around a center point; crossings its `alert_on` names raise alerts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Geofence {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub name: String,
+    pub lat_center: f64,
+    pub lon_center: f64,
+    pub radius_m: f64,
+    pub alert_on: String,
+}
+
+/// Body of `POST /goats/{id}/location`, in decimal degrees. `recorded_at` defaults to now.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LocationPayload {
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(default)]
+    pub recorded_at: Option<String>,
+}
+
+/// A geofence boundary crossed by a goat. `event` is `Entry` or `Exit`; `distance_m` is
+/// the goat's distance from the fence center at the time.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LocationAlert {
+    pub id: i64,
+    pub goat_id: i64,
+    pub geofence_id: Option<i64>,
+    pub geofence_name: String,
+    pub event: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub distance_m: f64,
+    pub created_at: String,
+}
+
+/// Response of `POST /goats/{id}/location`: the stored fix and any alerts it raised.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LocationReport {
+    pub goat_id: i64,
+    pub lat: f64,
+    pub lon: f64,
+    pub recorded_at: String,
+    pub alerts: Vec<LocationAlert>,
+}
+
+/// Behaviors accepted by `POST /goats/{id}/behavior`.
+pub const BEHAVIORS: [&str; 9] = [
+    "Grazing",
+    "Resting",
+    "Ruminating",
+    "Playing",
+    "Aggressive",
+    "Lethargic",
+    "Abnormal_Gait",
+    "Off_Feed",
+    "Normal",
+];
+
+/// Behaviors that put a goat on the `GET /goats/behavior/concerning` list.
+pub const CONCERNING_BEHAVIORS: [&str; 4] =
+    ["Lethargic", "Aggressive", "Abnormal_Gait", "Off_Feed"];
+
+/// Intensities accepted for a behavior observation, mildest first.
+pub const INTENSITIES: [&str; 3] = ["Mild", "Moderate", "Severe"];
+
+/// A single behavior observation.
+///
+/// `goat_id` is taken from the URL on `POST /goats/{id}/behavior`; `recorded_at` defaults to now.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BehaviorObservation {
+    #[serde(default)]
+    pub id: Option<i64>,
+    #[serde(default)]
+    pub goat_id: i64,
+    pub behavior: String,
+    pub intensity: String,
+    #[serde(default)]
+    pub recorded_at: Option<String>,
+    #[serde(default)]
+    pub observed_by: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// A goat with a concerning behavior observed recently, with its latest such observation.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BehaviorConcern {
+    #[serde(flatten)]
+    pub goat: Goat,
+    pub behavior: String,
+    pub intensity: String,
+    pub recorded_at: String,
+}
+
+/// A registered background job and the result of its most recent run.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JobStatus {
+    pub name: String,
+    pub interval_secs: u64,
+    pub run_count: u64,
+    /// RFC 3339 time the last run finished; `None` until the first run completes.
+    pub last_run_at: Option<String>,
+    /// `success` or `failure`.
+    pub last_outcome: Option<String>,
+    /// Error from the last run, if it failed.
+    pub last_error: Option<String>,
+}
+
+/// A vaccination whose most recent `next_due_on` has passed without a later dose.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OverdueVaccination {
+    pub goat_id: i64,
+    pub goat_name: String,
+    pub vaccine_id: i64,
+    pub vaccine: String,
+    pub due_on: String,
+    pub days_overdue: i64,
+}
+
+/// Most goats one `POST /vaccinations/campaign` call may list.
+pub const MAX_CAMPAIGN_GOATS: usize = 1000;
+
+/// Body of `POST /vaccinations/campaign`: one vaccine given to many goats on one day.
+#[derive(Deserialize, Debug)]
+pub struct VaccinationCampaign {
+    pub vaccine: VaccineRef,
+    pub goat_ids: Vec<i64>,
+    /// `YYYY-MM-DD`.
+    pub administered_on: String,
+    /// `YYYY-MM-DD`; after `administered_on`.
+    pub next_due_on: String,
+    /// Stock units used per goat when the vaccine is tracked in medicine inventory.
+    #[serde(default = "default_dose_units")]
+    pub dose_units: f64,
+}
+
+fn default_dose_units() -> f64 {
+    1.0
+}
+
+/// Outcome of a campaign for one goat, reported in request order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CampaignGoatResult {
+    pub goat_id: i64,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Response body for `POST /vaccinations/campaign`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CampaignSummary {
+    pub vaccine_id: i64,
+    pub vaccinated: usize,
+    /// Medicine inventory item stock was taken from, if the vaccine is tracked there.
+    pub stock_medicine_id: Option<i64>,
+    pub results: Vec<CampaignGoatResult>,
+}
+
+/// Query string for `GET /vaccines/schedule`.
+#[derive(Deserialize, Debug)]
+pub struct VaccinationScheduleQuery {
+    /// Days to plan ahead, starting today; 30 by default.
+    pub date_range_days: Option<u32>,
+}
+
+/// Priorities accepted for a worker task, lowest first.
+pub const TASK_PRIORITIES: [&str; 3] = ["Low", "Medium", "High"];
+
+fn default_task_priority() -> String {
+    "Medium".to_string()
+}
+
+/// A task assigned to a worker; it is open until `completed_at` is set.
+///
+/// `worker_id` is taken from the URL on `POST /workers/{id}/tasks` and from the body on
+/// `/tasks`, where it may also be sent as `assignee_worker_id` (and `task` as `title`).
+/// `assigned_at` defaults to now and `completed_at` is set by the complete endpoints.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WorkerTask {
+    #[serde(default)]
+    pub id: Option<i64>,
+    #[serde(default, alias = "assignee_worker_id")]
+    pub worker_id: i64,
+    #[serde(alias = "title")]
+    pub task: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub assigned_at: Option<String>,
+    #[serde(default)]
+    pub due_at: Option<String>,
+    #[serde(default)]
+    pub completed_at: Option<String>,
+    #[serde(default = "default_task_priority")]
+    pub priority: String,
+}
+
+/// Query string for `GET /tasks`.
+#[derive(Deserialize, Debug)]
+pub struct TasksQuery {
+    /// `open` or `done`; all tasks when omitted.
+    pub status: Option<String>,
+}
+
+/// Query string for `GET /workers/{id}/tasks`.
+#[derive(Deserialize, Debug)]
+pub struct WorkerTasksQuery {
+    /// `false` for open tasks only, `true` for completed ones; all tasks when omitted.
+    pub completed: Option<bool>,
+}
+
+/// Accepted leave request `status` values.
+pub const LEAVE_STATUSES: [&str; 3] = ["pending", "approved", "rejected"];
+
+/// A worker's request for leave over whole days, `from_date` to `to_date` inclusive.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeaveRequest {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub worker_id: i64,
+    /// First day of leave, `YYYY-MM-DD`.
+    pub from_date: String,
+    /// Last day of leave, `YYYY-MM-DD`.
+    pub to_date: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// One of `LEAVE_STATUSES`; set by the server, `pending` until decided.
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub decided_by: Option<String>,
+    #[serde(default)]
+    pub decided_at: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+/// Body of `POST /leave-requests/{id}/decide`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeaveDecision {
+    /// `approved` or `rejected`.
+    pub status: String,
+    /// Who decided, e.g. the manager's name.
+    pub decided_by: String,
+}
+
+/// Query string for `GET /leave-requests`.
+#[derive(Deserialize, Debug, Default)]
+pub struct LeaveRequestsQuery {
+    /// Only requests ending on or after this date, `YYYY-MM-DD`.
+    pub from: Option<String>,
+    /// Only requests starting on or before this date, `YYYY-MM-DD`.
+    pub to: Option<String>,
+    /// Only requests with this status.
+    pub status: Option<String>,
+}
+
+/// Hours a worker logged for one day.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimeEntry {
+    #[serde(default)]
+    pub id: Option<i64>,
+    /// Taken from the request path when logging an entry.
+    #[serde(default)]
+    pub worker_id: i64,
+    /// `YYYY-MM-DD`.
+    pub work_date: String,
+    pub hours: f64,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Body of `PUT /workers/{id}/pay-terms`; an absent value reverts to the farm default.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WorkerPayTerms {
+    #[serde(default)]
+    pub hourly_rate: Option<f64>,
+    #[serde(default)]
+    pub overtime_threshold_hours: Option<f64>,
+}
+
+/// Query string for the payroll endpoints.
+#[derive(Deserialize, Debug)]
+pub struct PayrollQuery {
+    /// `YYYY-MM`.
+    pub month: String,
+}
+
+/// One worker's pay for a month, for `GET /workers/{id}/payroll`. Amounts are in rupees.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WorkerPayroll {
+    pub worker_id: i64,
+    pub worker_name: String,
+    /// `YYYY-MM`.
+    pub month: String,
+    /// Sum of the month's time entries.
+    pub hours_worked: f64,
+    /// Days of approved leave falling in the month.
+    pub leave_days: i64,
+    pub overtime_hours: f64,
+    /// Rate and threshold applied, after falling back to the farm defaults.
+    pub hourly_rate: f64,
+    pub overtime_threshold_hours: f64,
+    pub gross_pay: f64,
+}
+
+/// Payroll of every worker for a month, for `GET /payroll/summary`. Each total is the sum
+/// over `workers`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PayrollSummary {
+    /// `YYYY-MM`.
+    pub month: String,
+    pub total_hours: f64,
+    pub total_overtime_hours: f64,
+    pub total_leave_days: i64,
+    pub total_gross_pay: f64,
+    pub workers: Vec<WorkerPayroll>,
+}
+
+/// A worker's task metrics over the last 30 days.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WorkerPerformance {
+    pub worker_id: i64,
+    pub total_tasks: i32,
+    pub completed_tasks: i32,
+    /// `completed_tasks / total_tasks * 100`, or 0 with no tasks.
+    pub task_completion_pct: f64,
+    /// Open tasks whose `due_at` has passed.
+    pub overdue_tasks: i32,
+    /// Mean hours from assignment to completion, or 0 with no completed tasks.
+    pub avg_completion_hours: f64,
+}
+
+/// One entry in the recent-activity feed across goats, workers and equipment.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ActivityEntry {
+    /// `goat`, `worker` or `equipment`.
+    pub entity_type: String,
+    pub entity_id: i64,
+    /// The entity's name when the change was made; kept after deletion.
+    pub name: Option<String>,
+    /// `insert`, `update` or `delete`, or an operator action such as `reclassify`.
+    pub action: String,
+    pub timestamp: String,
+}
+
+/// Query string for `GET /activity`.
+#[derive(Deserialize, Debug)]
+pub struct ActivityQuery {
+    /// Maximum entries to return; defaults to 20, capped at 200.
+    pub limit: Option<u32>,
+}
+
+/// Genetic test types a lab result may be filed under.
+pub const GENETIC_TEST_TYPES: [&str; 5] = [
+    "Parentage",
+    "Genotype",
+    "Scrapie",
+    "DiseaseScreening",
+    "CoatColor",
+];
+
+/// A lab genetic test for a goat. `result_json` is a free-form JSON object of trait
+/// results, e.g. `{ "kappa_casein": "AB", "scrapie_codon_146": "NN" }`.
+///
+/// `goat_id` is taken from the URL on `POST /goats/{id}/genetic-tests`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GeneticTest {
+    #[serde(default)]
+    pub id: Option<i64>,
+    #[serde(default)]
+    pub goat_id: i64,
+    pub test_type: String,
+    /// `YYYY-MM-DD`.
+    pub test_date: String,
+    #[serde(default)]
+    pub lab_name: Option<String>,
+    pub result_json: Value,
+    #[serde(default)]
+    pub report_url: Option<String>,
+}
+
+/// Query string for `GET /genetic-tests/summary`.
+#[derive(Deserialize, Debug)]
+pub struct GeneticSummaryQuery {
+    /// Top-level key of `result_json` to group by.
+    #[serde(rename = "trait")]
+    pub trait_name: String,
+}
+
+/// How many genetic tests reported one value of a trait.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GeneticTraitCount {
+    pub value: Value,
+    pub count: i64,
+}
+
+/// Body of `POST /tools/feed-formulation`. `production_stage` is one of
+/// `domain::nutrition::PRODUCTION_STAGES`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FeedFormulationRequest {
+    pub goat_weight_kg: f64,
+    pub age_months: u32,
+    pub production_stage: String,
+    pub available_feeds: Vec<FeedIngredient>,
+}
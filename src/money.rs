@@ -0,0 +1,102 @@
+//! Money amounts held as integer minor units (paise for INR).
+//!
+//! Goat `cost` and `current_price` are stored as minor units and reported in JSON as
+//! `{ "amount": 12345, "currency": "INR" }`. Writes also accept the legacy bare number,
+//! read as major units of INR (rupees). Only INR is supported for now.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+
+/// Currencies money fields may be expressed in.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Currency {
+    #[default]
+    #[serde(rename = "INR")]
+    Inr,
+}
+
+impl Currency {
+    /// Parses an ISO 4217 code, ignoring case. Returns `None` for unsupported currencies.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim().to_uppercase().as_str() {
+            "INR" => Some(Currency::Inr),
+            _ => None,
+        }
+    }
+
+    /// ISO 4217 code, as stored in the database.
+    pub fn code(self) -> &'static str {
+        match self {
+            Currency::Inr => "INR",
+        }
+    }
+
+    /// Minor units per major unit (100 paise to the rupee).
+    pub fn minor_per_major(self) -> i64 {
+        match self {
+            Currency::Inr => 100,
+        }
+    }
+}
+
+/// An exact amount in minor units of `currency`.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Money {
+    pub amount: i64,
+    pub currency: Currency,
+}
+
+impl Money {
+    /// Converts a major-unit amount (e.g. rupees) to minor units, rounding to the nearest.
+    pub fn from_major(value: f64, currency: Currency) -> Self {
+        Money {
+            amount: (value * currency.minor_per_major() as f64).round() as i64,
+            currency,
+        }
+    }
+
+    /// The amount in major units, for the legacy `REAL` columns and arithmetic.
+    pub fn to_major(self) -> f64 {
+        self.amount as f64 / self.currency.minor_per_major() as f64
+    }
+}
+
+/// A money field as sent by a client: the structured form or a legacy bare number.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum MoneyInput {
+    Structured { amount: i64, currency: String },
+    Legacy(f64),
+}
+
+impl MoneyInput {
+    /// Resolves the input to `Money`; legacy numbers are rupees.
+    ///
+    /// # Errors
+    /// Returns the offending code if the currency is not supported.
+    pub fn resolve(&self) -> Result<Money, String> {
+        match self {
+            MoneyInput::Legacy(value) => Ok(Money::from_major(*value, Currency::Inr)),
+            MoneyInput::Structured { amount, currency } => Currency::from_code(currency)
+                .map(|currency| Money {
+                    amount: *amount,
+                    currency,
+                })
+                .ok_or_else(|| currency.clone()),
+        }
+    }
+}
+
+/// Goat fields that carry money.
+pub const GOAT_MONEY_FIELDS: [&str; 2] = ["cost", "current_price"];
+
+/// Rewrites the bare-number money fields of a serialized goat into the structured form.
+pub fn money_fields_to_structured(goat: &mut Map<String, Value>) {
+    for field in GOAT_MONEY_FIELDS {
+        if let Some(major) = goat.get(field).and_then(Value::as_f64) {
+            let money = Money::from_major(major, Currency::Inr);
+            goat.insert(field.to_string(), serde_json::json!(money));
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! OpenAPI description of the API, served as JSON with a Swagger UI in front of it.
+//!
+//! `Goat`, `GoatPayload` and the relation references wrap types from the `shared` crate
+//! with hand-written serde, so their schemas are declared here as documentation-only
+//! structs mirroring the JSON rather than derived from the real types.
+
+use crate::errors::{ErrorBody, FieldError};
+use crate::handlers::goats;
+use crate::models::NamePayload;
+use crate::money::{Currency, Money};
+use utoipa::{OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
+
+/// Path the generated OpenAPI JSON is served from.
+pub const OPENAPI_JSON_PATH: &str = "/api-docs/openapi.json";
+
+/// Generated API description covering the goat routes and their error responses.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Yagi livestock management API",
+        description = "Goat herd, health and farm operations records."
+    ),
+    paths(
+        goats::get_goats,
+        goats::add_goat,
+        goats::update_goat,
+        goats::delete_goat
+    ),
+    components(schemas(
+        GoatDoc,
+        GoatPayloadDoc,
+        RelationRefDoc,
+        Money,
+        Currency,
+        NamePayload,
+        FieldError,
+        ErrorBody
+    )),
+    tags((name = "goats", description = "Goat records and their vaccines and diseases"))
+)]
+pub struct ApiDoc;
+
+/// Swagger UI at `/swagger-ui`, backed by the spec at `OPENAPI_JSON_PATH`.
+pub fn swagger_ui() -> SwaggerUi {
+    SwaggerUi::new("/swagger-ui/{_:.*}").url(OPENAPI_JSON_PATH, ApiDoc::openapi())
+}
+
+/// A stored goat as returned by the API.
+#[allow(dead_code)]
+#[derive(ToSchema)]
+#[schema(as = Goat)]
+struct GoatDoc {
+    id: Option<i64>,
+    /// Bumped by every update; send it back with a write to detect concurrent edits.
+    version: u32,
+    #[schema(example = "Beetal")]
+    breed: String,
+    name: String,
+    /// `Male`, `Female` or `Wether`.
+    gender: String,
+    offspring: i32,
+    cost: Money,
+    /// Kilograms, or pounds with `?units=imperial`.
+    weight: f64,
+    current_price: Money,
+    /// `Hay`, `Pasture`, `Mixed` or `Concentrate`.
+    diet: String,
+    /// Date of last breeding, `YYYY-MM-DD`.
+    last_bred: Option<String>,
+    health_status: String,
+    vaccinations: Vec<RelationRefDoc>,
+    diseases: Vec<RelationRefDoc>,
+    /// Tag names; included by `GET /goats`.
+    tags: Option<Vec<String>>,
+}
+
+/// Goat write payload for `POST /goats` and `PUT /goats`.
+#[allow(dead_code)]
+#[derive(ToSchema)]
+#[schema(as = GoatPayload)]
+struct GoatPayloadDoc {
+    breed: String,
+    name: String,
+    gender: String,
+    offspring: i32,
+    /// A `Money` object, or a legacy bare amount in rupees. `POST /goats` estimates it
+    /// from the breed when left out.
+    #[schema(value_type = Object)]
+    cost: Option<Money>,
+    weight: f64,
+    /// `kg` (default) or `lb`.
+    weight_unit: Option<String>,
+    /// Version last read. Required by `PUT /goats`, where a stale one returns 409;
+    /// ignored by `POST /goats`.
+    version: Option<u32>,
+    /// A `Money` object, or a legacy bare amount in rupees. `POST /goats` estimates it
+    /// from the breed when left out.
+    #[schema(value_type = Object)]
+    current_price: Option<Money>,
+    diet: String,
+    last_bred: Option<String>,
+    health_status: String,
+    vaccinations: Vec<RelationRefDoc>,
+    diseases: Vec<RelationRefDoc>,
+}
+
+/// A vaccine or disease linked to a goat.
+#[allow(dead_code)]
+#[derive(ToSchema)]
+#[schema(as = RelationRef)]
+struct RelationRefDoc {
+    id: i64,
+    name: String,
+}
@@ -0,0 +1,128 @@
+//! In-memory token buckets used by the rate-limiting middleware.
+//!
+//! Each client (a configured API key, or the peer address otherwise) owns a bucket
+//! holding up to `requests_per_minute` tokens that refills continuously at the same rate.
+//! Buckets live only in memory, so limits reset when the server restarts. A bucket left
+//! idle long enough to refill is indistinguishable from a new one, so such buckets are
+//! swept out periodically to keep the map bounded by recently active clients.
+
+use dashmap::DashMap;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::trace;
+
+/// Tokens remaining for one client and when they were last topped up.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Per-client token-bucket limiter shared across all workers.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: DashMap<String, Bucket>,
+    api_keys: HashSet<String>,
+    swept_at: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `requests_per_minute` requests per client, with bursts
+    /// up to the same number. A limit of zero is treated as one.
+    pub fn new(requests_per_minute: u32) -> Self {
+        let capacity = f64::from(requests_per_minute.max(1));
+        Self {
+            capacity,
+            refill_per_sec: capacity / 60.0,
+            buckets: DashMap::new(),
+            api_keys: HashSet::new(),
+            swept_at: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Sets the API keys accepted as client identities. Requests presenting any other
+    /// key are limited by peer address, so made-up keys cannot mint fresh buckets.
+    pub fn with_api_keys<I: IntoIterator<Item = String>>(mut self, keys: I) -> Self {
+        self.api_keys = keys.into_iter().collect();
+        self
+    }
+
+    /// Whether `key` is one of the configured API keys.
+    pub fn is_known_key(&self, key: &str) -> bool {
+        self.api_keys.contains(key)
+    }
+
+    /// Number of clients currently holding a bucket.
+    pub fn tracked_clients(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Drops every bucket that has been idle long enough to refill completely.
+    pub fn sweep(&self) {
+        self.sweep_at(Instant::now());
+    }
+
+    /// Like [`RateLimiter::sweep`], treating `now` as the current time.
+    pub fn sweep_at(&self, now: Instant) {
+        let full_after = self.capacity / self.refill_per_sec;
+        let before = self.buckets.len();
+        self.buckets.retain(|_, bucket| {
+            now.saturating_duration_since(bucket.refilled_at)
+                .as_secs_f64()
+                < full_after
+        });
+        trace!(
+            removed = before.saturating_sub(self.buckets.len()),
+            "Idle rate limit buckets swept"
+        );
+    }
+
+    /// Sweeps idle buckets at most once per refill period, skipping the sweep if another
+    /// request is already running one.
+    fn maybe_sweep(&self, now: Instant) {
+        let Ok(mut swept_at) = self.swept_at.try_lock() else {
+            return;
+        };
+        if now.saturating_duration_since(*swept_at).as_secs_f64()
+            >= self.capacity / self.refill_per_sec
+        {
+            *swept_at = now;
+            drop(swept_at);
+            self.sweep_at(now);
+        }
+    }
+
+    /// Takes a token from `client`'s bucket.
+    ///
+    /// Returns `Err` with the wait until the next token is available if the bucket is
+    /// empty.
+    pub fn check(&self, client: &str) -> Result<(), Duration> {
+        self.check_at(client, Instant::now())
+    }
+
+    fn check_at(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        self.maybe_sweep(now);
+        let mut bucket = self.buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: self.capacity,
+            refilled_at: now,
+        });
+        let elapsed = now
+            .saturating_duration_since(bucket.refilled_at)
+            .as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            trace!(client, tokens = bucket.tokens, "Rate limit token taken");
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.refill_per_sec,
+            ))
+        }
+    }
+}
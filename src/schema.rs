@@ -0,0 +1,81 @@
+//! Startup schema bootstrap for databases that refinery has not migrated.
+//!
+//! Migrations are currently disabled, so a fresh clone would otherwise start against an
+//! empty database and fail every request. `ensure_schema` fills the gap from `SCHEMA_SQL`
+//! and stands aside as soon as refinery's history table shows migrations are in charge.
+
+use crate::db::{SCHEMA_SQL, timed_query_map};
+use crate::errors::AppError;
+use rusqlite::Connection;
+use std::collections::HashSet;
+use tracing::{debug, info};
+
+/// Table refinery records applied migrations in.
+pub const REFINERY_HISTORY_TABLE: &str = "refinery_schema_history";
+
+/// Applies `SCHEMA_SQL` and returns the names of the tables it created.
+///
+/// Every statement in the schema is `IF NOT EXISTS`, so the whole script is run each
+/// time: missing tables, indexes and triggers are created and existing ones are left
+/// alone. Existing tables are not altered, so a database whose tables predate columns
+/// the schema's indexes refer to fails here and needs its migrations run first. Nothing
+/// at all is done once refinery has run, so the two never both manage the schema.
+///
+/// Space assignments without an open `goat_space_history` row, such as those made before
+/// the history table existed, are then given one starting at their assignment time, as
+/// migration V46 does for migrated databases.
+///
+/// # Errors
+/// Returns a database error if any statement fails.
+///
+/// # Logging
+/// Info-level with the created tables and the backfilled stays, if any.
+pub fn ensure_schema(conn: &Connection) -> Result<Vec<String>, AppError> {
+    let existing = table_names(conn)?;
+    if existing.contains(REFINERY_HISTORY_TABLE) {
+        debug!("Refinery manages the schema; skipping bootstrap");
+        return Ok(Vec::new());
+    }
+
+    conn.execute_batch(SCHEMA_SQL)?;
+    let mut created: Vec<String> = table_names(conn)?
+        .into_iter()
+        .filter(|table| !existing.contains(table))
+        .collect();
+    created.sort();
+
+    let backfilled = conn.execute(
+        "INSERT INTO goat_space_history (goat_id, space_id, moved_in_at) \
+         SELECT sg.goat_id, sg.space_id, COALESCE(sg.assigned_at, CURRENT_TIMESTAMP) \
+         FROM space_goats sg \
+         WHERE NOT EXISTS ( \
+             SELECT 1 FROM goat_space_history h \
+             WHERE h.goat_id = sg.goat_id AND h.moved_out_at IS NULL)",
+        [],
+    )?;
+    if backfilled > 0 {
+        info!(
+            backfilled,
+            "Recorded current space assignments as open stays"
+        );
+    }
+
+    if created.is_empty() {
+        debug!("Database schema already in place");
+    } else {
+        info!(tables = ?created, "Created missing database tables");
+    }
+    Ok(created)
+}
+
+/// Names of the database's own tables, leaving out SQLite's internal ones.
+fn table_names(conn: &Connection) -> Result<HashSet<String>, AppError> {
+    Ok(timed_query_map(
+        conn,
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        [],
+        |r| r.get(0),
+    )?
+    .into_iter()
+    .collect())
+}
@@ -3,8 +3,8 @@
 CREATE TABLE IF NOT EXISTS goats (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     breed TEXT NOT NULL,
-    name TEXT NOT NULL UNIQUE,
-    gender TEXT CHECK(gender IN ('Male', 'Female')) NOT NULL,
+    name TEXT NOT NULL,
+    gender TEXT CHECK(gender IN ('Male', 'Female', 'Wether')) NOT NULL,
     offspring INTEGER DEFAULT 0,
     cost REAL,
     weight REAL,
@@ -12,9 +12,29 @@ CREATE TABLE IF NOT EXISTS goats (
     diet TEXT,
     last_bred DATE,
     health_status TEXT,
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP,
+    deleted_at TIMESTAMP,
+    lifecycle_state TEXT NOT NULL DEFAULT 'Active',
+    birth_date DATE,
+    is_pregnant INTEGER NOT NULL DEFAULT 0,
+    is_sold INTEGER NOT NULL DEFAULT 0,
+    -- Exact amounts in paise; `cost` and `current_price` hold the same values in rupees
+    cost_minor INTEGER,
+    current_price_minor INTEGER,
+    currency TEXT NOT NULL DEFAULT 'INR',
+    -- Bumped by every update; writes carrying a stale version are rejected
+    version INTEGER NOT NULL DEFAULT 0
 );
 
+-- Live goat names are unique ignoring case; writes store them trimmed and
+-- whitespace-collapsed. Soft-deleted goats give their names up for reuse.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_goats_name_nocase ON goats(name COLLATE NOCASE)
+    WHERE deleted_at IS NULL;
+
+-- Diets are stored as canonical `Diet` names (Hay, Pasture, Mixed, Concentrate)
+CREATE INDEX IF NOT EXISTS idx_goats_diet ON goats(diet);
+
 -- Vaccines master table
 CREATE TABLE IF NOT EXISTS vaccines (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -27,10 +47,24 @@ CREATE TABLE IF NOT EXISTS diseases (
     name TEXT UNIQUE NOT NULL
 );
 
+-- Vaccine and disease names are unique ignoring case; writes store them trimmed
+CREATE UNIQUE INDEX IF NOT EXISTS idx_vaccines_name_nocase ON vaccines(name COLLATE NOCASE);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_diseases_name_nocase ON diseases(name COLLATE NOCASE);
+
+-- Breeds registered by users beyond the built-in `Breed` variants
+CREATE TABLE IF NOT EXISTS custom_breeds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_custom_breeds_name_nocase ON custom_breeds(name COLLATE NOCASE);
+
 -- Join table for goats and vaccines (many-to-many)
 CREATE TABLE IF NOT EXISTS goat_vaccines (
     goat_id INTEGER NOT NULL,
     vaccine_id INTEGER NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
     PRIMARY KEY (goat_id, vaccine_id),
     FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
     FOREIGN KEY (vaccine_id) REFERENCES vaccines(id) ON DELETE CASCADE
@@ -40,11 +74,24 @@ CREATE TABLE IF NOT EXISTS goat_vaccines (
 CREATE TABLE IF NOT EXISTS goat_diseases (
     goat_id INTEGER NOT NULL,
     disease_id INTEGER NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
     PRIMARY KEY (goat_id, disease_id),
     FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
     FOREIGN KEY (disease_id) REFERENCES diseases(id) ON DELETE CASCADE
 );
 
+-- Diagnoses and resolutions of goat diseases, recorded by triggers on goat_diseases:
+-- adding a link is a diagnosis, removing it a resolution. Kept after the goat is gone.
+CREATE TABLE IF NOT EXISTS disease_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    disease_id INTEGER NOT NULL,
+    event TEXT CHECK(event IN ('Diagnosed', 'Resolved')) NOT NULL,
+    occurred_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_disease_events_occurred ON disease_events(occurred_at);
+
 -- Table for workers
 CREATE TABLE IF NOT EXISTS workers (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -53,6 +100,9 @@ CREATE TABLE IF NOT EXISTS workers (
     leaves INTEGER DEFAULT 0,
     role TEXT,
     contact TEXT,
+    -- Pay terms; NULL falls back to the farm-wide defaults
+    hourly_rate REAL,
+    overtime_threshold_hours REAL,
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
 );
 
@@ -64,7 +114,11 @@ CREATE TABLE IF NOT EXISTS equipment (
     purchase_date DATE,
     condition TEXT,
     last_maintenance DATE,
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    -- Straight-line depreciation inputs; equipment lacking either is not valued
+    purchase_price REAL,
+    useful_life_years INTEGER,
+    supplier_id INTEGER REFERENCES suppliers(id) ON DELETE SET NULL
 );
 
 -- Sensors table
@@ -75,7 +129,39 @@ CREATE TABLE IF NOT EXISTS sensors (
     last_reading REAL,
     last_reading_time TIMESTAMP,
     status TEXT,
-    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    -- Space the sensor is installed in; `location` stays as a free-text note
+    space_id INTEGER REFERENCES spaces(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sensors_space ON sensors(space_id);
+
+-- Every reading a sensor reports; `sensors.last_reading` mirrors the newest one
+CREATE TABLE IF NOT EXISTS sensor_readings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    sensor_id INTEGER NOT NULL,
+    value REAL NOT NULL,
+    recorded_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    is_anomaly INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (sensor_id) REFERENCES sensors(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_sensor_readings_sensor ON sensor_readings(sensor_id, id);
+CREATE INDEX IF NOT EXISTS idx_sensor_readings_recorded ON sensor_readings(recorded_at);
+CREATE INDEX IF NOT EXISTS idx_sensor_readings_sensor_recorded
+    ON sensor_readings(sensor_id, recorded_at);
+
+-- Per-day summaries of sensor readings removed by retention pruning
+CREATE TABLE IF NOT EXISTS sensor_reading_daily (
+    sensor_id INTEGER NOT NULL,
+    day TEXT NOT NULL,
+    count INTEGER NOT NULL,
+    avg REAL NOT NULL,
+    min REAL NOT NULL,
+    max REAL NOT NULL,
+    anomalies INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (sensor_id, day),
+    FOREIGN KEY (sensor_id) REFERENCES sensors(id) ON DELETE CASCADE
 );
 
 -- Spaces table
@@ -88,3 +174,672 @@ CREATE TABLE IF NOT EXISTS spaces (
     health TEXT,
     created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
 );
+
+-- Current space of each goat; a goat is in at most one space at a time
+CREATE TABLE IF NOT EXISTS space_goats (
+    goat_id INTEGER PRIMARY KEY,
+    space_id INTEGER NOT NULL,
+    assigned_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
+    FOREIGN KEY (space_id) REFERENCES spaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_space_goats_space_id ON space_goats(space_id);
+
+-- Every stay of a goat in a space; the open row (moved_out_at NULL) mirrors space_goats
+CREATE TABLE IF NOT EXISTS goat_space_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    space_id INTEGER NOT NULL,
+    moved_in_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    moved_out_at TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
+    FOREIGN KEY (space_id) REFERENCES spaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_goat_space_history_goat_id
+    ON goat_space_history(goat_id, moved_in_at);
+CREATE INDEX IF NOT EXISTS idx_goat_space_history_space_id
+    ON goat_space_history(space_id, moved_in_at);
+
+-- Recorded sire and dam of each goat; either may be unknown
+CREATE TABLE IF NOT EXISTS goat_pedigree (
+    goat_id INTEGER PRIMARY KEY,
+    sire_id INTEGER,
+    dam_id INTEGER,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
+    FOREIGN KEY (sire_id) REFERENCES goats(id) ON DELETE SET NULL,
+    FOREIGN KEY (dam_id) REFERENCES goats(id) ON DELETE SET NULL
+);
+
+-- Files attached to goats, such as purchase receipts and vet reports
+CREATE TABLE IF NOT EXISTS goat_documents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    filename TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    uploaded_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    content BLOB NOT NULL,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_goat_documents_goat_id ON goat_documents(goat_id);
+
+-- Position and size of each space on the farm map, in grid units
+CREATE TABLE IF NOT EXISTS farm_map (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    space_id INTEGER NOT NULL UNIQUE,
+    x REAL NOT NULL,
+    y REAL NOT NULL,
+    width REAL NOT NULL,
+    height REAL NOT NULL,
+    FOREIGN KEY (space_id) REFERENCES spaces(id) ON DELETE CASCADE
+);
+
+-- Weight measurements over time
+CREATE TABLE IF NOT EXISTS goat_weight_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    weight REAL NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+-- Feed consumption events
+CREATE TABLE IF NOT EXISTS feed_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER,
+    feed TEXT NOT NULL,
+    quantity_kg REAL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE SET NULL
+);
+
+-- Veterinarian visits
+CREATE TABLE IF NOT EXISTS vet_visits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    vet_name TEXT,
+    reason TEXT,
+    cost REAL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+-- Money spent, optionally on a single goat; `amount` is in rupees
+CREATE TABLE IF NOT EXISTS expenses (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER,
+    category TEXT CHECK(category IN ('Feed', 'Vet', 'Labor', 'Equipment', 'Other')) NOT NULL,
+    amount REAL NOT NULL,
+    incurred_on DATE NOT NULL DEFAULT CURRENT_DATE,
+    notes TEXT,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_expenses_goat ON expenses(goat_id, category);
+
+-- What each goat produces and what it sold for; `revenue` is in rupees
+CREATE TABLE IF NOT EXISTS production_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    type TEXT CHECK(type IN ('Milk', 'Fiber', 'Meat')) NOT NULL,
+    quantity REAL NOT NULL,
+    revenue REAL NOT NULL DEFAULT 0,
+    recorded_on DATE NOT NULL DEFAULT CURRENT_DATE,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_production_records_goat ON production_records(goat_id, type);
+
+-- Goat sales
+CREATE TABLE IF NOT EXISTS sales (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    buyer TEXT,
+    price REAL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    -- `buyer` keeps the name given at sale time; `buyer_id` links the buyer record
+    buyer_id INTEGER REFERENCES buyers(id) ON DELETE SET NULL,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_sales_buyer ON sales(buyer_id);
+
+-- Exhibition and show results
+CREATE TABLE IF NOT EXISTS show_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    show_name TEXT NOT NULL,
+    show_date DATE NOT NULL,
+    location TEXT,
+    class TEXT,
+    placement INTEGER CHECK(placement IS NULL OR placement >= 1),
+    prize_amount REAL,
+    notes TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_show_entries_goat_id ON show_entries(goat_id);
+
+-- FAMACHA anemia scores (1 = healthy, 5 = severely anemic)
+CREATE TABLE IF NOT EXISTS famacha_scores (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    score INTEGER NOT NULL CHECK(score BETWEEN 1 AND 5),
+    scored_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    scored_by TEXT,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_famacha_scores_goat_id ON famacha_scores(goat_id, scored_at);
+
+-- Deworming treatments, either suggested (pending) or administered (completed)
+CREATE TABLE IF NOT EXISTS deworming_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK(status IN ('pending', 'completed')),
+    reason TEXT,
+    product TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+-- Named groups of goats; a goat may belong to several cohorts
+CREATE TABLE IF NOT EXISTS cohorts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS cohort_goats (
+    cohort_id INTEGER NOT NULL,
+    goat_id INTEGER NOT NULL,
+    added_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (cohort_id, goat_id),
+    FOREIGN KEY (cohort_id) REFERENCES cohorts(id) ON DELETE CASCADE,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+-- Medicines on hand; `stock_units` is counted in `unit`
+CREATE TABLE IF NOT EXISTS medicine_inventory (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    category TEXT,
+    stock_units REAL NOT NULL DEFAULT 0,
+    unit TEXT NOT NULL,
+    expiry_date TEXT,
+    cost_per_unit REAL
+);
+
+-- Each dispensing of a medicine to a goat, deducted from stock
+CREATE TABLE IF NOT EXISTS medicine_dispensing (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    medicine_id INTEGER NOT NULL,
+    goat_id INTEGER NOT NULL,
+    amount REAL NOT NULL,
+    reason TEXT NOT NULL,
+    dispensed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (medicine_id) REFERENCES medicine_inventory(id) ON DELETE CASCADE,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_medicine_dispensing_goat_id ON medicine_dispensing(goat_id);
+
+-- Observed behaviors; `behavior` is one of `models::BEHAVIORS`
+CREATE TABLE IF NOT EXISTS behavior_observations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    behavior TEXT NOT NULL,
+    intensity TEXT NOT NULL CHECK(intensity IN ('Mild', 'Moderate', 'Severe')),
+    recorded_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    observed_by TEXT,
+    notes TEXT,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_behavior_observations_goat_recorded
+    ON behavior_observations(goat_id, recorded_at);
+
+-- Conditions raised for an operator's attention; open until `resolved_at` is set
+CREATE TABLE IF NOT EXISTS alerts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER,
+    kind TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_alerts_open ON alerts(resolved_at, created_at);
+
+-- Vaccinations given and when each is next due; the latest row per goat and vaccine is current
+CREATE TABLE IF NOT EXISTS vaccination_schedule (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    vaccine_id INTEGER NOT NULL,
+    administered_on DATE,
+    next_due_on DATE NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
+    FOREIGN KEY (vaccine_id) REFERENCES vaccines(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_vaccination_schedule_goat_vaccine
+    ON vaccination_schedule(goat_id, vaccine_id, next_due_on);
+
+-- Tasks assigned to workers; a task is open until `completed_at` is set
+CREATE TABLE IF NOT EXISTS worker_tasks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    worker_id INTEGER NOT NULL,
+    task TEXT NOT NULL,
+    assigned_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    due_at TIMESTAMP,
+    completed_at TIMESTAMP,
+    priority TEXT NOT NULL DEFAULT 'Medium' CHECK(priority IN ('Low', 'Medium', 'High')),
+    description TEXT,
+    FOREIGN KEY (worker_id) REFERENCES workers(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_worker_tasks_worker_assigned ON worker_tasks(worker_id, assigned_at);
+
+-- Workers' requests for leave, `from_date` to `to_date` inclusive; approval adds the days
+-- to the worker's `leaves` total
+CREATE TABLE IF NOT EXISTS leave_requests (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    worker_id INTEGER NOT NULL,
+    from_date DATE NOT NULL,
+    to_date DATE NOT NULL,
+    reason TEXT,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK(status IN ('pending', 'approved', 'rejected')),
+    decided_by TEXT,
+    decided_at TIMESTAMP,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    CHECK (to_date >= from_date),
+    FOREIGN KEY (worker_id) REFERENCES workers(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_leave_requests_worker_dates
+    ON leave_requests(worker_id, from_date, to_date);
+
+-- Hours a worker put in on one day, the basis of payroll
+CREATE TABLE IF NOT EXISTS worker_time_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    worker_id INTEGER NOT NULL,
+    work_date DATE NOT NULL,
+    hours REAL NOT NULL CHECK(hours > 0 AND hours <= 24),
+    note TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (worker_id) REFERENCES workers(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_worker_time_entries_worker_date
+    ON worker_time_entries(worker_id, work_date);
+
+-- Free-form goat labels such as "show-quality"; names are unique ignoring case
+CREATE TABLE IF NOT EXISTS tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+
+CREATE TABLE IF NOT EXISTS goat_tags (
+    goat_id INTEGER NOT NULL,
+    tag_id INTEGER NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (goat_id, tag_id),
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_goat_tags_tag ON goat_tags(tag_id);
+
+-- Lab genetic test results; `result_json` holds arbitrary trait data as a JSON object
+CREATE TABLE IF NOT EXISTS genetic_tests (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    test_type TEXT NOT NULL,
+    test_date TEXT NOT NULL,
+    lab_name TEXT,
+    result_json TEXT NOT NULL DEFAULT '{}' CHECK(json_valid(result_json)),
+    report_url TEXT,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_genetic_tests_goat_date ON genetic_tests(goat_id, test_date);
+
+-- Vendors we buy feed, medicines and equipment from
+CREATE TABLE IF NOT EXISTS suppliers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    contact TEXT,
+    notes TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Stock added to a medicine, optionally bought from a supplier at `cost_per_unit`
+CREATE TABLE IF NOT EXISTS medicine_restocks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    medicine_id INTEGER NOT NULL,
+    supplier_id INTEGER,
+    units REAL NOT NULL,
+    cost_per_unit REAL,
+    restocked_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (medicine_id) REFERENCES medicine_inventory(id) ON DELETE CASCADE,
+    FOREIGN KEY (supplier_id) REFERENCES suppliers(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_medicine_restocks_supplier ON medicine_restocks(supplier_id);
+
+-- Append-only record of goat trades; each row's `transaction_hash` chains to the previous
+-- row's, see `domain::trade_log`
+CREATE TABLE IF NOT EXISTS immutable_trade_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    transaction_hash TEXT NOT NULL,
+    goat_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    from_party TEXT,
+    to_party TEXT,
+    price REAL,
+    timestamp TEXT NOT NULL,
+    prev_hash TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_immutable_trade_log_goat ON immutable_trade_log(goat_id);
+
+-- Rows can only be appended; the hash chain detects edits made around these
+CREATE TRIGGER IF NOT EXISTS immutable_trade_log_no_update BEFORE UPDATE ON immutable_trade_log
+BEGIN
+    SELECT RAISE(ABORT, 'immutable_trade_log is append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS immutable_trade_log_no_delete BEFORE DELETE ON immutable_trade_log
+BEGIN
+    SELECT RAISE(ABORT, 'immutable_trade_log is append-only');
+END;
+
+-- People and businesses we sell goats to; names are unique ignoring case
+CREATE TABLE IF NOT EXISTS buyers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    contact TEXT,
+    notes TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Circular zones; `alert_on` says which crossings of the boundary raise a location alert
+CREATE TABLE IF NOT EXISTS geofences (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    lat_center REAL NOT NULL,
+    lon_center REAL NOT NULL,
+    radius_m REAL NOT NULL CHECK(radius_m > 0),
+    alert_on TEXT NOT NULL CHECK(alert_on IN ('Exit', 'Entry', 'Both')),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- GPS fixes reported for each goat; the latest row per goat is its current position
+CREATE TABLE IF NOT EXISTS goat_locations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    lat REAL NOT NULL,
+    lon REAL NOT NULL,
+    recorded_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_goat_locations_goat ON goat_locations(goat_id, id);
+
+-- Geofence boundary crossings; `geofence_name` is kept if the fence is deleted
+CREATE TABLE IF NOT EXISTS location_alerts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    goat_id INTEGER NOT NULL,
+    geofence_id INTEGER,
+    geofence_name TEXT NOT NULL,
+    event TEXT NOT NULL CHECK(event IN ('Exit', 'Entry')),
+    lat REAL NOT NULL,
+    lon REAL NOT NULL,
+    distance_m REAL NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (goat_id) REFERENCES goats(id) ON DELETE CASCADE,
+    FOREIGN KEY (geofence_id) REFERENCES geofences(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_location_alerts_created ON location_alerts(created_at);
+
+-- Key/value server settings that survive restarts
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Market price observations; a NULL breed is a herd-wide price
+CREATE TABLE IF NOT EXISTS market_prices (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    breed TEXT,
+    price_per_kg REAL NOT NULL,
+    recorded_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Operator actions worth keeping a record of, e.g. catalog merges; `details` holds JSON
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity TEXT NOT NULL,
+    entity_id INTEGER,
+    action TEXT NOT NULL,
+    details TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Append-only log bumped by triggers on every mutation; its sequence backs `change_seq`.
+-- `name` keeps the entity's name at the time of the change for the activity feed.
+CREATE TABLE IF NOT EXISTS change_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity TEXT NOT NULL,
+    entity_id INTEGER,
+    action TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    name TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_change_log_created ON change_log(created_at);
+
+CREATE TRIGGER IF NOT EXISTS goats_change_insert AFTER INSERT ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('goat', NEW.id, 'insert', NEW.name);
+END;
+
+-- Soft deletes arrive as updates setting `deleted_at`; log them as deletions.
+CREATE TRIGGER IF NOT EXISTS goats_change_update AFTER UPDATE ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES (
+        'goat',
+        NEW.id,
+        CASE WHEN NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL THEN 'delete' ELSE 'update' END,
+        NEW.name
+    );
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_change_delete AFTER DELETE ON goats
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('goat', OLD.id, 'delete', OLD.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS workers_change_insert AFTER INSERT ON workers
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('worker', NEW.id, 'insert', NEW.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS workers_change_update AFTER UPDATE ON workers
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('worker', NEW.id, 'update', NEW.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS workers_change_delete AFTER DELETE ON workers
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('worker', OLD.id, 'delete', OLD.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS equipment_change_insert AFTER INSERT ON equipment
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('equipment', NEW.id, 'insert', NEW.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS equipment_change_update AFTER UPDATE ON equipment
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('equipment', NEW.id, 'update', NEW.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS equipment_change_delete AFTER DELETE ON equipment
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action, name) VALUES ('equipment', OLD.id, 'delete', OLD.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS vaccines_change_insert AFTER INSERT ON vaccines
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('vaccine', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER IF NOT EXISTS vaccines_change_update AFTER UPDATE ON vaccines
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('vaccine', NEW.id, 'update');
+END;
+
+CREATE TRIGGER IF NOT EXISTS vaccines_change_delete AFTER DELETE ON vaccines
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('vaccine', OLD.id, 'delete');
+END;
+
+CREATE TRIGGER IF NOT EXISTS diseases_change_insert AFTER INSERT ON diseases
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('disease', NEW.id, 'insert');
+END;
+
+CREATE TRIGGER IF NOT EXISTS diseases_change_update AFTER UPDATE ON diseases
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('disease', NEW.id, 'update');
+END;
+
+CREATE TRIGGER IF NOT EXISTS diseases_change_delete AFTER DELETE ON diseases
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('disease', OLD.id, 'delete');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goat_vaccines_change_insert AFTER INSERT ON goat_vaccines
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat_vaccine', NEW.goat_id, 'insert');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goat_vaccines_change_delete AFTER DELETE ON goat_vaccines
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat_vaccine', OLD.goat_id, 'delete');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goat_diseases_change_insert AFTER INSERT ON goat_diseases
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat_disease', NEW.goat_id, 'insert');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goat_diseases_change_delete AFTER DELETE ON goat_diseases
+BEGIN
+    INSERT INTO change_log (entity, entity_id, action) VALUES ('goat_disease', OLD.goat_id, 'delete');
+END;
+
+CREATE TRIGGER IF NOT EXISTS goat_diseases_diagnosed AFTER INSERT ON goat_diseases
+BEGIN
+    INSERT INTO disease_events (goat_id, disease_id, event, occurred_at)
+    VALUES (NEW.goat_id, NEW.disease_id, 'Diagnosed', COALESCE(NEW.created_at, CURRENT_TIMESTAMP));
+END;
+
+CREATE TRIGGER IF NOT EXISTS goat_diseases_resolved AFTER DELETE ON goat_diseases
+BEGIN
+    INSERT INTO disease_events (goat_id, disease_id, event)
+    VALUES (OLD.goat_id, OLD.disease_id, 'Resolved');
+END;
+
+-- Versions of the reference lists behind the `ETag`s of `GET /vaccines`, `GET /diseases` and
+-- `GET /breeds`, bumped by triggers in the transaction of every write that can change them.
+-- The breed list includes breeds recorded on live goats.
+
+CREATE TRIGGER IF NOT EXISTS vaccines_version_insert AFTER INSERT ON vaccines
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('vaccines_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS vaccines_version_update AFTER UPDATE ON vaccines
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('vaccines_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS vaccines_version_delete AFTER DELETE ON vaccines
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('vaccines_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS diseases_version_insert AFTER INSERT ON diseases
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('diseases_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS diseases_version_update AFTER UPDATE ON diseases
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('diseases_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS diseases_version_delete AFTER DELETE ON diseases
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('diseases_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS custom_breeds_version_insert AFTER INSERT ON custom_breeds
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS custom_breeds_version_update AFTER UPDATE ON custom_breeds
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS custom_breeds_version_delete AFTER DELETE ON custom_breeds
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_breeds_version_insert AFTER INSERT ON goats
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_breeds_version_update AFTER UPDATE OF breed, deleted_at ON goats
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+CREATE TRIGGER IF NOT EXISTS goats_breeds_version_delete AFTER DELETE ON goats
+BEGIN
+    INSERT INTO settings (key, value) VALUES ('breeds_version', '1')
+    ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1, updated_at = CURRENT_TIMESTAMP;
+END;
+
+-- Foreign keys are not enforced, so a goat's documents go with its row
+CREATE TRIGGER IF NOT EXISTS goats_documents_delete AFTER DELETE ON goats
+BEGIN
+    DELETE FROM goat_documents WHERE goat_id = OLD.id;
+END;
@@ -0,0 +1,94 @@
+//! Goat operations addressed by database id, for handlers that work with whole goats.
+//!
+//! Each function takes the pool, runs in its own transaction (retried while the database
+//! is busy, for writes), and returns the goat as stored, id included, so handlers never
+//! deal with id plumbing themselves.
+
+use crate::db::{DbPool, load_goat_details};
+use crate::errors::AppError;
+use crate::models::{Goat, GoatParams};
+use rusqlite::Transaction;
+use tracing::{info, warn};
+
+/// Stateless entry point for id-addressed goat reads and writes.
+pub struct GoatService;
+
+impl GoatService {
+    /// Loads a live goat with its vaccines and diseases.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such goat, or a database error.
+    pub fn get(db: &DbPool, goat_id: i64) -> Result<Goat, AppError> {
+        let conn = db.get_conn()?;
+        load_goat_details(&conn, goat_id)
+    }
+
+    /// Stores a new goat and returns it with its new id.
+    ///
+    /// # Errors
+    /// Returns `AppError::Conflict` if the name is taken, ignoring case, or a database error.
+    pub fn insert(db: &DbPool, goat: &GoatParams) -> Result<Goat, AppError> {
+        let created = db.with_write_retry(|tx| insert_goat_in(tx, goat))?;
+        info!(goat_id = created.id(), "Goat inserted");
+        Ok(created)
+    }
+
+    /// Overwrites a live goat, name included, and returns it as stored. With
+    /// `expected_version` set the write only applies if the goat is still at that version.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such goat, `AppError::Conflict` if the
+    /// new name belongs to another goat, `AppError::VersionConflict` on a stale version,
+    /// or a database error.
+    pub fn update(
+        db: &DbPool,
+        goat_id: i64,
+        goat: &GoatParams,
+        expected_version: Option<u32>,
+    ) -> Result<Goat, AppError> {
+        let updated =
+            db.with_write_retry(|tx| update_goat_in(tx, goat_id, goat, expected_version))?;
+        info!(goat_id, "Goat updated");
+        Ok(updated)
+    }
+
+    /// Soft-deletes a live goat and returns it as it was before deletion.
+    ///
+    /// # Errors
+    /// Returns `AppError::NotFound` if there is no such goat, or a database error.
+    pub fn delete(db: &DbPool, goat_id: i64) -> Result<Goat, AppError> {
+        let goat = db.with_write_retry(|tx| delete_goat_in(tx, goat_id))?;
+        info!(goat_id, "Goat deleted");
+        Ok(goat)
+    }
+}
+
+/// The write behind `GoatService::insert`, on a caller-owned transaction.
+pub(crate) fn insert_goat_in(tx: &Transaction, goat: &GoatParams) -> Result<Goat, AppError> {
+    let goat_id = DbPool::insert_goat(tx, goat)?;
+    load_goat_details(tx, goat_id)
+}
+
+/// The write behind `GoatService::update`, on a caller-owned transaction.
+pub(crate) fn update_goat_in(
+    tx: &Transaction,
+    goat_id: i64,
+    goat: &GoatParams,
+    expected_version: Option<u32>,
+) -> Result<Goat, AppError> {
+    if DbPool::update_goat(tx, goat_id, goat, expected_version)? == 0 {
+        warn!(goat_id, "Goat not found for update");
+        return Err(AppError::NotFound(format!(
+            "No goat found with id {}",
+            goat_id
+        )));
+    }
+    load_goat_details(tx, goat_id)
+}
+
+/// The write behind `GoatService::delete`, on a caller-owned transaction.
+pub(crate) fn delete_goat_in(tx: &Transaction, goat_id: i64) -> Result<Goat, AppError> {
+    let goat = load_goat_details(tx, goat_id)?;
+    DbPool::soft_delete_goat(tx, goat_id)?;
+    Ok(goat)
+}
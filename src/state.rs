@@ -0,0 +1,92 @@
+//! Shared, mutable server state registered as Actix application data.
+//!
+//! Unlike `DbPool`, which is only a handle to persistent storage, `AppState` holds
+//! runtime switches that middleware and handlers consult on every request.
+
+use crate::errors::AppError;
+use actix_web::HttpRequest;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{info, warn};
+
+/// Settings key under which the maintenance flag is persisted.
+pub const READ_ONLY_SETTING: &str = "read_only";
+
+/// Header carrying the admin token on admin-only requests.
+pub const ADMIN_TOKEN_HEADER: &str = "X-Admin-Token";
+
+/// Process-wide runtime flags shared across all workers.
+#[derive(Debug, Default)]
+pub struct AppState {
+    read_only: AtomicBool,
+}
+
+impl AppState {
+    /// Creates the state with the maintenance flag initialised to `read_only`.
+    pub fn new(read_only: bool) -> Self {
+        Self {
+            read_only: AtomicBool::new(read_only),
+        }
+    }
+
+    /// Returns whether the API is currently rejecting writes.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::SeqCst)
+    }
+
+    /// Switches maintenance mode on or off.
+    pub fn set_read_only(&self, read_only: bool) {
+        info!(read_only, "Maintenance mode toggled");
+        self.read_only.store(read_only, Ordering::SeqCst);
+    }
+}
+
+/// Secret that admin-only routes expect in the `X-Admin-Token` header. Without one
+/// configured, those routes refuse every request.
+#[derive(Debug, Default)]
+pub struct AdminToken {
+    token: Option<String>,
+}
+
+impl AdminToken {
+    /// Creates the guard; `None` locks admin-only routes entirely.
+    pub fn new(token: Option<String>) -> Self {
+        Self { token }
+    }
+
+    /// Checks that `req` carries the configured token.
+    ///
+    /// # Errors
+    /// Returns `AppError::Unauthorized` if no token is configured, or if the header is
+    /// missing or does not match.
+    pub fn authorize(&self, req: &HttpRequest) -> Result<(), AppError> {
+        let Some(expected) = self.token.as_deref() else {
+            warn!(
+                path = req.path(),
+                "Admin request refused: no admin token configured"
+            );
+            return Err(AppError::Unauthorized(
+                "Admin access is not configured".to_string(),
+            ));
+        };
+        let given = req
+            .headers()
+            .get(ADMIN_TOKEN_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        // Compare every byte so the time taken does not reveal how much matched.
+        let matches = given.len() == expected.len()
+            && given
+                .bytes()
+                .zip(expected.bytes())
+                .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+                == 0;
+        if matches {
+            Ok(())
+        } else {
+            Err(AppError::Unauthorized(format!(
+                "A valid {} header is required",
+                ADMIN_TOKEN_HEADER
+            )))
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Logging setup, with optional OpenTelemetry trace export.
+//!
+//! Logs always go to stdout at info level. When `OTEL_EXPORTER_OTLP_ENDPOINT` is set,
+//! spans are also exported over OTLP (e.g. to Jaeger), and incoming W3C `traceparent`
+//! headers are honoured so request spans join the caller's trace.
+
+use opentelemetry::KeyValue;
+use opentelemetry::global;
+use opentelemetry::trace::{TraceError, TracerProvider as _};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{Resource, runtime};
+use tracing::level_filters::LevelFilter;
+use tracing::{info, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Environment variable naming the OTLP collector, e.g. `http://localhost:4317`.
+pub const OTLP_ENDPOINT: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// `service.name` reported on exported spans.
+const SERVICE_NAME: &str = "yagi-backend";
+
+/// Keeps the trace exporter alive; call `shutdown` before exiting to flush it.
+pub struct TelemetryGuard {
+    provider: Option<TracerProvider>,
+}
+
+impl TelemetryGuard {
+    /// Whether spans are being exported over OTLP.
+    pub fn is_exporting(&self) -> bool {
+        self.provider.is_some()
+    }
+
+    /// Flushes buffered spans and stops the exporter. A no-op when export is disabled.
+    ///
+    /// Runs on a blocking thread: the exporter's network client lives on the server's
+    /// runtime, which must keep polling while the flush waits on it.
+    pub async fn shutdown(self) {
+        let Some(provider) = self.provider else {
+            return;
+        };
+        match tokio::task::spawn_blocking(move || provider.shutdown()).await {
+            Ok(Ok(())) => info!("Trace exporter flushed"),
+            Ok(Err(e)) => warn!("Failed to flush trace exporter: {}", e),
+            Err(e) => warn!("Trace exporter shutdown panicked: {}", e),
+        }
+    }
+}
+
+/// Installs the global subscriber: the fmt layer, plus an OpenTelemetry layer when
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` is set. If the exporter cannot be built, logging
+/// continues without export.
+///
+/// # Panics
+/// Panics if a global subscriber is already installed.
+pub fn init_tracing() -> TelemetryGuard {
+    let registry = tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(tracing_subscriber::fmt::layer());
+
+    let endpoint = std::env::var(OTLP_ENDPOINT)
+        .ok()
+        .filter(|endpoint| !endpoint.trim().is_empty());
+    let Some(endpoint) = endpoint else {
+        registry.init();
+        return TelemetryGuard { provider: None };
+    };
+
+    match build_provider(&endpoint) {
+        Ok(provider) => {
+            global::set_text_map_propagator(TraceContextPropagator::new());
+            let tracer = provider.tracer(SERVICE_NAME);
+            registry
+                .with(tracing_opentelemetry::layer().with_tracer(tracer))
+                .init();
+            info!(%endpoint, "Exporting traces over OTLP");
+            TelemetryGuard {
+                provider: Some(provider),
+            }
+        }
+        Err(e) => {
+            registry.init();
+            warn!(%endpoint, "Trace export disabled, could not build exporter: {}", e);
+            TelemetryGuard { provider: None }
+        }
+    }
+}
+
+/// Builds a batching OTLP/gRPC tracer provider for `endpoint`.
+///
+/// The batch processor runs on its own thread so a flush at shutdown cannot deadlock
+/// the single-threaded actix runtime.
+fn build_provider(endpoint: &str) -> Result<TracerProvider, TraceError> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()?;
+    Ok(TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::TokioCurrentThread)
+        .with_resource(Resource::new([KeyValue::new("service.name", SERVICE_NAME)]))
+        .build())
+}
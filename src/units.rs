@@ -0,0 +1,87 @@
+//! Weight unit conversion for the API boundary.
+//!
+//! Weights are always stored in kilograms. Clients may send pounds on writes
+//! (`weight_unit`) and ask for pounds on reads (`?units=imperial`); anything missing or
+//! unrecognised is treated as metric.
+
+use serde::{Deserialize, Serialize};
+
+/// Pounds in one kilogram.
+pub const LB_PER_KG: f64 = 2.204_622_621_848_776;
+
+/// Measurement system requested through `?units=`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl UnitSystem {
+    /// Reads a `?units=` value, ignoring case; anything but "imperial" is metric.
+    pub fn from_param(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_lowercase()).as_deref() {
+            Some("imperial") => UnitSystem::Imperial,
+            _ => UnitSystem::Metric,
+        }
+    }
+
+    /// Unit weights are reported in under this system.
+    pub fn weight_unit(self) -> WeightUnit {
+        match self {
+            UnitSystem::Metric => WeightUnit::Kg,
+            UnitSystem::Imperial => WeightUnit::Lb,
+        }
+    }
+}
+
+/// Unit a single weight value is expressed in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WeightUnit {
+    #[default]
+    Kg,
+    Lb,
+}
+
+impl WeightUnit {
+    /// Reads a `weight_unit` value, ignoring case. "lb", "lbs" and "pound(s)" are pounds;
+    /// anything else, including a missing value, is kilograms.
+    pub fn from_param(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_lowercase()).as_deref() {
+            Some("lb" | "lbs" | "pound" | "pounds") => WeightUnit::Lb,
+            _ => WeightUnit::Kg,
+        }
+    }
+
+    /// Short label for headers and logs, e.g. "kg".
+    pub fn label(self) -> &'static str {
+        match self {
+            WeightUnit::Kg => "kg",
+            WeightUnit::Lb => "lb",
+        }
+    }
+
+    /// Converts `value` in this unit to kilograms for storage. Not rounded.
+    pub fn to_kg(self, value: f64) -> f64 {
+        match self {
+            WeightUnit::Kg => value,
+            WeightUnit::Lb => value / LB_PER_KG,
+        }
+    }
+
+    /// Converts a stored kilogram value to this unit. Converted values are rounded to
+    /// two decimals; kilograms pass through untouched.
+    pub fn from_kg(self, kg: f64) -> f64 {
+        match self {
+            WeightUnit::Kg => kg,
+            WeightUnit::Lb => round2(kg * LB_PER_KG),
+        }
+    }
+}
+
+/// Rounds to two decimal places, halves away from zero.
+pub fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
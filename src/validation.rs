@@ -0,0 +1,831 @@
+//! Input validation for API payloads.
+//!
+//! Validators collect every problem in a payload instead of stopping at the first one,
+//! so clients can highlight all invalid form fields after a single round trip.
+
+use crate::db_helpers::{parse_diet, parse_timestamp};
+use crate::domain::breeding::is_breeding_eligible;
+use crate::domain::nutrition::ProductionStage;
+use crate::domain::visualization::MapPosition;
+use crate::errors::{AppError, FieldError};
+use crate::models::{
+    BEHAVIORS, BehaviorObservation, BulkTransferPayload, Buyer, FamachaScore,
+    FeedFormulationRequest, GENETIC_TEST_TYPES, GEOFENCE_ALERT_ON, GeneticTest, Geofence,
+    GoatParams, GoatPayload, INTENSITIES, LeaveDecision, LeaveRequest, LocationPayload,
+    MAX_CAMPAIGN_GOATS, MAX_DIET_LEN, MAX_GOAT_NAME_LEN, MAX_HEALTH_STATUS_LEN, MAX_TAG_LEN,
+    MedicineItem, RestockPayload, SalePayload, ShowEntry, Supplier, TASK_PRIORITIES, TagPayload,
+    TimeEntry, TradePayload, VaccinationCampaign, WorkerPayTerms, WorkerTask,
+};
+use chrono::{NaiveDate, Utc};
+use tracing::debug;
+
+/// Types that can check their own contents before being written to the database.
+pub trait Validate {
+    /// Returns `AppError::Validation` listing every invalid field, or `Ok(())`.
+    fn validate(&self) -> Result<(), AppError>;
+}
+
+/// Pushes a `FieldError` for `field` onto `errors`.
+fn reject(errors: &mut Vec<FieldError>, field: &str, message: &str) {
+    errors.push(FieldError::new(field, message));
+}
+
+/// Rejects NaN, infinite, and negative amounts.
+fn check_non_negative(errors: &mut Vec<FieldError>, field: &str, value: f64) {
+    if !value.is_finite() {
+        reject(errors, field, "must be a finite number");
+    } else if value < 0.0 {
+        reject(errors, field, "must not be negative");
+    }
+}
+
+/// Rejects text that is blank or longer than `max` characters once trimmed. Returns
+/// whether it passed.
+fn check_length(errors: &mut Vec<FieldError>, field: &str, value: &str, max: usize) -> bool {
+    let length = value.trim().chars().count();
+    if length == 0 {
+        reject(errors, field, "must not be empty");
+    } else if length > max {
+        reject(
+            errors,
+            field,
+            &format!("must be at most {} characters", max),
+        );
+    }
+    (1..=max).contains(&length)
+}
+
+/// Checks a goat's fields, returning every problem as a `field: message` line; the
+/// same rules `GoatParams::validate` reports as `FieldError`s.
+pub fn validate_goat_params(params: &GoatParams) -> Result<(), Vec<String>> {
+    match params.validate() {
+        Ok(()) => Ok(()),
+        Err(AppError::Validation(errors)) => Err(errors
+            .into_iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect()),
+        Err(other) => Err(vec![other.to_string()]),
+    }
+}
+
+impl Validate for GoatParams {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        check_length(&mut errors, "name", &self.name, MAX_GOAT_NAME_LEN);
+        check_non_negative(&mut errors, "weight", self.weight);
+        check_non_negative(&mut errors, "cost", self.cost);
+        check_non_negative(&mut errors, "current_price", self.current_price);
+        if check_length(&mut errors, "diet", &self.diet, MAX_DIET_LEN)
+            && parse_diet(&self.diet).is_err()
+        {
+            reject(
+                &mut errors,
+                "diet",
+                "must be one of Hay, Pasture, Mixed, Concentrate",
+            );
+        }
+        check_length(
+            &mut errors,
+            "health_status",
+            &self.health_status,
+            MAX_HEALTH_STATUS_LEN,
+        );
+        if let Some(last_bred) = &self.last_bred {
+            if !is_breeding_eligible(&self.gender) {
+                reject(&mut errors, "last_bred", "wethers cannot be bred");
+            } else {
+                match NaiveDate::parse_from_str(last_bred, "%Y-%m-%d") {
+                    Err(_) => reject(
+                        &mut errors,
+                        "last_bred",
+                        "must be a date in YYYY-MM-DD format",
+                    ),
+                    Ok(date) if date > Utc::now().date_naive() => {
+                        reject(&mut errors, "last_bred", "must not be in the future")
+                    }
+                    Ok(_) => {}
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(name = %self.name, count = errors.len(), "Goat payload failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for GoatPayload {
+    /// Checks that every money field is present and in a supported currency, then the
+    /// goat itself, reporting all problems together.
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        for field in &self.omitted {
+            reject(&mut errors, field, "is required");
+        }
+        for (field, input) in &self.money {
+            if let Err(code) = input.resolve() {
+                reject(
+                    &mut errors,
+                    field,
+                    &format!("unsupported currency {}; expected INR", code),
+                );
+            }
+        }
+        match self.goat.validate() {
+            Ok(()) => {}
+            Err(AppError::Validation(goat_errors)) => errors.extend(goat_errors),
+            Err(other) => return Err(other),
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for ShowEntry {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if self.show_name.trim().is_empty() {
+            reject(&mut errors, "show_name", "must not be empty");
+        }
+        if NaiveDate::parse_from_str(&self.show_date, "%Y-%m-%d").is_err() {
+            reject(
+                &mut errors,
+                "show_date",
+                "must be a date in YYYY-MM-DD format",
+            );
+        }
+        if self.placement.is_some_and(|placement| placement < 1) {
+            reject(&mut errors, "placement", "must be 1 or greater");
+        }
+        if let Some(prize_amount) = self.prize_amount {
+            check_non_negative(&mut errors, "prize_amount", prize_amount);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(show = %self.show_name, count = errors.len(), "Show entry failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for FamachaScore {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if !(1..=5).contains(&self.score) {
+            reject(&mut errors, "score", "must be between 1 and 5");
+        }
+        if let Some(scored_at) = &self.scored_at {
+            if parse_timestamp("scored_at", scored_at).is_err() {
+                reject(
+                    &mut errors,
+                    "scored_at",
+                    "must be a timestamp (YYYY-MM-DD HH:MM:SS or RFC 3339)",
+                );
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(
+                goat_id = self.goat_id,
+                count = errors.len(),
+                "FAMACHA score failed validation"
+            );
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for MedicineItem {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            reject(&mut errors, "name", "must not be empty");
+        }
+        if self.unit.trim().is_empty() {
+            reject(&mut errors, "unit", "must not be empty");
+        }
+        check_non_negative(&mut errors, "stock_units", self.stock_units);
+        if let Some(cost) = self.cost_per_unit {
+            check_non_negative(&mut errors, "cost_per_unit", cost);
+        }
+        if let Some(expiry_date) = &self.expiry_date {
+            if NaiveDate::parse_from_str(expiry_date, "%Y-%m-%d").is_err() {
+                reject(
+                    &mut errors,
+                    "expiry_date",
+                    "must be a date in YYYY-MM-DD format",
+                );
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(name = %self.name, count = errors.len(), "Medicine item failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for RestockPayload {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if !self.units.is_finite() || self.units <= 0.0 {
+            reject(&mut errors, "units", "must be a positive number");
+        }
+        if let Some(cost) = self.cost_per_unit {
+            check_non_negative(&mut errors, "cost_per_unit", cost);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(?errors, "Restock failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for Supplier {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            reject(&mut errors, "name", "must not be empty");
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(name = %self.name, count = errors.len(), "Supplier failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for TradePayload {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if self.action.trim().is_empty() {
+            reject(&mut errors, "action", "must not be empty");
+        }
+        if let Some(price) = self.price {
+            check_non_negative(&mut errors, "price", price);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(action = %self.action, count = errors.len(), "Trade failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for Buyer {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            reject(&mut errors, "name", "must not be empty");
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(name = %self.name, count = errors.len(), "Buyer failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for SalePayload {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        let has_name = self
+            .buyer_name
+            .as_deref()
+            .is_some_and(|name| !name.trim().is_empty());
+        if self.buyer_id.is_none() && !has_name {
+            reject(
+                &mut errors,
+                "buyer_name",
+                "is required when buyer_id is not given",
+            );
+        }
+        check_non_negative(&mut errors, "price", self.price);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(?errors, "Sale failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+/// Rejects latitudes outside -90..=90 and longitudes outside -180..=180.
+fn check_coordinates(
+    errors: &mut Vec<FieldError>,
+    lat_field: &str,
+    lat: f64,
+    lon_field: &str,
+    lon: f64,
+) {
+    if !(-90.0..=90.0).contains(&lat) {
+        reject(errors, lat_field, "must be between -90 and 90");
+    }
+    if !(-180.0..=180.0).contains(&lon) {
+        reject(errors, lon_field, "must be between -180 and 180");
+    }
+}
+
+impl Validate for Geofence {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if self.name.trim().is_empty() {
+            reject(&mut errors, "name", "must not be empty");
+        }
+        check_coordinates(
+            &mut errors,
+            "lat_center",
+            self.lat_center,
+            "lon_center",
+            self.lon_center,
+        );
+        if !self.radius_m.is_finite() || self.radius_m <= 0.0 {
+            reject(&mut errors, "radius_m", "must be a positive number");
+        }
+        if !GEOFENCE_ALERT_ON.contains(&self.alert_on.as_str()) {
+            reject(
+                &mut errors,
+                "alert_on",
+                &format!("must be one of {}", GEOFENCE_ALERT_ON.join(", ")),
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(name = %self.name, count = errors.len(), "Geofence failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for LocationPayload {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        check_coordinates(&mut errors, "lat", self.lat, "lon", self.lon);
+        if let Some(recorded_at) = &self.recorded_at {
+            if parse_timestamp("recorded_at", recorded_at).is_err() {
+                reject(
+                    &mut errors,
+                    "recorded_at",
+                    "must be a timestamp (YYYY-MM-DD HH:MM:SS or RFC 3339)",
+                );
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(?errors, "Location failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for BehaviorObservation {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if !BEHAVIORS.contains(&self.behavior.as_str()) {
+            reject(
+                &mut errors,
+                "behavior",
+                &format!("must be one of {}", BEHAVIORS.join(", ")),
+            );
+        }
+        if !INTENSITIES.contains(&self.intensity.as_str()) {
+            reject(
+                &mut errors,
+                "intensity",
+                &format!("must be one of {}", INTENSITIES.join(", ")),
+            );
+        }
+        if let Some(recorded_at) = &self.recorded_at {
+            if parse_timestamp("recorded_at", recorded_at).is_err() {
+                reject(
+                    &mut errors,
+                    "recorded_at",
+                    "must be a timestamp (YYYY-MM-DD HH:MM:SS or RFC 3339)",
+                );
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(
+                goat_id = self.goat_id,
+                count = errors.len(),
+                "Behavior observation failed validation"
+            );
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for WorkerTask {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if self.task.trim().is_empty() {
+            reject(&mut errors, "task", "must not be empty");
+        }
+        if !TASK_PRIORITIES.contains(&self.priority.as_str()) {
+            reject(
+                &mut errors,
+                "priority",
+                &format!("must be one of {}", TASK_PRIORITIES.join(", ")),
+            );
+        }
+        if let Some(due_at) = &self.due_at {
+            if parse_timestamp("due_at", due_at).is_err() {
+                reject(
+                    &mut errors,
+                    "due_at",
+                    "must be a timestamp (YYYY-MM-DD HH:MM:SS or RFC 3339)",
+                );
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(
+                worker_id = self.worker_id,
+                count = errors.len(),
+                "Worker task failed validation"
+            );
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for LeaveRequest {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        let from = NaiveDate::parse_from_str(self.from_date.trim(), "%Y-%m-%d");
+        let to = NaiveDate::parse_from_str(self.to_date.trim(), "%Y-%m-%d");
+        if from.is_err() {
+            reject(
+                &mut errors,
+                "from_date",
+                "must be a date in YYYY-MM-DD format",
+            );
+        }
+        if to.is_err() {
+            reject(
+                &mut errors,
+                "to_date",
+                "must be a date in YYYY-MM-DD format",
+            );
+        }
+        if let (Ok(from), Ok(to)) = (from, to) {
+            if to < from {
+                reject(&mut errors, "to_date", "must not be before from_date");
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(
+                worker_id = self.worker_id,
+                count = errors.len(),
+                "Leave request failed validation"
+            );
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for LeaveDecision {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if !["approved", "rejected"].contains(&self.status.as_str()) {
+            reject(&mut errors, "status", "must be approved or rejected");
+        }
+        if self.decided_by.trim().is_empty() {
+            reject(&mut errors, "decided_by", "must not be empty");
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(count = errors.len(), "Leave decision failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for TimeEntry {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if NaiveDate::parse_from_str(self.work_date.trim(), "%Y-%m-%d").is_err() {
+            reject(
+                &mut errors,
+                "work_date",
+                "must be a date in YYYY-MM-DD format",
+            );
+        }
+        if !self.hours.is_finite() || self.hours <= 0.0 || self.hours > 24.0 {
+            reject(&mut errors, "hours", "must be more than 0 and at most 24");
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(?errors, "Time entry failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for WorkerPayTerms {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if self
+            .hourly_rate
+            .is_some_and(|rate| !rate.is_finite() || rate < 0.0)
+        {
+            reject(&mut errors, "hourly_rate", "must not be negative");
+        }
+        if self
+            .overtime_threshold_hours
+            .is_some_and(|hours| !hours.is_finite() || hours < 0.0)
+        {
+            reject(
+                &mut errors,
+                "overtime_threshold_hours",
+                "must not be negative",
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(?errors, "Pay terms failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for BulkTransferPayload {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if self.goat_ids.is_empty() {
+            reject(&mut errors, "goat_ids", "must list at least one goat");
+        }
+        if self.from_space_id == self.to_space_id {
+            reject(&mut errors, "to_space_id", "must differ from from_space_id");
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(?errors, "Bulk transfer payload failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for VaccinationCampaign {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if self.vaccine.id.is_none() && self.vaccine.name.trim().is_empty() {
+            reject(&mut errors, "vaccine.name", "must not be empty");
+        }
+        if self.goat_ids.is_empty() {
+            reject(&mut errors, "goat_ids", "must list at least one goat");
+        } else if self.goat_ids.len() > MAX_CAMPAIGN_GOATS {
+            reject(
+                &mut errors,
+                "goat_ids",
+                &format!("must list at most {} goats", MAX_CAMPAIGN_GOATS),
+            );
+        }
+        let administered = NaiveDate::parse_from_str(self.administered_on.trim(), "%Y-%m-%d");
+        let next_due = NaiveDate::parse_from_str(self.next_due_on.trim(), "%Y-%m-%d");
+        if administered.is_err() {
+            reject(
+                &mut errors,
+                "administered_on",
+                "must be a date in YYYY-MM-DD format",
+            );
+        }
+        if next_due.is_err() {
+            reject(
+                &mut errors,
+                "next_due_on",
+                "must be a date in YYYY-MM-DD format",
+            );
+        }
+        if let (Ok(administered), Ok(next_due)) = (administered, next_due) {
+            if next_due <= administered {
+                reject(&mut errors, "next_due_on", "must be after administered_on");
+            }
+        }
+        if !self.dose_units.is_finite() || self.dose_units <= 0.0 {
+            reject(&mut errors, "dose_units", "must be a positive number");
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(?errors, "Vaccination campaign failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for TagPayload {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        let tag = self.tag.trim();
+        if tag.is_empty() {
+            reject(&mut errors, "tag", "must not be empty");
+        } else if tag.chars().count() > MAX_TAG_LEN {
+            reject(
+                &mut errors,
+                "tag",
+                &format!("must be at most {} characters", MAX_TAG_LEN),
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(?errors, "Tag payload failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for GeneticTest {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if !GENETIC_TEST_TYPES.contains(&self.test_type.as_str()) {
+            reject(
+                &mut errors,
+                "test_type",
+                &format!("must be one of {}", GENETIC_TEST_TYPES.join(", ")),
+            );
+        }
+        if NaiveDate::parse_from_str(&self.test_date, "%Y-%m-%d").is_err() {
+            reject(
+                &mut errors,
+                "test_date",
+                "must be a date in YYYY-MM-DD format",
+            );
+        }
+        if !self.result_json.is_object() {
+            reject(&mut errors, "result_json", "must be a JSON object");
+        }
+        if let Some(url) = &self.report_url {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                reject(&mut errors, "report_url", "must be an http(s) URL");
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(
+                goat_id = self.goat_id,
+                ?errors,
+                "Genetic test failed validation"
+            );
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for FeedFormulationRequest {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if !self.goat_weight_kg.is_finite() || self.goat_weight_kg <= 0.0 {
+            reject(&mut errors, "goat_weight_kg", "must be a positive number");
+        }
+        if let Err(message) = self.production_stage.parse::<ProductionStage>() {
+            reject(&mut errors, "production_stage", &message);
+        }
+        if self.available_feeds.is_empty() {
+            reject(
+                &mut errors,
+                "available_feeds",
+                "must list at least one feed",
+            );
+        }
+        for (i, feed) in self.available_feeds.iter().enumerate() {
+            let field = |name: &str| format!("available_feeds[{}].{}", i, name);
+            if feed.name.trim().is_empty() {
+                reject(&mut errors, &field("name"), "must not be empty");
+            }
+            if !(0.0..=100.0).contains(&feed.crude_protein_pct) {
+                reject(
+                    &mut errors,
+                    &field("crude_protein_pct"),
+                    "must be between 0 and 100",
+                );
+            }
+            check_non_negative(
+                &mut errors,
+                &field("energy_mj_per_kg"),
+                feed.energy_mj_per_kg,
+            );
+            check_non_negative(&mut errors, &field("cost_per_kg"), feed.cost_per_kg);
+            if !(feed.max_inclusion_pct > 0.0 && feed.max_inclusion_pct <= 100.0) {
+                reject(
+                    &mut errors,
+                    &field("max_inclusion_pct"),
+                    "must be above 0 and at most 100",
+                );
+            }
+        }
+        let total_inclusion: f64 = self
+            .available_feeds
+            .iter()
+            .map(|feed| feed.max_inclusion_pct)
+            .sum();
+        if !self.available_feeds.is_empty() && total_inclusion < 100.0 {
+            reject(
+                &mut errors,
+                "available_feeds",
+                "max_inclusion_pct must add up to at least 100",
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(?errors, "Feed formulation request failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
+
+impl Validate for MapPosition {
+    fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        check_non_negative(&mut errors, "x", self.x);
+        check_non_negative(&mut errors, "y", self.y);
+        for (field, value) in [("width", self.width), ("height", self.height)] {
+            if !value.is_finite() {
+                reject(&mut errors, field, "must be a finite number");
+            } else if value <= 0.0 {
+                reject(&mut errors, field, "must be positive");
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            debug!(?errors, "Map position failed validation");
+            Err(AppError::Validation(errors))
+        }
+    }
+}
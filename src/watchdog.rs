@@ -0,0 +1,111 @@
+//! Interrupts database work that outlives its deadline.
+//!
+//! Every connection handed out by `DbPool` is registered here with the time it must be
+//! given back by. A single background thread sleeps until the earliest deadline and calls
+//! `sqlite3_interrupt` on connections still checked out past theirs, so the running query
+//! fails with `SQLITE_INTERRUPT` and the connection can return to the pool.
+
+use rusqlite::InterruptHandle;
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+#[derive(Default)]
+struct Deadlines {
+    next_id: u64,
+    entries: HashMap<u64, (Instant, InterruptHandle)>,
+}
+
+#[derive(Default)]
+struct Shared {
+    deadlines: Mutex<Deadlines>,
+    changed: Condvar,
+}
+
+/// Deadline registration for one checked-out connection; dropping it disarms the
+/// interrupt.
+pub struct WatchGuard {
+    id: u64,
+    shared: Arc<Shared>,
+}
+
+impl Drop for WatchGuard {
+    fn drop(&mut self) {
+        // Interrupts are sent with this lock held, so once the entry is gone no interrupt
+        // can reach the connection after it is back in the pool.
+        self.shared
+            .deadlines
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .remove(&self.id);
+    }
+}
+
+/// Arms an interrupt for `handle`'s connection `timeout` from now.
+pub fn watch(handle: InterruptHandle, timeout: Duration) -> WatchGuard {
+    let shared = watchdog();
+    let mut deadlines = shared.deadlines.lock().unwrap_or_else(|e| e.into_inner());
+    let id = deadlines.next_id;
+    deadlines.next_id += 1;
+    deadlines
+        .entries
+        .insert(id, (Instant::now() + timeout, handle));
+    drop(deadlines);
+    shared.changed.notify_one();
+    WatchGuard {
+        id,
+        shared: Arc::clone(shared),
+    }
+}
+
+/// The process-wide watchdog, started on first use.
+fn watchdog() -> &'static Arc<Shared> {
+    static WATCHDOG: OnceLock<Arc<Shared>> = OnceLock::new();
+    WATCHDOG.get_or_init(|| {
+        let shared = Arc::new(Shared::default());
+        let worker = Arc::clone(&shared);
+        std::thread::Builder::new()
+            .name("query-watchdog".to_string())
+            .spawn(move || run(&worker))
+            .expect("Failed to start the query watchdog thread");
+        shared
+    })
+}
+
+fn run(shared: &Shared) {
+    let mut deadlines = shared.deadlines.lock().unwrap_or_else(|e| e.into_inner());
+    loop {
+        let now = Instant::now();
+        deadlines.entries.retain(|id, (deadline, handle)| {
+            if *deadline > now {
+                return true;
+            }
+            warn!(
+                checkout = id,
+                "Query timeout reached; interrupting connection"
+            );
+            handle.interrupt();
+            false
+        });
+        let next = deadlines
+            .entries
+            .values()
+            .map(|(deadline, _)| *deadline)
+            .min();
+        deadlines = match next {
+            Some(deadline) => {
+                shared
+                    .changed
+                    .wait_timeout(deadlines, deadline.saturating_duration_since(now))
+                    .unwrap_or_else(|e| e.into_inner())
+                    .0
+            }
+            None => shared
+                .changed
+                .wait(deadlines)
+                .unwrap_or_else(|e| e.into_inner()),
+        };
+    }
+}
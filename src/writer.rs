@@ -0,0 +1,250 @@
+//! Single writer for database writes.
+//!
+//! SQLite allows one writer at a time, so request handlers writing through their own
+//! pooled connections queue up on the file lock and spin on `SQLITE_BUSY`. The writer
+//! instead owns one connection on a dedicated thread and applies `WriteCommand`s from a
+//! channel one after another, replying to each sender on a oneshot channel. Handlers
+//! await the reply without tying up an Actix worker, and commands take effect in the
+//! order they were sent. Goat writes have commands of their own; every other handler
+//! write reaches the writer as a `WriteCommand::Transaction` via `DbPool::write`.
+//!
+//! The writer runs on its own OS thread rather than a tokio task because rusqlite calls
+//! block; on a runtime thread they would stall every task scheduled there.
+
+use crate::db::write_with_retry;
+use crate::errors::AppError;
+use crate::models::{Goat, GoatParams};
+use crate::services::{delete_goat_in, insert_goat_in, update_goat_in};
+use crate::watchdog::watch;
+use r2d2::{Pool, PooledConnection};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{Connection, Transaction};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, error, info, warn};
+
+/// Where the writer sends the outcome of one command.
+pub type Reply<T> = oneshot::Sender<Result<T, AppError>>;
+
+/// A queued transaction: given the writer's connection, or the error that kept it from
+/// getting one, it runs its write and replies to its sender itself.
+pub type TransactionWork = Box<dyn FnOnce(Result<&mut Connection, AppError>) + Send>;
+
+/// A write applied by the writer, with the channel its outcome goes back on.
+pub enum WriteCommand {
+    /// Stores a new goat and replies with it as stored.
+    InsertGoat {
+        goat: GoatParams,
+        reply: Reply<Goat>,
+    },
+    /// Overwrites a live goat, at `expected_version` if set, and replies with it as stored.
+    UpdateGoat {
+        goat_id: i64,
+        goat: GoatParams,
+        expected_version: Option<u32>,
+        reply: Reply<Goat>,
+    },
+    /// Soft-deletes a live goat and replies with it as it was.
+    DeleteGoat { goat_id: i64, reply: Reply<Goat> },
+    /// Runs an arbitrary write transaction; see `Writer::transaction`.
+    Transaction { work: TransactionWork },
+}
+
+impl WriteCommand {
+    /// Name used in logs.
+    fn name(&self) -> &'static str {
+        match self {
+            WriteCommand::InsertGoat { .. } => "InsertGoat",
+            WriteCommand::UpdateGoat { .. } => "UpdateGoat",
+            WriteCommand::DeleteGoat { .. } => "DeleteGoat",
+            WriteCommand::Transaction { .. } => "Transaction",
+        }
+    }
+}
+
+/// Handle for sending commands to a writer; see `DbPool::writer`. The writer thread
+/// exits once every handle is dropped.
+#[derive(Clone)]
+pub struct Writer {
+    commands: mpsc::UnboundedSender<WriteCommand>,
+}
+
+impl Writer {
+    /// Starts a writer thread that checks its connection out of `pool` on the first
+    /// command and keeps it. Each command's queries are interrupted after
+    /// `query_timeout`.
+    ///
+    /// # Panics
+    /// Panics if the OS refuses to start the thread.
+    pub(crate) fn spawn(pool: Arc<Pool<SqliteConnectionManager>>, query_timeout: Duration) -> Self {
+        let (commands, receiver) = mpsc::unbounded_channel();
+        std::thread::Builder::new()
+            .name("db-writer".to_string())
+            .spawn(move || run(&pool, receiver, query_timeout))
+            .expect("Failed to start the database writer thread");
+        info!("Database writer started");
+        Self { commands }
+    }
+
+    /// Queues a new goat. The command is queued when this is called, not when the
+    /// returned future is first polled, so calls take effect in call order.
+    ///
+    /// # Errors
+    /// As `GoatService::insert`, or `AppError::Internal` if the writer has stopped.
+    pub fn insert_goat(
+        &self,
+        goat: GoatParams,
+    ) -> impl Future<Output = Result<Goat, AppError>> + use<> {
+        self.send(|reply| WriteCommand::InsertGoat { goat, reply })
+    }
+
+    /// Queues an overwrite of a live goat; see `insert_goat` for ordering.
+    ///
+    /// # Errors
+    /// As `GoatService::update`, or `AppError::Internal` if the writer has stopped.
+    pub fn update_goat(
+        &self,
+        goat_id: i64,
+        goat: GoatParams,
+        expected_version: Option<u32>,
+    ) -> impl Future<Output = Result<Goat, AppError>> + use<> {
+        self.send(|reply| WriteCommand::UpdateGoat {
+            goat_id,
+            goat,
+            expected_version,
+            reply,
+        })
+    }
+
+    /// Queues a soft delete of a live goat; see `insert_goat` for ordering.
+    ///
+    /// # Errors
+    /// As `GoatService::delete`, or `AppError::Internal` if the writer has stopped.
+    pub fn delete_goat(
+        &self,
+        goat_id: i64,
+    ) -> impl Future<Output = Result<Goat, AppError>> + use<> {
+        self.send(|reply| WriteCommand::DeleteGoat { goat_id, reply })
+    }
+
+    /// Queues `f` to run in an immediate transaction of its own, retried while the
+    /// database is busy as `write_with_retry` does; see `insert_goat` for ordering.
+    ///
+    /// # Errors
+    /// Whatever `f` returns, a database error, or `AppError::Internal` if the writer has
+    /// stopped.
+    pub fn transaction<T, F>(
+        &self,
+        mut f: F,
+    ) -> impl Future<Output = Result<T, AppError>> + use<T, F>
+    where
+        T: Send + 'static,
+        F: FnMut(&Transaction) -> Result<T, AppError> + Send + 'static,
+    {
+        self.send(|reply| WriteCommand::Transaction {
+            work: Box::new(move |conn: Result<&mut Connection, AppError>| {
+                let _ = reply.send(conn.and_then(|conn| write_with_retry(conn, &mut f)));
+            }),
+        })
+    }
+
+    /// Queues the command built by `command` around a fresh reply channel and returns a
+    /// future resolving to the writer's reply.
+    fn send<T>(
+        &self,
+        command: impl FnOnce(Reply<T>) -> WriteCommand,
+    ) -> impl Future<Output = Result<T, AppError>> + use<T> {
+        let (reply, outcome) = oneshot::channel();
+        let sent = self.commands.send(command(reply));
+        async move {
+            if sent.is_err() {
+                error!("Database writer has stopped; write not applied");
+                return Err(AppError::Internal(
+                    "Database writer is not running".to_string(),
+                ));
+            }
+            outcome
+                .await
+                .map_err(|_| AppError::Internal("Database writer dropped the write".to_string()))?
+        }
+    }
+}
+
+/// The writer thread: applies commands in arrival order until every handle is gone.
+fn run(
+    pool: &Pool<SqliteConnectionManager>,
+    mut receiver: mpsc::UnboundedReceiver<WriteCommand>,
+    query_timeout: Duration,
+) {
+    let mut conn: Option<PooledConnection<SqliteConnectionManager>> = None;
+    while let Some(command) = receiver.blocking_recv() {
+        let name = command.name();
+        if conn.is_none() {
+            match pool.get() {
+                Ok(checked_out) => conn = Some(checked_out),
+                Err(e) => {
+                    warn!(command = name, error = %e, "Writer could not get a connection");
+                    fail(command, AppError::PoolError(e));
+                    continue;
+                }
+            }
+        }
+        let Some(conn) = conn.as_mut() else {
+            continue;
+        };
+        let _watch = watch(conn.get_interrupt_handle(), query_timeout);
+        debug!(command = name, "Applying write command");
+        apply(conn, command);
+    }
+    info!("Database writer stopped");
+}
+
+/// Applies one command in its own transaction and replies with the outcome. A sender
+/// that has stopped waiting is not an error; the write still stands.
+fn apply(conn: &mut PooledConnection<SqliteConnectionManager>, command: WriteCommand) {
+    match command {
+        WriteCommand::InsertGoat { goat, reply } => {
+            let result = write_with_retry(conn, |tx| insert_goat_in(tx, &goat));
+            if let Ok(created) = &result {
+                info!(goat_id = created.id(), "Goat inserted by writer");
+            }
+            let _ = reply.send(result);
+        }
+        WriteCommand::UpdateGoat {
+            goat_id,
+            goat,
+            expected_version,
+            reply,
+        } => {
+            let result = write_with_retry(conn, |tx| {
+                update_goat_in(tx, goat_id, &goat, expected_version)
+            });
+            if result.is_ok() {
+                info!(goat_id, "Goat updated by writer");
+            }
+            let _ = reply.send(result);
+        }
+        WriteCommand::DeleteGoat { goat_id, reply } => {
+            let result = write_with_retry(conn, |tx| delete_goat_in(tx, goat_id));
+            if result.is_ok() {
+                info!(goat_id, "Goat deleted by writer");
+            }
+            let _ = reply.send(result);
+        }
+        WriteCommand::Transaction { work } => work(Ok(&mut **conn)),
+    }
+}
+
+/// Replies to `command` with `err` without applying it.
+fn fail(command: WriteCommand, err: AppError) {
+    match command {
+        WriteCommand::InsertGoat { reply, .. }
+        | WriteCommand::UpdateGoat { reply, .. }
+        | WriteCommand::DeleteGoat { reply, .. } => {
+            let _ = reply.send(Err(err));
+        }
+        WriteCommand::Transaction { work } => work(Err(err)),
+    }
+}
@@ -0,0 +1,187 @@
+//! Property tests for the `POST /goats` insert path, `validate_and_insert_goat`: arbitrary
+//! goats never panic it, and each class of invalid input is always rejected before
+//! anything is written.
+
+mod helpers;
+
+use backend::db::DbPool;
+use backend::db_helpers::{BUILTIN_BREEDS, parse_diet, str_to_breed, str_to_gender};
+use backend::errors::AppError;
+use backend::handlers::goats::validate_and_insert_goat;
+use backend::models::GoatParams;
+use helpers::{seed_goat, setup_test_db};
+use proptest::prelude::*;
+use proptest::sample::select;
+use std::sync::OnceLock;
+
+/// Name of the goat seeded into `pool` before any case runs.
+const SEEDED_NAME: &str = "Seeded Nanny";
+
+/// One database shared by every case; inserts from the never-panics property pile up in
+/// it, which only adds name conflicts to what that property exercises.
+fn pool() -> &'static DbPool {
+    static POOL: OnceLock<DbPool> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let pool = setup_test_db();
+        seed_goat(&pool, SEEDED_NAME, "Beetal");
+        pool
+    })
+}
+
+fn goat_count() -> i64 {
+    let conn = pool().get_conn().unwrap();
+    conn.query_row("SELECT COUNT(*) FROM goats", [], |r| r.get(0))
+        .unwrap()
+}
+
+/// Asserts `result` is a validation failure naming `field`.
+fn assert_rejects(result: Result<i64, AppError>, field: &str) -> Result<(), TestCaseError> {
+    match result {
+        Err(AppError::Validation(errors)) => {
+            prop_assert!(
+                errors.iter().any(|e| e.field == field),
+                "expected an error on {}, got {:?}",
+                field,
+                errors
+            );
+            Ok(())
+        }
+        other => Err(TestCaseError::fail(format!(
+            "expected a validation error on {}, got {:?}",
+            field, other
+        ))),
+    }
+}
+
+prop_compose! {
+    /// Any goat at all: arbitrary text and numbers, including NaN and infinities.
+    fn goat_params_arb()(
+        name in any::<String>(),
+        breed in select(BUILTIN_BREEDS.to_vec()),
+        gender in select(vec!["Male", "Female", "Wether"]),
+        offspring in any::<i32>(),
+        cost in any::<f64>(),
+        weight in any::<f64>(),
+        current_price in any::<f64>(),
+        diet in any::<String>(),
+        last_bred in proptest::option::of(any::<String>()),
+        health_status in any::<String>(),
+    ) -> GoatParams {
+        GoatParams {
+            breed: str_to_breed(breed).unwrap(),
+            name,
+            gender: str_to_gender(gender).unwrap(),
+            offspring,
+            cost,
+            weight,
+            current_price,
+            diet,
+            last_bred,
+            health_status,
+            vaccinations: Vec::new(),
+            diseases: Vec::new(),
+        }
+    }
+}
+
+prop_compose! {
+    /// A goat that passes validation; the invalid classes below break one field of it.
+    fn valid_goat()(
+        base in goat_params_arb(),
+        name in "[A-Za-z][A-Za-z ]{0,20}",
+        cost in 0.0..1e6f64,
+        weight in 0.0..200.0f64,
+        current_price in 0.0..1e6f64,
+        diet in select(vec!["Hay", "Pasture", "Mixed", "Concentrate"]),
+        health_status in "[A-Za-z][A-Za-z ]{0,20}",
+    ) -> GoatParams {
+        GoatParams {
+            name,
+            cost,
+            weight,
+            current_price,
+            diet: diet.to_string(),
+            last_bred: None,
+            health_status,
+            ..base
+        }
+    }
+}
+
+fn non_finite() -> impl Strategy<Value = f64> {
+    select(vec![f64::NAN, f64::INFINITY, f64::NEG_INFINITY])
+}
+
+proptest! {
+    #[test]
+    fn prop_arbitrary_goats_never_panic(goat in goat_params_arb()) {
+        let _ = validate_and_insert_goat(pool(), goat);
+    }
+
+    #[test]
+    fn prop_negative_weight_is_rejected(
+        goat in valid_goat(),
+        weight in f64::MIN..-f64::MIN_POSITIVE,
+    ) {
+        let before = goat_count();
+        assert_rejects(validate_and_insert_goat(pool(), GoatParams { weight, ..goat }), "weight")?;
+        prop_assert_eq!(goat_count(), before);
+    }
+
+    #[test]
+    fn prop_blank_name_is_rejected(goat in valid_goat(), name in "[ \t\n]{0,8}") {
+        assert_rejects(validate_and_insert_goat(pool(), GoatParams { name, ..goat }), "name")?;
+    }
+
+    #[test]
+    fn prop_non_finite_amounts_are_rejected(
+        goat in valid_goat(),
+        value in non_finite(),
+        field in select(vec!["cost", "weight", "current_price"]),
+    ) {
+        let goat = match field {
+            "cost" => GoatParams { cost: value, ..goat },
+            "weight" => GoatParams { weight: value, ..goat },
+            _ => GoatParams { current_price: value, ..goat },
+        };
+        assert_rejects(validate_and_insert_goat(pool(), goat), field)?;
+    }
+
+    #[test]
+    fn prop_overlong_name_is_rejected(goat in valid_goat(), name in "[A-Za-z]{101,140}") {
+        assert_rejects(validate_and_insert_goat(pool(), GoatParams { name, ..goat }), "name")?;
+    }
+
+    #[test]
+    fn prop_unknown_diet_is_rejected(
+        goat in valid_goat(),
+        diet in any::<String>().prop_filter("known diet", |d| parse_diet(d).is_err()),
+    ) {
+        assert_rejects(validate_and_insert_goat(pool(), GoatParams { diet, ..goat }), "diet")?;
+    }
+
+    #[test]
+    fn prop_malformed_last_bred_is_rejected(
+        goat in valid_goat(),
+        last_bred in "[0-9/ -]{0,12}".prop_filter("valid date", |s| {
+            chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_err()
+        }),
+    ) {
+        let result = validate_and_insert_goat(
+            pool(),
+            GoatParams { last_bred: Some(last_bred), ..goat },
+        );
+        assert_rejects(result, "last_bred")?;
+    }
+
+    #[test]
+    fn prop_taken_name_conflicts_in_any_case(goat in valid_goat(), upper in any::<bool>()) {
+        let name = if upper {
+            SEEDED_NAME.to_uppercase()
+        } else {
+            SEEDED_NAME.to_lowercase()
+        };
+        let result = validate_and_insert_goat(pool(), GoatParams { name, ..goat });
+        prop_assert!(matches!(result, Err(AppError::Conflict(_))), "got {:?}", result);
+    }
+}
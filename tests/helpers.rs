@@ -0,0 +1,55 @@
+//! Shared setup for integration tests: private in-memory databases and seed data, so no
+//! test depends on a pre-populated database file or on the order tests run in.
+
+use backend::db::{DbPool, SCHEMA_SQL};
+use backend::models::GoatParams;
+use serde_json::json;
+
+/// Creates an empty in-memory database with the full schema applied. Each call gets its
+/// own database, which lives as long as the returned pool.
+pub fn setup_test_db() -> DbPool {
+    let pool = DbPool::in_memory().expect("Failed to open in-memory database");
+    pool.get_conn()
+        .expect("Failed to get connection")
+        .execute_batch(SCHEMA_SQL)
+        .expect("Failed to apply schema");
+    pool
+}
+
+/// Inserts a healthy, unvaccinated female goat and returns its id.
+pub fn seed_goat(pool: &DbPool, name: &str, breed: &str) -> i64 {
+    let goat: GoatParams = serde_json::from_value(json!({
+        "breed": breed,
+        "name": name,
+        "gender": "Female",
+        "offspring": 0,
+        "cost": 100.0,
+        "weight": 40.0,
+        "current_price": 120.0,
+        "diet": "Hay",
+        "last_bred": null,
+        "health_status": "healthy",
+        "vaccinations": [],
+        "diseases": []
+    }))
+    .expect("Invalid seed goat");
+    let conn = pool.get_conn().expect("Failed to get connection");
+    DbPool::insert_goat(&conn, &goat).expect("Failed to seed goat")
+}
+
+/// Boundary separating the parts of bodies built by `multipart_file`.
+pub const MULTIPART_BOUNDARY: &str = "yagi-test-boundary";
+
+/// A `multipart/form-data` body, split by `MULTIPART_BOUNDARY`, carrying one file part
+/// named `file`.
+pub fn multipart_file(filename: &str, content_type: &str, content: &[u8]) -> Vec<u8> {
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\n\
+         Content-Type: {}\r\n\r\n",
+        MULTIPART_BOUNDARY, filename, content_type
+    )
+    .into_bytes();
+    body.extend_from_slice(content);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", MULTIPART_BOUNDARY).as_bytes());
+    body
+}
@@ -1,19 +1,173 @@
+use std::collections::HashMap;
+use std::future::Future;
 use std::io::stdin;
+use std::time::{Duration, Instant};
 
-use actix_web::{App, test, web};
-use backend::db::DbPool;
-use backend::handlers::goats::{add_goat, delete_goat, get_goats, update_goat};
+use actix_web::{App, HttpResponse, middleware, test, web};
+use backend::cache::GoatListCache;
+use backend::config::{JournalMode, SynchronousMode};
+use backend::db::{
+    CatalogEntry, DbPool, GOAT_COLUMNS, GOAT_DOCUMENT_VERSION, PoolOptions, SCHEMA_SQL,
+    build_goat_search_query, check_goat_columns, ensure_goat_exists, get_or_insert_disease,
+    get_or_insert_vaccine, load_goat_details, timed_with,
+};
+use backend::db_helpers::{
+    BUILTIN_BREEDS, breed_to_str, diet_to_str, gender_to_str, parse_diet, str_to_breed,
+    str_to_diet, str_to_gender,
+};
+use backend::domain::anomaly::{RollingStats, is_anomaly};
+use backend::domain::breeding::{Parents, Pedigree, kinship, shares_close_ancestor};
+use backend::domain::finance::compute_depreciation;
+use backend::domain::geo::{FenceCrossing, alerts_on, fence_crossing, haversine_distance};
+use backend::domain::nutrition::{
+    FeedIngredient, FormulaLine, ProductionStage, formulate_feed, requirement,
+};
+use backend::domain::payroll::{PayTerms, compute_monthly_pay, month_bounds};
+use backend::domain::prediction::{Confidence, SECS_PER_DAY, linear_regression, predict_weight};
+use backend::domain::sale::is_sale_ready;
+use backend::domain::scheduling::{ScheduleSlot, optimize_vaccination_schedule};
+use backend::domain::simulation::{simulate_population, simulate_population_with};
+use backend::domain::trade_log::{compute_trade_hash, trade_payload};
+use backend::domain::visualization::occupancy_color;
+use backend::errors::AppError;
+use backend::errors::{
+    ErrorBody, FieldError, POOL_RETRY_AFTER_SECS, json_config, path_config, query_config,
+};
+use backend::handlers::activity::get_activity;
+use backend::handlers::admin::{
+    export_full_archive, export_snapshot, get_data_quality, import_snapshot, list_jobs,
+    prune_sensor_readings, reclassify_wethers, set_maintenance,
+};
+use backend::handlers::batch::run_batch;
+use backend::handlers::behavior::{
+    add_behavior_observation, get_behavior_history, get_concerning_behavior,
+};
+use backend::handlers::breeding::{plan_breeding, set_goat_pedigree};
+use backend::handlers::buyers::{
+    create_buyer, delete_buyer, get_buyer_purchases, list_buyers, update_buyer,
+};
+use backend::handlers::catalog::{
+    add_vaccine, list_breeds, list_diseases, list_vaccines, merge_vaccines, register_breed,
+};
+use backend::handlers::cohorts::{
+    add_cohort_goat, create_cohort, get_cohort_goats, get_cohort_stats, remove_cohort_goat,
+};
+use backend::handlers::documents::{get_document, list_goat_documents, upload_goat_document};
+use backend::handlers::equipment::get_equipment_depreciation;
+use backend::handlers::famacha::{
+    add_famacha_score, get_famacha_action_needed, get_famacha_history,
+};
+use backend::handlers::genetics::{add_genetic_test, get_genetic_tests, get_genetic_trait_summary};
+use backend::handlers::geofences::{
+    create_geofence, delete_geofence, get_location_alerts, list_geofences, record_goat_location,
+};
+use backend::handlers::goats::{
+    add_goat, add_goat_tag, delete_goat, delete_goat_by_id, export_goat_document,
+    get_attention_goats, get_compact_goats, get_goat, get_goat_changes, get_goat_diseases,
+    get_goat_profit_analysis, get_goat_qrcode, get_goat_timeline, get_goat_vaccines, get_goats,
+    get_random_goats, get_sale_ready_goats, get_weight_prediction, import_goat_document,
+    import_goats, import_goats_csv, patch_goat, remove_goat_tag, search_goats, sell_goat,
+    update_goat, update_goat_by_id,
+};
+use backend::handlers::health::health;
+use backend::handlers::inventory::{
+    add_medicine, dispense_medicine, get_expiring_medicines, get_low_stock_medicines,
+    restock_medicine,
+};
+use backend::handlers::leave_requests::{
+    create_leave_request, decide_leave_request, list_leave_requests,
+};
+use backend::handlers::payroll::{
+    add_time_entry, get_payroll_summary, get_worker_payroll, set_worker_pay_terms,
+};
+use backend::handlers::reports::{
+    get_census, get_daily_report, get_health_trends, get_latest_monthly_report, get_monthly_report,
+};
+use backend::handlers::sensors::{
+    MAX_RAW_READINGS, MAX_READING_BUCKETS, add_sensor_reading, get_sensor_readings,
+};
+use backend::handlers::shows::{add_show_entry, get_show_champions, get_show_entries};
+use backend::handlers::spaces::{
+    assign_goat, assign_sensor, bulk_transfer, get_farm_map, get_farm_map_svg, get_goat_movements,
+    get_space_environment, get_space_goats, get_space_residents, update_space_position,
+};
+use backend::handlers::stats::{
+    get_breed_weight_gain, get_disease_trends, get_financial_stats, get_profitability,
+    get_vaccination_coverage, simulate_population_growth,
+};
+use backend::handlers::suppliers::{
+    create_supplier, delete_supplier, get_supplier, get_supplier_purchases, list_suppliers,
+    update_supplier,
+};
+use backend::handlers::tasks::{
+    complete_task, create_task, delete_task, get_task, list_tasks, update_task,
+};
+use backend::handlers::tools::feed_formulation;
+use backend::handlers::trade_log::{add_trade, get_goat_trade_log, verify_trade_log};
+use backend::handlers::vaccines::{get_vaccination_schedule, run_vaccination_campaign};
+use backend::handlers::workers::{
+    add_worker_task, complete_worker_task, get_worker_performance, get_worker_tasks,
+};
+use backend::jobs::{
+    Job, JobRegistry, OVERDUE_VACCINATION_ALERT, Scheduler, WalCheckpointJob,
+    scan_overdue_vaccinations,
+};
+use backend::middleware::{
+    API_KEY_HEADER, LOG_BODY_MAX_BYTES, body_logger, rate_limit, read_only_guard,
+};
+use backend::models::{
+    ActivityEntry, BatchResponse, BehaviorObservation, BreedStats, BreedWeightGain,
+    BulkTransferSummary, Buyer, BuyerPurchase, CensusReport, Cohort, CohortStats, CustomBreed,
+    DailyReport, DataQualityReport, Diet, DiseaseTrendPoint, FamachaScore, FinancialStats,
+    GeneticTest, GeneticTraitCount, Geofence, Goat, GoatChanges, GoatDocument, GoatImportReport,
+    GoatSearchParams, HealthTrendPoint, JobStatus, LocationAlert, LocationReport, MedicineItem,
+    MergeSummary, MonthlyFinancialReport, OverdueVaccination, ProfitAnalysis, ReclassifySummary,
+    Sale, SalePayload, SensorReading, SensorReadingBucket, ShowEntry, SpaceEnvironment, SpaceGoats,
+    SpaceMovement, SpaceResident, Supplier, SupplierPurchases, TimelineEvent, TradeLogEntry,
+    TradeLogVerification, VaccineCoverage, WorkerPerformance, WorkerTask,
+};
+use backend::money::{Currency, Money, MoneyInput};
+use backend::openapi::{OPENAPI_JSON_PATH, swagger_ui};
+use backend::rate_limit::RateLimiter;
+use backend::schema::{REFINERY_HISTORY_TABLE, ensure_schema};
+use backend::services::GoatService;
+use backend::state::{ADMIN_TOKEN_HEADER, AdminToken, AppState};
+use backend::units::{LB_PER_KG, UnitSystem, WeightUnit, round2};
+use backend::validation::validate_goat_params;
+use backend::writer::Writer;
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::TestRunner;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use serde_json::json;
+use shared::{Breed, DiseaseRef, Gender, GoatParams, VaccineRef};
 use tracing::{debug, info};
 use tracing_subscriber;
 
+mod helpers;
+
+use helpers::{MULTIPART_BOUNDARY, multipart_file, seed_goat, setup_test_db};
+
+/// Creates an isolated database file with the full schema applied, for tests that need a
+/// real file (WAL, several concurrent connections); see `helpers::setup_test_db`.
+fn fresh_db(name: &str) -> DbPool {
+    let path = std::env::temp_dir().join(format!("yagi_test_{}.db", name));
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+    let pool = DbPool::new(path.to_str().expect("temp path is not UTF-8"))
+        .expect("Failed to create DbPool");
+    pool.get_conn()
+        .expect("Failed to get connection")
+        .execute_batch(SCHEMA_SQL)
+        .expect("Failed to apply schema");
+    pool
+}
+
 #[actix_rt::test]
 async fn test_db_connection() {
-    // Use the test database
-    let db_path = "sample_livestock.db";
-
-    // Create the pool
-    let pool = DbPool::new(db_path).expect("Failed to create database pool");
+    let pool = setup_test_db();
 
     // Attempt to lock the SQLite connection mutex
     {
@@ -21,9 +175,19 @@ async fn test_db_connection() {
 
         // Execute a simple query to verify the DB is accessible and schema exists
         let result = conn.execute_batch("PRAGMA journal_mode;");
-
         assert!(result.is_ok(), "Database PRAGMA command failed");
     }
+
+    // Every pooled connection sees the same database.
+    seed_goat(&pool, "Shared", "Beetal");
+    let first = pool.get_conn().unwrap();
+    let second = pool.get_conn().unwrap();
+    for conn in [&first, &second] {
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM goats", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
 }
 
 #[actix_rt::test]
@@ -35,7 +199,8 @@ async fn test_get_goats_endpoint() {
         .try_init();
 
     info!("Initializing test DB pool");
-    let db_pool = DbPool::new("sample_livestock.db").expect("Failed to create DbPool");
+    let db_pool = setup_test_db();
+    seed_goat(&db_pool, "Listed", "Beetal");
 
     let app = test::init_service(
         App::new()
@@ -63,6 +228,9 @@ async fn test_get_goats_endpoint() {
         "Content-Type not JSON, got {}",
         ct_str
     );
+    let goats: Vec<Goat> = test::read_body_json(resp).await;
+    assert_eq!(goats.len(), 1);
+    assert_eq!(goats[0].params.name, "Listed");
 }
 
 #[actix_rt::test]
@@ -73,8 +241,7 @@ async fn test_add_goat_endpoint() {
         .with_test_writer()
         .try_init();
 
-    // Setup DB pool pointing to test database
-    let db_pool = DbPool::new("sample_livestock.db").expect("Failed to create DbPool");
+    let db_pool = setup_test_db();
 
     // Initialize Actix app with POST /goats route
     let app = test::init_service(
@@ -126,7 +293,8 @@ async fn test_update_goat_endpoint() {
         .with_test_writer()
         .try_init();
 
-    let db_pool = DbPool::new("sample_livestock.db").expect("Failed to create DbPool");
+    let db_pool = setup_test_db();
+    seed_goat(&db_pool, "NewGoat", "Beetal");
     debug!("Pool generated");
 
     let app = test::init_service(
@@ -137,7 +305,6 @@ async fn test_update_goat_endpoint() {
     .await;
     debug!("App created in test_update_goats");
 
-    // Example of goat data with an existing id (adjust id according to your test DB)
     let updated_goat = json!({
         "breed": "Beetal",
         "name": "NewGoat",
@@ -150,7 +317,8 @@ async fn test_update_goat_endpoint() {
         "last_bred": null,
         "health_status": "good",
         "vaccinations": [],
-        "diseases": []
+        "diseases": [],
+        "version": 0
     });
     debug!("Updated Goat created");
 
@@ -169,7 +337,75 @@ async fn test_update_goat_endpoint() {
     // debug!("Response body: {}", body_str);
 }
 
-//ToDo: Delete goat has to take a hardcoded id
+#[actix_rt::test]
+async fn test_update_goat_by_name_never_moves_links_between_namesakes() {
+    // A database from before the unique name index, holding two goats named alike.
+    let path = std::env::temp_dir().join("yagi_test_namesake_update.db");
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+    let legacy_schema = SCHEMA_SQL.replace(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_goats_name_nocase ON goats(name COLLATE NOCASE)
+    WHERE deleted_at IS NULL;",
+        "",
+    );
+    assert!(!legacy_schema.contains("idx_goats_name_nocase"));
+    // Migrated by refinery, so the bootstrap leaves the missing index alone.
+    rusqlite::Connection::open(&path)
+        .unwrap()
+        .execute_batch(&format!(
+            "{}
+             CREATE TABLE {} (version INTEGER);
+             INSERT INTO goats (id, breed, name, gender) VALUES
+                 (1, 'Beetal', 'Twin', 'Female'), (2, 'Beetal', 'Twin', 'Female');
+             INSERT INTO vaccines (id, name) VALUES (1, 'CDT'), (2, 'Rabies');
+             INSERT INTO goat_vaccines (goat_id, vaccine_id) VALUES (1, 1), (2, 2);",
+            legacy_schema, REFINERY_HISTORY_TABLE
+        ))
+        .unwrap();
+    let db_pool = DbPool::new(path.to_str().unwrap()).unwrap();
+    let conn = db_pool.get_conn().unwrap();
+    let links = |goat_id: i64| -> Vec<String> {
+        conn.prepare(
+            "SELECT v.name FROM goat_vaccines gv JOIN vaccines v ON v.id = gv.vaccine_id \
+             WHERE gv.goat_id = ?1 ORDER BY v.name",
+        )
+        .unwrap()
+        .query_map([goat_id], |r| r.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+    };
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats", web::put().to(update_goat)),
+    )
+    .await;
+    let mut twin = goat_json("Twin");
+    twin["vaccinations"] = json!([{ "id": null, "name": "PPR" }]);
+    twin["version"] = json!(0);
+    let put = || {
+        test::TestRequest::put()
+            .uri("/goats")
+            .set_json(&twin)
+            .to_request()
+    };
+
+    // While the name is ambiguous the update is refused and neither goat's links move.
+    assert_eq!(test::call_service(&app, put()).await.status(), 409);
+    assert_eq!(links(1), vec!["CDT"]);
+    assert_eq!(links(2), vec!["Rabies"]);
+
+    // Once it is not, only the goat the name resolved to is rewritten.
+    conn.execute("UPDATE goats SET name = 'Twin B' WHERE id = 2", [])
+        .unwrap();
+    assert_eq!(test::call_service(&app, put()).await.status(), 200);
+    assert_eq!(links(1), vec!["PPR"]);
+    assert_eq!(links(2), vec!["Rabies"]);
+}
+
 #[actix_rt::test]
 async fn test_delete_goat_endpoint() {
     // Init tracing
@@ -178,7 +414,8 @@ async fn test_delete_goat_endpoint() {
         .with_test_writer()
         .try_init();
 
-    let db_pool = DbPool::new("sample_livestock.db").expect("Failed to create DbPool");
+    let db_pool = setup_test_db();
+    seed_goat(&db_pool, "NewGoat8", "Beetal");
 
     let app = test::init_service(
         App::new()
@@ -187,7 +424,6 @@ async fn test_delete_goat_endpoint() {
     )
     .await;
 
-    // Provide the ID of the goat to delete (adjust based on your test DB content)
     let name_payload = json!({ "name": "NewGoat8"});
 
     let req = test::TestRequest::delete()
@@ -203,3 +439,8015 @@ async fn test_delete_goat_endpoint() {
     let body_str = std::str::from_utf8(&body_bytes).unwrap_or("<invalid utf8>");
     debug!("Response body: {}", body_str);
 }
+
+#[actix_rt::test]
+async fn test_deleted_goat_name_can_be_reused() {
+    let db_pool = fresh_db("goat_name_reuse");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/goats")
+                    .route("", web::post().to(add_goat))
+                    .route("", web::delete().to(delete_goat)),
+            ),
+    )
+    .await;
+    let create = || {
+        test::TestRequest::post()
+            .uri("/goats")
+            .set_json(goat_json("Reused Name"))
+            .to_request()
+    };
+
+    assert_eq!(test::call_service(&app, create()).await.status(), 201);
+    assert_eq!(test::call_service(&app, create()).await.status(), 409);
+
+    let req = test::TestRequest::delete()
+        .uri("/goats")
+        .set_json(json!({ "name": "reused name" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    // The deleted goat keeps its row and name, but the name is free again.
+    assert_eq!(test::call_service(&app, create()).await.status(), 201);
+    assert_eq!(test::call_service(&app, create()).await.status(), 409);
+    let conn = db_pool.get_conn().unwrap();
+    let (total, live): (i64, i64) = conn
+        .query_row(
+            "SELECT COUNT(*), COUNT(*) FILTER (WHERE deleted_at IS NULL) FROM goats \
+             WHERE name = 'Reused Name'",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((total, live), (2, 1));
+}
+
+#[actix_rt::test]
+async fn test_daily_report_counts_events_on_date() {
+    let db_pool = fresh_db("daily_report");
+    {
+        let conn = db_pool.get_conn().expect("Failed to get connection");
+        conn.execute_batch(
+            "INSERT INTO goats (id, breed, name, gender, created_at, updated_at) VALUES
+                (1, 'Beetal', 'ReportGoat1', 'Female', '2025-03-10 08:00:00', NULL),
+                (2, 'Sirohi', 'ReportGoat2', 'Male', '2025-03-10 09:30:00', '2025-03-11 10:00:00'),
+                (3, 'Barbari', 'ReportGoat3', 'Female', '2025-01-01 12:00:00', '2025-03-10 17:45:00');
+             INSERT INTO vaccines (id, name) VALUES (1, 'CDT'), (2, 'Rabies');
+             INSERT INTO diseases (id, name) VALUES (1, 'Mastitis');
+             INSERT INTO goat_vaccines (goat_id, vaccine_id, created_at) VALUES
+                (1, 1, '2025-03-10 08:05:00'),
+                (1, 2, '2025-03-10 08:06:00'),
+                (2, 1, '2025-03-09 08:00:00');
+             INSERT INTO goat_diseases (goat_id, disease_id, created_at) VALUES
+                (3, 1, '2025-03-10 11:00:00');
+             INSERT INTO goat_weight_history (goat_id, weight, created_at) VALUES
+                (1, 40.0, '2025-03-10 07:00:00'),
+                (2, 52.5, '2025-03-10 07:10:00'),
+                (3, 38.0, '2025-03-10 07:20:00'),
+                (3, 37.5, '2025-03-12 07:20:00');
+             INSERT INTO feed_events (goat_id, feed, quantity_kg, created_at) VALUES
+                (NULL, 'Hay', 20.0, '2025-03-10 06:00:00'),
+                (NULL, 'Hay', 20.0, '2025-03-10 18:00:00');
+             INSERT INTO vet_visits (goat_id, vet_name, reason, created_at) VALUES
+                (3, 'Dr. Rao', 'Mastitis check', '2025-03-10 11:00:00');
+             INSERT INTO sales (goat_id, buyer, price, created_at) VALUES
+                (2, 'Local market', 210.0, '2025-03-11 09:00:00');",
+        )
+        .expect("Failed to seed events");
+    }
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .service(web::scope("/reports").route("/daily", web::get().to(get_daily_report))),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/reports/daily?date=2025-03-10")
+        .to_request();
+    let report: DailyReport = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(
+        report,
+        DailyReport {
+            date: "2025-03-10".to_string(),
+            goats_added: 2,
+            goats_updated: 1,
+            diseases_diagnosed: 1,
+            vaccinations_administered: 2,
+            weight_measurements: 3,
+            feed_events: 2,
+            vet_visits: 1,
+            sales: 0,
+        }
+    );
+}
+
+#[actix_rt::test]
+async fn test_daily_report_rejects_future_date() {
+    let db_pool = fresh_db("daily_report_future");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .service(web::scope("/reports").route("/daily", web::get().to(get_daily_report))),
+    )
+    .await;
+
+    let tomorrow = chrono::Utc::now().date_naive() + chrono::Duration::days(1);
+    let req = test::TestRequest::get()
+        .uri(&format!("/reports/daily?date={}", tomorrow))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_maintenance_mode_blocks_writes_only() {
+    let db_pool = fresh_db("maintenance_mode");
+
+    let app = test::init_service(
+        App::new()
+            .wrap(middleware::from_fn(read_only_guard))
+            .app_data(web::Data::new(db_pool))
+            .app_data(web::Data::new(AppState::default()))
+            .route("/health", web::get().to(health))
+            .service(
+                web::scope("/goats")
+                    .route("", web::get().to(get_goats))
+                    .route("", web::post().to(add_goat)),
+            )
+            .service(web::scope("/admin").route("/maintenance", web::post().to(set_maintenance))),
+    )
+    .await;
+
+    let goat = |name: &str| {
+        json!({
+            "breed": "Beetal",
+            "name": name,
+            "gender": "Female",
+            "offspring": 0,
+            "cost": 100.0,
+            "weight": 40.0,
+            "current_price": 120.0,
+            "diet": "Hay",
+            "last_bred": null,
+            "health_status": "healthy",
+            "vaccinations": [],
+            "diseases": []
+        })
+    };
+
+    // Enable maintenance mode
+    let req = test::TestRequest::post()
+        .uri("/admin/maintenance")
+        .set_json(json!({ "read_only": true }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::get().uri("/health").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["read_only"], true);
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(goat("MaintenanceGoat1"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 503);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"], "MaintenanceMode");
+
+    let req = test::TestRequest::get().uri("/goats").to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    // Disable maintenance mode and confirm writes resume
+    let req = test::TestRequest::post()
+        .uri("/admin/maintenance")
+        .set_json(json!({ "read_only": false }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(goat("MaintenanceGoat2"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+}
+
+/// Builds a minimal valid goat JSON payload with the given name.
+fn goat_json(name: &str) -> serde_json::Value {
+    json!({
+        "breed": "Beetal",
+        "name": name,
+        "gender": "Female",
+        "offspring": 0,
+        "cost": 100.0,
+        "weight": 40.0,
+        "current_price": 120.0,
+        "diet": "Hay",
+        "last_bred": null,
+        "health_status": "healthy",
+        "vaccinations": [],
+        "diseases": []
+    })
+}
+
+fn count_goats(db_pool: &DbPool) -> i64 {
+    db_pool
+        .get_conn()
+        .expect("Failed to get connection")
+        .query_row("SELECT COUNT(*) FROM goats", [], |row| row.get(0))
+        .expect("Failed to count goats")
+}
+
+#[actix_rt::test]
+async fn test_batch_mixed_operations() {
+    let db_pool = fresh_db("batch_mixed");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/batch", web::post().to(run_batch)),
+    )
+    .await;
+
+    let mut updated = goat_json("BatchGoat1");
+    updated["weight"] = json!(45.5);
+    updated["version"] = json!(0);
+
+    let ops = json!([
+        { "op": "create", "entity": "goat", "payload": goat_json("BatchGoat1") },
+        { "op": "create", "entity": "goat", "payload": goat_json("BatchGoat2") },
+        { "op": "update", "entity": "goat", "payload": updated },
+        { "op": "delete", "entity": "goat", "payload": { "name": "Missing" } },
+        { "op": "delete", "entity": "goat", "payload": { "name": "BatchGoat2" } }
+    ]);
+
+    let req = test::TestRequest::post()
+        .uri("/batch")
+        .set_json(&ops)
+        .to_request();
+    let resp: BatchResponse = test::call_and_read_body_json(&app, req).await;
+
+    assert!(resp.committed);
+    let successes: Vec<bool> = resp.results.iter().map(|r| r.success).collect();
+    assert_eq!(successes, vec![true, true, true, false, true]);
+    assert!(resp.results[3].error.is_some());
+
+    let weight: f64 = db_pool
+        .get_conn()
+        .unwrap()
+        .query_row(
+            "SELECT weight FROM goats WHERE name = 'BatchGoat1'",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(weight, 45.5);
+    assert_eq!(count_goats(&db_pool), 1);
+}
+
+#[actix_rt::test]
+async fn test_batch_atomic_rolls_back_on_failure() {
+    let db_pool = fresh_db("batch_atomic");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/batch", web::post().to(run_batch)),
+    )
+    .await;
+
+    let mut missing = goat_json("DoesNotExist");
+    missing["version"] = json!(0);
+    let ops = json!([
+        { "op": "create", "entity": "goat", "payload": goat_json("AtomicGoat1") },
+        { "op": "update", "entity": "goat", "payload": missing },
+        { "op": "create", "entity": "goat", "payload": goat_json("AtomicGoat2") }
+    ]);
+
+    let req = test::TestRequest::post()
+        .uri("/batch?atomic=true")
+        .set_json(&ops)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+
+    let body: BatchResponse = test::read_body_json(resp).await;
+    assert!(!body.committed);
+    assert_eq!(body.results.len(), 2);
+    assert!(!body.results[1].success);
+    assert_eq!(count_goats(&db_pool), 0);
+}
+
+fn trend_point(interval: &str, disease: &str, count: i32) -> DiseaseTrendPoint {
+    DiseaseTrendPoint {
+        interval: interval.to_string(),
+        disease: disease.to_string(),
+        count,
+    }
+}
+
+#[actix_rt::test]
+async fn test_disease_trends_weekly_and_monthly_buckets() {
+    let db_pool = fresh_db("disease_trends");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'TrendGoat1', 'Female'),
+                (2, 'Beetal', 'TrendGoat2', 'Female'),
+                (3, 'Sirohi', 'TrendGoat3', 'Male');
+             INSERT INTO diseases (id, name) VALUES (1, 'FootRot'), (2, 'Mastitis');
+             INSERT INTO goat_diseases (goat_id, disease_id, created_at) VALUES
+                (1, 1, '2025-01-06 09:00:00'),
+                (2, 1, '2025-01-08 09:00:00'),
+                (3, 2, '2025-01-07 09:00:00'),
+                (3, 1, '2025-01-14 09:00:00'),
+                (1, 2, '2025-02-03 09:00:00');",
+        )
+        .expect("Failed to seed diagnoses");
+
+    let app = test::init_service(App::new().app_data(web::Data::new(db_pool)).service(
+        web::scope("/stats").route("/diseases/trends", web::get().to(get_disease_trends)),
+    ))
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/stats/diseases/trends?from=2025-01-01&to=2025-01-31&interval=week")
+        .to_request();
+    let weekly: Vec<DiseaseTrendPoint> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        weekly,
+        vec![
+            trend_point("2025-01", "FootRot", 2),
+            trend_point("2025-01", "Mastitis", 1),
+            trend_point("2025-02", "FootRot", 1),
+        ]
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/stats/diseases/trends?from=2025-01-01&to=2025-02-28&interval=month")
+        .to_request();
+    let monthly: Vec<DiseaseTrendPoint> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        monthly,
+        vec![
+            trend_point("2025-01", "FootRot", 3),
+            trend_point("2025-01", "Mastitis", 1),
+            trend_point("2025-02", "Mastitis", 1),
+        ]
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/stats/diseases/trends?from=2020-01-01&to=2025-01-01&interval=month")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_monthly_report_nets_revenue_against_costs() {
+    let db_pool = setup_test_db();
+    let conn = db_pool.get_conn().unwrap();
+    conn.execute_batch(
+        "INSERT INTO goats (id, breed, name, gender, cost, cost_minor, created_at) VALUES
+            (1, 'Beetal', 'MarchBuy', 'Female', 1500.0, 150000, '2025-03-02 10:00:00'),
+            (2, 'Beetal', 'MarchLegacy', 'Female', 500.25, NULL, '2025-03-20 10:00:00'),
+            (3, 'Sirohi', 'FebBuy', 'Male', 9000.0, 900000, '2025-02-27 10:00:00');
+         INSERT INTO sales (goat_id, price, created_at) VALUES
+            (3, 8000.0, '2025-03-15 12:00:00'),
+            (3, 99.0, '2025-04-01 00:00:00');
+         INSERT INTO expenses (category, amount, incurred_on) VALUES
+            ('Feed', 300.0, '2025-03-05'),
+            ('Feed', 200.0, '2025-03-31'),
+            ('Vet', 150.0, '2025-03-10'),
+            ('Labor', 1000.0, '2025-03-28'),
+            ('Labor', 777.0, '2024-03-28'),
+            ('Equipment', 400.0, '2025-03-12');
+         INSERT INTO vet_visits (goat_id, cost, created_at) VALUES
+            (1, 250.0, '2025-03-11 09:00:00');",
+    )
+    .expect("Failed to seed monthly finances");
+    drop(conn);
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/reports")
+                .route("/monthly", web::get().to(get_monthly_report))
+                .route("/monthly/latest", web::get().to(get_latest_monthly_report)),
+        ),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/reports/monthly?month=3&year=2025")
+        .to_request();
+    let report: MonthlyFinancialReport = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(report.month, "2025-03");
+    assert_eq!(report.total_purchases, 2000.25);
+    assert_eq!(report.total_sales_revenue, 8000.0);
+    assert_eq!(report.total_feed_costs, 500.0);
+    assert_eq!(report.total_vet_costs, 400.0);
+    assert_eq!(report.total_payroll, 1000.0);
+    assert_eq!(report.net_profit, 4099.75);
+    assert!((report.profit_margin_percent.unwrap() - 51.246875).abs() < 1e-9);
+
+    // Nothing sold in February: a loss with no margin.
+    let req = test::TestRequest::get()
+        .uri("/reports/monthly?month=2&year=2025")
+        .to_request();
+    let report: MonthlyFinancialReport = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(report.net_profit, -9000.0);
+    assert_eq!(report.profit_margin_percent, None);
+
+    let req = test::TestRequest::get()
+        .uri("/reports/monthly/latest")
+        .to_request();
+    let report: MonthlyFinancialReport = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(report.month, chrono::Utc::now().format("%Y-%m").to_string());
+
+    for uri in [
+        "/reports/monthly?month=0&year=2025",
+        "/reports/monthly?month=13&year=2025",
+        "/reports/monthly?month=3&year=1999",
+        "/reports/monthly?month=3&year=2101",
+    ] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400, "{}", uri);
+    }
+}
+
+#[actix_rt::test]
+async fn test_census_follows_a_year_of_additions_and_removals() {
+    let db_pool = setup_test_db();
+    let conn = db_pool.get_conn().unwrap();
+    conn.execute_batch(
+        "INSERT INTO goats (id, breed, name, gender, created_at, deleted_at, birth_date) VALUES
+            (1, 'Beetal', 'Stayer', 'Female', '2023-05-01 09:00:00', NULL, NULL),
+            (2, 'Beetal', 'SoldInMarch', 'Male', '2023-06-01 09:00:00', NULL, NULL),
+            (3, 'Sirohi', 'DiedInAugust', 'Female', '2023-07-01 09:00:00', '2024-08-20 09:00:00', NULL),
+            (4, 'Sirohi', 'FebKid', 'Wether', '2024-02-10 09:00:00', NULL, '2024-02-10'),
+            (5, 'Beetal', 'NovKid', 'Female', '2024-11-05 09:00:00', NULL, '2024-11-05'),
+            (6, 'Boer', 'GoneBefore', 'Male', '2023-01-01 09:00:00', '2023-12-31 09:00:00', NULL),
+            (7, 'Beetal', 'NextYear', 'Male', '2025-01-02 09:00:00', NULL, NULL),
+            (8, 'Beetal', 'SoldThenDeleted', 'Female', '2023-04-01 09:00:00', '2024-07-01 09:00:00', NULL);
+         INSERT INTO sales (goat_id, price, created_at) VALUES
+            (2, 9000.0, '2024-03-15 12:00:00'),
+            (8, 7000.0, '2024-06-01 10:00:00');",
+    )
+    .expect("Failed to seed census year");
+    drop(conn);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .service(web::scope("/reports").route("/census", web::get().to(get_census))),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/reports/census?year=2024")
+        .to_request();
+    let census: CensusReport = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(census.year, 2024);
+    assert_eq!(census.opening_count, 4);
+    assert_eq!(census.closing_count, 3);
+    assert_eq!(
+        census.by_breed,
+        vec![
+            BreedStats {
+                breed: "Beetal".to_string(),
+                total: 2,
+                male: 0,
+                female: 2,
+                wether: 0,
+            },
+            BreedStats {
+                breed: "Sirohi".to_string(),
+                total: 1,
+                male: 0,
+                female: 0,
+                wether: 1,
+            },
+        ]
+    );
+    assert_eq!(census.born, 2);
+    assert_eq!(census.died, 1);
+    assert_eq!(census.sold, 2);
+    // Month-ends Jan-Dec: 4, 5, 4, 4, 4, 3, 3, 2, 2, 2, 3, 3.
+    assert_eq!(census.average_herd_size, 39.0 / 12.0);
+
+    for uri in ["/reports/census?year=1999", "/reports/census?year=2101"] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 400, "{}", uri);
+    }
+}
+
+#[actix_rt::test]
+async fn test_health_trends_count_diagnoses_and_resolutions_per_bucket() {
+    let db_pool = setup_test_db();
+    let conn = db_pool.get_conn().unwrap();
+    conn.execute_batch(
+        "INSERT INTO goats (id, breed, name, gender) VALUES
+            (1, 'Beetal', 'HealthGoat1', 'Female'),
+            (2, 'Beetal', 'HealthGoat2', 'Female'),
+            (3, 'Sirohi', 'HealthGoat3', 'Male');
+         INSERT INTO diseases (id, name) VALUES (1, 'FootRot'), (2, 'Mastitis');
+         INSERT INTO goat_diseases (goat_id, disease_id, created_at) VALUES
+            (1, 1, '2025-01-06 09:00:00'),
+            (2, 1, '2025-01-08 09:00:00'),
+            (3, 2, '2025-01-14 09:00:00');
+         INSERT INTO disease_events (goat_id, disease_id, event, occurred_at) VALUES
+            (1, 1, 'Resolved', '2025-01-09 09:00:00'),
+            (2, 1, 'Resolved', '2025-01-20 09:00:00');",
+    )
+    .expect("Failed to seed disease events");
+
+    // Removing a disease link is recorded as a resolution.
+    conn.execute(
+        "DELETE FROM goat_diseases WHERE goat_id = 3 AND disease_id = 2",
+        [],
+    )
+    .unwrap();
+    let resolved: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM disease_events WHERE goat_id = 3 AND event = 'Resolved'",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(resolved, 1);
+    drop(conn);
+
+    let app =
+        test::init_service(App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/reports").route("/health-trends", web::get().to(get_health_trends)),
+        ))
+        .await;
+
+    let req = test::TestRequest::get()
+        .uri("/reports/health-trends?from=2025-01-01&to=2025-01-31&bucket=week")
+        .to_request();
+    let weekly: Vec<HealthTrendPoint> = test::call_and_read_body_json(&app, req).await;
+    let point = |bucket: &str, diagnoses, resolutions| HealthTrendPoint {
+        bucket: bucket.to_string(),
+        diagnoses,
+        resolutions,
+    };
+    assert_eq!(
+        weekly,
+        vec![
+            point("2025-01", 2, 1),
+            point("2025-02", 1, 0),
+            point("2025-03", 0, 1),
+        ]
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/reports/health-trends?from=2025-01-01&to=2025-01-31&bucket=month")
+        .to_request();
+    let monthly: Vec<HealthTrendPoint> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(monthly, vec![point("2025-01", 3, 2)]);
+
+    let req = test::TestRequest::get()
+        .uri("/reports/health-trends?from=2025-02-01&to=2025-01-01")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_goat_changes_include_update_and_tombstone() {
+    let db_pool = fresh_db("goat_changes");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (breed, name, gender, weight, created_at) VALUES
+                ('Beetal', 'SyncGoat1', 'Female', 40.0, '2024-01-01 00:00:00'),
+                ('Beetal', 'SyncGoat2', 'Female', 42.0, '2024-01-01 00:00:00'),
+                ('Beetal', 'SyncGoat3', 'Male', 50.0, '2024-01-01 00:00:00');",
+        )
+        .expect("Failed to seed goats");
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/goats")
+                .route("", web::put().to(update_goat))
+                .route("", web::delete().to(delete_goat))
+                .route("/changes", web::get().to(get_goat_changes)),
+        ),
+    )
+    .await;
+
+    let since = "2024-06-01T00:00:00Z";
+    let req = test::TestRequest::get()
+        .uri(&format!("/goats/changes?since={}", since))
+        .to_request();
+    let before: GoatChanges = test::call_and_read_body_json(&app, req).await;
+    assert!(before.changed.is_empty());
+    assert!(before.deleted.is_empty());
+
+    let mut updated = goat_json("SyncGoat1");
+    updated["weight"] = json!(44.0);
+    updated["version"] = json!(0);
+    let req = test::TestRequest::put()
+        .uri("/goats")
+        .set_json(&updated)
+        .to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    let req = test::TestRequest::delete()
+        .uri("/goats")
+        .set_json(json!({ "name": "SyncGoat2" }))
+        .to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/goats/changes?since={}", since))
+        .to_request();
+    let after: GoatChanges = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(after.changed.len(), 1);
+    assert_eq!(after.changed[0].params.name, "SyncGoat1");
+    assert_eq!(after.changed[0].params.weight, 44.0);
+    assert_eq!(after.deleted.len(), 1);
+    assert_eq!(after.deleted[0].name, "SyncGoat2");
+    assert!(!after.server_time.is_empty());
+}
+
+#[actix_rt::test]
+async fn test_breed_weight_gain_excludes_single_measurements() {
+    let db_pool = fresh_db("weight_gain");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'GainGoat1', 'Female'),
+                (2, 'Beetal', 'GainGoat2', 'Male'),
+                (3, 'Beetal', 'GainGoat3', 'Female'),
+                (4, 'Sirohi', 'GainGoat4', 'Female');
+             INSERT INTO goat_weight_history (goat_id, weight, created_at) VALUES
+                (1, 35.0, '2024-12-01 08:00:00'),
+                (1, 40.0, '2025-01-01 08:00:00'),
+                (1, 41.0, '2025-01-15 08:00:00'),
+                (1, 43.0, '2025-01-31 08:00:00'),
+                (2, 30.0, '2025-01-01 08:00:00'),
+                (2, 36.0, '2025-01-21 08:00:00'),
+                (3, 25.0, '2025-01-10 08:00:00'),
+                (4, 20.0, '2025-01-01 08:00:00'),
+                (4, 50.0, '2025-01-31 08:00:00');",
+        )
+        .expect("Failed to seed weight history");
+
+    let app =
+        test::init_service(App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/stats").route("/weight-gain", web::get().to(get_breed_weight_gain)),
+        ))
+        .await;
+
+    let req = test::TestRequest::get()
+        .uri("/stats/weight-gain?breed=Beetal&from=2025-01-01&to=2025-01-31")
+        .to_request();
+    let gain: BreedWeightGain = test::call_and_read_body_json(&app, req).await;
+
+    // GainGoat1: 3 kg over 30 days = 100 g/day; GainGoat2: 6 kg over 20 days = 300 g/day.
+    assert_eq!(gain.breed, "Beetal");
+    assert_eq!(gain.sample_size, 2);
+    assert!((gain.avg_daily_gain_g - 200.0).abs() < 1e-6);
+    assert_eq!(gain.from, "2025-01-01");
+    assert_eq!(gain.to, "2025-01-31");
+}
+
+#[actix_rt::test]
+async fn test_goat_qrcode_png_and_missing_goat() {
+    let db_pool = fresh_db("goat_qrcode");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute(
+            "INSERT INTO goats (id, breed, name, gender) VALUES (7, 'Beetal', 'QrGoat', 'Female')",
+            [],
+        )
+        .expect("Failed to seed goat");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .service(web::scope("/goats").route("/{id}/qrcode", web::get().to(get_goat_qrcode))),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/goats/7/qrcode?size=300")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("content-type").unwrap(), "image/png");
+    assert!(resp.headers().contains_key("cache-control"));
+    let body = test::read_body(resp).await;
+    let png = image::load_from_memory(&body).expect("Response is not a valid PNG");
+    assert_eq!((png.width(), png.height()), (300, 300));
+
+    // Oversized requests are clamped rather than rejected.
+    let req = test::TestRequest::get()
+        .uri("/goats/7/qrcode?size=100000")
+        .to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    let png = image::load_from_memory(&body).expect("Response is not a valid PNG");
+    assert_eq!(png.width(), 1024);
+
+    let req = test::TestRequest::get()
+        .uri("/goats/999/qrcode")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+#[actix_rt::test]
+async fn test_goats_meta_envelope_only_when_requested() {
+    let db_pool = fresh_db("meta_envelope");
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/goats")
+                .route("", web::get().to(get_goats))
+                .route("", web::post().to(add_goat)),
+        ),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(goat_json("MetaGoat"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let req = test::TestRequest::get().uri("/goats").to_request();
+    let plain: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(plain.is_array(), "Plain response should be a bare array");
+
+    let req = test::TestRequest::get()
+        .uri("/goats?meta=true")
+        .to_request();
+    let wrapped: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(wrapped["data"].as_array().map(Vec::len), Some(1));
+    assert_eq!(wrapped["meta"]["api_version"], "1");
+    assert!(wrapped["meta"]["server_time"].is_string());
+    assert!(wrapped["meta"]["change_seq"].as_i64().unwrap() > 0);
+}
+
+#[actix_rt::test]
+async fn test_compact_goats_omit_relations_and_money() {
+    let db_pool = fresh_db("compact_goats");
+    let goat_id = seed_goat(&db_pool, "CompactGoat", "Beetal");
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/goats")
+                .route("", web::get().to(get_goats))
+                .route("/compact", web::get().to(get_compact_goats))
+                .route("/{id}", web::get().to(get_goat)),
+        ),
+    )
+    .await;
+    let get = |uri: &str| test::TestRequest::get().uri(uri).to_request();
+    let keys = |goat: &serde_json::Value| -> Vec<String> {
+        let mut keys: Vec<String> = goat.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    };
+    let compact_keys = ["breed", "gender", "health_status", "id", "name", "weight"];
+
+    let full: serde_json::Value = test::call_and_read_body_json(&app, get("/goats")).await;
+    assert!(full[0].get("cost").is_some());
+    assert!(full[0].get("vaccinations").is_some());
+
+    for uri in ["/goats?compact=true", "/goats/compact"] {
+        let compact: serde_json::Value = test::call_and_read_body_json(&app, get(uri)).await;
+        assert_eq!(keys(&compact[0]), compact_keys, "{}", uri);
+        assert_eq!(compact[0]["id"], goat_id);
+        assert_eq!(compact[0]["name"], "CompactGoat");
+        assert_eq!(compact[0]["weight"], 40.0);
+    }
+
+    // Other parameters still apply.
+    let imperial: serde_json::Value =
+        test::call_and_read_body_json(&app, get("/goats/compact?units=imperial&meta=true")).await;
+    assert_eq!(imperial["data"][0]["weight"], 88.18);
+    assert!(imperial["data"][0].get("cost").is_none());
+
+    let one: serde_json::Value =
+        test::call_and_read_body_json(&app, get(&format!("/goats/{}?compact=true", goat_id))).await;
+    assert_eq!(keys(&one), compact_keys);
+    assert_eq!(one["id"], goat_id);
+    let full: serde_json::Value =
+        test::call_and_read_body_json(&app, get(&format!("/goats/{}", goat_id))).await;
+    assert!(full.get("cost").is_some());
+}
+
+#[actix_rt::test]
+async fn test_goat_documents_round_trip_and_reject_other_types() {
+    let db_pool = fresh_db("goat_documents");
+    let goat_id = seed_goat(&db_pool, "Papered", "Beetal");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route(
+                "/goats/{id}/documents",
+                web::post().to(upload_goat_document),
+            )
+            .route("/goats/{id}/documents", web::get().to(list_goat_documents))
+            .route("/documents/{doc_id}", web::get().to(get_document)),
+    )
+    .await;
+    let upload = |goat_id: i64, filename: &str, content_type: &str, content: &[u8]| {
+        test::TestRequest::post()
+            .uri(&format!("/goats/{}/documents", goat_id))
+            .insert_header((
+                "content-type",
+                format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+            ))
+            .set_payload(multipart_file(filename, content_type, content))
+            .to_request()
+    };
+    let pdf: &[u8] = b"%PDF-1.4\n1 0 obj << /Type /Catalog >> endobj\ntrailer << >>\n%%EOF\n";
+
+    let resp =
+        test::call_service(&app, upload(goat_id, "receipt.pdf", "application/pdf", pdf)).await;
+    assert_eq!(resp.status(), 201);
+    let stored: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(stored["filename"], "receipt.pdf");
+    assert_eq!(stored["size"], pdf.len());
+    let document_id = stored["id"].as_i64().unwrap();
+
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/documents/{}", document_id))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/pdf");
+    assert!(
+        resp.headers()["content-disposition"]
+            .to_str()
+            .unwrap()
+            .contains("receipt.pdf")
+    );
+    assert_eq!(test::read_body(resp).await.as_ref(), pdf);
+
+    // Executables are refused whether declared as such or disguised as a PDF.
+    let exe: &[u8] = b"MZ\x90\x00\x03\x00\x00\x00\x04\x00\x00\x00\xff\xff";
+    for content_type in ["application/x-msdownload", "application/pdf"] {
+        let resp = test::call_service(&app, upload(goat_id, "setup.exe", content_type, exe)).await;
+        assert_eq!(resp.status(), 415, "{}", content_type);
+    }
+    let resp =
+        test::call_service(&app, upload(99_999, "receipt.pdf", "application/pdf", pdf)).await;
+    assert_eq!(resp.status(), 404);
+
+    let listed: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/goats/{}/documents", goat_id))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(listed.as_array().map(Vec::len), Some(1));
+    assert_eq!(listed[0]["content_type"], "application/pdf");
+    assert!(listed[0].get("content").is_none());
+
+    // Removing the goat's row takes its documents with it.
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute("DELETE FROM goats WHERE id = ?1", [goat_id])
+        .unwrap();
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/documents/{}", document_id))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_rt::test]
+async fn test_attention_list_scores_sick_and_overdue_goats() {
+    let db_pool = fresh_db("attention");
+    let sick = seed_goat(&db_pool, "Coughing", "Beetal");
+    let overdue = seed_goat(&db_pool, "Unjabbed", "Beetal");
+    seed_goat(&db_pool, "Fine", "Beetal");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(&format!(
+            "INSERT INTO diseases (id, name) VALUES (1, 'Pneumonia');
+             INSERT INTO goat_diseases (goat_id, disease_id) VALUES ({sick}, 1);
+             INSERT INTO vaccines (id, name) VALUES (1, 'CDT');
+             INSERT INTO vaccination_schedule (goat_id, vaccine_id, next_due_on)
+                VALUES ({overdue}, 1, '2020-01-01');"
+        ))
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats/attention", web::get().to(get_attention_goats)),
+    )
+    .await;
+    let get = |uri: &str| test::TestRequest::get().uri(uri).to_request();
+
+    let flagged: serde_json::Value =
+        test::call_and_read_body_json(&app, get("/goats/attention")).await;
+    assert_eq!(flagged.as_array().map(Vec::len), Some(2));
+    // Illness outranks a missed vaccination.
+    assert_eq!(flagged[0]["goat_id"], sick);
+    assert_eq!(flagged[0]["reasons"][0]["kind"], "active_disease");
+    assert_eq!(flagged[0]["reasons"][0]["detail"], "Has Pneumonia");
+    assert_eq!(flagged[1]["goat_id"], overdue);
+    assert_eq!(flagged[1]["reasons"][0]["kind"], "overdue_vaccination");
+    assert!(
+        flagged[1]["reasons"][0]["detail"]
+            .as_str()
+            .unwrap()
+            .starts_with("CDT was due on 2020-01-01")
+    );
+    assert!(flagged[0]["score"].as_u64() > flagged[1]["score"].as_u64());
+
+    // Thresholds from the query add reasons to every goat they catch.
+    let flagged: serde_json::Value =
+        test::call_and_read_body_json(&app, get("/goats/attention?min_weight_kg=45")).await;
+    assert_eq!(flagged.as_array().map(Vec::len), Some(3));
+    assert_eq!(flagged[0]["reasons"].as_array().map(Vec::len), Some(2));
+    assert_eq!(flagged[2]["reasons"][0]["kind"], "underweight");
+
+    // Conditions whose tables are missing are skipped rather than failing.
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch("DROP TABLE vaccination_schedule;")
+        .unwrap();
+    let flagged: serde_json::Value =
+        test::call_and_read_body_json(&app, get("/goats/attention")).await;
+    assert_eq!(flagged.as_array().map(Vec::len), Some(1));
+    assert_eq!(flagged[0]["goat_id"], sick);
+}
+
+#[actix_rt::test]
+async fn test_full_export_archive_requires_the_admin_token() {
+    let db_pool = fresh_db("full_export_auth");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .app_data(web::Data::new(AdminToken::new(Some("s3cret".to_string()))))
+            .route("/admin/export/full", web::get().to(export_full_archive)),
+    )
+    .await;
+    let export = |token: Option<&str>| {
+        let mut req = test::TestRequest::get().uri("/admin/export/full");
+        if let Some(token) = token {
+            req = req.insert_header((ADMIN_TOKEN_HEADER, token));
+        }
+        req.to_request()
+    };
+
+    for token in [None, Some("wrong"), Some("s3cre")] {
+        let resp = test::call_service(&app, export(token)).await;
+        assert_eq!(resp.status(), 401, "{:?}", token);
+        let body: ErrorBody = test::read_body_json(resp).await;
+        assert_eq!((body.error.as_str(), body.code), ("Unauthorized", 401));
+    }
+    let resp = test::call_service(&app, export(Some("s3cret"))).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/zip");
+
+    // Without a configured token the export is refused outright.
+    let locked = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .route("/admin/export/full", web::get().to(export_full_archive)),
+    )
+    .await;
+    let resp = test::call_service(&locked, export(Some("s3cret"))).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_rt::test]
+async fn test_full_export_archive_has_csv_files_and_manifest() {
+    use std::io::Read;
+
+    let db_pool = fresh_db("full_export");
+    seed_goat(&db_pool, "Zipped, \"the\" goat", "Beetal");
+    let gone = seed_goat(&db_pool, "Deleted", "Beetal");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(&format!(
+            "UPDATE goats SET deleted_at = CURRENT_TIMESTAMP WHERE id = {gone};
+             INSERT INTO workers (name, hours_worked, leaves, role, contact)
+                VALUES ('Worker1', 160, 2, 'Feeder', 'w1@farm.com');"
+        ))
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .app_data(web::Data::new(AdminToken::new(Some("s3cret".to_string()))))
+            .route("/admin/export/full", web::get().to(export_full_archive)),
+    )
+    .await;
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri("/admin/export/full")
+            .insert_header((ADMIN_TOKEN_HEADER, "s3cret"))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/zip");
+    let disposition = resp.headers()["content-disposition"].to_str().unwrap();
+    let expected = format!(
+        "attachment; filename=\"farm_export_{}.zip\"",
+        chrono::Utc::now().format("%Y%m%d")
+    );
+    assert_eq!(disposition, expected);
+
+    let bytes = test::read_body(resp).await;
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes.to_vec())).unwrap();
+    let mut read = |name: &str| {
+        let mut text = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    };
+
+    let manifest: serde_json::Value = serde_json::from_str(&read("manifest.json")).unwrap();
+    assert!(manifest["exported_at"].is_string());
+    assert_eq!(
+        manifest["record_counts"],
+        json!({
+            "goats.csv": 1, "workers.csv": 1, "equipment.csv": 0, "sensors.csv": 0,
+            "spaces.csv": 0, "vaccines.csv": 0, "diseases.csv": 0
+        })
+    );
+
+    let goats = read("goats.csv");
+    let mut lines = goats.lines();
+    assert!(lines.next().unwrap().starts_with("id,breed,name,"));
+    assert!(
+        lines
+            .next()
+            .unwrap()
+            .contains(",\"Zipped, \"\"the\"\" goat\",")
+    );
+    assert_eq!(lines.next(), None);
+    assert!(read("diseases.csv").starts_with("id,name"));
+}
+
+#[actix_rt::test]
+async fn test_leave_approval_adds_days_and_rejects_overlaps() {
+    let db_pool = fresh_db("leave_requests");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO workers (id, name, hours_worked, leaves, role, contact)
+                VALUES (1, 'Asha', 160, 2, 'Herder', 'asha@farm.com');",
+        )
+        .unwrap();
+    let leaves = |pool: &DbPool| -> i64 {
+        pool.get_conn()
+            .unwrap()
+            .query_row("SELECT leaves FROM workers WHERE id = 1", [], |r| r.get(0))
+            .unwrap()
+    };
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/leave-requests")
+                    .route("", web::get().to(list_leave_requests))
+                    .route("", web::post().to(create_leave_request))
+                    .route("/{id}/decide", web::post().to(decide_leave_request)),
+            ),
+    )
+    .await;
+    let request = |from: &str, to: &str| {
+        test::TestRequest::post()
+            .uri("/leave-requests")
+            .set_json(
+                json!({ "worker_id": 1, "from_date": from, "to_date": to, "reason": "Family" }),
+            )
+            .to_request()
+    };
+    let decide = |id: &serde_json::Value, status: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/leave-requests/{}/decide", id))
+            .set_json(json!({ "status": status, "decided_by": "Manager" }))
+            .to_request()
+    };
+
+    let first: serde_json::Value =
+        test::call_and_read_body_json(&app, request("2026-03-02", "2026-03-04")).await;
+    assert_eq!(first["status"], "pending");
+    let overlapping: serde_json::Value =
+        test::call_and_read_body_json(&app, request("2026-03-04", "2026-03-06")).await;
+
+    let approved: serde_json::Value =
+        test::call_and_read_body_json(&app, decide(&first["id"], "approved")).await;
+    assert_eq!(approved["status"], "approved");
+    assert_eq!(approved["decided_by"], "Manager");
+    // Three days, both ends included.
+    assert_eq!(leaves(&db_pool), 5);
+
+    // Approving a pending request that overlaps approved leave conflicts and adds nothing.
+    let resp = test::call_service(&app, decide(&overlapping["id"], "approved")).await;
+    assert_eq!(resp.status(), 409);
+    assert_eq!(leaves(&db_pool), 5);
+    // New requests over approved days are refused outright.
+    let resp = test::call_service(&app, request("2026-03-01", "2026-03-02")).await;
+    assert_eq!(resp.status(), 409);
+    // A decided request cannot be decided again.
+    let resp = test::call_service(&app, decide(&first["id"], "rejected")).await;
+    assert_eq!(resp.status(), 409);
+
+    let rejected: serde_json::Value =
+        test::call_and_read_body_json(&app, decide(&overlapping["id"], "rejected")).await;
+    assert_eq!(rejected["status"], "rejected");
+    assert_eq!(leaves(&db_pool), 5);
+
+    let resp = test::call_service(&app, request("2026-03-10", "2026-03-09")).await;
+    assert_eq!(resp.status(), 422);
+    let resp = test::call_service(&app, decide(&first["id"], "maybe")).await;
+    assert_eq!(resp.status(), 422);
+
+    let listed: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::get()
+            .uri("/leave-requests?from=2026-03-05&to=2026-03-31")
+            .to_request(),
+    )
+    .await;
+    assert_eq!(listed.as_array().map(Vec::len), Some(1));
+    assert_eq!(listed[0]["id"], overlapping["id"]);
+    let listed: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::get()
+            .uri("/leave-requests?status=approved")
+            .to_request(),
+    )
+    .await;
+    assert_eq!(listed.as_array().map(Vec::len), Some(1));
+    assert_eq!(listed[0]["id"], first["id"]);
+}
+
+#[actix_rt::test]
+async fn test_vaccination_campaign_vaccinates_many_goats_in_one_call() {
+    let db_pool = fresh_db("vaccination_campaign");
+    let goat_ids: Vec<i64> = (1..=5)
+        .map(|i| seed_goat(&db_pool, &format!("Herd{}", i), "Beetal"))
+        .collect();
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO medicine_inventory (id, name, category, stock_units, unit)
+                VALUES (1, 'enterotoxemia', 'Vaccine', 10, 'dose');",
+        )
+        .unwrap();
+
+    let app = test::init_service(App::new().app_data(web::Data::new(db_pool.clone())).route(
+        "/vaccinations/campaign",
+        web::post().to(run_vaccination_campaign),
+    ))
+    .await;
+
+    let mut listed = goat_ids.clone();
+    listed.push(9999);
+    let req = test::TestRequest::post()
+        .uri("/vaccinations/campaign")
+        .set_json(json!({
+            "vaccine": { "name": "Enterotoxemia" },
+            "goat_ids": listed,
+            "administered_on": "2026-03-01",
+            "next_due_on": "2027-03-01"
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["vaccinated"], 5);
+    assert_eq!(body["stock_medicine_id"], 1);
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results.len(), 6);
+    for (result, id) in results.iter().zip(&goat_ids) {
+        assert_eq!(result["goat_id"], *id);
+        assert_eq!(result["success"], true);
+    }
+    assert_eq!(results[5]["goat_id"], 9999);
+    assert_eq!(results[5]["success"], false);
+
+    let conn = db_pool.get_conn().unwrap();
+    let count = |sql: &str| -> i64 { conn.query_row(sql, [], |r| r.get(0)).unwrap() };
+    assert_eq!(count("SELECT COUNT(*) FROM vaccines"), 1);
+    assert_eq!(count("SELECT COUNT(*) FROM goat_vaccines"), 5);
+    assert_eq!(
+        count(
+            "SELECT COUNT(*) FROM vaccination_schedule \
+             WHERE administered_on = '2026-03-01' AND next_due_on = '2027-03-01'"
+        ),
+        5
+    );
+    let stock: f64 = conn
+        .query_row(
+            "SELECT stock_units FROM medicine_inventory WHERE id = 1",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(stock, 5.0);
+    drop(conn);
+
+    // With only five doses left, a second round for six goats runs out on the last one.
+    let extra = seed_goat(&db_pool, "Latecomer", "Beetal");
+    let mut listed = goat_ids.clone();
+    listed.push(extra);
+    let req = test::TestRequest::post()
+        .uri("/vaccinations/campaign")
+        .set_json(json!({
+            "vaccine": { "name": "enterotoxemia" },
+            "goat_ids": listed,
+            "administered_on": "2027-03-01",
+            "next_due_on": "2028-03-01"
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["vaccinated"], 5);
+    assert_eq!(body["results"][5]["success"], false);
+    assert!(
+        body["results"][5]["error"]
+            .as_str()
+            .unwrap()
+            .contains("in stock")
+    );
+
+    let req = test::TestRequest::post()
+        .uri("/vaccinations/campaign")
+        .set_json(json!({
+            "vaccine": { "name": "Enterotoxemia" },
+            "goat_ids": [],
+            "administered_on": "2026-03-01",
+            "next_due_on": "2026-02-01"
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let errors = test::read_body_json::<ErrorBody, _>(resp).await.fields;
+    let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(fields, ["goat_ids", "next_due_on"]);
+}
+
+#[test]
+fn test_overtime_starts_after_the_threshold() {
+    let terms = PayTerms {
+        hourly_rate: 100.0,
+        overtime_threshold_hours: 160.0,
+        overtime_multiplier: 1.5,
+    };
+
+    let at_threshold = compute_monthly_pay(160.0, &terms);
+    assert_eq!(at_threshold.regular_hours, 160.0);
+    assert_eq!(at_threshold.overtime_hours, 0.0);
+    assert_eq!(at_threshold.gross_pay, 16000.0);
+
+    let just_over = compute_monthly_pay(160.5, &terms);
+    assert_eq!(just_over.regular_hours, 160.0);
+    assert_eq!(just_over.overtime_hours, 0.5);
+    assert_eq!(just_over.gross_pay, 16075.0);
+
+    assert_eq!(compute_monthly_pay(0.0, &terms).gross_pay, 0.0);
+    assert_eq!(
+        month_bounds("2024-02"),
+        Some((
+            chrono::NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+            chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        ))
+    );
+    assert_eq!(month_bounds("2024-13"), None);
+}
+
+#[actix_rt::test]
+async fn test_payroll_summary_adds_up_worker_payrolls() {
+    let db_pool = fresh_db("payroll");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO workers (id, name, hours_worked, leaves, role, contact)
+                VALUES (1, 'Asha', 0, 0, 'Herder', 'asha@farm.com'),
+                       (2, 'Ravi', 0, 0, 'Milker', 'ravi@farm.com');
+             INSERT INTO leave_requests (worker_id, from_date, to_date, status)
+                VALUES (1, '2025-07-30', '2025-08-02', 'approved'),
+                       (2, '2025-08-10', '2025-08-11', 'rejected');",
+        )
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/workers")
+                    .route("/{id}/time-entries", web::post().to(add_time_entry))
+                    .route("/{id}/pay-terms", web::put().to(set_worker_pay_terms))
+                    .route("/{id}/payroll", web::get().to(get_worker_payroll)),
+            )
+            .route("/payroll/summary", web::get().to(get_payroll_summary)),
+    )
+    .await;
+
+    for worker_id in [1, 2] {
+        let req = test::TestRequest::put()
+            .uri(&format!("/workers/{}/pay-terms", worker_id))
+            .set_json(
+                json!({ "hourly_rate": 50.0 * worker_id as f64, "overtime_threshold_hours": 20.0 }),
+            )
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    }
+    // Asha works 24 hours in August, Ravi exactly the threshold; July does not count.
+    for (worker_id, date, hours) in [
+        (1, "2025-08-04", 12.0),
+        (1, "2025-08-05", 12.0),
+        (1, "2025-07-31", 8.0),
+        (2, "2025-08-04", 10.0),
+        (2, "2025-08-31", 10.0),
+    ] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/workers/{}/time-entries", worker_id))
+            .set_json(json!({ "work_date": date, "hours": hours }))
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::CREATED
+        );
+    }
+    let req = test::TestRequest::post()
+        .uri("/workers/1/time-entries")
+        .set_json(json!({ "work_date": "2025-08-06", "hours": 25.0 }))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, req).await.status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+
+    let get = |uri: &str| test::TestRequest::get().uri(uri).to_request();
+    let resp = test::call_service(&app, get("/workers/1/payroll?month=2025-08")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let asha: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(asha["hours_worked"], 24.0);
+    assert_eq!(asha["overtime_hours"], 4.0);
+    assert_eq!(asha["leave_days"], 2);
+    assert_eq!(asha["gross_pay"], 20.0 * 50.0 + 4.0 * 50.0 * 1.5);
+
+    let resp = test::call_service(&app, get("/workers/2/payroll?month=2025-08")).await;
+    let ravi: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(ravi["overtime_hours"], 0.0);
+    assert_eq!(ravi["leave_days"], 0);
+    assert_eq!(ravi["gross_pay"], 2000.0);
+
+    let resp = test::call_service(&app, get("/payroll/summary?month=2025-08")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let summary: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(summary["workers"], json!([asha, ravi]));
+    assert_eq!(summary["total_hours"], 44.0);
+    assert_eq!(summary["total_overtime_hours"], 4.0);
+    assert_eq!(summary["total_leave_days"], 2);
+    assert_eq!(
+        summary["total_gross_pay"].as_f64().unwrap(),
+        asha["gross_pay"].as_f64().unwrap() + ravi["gross_pay"].as_f64().unwrap()
+    );
+
+    // A month without entries reports zeros rather than a 404.
+    let resp = test::call_service(&app, get("/workers/2/payroll?month=2025-09")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let empty: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(empty["hours_worked"], 0.0);
+    assert_eq!(empty["gross_pay"], 0.0);
+
+    let resp = test::call_service(&app, get("/payroll/summary?month=August")).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = test::call_service(&app, get("/workers/9/payroll?month=2025-08")).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_rt::test]
+async fn test_error_responses_share_one_json_shape() {
+    let db_pool = fresh_db("error_shape");
+    let goat_id = seed_goat(&db_pool, "Shape", "Beetal");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .app_data(json_config(1024))
+            .app_data(query_config())
+            .app_data(path_config())
+            .route("/payroll/summary", web::get().to(get_payroll_summary))
+            .route("/goats/{id}/documents", web::get().to(list_goat_documents))
+            .route("/cohorts", web::post().to(create_cohort))
+            .route("/cohorts/{id}/add", web::post().to(add_cohort_goat)),
+    )
+    .await;
+
+    // Extractor failures answer with the same shape.
+    for req in [
+        test::TestRequest::get()
+            .uri("/payroll/summary")
+            .to_request(),
+        test::TestRequest::get()
+            .uri("/goats/abc/documents")
+            .to_request(),
+        test::TestRequest::post()
+            .uri("/cohorts/1/add")
+            .set_payload("{ not json")
+            .insert_header(("content-type", "application/json"))
+            .to_request(),
+    ] {
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body: ErrorBody = test::read_body_json(resp).await;
+        assert_eq!((body.error.as_str(), body.code), ("InvalidInput", 400));
+    }
+    let req = test::TestRequest::post()
+        .uri("/cohorts/1/add")
+        .set_payload("goat_id=1")
+        .insert_header(("content-type", "text/plain"))
+        .to_request();
+    let body: ErrorBody = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        (body.error.as_str(), body.code),
+        ("UnsupportedMediaType", 415)
+    );
+
+    // Successful writes without a record to return answer with a JSON message.
+    let req = test::TestRequest::post()
+        .uri("/cohorts")
+        .set_json(json!({ "name": "Spring kids" }))
+        .to_request();
+    let cohort: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let req = test::TestRequest::post()
+        .uri(&format!("/cohorts/{}/add", cohort["id"]))
+        .set_json(json!({ "goat_id": goat_id }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body, json!({ "message": "Goat added to cohort" }));
+
+    let req = test::TestRequest::get()
+        .uri("/payroll/summary?month=someday")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        resp.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"].as_str(), Some("InvalidInput"));
+    assert!(body["message"].as_str().unwrap().contains("someday"));
+    assert_eq!(body["code"].as_u64(), Some(400));
+
+    let req = test::TestRequest::get()
+        .uri("/goats/999/documents")
+        .to_request();
+    let body: ErrorBody = test::call_and_read_body_json(&app, req).await;
+    assert_eq!((body.error.as_str(), body.code), ("NotFound", 404));
+
+    // Database failures are logged in full but reported without detail.
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch("DROP TABLE goat_documents;")
+        .unwrap();
+    let req = test::TestRequest::get()
+        .uri(&format!("/goats/{}/documents", goat_id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body: ErrorBody = test::read_body_json(resp).await;
+    assert_eq!(body.error, "DbError");
+    assert_eq!(body.message, "Internal database error");
+    assert_eq!(body.code, 500);
+}
+
+#[actix_rt::test]
+async fn test_goat_import_dry_run_reports_errors_without_writing() {
+    let db_pool = fresh_db("goat_import_dry_run");
+    seed_goat(&db_pool, "Existing", "Beetal");
+    let goat_count = |pool: &DbPool| -> i64 {
+        pool.get_conn()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM goats", [], |r| r.get(0))
+            .unwrap()
+    };
+    let goat = |name: &str, weight: f64| {
+        json!({
+            "breed": "Beetal",
+            "name": name,
+            "gender": "Female",
+            "offspring": 0,
+            "cost": 100.0,
+            "weight": weight,
+            "current_price": 120.0,
+            "diet": "hay",
+            "last_bred": null,
+            "health_status": "healthy",
+            "vaccinations": [],
+            "diseases": []
+        })
+    };
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats/import", web::post().to(import_goats))
+            .route("/goats/import/csv", web::post().to(import_goats_csv)),
+    )
+    .await;
+
+    let rows = json!([
+        goat("Fresh", 30.0),
+        goat("Heavy", -5.0),
+        goat("existing", 30.0),
+        goat("fresh", 30.0),
+        { "name": "Incomplete" }
+    ]);
+    let req = test::TestRequest::post()
+        .uri("/goats/import?dry_run=true")
+        .set_json(&rows)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let report: GoatImportReport = test::read_body_json(resp).await;
+    assert!(report.dry_run);
+    assert_eq!(
+        (report.rows, report.would_insert, report.inserted),
+        (5, 1, 0)
+    );
+    let failed: Vec<(usize, Option<&str>)> = report
+        .errors
+        .iter()
+        .map(|e| (e.row, e.field.as_deref()))
+        .collect();
+    assert_eq!(
+        failed,
+        [
+            (2, Some("weight")),
+            (3, Some("name")),
+            (4, Some("name")),
+            (5, None)
+        ]
+    );
+    assert_eq!(goat_count(&db_pool), 1);
+
+    let csv = "name,breed,gender,offspring,cost,weight,current_price,diet,health_status,vaccinations\n\
+               Csv One,Beetal,Female,0,100,30,120,hay,healthy,PPR; Enterotoxemia\n\
+               \"Csv, Two\",Beetal,Male,two,100,30,120,hay,healthy,\n";
+    let req = test::TestRequest::post()
+        .uri("/goats/import/csv?dry_run=true")
+        .insert_header(("content-type", "text/csv"))
+        .set_payload(csv)
+        .to_request();
+    let report: GoatImportReport = test::call_and_read_body_json(&app, req).await;
+    assert_eq!((report.rows, report.would_insert), (2, 1));
+    assert_eq!(report.errors[0].row, 2);
+    assert_eq!(report.errors[0].field.as_deref(), Some("offspring"));
+    assert_eq!(goat_count(&db_pool), 1);
+    let vaccines: i64 = db_pool
+        .get_conn()
+        .unwrap()
+        .query_row("SELECT COUNT(*) FROM vaccines", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(vaccines, 0);
+
+    // A real import with errors is rejected as a whole.
+    let req = test::TestRequest::post()
+        .uri("/goats/import")
+        .set_json(&rows)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(goat_count(&db_pool), 1);
+
+    let req = test::TestRequest::post()
+        .uri("/goats/import")
+        .set_json(json!([goat("Fresh", 30.0), goat("Second", 32.0)]))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let report: GoatImportReport = test::read_body_json(resp).await;
+    assert_eq!((report.would_insert, report.inserted), (2, 2));
+    assert_eq!(goat_count(&db_pool), 3);
+}
+
+#[test]
+fn test_goat_name_must_be_one_to_hundred_chars() {
+    let mut goat = goat_json("x");
+    assert_eq!(validate_goat_params(&goat_params(goat.clone())), Ok(()));
+    goat["name"] = json!("n".repeat(100));
+    assert_eq!(validate_goat_params(&goat_params(goat.clone())), Ok(()));
+    goat["name"] = json!("n".repeat(101));
+    assert_eq!(
+        validate_goat_params(&goat_params(goat.clone())),
+        Err(vec!["name: must be at most 100 characters".to_string()])
+    );
+    goat["name"] = json!("   ");
+    assert_eq!(
+        validate_goat_params(&goat_params(goat)),
+        Err(vec!["name: must not be empty".to_string()])
+    );
+}
+
+#[test]
+fn test_goat_diet_must_be_one_to_two_hundred_chars() {
+    let mut goat = goat_json("Diet");
+    goat["diet"] = json!("");
+    assert_eq!(
+        validate_goat_params(&goat_params(goat.clone())),
+        Err(vec!["diet: must not be empty".to_string()])
+    );
+    goat["diet"] = json!("h".repeat(201));
+    assert_eq!(
+        validate_goat_params(&goat_params(goat)),
+        Err(vec!["diet: must be at most 200 characters".to_string()])
+    );
+}
+
+#[test]
+fn test_goat_health_status_must_be_one_to_hundred_chars() {
+    let mut goat = goat_json("Health");
+    goat["health_status"] = json!("");
+    assert_eq!(
+        validate_goat_params(&goat_params(goat.clone())),
+        Err(vec!["health_status: must not be empty".to_string()])
+    );
+    goat["health_status"] = json!("s".repeat(100));
+    assert_eq!(validate_goat_params(&goat_params(goat.clone())), Ok(()));
+    goat["health_status"] = json!("s".repeat(101));
+    assert_eq!(
+        validate_goat_params(&goat_params(goat)),
+        Err(vec![
+            "health_status: must be at most 100 characters".to_string()
+        ])
+    );
+}
+
+#[test]
+fn test_goat_last_bred_must_be_a_past_date() {
+    let mut goat = goat_json("Bred");
+    goat["last_bred"] = json!(chrono::Utc::now().date_naive().to_string());
+    assert_eq!(validate_goat_params(&goat_params(goat.clone())), Ok(()));
+    goat["last_bred"] = json!("01/02/2025");
+    assert_eq!(
+        validate_goat_params(&goat_params(goat.clone())),
+        Err(vec![
+            "last_bred: must be a date in YYYY-MM-DD format".to_string()
+        ])
+    );
+    let tomorrow = chrono::Utc::now().date_naive() + chrono::Days::new(1);
+    goat["last_bred"] = json!(tomorrow.to_string());
+    assert_eq!(
+        validate_goat_params(&goat_params(goat)),
+        Err(vec!["last_bred: must not be in the future".to_string()])
+    );
+}
+
+#[actix_rt::test]
+async fn test_goat_validation_reports_every_violation_at_once() {
+    let mut goat = goat_json(&"n".repeat(101));
+    goat["diet"] = json!("");
+    goat["health_status"] = json!(" ");
+    goat["last_bred"] = json!("2999-01-01");
+    assert_eq!(
+        validate_goat_params(&goat_params(goat.clone())),
+        Err(vec![
+            "name: must be at most 100 characters".to_string(),
+            "diet: must not be empty".to_string(),
+            "health_status: must not be empty".to_string(),
+            "last_bred: must not be in the future".to_string(),
+        ])
+    );
+
+    goat["version"] = json!(0);
+    let db_pool = fresh_db("goat_string_validation");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats", web::post().to(add_goat))
+            .route("/goats", web::put().to(update_goat)),
+    )
+    .await;
+    for req in [
+        test::TestRequest::post().uri("/goats"),
+        test::TestRequest::put().uri("/goats"),
+    ] {
+        let resp = test::call_service(&app, req.set_json(&goat).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: ErrorBody = test::read_body_json(resp).await;
+        let fields: Vec<&str> = body.fields.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["name", "diet", "health_status", "last_bred"]);
+    }
+    assert_eq!(count_goats(&db_pool), 0);
+}
+
+#[actix_rt::test]
+async fn test_goat_space_history_tracks_moves_and_residents() {
+    let db_pool = fresh_db("goat_space_history");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'Wanderer', 'Female'),
+                (2, 'Beetal', 'Homebody', 'Female');
+             INSERT INTO spaces (id, name, type, capacity) VALUES
+                (1, 'Pen A', 'enclosure', 10),
+                (2, 'Pen B', 'enclosure', 10),
+                (3, 'Field C', 'grazing', NULL);",
+        )
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/spaces/{id}/goats", web::post().to(assign_goat))
+            .route("/spaces/{id}/residents", web::get().to(get_space_residents))
+            .route("/goats/{id}/movements", web::get().to(get_goat_movements)),
+    )
+    .await;
+    let assign = |space_id: i64, goat_id: i64| {
+        test::TestRequest::post()
+            .uri(&format!("/spaces/{}/goats", space_id))
+            .set_json(json!({ "goat_id": goat_id }))
+            .to_request()
+    };
+
+    for space_id in [1, 1, 2, 3] {
+        let resp = test::call_service(&app, assign(space_id, 1)).await;
+        assert_eq!(resp.status(), 200);
+    }
+    let resp = test::call_service(&app, assign(2, 2)).await;
+    assert_eq!(resp.status(), 200);
+
+    // Reassigning to the current space adds no stay; each move closes the previous one.
+    let req = test::TestRequest::get()
+        .uri("/goats/1/movements")
+        .to_request();
+    let movements: Vec<SpaceMovement> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        movements.iter().map(|m| m.space_id).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(
+        movements[0].moved_out_at.as_ref(),
+        Some(&movements[1].moved_in_at)
+    );
+    assert_eq!(
+        movements[1].moved_out_at.as_ref(),
+        Some(&movements[2].moved_in_at)
+    );
+    assert_eq!(movements[2].moved_out_at, None);
+    assert_eq!(movements[2].space_name, "Field C");
+
+    // Spread the stays over the summer so residency can be asked about a past day.
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "UPDATE goat_space_history SET moved_in_at = '2025-06-01 08:00:00',
+                moved_out_at = '2025-07-01 12:00:00' WHERE goat_id = 1 AND space_id = 1;
+             UPDATE goat_space_history SET moved_in_at = '2025-07-01 12:00:00',
+                moved_out_at = '2025-08-01 12:00:00' WHERE goat_id = 1 AND space_id = 2;
+             UPDATE goat_space_history SET moved_in_at = '2025-08-01 12:00:00'
+                WHERE goat_id = 1 AND space_id = 3;
+             UPDATE goat_space_history SET moved_in_at = '2025-07-10 09:00:00'
+                WHERE goat_id = 2;",
+        )
+        .unwrap();
+
+    let residents = |space_id: i64, at: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/spaces/{}/residents?at={}", space_id, at))
+            .to_request()
+    };
+    let ids =
+        |residents: Vec<SpaceResident>| residents.iter().map(|r| r.goat_id).collect::<Vec<i64>>();
+
+    let in_a: Vec<SpaceResident> =
+        test::call_and_read_body_json(&app, residents(1, "2025-07-01")).await;
+    assert_eq!(ids(in_a), vec![1]);
+    let in_b: Vec<SpaceResident> =
+        test::call_and_read_body_json(&app, residents(2, "2025-07-01")).await;
+    assert!(in_b.is_empty());
+    let in_b: Vec<SpaceResident> =
+        test::call_and_read_body_json(&app, residents(2, "2025-07-15T00:00:00Z")).await;
+    assert_eq!(ids(in_b.clone()), vec![1, 2]);
+    assert_eq!(in_b[0].name, "Wanderer");
+    assert_eq!(in_b[0].moved_out_at.as_deref(), Some("2025-08-01 12:00:00"));
+    // A stay ends the moment the goat moves out.
+    let in_b: Vec<SpaceResident> =
+        test::call_and_read_body_json(&app, residents(2, "2025-08-01%2012:00:00")).await;
+    assert_eq!(ids(in_b), vec![2]);
+
+    let req = test::TestRequest::get()
+        .uri("/spaces/3/residents")
+        .to_request();
+    let now_in_c: Vec<SpaceResident> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(ids(now_in_c), vec![1]);
+
+    let resp = test::call_service(&app, residents(1, "July")).await;
+    assert_eq!(resp.status(), 400);
+    let resp = test::call_service(&app, residents(99, "2025-07-01")).await;
+    assert_eq!(resp.status(), 404);
+    let req = test::TestRequest::get()
+        .uri("/goats/99/movements")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+#[actix_rt::test]
+async fn test_snapshot_export_import_round_trip() {
+    let source = fresh_db("snapshot_source");
+    source
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO vaccines (name) VALUES ('CDT'), ('Rabies');
+             INSERT INTO diseases (name) VALUES ('FootRot');
+             INSERT INTO goats (breed, name, gender, offspring, cost, weight, current_price, diet, health_status)
+                VALUES ('Beetal', 'SnapGoat1', 'Female', 1, 100.0, 40.0, 120.0, 'Hay', 'healthy'),
+                       ('Sirohi', 'SnapGoat2', 'Male', 0, 150.0, 55.0, 170.0, 'Pasture', 'sick');
+             INSERT INTO goat_vaccines (goat_id, vaccine_id) VALUES (1, 1), (1, 2), (2, 1);
+             INSERT INTO goat_diseases (goat_id, disease_id) VALUES (2, 1);
+             INSERT INTO workers (name, hours_worked, leaves, role, contact)
+                VALUES ('Worker1', 160, 2, 'Feeder', 'w1@farm.com');
+             INSERT INTO equipment (name, description, purchase_date, condition, last_maintenance)
+                VALUES ('Tractor', 'Farm tractor', '2020-03-14', 'Good', '2025-02-28');
+             INSERT INTO spaces (name, type, capacity, grass_condition, health)
+                VALUES ('Enclosure 1', 'enclosure', 50, 'Good', 'Healthy');
+             INSERT INTO sensors (sensor_type, location, last_reading, last_reading_time, status, space_id)
+                VALUES ('Temp Sensor', 'Barn', 21.5, '2025-08-01', 'Active', 1);",
+        )
+        .expect("Failed to seed source database");
+    let target = fresh_db("snapshot_target");
+    let merged = fresh_db("snapshot_merged");
+    merged
+        .get_conn()
+        .unwrap()
+        .execute_batch("INSERT INTO spaces (name, type) VALUES ('Shed', 'other');")
+        .unwrap();
+
+    let app_for = |pool: DbPool| {
+        App::new().app_data(web::Data::new(pool)).service(
+            web::scope("/admin")
+                .route("/export", web::get().to(export_snapshot))
+                .route("/import", web::post().to(import_snapshot)),
+        )
+    };
+    let source_app = test::init_service(app_for(source)).await;
+    let target_app = test::init_service(app_for(target)).await;
+    let merged_app = test::init_service(app_for(merged.clone())).await;
+
+    let req = test::TestRequest::get().uri("/admin/export").to_request();
+    let mut exported: serde_json::Value = test::call_and_read_body_json(&source_app, req).await;
+    assert_eq!(exported["schema_version"], 1);
+
+    let req = test::TestRequest::post()
+        .uri("/admin/import?mode=replace")
+        .set_json(&exported)
+        .to_request();
+    assert_eq!(test::call_service(&target_app, req).await.status(), 200);
+
+    let req = test::TestRequest::get().uri("/admin/export").to_request();
+    let mut reexported: serde_json::Value = test::call_and_read_body_json(&target_app, req).await;
+
+    exported.as_object_mut().unwrap().remove("exported_at");
+    reexported.as_object_mut().unwrap().remove("exported_at");
+    assert_eq!(exported, reexported);
+    assert_eq!(reexported["sensors"][0]["space_id"], 1);
+
+    // Merged elsewhere, the sensor follows its space to the id it gets there.
+    let req = test::TestRequest::post()
+        .uri("/admin/import?mode=merge")
+        .set_json(&exported)
+        .to_request();
+    assert_eq!(test::call_service(&merged_app, req).await.status(), 200);
+    let installed_in: String = merged
+        .get_conn()
+        .unwrap()
+        .query_row(
+            "SELECT spaces.name FROM sensors JOIN spaces ON spaces.id = sensors.space_id \
+             WHERE sensors.sensor_type = 'Temp Sensor'",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(installed_in, "Enclosure 1");
+
+    // Unknown document versions are rejected outright.
+    let mut future = reexported.clone();
+    future["schema_version"] = json!(99);
+    future["exported_at"] = json!("2030-01-01T00:00:00Z");
+    let req = test::TestRequest::post()
+        .uri("/admin/import?mode=merge")
+        .set_json(&future)
+        .to_request();
+    assert_eq!(test::call_service(&target_app, req).await.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_snapshot_import_keeps_records_it_does_not_carry() {
+    let db_pool = fresh_db("snapshot_keeps_history");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender, offspring, cost, weight, current_price,
+                    diet, health_status)
+                VALUES (1, 'Beetal', 'Kept', 'Female', 1, 100.0, 40.0, 120.0, 'Hay', 'healthy'),
+                       (2, 'Sirohi', 'Dropped', 'Male', 0, 150.0, 55.0, 170.0, 'Hay', 'healthy');
+             INSERT INTO goat_weight_history (goat_id, weight) VALUES (1, 40.0), (2, 55.0);
+             INSERT INTO expenses (goat_id, category, amount) VALUES (2, 'Feed', 10.0);
+             INSERT INTO workers (id, name) VALUES (1, 'Stays'), (2, 'Leaves');
+             INSERT INTO leave_requests (worker_id, from_date, to_date)
+                VALUES (1, '2025-02-01', '2025-02-02'), (2, '2025-02-01', '2025-02-03');
+             INSERT INTO goat_documents (goat_id, filename, content_type, size, content)
+                VALUES (1, 'papers.pdf', 'application/pdf', 3, X'255044');",
+        )
+        .expect("Failed to seed history");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/admin")
+                    .route("/export", web::get().to(export_snapshot))
+                    .route("/import", web::post().to(import_snapshot)),
+            ),
+    )
+    .await;
+    let count = |sql: &str| -> i64 {
+        db_pool
+            .get_conn()
+            .unwrap()
+            .query_row(sql, [], |r| r.get(0))
+            .unwrap()
+    };
+
+    let req = test::TestRequest::get().uri("/admin/export").to_request();
+    let mut snapshot: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    snapshot["goats"]
+        .as_array_mut()
+        .unwrap()
+        .retain(|goat| goat["name"] == "Kept");
+    snapshot["workers"]
+        .as_array_mut()
+        .unwrap()
+        .retain(|worker| worker["name"] == "Stays");
+    let req = test::TestRequest::post()
+        .uri("/admin/import?mode=replace")
+        .set_json(&snapshot)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    // The restored goat keeps its id, weighings, and documents; the dropped goat's
+    // records go with it, as its foreign keys say.
+    assert_eq!(
+        count("SELECT COUNT(*) FROM goats WHERE id = 1 AND name = 'Kept'"),
+        1
+    );
+    assert_eq!(count("SELECT COUNT(*) FROM goats"), 1);
+    assert_eq!(
+        count("SELECT COUNT(*) FROM goat_weight_history WHERE goat_id = 1"),
+        1
+    );
+    assert_eq!(count("SELECT COUNT(*) FROM goat_weight_history"), 1);
+    assert_eq!(
+        count("SELECT COUNT(*) FROM goat_documents WHERE goat_id = 1"),
+        1
+    );
+    assert_eq!(
+        count("SELECT COUNT(*) FROM expenses WHERE goat_id IS NULL"),
+        1
+    );
+    assert_eq!(
+        count("SELECT COUNT(*) FROM leave_requests WHERE worker_id = 1"),
+        1
+    );
+    assert_eq!(count("SELECT COUNT(*) FROM leave_requests"), 1);
+
+    // Merging a goat whose name only a soft-deleted goat has brings that goat back.
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute(
+            "UPDATE goats SET deleted_at = CURRENT_TIMESTAMP WHERE id = 1",
+            [],
+        )
+        .unwrap();
+    let req = test::TestRequest::post()
+        .uri("/admin/import?mode=merge")
+        .set_json(&snapshot)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    assert_eq!(
+        count("SELECT COUNT(*) FROM goats WHERE deleted_at IS NULL"),
+        1
+    );
+    assert_eq!(
+        count("SELECT COUNT(*) FROM goats WHERE id = 1 AND deleted_at IS NULL"),
+        1
+    );
+}
+
+#[actix_web::test]
+async fn test_oversized_json_body_returns_413() {
+    let db_pool = setup_test_db();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .app_data(json_config(64))
+            .route("/goats", web::post().to(add_goat))
+            .service(
+                web::scope("/admin")
+                    .route("/export", web::get().to(export_snapshot))
+                    .service(
+                        web::resource("/import")
+                            .app_data(json_config(1024 * 1024))
+                            .route(web::post().to(import_snapshot)),
+                    ),
+            ),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(json!({ "name": "x".repeat(100) }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 413);
+    let body: ErrorBody = test::read_body_json(resp).await;
+    assert_eq!(body.error, "PayloadTooLarge");
+    assert_eq!(body.message, "Request body exceeds the 64 byte limit");
+
+    // The import route has a larger limit of its own.
+    let req = test::TestRequest::get().uri("/admin/export").to_request();
+    let snapshot: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(snapshot.to_string().len() > 64);
+    let req = test::TestRequest::post()
+        .uri("/admin/import?mode=merge")
+        .set_json(&snapshot)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+}
+
+#[actix_rt::test]
+async fn test_vaccination_coverage_half_the_herd() {
+    let db_pool = fresh_db("vaccination_coverage");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender, lifecycle_state, deleted_at) VALUES
+                (1, 'Beetal', 'CovGoat1', 'Female', 'Active', NULL),
+                (2, 'Beetal', 'CovGoat2', 'Female', 'Active', NULL),
+                (3, 'Beetal', 'CovGoat3', 'Male', 'Active', NULL),
+                (4, 'Beetal', 'CovGoat4', 'Male', 'Active', NULL),
+                (5, 'Beetal', 'CovGoat5', 'Female', 'Deceased', NULL),
+                (6, 'Beetal', 'CovGoat6', 'Female', 'Active', '2025-01-01 00:00:00'),
+                (7, 'Sirohi', 'CovGoat7', 'Female', 'Active', NULL);
+             INSERT INTO vaccines (id, name) VALUES (1, 'CDT'), (2, 'Rabies');
+             INSERT INTO goat_vaccines (goat_id, vaccine_id) VALUES
+                (1, 1), (2, 1), (5, 1), (6, 1), (7, 1);",
+        )
+        .expect("Failed to seed coverage data");
+
+    let app = test::init_service(App::new().app_data(web::Data::new(db_pool)).service(
+        web::scope("/stats").route(
+            "/vaccination-coverage",
+            web::get().to(get_vaccination_coverage),
+        ),
+    ))
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/stats/vaccination-coverage?breed=Beetal")
+        .to_request();
+    let coverage: Vec<VaccineCoverage> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(coverage.len(), 2);
+    assert_eq!(coverage[0].vaccine, "CDT");
+    assert_eq!(coverage[0].vaccinated_count, 2);
+    assert_eq!(coverage[0].total_goats, 4);
+    assert!((coverage[0].coverage_pct - 50.0).abs() < 1e-9);
+    assert_eq!(coverage[1].vaccine, "Rabies");
+    assert_eq!(coverage[1].vaccinated_count, 0);
+    assert_eq!(coverage[1].coverage_pct, 0.0);
+
+    let req = test::TestRequest::get()
+        .uri("/stats/vaccination-coverage")
+        .to_request();
+    let coverage: Vec<VaccineCoverage> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(coverage[0].vaccinated_count, 3);
+    assert_eq!(coverage[0].total_goats, 5);
+    assert!((coverage[0].coverage_pct - 60.0).abs() < 1e-9);
+}
+
+#[actix_rt::test]
+async fn test_add_goat_reports_all_validation_errors() {
+    let db_pool = fresh_db("validation_errors");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(web::scope("/goats").route("", web::post().to(add_goat))),
+    )
+    .await;
+
+    let mut invalid = goat_json("   ");
+    invalid["weight"] = json!(-5.0);
+    invalid["last_bred"] = json!("last spring");
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&invalid)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+
+    let errors = test::read_body_json::<ErrorBody, _>(resp).await.fields;
+    let mut fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+    fields.sort_unstable();
+    assert_eq!(fields, vec!["last_bred", "name", "weight"]);
+    assert!(errors.iter().all(|e| !e.message.is_empty()));
+    assert_eq!(count_goats(&db_pool), 0);
+}
+
+fn sale_goat(weight: f64, health: &str) -> GoatParams {
+    let mut payload = goat_json("SaleGoat");
+    payload["weight"] = json!(weight);
+    payload["health_status"] = json!(health);
+    serde_json::from_value(payload).unwrap()
+}
+
+fn born(date: &str) -> Option<chrono::NaiveDate> {
+    Some(chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap())
+}
+
+fn sale_today() -> chrono::NaiveDate {
+    chrono::NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+}
+
+#[test]
+fn test_sale_ready_when_all_criteria_met() {
+    let goat = sale_goat(30.0, "healthy");
+    assert!(is_sale_ready(
+        &goat,
+        born("2024-06-01"),
+        25.0,
+        6,
+        sale_today()
+    ));
+}
+
+#[test]
+fn test_sale_ready_rejects_underweight() {
+    let goat = sale_goat(24.9, "healthy");
+    assert!(!is_sale_ready(
+        &goat,
+        born("2024-06-01"),
+        25.0,
+        6,
+        sale_today()
+    ));
+}
+
+#[test]
+fn test_sale_ready_rejects_too_young() {
+    let goat = sale_goat(30.0, "healthy");
+    assert!(!is_sale_ready(
+        &goat,
+        born("2025-01-02"),
+        25.0,
+        6,
+        sale_today()
+    ));
+    assert!(is_sale_ready(
+        &goat,
+        born("2024-12-01"),
+        25.0,
+        6,
+        sale_today()
+    ));
+}
+
+#[test]
+fn test_sale_ready_rejects_unknown_age() {
+    let goat = sale_goat(30.0, "healthy");
+    assert!(!is_sale_ready(&goat, None, 25.0, 6, sale_today()));
+}
+
+#[test]
+fn test_sale_ready_rejects_unhealthy() {
+    let goat = sale_goat(30.0, "sick");
+    assert!(!is_sale_ready(
+        &goat,
+        born("2024-06-01"),
+        25.0,
+        6,
+        sale_today()
+    ));
+}
+
+#[test]
+fn test_sale_candidates_exclude_pregnant_and_sold() {
+    let db_pool = fresh_db("sale_candidates");
+    let conn = db_pool.get_conn().unwrap();
+    conn.execute_batch(
+        "INSERT INTO goats (id, breed, name, gender, weight, health_status, birth_date, is_pregnant, is_sold) VALUES
+            (1, 'Beetal', 'Open', 'Female', 30.0, 'healthy', '2024-06-01', 0, 0),
+            (2, 'Beetal', 'Pregnant', 'Female', 30.0, 'healthy', '2024-06-01', 1, 0),
+            (3, 'Beetal', 'Sold', 'Male', 30.0, 'healthy', '2024-06-01', 0, 1);",
+    )
+    .unwrap();
+    let candidates = DbPool::load_sale_candidates(&conn).unwrap();
+    let names: Vec<&str> = candidates
+        .iter()
+        .map(|c| c.goat.params.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Open"]);
+    assert_eq!(candidates[0].birth_date, born("2024-06-01"));
+}
+
+#[actix_rt::test]
+async fn test_sale_ready_endpoint_filters_and_sorts() {
+    let db_pool = fresh_db("sale_ready");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender, weight, health_status, birth_date, is_pregnant, is_sold) VALUES
+                (1, 'Beetal', 'Light', 'Male', 30.0, 'healthy', '2023-01-01', 0, 0),
+                (2, 'Beetal', 'Heavy', 'Male', 45.0, 'healthy', '2023-01-01', 0, 0),
+                (3, 'Beetal', 'Skinny', 'Male', 10.0, 'healthy', '2023-01-01', 0, 0),
+                (4, 'Beetal', 'Pregnant', 'Female', 50.0, 'healthy', '2023-01-01', 1, 0),
+                (5, 'Beetal', 'Sold', 'Male', 50.0, 'healthy', '2023-01-01', 0, 1),
+                (6, 'Beetal', 'Sick', 'Male', 50.0, 'sick', '2023-01-01', 0, 0),
+                (7, 'Sirohi', 'NoPrice', 'Male', 35.0, 'healthy', '2023-01-01', 0, 0);
+             INSERT INTO market_prices (breed, price_per_kg, recorded_at) VALUES
+                ('Beetal', 3.0, '2025-01-01 00:00:00'),
+                ('Beetal', 4.0, '2025-02-01 00:00:00');",
+        )
+        .expect("Failed to seed sale data");
+
+    let app =
+        test::init_service(App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/goats").route("/sale-ready", web::get().to(get_sale_ready_goats)),
+        ))
+        .await;
+
+    let req = test::TestRequest::get()
+        .uri("/goats/sale-ready")
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let goats = body.as_array().expect("Expected a JSON array");
+
+    let names: Vec<&str> = goats.iter().map(|g| g["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["Heavy", "NoPrice", "Light"]);
+    assert_eq!(goats[0]["estimated_sale_price"], 180.0);
+    assert!(goats[1]["estimated_sale_price"].is_null());
+    assert_eq!(goats[2]["estimated_sale_price"], 120.0);
+}
+
+#[actix_rt::test]
+async fn test_reference_lists_revalidate_with_etags() {
+    let db_pool = fresh_db("reference_etags");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/vaccines", web::get().to(list_vaccines))
+            .route("/vaccines", web::post().to(add_vaccine))
+            .route("/diseases", web::get().to(list_diseases))
+            .route("/breeds", web::get().to(list_breeds)),
+    )
+    .await;
+    let get = |uri: &str, etag: Option<&str>| {
+        let mut req = test::TestRequest::get().uri(uri);
+        if let Some(etag) = etag {
+            req = req.insert_header(("If-None-Match", etag));
+        }
+        req.to_request()
+    };
+    let add = |name: &str| {
+        test::TestRequest::post()
+            .uri("/vaccines")
+            .set_json(json!({ "name": name }))
+            .to_request()
+    };
+
+    let resp = test::call_service(&app, get("/vaccines", None)).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers().get("cache-control").unwrap(),
+        "public, max-age=3600"
+    );
+    let etag = resp
+        .headers()
+        .get("etag")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    // Unchanged, the list is revalidated without a body, however often it is asked for.
+    for _ in 0..2 {
+        let resp = test::call_service(&app, get("/vaccines", Some(&etag))).await;
+        assert_eq!(resp.status(), 304);
+        assert_eq!(resp.headers().get("etag").unwrap(), etag.as_str());
+        assert!(test::read_body(resp).await.is_empty());
+    }
+    // A new vaccine on another list leaves this one alone.
+    let resp = test::call_service(&app, get("/diseases", None)).await;
+    let disease_etag = resp
+        .headers()
+        .get("etag")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let resp = test::call_service(&app, add(" PPR ")).await;
+    assert_eq!(resp.status(), 201);
+    let resp = test::call_service(&app, get("/vaccines", Some(&etag))).await;
+    assert_eq!(resp.status(), 200);
+    let new_etag = resp
+        .headers()
+        .get("etag")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert_ne!(new_etag, etag);
+    let vaccines: Vec<serde_json::Value> = test::read_body_json(resp).await;
+    assert_eq!(vaccines.len(), 1);
+    assert_eq!(vaccines[0]["name"], "PPR");
+
+    // Naming an existing vaccine writes nothing, so the new tag still holds.
+    let resp = test::call_service(&app, add("ppr")).await;
+    assert_eq!(resp.status(), 200);
+    let existing: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(existing["name"], "PPR");
+    let resp = test::call_service(&app, get("/vaccines", Some(&new_etag))).await;
+    assert_eq!(resp.status(), 304);
+    let resp = test::call_service(&app, get("/diseases", Some(&disease_etag))).await;
+    assert_eq!(resp.status(), 304);
+    let resp = test::call_service(&app, add("  ")).await;
+    assert_eq!(resp.status(), 400);
+
+    // Breeds recorded on goats are part of the breed list, so goat writes revalidate it.
+    let resp = test::call_service(&app, get("/breeds", None)).await;
+    let breeds_etag = resp
+        .headers()
+        .get("etag")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let resp = test::call_service(&app, get("/breeds", Some(&breeds_etag))).await;
+    assert_eq!(resp.status(), 304);
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute(
+            "INSERT INTO goats (breed, name, gender) VALUES ('Kiko', 'Rare Breed Goat', 'Female')",
+            [],
+        )
+        .unwrap();
+    let resp = test::call_service(&app, get("/breeds", Some(&breeds_etag))).await;
+    assert_eq!(resp.status(), 200);
+    let breeds: Vec<String> = test::read_body_json(resp).await;
+    assert!(breeds.iter().any(|b| b == "Kiko"));
+}
+
+#[actix_rt::test]
+async fn test_merge_duplicate_vaccines() {
+    let db_pool = fresh_db("merge_vaccines");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            // Duplicates only a database from before the case-insensitive index can hold.
+            "DROP INDEX idx_vaccines_name_nocase;
+             INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'MergeGoat1', 'Female'),
+                (2, 'Beetal', 'MergeGoat2', 'Female'),
+                (3, 'Beetal', 'MergeGoat3', 'Male'),
+                (4, 'Beetal', 'MergeGoat4', 'Male');
+             INSERT INTO vaccines (id, name) VALUES (1, 'CDT'), (2, 'cdt'), (3, 'C.D.T.'), (4, 'Rabies');
+             INSERT INTO goat_vaccines (goat_id, vaccine_id) VALUES
+                (1, 1), (1, 2),
+                (2, 2), (2, 3),
+                (3, 3), (3, 4),
+                (4, 1), (4, 2), (4, 3);",
+        )
+        .expect("Failed to seed merge data");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/vaccines/{keep_id}/merge", web::post().to(merge_vaccines))
+            .service(web::scope("/goats").route("", web::post().to(add_goat))),
+    )
+    .await;
+
+    // A missing duplicate aborts the whole merge.
+    let req = test::TestRequest::post()
+        .uri("/vaccines/1/merge")
+        .set_json(json!({ "duplicate_ids": [2, 99] }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+
+    let req = test::TestRequest::post()
+        .uri("/vaccines/1/merge")
+        .set_json(json!({ "duplicate_ids": [3, 2] }))
+        .to_request();
+    let summary: MergeSummary = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        summary,
+        MergeSummary {
+            kept_id: 1,
+            merged_ids: vec![2, 3],
+            links_moved: 2,
+            links_deduplicated: 4,
+        }
+    );
+
+    let conn = db_pool.get_conn().unwrap();
+    let count = |sql: &str| -> i64 { conn.query_row(sql, [], |r| r.get(0)).unwrap() };
+    assert_eq!(count("SELECT COUNT(*) FROM vaccines"), 2);
+    assert_eq!(
+        count("SELECT COUNT(*) FROM goat_vaccines WHERE vaccine_id = 1"),
+        4
+    );
+    assert_eq!(
+        count("SELECT COUNT(*) FROM goat_vaccines WHERE vaccine_id = 4"),
+        1
+    );
+    assert_eq!(
+        count("SELECT COUNT(*) FROM audit_log WHERE entity = 'vaccines' AND action = 'merge'"),
+        1
+    );
+
+    // New goats naming the vaccine in another case reuse the surviving entry.
+    let mut payload = goat_json("MergeGoat5");
+    payload["vaccinations"] = json!([{ "id": null, "name": "cdt" }]);
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&payload)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    assert_eq!(count("SELECT COUNT(*) FROM vaccines"), 2);
+    assert_eq!(
+        count("SELECT COUNT(*) FROM goat_vaccines WHERE vaccine_id = 1"),
+        5
+    );
+}
+
+#[test]
+fn test_catalog_lookups_trim_and_reuse_mixed_case_names() {
+    let db_pool = setup_test_db();
+    let conn = db_pool.get_conn().unwrap();
+    let vaccine = |name: &str| VaccineRef {
+        id: None,
+        name: name.to_string(),
+    };
+    let disease = |name: &str| DiseaseRef {
+        id: None,
+        name: name.to_string(),
+    };
+
+    let created = get_or_insert_vaccine(&conn, &vaccine("  Rabies ")).unwrap();
+    assert!(created.inserted());
+    for name in ["rabies", "RABIES", " Rabies"] {
+        assert_eq!(
+            get_or_insert_vaccine(&conn, &vaccine(name)).unwrap(),
+            CatalogEntry::Found(created.id())
+        );
+    }
+    let stored: String = conn
+        .query_row("SELECT name FROM vaccines", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(stored, "Rabies");
+
+    let created = get_or_insert_disease(&conn, &disease("Foot Rot")).unwrap();
+    assert_eq!(created, CatalogEntry::Inserted(created.id()));
+    assert_eq!(
+        get_or_insert_disease(&conn, &disease("foot rot\t")).unwrap(),
+        CatalogEntry::Found(created.id())
+    );
+
+    for blank in ["", "   "] {
+        assert!(matches!(
+            get_or_insert_vaccine(&conn, &vaccine(blank)),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            get_or_insert_disease(&conn, &disease(blank)),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    // The index backs the lookup up against writes that bypass it.
+    assert!(
+        conn.execute("INSERT INTO vaccines (name) VALUES ('rabies')", [])
+            .is_err()
+    );
+}
+
+#[test]
+fn test_catalog_dedup_migration_folds_case_variants_into_the_oldest() {
+    let db_pool = fresh_db("catalog_dedup_migration");
+    let conn = db_pool.get_conn().unwrap();
+    // A database from before the case-insensitive indexes, with duplicates in it.
+    conn.execute_batch(
+        "DROP INDEX idx_vaccines_name_nocase;
+         DROP INDEX idx_diseases_name_nocase;
+         INSERT INTO goats (id, breed, name, gender) VALUES
+            (1, 'Beetal', 'Dirty1', 'Female'),
+            (2, 'Beetal', 'Dirty2', 'Female');
+         INSERT INTO vaccines (id, name) VALUES (1, 'CDT'), (2, 'cdt'), (3, ' CDT '), (4, 'Rabies');
+         INSERT INTO goat_vaccines (goat_id, vaccine_id) VALUES (1, 1), (1, 2), (2, 3), (2, 4);
+         INSERT INTO vaccination_schedule (goat_id, vaccine_id, next_due_on) VALUES
+            (2, 3, '2026-03-01');
+         INSERT INTO diseases (id, name) VALUES (1, ' Mastitis'), (2, 'mastitis');
+         INSERT INTO goat_diseases (goat_id, disease_id) VALUES (1, 1), (1, 2), (2, 2);",
+    )
+    .unwrap();
+
+    conn.execute_batch(include_str!(
+        "../migrations/V39__dedupe_vaccine_and_disease_names.sql"
+    ))
+    .unwrap();
+
+    let rows = |sql: &str| -> Vec<(i64, String)> {
+        let mut stmt = conn.prepare(sql).unwrap();
+        stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    };
+    assert_eq!(
+        rows("SELECT id, name FROM vaccines ORDER BY id"),
+        vec![(1, "CDT".to_string()), (4, "Rabies".to_string())]
+    );
+    assert_eq!(
+        rows("SELECT id, name FROM diseases ORDER BY id"),
+        vec![(1, "Mastitis".to_string())]
+    );
+    let links = |sql: &str| -> Vec<(i64, i64)> {
+        let mut stmt = conn.prepare(sql).unwrap();
+        stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    };
+    assert_eq!(
+        links("SELECT goat_id, vaccine_id FROM goat_vaccines ORDER BY goat_id, vaccine_id"),
+        vec![(1, 1), (2, 1), (2, 4)]
+    );
+    assert_eq!(
+        links("SELECT goat_id, vaccine_id FROM vaccination_schedule"),
+        vec![(2, 1)]
+    );
+    assert_eq!(
+        links("SELECT goat_id, disease_id FROM goat_diseases ORDER BY goat_id"),
+        vec![(1, 1), (2, 1)]
+    );
+    // Each diagnosis is kept against the surviving disease, and folding goat 1's two
+    // links into one resolves nothing.
+    let events = rows("SELECT disease_id, event FROM disease_events ORDER BY id");
+    assert_eq!(events.len(), 3);
+    assert!(
+        events
+            .iter()
+            .all(|(id, event)| *id == 1 && event == "Diagnosed")
+    );
+
+    assert!(
+        conn.execute("INSERT INTO diseases (name) VALUES ('MASTITIS')", [])
+            .is_err()
+    );
+}
+
+#[test]
+fn test_kinship_follows_shared_ancestry() {
+    let pedigree: Pedigree = HashMap::from([
+        (
+            3,
+            Parents {
+                sire_id: Some(1),
+                dam_id: Some(2),
+            },
+        ),
+        (
+            4,
+            Parents {
+                sire_id: Some(1),
+                dam_id: Some(2),
+            },
+        ),
+        (
+            5,
+            Parents {
+                sire_id: Some(1),
+                dam_id: Some(10),
+            },
+        ),
+        (
+            6,
+            Parents {
+                sire_id: Some(3),
+                dam_id: Some(4),
+            },
+        ),
+    ]);
+    assert_eq!(kinship(&pedigree, 3, 4), 0.25, "full siblings");
+    assert_eq!(kinship(&pedigree, 3, 5), 0.125, "half siblings");
+    assert_eq!(kinship(&pedigree, 1, 3), 0.25, "parent and offspring");
+    assert_eq!(kinship(&pedigree, 1, 2), 0.0, "unrelated founders");
+    // The kid of full siblings is itself inbred.
+    assert_eq!(kinship(&pedigree, 6, 6), 0.625);
+    assert!(shares_close_ancestor(&pedigree, 3, 5));
+    assert!(!shares_close_ancestor(&pedigree, 2, 10));
+}
+
+#[actix_rt::test]
+async fn test_breeding_plan_excludes_close_relatives_and_ranks_by_kinship() {
+    let db_pool = fresh_db("breeding_plan");
+    let ids: HashMap<&str, i64> = [
+        "Sire A",
+        "Dam B",
+        "Son S",
+        "Daughter D",
+        "Daughter K",
+        "Grandson L",
+        "Distant M",
+        "Outcross U",
+        "Doe E",
+    ]
+    .into_iter()
+    .map(|name| (name, seed_goat(&db_pool, name, "Beetal")))
+    .collect();
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute(
+            "UPDATE goats SET gender = 'Male' WHERE name IN \
+             ('Sire A', 'Son S', 'Grandson L', 'Distant M', 'Outcross U')",
+            [],
+        )
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats/{id}/pedigree", web::put().to(set_goat_pedigree))
+            .route("/breeding/plan", web::post().to(plan_breeding)),
+    )
+    .await;
+    let set_parents = |goat: i64, sire: Option<i64>, dam: Option<i64>| {
+        test::TestRequest::put()
+            .uri(&format!("/goats/{}/pedigree", goat))
+            .set_json(json!({ "sire_id": sire, "dam_id": dam }))
+            .to_request()
+    };
+    let plan = |sires: &[i64], dams: &[i64]| {
+        test::TestRequest::post()
+            .uri("/breeding/plan")
+            .set_json(json!({ "sire_ids": sires, "dam_ids": dams }))
+            .to_request()
+    };
+
+    let (a, b) = (ids["Sire A"], ids["Dam B"]);
+    for (child, sire, dam) in [
+        (ids["Son S"], Some(a), Some(b)),
+        (ids["Daughter D"], Some(a), Some(b)),
+        (ids["Daughter K"], Some(a), Some(b)),
+        (ids["Grandson L"], None, Some(ids["Daughter K"])),
+        (ids["Distant M"], Some(ids["Grandson L"]), None),
+    ] {
+        let resp = test::call_service(&app, set_parents(child, sire, dam)).await;
+        assert_eq!(resp.status(), 200);
+    }
+
+    // A goat cannot descend from itself, and parents must have the right gender.
+    let resp = test::call_service(&app, set_parents(a, Some(ids["Son S"]), None)).await;
+    assert_eq!(resp.status(), 422);
+    let errors = test::read_body_json::<ErrorBody, _>(resp).await.fields;
+    assert_eq!(errors[0].field, "sire_id");
+    let resp = test::call_service(&app, set_parents(ids["Doe E"], None, Some(a))).await;
+    assert_eq!(resp.status(), 422);
+    let resp = test::call_service(&app, set_parents(99_999, Some(a), None)).await;
+    assert_eq!(resp.status(), 404);
+
+    let d = ids["Daughter D"];
+    let sires = [a, ids["Son S"], ids["Distant M"], ids["Outcross U"]];
+    let suggestions: serde_json::Value =
+        test::call_and_read_body_json(&app, plan(&sires, &[d, ids["Doe E"]])).await;
+    // Both dams can be paired with an unrelated sire; the father and full brother of D
+    // are ruled out for her but not for E.
+    assert_eq!(suggestions[0]["dam_id"], d);
+    assert_eq!(suggestions[0]["sire_id"], ids["Outcross U"]);
+    assert_eq!(suggestions[0]["inbreeding_coefficient"], 0.0);
+    assert_eq!(
+        suggestions[0]["excluded_sire_ids"],
+        json!([a, ids["Son S"]])
+    );
+    assert_eq!(suggestions[1]["dam_id"], ids["Doe E"]);
+    assert_eq!(suggestions[1]["excluded_sire_ids"], json!([]));
+
+    // A distant relative is allowed, at his kinship with the dam.
+    let suggestions: serde_json::Value =
+        test::call_and_read_body_json(&app, plan(&[ids["Son S"], ids["Distant M"]], &[d])).await;
+    assert_eq!(suggestions[0]["sire_id"], ids["Distant M"]);
+    assert_eq!(suggestions[0]["inbreeding_coefficient"], 0.0625);
+
+    // With only close relatives on offer, the dam goes unmatched.
+    let suggestions: serde_json::Value =
+        test::call_and_read_body_json(&app, plan(&[a, ids["Son S"]], &[d])).await;
+    assert_eq!(suggestions[0]["sire_id"], serde_json::Value::Null);
+    assert_eq!(
+        suggestions[0]["inbreeding_coefficient"],
+        serde_json::Value::Null
+    );
+
+    let resp = test::call_service(&app, plan(&[d], &[])).await;
+    assert_eq!(resp.status(), 422);
+    let errors = test::read_body_json::<ErrorBody, _>(resp).await.fields;
+    let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(fields, ["dam_ids", "sire_ids[0]"]);
+}
+
+#[actix_rt::test]
+async fn test_farm_map_positions_and_svg() {
+    let db_pool = fresh_db("farm_map");
+    let goat_ids: Vec<i64> = (0..7)
+        .map(|i| seed_goat(&db_pool, &format!("Mapped {}", i), "Beetal"))
+        .collect();
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(&format!(
+            "INSERT INTO spaces (id, name, type, capacity) VALUES
+                (1, 'North Pen', 'enclosure', 10),
+                (2, 'Barn & Loft', 'enclosure', 2),
+                (3, 'Back Field', 'grazing_field', NULL),
+                (4, 'Unplaced', 'other', 5);
+             INSERT INTO space_goats (goat_id, space_id) VALUES
+                ({}, 1), ({}, 1), ({}, 1), ({}, 1), ({}, 1), ({}, 2), ({}, 2);",
+            goat_ids[0],
+            goat_ids[1],
+            goat_ids[2],
+            goat_ids[3],
+            goat_ids[4],
+            goat_ids[5],
+            goat_ids[6]
+        ))
+        .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/farm/map")
+                    .route("", web::get().to(get_farm_map))
+                    .route("/svg", web::get().to(get_farm_map_svg))
+                    .route("/{space_id}", web::put().to(update_space_position)),
+            ),
+    )
+    .await;
+    let place = |space_id: i64, position: serde_json::Value| {
+        test::TestRequest::put()
+            .uri(&format!("/farm/map/{}", space_id))
+            .set_json(position)
+            .to_request()
+    };
+
+    for (space_id, x) in [(1, 0.0), (2, 6.0), (3, 9.0)] {
+        let resp = test::call_service(
+            &app,
+            place(
+                space_id,
+                json!({ "x": x, "y": 0.0, "width": 5.0, "height": 4.0 }),
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), 200);
+    }
+    // Moving a space replaces its position.
+    let moved: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        place(
+            3,
+            json!({ "x": 12.0, "y": 1.5, "width": 8.0, "height": 4.0 }),
+        ),
+    )
+    .await;
+    assert_eq!(
+        moved["position"],
+        json!({ "x": 12.0, "y": 1.5, "width": 8.0, "height": 4.0 })
+    );
+    let resp = test::call_service(
+        &app,
+        place(
+            1,
+            json!({ "x": -1.0, "y": 0.0, "width": 0.0, "height": 4.0 }),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), 422);
+    let resp = test::call_service(
+        &app,
+        place(
+            99,
+            json!({ "x": 0.0, "y": 0.0, "width": 1.0, "height": 1.0 }),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), 404);
+
+    let map: Vec<serde_json::Value> =
+        test::call_and_read_body_json(&app, test::TestRequest::get().uri("/farm/map").to_request())
+            .await;
+    assert_eq!(map.len(), 4);
+    assert_eq!(map[0]["occupancy"], 5);
+    assert_eq!(map[0]["occupancy_pct"], 50.0);
+    assert_eq!(map[1]["occupancy_pct"], 100.0);
+    assert!(map[2]["occupancy_pct"].is_null());
+    assert!(map[3]["position"].is_null());
+
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::get().uri("/farm/map/svg").to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("content-type").unwrap(), "image/svg+xml");
+    let svg = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(svg.starts_with("<svg"), "not an SVG document: {}", svg);
+    assert!(svg.contains("<rect"));
+    assert_eq!(
+        svg.matches("<rect").count(),
+        3,
+        "unplaced spaces are not drawn"
+    );
+    assert!(
+        svg.contains(r##"fill="#4caf50""##),
+        "half-full pen is green"
+    );
+    assert!(svg.contains(r##"fill="#f44336""##), "full barn is red");
+    assert!(svg.contains("Barn &amp; Loft"));
+    assert!(!svg.contains("Unplaced"));
+    assert_eq!(occupancy_color(Some(80.0)), "#ffeb3b");
+}
+
+#[actix_rt::test]
+async fn test_list_goats_by_space() {
+    let db_pool = fresh_db("space_goats");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'PenGoat1', 'Female'),
+                (2, 'Beetal', 'PenGoat2', 'Male'),
+                (3, 'Beetal', 'FieldGoat', 'Male');
+             INSERT INTO vaccines (id, name) VALUES (1, 'CDT');
+             INSERT INTO goat_vaccines (goat_id, vaccine_id) VALUES (1, 1);
+             INSERT INTO spaces (id, name, type, capacity) VALUES
+                (1, 'Enclosure A', 'enclosure', 10),
+                (2, 'North Field', 'grazing_field', NULL);",
+        )
+        .expect("Failed to seed space data");
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/spaces")
+                .route("/{id}/goats", web::get().to(get_space_goats))
+                .route("/{id}/goats", web::post().to(assign_goat)),
+        ),
+    )
+    .await;
+
+    // Goat 2 starts in the field, then moves into the enclosure.
+    for (space_id, goat_id) in [(2, 2), (1, 1), (2, 3), (1, 2)] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/spaces/{}/goats", space_id))
+            .set_json(json!({ "goat_id": goat_id }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+    }
+
+    let req = test::TestRequest::get().uri("/spaces/1/goats").to_request();
+    let listing: SpaceGoats = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(listing.space.name, "Enclosure A");
+    assert_eq!(listing.occupancy, 2);
+    assert_eq!(listing.capacity, Some(10));
+    let names: Vec<&str> = listing
+        .goats
+        .iter()
+        .map(|g| g.params.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["PenGoat1", "PenGoat2"]);
+    assert_eq!(listing.goats[0].id(), Some(1));
+    assert_eq!(listing.goats[0].params.vaccinations.len(), 1);
+
+    let req = test::TestRequest::get()
+        .uri("/spaces/99/goats")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_rt::test]
+async fn test_goat_names_match_ignoring_case_and_whitespace() {
+    let db_pool = fresh_db("name_normalization");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/goats")
+                    .route("", web::post().to(add_goat))
+                    .route("", web::put().to(update_goat))
+                    .route("", web::delete().to(delete_goat)),
+            ),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(goat_json("  Billy  "))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["name"], "Billy");
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(goat_json("BILLY"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+    assert_eq!(count_goats(&db_pool), 1);
+
+    let mut update = goat_json("billy");
+    update["weight"] = json!(52.5);
+    update["version"] = json!(0);
+    let req = test::TestRequest::put()
+        .uri("/goats")
+        .set_json(&update)
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["name"], "Billy");
+    let weight: f64 = db_pool
+        .get_conn()
+        .unwrap()
+        .query_row("SELECT weight FROM goats WHERE name = 'Billy'", [], |r| {
+            r.get(0)
+        })
+        .unwrap();
+    assert_eq!(weight, 52.5);
+
+    let req = test::TestRequest::delete()
+        .uri("/goats")
+        .set_json(json!({ "name": " BILLY " }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["name"], "Billy");
+    let deleted: bool = db_pool
+        .get_conn()
+        .unwrap()
+        .query_row(
+            "SELECT deleted_at IS NOT NULL FROM goats WHERE name = 'Billy'",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert!(deleted);
+}
+
+#[actix_rt::test]
+async fn test_show_champions_and_prize_money() {
+    let db_pool = fresh_db("show_entries");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender, cost) VALUES
+                (1, 'Beetal', 'Champion', 'Female', 100.0),
+                (2, 'Beetal', 'RunnerUp', 'Female', 80.0),
+                (3, 'Sirohi', 'Retired', 'Male', 50.0);
+             UPDATE goats SET deleted_at = CURRENT_TIMESTAMP WHERE id = 3;
+             INSERT INTO show_entries (goat_id, show_name, show_date, placement, prize_amount) VALUES
+                (3, 'County Fair', '2023-08-01', 1, 40.0);",
+        )
+        .expect("Failed to seed show data");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .service(
+                web::scope("/goats")
+                    .route("/show-champions", web::get().to(get_show_champions))
+                    .route("/{id}/shows", web::post().to(add_show_entry)),
+            )
+            .route("/shows", web::get().to(get_show_entries))
+            .service(web::scope("/stats").route("/financial", web::get().to(get_financial_stats))),
+    )
+    .await;
+
+    let entries = [
+        (1, "Spring Show", "2024-04-10", 1, 150.0),
+        (2, "Spring Show", "2024-04-10", 2, 75.0),
+        (1, "State Fair", "2025-09-01", 3, 20.0),
+    ];
+    for (goat_id, show, date, placement, prize) in entries {
+        let req = test::TestRequest::post()
+            .uri(&format!("/goats/{}/shows", goat_id))
+            .set_json(json!({
+                "show_name": show,
+                "show_date": date,
+                "location": null,
+                "class": "Doe",
+                "placement": placement,
+                "prize_amount": prize,
+                "notes": null
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+    }
+
+    let req = test::TestRequest::post()
+        .uri("/goats/2/shows")
+        .set_json(json!({
+            "show_name": "Spring Show",
+            "show_date": "2024-04-10",
+            "location": null,
+            "class": null,
+            "placement": 0,
+            "prize_amount": null,
+            "notes": null
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+
+    let req = test::TestRequest::get()
+        .uri("/goats/show-champions")
+        .to_request();
+    let champions: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(champions.len(), 1);
+    assert_eq!(champions[0]["name"], "Champion");
+
+    let req = test::TestRequest::get()
+        .uri("/shows?year=2024")
+        .to_request();
+    let shows: Vec<ShowEntry> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(shows.len(), 2);
+    assert!(shows.iter().all(|s| s.show_date.starts_with("2024")));
+
+    let req = test::TestRequest::get()
+        .uri("/stats/financial")
+        .to_request();
+    let stats: FinancialStats = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(stats.total_prize_money, 285.0);
+    assert_eq!(stats.total_purchase_cost, 180.0);
+}
+
+/// Log sink shared between a test and the subscriber it installs.
+#[derive(Clone, Default)]
+struct CapturedLogs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl CapturedLogs {
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+#[test]
+fn test_slow_query_is_logged() {
+    let db_pool = fresh_db("slow_query");
+    let conn = db_pool.get_conn().unwrap();
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::WARN)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+
+    let slow_sql = "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 2000000) \
+                    SELECT COUNT(*) FROM n";
+    tracing::subscriber::with_default(subscriber, || {
+        let total: i64 = timed_with(Some(std::time::Duration::from_millis(1)), slow_sql, || {
+            conn.query_row(slow_sql, [], |r| r.get(0))
+        })
+        .unwrap();
+        assert_eq!(total, 2_000_000);
+
+        // Disabled timing never logs, however long the query takes.
+        let _: i64 = timed_with(None, "SELECT 1 -- disabled", || {
+            conn.query_row(slow_sql, [], |r| r.get(0))
+        })
+        .unwrap();
+    });
+
+    let output = logs.contents();
+    assert!(output.contains("Slow query"), "missing warn in: {}", output);
+    assert!(output.contains("WITH RECURSIVE n(i)"));
+    assert!(output.contains("elapsed_ms="));
+    assert!(!output.contains("-- disabled"));
+}
+
+#[test]
+fn test_diet_round_trip() {
+    for diet in [Diet::Hay, Diet::Pasture, Diet::Mixed, Diet::Concentrate] {
+        assert_eq!(str_to_diet(diet_to_str(&diet)), diet);
+        assert_eq!(parse_diet(diet_to_str(&diet)).unwrap(), diet);
+    }
+    assert_eq!(parse_diet(" HAY ").unwrap(), Diet::Hay);
+    assert_eq!(parse_diet("grass").unwrap(), Diet::Pasture);
+
+    // Reads keep legacy free text; API writes reject it.
+    let legacy = str_to_diet("kitchen scraps");
+    assert_eq!(legacy, Diet::Other("kitchen scraps".to_string()));
+    assert_eq!(diet_to_str(&legacy), "kitchen scraps");
+    assert!(parse_diet("kitchen scraps").is_err());
+}
+
+#[actix_rt::test]
+async fn test_get_goats_filters_by_diet() {
+    let db_pool = fresh_db("diet_filter");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (breed, name, gender, diet) VALUES
+                ('Beetal', 'HayGoat', 'Female', 'Hay'),
+                ('Beetal', 'FieldGoat', 'Female', 'Pasture'),
+                ('Beetal', 'OddGoat', 'Male', 'kitchen scraps');",
+        )
+        .expect("Failed to seed diet data");
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/goats")
+                .route("", web::get().to(get_goats))
+                .route("", web::post().to(add_goat)),
+        ),
+    )
+    .await;
+
+    let mut payload = goat_json("MeadowGoat");
+    payload["diet"] = json!("pasture");
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&payload)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    payload = goat_json("ScrapGoat");
+    payload["diet"] = json!("kitchen scraps");
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&payload)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::get()
+        .uri("/goats?diet=PASTURE")
+        .to_request();
+    let goats: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    let mut names: Vec<&str> = goats.iter().map(|g| g["name"].as_str().unwrap()).collect();
+    names.sort_unstable();
+    assert_eq!(names, vec!["FieldGoat", "MeadowGoat"]);
+    assert!(goats.iter().all(|g| g["diet"] == "Pasture"));
+
+    let req = test::TestRequest::get().uri("/goats").to_request();
+    let goats: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(goats.len(), 4);
+
+    let req = test::TestRequest::get()
+        .uri("/goats?diet=gruel")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_famacha_scores_and_action_needed() {
+    let db_pool = fresh_db("famacha");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'Anemic', 'Female'),
+                (2, 'Beetal', 'Recovered', 'Female'),
+                (3, 'Beetal', 'Healthy', 'Male'),
+                (4, 'Beetal', 'Borderline', 'Male');",
+        )
+        .expect("Failed to seed FAMACHA goats");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/goats")
+                    .route(
+                        "/famacha/action-needed",
+                        web::get().to(get_famacha_action_needed),
+                    )
+                    .route("/{id}/famacha", web::post().to(add_famacha_score))
+                    .route("/{id}/famacha/history", web::get().to(get_famacha_history)),
+            ),
+    )
+    .await;
+
+    for bad_score in [0, 6] {
+        let req = test::TestRequest::post()
+            .uri("/goats/1/famacha")
+            .set_json(json!({ "score": bad_score }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 422);
+    }
+
+    let scores = [
+        (1, 2, "2025-05-01 08:00:00"),
+        (1, 4, "2025-06-01 08:00:00"),
+        (1, 5, "2025-06-15 08:00:00"),
+        (2, 4, "2025-05-01 08:00:00"),
+        (2, 1, "2025-06-01 08:00:00"),
+        (3, 2, "2025-06-01 08:00:00"),
+        (4, 3, "2025-06-01"),
+    ];
+    for (goat_id, score, scored_at) in scores {
+        let req = test::TestRequest::post()
+            .uri(&format!("/goats/{}/famacha", goat_id))
+            .set_json(json!({ "score": score, "scored_at": scored_at, "scored_by": "vet" }))
+            .to_request();
+        let stored: FamachaScore = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(stored.goat_id, goat_id);
+        assert!(stored.id.is_some());
+    }
+
+    let req = test::TestRequest::get()
+        .uri("/goats/famacha/action-needed")
+        .to_request();
+    let alerts: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    let flagged: Vec<(&str, i64)> = alerts
+        .iter()
+        .map(|a| {
+            (
+                a["name"].as_str().unwrap(),
+                a["latest_score"].as_i64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(flagged, vec![("Anemic", 5), ("Borderline", 3)]);
+
+    let req = test::TestRequest::get()
+        .uri("/goats/1/famacha/history")
+        .to_request();
+    let history: Vec<FamachaScore> = test::call_and_read_body_json(&app, req).await;
+    let history_scores: Vec<i64> = history.iter().map(|s| s.score).collect();
+    assert_eq!(history_scores, vec![5, 4, 2]);
+
+    // Two high scores for goat 1 open only one pending deworming record.
+    let pending = |goat_id: i64| -> i64 {
+        db_pool
+            .get_conn()
+            .unwrap()
+            .query_row(
+                "SELECT COUNT(*) FROM deworming_records WHERE goat_id = ?1 AND status = 'pending'",
+                [goat_id],
+                |r| r.get(0),
+            )
+            .unwrap()
+    };
+    assert_eq!(pending(1), 1);
+    assert_eq!(pending(2), 1);
+    assert_eq!(pending(3), 0);
+    assert_eq!(pending(4), 1);
+}
+
+#[actix_rt::test]
+async fn test_add_goat_returns_created_goat() {
+    let db_pool = fresh_db("add_goat_body");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .service(web::scope("/goats").route("", web::post().to(add_goat))),
+    )
+    .await;
+
+    let mut payload = goat_json("  Created   Goat ");
+    payload["diet"] = json!("grass");
+    payload["vaccinations"] = json!([{ "id": null, "name": "CDT" }]);
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&payload)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+
+    let location = resp
+        .headers()
+        .get("location")
+        .expect("Missing Location header")
+        .to_str()
+        .unwrap()
+        .to_string();
+    let created: Goat = test::read_body_json(resp).await;
+    let id = created.id().expect("Created goat has no id");
+    assert_eq!(location, format!("/goats/{}", id));
+    assert_eq!(created.params.name, "Created Goat");
+    assert_eq!(created.params.diet, "Pasture");
+    assert_eq!(created.params.vaccinations.len(), 1);
+    assert!(created.params.vaccinations[0].id.is_some());
+}
+
+#[actix_rt::test]
+async fn test_cohort_membership_and_stats() {
+    let db_pool = fresh_db("cohorts");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender, weight, health_status) VALUES
+                (1, 'Beetal', 'CohortGoat1', 'Female', 30.0, 'healthy'),
+                (2, 'Beetal', 'CohortGoat2', 'Female', 40.0, 'healthy'),
+                (3, 'Beetal', 'CohortGoat3', 'Male', 50.0, 'sick'),
+                (4, 'Beetal', 'CohortGoat4', 'Male', 60.0, 'healthy');
+             INSERT INTO vaccines (id, name) VALUES (1, 'CDT'), (2, 'Rabies');
+             INSERT INTO goat_vaccines (goat_id, vaccine_id) VALUES (1, 1), (2, 1), (3, 2), (4, 1);",
+        )
+        .expect("Failed to seed cohort goats");
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/cohorts")
+                .route("", web::post().to(create_cohort))
+                .route("/{id}/add", web::post().to(add_cohort_goat))
+                .route(
+                    "/{id}/remove/{goat_id}",
+                    web::delete().to(remove_cohort_goat),
+                )
+                .route("/{id}/goats", web::get().to(get_cohort_goats))
+                .route("/{id}/stats", web::get().to(get_cohort_stats)),
+        ),
+    )
+    .await;
+
+    let mut cohort_ids = Vec::new();
+    for name in ["Spring Kids", "Trial Feed"] {
+        let req = test::TestRequest::post()
+            .uri("/cohorts")
+            .set_json(json!({ "name": name }))
+            .to_request();
+        let cohort: Cohort = test::call_and_read_body_json(&app, req).await;
+        cohort_ids.push(cohort.id.unwrap());
+    }
+    let (spring, trial) = (cohort_ids[0], cohort_ids[1]);
+
+    let req = test::TestRequest::post()
+        .uri("/cohorts")
+        .set_json(json!({ "name": "Spring Kids" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    // Goats 2 and 3 belong to both cohorts.
+    for (cohort_id, goat_id) in [
+        (spring, 1),
+        (spring, 2),
+        (spring, 3),
+        (trial, 2),
+        (trial, 3),
+        (trial, 4),
+    ] {
+        let req = test::TestRequest::post()
+            .uri(&format!("/cohorts/{}/add", cohort_id))
+            .set_json(json!({ "goat_id": goat_id }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+    }
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/cohorts/{}/goats", trial))
+        .to_request();
+    let goats: Vec<Goat> = test::call_and_read_body_json(&app, req).await;
+    let ids: Vec<Option<i64>> = goats.iter().map(|g| g.id()).collect();
+    assert_eq!(ids, vec![Some(2), Some(3), Some(4)]);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/cohorts/{}/stats", spring))
+        .to_request();
+    let stats: CohortStats = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(stats.goat_count, 3);
+    assert_eq!(stats.average_weight, Some(40.0));
+    assert_eq!(stats.health_status_distribution.get("healthy"), Some(&2));
+    assert_eq!(stats.health_status_distribution.get("sick"), Some(&1));
+    assert_eq!(stats.vaccination_coverage.len(), 2);
+    assert_eq!(stats.vaccination_coverage[0].vaccine, "CDT");
+    assert_eq!(stats.vaccination_coverage[0].vaccinated_count, 2);
+    assert!((stats.vaccination_coverage[0].coverage_pct - 200.0 / 3.0).abs() < 1e-9);
+
+    // Removing a goat from one cohort leaves its other membership intact.
+    let req = test::TestRequest::delete()
+        .uri(&format!("/cohorts/{}/remove/2", spring))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    let req = test::TestRequest::delete()
+        .uri(&format!("/cohorts/{}/remove/2", spring))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/cohorts/{}/stats", spring))
+        .to_request();
+    let stats: CohortStats = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(stats.goat_count, 2);
+    assert_eq!(stats.average_weight, Some(40.0));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/cohorts/{}/stats", trial))
+        .to_request();
+    let stats: CohortStats = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(stats.goat_count, 3);
+    assert_eq!(stats.average_weight, Some(50.0));
+}
+
+#[test]
+fn test_gender_round_trips_including_wether() {
+    for name in ["Male", "Female", "Wether"] {
+        let gender = str_to_gender(name).expect("known gender");
+        assert_eq!(gender_to_str(&gender), name);
+    }
+    assert!(str_to_gender("Buck").is_err());
+}
+
+#[actix_rt::test]
+async fn test_wethers_are_not_breeding_eligible() {
+    let db_pool = fresh_db("wethers");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(web::scope("/goats").route("", web::post().to(add_goat)))
+            .service(web::scope("/admin").route(
+                "/goats/reclassify-wethers",
+                web::post().to(reclassify_wethers),
+            )),
+    )
+    .await;
+
+    let mut bred_wether = goat_json("BredWether");
+    bred_wether["gender"] = json!("Wether");
+    bred_wether["last_bred"] = json!("2025-03-01");
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&bred_wether)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let errors = test::read_body_json::<ErrorBody, _>(resp).await.fields;
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "last_bred");
+
+    // Old clients that only know Male/Female keep working.
+    let mut buck = goat_json("Buck");
+    buck["gender"] = json!("Male");
+    buck["last_bred"] = json!("2025-03-01");
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&buck)
+        .to_request();
+    let created: Goat = test::call_and_read_body_json(&app, req).await;
+    let buck_id = created.id().unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(goat_json("Doe"))
+        .to_request();
+    let doe: Goat = test::call_and_read_body_json(&app, req).await;
+    let doe_id = doe.id().unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/admin/goats/reclassify-wethers")
+        .set_json(json!({ "goat_ids": [buck_id, doe_id, 999] }))
+        .to_request();
+    let summary: ReclassifySummary = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(summary.reclassified, vec![buck_id]);
+    assert_eq!(summary.skipped, vec![doe_id, 999]);
+
+    let (gender, last_bred): (String, Option<String>) = db_pool
+        .get_conn()
+        .unwrap()
+        .query_row(
+            "SELECT gender, last_bred FROM goats WHERE id = ?1",
+            [buck_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(gender, "Wether");
+    assert_eq!(last_bred, None);
+}
+
+#[actix_rt::test]
+async fn test_goat_relation_sub_resources() {
+    let db_pool = fresh_db("goat_relations");
+
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/goats")
+                .route("", web::post().to(add_goat))
+                .route("/{id}/vaccines", web::get().to(get_goat_vaccines))
+                .route("/{id}/diseases", web::get().to(get_goat_diseases)),
+        ),
+    )
+    .await;
+
+    let mut payload = goat_json("RelationsGoat");
+    payload["vaccinations"] = json!([{ "id": null, "name": "CDT" }, { "id": null, "name": "PPR" }]);
+    payload["diseases"] = json!([{ "id": null, "name": "Mastitis" }]);
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&payload)
+        .to_request();
+    let goat: Goat = test::call_and_read_body_json(&app, req).await;
+    let goat_id = goat.id().unwrap();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/goats/{}/vaccines", goat_id))
+        .to_request();
+    let vaccines: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    let mut names: Vec<&str> = vaccines
+        .iter()
+        .map(|v| v["name"].as_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    assert_eq!(names, vec!["CDT", "PPR"]);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/goats/{}/diseases", goat_id))
+        .to_request();
+    let diseases: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(diseases.len(), 1);
+    assert_eq!(diseases[0]["name"], "Mastitis");
+
+    for relation in ["vaccines", "diseases"] {
+        let req = test::TestRequest::get()
+            .uri(&format!("/goats/999/{}", relation))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+}
+
+#[actix_rt::test]
+async fn test_breed_registry_lists_custom_breeds() {
+    let db_pool = fresh_db("breeds");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (breed, name, gender) VALUES ('Saanen', 'Import', 'Female');",
+        )
+        .expect("Failed to seed goat");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .route("/breeds", web::get().to(list_breeds))
+            .route("/breeds", web::post().to(register_breed)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/breeds")
+        .set_json(json!({ "name": "Boer" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let created: CustomBreed = test::read_body_json(resp).await;
+
+    let req = test::TestRequest::post()
+        .uri("/breeds")
+        .set_json(json!({ "name": "  boer " }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let existing: CustomBreed = test::read_body_json(resp).await;
+    assert_eq!(existing, created);
+
+    let req = test::TestRequest::post()
+        .uri("/breeds")
+        .set_json(json!({ "name": "beetal" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    let req = test::TestRequest::get().uri("/breeds").to_request();
+    let breeds: Vec<String> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(breeds.len(), 12);
+    assert_eq!(breeds[0], "Beetal");
+    assert_eq!(&breeds[10..], ["Boer", "Saanen"]);
+}
+
+#[actix_rt::test]
+async fn test_medicine_dispensing_and_expiry() {
+    let db_pool = fresh_db("medicine_inventory");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch("INSERT INTO goats (id, breed, name, gender) VALUES (1, 'Beetal', 'Patient', 'Female');")
+        .expect("Failed to seed goat");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/inventory/medicine")
+                    .route("", web::post().to(add_medicine))
+                    .route("/expiring", web::get().to(get_expiring_medicines))
+                    .route("/low-stock", web::get().to(get_low_stock_medicines))
+                    .route("/{id}/dispense", web::post().to(dispense_medicine)),
+            ),
+    )
+    .await;
+
+    let today = chrono::Utc::now().date_naive();
+    let mut ids = Vec::new();
+    for (name, stock, expires_in) in [("Ivermectin", 10.0, 10), ("Oxytetracycline", 3.0, 90)] {
+        let req = test::TestRequest::post()
+            .uri("/inventory/medicine")
+            .set_json(json!({
+                "name": name,
+                "category": "Antiparasitic",
+                "stock_units": stock,
+                "unit": "ml",
+                "expiry_date": (today + chrono::Duration::days(expires_in)).to_string(),
+                "cost_per_unit": 2.5
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let item: MedicineItem = test::read_body_json(resp).await;
+        ids.push(item.id.unwrap());
+    }
+
+    let dispense = |amount: f64| {
+        test::TestRequest::post()
+            .uri(&format!("/inventory/medicine/{}/dispense", ids[0]))
+            .set_json(json!({ "goat_id": 1, "amount": amount, "reason": "Worm load" }))
+            .to_request()
+    };
+    let item: MedicineItem = test::call_and_read_body_json(&app, dispense(4.0)).await;
+    assert_eq!(item.stock_units, 6.0);
+
+    // Overdrawing is rejected and leaves stock and the dispensing log untouched.
+    assert_eq!(test::call_service(&app, dispense(6.5)).await.status(), 409);
+    let (stock, logged): (f64, i64) = db_pool
+        .get_conn()
+        .unwrap()
+        .query_row(
+            "SELECT stock_units, (SELECT COUNT(*) FROM medicine_dispensing)              FROM medicine_inventory WHERE id = ?1",
+            [ids[0]],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(stock, 6.0);
+    assert_eq!(logged, 1);
+
+    let req = test::TestRequest::get()
+        .uri("/inventory/medicine/expiring?days=30")
+        .to_request();
+    let expiring: Vec<MedicineItem> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(expiring.len(), 1);
+    assert_eq!(expiring[0].name, "Ivermectin");
+
+    let req = test::TestRequest::get()
+        .uri("/inventory/medicine/expiring?days=120")
+        .to_request();
+    let expiring: Vec<MedicineItem> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(expiring.len(), 2);
+
+    let req = test::TestRequest::get()
+        .uri("/inventory/medicine/low-stock?threshold=5")
+        .to_request();
+    let low: Vec<MedicineItem> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(low.len(), 1);
+    assert_eq!(low[0].id, Some(ids[1]));
+}
+
+#[actix_rt::test]
+async fn test_behavior_validation_and_concern_window() {
+    let db_pool = fresh_db("behavior");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'Stale', 'Female'),
+                (2, 'Beetal', 'Grumpy', 'Male'),
+                (3, 'Beetal', 'Calm', 'Female'),
+                (4, 'Beetal', 'Picky', 'Female');",
+        )
+        .expect("Failed to seed goats");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/goats")
+                    .route(
+                        "/behavior/concerning",
+                        web::get().to(get_concerning_behavior),
+                    )
+                    .route("/{id}/behavior", web::post().to(add_behavior_observation))
+                    .route(
+                        "/{id}/behavior/history",
+                        web::get().to(get_behavior_history),
+                    ),
+            ),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/goats/1/behavior")
+        .set_json(json!({ "behavior": "Sulking", "intensity": "Extreme" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let errors = test::read_body_json::<ErrorBody, _>(resp).await.fields;
+    let mut fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+    fields.sort_unstable();
+    assert_eq!(fields, vec!["behavior", "intensity"]);
+
+    let now = chrono::Utc::now();
+    let observations = [
+        (
+            1,
+            "Lethargic",
+            "Moderate",
+            now - chrono::Duration::hours(30),
+        ),
+        (2, "Aggressive", "Mild", now - chrono::Duration::hours(2)),
+        (3, "Grazing", "Mild", now - chrono::Duration::hours(1)),
+        (4, "Off_Feed", "Severe", now - chrono::Duration::minutes(10)),
+    ];
+    for (goat_id, behavior, intensity, at) in observations {
+        let req = test::TestRequest::post()
+            .uri(&format!("/goats/{}/behavior", goat_id))
+            .set_json(json!({
+                "behavior": behavior,
+                "intensity": intensity,
+                "recorded_at": at.to_rfc3339(),
+                "observed_by": "Asha"
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let stored: BehaviorObservation = test::read_body_json(resp).await;
+        assert_eq!(stored.goat_id, goat_id);
+    }
+
+    // Goat 1's only concern is older than 24 hours; goat 3 was seen grazing.
+    let req = test::TestRequest::get()
+        .uri("/goats/behavior/concerning")
+        .to_request();
+    let concerns: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    let names: Vec<&str> = concerns
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Picky", "Grumpy"]);
+    assert_eq!(concerns[0]["behavior"], "Off_Feed");
+
+    let alerts: i64 = db_pool
+        .get_conn()
+        .unwrap()
+        .query_row(
+            "SELECT COUNT(*) FROM alerts WHERE goat_id = 4 AND kind = 'behavior'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(alerts, 1);
+
+    let req = test::TestRequest::get()
+        .uri("/goats/1/behavior/history")
+        .to_request();
+    let history: Vec<BehaviorObservation> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].behavior, "Lethargic");
+}
+
+#[test]
+fn test_weight_unit_conversions() {
+    assert_eq!(UnitSystem::from_param(None), UnitSystem::Metric);
+    assert_eq!(UnitSystem::from_param(Some("metric")), UnitSystem::Metric);
+    assert_eq!(
+        UnitSystem::from_param(Some(" Imperial ")),
+        UnitSystem::Imperial
+    );
+    assert_eq!(UnitSystem::from_param(Some("mixed")), UnitSystem::Metric);
+    assert_eq!(UnitSystem::Metric.weight_unit(), WeightUnit::Kg);
+    assert_eq!(UnitSystem::Imperial.weight_unit(), WeightUnit::Lb);
+
+    for raw in ["lb", "LBS", "pound", "Pounds"] {
+        assert_eq!(WeightUnit::from_param(Some(raw)), WeightUnit::Lb);
+    }
+    for raw in [None, Some("kg"), Some("stone"), Some("")] {
+        assert_eq!(WeightUnit::from_param(raw), WeightUnit::Kg);
+    }
+    assert_eq!(WeightUnit::Kg.label(), "kg");
+    assert_eq!(WeightUnit::Lb.label(), "lb");
+
+    assert_eq!(WeightUnit::Kg.to_kg(41.5), 41.5);
+    assert!((WeightUnit::Lb.to_kg(LB_PER_KG) - 1.0).abs() < 1e-12);
+    assert_eq!(WeightUnit::Kg.from_kg(40.123), 40.123);
+    assert_eq!(WeightUnit::Lb.from_kg(40.0), 88.18);
+    assert_eq!(WeightUnit::Lb.from_kg(0.0), 0.0);
+    assert_eq!(WeightUnit::Lb.from_kg(WeightUnit::Lb.to_kg(123.45)), 123.45);
+
+    assert_eq!(round2(1.005_000_1), 1.01);
+    assert_eq!(round2(-2.345_1), -2.35);
+    assert_eq!(round2(7.0), 7.0);
+}
+
+#[actix_rt::test]
+async fn test_goat_weights_in_imperial_units() {
+    let db_pool = fresh_db("imperial_units");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/goats")
+                    .route("", web::get().to(get_goats))
+                    .route("", web::post().to(add_goat)),
+            ),
+    )
+    .await;
+
+    let mut payload = goat_json("Heavy");
+    payload["weight"] = json!(88.18);
+    payload["weight_unit"] = json!("lb");
+    let req = test::TestRequest::post()
+        .uri("/goats?units=imperial")
+        .set_json(&payload)
+        .to_request();
+    let created: Goat = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(created.params.weight, 88.18);
+
+    let stored: f64 = db_pool
+        .get_conn()
+        .unwrap()
+        .query_row("SELECT weight FROM goats WHERE name = 'Heavy'", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert!((stored - 40.0).abs() < 0.01);
+
+    let req = test::TestRequest::get()
+        .uri("/goats?units=imperial")
+        .to_request();
+    let goats: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(goats[0]["weight"], 88.18);
+
+    let req = test::TestRequest::get().uri("/goats").to_request();
+    let goats: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    assert!((goats[0]["weight"].as_f64().unwrap() - 40.0).abs() < 0.01);
+}
+
+#[test]
+fn test_overdue_vaccination_scan_raises_alerts_once() {
+    let db_pool = fresh_db("overdue_vaccinations");
+    let conn = db_pool.get_conn().unwrap();
+    conn.execute_batch(
+        "INSERT INTO goats (id, breed, name, gender, deleted_at) VALUES
+            (1, 'Beetal', 'Late', 'Female', NULL),
+            (2, 'Beetal', 'Boosted', 'Female', NULL),
+            (3, 'Beetal', 'Gone', 'Male', '2026-01-01 00:00:00');
+         INSERT INTO vaccines (id, name) VALUES (1, 'CDT'), (2, 'PPR');
+         INSERT INTO vaccination_schedule (goat_id, vaccine_id, administered_on, next_due_on) VALUES
+            (1, 1, '2025-03-01', '2026-03-01'),
+            (1, 2, '2025-09-01', '2026-09-01'),
+            (2, 1, '2025-03-01', '2026-03-01'),
+            (2, 1, '2026-03-05', '2027-03-05'),
+            (3, 1, '2025-03-01', '2026-03-01');",
+    )
+    .expect("Failed to seed vaccination schedule");
+
+    let today = chrono::NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+    let overdue = scan_overdue_vaccinations(&conn, today).unwrap();
+    assert_eq!(overdue.len(), 1);
+    assert_eq!(overdue[0].goat_name, "Late");
+    assert_eq!(overdue[0].vaccine, "CDT");
+    assert_eq!(overdue[0].days_overdue, 92);
+
+    // A second scan finds the same entry but does not duplicate the open alert.
+    assert_eq!(scan_overdue_vaccinations(&conn, today).unwrap().len(), 1);
+    let alerts: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM alerts WHERE goat_id = 1 AND kind = ?1",
+            [OVERDUE_VACCINATION_ALERT],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(alerts, 1);
+}
+
+#[test]
+fn test_money_round_trips_and_legacy_input() {
+    let money = Money::from_major(123.45, Currency::Inr);
+    assert_eq!(money.amount, 12345);
+    assert_eq!(money.to_major(), 123.45);
+    assert_eq!(
+        serde_json::to_value(money).unwrap(),
+        json!({ "amount": 12345, "currency": "INR" })
+    );
+
+    let legacy: MoneyInput = serde_json::from_value(json!(99.99)).unwrap();
+    assert_eq!(
+        legacy.resolve(),
+        Ok(Money::from_major(99.99, Currency::Inr))
+    );
+    let structured: MoneyInput =
+        serde_json::from_value(json!({ "amount": 500, "currency": "inr" })).unwrap();
+    assert_eq!(structured.resolve().unwrap().amount, 500);
+    let foreign: MoneyInput =
+        serde_json::from_value(json!({ "amount": 500, "currency": "USD" })).unwrap();
+    assert_eq!(foreign.resolve(), Err("USD".to_string()));
+
+    // Goats serialize money as objects and read back either form.
+    let mut legacy_goat = goat_json("Legacy");
+    legacy_goat["id"] = json!(7);
+    let goat: Goat = serde_json::from_value(legacy_goat).unwrap();
+    assert_eq!(goat.params.cost, 100.0);
+    let value = serde_json::to_value(&goat).unwrap();
+    assert_eq!(value["id"], 7);
+    assert_eq!(value["cost"], json!({ "amount": 10000, "currency": "INR" }));
+    let again: Goat = serde_json::from_value(value).unwrap();
+    assert_eq!(again.params.current_price, 120.0);
+    assert_eq!(again.id(), Some(7));
+}
+
+#[actix_rt::test]
+async fn test_omitted_goat_prices_are_estimated_from_the_breed() {
+    let db_pool = fresh_db("breed_price_hint");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (breed, name, gender, cost, current_price, cost_minor, current_price_minor)
+             VALUES ('Beetal', 'Priced1', 'Female', 100.0, 120.0, 10000, 12000),
+                    ('Beetal', 'Priced2', 'Female', 200.0, 240.0, NULL, NULL);
+             INSERT INTO goats (breed, name, gender, cost, current_price, deleted_at)
+             VALUES ('Beetal', 'Gone', 'Male', 9000.0, 9000.0, CURRENT_TIMESTAMP);",
+        )
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .service(web::scope("/goats").route("", web::post().to(add_goat))),
+    )
+    .await;
+
+    let mut unpriced = goat_json("Unpriced");
+    unpriced.as_object_mut().unwrap().remove("cost");
+    unpriced.as_object_mut().unwrap().remove("current_price");
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&unpriced)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["cost"], json!({ "amount": 15000, "currency": "INR" }));
+    assert_eq!(
+        body["current_price"],
+        json!({ "amount": 18000, "currency": "INR" })
+    );
+    assert_eq!(body["cost_estimated"], json!(true));
+
+    // A price that is sent is kept; only the missing one is estimated.
+    let mut half_priced = goat_json("Half Priced");
+    half_priced.as_object_mut().unwrap().remove("current_price");
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&half_priced)
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["cost"], json!({ "amount": 10000, "currency": "INR" }));
+    assert_eq!(body["cost_estimated"], json!(true));
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(goat_json("Fully Priced"))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!(body.get("cost_estimated").is_none(), "{}", body);
+}
+
+#[actix_rt::test]
+async fn test_omitted_goat_prices_are_required_without_breed_data() {
+    let db_pool = fresh_db("breed_price_required");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(web::scope("/goats").route("", web::post().to(add_goat))),
+    )
+    .await;
+
+    let mut unpriced = goat_json("First Of Its Breed");
+    unpriced["breed"] = json!("Sirohi");
+    unpriced.as_object_mut().unwrap().remove("cost");
+    unpriced.as_object_mut().unwrap().remove("current_price");
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&unpriced)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let errors = test::read_body_json::<ErrorBody, _>(resp).await.fields;
+    let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(fields, vec!["cost", "current_price"]);
+    assert_eq!(count_goats(&db_pool), 0);
+}
+
+#[actix_rt::test]
+async fn test_goat_money_fields_are_stored_in_minor_units() {
+    let db_pool = fresh_db("goat_money");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/goats")
+                    .route("", web::get().to(get_goats))
+                    .route("", web::post().to(add_goat)),
+            )
+            .service(web::scope("/stats").route("/financial", web::get().to(get_financial_stats))),
+    )
+    .await;
+
+    let mut structured = goat_json("Structured");
+    structured["cost"] = json!({ "amount": 10, "currency": "INR" });
+    structured["current_price"] = json!({ "amount": 12345, "currency": "INR" });
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&structured)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(
+        body["current_price"],
+        json!({ "amount": 12345, "currency": "INR" })
+    );
+
+    // Legacy clients still send bare rupee amounts.
+    let mut legacy = goat_json("Legacy");
+    legacy["cost"] = json!(0.2);
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&legacy)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+
+    let minor: Vec<(i64, i64)> = db_pool
+        .get_conn()
+        .unwrap()
+        .prepare("SELECT cost_minor, current_price_minor FROM goats ORDER BY id")
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(minor, vec![(10, 12345), (20, 12000)]);
+
+    let mut foreign = goat_json("Foreign");
+    foreign["cost"] = json!({ "amount": 100, "currency": "USD" });
+    foreign["current_price"] = json!({ "amount": -5, "currency": "INR" });
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(&foreign)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let errors = test::read_body_json::<ErrorBody, _>(resp).await.fields;
+    let mut fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+    fields.sort_unstable();
+    assert_eq!(fields, vec!["cost", "current_price"]);
+
+    let req = test::TestRequest::get().uri("/goats").to_request();
+    let goats: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(goats[1]["cost"], json!({ "amount": 20, "currency": "INR" }));
+
+    // Sums are exact in paise: 0.10 + 0.20 is 0.30, not 0.30000000000000004.
+    let req = test::TestRequest::get()
+        .uri("/stats/financial")
+        .to_request();
+    let stats: FinancialStats = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(stats.total_purchase_cost, 0.3);
+    assert_eq!(stats.total_herd_value, 243.45);
+}
+
+#[actix_rt::test]
+async fn test_worker_tasks_and_performance() {
+    let db_pool = fresh_db("worker_tasks");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO workers (id, name, hours_worked, leaves, role, contact)
+                VALUES (1, 'Ravi', 160, 0, 'Herder', 'ravi@farm.com');
+             INSERT INTO worker_tasks (worker_id, task, assigned_at, due_at, completed_at) VALUES
+                (1, 'Trim hooves', datetime('now', '-10 hours'), NULL, datetime('now', '-6 hours')),
+                (1, 'Clean pens', datetime('now', '-3 days'), datetime('now', '-1 day'), NULL),
+                (1, 'Old chore', datetime('now', '-40 days'), datetime('now', '-35 days'), NULL);",
+        )
+        .expect("Failed to seed worker tasks");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/workers")
+                    .route("/{id}/tasks", web::post().to(add_worker_task))
+                    .route("/{id}/tasks", web::get().to(get_worker_tasks))
+                    .route(
+                        "/{id}/tasks/{task_id}/complete",
+                        web::patch().to(complete_worker_task),
+                    )
+                    .route("/{id}/performance", web::get().to(get_worker_performance)),
+            ),
+    )
+    .await;
+
+    // Two tasks fall in the 30-day window: one done in 4 hours, one open and overdue.
+    let req = test::TestRequest::get()
+        .uri("/workers/1/performance")
+        .to_request();
+    let perf: WorkerPerformance = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(perf.total_tasks, 2);
+    assert_eq!(perf.task_completion_pct, 50.0);
+    assert_eq!(perf.overdue_tasks, 1);
+    assert!((perf.avg_completion_hours - 4.0).abs() < 0.01);
+
+    let req = test::TestRequest::post()
+        .uri("/workers/1/tasks")
+        .set_json(json!({ "task": "Refill water", "priority": "Urgent" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri("/workers/1/tasks")
+        .set_json(json!({ "task": "Refill water", "priority": "High" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let created: WorkerTask = test::read_body_json(resp).await;
+    assert_eq!(created.worker_id, 1);
+    assert!(created.assigned_at.is_some());
+    assert!(created.completed_at.is_none());
+
+    let req = test::TestRequest::get()
+        .uri("/workers/1/tasks?completed=false")
+        .to_request();
+    let open: Vec<WorkerTask> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(open.len(), 3);
+    assert!(open.iter().all(|t| t.completed_at.is_none()));
+
+    let complete = test::TestRequest::patch()
+        .uri(&format!(
+            "/workers/1/tasks/{}/complete",
+            created.id.unwrap()
+        ))
+        .to_request();
+    let done: WorkerTask = test::call_and_read_body_json(&app, complete).await;
+    assert!(done.completed_at.is_some());
+
+    let again = test::TestRequest::patch()
+        .uri(&format!(
+            "/workers/1/tasks/{}/complete",
+            created.id.unwrap()
+        ))
+        .to_request();
+    assert_eq!(test::call_service(&app, again).await.status(), 409);
+
+    let req = test::TestRequest::get()
+        .uri("/workers/99/performance")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+#[actix_rt::test]
+async fn test_rate_limit_rejects_requests_over_the_limit() {
+    let db_pool = fresh_db("rate_limit");
+    let limit = 3;
+
+    let app = test::init_service(
+        App::new()
+            .wrap(middleware::from_fn(rate_limit))
+            .app_data(web::Data::new(db_pool))
+            .app_data(web::Data::new(AppState::default()))
+            .app_data(web::Data::new(
+                RateLimiter::new(limit)
+                    .with_api_keys(["client-a".to_string(), "client-b".to_string()]),
+            ))
+            .route("/health", web::get().to(health))
+            .service(web::scope("/goats").route("", web::get().to(get_goats))),
+    )
+    .await;
+
+    let goats = |key: &str| {
+        test::TestRequest::get()
+            .uri("/goats")
+            .insert_header(("X-API-Key", key))
+            .to_request()
+    };
+
+    for _ in 0..limit {
+        assert_eq!(
+            test::call_service(&app, goats("client-a")).await.status(),
+            200
+        );
+    }
+    let resp = test::call_service(&app, goats("client-a")).await;
+    assert_eq!(resp.status(), 429);
+    let retry_after: u64 = resp.headers()["Retry-After"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=20).contains(&retry_after));
+
+    // Buckets are per client, and health checks are never limited.
+    assert_eq!(
+        test::call_service(&app, goats("client-b")).await.status(),
+        200
+    );
+    for _ in 0..limit + 2 {
+        let req = test::TestRequest::get()
+            .uri("/health")
+            .insert_header(("X-API-Key", "client-a"))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+    }
+}
+
+#[actix_rt::test]
+async fn test_rate_limit_ignores_unknown_keys_and_forwarding_headers() {
+    let db_pool = fresh_db("rate_limit_peer");
+    let limit = 2;
+
+    let app = test::init_service(
+        App::new()
+            .wrap(middleware::from_fn(rate_limit))
+            .app_data(web::Data::new(db_pool))
+            .app_data(web::Data::new(AppState::default()))
+            .app_data(web::Data::new(
+                RateLimiter::new(limit).with_api_keys(["known".to_string()]),
+            ))
+            .service(web::scope("/goats").route("", web::get().to(get_goats))),
+    )
+    .await;
+
+    let peer: std::net::SocketAddr = "10.0.0.7:5000".parse().unwrap();
+    let goats = |i: u32| {
+        test::TestRequest::get()
+            .uri("/goats")
+            .peer_addr(peer)
+            .insert_header(("X-API-Key", format!("made-up-{}", i)))
+            .insert_header(("X-Forwarded-For", format!("203.0.113.{}", i)))
+            .to_request()
+    };
+
+    // Fresh unknown keys and spoofed forwarding addresses all share the peer's bucket.
+    for i in 0..limit {
+        assert_eq!(test::call_service(&app, goats(i)).await.status(), 200);
+    }
+    assert_eq!(test::call_service(&app, goats(limit)).await.status(), 429);
+
+    // A configured key still gets a bucket of its own.
+    let req = test::TestRequest::get()
+        .uri("/goats")
+        .peer_addr(peer)
+        .insert_header(("X-API-Key", "known"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+}
+
+#[test]
+fn test_rate_limiter_sweeps_idle_buckets() {
+    let limiter = RateLimiter::new(60);
+    limiter.check("idle").unwrap();
+    limiter.check("busy").unwrap();
+    assert_eq!(limiter.tracked_clients(), 2);
+
+    // Nothing has refilled yet, so nothing is swept.
+    limiter.sweep();
+    assert_eq!(limiter.tracked_clients(), 2);
+
+    // After a full refill period every bucket is back to capacity and can go.
+    let later = Instant::now() + Duration::from_secs(61);
+    limiter.sweep_at(later);
+    assert_eq!(limiter.tracked_clients(), 0);
+    limiter.check("busy").unwrap();
+    assert_eq!(limiter.tracked_clients(), 1);
+}
+
+#[actix_rt::test]
+async fn test_activity_feed_lists_recent_changes() {
+    let db_pool = fresh_db("activity_feed");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/activity", web::get().to(get_activity))
+            .service(
+                web::scope("/goats")
+                    .route("", web::post().to(add_goat))
+                    .route("", web::put().to(update_goat))
+                    .route("", web::delete().to(delete_goat)),
+            ),
+    )
+    .await;
+
+    let goat = |weight: f64| {
+        json!({
+            "breed": "Beetal",
+            "name": "Activity",
+            "gender": "Female",
+            "offspring": 0,
+            "cost": 100.0,
+            "weight": weight,
+            "current_price": 120.0,
+            "diet": "Hay",
+            "last_bred": null,
+            "health_status": "healthy",
+            "vaccinations": [],
+            "diseases": [],
+            "version": 0
+        })
+    };
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(goat(30.0))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 201);
+    let req = test::TestRequest::put()
+        .uri("/goats")
+        .set_json(goat(32.5))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    let req = test::TestRequest::delete()
+        .uri("/goats")
+        .set_json(json!({ "name": "Activity" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let req = test::TestRequest::get().uri("/activity").to_request();
+    let feed: Vec<ActivityEntry> = test::call_and_read_body_json(&app, req).await;
+    let actions: Vec<&str> = feed.iter().map(|e| e.action.as_str()).collect();
+    assert_eq!(actions, ["delete", "update", "insert"]);
+    assert!(feed.iter().all(|e| e.entity_type == "goat"));
+    assert!(feed.iter().all(|e| e.name.as_deref() == Some("Activity")));
+
+    let req = test::TestRequest::get()
+        .uri("/activity?limit=1")
+        .to_request();
+    let feed: Vec<ActivityEntry> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(feed.len(), 1);
+    assert_eq!(feed[0].action, "delete");
+}
+
+async fn search_app(
+    name: &str,
+) -> impl actix_web::dev::Service<
+    actix_http::Request,
+    Response = actix_web::dev::ServiceResponse,
+    Error = actix_web::Error,
+> {
+    let db_pool = fresh_db(name);
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender, weight, health_status, is_pregnant) VALUES
+                (1, 'Beetal', 'Bella', 'Female', 42.0, 'healthy', 1),
+                (2, 'Beetal', 'Bruno', 'Male', 55.0, 'healthy', 0),
+                (3, 'Jamunapari', 'Bindi', 'Female', 38.0, 'sick', 0),
+                (4, 'Beetal', 'Chhoti', 'Female', 30.0, 'healthy', 0),
+                (5, 'Beetal', 'Bad%Name', 'Female', 40.0, 'healthy', 0);
+             INSERT INTO vaccines (id, name) VALUES (1, 'PPR'), (2, 'Enterotoxemia');
+             INSERT INTO diseases (id, name) VALUES (1, 'Mastitis');
+             INSERT INTO goat_vaccines (goat_id, vaccine_id) VALUES (1, 1), (2, 1), (3, 2), (4, 1);
+             INSERT INTO goat_diseases (goat_id, disease_id) VALUES (3, 1);
+             INSERT INTO spaces (id, name) VALUES (1, 'North pen');
+             INSERT INTO space_goats (goat_id, space_id) VALUES (1, 1), (4, 1);",
+        )
+        .expect("Failed to seed goats");
+    test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .service(web::scope("/goats").route("/search", web::get().to(search_goats))),
+    )
+    .await
+}
+
+async fn search_names(
+    app: &impl actix_web::dev::Service<
+        actix_http::Request,
+        Response = actix_web::dev::ServiceResponse,
+        Error = actix_web::Error,
+    >,
+    query: &str,
+) -> Vec<String> {
+    let req = test::TestRequest::get()
+        .uri(&format!("/goats/search?{}", query))
+        .to_request();
+    let goats: Vec<Goat> = test::call_and_read_body_json(app, req).await;
+    goats.into_iter().map(|g| g.params.name).collect()
+}
+
+#[actix_rt::test]
+async fn test_goat_search_single_filter() {
+    let app = search_app("goat_search_single").await;
+    assert_eq!(
+        search_names(&app, "has_vaccine=ppr").await,
+        ["Bella", "Bruno", "Chhoti"]
+    );
+    assert_eq!(search_names(&app, "q=BR").await, ["Bruno"]);
+    assert_eq!(search_names(&app, "is_pregnant=true").await, ["Bella"]);
+}
+
+#[actix_rt::test]
+async fn test_goat_search_combined_filters() {
+    let app = search_app("goat_search_combined").await;
+    assert_eq!(
+        search_names(&app, "gender=Female&min_weight=35&has_vaccine=PPR").await,
+        ["Bella"]
+    );
+    assert_eq!(
+        search_names(&app, "breed=beetal&space_id=1&max_weight=35").await,
+        ["Chhoti"]
+    );
+    assert_eq!(
+        search_names(&app, "has_disease=Mastitis&health_status=sick&q=bin").await,
+        ["Bindi"]
+    );
+}
+
+#[actix_rt::test]
+async fn test_goat_search_binds_text_filters() {
+    let (sql, values) = build_goat_search_query(&GoatSearchParams {
+        q: Some("x' OR '1'='1".to_string()),
+        breed: Some("Beetal'; DROP TABLE goats; --".to_string()),
+        ..Default::default()
+    });
+    assert!(!sql.contains("DROP"));
+    assert!(!sql.contains("OR '1'"));
+    assert_eq!(values.len(), 2);
+
+    let app = search_app("goat_search_injection").await;
+    assert!(
+        search_names(&app, "q=x%27%20OR%20%271%27%3D%271")
+            .await
+            .is_empty()
+    );
+    assert!(
+        search_names(&app, "breed=Beetal%27%3B%20DROP%20TABLE%20goats%3B%20--")
+            .await
+            .is_empty()
+    );
+    // LIKE wildcards in `q` are matched literally.
+    assert_eq!(search_names(&app, "q=%25").await, ["Bad%Name"]);
+    assert_eq!(search_names(&app, "breed=Beetal").await.len(), 4);
+}
+
+#[actix_rt::test]
+async fn test_openapi_spec_documents_goat_routes() {
+    let app = test::init_service(App::new().service(swagger_ui())).await;
+
+    let req = test::TestRequest::get().uri(OPENAPI_JSON_PATH).to_request();
+    let spec: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let goats = &spec["paths"]["/goats"];
+    for method in ["get", "post", "put", "delete"] {
+        assert!(goats.get(method).is_some(), "missing {} /goats", method);
+    }
+    assert!(goats["post"]["responses"].get("422").is_some());
+    assert!(spec["components"]["schemas"].get("Goat").is_some());
+    assert!(spec["components"]["schemas"].get("FieldError").is_some());
+
+    let req = test::TestRequest::get()
+        .uri("/swagger-ui/index.html")
+        .to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+}
+
+#[test]
+fn test_straight_line_depreciation_over_useful_life() {
+    // Eight years is exactly 2922 days, so each checkpoint lands on a whole day.
+    let purchased = chrono::NaiveDate::from_ymd_opt(2016, 1, 1).unwrap();
+    let at = |days| purchased + chrono::Duration::days(days);
+
+    let new = compute_depreciation(purchased, 8000.0, 8, at(0));
+    assert_eq!(new.current_value, 8000.0);
+    assert_eq!(new.accumulated_depreciation, 0.0);
+    assert_eq!(new.remaining_useful_life_years, 8.0);
+
+    let half = compute_depreciation(purchased, 8000.0, 8, at(1461));
+    assert_eq!(half.current_value, 4000.0);
+    assert_eq!(half.accumulated_depreciation, 4000.0);
+    assert_eq!(half.remaining_useful_life_years, 4.0);
+
+    let spent = compute_depreciation(purchased, 8000.0, 8, at(2922));
+    assert_eq!(spent.current_value, 0.0);
+    assert_eq!(spent.accumulated_depreciation, 8000.0);
+    assert_eq!(spent.remaining_useful_life_years, 0.0);
+
+    let overdue = compute_depreciation(purchased, 8000.0, 8, at(4383));
+    assert_eq!(overdue.current_value, 0.0);
+    assert_eq!(overdue.accumulated_depreciation, 8000.0);
+    assert_eq!(overdue.remaining_useful_life_years, 0.0);
+}
+
+/// A feed on a dry-matter basis for formulation tests.
+fn feed(name: &str, protein: f64, energy: f64, cost: f64, max_pct: f64) -> FeedIngredient {
+    FeedIngredient {
+        name: name.to_string(),
+        crude_protein_pct: protein,
+        energy_mj_per_kg: energy,
+        cost_per_kg: cost,
+        max_inclusion_pct: max_pct,
+    }
+}
+
+#[test]
+fn test_two_feed_formulation_meets_lactation_requirements() {
+    let needs = requirement(ProductionStage::Lactation, 36);
+    assert_eq!(needs.crude_protein_pct, 14.0);
+    assert_eq!(needs.energy_mj_per_kg, 10.5);
+    let feeds = [
+        feed("Grass hay", 8.0, 9.5, 10.0, 100.0),
+        feed("Concentrate", 40.0, 12.5, 35.0, 40.0),
+    ];
+
+    // Protein needs 19% concentrate, but energy needs 33.5% (in 0.5-point steps):
+    // 9.5 * 0.665 + 12.5 * 0.335 = 10.505 MJ/kg, at 8 * 0.665 + 40 * 0.335 = 18.72% CP.
+    // A 50 kg doe eats 3.5% of her weight, 1.75 kg of dry matter a day.
+    let mix = formulate_feed(50.0, &needs, &feeds);
+    assert!(mix.meets_requirements);
+    assert_eq!(
+        mix.formula,
+        vec![
+            FormulaLine {
+                feed: "Grass hay".to_string(),
+                inclusion_pct: 66.5,
+                kg_per_day: 1.16,
+            },
+            FormulaLine {
+                feed: "Concentrate".to_string(),
+                inclusion_pct: 33.5,
+                kg_per_day: 0.59,
+            },
+        ]
+    );
+    assert_eq!(mix.protein_pct, 18.72);
+    assert_eq!(mix.total_cost_per_day, 32.16);
+
+    // Capped below the energy need, the concentrate is used to its limit and falls short.
+    let capped = [
+        feeds[0].clone(),
+        feed("Concentrate", 40.0, 12.5, 35.0, 15.0),
+    ];
+    let mix = formulate_feed(50.0, &needs, &capped);
+    assert!(!mix.meets_requirements);
+    assert_eq!(mix.formula[1].inclusion_pct, 15.0);
+
+    // Kids are held to growing levels whatever their stage.
+    let kid = requirement(ProductionStage::Maintenance, 4);
+    assert_eq!(kid.crude_protein_pct, 14.0);
+}
+
+#[actix_rt::test]
+async fn test_feed_formulation_endpoint_validates_and_formulates() {
+    let app = test::init_service(
+        App::new().route("/tools/feed-formulation", web::post().to(feed_formulation)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/tools/feed-formulation")
+        .set_json(json!({
+            "goat_weight_kg": 50.0,
+            "age_months": 36,
+            "production_stage": "lactation",
+            "available_feeds": [
+                { "name": "Grass hay", "crude_protein_pct": 8.0, "energy_mj_per_kg": 9.5,
+                  "cost_per_kg": 10.0, "max_inclusion_pct": 100.0 },
+                { "name": "Concentrate", "crude_protein_pct": 40.0, "energy_mj_per_kg": 12.5,
+                  "cost_per_kg": 35.0, "max_inclusion_pct": 40.0 }
+            ]
+        }))
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(body["meets_requirements"], true);
+    assert_eq!(body["total_cost_per_day"], 32.16);
+    assert_eq!(body["formula"][1]["feed"], "Concentrate");
+
+    let req = test::TestRequest::post()
+        .uri("/tools/feed-formulation")
+        .set_json(json!({
+            "goat_weight_kg": 0.0,
+            "age_months": 36,
+            "production_stage": "dry",
+            "available_feeds": [
+                { "name": "Grass hay", "crude_protein_pct": 8.0, "energy_mj_per_kg": 9.5,
+                  "cost_per_kg": 10.0, "max_inclusion_pct": 60.0 }
+            ]
+        }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let errors = test::read_body_json::<ErrorBody, _>(resp).await.fields;
+    let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+    assert_eq!(
+        fields,
+        vec!["goat_weight_kg", "production_stage", "available_feeds"]
+    );
+}
+
+#[actix_rt::test]
+async fn test_equipment_depreciation_endpoint_and_financial_total() {
+    let db_pool = fresh_db("equipment_depreciation");
+    let today = chrono::Utc::now().date_naive();
+    // Four years (1461 days) into an eight-year life, so worth half its price.
+    let purchased = today - chrono::Duration::days(1461);
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute(
+            "INSERT INTO equipment (id, name, purchase_date, purchase_price, useful_life_years) VALUES
+                (1, 'Tractor', ?1, 12000.0, 8),
+                (2, 'Old pump', '2000-01-01', 500.0, 5),
+                (3, 'Borrowed trailer', NULL, NULL, NULL)",
+            [purchased.to_string()],
+        )
+        .expect("Failed to seed equipment");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route(
+                "/equipment/{id}/depreciation",
+                web::get().to(get_equipment_depreciation),
+            )
+            .route("/stats/financial", web::get().to(get_financial_stats)),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/equipment/1/depreciation")
+        .to_request();
+    let info: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert!((info["current_value"].as_f64().unwrap() - 6000.0).abs() < 1e-6);
+    assert!((info["remaining_useful_life_years"].as_f64().unwrap() - 4.0).abs() < 1e-6);
+
+    // Long past its five-year life: book value floors at zero.
+    let req = test::TestRequest::get()
+        .uri("/equipment/2/depreciation")
+        .to_request();
+    let info: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(info["current_value"].as_f64(), Some(0.0));
+    assert_eq!(info["accumulated_depreciation"].as_f64(), Some(500.0));
+    assert_eq!(info["remaining_useful_life_years"].as_f64(), Some(0.0));
+
+    let req = test::TestRequest::get()
+        .uri("/equipment/3/depreciation")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+    let req = test::TestRequest::get()
+        .uri("/equipment/99/depreciation")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    // The pump is fully depreciated and the trailer is not valued.
+    let req = test::TestRequest::get()
+        .uri("/stats/financial")
+        .to_request();
+    let stats: FinancialStats = test::call_and_read_body_json(&app, req).await;
+    assert!((stats.total_equipment_value - 6000.0).abs() < 1e-6);
+}
+
+/// Test job recording one `settings` row per run.
+struct TickJob;
+
+impl Job for TickJob {
+    fn name(&self) -> &'static str {
+        "tick"
+    }
+
+    fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(20)
+    }
+
+    fn run(&self, pool: &DbPool) -> Result<(), AppError> {
+        pool.get_conn()?.execute(
+            "INSERT INTO settings (key, value) VALUES ('ticks', '1') \
+             ON CONFLICT(key) DO UPDATE SET value = CAST(value AS INTEGER) + 1",
+            [],
+        )?;
+        Ok(())
+    }
+}
+
+/// Test job that always fails.
+struct FailingJob;
+
+impl Job for FailingJob {
+    fn name(&self) -> &'static str {
+        "failing"
+    }
+
+    fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(20)
+    }
+
+    fn run(&self, _pool: &DbPool) -> Result<(), AppError> {
+        Err(AppError::Internal("boom".to_string()))
+    }
+}
+
+#[actix_rt::test]
+async fn test_scheduler_runs_jobs_and_isolates_failures() {
+    let db_pool = fresh_db("scheduler");
+    let mut scheduler = Scheduler::new();
+    scheduler.register(TickJob);
+    scheduler.register(FailingJob);
+    scheduler.register(WalCheckpointJob {
+        interval: std::time::Duration::from_millis(20),
+    });
+    let handles = scheduler.start(&db_pool);
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    for handle in handles {
+        handle.abort();
+    }
+
+    let ticks: i64 = db_pool
+        .get_conn()
+        .unwrap()
+        .query_row(
+            "SELECT CAST(value AS INTEGER) FROM settings WHERE key = 'ticks'",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert!(ticks >= 2, "tick job ran {} times", ticks);
+
+    let registry: web::Data<JobRegistry> = web::Data::from(scheduler.registry());
+    let app = test::init_service(
+        App::new()
+            .app_data(registry)
+            .service(web::scope("/admin").route("/jobs", web::get().to(list_jobs))),
+    )
+    .await;
+    let req = test::TestRequest::get().uri("/admin/jobs").to_request();
+    let jobs: Vec<JobStatus> = test::call_and_read_body_json(&app, req).await;
+    let names: Vec<&str> = jobs.iter().map(|j| j.name.as_str()).collect();
+    assert_eq!(names, ["tick", "failing", "wal_checkpoint"]);
+
+    // The failing job keeps being retried and never stops the others.
+    assert!(jobs[1].run_count >= 2);
+    assert_eq!(jobs[1].last_outcome.as_deref(), Some("failure"));
+    assert!(jobs[1].last_error.as_deref().unwrap().contains("boom"));
+    for job in [&jobs[0], &jobs[2]] {
+        assert_eq!(job.last_outcome.as_deref(), Some("success"));
+        assert!(job.last_run_at.is_some());
+    }
+}
+
+#[actix_rt::test]
+async fn test_goat_tags_filter_and_untag() {
+    let db_pool = fresh_db("goat_tags");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'Star', 'Female'),
+                (2, 'Beetal', 'Plain', 'Female');",
+        )
+        .expect("Failed to seed goats");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/goats")
+                    .route("", web::get().to(get_goats))
+                    .route("/{id}/tags", web::post().to(add_goat_tag))
+                    .route("/{id}/tags/{tag}", web::delete().to(remove_goat_tag)),
+            ),
+    )
+    .await;
+
+    let tag = |goat_id: i64, tag: &str| {
+        test::TestRequest::post()
+            .uri(&format!("/goats/{}/tags", goat_id))
+            .set_json(json!({ "tag": tag }))
+            .to_request()
+    };
+    let _: Vec<String> = test::call_and_read_body_json(&app, tag(1, "show-quality")).await;
+    let tags: Vec<String> = test::call_and_read_body_json(&app, tag(1, "for-sale")).await;
+    assert_eq!(tags, ["for-sale", "show-quality"]);
+    // Tags match ignoring case, so this reuses the existing tag.
+    let tags: Vec<String> = test::call_and_read_body_json(&app, tag(2, "For-Sale")).await;
+    assert_eq!(tags, ["for-sale"]);
+    assert_eq!(test::call_service(&app, tag(1, "  ")).await.status(), 422);
+    assert_eq!(test::call_service(&app, tag(99, "x")).await.status(), 404);
+
+    let req = test::TestRequest::get()
+        .uri("/goats?tag=show-quality")
+        .to_request();
+    let goats: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(goats.len(), 1);
+    assert_eq!(goats[0]["name"], "Star");
+    assert_eq!(goats[0]["tags"], json!(["for-sale", "show-quality"]));
+
+    let req = test::TestRequest::get()
+        .uri("/goats?tag=for-sale")
+        .to_request();
+    let goats: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(goats.len(), 2);
+
+    let untag = || {
+        test::TestRequest::delete()
+            .uri("/goats/1/tags/show-quality")
+            .to_request()
+    };
+    let tags: Vec<String> = test::call_and_read_body_json(&app, untag()).await;
+    assert_eq!(tags, ["for-sale"]);
+    assert_eq!(test::call_service(&app, untag()).await.status(), 404);
+
+    let req = test::TestRequest::get()
+        .uri("/goats?tag=show-quality")
+        .to_request();
+    let goats: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    assert!(goats.is_empty());
+}
+
+#[actix_rt::test]
+async fn test_genetic_tests_and_trait_summary() {
+    let db_pool = fresh_db("genetic_tests");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'Asha', 'Female'),
+                (2, 'Beetal', 'Veer', 'Male');",
+        )
+        .expect("Failed to seed goats");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route(
+                "/goats/{id}/genetic-tests",
+                web::post().to(add_genetic_test),
+            )
+            .route(
+                "/goats/{id}/genetic-tests",
+                web::get().to(get_genetic_tests),
+            )
+            .route(
+                "/genetic-tests/summary",
+                web::get().to(get_genetic_trait_summary),
+            ),
+    )
+    .await;
+
+    let record = |goat_id: i64, test_type: &str, result: serde_json::Value| {
+        test::TestRequest::post()
+            .uri(&format!("/goats/{}/genetic-tests", goat_id))
+            .set_json(json!({
+                "test_type": test_type,
+                "test_date": "2025-03-01",
+                "lab_name": "NDDB Genomics",
+                "result_json": result
+            }))
+            .to_request()
+    };
+    let resp = test::call_service(
+        &app,
+        record(
+            1,
+            "Genotype",
+            json!({ "kappa_casein": "AB", "polled": true }),
+        ),
+    )
+    .await;
+    assert_eq!(resp.status(), 201);
+    let stored: GeneticTest = test::read_body_json(resp).await;
+    assert_eq!(stored.result_json["kappa_casein"], "AB");
+    for (goat_id, result) in [
+        (2, json!({ "kappa_casein": "AB" })),
+        (2, json!({ "kappa_casein": "BB", "polled": false })),
+    ] {
+        assert_eq!(
+            test::call_service(&app, record(goat_id, "Genotype", result))
+                .await
+                .status(),
+            201
+        );
+    }
+    assert_eq!(
+        test::call_service(&app, record(1, "Astrology", json!({})))
+            .await
+            .status(),
+        422
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/genetic-tests/summary?trait=kappa_casein")
+        .to_request();
+    let summary: Vec<GeneticTraitCount> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        summary,
+        [
+            GeneticTraitCount {
+                value: json!("AB"),
+                count: 2
+            },
+            GeneticTraitCount {
+                value: json!("BB"),
+                count: 1
+            },
+        ]
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/genetic-tests/summary?trait=polled")
+        .to_request();
+    let summary: Vec<GeneticTraitCount> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(summary.len(), 2);
+    assert!(summary.iter().all(|s| s.value.is_boolean() && s.count == 1));
+
+    let req = test::TestRequest::get()
+        .uri("/genetic-tests/summary?trait=a%27)%20OR%201%3D1--")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    let req = test::TestRequest::get()
+        .uri("/goats/2/genetic-tests")
+        .to_request();
+    let tests: Vec<GeneticTest> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(tests.len(), 2);
+}
+
+#[test]
+fn test_slow_query_span_carries_sql_without_parameters() {
+    let db_pool = fresh_db("slow_query_span");
+    let conn = db_pool.get_conn().unwrap();
+    conn.execute_batch(
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 300)
+         INSERT INTO workers (name) SELECT 'Worker ' || i FROM n;",
+    )
+    .unwrap();
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+
+    // A three-way cross join over the seeded rows: 27 million combinations.
+    let slow_sql = "SELECT COUNT(*) FROM workers a, workers b, workers c WHERE a.name <> ?1";
+    tracing::subscriber::with_default(subscriber, || {
+        let total: i64 = timed_with(Some(std::time::Duration::from_millis(1)), slow_sql, || {
+            conn.query_row(slow_sql, ["hunter2"], |r| r.get(0))
+        })
+        .unwrap();
+        assert_eq!(total, 27_000_000);
+    });
+
+    let output = logs.contents();
+    assert!(output.contains("Slow query"), "missing warn in: {}", output);
+    assert!(output.contains("db.query"), "missing span in: {}", output);
+    assert!(output.contains("workers a, workers b, workers c"));
+    assert!(
+        !output.contains("hunter2"),
+        "parameter leaked in: {}",
+        output
+    );
+}
+
+fn supplier_app_routes() -> actix_web::Scope {
+    web::scope("/suppliers")
+        .route("", web::get().to(list_suppliers))
+        .route("", web::post().to(create_supplier))
+        .route("/{id}", web::get().to(get_supplier))
+        .route("/{id}", web::put().to(update_supplier))
+        .route("/{id}", web::delete().to(delete_supplier))
+        .route("/{id}/purchases", web::get().to(get_supplier_purchases))
+}
+
+#[actix_rt::test]
+async fn test_supplier_crud() {
+    let db_pool = fresh_db("supplier_crud");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch("INSERT INTO suppliers (id, name) VALUES (1, 'Old Feed Co');")
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(supplier_app_routes()),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/suppliers")
+        .set_json(json!({ "name": "  ", "contact": "nobody" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 422);
+
+    let req = test::TestRequest::post()
+        .uri("/suppliers")
+        .set_json(json!({ "name": "Agro Vet", "contact": "+91 98765 43210" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let created: Supplier = test::read_body_json(resp).await;
+    let id = created.id.unwrap();
+    assert_eq!(created.contact.as_deref(), Some("+91 98765 43210"));
+
+    let req = test::TestRequest::put()
+        .uri(&format!("/suppliers/{}", id))
+        .set_json(json!({ "name": "Agro Vet Supplies", "notes": "Delivers Tuesdays" }))
+        .to_request();
+    let updated: Supplier = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(updated.name, "Agro Vet Supplies");
+    assert_eq!(updated.contact, None);
+    assert_eq!(updated.notes.as_deref(), Some("Delivers Tuesdays"));
+
+    let req = test::TestRequest::get().uri("/suppliers").to_request();
+    let all: Vec<Supplier> = test::call_and_read_body_json(&app, req).await;
+    let names: Vec<&str> = all.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["Agro Vet Supplies", "Old Feed Co"]);
+
+    let req = test::TestRequest::delete().uri("/suppliers/1").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    for req in [
+        test::TestRequest::get().uri("/suppliers/1").to_request(),
+        test::TestRequest::delete().uri("/suppliers/1").to_request(),
+        test::TestRequest::put()
+            .uri("/suppliers/1")
+            .set_json(json!({ "name": "Ghost" }))
+            .to_request(),
+    ] {
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+}
+
+#[actix_rt::test]
+async fn test_supplier_purchases_aggregate_equipment_and_restocks() {
+    let db_pool = fresh_db("supplier_purchases");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO suppliers (id, name) VALUES (1, 'Agro Vet'), (2, 'Other');
+             INSERT INTO equipment (id, name, purchase_date, purchase_price, supplier_id) VALUES
+                (1, 'Milking machine', '2020-03-01', 1200.0, 1),
+                (2, 'Water trough', '2021-06-15', NULL, 1),
+                (3, 'Tractor', '2019-01-01', 9000.0, 2);
+             INSERT INTO medicine_inventory (id, name, stock_units, unit) VALUES
+                (1, 'PPR vaccine', 2.0, 'dose');",
+        )
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(supplier_app_routes())
+            .route(
+                "/inventory/medicine/{id}/restock",
+                web::post().to(restock_medicine),
+            ),
+    )
+    .await;
+
+    let restock = |body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri("/inventory/medicine/1/restock")
+            .set_json(body)
+            .to_request()
+    };
+    let item: MedicineItem = test::call_and_read_body_json(
+        &app,
+        restock(json!({ "units": 50.0, "cost_per_unit": 3.0, "supplier_id": 1 })),
+    )
+    .await;
+    assert_eq!(item.stock_units, 52.0);
+    assert_eq!(
+        test::call_service(&app, restock(json!({ "units": 0.0 })))
+            .await
+            .status(),
+        422
+    );
+    assert_eq!(
+        test::call_service(&app, restock(json!({ "units": 5.0, "supplier_id": 99 })))
+            .await
+            .status(),
+        404
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/suppliers/1/purchases")
+        .to_request();
+    let purchases: SupplierPurchases = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(purchases.purchase_count, 3);
+    // 1200 for the milking machine + 50 doses at 3.0; the unpriced trough is not counted.
+    assert_eq!(purchases.total_spent, 1350.0);
+    assert_eq!(purchases.purchases[0].kind, "medicine");
+    assert_eq!(purchases.purchases[0].amount, Some(150.0));
+    let unpriced = purchases
+        .purchases
+        .iter()
+        .find(|p| p.item_name == "Water trough")
+        .unwrap();
+    assert_eq!(unpriced.amount, None);
+
+    let req = test::TestRequest::get()
+        .uri("/suppliers/99/purchases")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+#[test]
+fn test_compute_trade_hash_is_sha256_of_prev_and_payload() {
+    // SHA-256("abc"), from FIPS 180-2.
+    assert_eq!(
+        compute_trade_hash("a", "bc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
+
+#[test]
+fn test_trade_payload_keeps_fields_apart() {
+    assert_eq!(
+        trade_payload(1, "Sale", Some("Farm"), None, Some(110.5), "2024-01-01"),
+        "1:14:Sale4:Farm-5:110.510:2024-01-01"
+    );
+    // Shifting text between adjacent fields, or blanking an optional one, changes the
+    // payload.
+    assert_ne!(
+        trade_payload(1, "Sale", Some("AB"), Some("C"), None, "t"),
+        trade_payload(1, "Sale", Some("A"), Some("BC"), None, "t")
+    );
+    assert_ne!(
+        trade_payload(1, "Sale", Some(""), None, None, "t"),
+        trade_payload(1, "Sale", None, None, None, "t")
+    );
+    assert_ne!(
+        trade_payload(1, "Sale1", None, None, None, "t"),
+        trade_payload(11, "Sale", None, None, None, "t")
+    );
+}
+
+#[actix_rt::test]
+async fn test_trade_log_verify_detects_tampering() {
+    let db_pool = fresh_db("trade_log");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES (1, 'Beetal', 'Traded', 'Female');",
+        )
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats/{id}/trade-log", web::post().to(add_trade))
+            .route("/goats/{id}/trade-log", web::get().to(get_goat_trade_log))
+            .route("/trade-log/verify", web::get().to(verify_trade_log)),
+    )
+    .await;
+
+    let mut entries = Vec::new();
+    for (action, from, to, price) in [
+        ("Purchase", "Breeder", "Farm", 8000.0),
+        ("Sale", "Farm", "Market", 11000.0),
+    ] {
+        let req = test::TestRequest::post()
+            .uri("/goats/1/trade-log")
+            .set_json(
+                json!({ "action": action, "from_party": from, "to_party": to, "price": price }),
+            )
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 201);
+        let entry: TradeLogEntry = test::read_body_json(resp).await;
+        entries.push(entry);
+    }
+    assert_eq!(entries[0].prev_hash, "");
+    assert_eq!(entries[1].prev_hash, entries[0].transaction_hash);
+
+    let req = test::TestRequest::post()
+        .uri("/goats/99/trade-log")
+        .set_json(json!({ "action": "Sale" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::get()
+        .uri("/goats/1/trade-log")
+        .to_request();
+    let listed: Vec<TradeLogEntry> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(listed, entries);
+
+    let verify = || {
+        test::TestRequest::get()
+            .uri("/trade-log/verify")
+            .to_request()
+    };
+    let result: TradeLogVerification = test::call_and_read_body_json(&app, verify()).await;
+    assert_eq!(
+        result,
+        TradeLogVerification {
+            valid: true,
+            broken_at: None
+        }
+    );
+
+    // The table itself refuses edits and deletes.
+    let conn = db_pool.get_conn().unwrap();
+    let err = conn
+        .execute(
+            "UPDATE immutable_trade_log SET price = 1 WHERE id = ?1",
+            [entries[1].id],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("append-only"));
+    let err = conn
+        .execute(
+            "DELETE FROM immutable_trade_log WHERE id = ?1",
+            [entries[0].id],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("append-only"));
+
+    // With the guard dropped, a changed price still breaks the chain at that entry.
+    conn.execute_batch(
+        "DROP TRIGGER immutable_trade_log_no_update;
+         DROP TRIGGER immutable_trade_log_no_delete;",
+    )
+    .unwrap();
+    conn.execute(
+        "UPDATE immutable_trade_log SET price = 1 WHERE id = ?1",
+        [entries[1].id],
+    )
+    .unwrap();
+    drop(conn);
+    let result: TradeLogVerification = test::call_and_read_body_json(&app, verify()).await;
+    assert_eq!(
+        result,
+        TradeLogVerification {
+            valid: false,
+            broken_at: Some(entries[1].id)
+        }
+    );
+
+    let conn = db_pool.get_conn().unwrap();
+    conn.execute(
+        "UPDATE immutable_trade_log SET price = 11000.0, to_party = 'Elsewhere' WHERE id = ?1",
+        [entries[1].id],
+    )
+    .unwrap();
+    drop(conn);
+    let result: TradeLogVerification = test::call_and_read_body_json(&app, verify()).await;
+    assert_eq!(result.broken_at, Some(entries[1].id));
+}
+
+#[actix_rt::test]
+async fn test_tracing_logger_spans_carry_route_name() {
+    let db_pool = fresh_db("tracing_logger");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES (7, 'Beetal', 'Traced', 'Female');",
+        )
+        .unwrap();
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let app = test::init_service(
+        App::new()
+            .wrap(tracing_actix_web::TracingLogger::default())
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats/{id}/vaccines", web::get().to(get_goat_vaccines)),
+    )
+    .await;
+
+    // An upstream trace context is accepted; without an exporter it is simply ignored.
+    let req = test::TestRequest::get()
+        .uri("/goats/7/vaccines")
+        .insert_header((
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+
+    let output = logs.contents();
+    assert!(
+        output.contains("Returning goat vaccines"),
+        "missing handler log in: {}",
+        output
+    );
+    assert!(
+        output.contains("http.route=/goats/{id}/vaccines"),
+        "missing route name in: {}",
+        output
+    );
+}
+
+#[actix_rt::test]
+async fn test_body_logging_is_opt_in_and_redacts_auth_headers() {
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    for enabled in [false, true] {
+        let app = test::init_service(
+            App::new()
+                .wrap(middleware::Condition::new(
+                    enabled,
+                    middleware::from_fn(body_logger),
+                ))
+                .route(
+                    "/echo",
+                    web::post().to(|body: web::Json<serde_json::Value>| async move {
+                        HttpResponse::Created().json(json!({ "echoed": body.into_inner() }))
+                    }),
+                ),
+        )
+        .await;
+        let marker = format!("marker-{}", enabled);
+        let req = test::TestRequest::post()
+            .uri("/echo")
+            .insert_header(("Authorization", "Bearer hunter2"))
+            .insert_header((API_KEY_HEADER, "secret-key"))
+            .set_json(json!({ "name": marker, "padding": "x".repeat(LOG_BODY_MAX_BYTES) }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        // The handler still sees the whole body.
+        assert_eq!(resp.status(), 201);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["echoed"]["name"], marker.as_str());
+    }
+
+    let output = logs.contents();
+    assert!(
+        !output.contains("marker-false"),
+        "body logged while disabled: {}",
+        output
+    );
+    assert!(
+        output.contains(r#"body={"echoed":{"name":"marker-true""#),
+        "response body missing from: {}",
+        output
+    );
+    assert!(output.contains(r#"body={"name":"marker-true""#));
+    assert!(output.contains("more bytes)"), "body was not truncated");
+    assert!(output.contains("authorization: [redacted]"));
+    assert!(!output.contains("hunter2"));
+    assert!(!output.contains("secret-key"));
+}
+
+fn sale_app_routes() -> actix_web::Scope {
+    web::scope("")
+        .route("/goats/{id}/sell", web::post().to(sell_goat))
+        .route("/buyers", web::get().to(list_buyers))
+        .route("/buyers", web::post().to(create_buyer))
+        .route("/buyers/{id}", web::put().to(update_buyer))
+        .route("/buyers/{id}", web::delete().to(delete_buyer))
+        .route("/buyers/{id}/purchases", web::get().to(get_buyer_purchases))
+}
+
+fn sell(goat_id: i64, body: serde_json::Value) -> actix_http::Request {
+    test::TestRequest::post()
+        .uri(&format!("/goats/{}/sell", goat_id))
+        .set_json(body)
+        .to_request()
+}
+
+#[actix_rt::test]
+async fn test_sell_goat_to_existing_buyer() {
+    let db_pool = fresh_db("sell_existing_buyer");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'First', 'Female'),
+                (2, 'Boer', 'Second', 'Male');",
+        )
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(sale_app_routes()),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/buyers")
+        .set_json(json!({ "name": "Ramesh Traders", "contact": "ramesh@example.com" }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 201);
+    let buyer: Buyer = test::read_body_json(resp).await;
+    let buyer_id = buyer.id.unwrap();
+
+    let req = test::TestRequest::post()
+        .uri("/buyers")
+        .set_json(json!({ "name": "ramesh traders" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+
+    for (goat_id, price) in [(1, 9000.0), (2, 12000.0)] {
+        let resp = test::call_service(
+            &app,
+            sell(goat_id, json!({ "buyer_id": buyer_id, "price": price })),
+        )
+        .await;
+        assert_eq!(resp.status(), 201);
+        let sale: Sale = test::read_body_json(resp).await;
+        assert_eq!(sale.buyer_id, Some(buyer_id));
+        assert_eq!(sale.buyer.as_deref(), Some("Ramesh Traders"));
+    }
+
+    // A goat can only be sold once.
+    assert_eq!(
+        test::call_service(&app, sell(1, json!({ "buyer_id": buyer_id, "price": 1.0 })))
+            .await
+            .status(),
+        409
+    );
+    let is_sold: bool = db_pool
+        .get_conn()
+        .unwrap()
+        .query_row("SELECT is_sold FROM goats WHERE id = 1", [], |r| r.get(0))
+        .unwrap();
+    assert!(is_sold);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/buyers/{}/purchases", buyer_id))
+        .to_request();
+    let purchases: Vec<BuyerPurchase> = test::call_and_read_body_json(&app, req).await;
+    let mut bought: Vec<(&str, Option<f64>)> = purchases
+        .iter()
+        .map(|p| (p.goat_name.as_str(), p.price))
+        .collect();
+    bought.sort_by(|a, b| a.0.cmp(b.0));
+    assert_eq!(bought, [("First", Some(9000.0)), ("Second", Some(12000.0))]);
+
+    let req = test::TestRequest::get()
+        .uri("/buyers/99/purchases")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+#[actix_rt::test]
+async fn test_sell_goat_creates_buyer_from_name() {
+    let db_pool = fresh_db("sell_new_buyer");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'First', 'Female'),
+                (2, 'Boer', 'Second', 'Male'),
+                (3, 'Boer', 'Third', 'Male');",
+        )
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(sale_app_routes()),
+    )
+    .await;
+
+    assert_eq!(
+        test::call_service(&app, sell(1, json!({ "price": 5000.0 })))
+            .await
+            .status(),
+        422
+    );
+    assert_eq!(
+        test::call_service(&app, sell(1, json!({ "buyer_id": 42, "price": 5000.0 })))
+            .await
+            .status(),
+        404
+    );
+
+    let first: Sale = test::call_and_read_body_json(
+        &app,
+        sell(
+            1,
+            json!({ "buyer_name": "  Village Co-op ", "price": 5000.0 }),
+        ),
+    )
+    .await;
+    let second: Sale = test::call_and_read_body_json(
+        &app,
+        sell(2, json!({ "buyer_name": "village co-op", "price": 6000.0 })),
+    )
+    .await;
+    assert!(first.buyer_id.is_some());
+    assert_eq!(first.buyer_id, second.buyer_id);
+
+    let req = test::TestRequest::get().uri("/buyers").to_request();
+    let buyers: Vec<Buyer> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(buyers.len(), 1);
+    assert_eq!(buyers[0].name, "Village Co-op");
+
+    // Deleting the buyer keeps the sales and the name recorded on them.
+    let req = test::TestRequest::delete()
+        .uri(&format!("/buyers/{}", first.buyer_id.unwrap()))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    let (linked, named): (i64, i64) = db_pool
+        .get_conn()
+        .unwrap()
+        .query_row("SELECT COUNT(buyer_id), COUNT(buyer) FROM sales", [], |r| {
+            Ok((r.get(0)?, r.get(1)?))
+        })
+        .unwrap();
+    assert_eq!((linked, named), (0, 2));
+}
+
+#[actix_rt::test]
+async fn test_data_quality_scan_flags_each_issue_type() {
+    let db_pool = fresh_db("data_quality");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender, weight, cost, last_bred, health_status, is_pregnant, deleted_at) VALUES
+                (1, 'Beetal', 'Clean', 'Female', 30, 5000, '2024-01-10', 'Healthy', 0, NULL),
+                (2, 'Beetal', 'Weightless', 'Female', 0, 5000, NULL, 'Healthy', 0, NULL),
+                (3, 'Beetal', 'Free', 'Female', 30, 0, NULL, 'Healthy', 0, NULL),
+                (4, 'Beetal', 'Prophetic', 'Female', 30, 5000, '2999-01-01', 'Healthy', 0, NULL),
+                (5, 'Beetal', 'Undiagnosed', 'Female', 30, 5000, NULL, 'sick', 0, NULL),
+                (6, 'Beetal', 'Unjabbed', 'Female', 30, 5000, NULL, 'Healthy', 0, NULL),
+                (7, 'Boer', 'Miracle', 'Male', 30, 5000, NULL, 'Healthy', 1, NULL),
+                (8, 'Boer', 'Diagnosed', 'Female', 30, 5000, NULL, 'Sick', 0, NULL),
+                (9, 'Boer', 'Deleted', 'Male', 0, 0, NULL, 'Sick', 1, '2024-01-01 00:00:00');
+             INSERT INTO vaccines (id, name) VALUES (1, 'PPR');
+             INSERT INTO diseases (id, name) VALUES (1, 'Pneumonia');
+             INSERT INTO goat_vaccines (goat_id, vaccine_id) VALUES
+                (1, 1), (2, 1), (3, 1), (4, 1), (5, 1), (7, 1), (8, 1);
+             INSERT INTO goat_diseases (goat_id, disease_id) VALUES (8, 1);",
+        )
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/admin/data-quality", web::get().to(get_data_quality)),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/admin/data-quality")
+        .to_request();
+    let report: DataQualityReport = test::call_and_read_body_json(&app, req).await;
+
+    let mut found: Vec<(i64, &str)> = report
+        .issues
+        .iter()
+        .map(|i| (i.goat_id, i.issue.as_str()))
+        .collect();
+    found.sort();
+    assert_eq!(
+        found,
+        [
+            (2, "Weight is 0"),
+            (3, "Cost is 0"),
+            (4, "Last bred date is in the future"),
+            (5, "Sick with no disease recorded"),
+            (6, "No vaccinations recorded"),
+            (7, "Male goat marked pregnant"),
+        ]
+    );
+    assert_eq!(report.total_issues, 6);
+    assert_eq!(report.issues[0].goat_name, "Weightless");
+    // Goats 1 and 8 are clean; the deleted goat is not scanned.
+    assert_eq!(report.quality_score_pct, 25.0);
+}
+
+#[actix_rt::test]
+async fn test_tasks_create_assign_and_complete() {
+    let db_pool = fresh_db("tasks");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch("INSERT INTO workers (id, name) VALUES (1, 'Ravi'), (2, 'Meena');")
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .service(
+                web::scope("/tasks")
+                    .route("", web::get().to(list_tasks))
+                    .route("", web::post().to(create_task))
+                    .route("/{id}", web::get().to(get_task))
+                    .route("/{id}", web::put().to(update_task))
+                    .route("/{id}", web::delete().to(delete_task))
+                    .route("/{id}/complete", web::post().to(complete_task)),
+            )
+            .route("/workers/{id}/tasks", web::get().to(get_worker_tasks)),
+    )
+    .await;
+
+    let create = |body: serde_json::Value| {
+        test::TestRequest::post()
+            .uri("/tasks")
+            .set_json(body)
+            .to_request()
+    };
+    assert_eq!(
+        test::call_service(
+            &app,
+            create(json!({ "title": "Vaccinate enclosure 1", "assignee_worker_id": 99 }))
+        )
+        .await
+        .status(),
+        404
+    );
+    let resp = test::call_service(
+        &app,
+        create(json!({
+            "title": "Vaccinate enclosure 1",
+            "description": "PPR booster for all kids",
+            "assignee_worker_id": 1,
+            "due_at": "2030-01-15 09:00:00"
+        })),
+    )
+    .await;
+    assert_eq!(resp.status(), 201);
+    let task: WorkerTask = test::read_body_json(resp).await;
+    let task_id = task.id.unwrap();
+    assert_eq!(task.worker_id, 1);
+    assert_eq!(
+        task.description.as_deref(),
+        Some("PPR booster for all kids")
+    );
+
+    // Reassign to Meena; the task leaves Ravi's queue and joins hers.
+    let reassign = |worker_id: i64| {
+        test::TestRequest::put()
+            .uri(&format!("/tasks/{}", task_id))
+            .set_json(json!({ "title": "Vaccinate enclosure 1", "assignee_worker_id": worker_id }))
+            .to_request()
+    };
+    assert_eq!(test::call_service(&app, reassign(99)).await.status(), 404);
+    let task: WorkerTask = test::call_and_read_body_json(&app, reassign(2)).await;
+    assert_eq!(task.worker_id, 2);
+    let queue = |worker_id: i64| {
+        test::TestRequest::get()
+            .uri(&format!("/workers/{}/tasks?completed=false", worker_id))
+            .to_request()
+    };
+    let ravi: Vec<WorkerTask> = test::call_and_read_body_json(&app, queue(1)).await;
+    let meena: Vec<WorkerTask> = test::call_and_read_body_json(&app, queue(2)).await;
+    assert!(ravi.is_empty());
+    assert_eq!(meena.len(), 1);
+
+    let complete = || {
+        test::TestRequest::post()
+            .uri(&format!("/tasks/{}/complete", task_id))
+            .to_request()
+    };
+    let done: WorkerTask = test::call_and_read_body_json(&app, complete()).await;
+    assert!(done.completed_at.is_some());
+    assert_eq!(test::call_service(&app, complete()).await.status(), 409);
+
+    let by_status = |status: &str| {
+        test::TestRequest::get()
+            .uri(&format!("/tasks?status={}", status))
+            .to_request()
+    };
+    let open: Vec<WorkerTask> = test::call_and_read_body_json(&app, by_status("open")).await;
+    let finished: Vec<WorkerTask> = test::call_and_read_body_json(&app, by_status("done")).await;
+    assert!(open.is_empty());
+    assert_eq!(finished.len(), 1);
+    assert_eq!(
+        test::call_service(&app, by_status("later")).await.status(),
+        400
+    );
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/tasks/{}", task_id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    let req = test::TestRequest::get()
+        .uri(&format!("/tasks/{}", task_id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+#[test]
+fn test_haversine_distance() {
+    assert_eq!(haversine_distance(12.97, 77.59, 12.97, 77.59), 0.0);
+    // One degree of latitude on a 6371 km sphere.
+    assert!((haversine_distance(0.0, 0.0, 1.0, 0.0) - 111_194.9).abs() < 0.1);
+    assert!((haversine_distance(0.0, 0.0, 0.0, 0.0089932) - 1000.0).abs() < 0.1);
+}
+
+#[test]
+fn test_fence_crossing_needs_a_previous_fix() {
+    assert_eq!(fence_crossing(None, true), None);
+    assert_eq!(fence_crossing(Some(true), true), None);
+    assert_eq!(
+        fence_crossing(Some(false), true),
+        Some(FenceCrossing::Entry)
+    );
+    assert_eq!(fence_crossing(Some(true), false), Some(FenceCrossing::Exit));
+    assert!(alerts_on("Both", FenceCrossing::Entry));
+    assert!(alerts_on("Exit", FenceCrossing::Exit));
+    assert!(!alerts_on("Exit", FenceCrossing::Entry));
+}
+
+#[actix_rt::test]
+async fn test_goat_location_raises_geofence_alerts() {
+    let db_pool = fresh_db("geofences");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES (1, 'Beetal', 'Roamer', 'Female');",
+        )
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/geofences", web::post().to(create_geofence))
+            .route("/geofences", web::get().to(list_geofences))
+            .route("/geofences/{id}", web::delete().to(delete_geofence))
+            .route("/goats/{id}/location", web::post().to(record_goat_location))
+            .route("/alerts/location", web::get().to(get_location_alerts)),
+    )
+    .await;
+
+    let fence = |name: &str, radius_m: f64, alert_on: &str| {
+        test::TestRequest::post()
+            .uri("/geofences")
+            .set_json(json!({
+                "name": name,
+                "lat_center": 12.9716,
+                "lon_center": 77.5946,
+                "radius_m": radius_m,
+                "alert_on": alert_on
+            }))
+            .to_request()
+    };
+    let paddock: Geofence =
+        test::call_and_read_body_json(&app, fence("Paddock", 100.0, "Both")).await;
+    let _: Geofence = test::call_and_read_body_json(&app, fence("Barn", 100.0, "Entry")).await;
+    assert_eq!(
+        test::call_service(&app, fence("Bad", 0.0, "Both"))
+            .await
+            .status(),
+        422
+    );
+    assert_eq!(
+        test::call_service(&app, fence("Bad", 50.0, "Sometimes"))
+            .await
+            .status(),
+        422
+    );
+
+    let locate = |goat_id: i64, lat: f64| {
+        test::TestRequest::post()
+            .uri(&format!("/goats/{}/location", goat_id))
+            .set_json(json!({ "lat": lat, "lon": 77.5946 }))
+            .to_request()
+    };
+    // The first fix has nothing to cross from.
+    let report: LocationReport = test::call_and_read_body_json(&app, locate(1, 12.9716)).await;
+    assert!(report.alerts.is_empty());
+    // ~200 m north: out of both fences, but only the paddock alerts on exit.
+    let report: LocationReport = test::call_and_read_body_json(&app, locate(1, 12.9734)).await;
+    assert_eq!(report.alerts.len(), 1);
+    assert_eq!(report.alerts[0].geofence_name, "Paddock");
+    assert_eq!(report.alerts[0].event, "Exit");
+    assert!((report.alerts[0].distance_m - 200.0).abs() < 1.0);
+    // Back inside: both fences alert on entry.
+    let report: LocationReport = test::call_and_read_body_json(&app, locate(1, 12.9716)).await;
+    assert_eq!(report.alerts.len(), 2);
+    assert!(report.alerts.iter().all(|a| a.event == "Entry"));
+
+    assert_eq!(
+        test::call_service(&app, locate(99, 12.9716)).await.status(),
+        404
+    );
+    assert_eq!(
+        test::call_service(&app, locate(1, 91.0)).await.status(),
+        422
+    );
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/geofences/{}", paddock.id.unwrap()))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+    let req = test::TestRequest::get().uri("/geofences").to_request();
+    let fences: Vec<Geofence> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(fences.len(), 1);
+
+    // Alerts outlive their fence, newest first.
+    let req = test::TestRequest::get()
+        .uri("/alerts/location")
+        .to_request();
+    let alerts: Vec<LocationAlert> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(alerts.len(), 3);
+    assert_eq!(alerts[2].event, "Exit");
+    assert_eq!(alerts[2].geofence_id, None);
+    assert_eq!(alerts[2].geofence_name, "Paddock");
+}
+
+/// Any breed: a built-in one, or `Other` with an arbitrary (often non-ASCII) name that
+/// does not collide with a built-in, since those read back as the built-in variant.
+fn arb_breed() -> impl Strategy<Value = Breed> {
+    prop_oneof![
+        prop::sample::select(BUILTIN_BREEDS.to_vec()).prop_map(|name| str_to_breed(name).unwrap()),
+        any::<String>()
+            .prop_filter("built-in breed name", |name| !BUILTIN_BREEDS
+                .contains(&name.as_str()))
+            .prop_map(Breed::Other),
+    ]
+}
+
+fn arb_gender() -> impl Strategy<Value = Gender> {
+    prop::sample::select(vec!["Male", "Female", "Wether"])
+        .prop_map(|name| str_to_gender(name).unwrap())
+}
+
+fn arb_diet() -> impl Strategy<Value = Diet> {
+    prop_oneof![
+        Just(Diet::Hay),
+        Just(Diet::Pasture),
+        Just(Diet::Mixed),
+        Just(Diet::Concentrate),
+        any::<String>()
+            .prop_filter("known diet name", |name| parse_diet(name).is_err())
+            .prop_map(Diet::Other),
+    ]
+}
+
+fn finite_f64() -> impl Strategy<Value = f64> {
+    any::<f64>().prop_filter("JSON has no NaN or infinity", |f| f.is_finite())
+}
+
+fn arb_goat_params() -> impl Strategy<Value = GoatParams> {
+    let relation = || (proptest::option::of(any::<i64>()), any::<String>());
+    (
+        (arb_breed(), any::<String>(), arb_gender(), any::<i32>()),
+        (finite_f64(), finite_f64(), finite_f64()),
+        (
+            arb_diet(),
+            proptest::option::of(any::<String>()),
+            any::<String>(),
+        ),
+        (
+            prop::collection::vec(relation(), 0..4),
+            prop::collection::vec(relation(), 0..4),
+        ),
+    )
+        .prop_map(
+            |(
+                (breed, name, gender, offspring),
+                (cost, weight, current_price),
+                (diet, last_bred, health_status),
+                (vaccinations, diseases),
+            )| GoatParams {
+                breed,
+                name,
+                gender,
+                offspring,
+                cost,
+                weight,
+                current_price,
+                diet: diet_to_str(&diet).to_string(),
+                last_bred,
+                health_status,
+                vaccinations: vaccinations
+                    .into_iter()
+                    .map(|(id, name)| VaccineRef { id, name })
+                    .collect(),
+                diseases: diseases
+                    .into_iter()
+                    .map(|(id, name)| DiseaseRef { id, name })
+                    .collect(),
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn prop_breed_round_trips(breed in arb_breed()) {
+        let stored = breed_to_str(&breed);
+        prop_assert_eq!(breed_to_str(&str_to_breed(stored).unwrap()), stored);
+
+        let json = serde_json::to_string(&breed).unwrap();
+        let parsed: Breed = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn prop_gender_strings_agree_with_serde(gender in arb_gender()) {
+        // The API (serde) and the database (db_helpers) must spell genders the same way.
+        let stored = gender_to_str(&gender);
+        prop_assert_eq!(serde_json::to_value(&gender).unwrap(), json!(stored));
+        prop_assert_eq!(gender_to_str(&str_to_gender(stored).unwrap()), stored);
+    }
+
+    #[test]
+    fn prop_gender_parsing_is_exact(name in any::<String>()) {
+        if let Ok(gender) = str_to_gender(&name) {
+            prop_assert_eq!(gender_to_str(&gender), name.as_str());
+        }
+    }
+
+    #[test]
+    fn prop_diet_round_trips(diet in arb_diet()) {
+        prop_assert_eq!(str_to_diet(diet_to_str(&diet)), diet);
+    }
+
+    #[test]
+    fn prop_goat_params_survive_json(params in arb_goat_params()) {
+        let json = serde_json::to_string(&params).unwrap();
+        let parsed: GoatParams = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(parsed, params);
+    }
+}
+
+#[actix_rt::test]
+async fn test_add_goat_rejects_malformed_json_with_400() {
+    let db_pool = fresh_db("add_goat_fuzz");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats", web::post().to(add_goat)),
+    )
+    .await;
+    let post = |body: Vec<u8>| {
+        test::TestRequest::post()
+            .uri("/goats")
+            .insert_header(("content-type", "application/json"))
+            .set_payload(body)
+            .to_request()
+    };
+
+    // Every strict prefix of a valid body is unterminated JSON.
+    let valid = serde_json::to_vec(&goat_json("Fuzzed")).unwrap();
+    for len in 0..valid.len() {
+        let status = test::call_service(&app, post(valid[..len].to_vec()))
+            .await
+            .status();
+        assert_eq!(
+            status,
+            400,
+            "prefix {:?}",
+            String::from_utf8_lossy(&valid[..len])
+        );
+    }
+
+    let mut runner = TestRunner::deterministic();
+    let bytes = prop::collection::vec(any::<u8>(), 0..512);
+    for _ in 0..256 {
+        let body = bytes.new_tree(&mut runner).unwrap().current();
+        let status = test::call_service(&app, post(body.clone())).await.status();
+        assert_eq!(status, 400, "body {:?}", body);
+    }
+    assert_eq!(count_goats(&db_pool), 0);
+}
+
+#[actix_rt::test]
+async fn test_space_environment_reports_latest_reading_per_sensor_type() {
+    let db_pool = fresh_db("space_environment");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO spaces (id, name, type) VALUES (1, 'Kidding Pen', 'enclosure');
+             INSERT INTO sensors (id, sensor_type, location, last_reading, last_reading_time, status) VALUES
+                (1, 'temperature', 'pen door', 31.5, '2026-05-01 08:00:00', 'active'),
+                (2, 'humidity', 'pen roof', 62.0, '2026-05-01 08:05:00', 'active'),
+                (3, 'temperature', 'pen back', 29.0, '2026-05-01 09:00:00', 'active'),
+                (4, 'temperature', 'north field', 35.0, '2026-05-01 10:00:00', 'active');",
+        )
+        .unwrap();
+    let app = test::init_service(
+        App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/spaces")
+                .route("/{id}/sensors", web::post().to(assign_sensor))
+                .route("/{id}/environment", web::get().to(get_space_environment)),
+        ),
+    )
+    .await;
+
+    // Sensor 4 is newest but stays outside the pen.
+    for sensor_id in [1, 2, 3] {
+        let req = test::TestRequest::post()
+            .uri("/spaces/1/sensors")
+            .set_json(json!({ "sensor_id": sensor_id }))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+    }
+    let req = test::TestRequest::post()
+        .uri("/spaces/1/sensors")
+        .set_json(json!({ "sensor_id": 99 }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::get()
+        .uri("/spaces/1/environment")
+        .to_request();
+    let environment: SpaceEnvironment = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(environment.space.name, "Kidding Pen");
+    let readings: Vec<(&str, i64, f64)> = environment
+        .readings
+        .iter()
+        .map(|r| (r.sensor_type.as_str(), r.sensor_id, r.reading))
+        .collect();
+    assert_eq!(
+        readings,
+        vec![("humidity", 2, 62.0), ("temperature", 3, 29.0)]
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/spaces/99/environment")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+/// Two pens holding goats 1-3 and 4, with room for `to_capacity` goats in the second.
+fn bulk_transfer_db(name: &str, to_capacity: i64) -> DbPool {
+    let db_pool = fresh_db(name);
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(&format!(
+            "INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'Mover1', 'Female'),
+                (2, 'Beetal', 'Mover2', 'Female'),
+                (3, 'Beetal', 'Mover3', 'Male'),
+                (4, 'Beetal', 'Resident', 'Female');
+             INSERT INTO spaces (id, name, type, capacity) VALUES
+                (1, 'Pen A', 'enclosure', 10),
+                (2, 'Pen B', 'enclosure', {});
+             INSERT INTO space_goats (goat_id, space_id) VALUES (1, 1), (2, 1), (3, 1), (4, 2);",
+            to_capacity
+        ))
+        .unwrap();
+    db_pool
+}
+
+fn goats_in(db_pool: &DbPool, space_id: i64) -> Vec<i64> {
+    let conn = db_pool.get_conn().unwrap();
+    let mut stmt = conn
+        .prepare("SELECT goat_id FROM space_goats WHERE space_id = ?1 ORDER BY goat_id")
+        .unwrap();
+    stmt.query_map([space_id], |r| r.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+fn bulk_transfer_request(goat_ids: &[i64]) -> actix_http::Request {
+    test::TestRequest::post()
+        .uri("/spaces/bulk-transfer")
+        .set_json(json!({ "from_space_id": 1, "to_space_id": 2, "goat_ids": goat_ids }))
+        .to_request()
+}
+
+#[actix_rt::test]
+async fn test_bulk_transfer_moves_goats() {
+    let db_pool = bulk_transfer_db("bulk_transfer_ok", 5);
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/spaces/bulk-transfer", web::post().to(bulk_transfer)),
+    )
+    .await;
+
+    let summary: BulkTransferSummary =
+        test::call_and_read_body_json(&app, bulk_transfer_request(&[1, 3])).await;
+    assert_eq!(
+        summary,
+        BulkTransferSummary {
+            moved: 2,
+            to_space: "Pen B".to_string(),
+            remaining_capacity: Some(2),
+        }
+    );
+    assert_eq!(goats_in(&db_pool, 1), vec![2]);
+    assert_eq!(goats_in(&db_pool, 2), vec![1, 3, 4]);
+}
+
+#[actix_rt::test]
+async fn test_bulk_transfer_rejects_goats_outside_source_space() {
+    let db_pool = bulk_transfer_db("bulk_transfer_membership", 5);
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/spaces/bulk-transfer", web::post().to(bulk_transfer)),
+    )
+    .await;
+
+    // Goat 4 is already in Pen B and goat 9 does not exist.
+    let resp = test::call_service(&app, bulk_transfer_request(&[1, 4, 9])).await;
+    assert_eq!(resp.status(), 422);
+    let errors = test::read_body_json::<ErrorBody, _>(resp).await.fields;
+    assert_eq!(
+        errors,
+        vec![FieldError::new("goat_ids", "goats 4, 9 are not in space 1")]
+    );
+    assert_eq!(goats_in(&db_pool, 1), vec![1, 2, 3]);
+
+    let resp = test::call_service(&app, bulk_transfer_request(&[])).await;
+    assert_eq!(resp.status(), 422);
+}
+
+#[actix_rt::test]
+async fn test_bulk_transfer_rejects_capacity_overflow() {
+    let db_pool = bulk_transfer_db("bulk_transfer_capacity", 3);
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/spaces/bulk-transfer", web::post().to(bulk_transfer)),
+    )
+    .await;
+
+    // Pen B holds one goat and has room for two more.
+    let resp = test::call_service(&app, bulk_transfer_request(&[1, 2, 3])).await;
+    assert_eq!(resp.status(), 409);
+    assert_eq!(goats_in(&db_pool, 1), vec![1, 2, 3]);
+    assert_eq!(goats_in(&db_pool, 2), vec![4]);
+}
+
+/// An in-memory database with the full schema, for exercising `DbPool` functions directly.
+fn memory_conn() -> rusqlite::Connection {
+    let conn = rusqlite::Connection::open_in_memory().unwrap();
+    conn.execute_batch(SCHEMA_SQL).unwrap();
+    conn
+}
+
+fn goat_params(value: serde_json::Value) -> GoatParams {
+    serde_json::from_value(value).expect("valid goat params")
+}
+
+#[test]
+fn test_db_insert_goat_writes_every_column() {
+    let conn = memory_conn();
+    let mut goat = goat_json("  Insert   Me ");
+    goat["diet"] = json!("grass");
+    goat["last_bred"] = json!("2026-03-01");
+    goat["cost"] = json!(1234.56);
+    goat["vaccinations"] = json!([{ "id": null, "name": "CDT" }]);
+    let goat_id = DbPool::insert_goat(&conn, &goat_params(goat)).unwrap();
+
+    let stored = load_goat_details(&conn, goat_id).unwrap();
+    assert_eq!(stored.params.name, "Insert Me");
+    assert_eq!(breed_to_str(&stored.params.breed), "Beetal");
+    assert_eq!(gender_to_str(&stored.params.gender), "Female");
+    assert_eq!(stored.params.diet, "Pasture");
+    assert_eq!(stored.params.cost, 1234.56);
+    assert_eq!(stored.params.weight, 40.0);
+    assert_eq!(stored.params.last_bred.as_deref(), Some("2026-03-01"));
+    assert_eq!(stored.params.health_status, "healthy");
+    assert_eq!(stored.params.vaccinations.len(), 1);
+    let (cost_minor, currency): (i64, String) = conn
+        .query_row(
+            "SELECT cost_minor, currency FROM goats WHERE id = ?1",
+            [goat_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((cost_minor, currency.as_str()), (123456, "INR"));
+
+    let duplicate = DbPool::insert_goat(&conn, &goat_params(goat_json("insert me")));
+    assert!(matches!(duplicate, Err(AppError::Conflict(_))));
+}
+
+#[test]
+fn test_db_update_goat_overwrites_fields_and_relations() {
+    let conn = memory_conn();
+    let mut original = goat_json("Before");
+    original["vaccinations"] = json!([{ "id": null, "name": "CDT" }]);
+    let goat_id = DbPool::insert_goat(&conn, &goat_params(original)).unwrap();
+    DbPool::insert_goat(&conn, &goat_params(goat_json("Neighbour"))).unwrap();
+
+    let mut changed = goat_json("After");
+    changed["breed"] = json!("Sirohi");
+    changed["gender"] = json!("Wether");
+    changed["weight"] = json!(52.5);
+    changed["diet"] = json!("Mixed");
+    changed["diseases"] = json!([{ "id": null, "name": "Mastitis" }]);
+    assert_eq!(
+        DbPool::update_goat(&conn, goat_id, &goat_params(changed), None).unwrap(),
+        1
+    );
+
+    let stored = load_goat_details(&conn, goat_id).unwrap();
+    assert_eq!(stored.params.name, "After");
+    assert_eq!(breed_to_str(&stored.params.breed), "Sirohi");
+    assert_eq!(gender_to_str(&stored.params.gender), "Wether");
+    assert_eq!(stored.params.weight, 52.5);
+    assert_eq!(stored.params.diet, "Mixed");
+    assert!(stored.params.vaccinations.is_empty());
+    assert_eq!(stored.params.diseases.len(), 1);
+
+    let taken = DbPool::update_goat(&conn, goat_id, &goat_params(goat_json("NEIGHBOUR")), None);
+    assert!(matches!(taken, Err(AppError::Conflict(_))));
+    assert_eq!(
+        DbPool::update_goat(&conn, 999, &goat_params(goat_json("Ghost")), None).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn test_is_anomaly_needs_history_and_spread() {
+    let stats = RollingStats::from_moments(10, 20.0, 404.0);
+    assert_eq!(stats.std_dev, 2.0);
+    assert!(!is_anomaly(25.9, &stats, 3.0));
+    assert!(is_anomaly(26.1, &stats, 3.0));
+    assert!(is_anomaly(13.9, &stats, 3.0));
+    // Too little history to judge, however far off.
+    assert!(!is_anomaly(
+        1000.0,
+        &RollingStats::from_moments(4, 20.0, 404.0),
+        3.0
+    ));
+    // A flat history is judged against a spread of 1% of its mean, so noise passes
+    // and real changes are still caught.
+    let flat = RollingStats::from_moments(10, 20.0, 400.0);
+    assert!(!is_anomaly(20.0, &flat, 3.0));
+    assert!(!is_anomaly(20.5, &flat, 3.0));
+    assert!(is_anomaly(20.7, &flat, 3.0));
+    // Around zero the absolute floor applies.
+    let zero = RollingStats::from_moments(10, 0.0, 0.0);
+    assert!(!is_anomaly(0.02, &zero, 3.0));
+    assert!(is_anomaly(0.05, &zero, 3.0));
+}
+
+#[actix_rt::test]
+async fn test_sensor_reading_spike_is_flagged_but_jitter_is_not() {
+    let db_pool = fresh_db("sensor_anomaly");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO sensors (id, sensor_type, location) VALUES (1, 'temperature', 'barn');",
+        )
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/sensors/{id}/readings", web::post().to(add_sensor_reading)),
+    )
+    .await;
+    let read = |sensor_id: i64, value: f64| {
+        test::TestRequest::post()
+            .uri(&format!("/sensors/{}/readings", sensor_id))
+            .set_json(json!({ "value": value }))
+            .to_request()
+    };
+
+    for value in [20.0, 20.2, 19.8, 20.1, 19.9, 20.0, 20.2, 19.8, 20.3, 19.7] {
+        let reading: SensorReading = test::call_and_read_body_json(&app, read(1, value)).await;
+        assert!(
+            !reading.is_anomaly,
+            "jitter {} flagged: {:?}",
+            value, reading
+        );
+    }
+    let spike: SensorReading = test::call_and_read_body_json(&app, read(1, 30.0)).await;
+    assert!(spike.is_anomaly);
+    assert!((spike.mean.unwrap() - 20.0).abs() < 1e-9);
+
+    let conn = db_pool.get_conn().unwrap();
+    let (alerts, last_reading): (i64, f64) = conn
+        .query_row(
+            "SELECT (SELECT COUNT(*) FROM alerts WHERE kind = 'sensor_anomaly'), \
+                    (SELECT last_reading FROM sensors WHERE id = 1)",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(alerts, 1);
+    assert_eq!(last_reading, 30.0);
+
+    assert_eq!(test::call_service(&app, read(99, 20.0)).await.status(), 404);
+}
+
+#[test]
+fn test_sensor_rolling_stats_window_follows_recorded_at() {
+    let db_pool = fresh_db("sensor_rolling_window");
+    let conn = db_pool.get_conn().unwrap();
+    // Five recent readings arrive first; five older ones are backfilled after them.
+    conn.execute_batch(
+        "INSERT INTO sensors (id, sensor_type, location) VALUES (1, 'temperature', 'barn');
+         INSERT INTO sensor_readings (sensor_id, value, recorded_at) VALUES
+            (1, 20.0, '2025-07-02 08:00:00'), (1, 20.0, '2025-07-02 09:00:00'),
+            (1, 20.0, '2025-07-02 10:00:00'), (1, 20.0, '2025-07-02 11:00:00'),
+            (1, 20.0, '2025-07-02 12:00:00'),
+            (1, 100.0, '2025-07-01 08:00:00'), (1, 100.0, '2025-07-01 09:00:00'),
+            (1, 100.0, '2025-07-01 10:00:00'), (1, 100.0, '2025-07-01 11:00:00'),
+            (1, 100.0, '2025-07-01 12:00:00');",
+    )
+    .unwrap();
+
+    let stats = DbPool::sensor_rolling_stats(&conn, 1, 5).unwrap();
+    assert_eq!(stats.count, 5);
+    assert_eq!(stats.mean, 20.0);
+}
+
+fn due(goat_id: i64, vaccine: &str, due_on: &str) -> OverdueVaccination {
+    OverdueVaccination {
+        goat_id,
+        goat_name: format!("Goat{}", goat_id),
+        vaccine_id: 0,
+        vaccine: vaccine.to_string(),
+        due_on: due_on.to_string(),
+        days_overdue: 0,
+    }
+}
+
+#[test]
+fn test_vaccination_schedule_groups_goats_by_vaccine_within_range() {
+    let today = chrono::NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+    let slots = optimize_vaccination_schedule(
+        &[
+            due(1, "CDT", "2026-05-20"),
+            due(2, "PPR", "2026-06-12"),
+            due(3, "CDT", "2026-06-05"),
+            due(4, "PPR", "2026-06-08"),
+            due(2, "CDT", "2026-06-10"),
+            // Past the 10-day range (last day 2026-06-10).
+            due(5, "PPR", "2026-06-11"),
+            due(6, "Rabies", "2026-07-01"),
+        ],
+        10,
+        today,
+    );
+
+    assert_eq!(
+        slots,
+        vec![
+            ScheduleSlot {
+                date: "2026-06-01".to_string(),
+                vaccine: "CDT".to_string(),
+                goat_count: 3,
+                goat_ids: vec![1, 2, 3],
+            },
+            ScheduleSlot {
+                date: "2026-06-08".to_string(),
+                vaccine: "PPR".to_string(),
+                goat_count: 1,
+                goat_ids: vec![4],
+            },
+        ]
+    );
+    let last_day = chrono::NaiveDate::from_ymd_opt(2026, 6, 10).unwrap();
+    for slot in &slots {
+        let date = chrono::NaiveDate::parse_from_str(&slot.date, "%Y-%m-%d").unwrap();
+        assert!(date >= today && date <= last_day);
+    }
+}
+
+#[actix_rt::test]
+async fn test_vaccination_schedule_endpoint() {
+    let db_pool = fresh_db("vaccination_schedule");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender) VALUES
+                (1, 'Beetal', 'Due1', 'Female'),
+                (2, 'Beetal', 'Due2', 'Female'),
+                (3, 'Beetal', 'Later', 'Male');
+             INSERT INTO vaccines (id, name) VALUES (1, 'CDT');
+             INSERT INTO vaccination_schedule (goat_id, vaccine_id, next_due_on) VALUES
+                (1, 1, date('now', '-3 days')),
+                (2, 1, date('now', '+4 days')),
+                (3, 1, date('now', '+60 days'));",
+        )
+        .unwrap();
+    let app = test::init_service(App::new().app_data(web::Data::new(db_pool)).route(
+        "/vaccines/schedule",
+        web::get().to(get_vaccination_schedule),
+    ))
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/vaccines/schedule?date_range_days=30")
+        .to_request();
+    let slots: Vec<ScheduleSlot> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(slots.len(), 1);
+    assert_eq!(slots[0].vaccine, "CDT");
+    assert_eq!(slots[0].goat_ids, vec![1, 2]);
+    assert_eq!(slots[0].date, chrono::Utc::now().date_naive().to_string());
+
+    let req = test::TestRequest::get()
+        .uri("/vaccines/schedule?date_range_days=0")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+}
+
+/// A temperature sensor with readings over two days: three in one hour, one the next
+/// hour, and two the following day.
+async fn bucketed_readings_app() -> impl actix_web::dev::Service<
+    actix_http::Request,
+    Response = actix_web::dev::ServiceResponse,
+    Error = actix_web::Error,
+> {
+    let db_pool = fresh_db("sensor_buckets");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO sensors (id, sensor_type) VALUES (1, 'temperature');
+             INSERT INTO sensor_readings (sensor_id, value, recorded_at) VALUES
+                (1, 20.0, '2026-05-01 08:00:00'),
+                (1, 22.0, '2026-05-01 08:20:00'),
+                (1, 24.0, '2026-05-01 08:59:59'),
+                (1, 30.0, '2026-05-01 09:10:00'),
+                (1, 10.0, '2026-05-02 03:00:00'),
+                (1, 12.0, '2026-05-02 23:30:00');",
+        )
+        .unwrap();
+    test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .route("/sensors/{id}/readings", web::get().to(get_sensor_readings)),
+    )
+    .await
+}
+
+fn bucket(bucket_start: &str, count: i64, avg: f64, min: f64, max: f64) -> SensorReadingBucket {
+    SensorReadingBucket {
+        bucket_start: bucket_start.to_string(),
+        count,
+        avg,
+        min,
+        max,
+    }
+}
+
+#[actix_rt::test]
+async fn test_sensor_readings_hourly_buckets() {
+    let app = bucketed_readings_app().await;
+
+    let req = test::TestRequest::get()
+        .uri("/sensors/1/readings?bucket=1h&to=2026-05-01T23:59:59Z")
+        .to_request();
+    let buckets: Vec<SensorReadingBucket> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        buckets,
+        vec![
+            bucket("2026-05-01 08:00:00", 3, 22.0, 20.0, 24.0),
+            bucket("2026-05-01 09:00:00", 1, 30.0, 30.0, 30.0),
+        ]
+    );
+
+    let req = test::TestRequest::get()
+        .uri("/sensors/1/readings?from=2026-05-02")
+        .to_request();
+    let raw: Vec<SensorReading> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(raw.len(), 2);
+
+    // Raw readings come a page at a time.
+    let req = test::TestRequest::get()
+        .uri("/sensors/1/readings?limit=4&offset=2")
+        .to_request();
+    let page: Vec<SensorReading> = test::call_and_read_body_json(&app, req).await;
+    let values: Vec<f64> = page.iter().map(|r| r.value).collect();
+    assert_eq!(values, vec![24.0, 30.0, 10.0, 12.0]);
+    let req = test::TestRequest::get()
+        .uri("/sensors/1/readings?limit=2")
+        .to_request();
+    let page: Vec<SensorReading> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page.len(), 2);
+
+    let req = test::TestRequest::get()
+        .uri("/sensors/1/readings?bucket=5m")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+    let req = test::TestRequest::get()
+        .uri("/sensors/9/readings?bucket=1h")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+#[actix_rt::test]
+async fn test_sensor_readings_reject_ranges_over_the_bucket_cap() {
+    let db_pool = fresh_db("sensor_bucket_cap");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(&format!(
+            "INSERT INTO sensors (id, sensor_type) VALUES (1, 'temperature');
+             WITH RECURSIVE hours(n) AS (SELECT 0 UNION ALL SELECT n + 1 FROM hours WHERE n < {})
+             INSERT INTO sensor_readings (sensor_id, value, recorded_at)
+             SELECT 1, 20.0, datetime('2026-01-01 00:00:00', '+' || n || ' hours') FROM hours;",
+            MAX_READING_BUCKETS
+        ))
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool))
+            .route("/sensors/{id}/readings", web::get().to(get_sensor_readings)),
+    )
+    .await;
+
+    // One hour too many is refused rather than silently cut off.
+    let req = test::TestRequest::get()
+        .uri("/sensors/1/readings?bucket=1h")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: ErrorBody = test::read_body_json(resp).await;
+    assert!(body.message.contains("buckets"));
+
+    let req = test::TestRequest::get()
+        .uri("/sensors/1/readings?bucket=1d")
+        .to_request();
+    let days: Vec<SensorReadingBucket> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(days.iter().map(|b| b.count).sum::<i64>(), 1001);
+
+    // Raw readings are capped per page.
+    let req = test::TestRequest::get()
+        .uri("/sensors/1/readings?limit=5000")
+        .to_request();
+    let raw: Vec<SensorReading> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(raw.len(), MAX_RAW_READINGS as usize);
+}
+
+#[actix_rt::test]
+async fn test_sensor_readings_daily_buckets() {
+    let app = bucketed_readings_app().await;
+
+    let req = test::TestRequest::get()
+        .uri("/sensors/1/readings?bucket=1d")
+        .to_request();
+    let buckets: Vec<SensorReadingBucket> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(
+        buckets,
+        vec![
+            bucket("2026-05-01 00:00:00", 4, 24.0, 20.0, 30.0),
+            bucket("2026-05-02 00:00:00", 2, 11.0, 10.0, 12.0),
+        ]
+    );
+}
+
+#[actix_rt::test]
+async fn test_sensor_pruning_removes_only_old_readings_and_rolls_them_up() {
+    let db_pool = fresh_db("sensor_pruning");
+    {
+        let conn = db_pool.get_conn().unwrap();
+        conn.execute_batch(
+            "INSERT INTO sensors (id, sensor_type, location) VALUES (1, 'temperature', 'barn');
+             INSERT INTO sensor_readings (sensor_id, value, recorded_at, is_anomaly) VALUES
+                 (1, 10.0, datetime('now', '-200 days', 'start of day', '+1 hours'), 0),
+                 (1, 20.0, datetime('now', '-200 days', 'start of day', '+2 hours'), 0),
+                 (1, 30.0, datetime('now', '-200 days', 'start of day', '+3 hours'), 1),
+                 (1, 40.0, datetime('now', '-100 days'), 0),
+                 (1, 21.0, datetime('now', '-1 days'), 0),
+                 (1, 22.0, datetime('now'), 0);",
+        )
+        .unwrap();
+    }
+    let cutoff: String = db_pool
+        .get_conn()
+        .unwrap()
+        .query_row("SELECT datetime('now', '-90 days')", [], |r| r.get(0))
+        .unwrap();
+
+    // Two per batch, so the first day's readings are rolled up across two batches.
+    let summary = db_pool.prune_sensor_readings(&cutoff, true, 2).unwrap();
+    assert_eq!(summary.deleted, 4);
+    assert_eq!(summary.batches, 2);
+    assert!(summary.rolled_up);
+
+    let conn = db_pool.get_conn().unwrap();
+    let remaining: Vec<f64> = conn
+        .prepare("SELECT value FROM sensor_readings ORDER BY id")
+        .unwrap()
+        .query_map([], |r| r.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(remaining, vec![21.0, 22.0]);
+    let daily: Vec<(i64, f64, f64, f64, i64)> = conn
+        .prepare("SELECT count, avg, min, max, anomalies FROM sensor_reading_daily ORDER BY day")
+        .unwrap()
+        .query_map([], |r| {
+            Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        daily,
+        vec![(3, 20.0, 10.0, 30.0, 1), (1, 40.0, 40.0, 40.0, 0)]
+    );
+
+    conn.execute(
+        "INSERT INTO sensor_readings (sensor_id, value, recorded_at) \
+         VALUES (1, 50.0, datetime('now', '-40 days'))",
+        [],
+    )
+    .unwrap();
+    let app = test::init_service(App::new().app_data(web::Data::new(db_pool.clone())).route(
+        "/admin/sensors/prune",
+        web::post().to(prune_sensor_readings),
+    ))
+    .await;
+    let req = test::TestRequest::post()
+        .uri("/admin/sensors/prune?older_than_days=30&rollup=false")
+        .to_request();
+    let summary: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(summary["deleted"], 1);
+    assert_eq!(summary["rolled_up"], false);
+    let (readings, days): (i64, i64) = conn
+        .query_row(
+            "SELECT (SELECT COUNT(*) FROM sensor_readings), \
+                    (SELECT COUNT(*) FROM sensor_reading_daily)",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((readings, days), (2, 2));
+}
+
+#[test]
+fn test_linear_regression_recovers_a_known_line() {
+    // y = 2x + 10, exactly.
+    let points = [(0, 10.0), (1, 12.0), (2, 14.0), (3, 16.0)];
+    assert_eq!(linear_regression(&points), Some((2.0, 10.0)));
+
+    // Noisy points: means (3, 38.5), covariance -14 over variance 20.
+    let (slope, intercept) =
+        linear_regression(&[(0, 41.0), (2, 38.0), (4, 39.0), (6, 36.0)]).unwrap();
+    assert!((slope - -0.7).abs() < 1e-9, "slope {}", slope);
+    assert!((intercept - 40.6).abs() < 1e-9, "intercept {}", intercept);
+
+    // Timestamps are fitted without losing the trend to precision.
+    let day = SECS_PER_DAY;
+    let start = 1_767_225_600; // 2026-01-01
+    let (slope, _) = linear_regression(&[
+        (start, 20.0),
+        (start + 10 * day, 25.0),
+        (start + 20 * day, 30.0),
+    ])
+    .unwrap();
+    assert!((slope * day as f64 - 0.5).abs() < 1e-9);
+
+    assert_eq!(linear_regression(&[]), None);
+    assert_eq!(linear_regression(&[(5, 1.0)]), None);
+    assert_eq!(linear_regression(&[(5, 1.0), (5, 3.0)]), None);
+}
+
+#[actix_rt::test]
+async fn test_weight_prediction_extends_the_weight_trend() {
+    let db_pool = fresh_db("weight_prediction");
+    let goat_id = seed_goat(&db_pool, "Growing Kid", "Beetal");
+    let app = test::init_service(App::new().app_data(web::Data::new(db_pool.clone())).route(
+        "/goats/{id}/weight-prediction",
+        web::get().to(get_weight_prediction),
+    ))
+    .await;
+    let predict = |uri: String| test::TestRequest::get().uri(&uri).to_request();
+
+    let resp = test::call_service(
+        &app,
+        predict(format!("/goats/{}/weight-prediction", goat_id)),
+    )
+    .await;
+    assert_eq!(resp.status(), 422);
+    let resp = test::call_service(&app, predict("/goats/999/weight-prediction".to_string())).await;
+    assert_eq!(resp.status(), 404);
+
+    // Half a kilogram a day.
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute(
+            "INSERT INTO goat_weight_history (goat_id, weight, created_at) VALUES \
+                 (?1, 20.0, datetime('now', '-20 days')), \
+                 (?1, 25.0, datetime('now', '-10 days')), \
+                 (?1, 30.0, datetime('now'))",
+            [goat_id],
+        )
+        .unwrap();
+    let body: serde_json::Value = test::call_and_read_body_json(
+        &app,
+        predict(format!("/goats/{}/weight-prediction?days=30", goat_id)),
+    )
+    .await;
+    assert_eq!(body["current_weight"], 30.0);
+    assert!((body["predicted_weight"].as_f64().unwrap() - 45.0).abs() < 0.02);
+    assert_eq!(body["confidence"], "medium");
+
+    // A losing trend bottoms out at zero rather than going negative.
+    let points = [(0, 10.0), (SECS_PER_DAY, 5.0)];
+    let prediction = predict_weight(&points, 30 * SECS_PER_DAY).unwrap();
+    assert_eq!(prediction.predicted_weight, 0.0);
+    assert_eq!(prediction.confidence, Confidence::Low);
+}
+
+#[test]
+fn test_population_simulation_with_deterministic_rates() {
+    // Every goat bears exactly one kid and none die or are sold: the herd doubles yearly
+    // with no spread between iterations.
+    let projection = simulate_population(10, 1.0, 0.0, 0.0, 3, 50);
+    let sizes: Vec<(i32, f64, f64, f64)> = projection
+        .iter()
+        .map(|y| {
+            (
+                y.year,
+                y.projected_herd_size,
+                y.confidence_lower,
+                y.confidence_upper,
+            )
+        })
+        .collect();
+    assert_eq!(
+        sizes,
+        vec![
+            (1, 20.0, 20.0, 20.0),
+            (2, 40.0, 40.0, 40.0),
+            (3, 80.0, 80.0, 80.0)
+        ]
+    );
+
+    // Total mortality without births empties the herd in the first year.
+    let projection = simulate_population(25, 0.0, 1.0, 0.0, 2, 10);
+    assert!(projection.iter().all(|y| y.projected_herd_size == 0.0));
+    // So does selling every survivor; out-of-range rates are clamped.
+    let projection = simulate_population(25, -1.0, 0.0, 3.0, 1, 10);
+    assert_eq!(projection[0].confidence_upper, 0.0);
+    assert!(simulate_population(25, 1.0, 0.1, 0.1, 0, 10).is_empty());
+
+    // Random rates are reproducible from a seed and bracket the mean.
+    let run = |seed| {
+        simulate_population_with(&mut StdRng::seed_from_u64(seed), 40, 0.5, 0.1, 0.2, 5, 200)
+    };
+    assert_eq!(run(7), run(7));
+    for year in run(7) {
+        assert!(year.confidence_lower <= year.projected_herd_size);
+        assert!(year.projected_herd_size <= year.confidence_upper);
+        assert!(year.confidence_lower < year.confidence_upper);
+    }
+    // Growth of 0.5 - 0.1 - 0.2 * 0.9 = 0.22 a year: 40 * 1.22 = 48.8 after one.
+    assert!((run(7)[0].projected_herd_size - 48.8).abs() < 1.5);
+}
+
+#[actix_rt::test]
+async fn test_population_simulation_endpoint_uses_herd_history_and_caps() {
+    let db_pool = fresh_db("population_simulation");
+    let ids: Vec<i64> = (0..4)
+        .map(|i| seed_goat(&db_pool, &format!("Herd {}", i), "Beetal"))
+        .collect();
+    {
+        let conn = db_pool.get_conn().unwrap();
+        conn.execute(
+            "UPDATE goats SET created_at = datetime('now', '-2 years')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE goats SET deleted_at = datetime('now', '-30 days') WHERE id = ?1",
+            [ids[0]],
+        )
+        .unwrap();
+        let rates = DbPool::population_rates(&conn, chrono::Utc::now().date_naive()).unwrap();
+        assert_eq!(rates.initial_herd, 3);
+        assert_eq!(rates.breeding_rate, 0.0);
+        assert_eq!(rates.sales_rate, 0.0);
+        // One death over an average herd of (4 + 3) / 2.
+        assert!((rates.mortality_rate - 1.0 / 3.5).abs() < 1e-9);
+    }
+
+    let app = test::init_service(App::new().app_data(web::Data::new(db_pool.clone())).route(
+        "/simulate/population",
+        web::post().to(simulate_population_growth),
+    ))
+    .await;
+    let body: Vec<serde_json::Value> = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri("/simulate/population?years=50&iterations=100000")
+            .to_request(),
+    )
+    .await;
+    assert_eq!(body.len(), 20);
+    let this_year = chrono::Datelike::year(&chrono::Utc::now()) as i64;
+    for (i, year) in body.iter().enumerate() {
+        assert_eq!(year["year"].as_i64().unwrap(), this_year + 1 + i as i64);
+        let size = year["projected_herd_size"].as_f64().unwrap();
+        assert!(size <= 3.0);
+        assert!(year["confidence_lower"].as_f64().unwrap() <= size);
+        assert!(size <= year["confidence_upper"].as_f64().unwrap());
+    }
+
+    let body: Vec<serde_json::Value> = test::call_and_read_body_json(
+        &app,
+        test::TestRequest::post()
+            .uri("/simulate/population")
+            .to_request(),
+    )
+    .await;
+    assert_eq!(body.len(), 5);
+}
+
+#[actix_rt::test]
+async fn test_random_goats_respect_count_cap_and_filters() {
+    let db_pool = fresh_db("random_goats");
+    let ids: Vec<i64> = (0..6)
+        .map(|i| seed_goat(&db_pool, &format!("Sample {}", i), "Beetal"))
+        .collect();
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(&format!(
+            "INSERT INTO spaces (id, name, type) VALUES (1, 'North pen', 'enclosure');
+             INSERT INTO space_goats (goat_id, space_id) VALUES ({}, 1), ({}, 1), ({}, 1);",
+            ids[0], ids[2], ids[4]
+        ))
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats/random", web::get().to(get_random_goats)),
+    )
+    .await;
+    let sample = |uri: &str| test::TestRequest::get().uri(uri).to_request();
+
+    let goats: Vec<Goat> = test::call_and_read_body_json(&app, sample("/goats/random")).await;
+    assert_eq!(goats.len(), 1);
+
+    let goats: Vec<Goat> =
+        test::call_and_read_body_json(&app, sample("/goats/random?count=4")).await;
+    let mut sampled: Vec<i64> = goats.iter().map(|g| g.id().unwrap()).collect();
+    sampled.sort_unstable();
+    sampled.dedup();
+    assert_eq!(sampled.len(), 4, "sampled goats are distinct");
+
+    // Asking for more than the enclosure holds returns exactly its goats.
+    for _ in 0..5 {
+        let goats: Vec<Goat> =
+            test::call_and_read_body_json(&app, sample("/goats/random?count=2&space_id=1")).await;
+        assert_eq!(goats.len(), 2);
+        assert!(
+            goats
+                .iter()
+                .all(|g| [ids[0], ids[2], ids[4]].contains(&g.id().unwrap()))
+        );
+    }
+    let goats: Vec<Goat> =
+        test::call_and_read_body_json(&app, sample("/goats/random?count=10&space_id=1")).await;
+    assert_eq!(goats.len(), 3);
+
+    let goats: Vec<Goat> =
+        test::call_and_read_body_json(&app, sample("/goats/random?count=100000")).await;
+    assert_eq!(goats.len(), 6);
+    let resp = test::call_service(&app, sample("/goats/random?count=many")).await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[test]
+fn test_goat_with_id_survives_json() {
+    let goat = Goat::new(None, goat_params(goat_json("Idless")));
+    assert_eq!(goat.id(), None);
+
+    let goat = goat.with_id(42);
+    assert_eq!(goat.id(), Some(42));
+    let round_tripped: Goat = serde_json::from_value(serde_json::to_value(&goat).unwrap()).unwrap();
+    assert_eq!(round_tripped.id(), Some(42));
+    assert_eq!(round_tripped.params.name, "Idless");
+}
+
+#[test]
+fn test_goat_service_round_trips_ids() {
+    let db_pool = fresh_db("goat_service_ids");
+    let created = GoatService::insert(&db_pool, &goat_params(goat_json("Service"))).unwrap();
+    let goat_id = created.id().expect("inserted goat has an id");
+    assert_eq!(
+        GoatService::get(&db_pool, goat_id).unwrap().id(),
+        Some(goat_id)
+    );
+
+    let renamed =
+        GoatService::update(&db_pool, goat_id, &goat_params(goat_json("Renamed")), None).unwrap();
+    assert_eq!(renamed.id(), Some(goat_id));
+    assert_eq!(renamed.params.name, "Renamed");
+    assert!(matches!(
+        GoatService::update(&db_pool, 999, &goat_params(goat_json("Ghost")), None),
+        Err(AppError::NotFound(_))
+    ));
+
+    let deleted = GoatService::delete(&db_pool, goat_id).unwrap();
+    assert_eq!(deleted.params.name, "Renamed");
+    assert!(matches!(
+        GoatService::get(&db_pool, goat_id),
+        Err(AppError::NotFound(_))
+    ));
+    assert!(matches!(
+        GoatService::delete(&db_pool, goat_id),
+        Err(AppError::NotFound(_))
+    ));
+}
+
+/// An export document as JSON, without what legitimately differs between databases: the
+/// export time and catalog ids. Relations are sorted by name, as they are unordered.
+fn comparable_document(document: &GoatDocument) -> serde_json::Value {
+    let mut value = serde_json::to_value(document).unwrap();
+    value.as_object_mut().unwrap().remove("exported_at");
+    for relation in ["vaccinations", "diseases"] {
+        let mut names: Vec<String> = value["goat"][relation]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["name"].as_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        value["goat"][relation] = json!(names);
+    }
+    value
+}
+
+#[actix_rt::test]
+async fn test_goat_document_round_trips_into_a_fresh_database() {
+    let source = fresh_db("goat_document_source");
+    let mut goat = goat_json("Traveller");
+    goat["last_bred"] = json!("2025-02-01");
+    goat["vaccinations"] = json!([{ "id": null, "name": "CDT" }, { "id": null, "name": "PPR" }]);
+    goat["diseases"] = json!([{ "id": null, "name": "Mastitis" }]);
+    let goat_id = DbPool::insert_goat(&source.get_conn().unwrap(), &goat_params(goat)).unwrap();
+    source
+        .get_conn()
+        .unwrap()
+        .execute_batch(&format!(
+            "INSERT INTO goat_weight_history (goat_id, weight, created_at) VALUES
+                ({id}, 31.5, '2025-01-10 08:00:00'),
+                ({id}, 36.0, '2025-02-10 08:00:00');",
+            id = goat_id
+        ))
+        .unwrap();
+
+    let app = |db_pool: DbPool| {
+        App::new().app_data(web::Data::new(db_pool)).service(
+            web::scope("/goats")
+                .route("/import-document", web::post().to(import_goat_document))
+                .route("/{id}/export", web::get().to(export_goat_document)),
+        )
+    };
+
+    let source_app = test::init_service(app(source)).await;
+    let req = test::TestRequest::get()
+        .uri(&format!("/goats/{}/export", goat_id))
+        .to_request();
+    let exported: GoatDocument = test::call_and_read_body_json(&source_app, req).await;
+    assert_eq!(exported.document_version, GOAT_DOCUMENT_VERSION);
+    assert_eq!(exported.weight_history.len(), 2);
+
+    // The destination already knows PPR, under another id.
+    let destination = fresh_db("goat_document_destination");
+    destination
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO vaccines (name) VALUES ('Rabies'), ('PPR');
+             INSERT INTO goats (breed, name, gender) VALUES ('Sirohi', 'Local', 'Male');",
+        )
+        .unwrap();
+    let catalog_pool = destination.clone();
+    let destination_app = test::init_service(app(destination)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/goats/import-document")
+        .set_json(&exported)
+        .to_request();
+    let resp = test::call_service(&destination_app, req).await;
+    assert_eq!(resp.status(), 201);
+    let imported: Goat = test::read_body_json(resp).await;
+    let imported_id = imported.id().unwrap();
+    assert_ne!(imported_id, goat_id);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/goats/{}/export", imported_id))
+        .to_request();
+    let reexported: GoatDocument = test::call_and_read_body_json(&destination_app, req).await;
+    assert_eq!(
+        comparable_document(&reexported),
+        comparable_document(&exported)
+    );
+
+    let conn = catalog_pool.get_conn().unwrap();
+    let ppr_rows: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM vaccines WHERE name = 'PPR'",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(ppr_rows, 1);
+    drop(conn);
+
+    // Importing the same goat twice conflicts on its name.
+    let req = test::TestRequest::post()
+        .uri("/goats/import-document")
+        .set_json(&exported)
+        .to_request();
+    assert_eq!(
+        test::call_service(&destination_app, req).await.status(),
+        409
+    );
+
+    let mut future = exported.clone();
+    future.document_version = GOAT_DOCUMENT_VERSION + 1;
+    future.goat.name = "From The Future".to_string();
+    let req = test::TestRequest::post()
+        .uri("/goats/import-document")
+        .set_json(&future)
+        .to_request();
+    assert_eq!(
+        test::call_service(&destination_app, req).await.status(),
+        400
+    );
+}
+
+#[actix_web::test]
+async fn test_goat_routes_by_id() {
+    let db_pool = fresh_db("goat_routes_by_id");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats", web::post().to(add_goat))
+            .route("/goats/{id}", web::get().to(get_goat))
+            .route("/goats/{id}", web::put().to(update_goat_by_id))
+            .route("/goats/{id}", web::delete().to(delete_goat_by_id)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(goat_json("Addressed"))
+        .to_request();
+    let created: Goat = test::call_and_read_body_json(&app, req).await;
+    let goat_id = created.id().expect("created goat has an id");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/goats/{}", goat_id))
+        .to_request();
+    let fetched: Goat = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(fetched.id(), Some(goat_id));
+    assert_eq!(fetched.params.name, "Addressed");
+
+    let mut readdressed = goat_json("Readdressed");
+    readdressed["version"] = json!(fetched.version());
+    let req = test::TestRequest::put()
+        .uri(&format!("/goats/{}", goat_id))
+        .set_json(&readdressed)
+        .to_request();
+    let updated: Goat = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(updated.id(), Some(goat_id));
+    assert_eq!(updated.params.name, "Readdressed");
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/goats/{}", goat_id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/goats/{}", goat_id))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
+
+#[actix_web::test]
+async fn test_stale_goat_version_is_rejected_with_409() {
+    let db_pool = fresh_db("goat_version_conflict");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats", web::post().to(add_goat))
+            .route("/goats", web::put().to(update_goat))
+            .route("/goats/{id}", web::get().to(get_goat))
+            .route("/goats/{id}", web::put().to(update_goat_by_id)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(goat_json("Contested"))
+        .to_request();
+    let created: Goat = test::call_and_read_body_json(&app, req).await;
+    let goat_id = created.id().unwrap();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/goats/{}", goat_id))
+        .to_request();
+    let fetched: Goat = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(fetched.version(), 0);
+
+    // Someone else writes the goat after it was fetched.
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute(
+            "UPDATE goats SET version = version + 1 WHERE id = ?1",
+            [goat_id],
+        )
+        .unwrap();
+
+    let mut stale = goat_json("Contested");
+    stale["weight"] = json!(99.0);
+    stale["version"] = json!(fetched.version());
+    for uri in [format!("/goats/{}", goat_id), "/goats".to_string()] {
+        let req = test::TestRequest::put()
+            .uri(&uri)
+            .set_json(&stale)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 409, "{}", uri);
+        let body: ErrorBody = test::read_body_json(resp).await;
+        assert_eq!(body.error, "VersionConflict");
+    }
+
+    stale["version"] = json!(1);
+    let req = test::TestRequest::put()
+        .uri(&format!("/goats/{}", goat_id))
+        .set_json(&stale)
+        .to_request();
+    let updated: Goat = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(updated.version(), 2);
+    assert_eq!(updated.params.weight, 99.0);
+}
+
+#[actix_web::test]
+async fn test_goat_patch_requires_a_current_version() {
+    let db_pool = fresh_db("goat_patch_version");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats", web::post().to(add_goat))
+            .route("/goats/{id}", web::patch().to(patch_goat)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(goat_json("Patched"))
+        .to_request();
+    let created: Goat = test::call_and_read_body_json(&app, req).await;
+    let goat_id = created.id().unwrap();
+    assert_eq!(created.version(), 0);
+    let uri = format!("/goats/{}", goat_id);
+
+    let req = test::TestRequest::patch()
+        .uri(&uri)
+        .set_json(json!({ "weight": 99.0 }))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    // Someone else writes the goat after it was read.
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute(
+            "UPDATE goats SET version = version + 1 WHERE id = ?1",
+            [goat_id],
+        )
+        .unwrap();
+
+    let req = test::TestRequest::patch()
+        .uri(&uri)
+        .set_json(json!({ "weight": 99.0, "version": 0 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+    let body: ErrorBody = test::read_body_json(resp).await;
+    assert_eq!(body.error, "VersionConflict");
+
+    let req = test::TestRequest::patch()
+        .uri(&uri)
+        .set_json(json!({ "weight": 99.0, "version": 1 }))
+        .to_request();
+    let updated: Goat = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(updated.version(), 2);
+    assert_eq!(updated.params.weight, 99.0);
+    assert_eq!(updated.params.name, created.params.name);
+    assert_eq!(updated.params.cost, created.params.cost);
+
+    let req = test::TestRequest::patch()
+        .uri(&uri)
+        .set_json(json!({ "name": " ", "version": 2 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let body: ErrorBody = test::read_body_json(resp).await;
+    assert_eq!(body.fields[0].field, "name");
+}
+
+#[actix_web::test]
+async fn test_every_goat_write_bumps_its_version() {
+    let db_pool = fresh_db("goat_version_bumps");
+    let goat_id = seed_goat(&db_pool, "Bumped", "Beetal");
+    let conn = db_pool.get_conn().unwrap();
+    conn.execute("UPDATE goats SET gender = 'Male' WHERE id = ?1", [goat_id])
+        .unwrap();
+    let version = || -> u32 {
+        conn.query_row("SELECT version FROM goats WHERE id = ?1", [goat_id], |r| {
+            r.get(0)
+        })
+        .unwrap()
+    };
+    assert_eq!(version(), 0);
+
+    DbPool::reclassify_wethers(&conn, &[goat_id]).unwrap();
+    assert_eq!(version(), 1);
+    let sale = SalePayload {
+        buyer_id: None,
+        buyer_name: Some("Bumped Buyer".to_string()),
+        price: 150.0,
+    };
+    DbPool::sell_goat(&conn, goat_id, &sale).unwrap();
+    assert_eq!(version(), 2);
+
+    // A PUT made from the goat as first read no longer undoes either write.
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats/{id}", web::put().to(update_goat_by_id)),
+    )
+    .await;
+    let mut stale = goat_json("Bumped");
+    stale["version"] = json!(0);
+    let req = test::TestRequest::put()
+        .uri(&format!("/goats/{}", goat_id))
+        .set_json(&stale)
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 409);
+    let req = test::TestRequest::put()
+        .uri(&format!("/goats/{}", goat_id))
+        .set_json(goat_json("Bumped"))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    DbPool::soft_delete_goat(&conn, goat_id).unwrap();
+    assert_eq!(version(), 3);
+}
+
+#[actix_web::test]
+async fn test_profit_analysis_nets_costs_against_value_and_milk() {
+    let db_pool = fresh_db("profit_analysis");
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender, cost, current_price, lifecycle_state) VALUES
+                (1, 'Beetal', 'Earner', 'Female', 5000.0, 8000.0, 'Active'),
+                (2, 'Sirohi', 'Idle', 'Male', 4000.0, 3000.0, 'Active'),
+                (3, 'Beetal', 'Gone', 'Female', 1000.0, 0.0, 'Deceased');
+             INSERT INTO expenses (goat_id, category, amount) VALUES
+                (1, 'Feed', 1000.0), (1, 'Feed', 500.0), (1, 'Vet', 300.0),
+                (1, 'Labor', 9999.0), (NULL, 'Feed', 9999.0), (3, 'Feed', 50.0);
+             INSERT INTO vet_visits (goat_id, vet_name, cost) VALUES (1, 'Dr. Rao', 200.0);
+             INSERT INTO production_records (goat_id, type, quantity, revenue) VALUES
+                (1, 'Milk', 100.0, 2000.0), (1, 'Milk', 25.0, 500.0), (1, 'Fiber', 2.0, 9999.0);",
+        )
+        .expect("Failed to seed profit data");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route(
+                "/goats/{id}/profit-analysis",
+                web::get().to(get_goat_profit_analysis),
+            )
+            .route("/stats/profitability", web::get().to(get_profitability)),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/goats/1/profit-analysis")
+        .to_request();
+    let earner: ProfitAnalysis = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(earner.acquisition_cost, 5000.0);
+    assert_eq!(earner.current_value, 8000.0);
+    assert_eq!(earner.feed_cost_to_date, 1500.0);
+    assert_eq!(earner.vet_cost_to_date, 500.0);
+    assert_eq!(earner.milk_revenue_to_date, 2500.0);
+    // 8000 + 2500 - 5000 - 1500 - 500
+    assert_eq!(earner.net_position, 3500.0);
+
+    // Nothing recorded against the goat: every running total is zero.
+    let req = test::TestRequest::get()
+        .uri("/goats/2/profit-analysis")
+        .to_request();
+    let idle: ProfitAnalysis = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(idle.feed_cost_to_date, 0.0);
+    assert_eq!(idle.milk_revenue_to_date, 0.0);
+    assert_eq!(idle.net_position, -1000.0);
+
+    let req = test::TestRequest::get()
+        .uri("/goats/99/profit-analysis")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+
+    let req = test::TestRequest::get()
+        .uri("/stats/profitability")
+        .to_request();
+    let ranking: Vec<ProfitAnalysis> = test::call_and_read_body_json(&app, req).await;
+    let names: Vec<&str> = ranking.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["Earner", "Idle"]);
+}
+
+#[test]
+fn test_goat_column_check_names_the_missing_column() {
+    let conn = memory_conn();
+    check_goat_columns(&conn).expect("current schema has every goat column");
+
+    // A database that never ran the goat version migration.
+    let stale = rusqlite::Connection::open_in_memory().unwrap();
+    let columns: Vec<&str> = GOAT_COLUMNS
+        .iter()
+        .copied()
+        .filter(|c| *c != "version")
+        .collect();
+    stale
+        .execute_batch(&format!("CREATE TABLE goats ({});", columns.join(", ")))
+        .unwrap();
+    match check_goat_columns(&stale) {
+        Err(AppError::Internal(msg)) => assert!(msg.contains("'version'"), "{}", msg),
+        other => panic!("expected a missing column error, got {:?}", other),
+    }
+}
+
+#[actix_web::test]
+async fn test_new_database_is_bootstrapped_and_serves_goats() {
+    let path = std::env::temp_dir().join("yagi_test_bootstrap.db");
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+    let db_pool = DbPool::new(path.to_str().unwrap()).unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats", web::get().to(get_goats)),
+    )
+    .await;
+
+    let req = test::TestRequest::get().uri("/goats").to_request();
+    let goats: Vec<serde_json::Value> = test::call_and_read_body_json(&app, req).await;
+    assert!(goats.is_empty());
+
+    // Opening it again finds nothing left to create.
+    let conn = db_pool.get_conn().unwrap();
+    assert!(ensure_schema(&conn).unwrap().is_empty());
+}
+
+#[test]
+fn test_schema_bootstrap_fills_gaps_and_defers_to_refinery() {
+    // An older database keeps its tables and rows and only gains what is missing,
+    // indexes and triggers included.
+    let legacy = rusqlite::Connection::open_in_memory().unwrap();
+    legacy
+        .execute_batch(&format!(
+            "{}
+             DROP TABLE goat_space_history;
+             DROP TRIGGER immutable_trade_log_no_update;
+             INSERT INTO goats (id, breed, name, gender) VALUES (1, 'Beetal', 'Kept', 'Female');
+             INSERT INTO spaces (id, name, type) VALUES (4, 'North Pen', 'enclosure');
+             INSERT INTO space_goats (goat_id, space_id, assigned_at)
+             VALUES (1, 4, '2025-03-01 08:00:00');",
+            SCHEMA_SQL
+        ))
+        .unwrap();
+    let created = ensure_schema(&legacy).unwrap();
+    assert_eq!(created, vec!["goat_space_history".to_string()]);
+    assert!(check_goat_columns(&legacy).is_ok());
+    let kept: String = legacy
+        .query_row("SELECT name FROM goats WHERE id = 1", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(kept, "Kept");
+    let triggers: i64 = legacy
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master \
+             WHERE type = 'trigger' AND name = 'immutable_trade_log_no_update'",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(triggers, 1);
+
+    // The goat's current assignment becomes its open stay, once.
+    assert!(ensure_schema(&legacy).unwrap().is_empty());
+    let stays: Vec<(i64, String, Option<String>)> = legacy
+        .prepare(
+            "SELECT space_id, moved_in_at, moved_out_at FROM goat_space_history WHERE goat_id = 1",
+        )
+        .unwrap()
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(stays, vec![(4, "2025-03-01 08:00:00".to_string(), None)]);
+
+    // Once refinery has run, the schema is left to it.
+    let migrated = rusqlite::Connection::open_in_memory().unwrap();
+    migrated
+        .execute_batch(&format!(
+            "CREATE TABLE {} (version INTEGER);",
+            REFINERY_HISTORY_TABLE
+        ))
+        .unwrap();
+    assert!(ensure_schema(&migrated).unwrap().is_empty());
+    let tables: i64 = migrated
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(tables, 1);
+}
+
+#[test]
+fn test_journal_mode_parsing_rejects_unknown_modes() {
+    assert_eq!(
+        " delete ".parse::<JournalMode>().unwrap(),
+        JournalMode::Delete
+    );
+    assert_eq!("Wal".parse::<JournalMode>().unwrap(), JournalMode::Wal);
+    match "OFF".parse::<JournalMode>() {
+        Err(AppError::InvalidInput(msg)) => {
+            assert!(msg.contains("'OFF'"), "{}", msg);
+            assert!(msg.contains("WAL, DELETE, TRUNCATE, MEMORY"), "{}", msg);
+        }
+        other => panic!("expected an unsupported mode error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_journal_mode_applies_to_every_pooled_connection() {
+    let journal_mode = |conn: &rusqlite::Connection| -> String {
+        conn.query_row("PRAGMA journal_mode", [], |r| r.get(0))
+            .unwrap()
+    };
+    for (mode, expected) in [
+        (JournalMode::Delete, "delete"),
+        (JournalMode::Truncate, "truncate"),
+    ] {
+        let path = std::env::temp_dir().join(format!("yagi_test_journal_{}.db", expected));
+        let _ = std::fs::remove_file(&path);
+        let options = PoolOptions {
+            journal_mode: mode,
+            ..PoolOptions::default()
+        };
+        let pool = DbPool::with_options(path.to_str().unwrap(), options).unwrap();
+
+        // Non-WAL modes are per connection, so check two connections held at once.
+        let first = pool.get_conn().unwrap();
+        let second = pool.get_conn().unwrap();
+        assert_eq!(journal_mode(&first), expected);
+        assert_eq!(journal_mode(&second), expected);
+    }
+}
+
+#[actix_web::test]
+async fn test_goat_timeline_merges_activity_in_time_order() {
+    let db_pool = fresh_db("goat_timeline");
+    // Inserted out of order so the timeline has to sort across tables.
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(
+            "INSERT INTO goats (id, breed, name, gender, created_at) VALUES
+                (1, 'Beetal', 'Storied', 'Female', '2024-01-05 09:00:00'),
+                (2, 'Beetal', 'Other', 'Female', '2024-01-01 09:00:00');
+             INSERT INTO sales (goat_id, buyer, price, created_at) VALUES
+                (1, 'Asha', 12000.0, '2024-06-01 12:00:00');
+             INSERT INTO vet_visits (goat_id, vet_name, reason, created_at) VALUES
+                (1, 'Dr. Rao', 'Limping', '2024-03-10 11:00:00');
+             INSERT INTO goat_weight_history (goat_id, weight, created_at) VALUES
+                (1, 31.5, '2024-02-01 08:00:00'), (2, 40.0, '2024-02-02 08:00:00');
+             INSERT INTO vaccines (id, name) VALUES (1, 'CDT');
+             INSERT INTO goat_vaccines (goat_id, vaccine_id, created_at) VALUES
+                (1, 1, '2024-01-20 10:00:00');",
+        )
+        .expect("Failed to seed goat history");
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats/{id}/timeline", web::get().to(get_goat_timeline)),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/goats/1/timeline")
+        .to_request();
+    let timeline: Vec<TimelineEvent> = test::call_and_read_body_json(&app, req).await;
+    let kinds: Vec<&str> = timeline.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(
+        kinds,
+        ["created", "vaccination", "weight", "vet_visit", "sale"]
+    );
+    assert_eq!(timeline[0].timestamp, "2024-01-05 09:00:00");
+    assert_eq!(timeline[1].description, "Vaccinated: CDT");
+    assert_eq!(timeline[2].description, "Weighed 31.5 kg");
+    assert_eq!(timeline[3].description, "Vet visit by Dr. Rao: Limping");
+    assert_eq!(timeline[4].description, "Sold to Asha for 12000.00");
+
+    let req = test::TestRequest::get()
+        .uri("/goats/99/timeline")
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 404);
+}
+
+#[actix_web::test]
+async fn test_exhausted_pool_returns_503_with_retry_after() {
+    let path = std::env::temp_dir().join("yagi_test_pool_exhausted.db");
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+    let options = PoolOptions {
+        max_size: 1,
+        connection_timeout: std::time::Duration::from_millis(100),
+        ..PoolOptions::default()
+    };
+    let db_pool = DbPool::with_options(path.to_str().unwrap(), options).unwrap();
+    db_pool
+        .get_conn()
+        .unwrap()
+        .execute_batch(SCHEMA_SQL)
+        .unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/goats", web::get().to(get_goats)),
+    )
+    .await;
+
+    // Another request is holding the only connection.
+    let held = db_pool.get_conn().unwrap();
+    let req = test::TestRequest::get().uri("/goats").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 503);
+    assert_eq!(
+        resp.headers().get("Retry-After").unwrap(),
+        &POOL_RETRY_AFTER_SECS.to_string()
+    );
+    let body: ErrorBody = test::read_body_json(resp).await;
+    assert_eq!(body.error, "PoolExhausted");
+    assert!(backend::db::pool_exhausted_total() >= 1);
+
+    drop(held);
+    let req = test::TestRequest::get().uri("/goats").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 200);
+}
+
+/// Counts to a billion in SQL, which takes far longer than any test's query timeout.
+async fn slow_query(db: web::Data<DbPool>) -> Result<HttpResponse, AppError> {
+    let conn = db.get_conn()?;
+    let count: i64 = conn.query_row(
+        "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c WHERE x < 1000000000) \
+         SELECT COUNT(*) FROM c",
+        [],
+        |r| r.get(0),
+    )?;
+    Ok(HttpResponse::Ok().json(count))
+}
+
+#[actix_web::test]
+async fn test_slow_query_is_interrupted_with_504() {
+    let path = std::env::temp_dir().join("yagi_test_query_timeout.db");
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+    let options = PoolOptions {
+        max_size: 1,
+        connection_timeout: std::time::Duration::from_secs(1),
+        query_timeout: std::time::Duration::from_millis(200),
+        ..PoolOptions::default()
+    };
+    let db_pool = DbPool::with_options(path.to_str().unwrap(), options).unwrap();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .route("/slow", web::get().to(slow_query)),
+    )
+    .await;
+
+    let started = std::time::Instant::now();
+    let req = test::TestRequest::get().uri("/slow").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 504);
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+    // The only connection is back in the pool and still usable.
+    let conn = db_pool
+        .get_conn()
+        .expect("connection should be back in the pool");
+    let one: i64 = conn.query_row("SELECT 1", [], |r| r.get(0)).unwrap();
+    assert_eq!(one, 1);
+}
+
+#[test]
+fn test_write_retry_lets_concurrent_writers_both_succeed() {
+    let path = std::env::temp_dir().join("yagi_test_write_retry.db");
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+    let options = PoolOptions {
+        max_size: 2,
+        ..PoolOptions::default()
+    };
+    let db_pool = DbPool::with_options(path.to_str().unwrap(), options).unwrap();
+    {
+        // Hold both connections so each gets a zero busy timeout: a writer that finds the
+        // lock taken fails with SQLITE_BUSY at once instead of SQLite waiting for it.
+        let first = db_pool.get_conn().unwrap();
+        let second = db_pool.get_conn().unwrap();
+        first.execute_batch(SCHEMA_SQL).unwrap();
+        first.busy_timeout(std::time::Duration::ZERO).unwrap();
+        second.busy_timeout(std::time::Duration::ZERO).unwrap();
+    }
+
+    let writers: Vec<_> = (0..2)
+        .map(|writer| {
+            let db_pool = db_pool.clone();
+            std::thread::spawn(move || {
+                for i in 0..10 {
+                    db_pool
+                        .with_write_retry(|tx| {
+                            tx.execute(
+                                "INSERT INTO vaccines (name) VALUES (?1)",
+                                [format!("Vaccine {}-{}", writer, i)],
+                            )?;
+                            // Keep the write lock long enough for the other writer to
+                            // run into it.
+                            std::thread::sleep(std::time::Duration::from_millis(5));
+                            Ok(())
+                        })
+                        .expect("write should succeed after retrying");
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    let conn = db_pool.get_conn().unwrap();
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM vaccines", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(count, 20);
+}
+
+/// Queues linking the vaccine `name` to a live goat as a writer transaction, replying
+/// with the vaccine's id.
+fn link_vaccine(
+    writer: &Writer,
+    goat_id: i64,
+    name: &'static str,
+) -> impl Future<Output = Result<i64, AppError>> + use<> {
+    writer.transaction(move |tx| {
+        ensure_goat_exists(tx, goat_id)?;
+        let vaccine = VaccineRef {
+            id: None,
+            name: name.to_string(),
+        };
+        let vaccine_id = get_or_insert_vaccine(tx, &vaccine)?.id();
+        tx.execute(
+            "INSERT OR IGNORE INTO goat_vaccines (goat_id, vaccine_id) VALUES (?1, ?2)",
+            [goat_id, vaccine_id],
+        )?;
+        Ok(vaccine_id)
+    })
+}
+
+#[actix_rt::test]
+async fn test_writer_applies_dependent_writes_in_send_order() {
+    let db_pool = setup_test_db();
+    let writer = db_pool.writer();
+    let created = writer
+        .insert_goat(goat_params(goat_json("Ordered")))
+        .await
+        .unwrap();
+    let goat_id = created.id().unwrap();
+
+    // Queue every write before awaiting any, then await them newest first: each must
+    // still see the writes sent before it.
+    let renamed = writer.update_goat(goat_id, goat_params(goat_json("Ordered Renamed")), None);
+    let linked = link_vaccine(writer, goat_id, "CDT");
+    let deleted = writer.delete_goat(goat_id);
+    let too_late = writer.update_goat(goat_id, goat_params(goat_json("Too Late")), None);
+
+    assert!(matches!(too_late.await, Err(AppError::NotFound(_))));
+    let deleted = deleted.await.unwrap();
+    assert_eq!(deleted.params.name, "Ordered Renamed");
+    assert_eq!(
+        deleted
+            .params
+            .vaccinations
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["CDT"]
+    );
+    let vaccine_id = linked.await.unwrap();
+    assert_eq!(deleted.params.vaccinations[0].id, Some(vaccine_id));
+    assert_eq!(renamed.await.unwrap().params.name, "Ordered Renamed");
+
+    assert!(matches!(
+        link_vaccine(writer, goat_id, "PPR").await,
+        Err(AppError::NotFound(_))
+    ));
+}
+
+#[actix_rt::test]
+async fn test_writer_serializes_concurrent_inserts_without_busy_errors() {
+    let path = std::env::temp_dir().join("yagi_test_writer_concurrency.db");
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+    let options = PoolOptions {
+        max_size: 2,
+        ..PoolOptions::default()
+    };
+    let db_pool = DbPool::with_options(path.to_str().unwrap(), options).unwrap();
+    {
+        // As in the retry test: a zero busy timeout on both connections makes any lock
+        // contention fail at once.
+        let first = db_pool.get_conn().unwrap();
+        let second = db_pool.get_conn().unwrap();
+        first.busy_timeout(std::time::Duration::ZERO).unwrap();
+        second.busy_timeout(std::time::Duration::ZERO).unwrap();
+    }
+
+    let inserts = (0..20).map(|i| {
+        let db_pool = db_pool.clone();
+        actix_rt::spawn(async move {
+            db_pool
+                .writer()
+                .insert_goat(goat_params(goat_json(&format!("Concurrent {}", i))))
+                .await
+        })
+    });
+    for insert in futures_util::future::join_all(inserts).await {
+        insert
+            .unwrap()
+            .expect("insert through the writer should succeed");
+    }
+
+    assert_eq!(count_goats(&db_pool), 20);
+}
+
+/// Names of the goats in a `GET /goats` response body.
+fn listed_names(body: &serde_json::Value) -> Vec<String> {
+    body.as_array()
+        .expect("goat list should be an array")
+        .iter()
+        .map(|goat| goat["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[actix_rt::test]
+async fn test_cached_goat_list_reflects_each_write() {
+    let db_pool = fresh_db("goat_list_cache");
+    let cache = web::Data::new(GoatListCache::new());
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .app_data(cache.clone())
+            .route("/goats", web::get().to(get_goats))
+            .route("/goats", web::post().to(add_goat))
+            .route("/goats/{id}/tags", web::post().to(add_goat_tag)),
+    )
+    .await;
+    let list = || test::TestRequest::get().uri("/goats").to_request();
+
+    let body: serde_json::Value = test::call_and_read_body_json(&app, list()).await;
+    assert!(listed_names(&body).is_empty());
+
+    let req = test::TestRequest::post()
+        .uri("/goats")
+        .set_json(goat_json("Cached Nanny"))
+        .to_request();
+    let created: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    let body: serde_json::Value = test::call_and_read_body_json(&app, list()).await;
+    assert_eq!(listed_names(&body), vec!["Cached Nanny"]);
+    let again: serde_json::Value = test::call_and_read_body_json(&app, list()).await;
+    assert_eq!(again, body, "a cache hit serves the same list");
+
+    // Tags have no change_log trigger; only the handler's invalidation reveals them.
+    let req = test::TestRequest::post()
+        .uri(&format!("/goats/{}/tags", created["id"]))
+        .set_json(json!({ "tag": "for-sale" }))
+        .to_request();
+    assert!(test::call_service(&app, req).await.status().is_success());
+    let body: serde_json::Value = test::call_and_read_body_json(&app, list()).await;
+    assert_eq!(body[0]["tags"], json!(["for-sale"]));
+
+    // A write outside the goat handlers still moves the change sequence.
+    db_pool
+        .with_write_retry(|tx| DbPool::insert_goat(tx, &goat_params(goat_json("Direct Buck"))))
+        .unwrap();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, list()).await;
+    assert_eq!(listed_names(&body).len(), 2);
+
+    // Filtered requests bypass the cache rather than being served the full list.
+    let req = test::TestRequest::get()
+        .uri("/goats?tag=for-sale")
+        .to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(listed_names(&body), vec!["Cached Nanny"]);
+
+    // A list loaded before an invalidation is never stored over it.
+    let generation = cache.generation();
+    cache.invalidate();
+    assert!(!cache.store(generation, i64::MAX, "[]".into(), 0));
+    assert!(cache.get(i64::MAX).is_none());
+}
+
+#[actix_rt::test]
+async fn test_cached_goat_list_has_no_stale_reads_under_concurrent_writes() {
+    let db_pool = fresh_db("goat_list_cache_stress");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db_pool.clone()))
+            .app_data(web::Data::new(GoatListCache::new()))
+            .route("/goats", web::get().to(get_goats))
+            .route("/goats", web::post().to(add_goat)),
+    )
+    .await;
+
+    // Each client adds a goat and then lists; the list must include its goat and every
+    // goat whose POST had completed before that list was requested.
+    let completed = std::cell::RefCell::new(Vec::new());
+    let clients = (0..16).map(|i| {
+        let app = &app;
+        let completed = &completed;
+        async move {
+            let name = format!("Stress {}", i);
+            let req = test::TestRequest::post()
+                .uri("/goats")
+                .set_json(goat_json(&name))
+                .to_request();
+            assert_eq!(test::call_service(app, req).await.status(), 201);
+            completed.borrow_mut().push(name);
+            for _ in 0..4 {
+                let expected = completed.borrow().clone();
+                let req = test::TestRequest::get().uri("/goats").to_request();
+                let body: serde_json::Value = test::call_and_read_body_json(app, req).await;
+                let listed = listed_names(&body);
+                for name in &expected {
+                    assert!(listed.contains(name), "stale list is missing {}", name);
+                }
+            }
+        }
+    });
+    futures_util::future::join_all(clients).await;
+
+    let req = test::TestRequest::get().uri("/goats").to_request();
+    let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(listed_names(&body).len(), 16);
+}
+
+#[test]
+fn test_write_retry_returns_other_errors_without_retrying() {
+    let db_pool = setup_test_db();
+    let mut calls = 0;
+    let result = db_pool.with_write_retry(|tx| {
+        calls += 1;
+        tx.execute("INSERT INTO vaccines (name) VALUES ('Rabies')", [])?;
+        tx.execute("INSERT INTO vaccines (name) VALUES ('Rabies')", [])?;
+        Ok(())
+    });
+
+    assert!(matches!(result, Err(AppError::DbError(_))));
+    assert_eq!(calls, 1);
+    let conn = db_pool.get_conn().unwrap();
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM vaccines", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(count, 0, "the failed transaction should have rolled back");
+}
+
+#[test]
+fn test_synchronous_setting_applies_to_checked_out_connections() {
+    assert_eq!(
+        "full".parse::<SynchronousMode>().unwrap(),
+        SynchronousMode::Full
+    );
+    assert!(matches!(
+        "EXTRA".parse::<SynchronousMode>(),
+        Err(AppError::InvalidInput(_))
+    ));
+
+    // SQLite reports the setting as a number: 0 = OFF, 1 = NORMAL, 2 = FULL.
+    for (synchronous, expected) in [
+        (SynchronousMode::Off, 0),
+        (SynchronousMode::Normal, 1),
+        (SynchronousMode::Full, 2),
+    ] {
+        let path = std::env::temp_dir().join(format!("yagi_test_synchronous_{}.db", expected));
+        let _ = std::fs::remove_file(&path);
+        let options = PoolOptions {
+            synchronous,
+            ..PoolOptions::default()
+        };
+        let pool = DbPool::with_options(path.to_str().unwrap(), options).unwrap();
+        let conn = pool.get_conn().unwrap();
+        let reported: i64 = conn
+            .query_row("PRAGMA synchronous", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(reported, expected, "{}", synchronous);
+    }
+}